@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    /// No character with this name exists.
+    #[error("Character '{name}' not found")]
+    CharacterNotFound { name: String },
+
+    /// A date filter that isn't `YYYY-MM-DD`.
+    #[error("Invalid date '{0}' (expected YYYY-MM-DD)")]
+    InvalidDate(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,216 @@
+//! Read-only query API over an Amanuensis database.
+//!
+//! For tools that want a character's kills, ranks, coins or sessions without depending on
+//! `amanuensis-core` (and with it the log parser and bundled game data). The database is
+//! opened read-only, so this is safe to use while the app or CLI is scanning.
+//!
+//! ```no_run
+//! let store = amanuensis_api::Store::open("amanuensis.db")?;
+//! for kill in store.kills().character("Gandor").since("2024-01-01").limit(10).list()? {
+//!     println!("{}: {}", kill.creature, kill.total());
+//! }
+//! # Ok::<(), amanuensis_api::Error>(())
+//! ```
+//!
+//! # Stability
+//!
+//! This crate follows semver independently of the app. The row types are `#[non_exhaustive]`
+//! and new filters are new builder methods, so both can grow in minor releases; renaming or
+//! removing either is a major release. It reads the database schema the app writes, and is
+//! updated in the same workspace whenever that schema changes.
+
+mod error;
+mod model;
+mod query;
+
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+
+pub use error::{Error, Result};
+pub use model::{Character, CoinDay, KillRecord, Session, TrainerRanks};
+pub use query::{CoinQuery, KillQuery, SessionQuery, TrainerQuery};
+
+/// Wait this long for a scan holding the write lock, like the app's own connections.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A read-only connection to an Amanuensis database.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open the database at `path` read-only. The file must exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI,
+        )?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(Self { conn })
+    }
+
+    /// All characters, by name, including ones merged into another character.
+    pub fn characters(&self) -> Result<Vec<Character>> {
+        let mut stmt = self.conn.prepare(&format!("{} ORDER BY name", CHARACTER_SELECT))?;
+        let rows = stmt.query_map([], character_from_row)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// One character by name (case-insensitive).
+    pub fn character(&self, name: &str) -> Result<Option<Character>> {
+        let sql = format!("{} WHERE name = ?1 COLLATE NOCASE", CHARACTER_SELECT);
+        Ok(self.conn.query_row(&sql, [name], character_from_row).optional()?)
+    }
+
+    pub fn kills(&self) -> KillQuery<'_> {
+        KillQuery::new(&self.conn)
+    }
+
+    pub fn trainers(&self) -> TrainerQuery<'_> {
+        TrainerQuery::new(&self.conn)
+    }
+
+    pub fn coins(&self) -> CoinQuery<'_> {
+        CoinQuery::new(&self.conn)
+    }
+
+    pub fn sessions(&self) -> SessionQuery<'_> {
+        SessionQuery::new(&self.conn)
+    }
+}
+
+const CHARACTER_SELECT: &str = "SELECT id, name, COALESCE(profession_override, profession), logins, deaths,
+        departs, coin_level, start_date, merged_into FROM characters";
+
+fn character_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Character> {
+    Ok(Character {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        profession: row.get(2)?,
+        logins: row.get(3)?,
+        deaths: row.get(4)?,
+        departs: row.get(5)?,
+        coin_level: row.get(6)?,
+        start_date: row.get(7)?,
+        merged_into: row.get(8)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amanuensis_core::Database;
+
+    fn kill(db: &Database, char_id: i64, creature: &str, field: &str, date: &str) {
+        db.upsert_kill(char_id, creature, field, 0, date).unwrap();
+        db.upsert_kill_hourly(char_id, creature, field, &date[..13]).unwrap();
+    }
+
+    fn fall(db: &Database, char_id: i64, creature: &str, date: &str) {
+        db.upsert_kill(char_id, creature, "killed_by_count", 0, date).unwrap();
+        db.record_death(char_id, creature, None, date).unwrap();
+    }
+
+    /// Gandor with an alt (Gandy) merged into him, and Ashwin.
+    fn fixture() -> (tempfile::TempDir, Store) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.db");
+        let db = Database::open(&path.to_string_lossy()).unwrap();
+        let gandor = db.get_or_create_character("Gandor").unwrap();
+        let gandy = db.get_or_create_character("Gandy").unwrap();
+        let ashwin = db.get_or_create_character("Ashwin").unwrap();
+        kill(&db, gandor, "Rat", "killed_count", "2024-01-01 10:00:00");
+        kill(&db, gandor, "Rat", "slaughtered_count", "2024-02-01 10:00:00");
+        kill(&db, gandy, "Rat", "assisted_kill_count", "2024-03-05 12:00:00");
+        kill(&db, ashwin, "Vermine", "killed_count", "2024-01-02 08:00:00");
+        fall(&db, gandor, "Rat", "2024-02-10 09:00:00");
+        fall(&db, gandy, "Rat", "2024-03-06 09:00:00");
+        db.upsert_trainer_rank(gandor, "Histia", "2024-01-01 10:00:00", 1.0).unwrap();
+        db.upsert_trainer_rank(gandy, "Histia", "2024-01-02 10:00:00", 1.0).unwrap();
+        db.upsert_trainer_rank(gandy, "Atkus", "2024-01-02 10:00:00", 1.0).unwrap();
+        db.set_trainer_maxed(gandor, "Histia", 1.0).unwrap();
+        db.merge_characters(&[gandy], gandor).unwrap();
+        drop(db);
+        let store = Store::open(&path).unwrap();
+        (dir, store)
+    }
+
+    #[test]
+    fn kills_merge_alts_and_filter_by_date_and_creature() {
+        let (_dir, store) = fixture();
+        let rat = &store.kills().character("gandor").list().unwrap()[0];
+        assert_eq!((rat.character.as_str(), rat.creature.as_str()), ("Gandor", "Rat"));
+        assert_eq!((rat.solo, rat.assisted, rat.killed_by), (2, 1, 2));
+
+        let own = store.kills().character("Gandor").include_merged(false).list().unwrap();
+        assert_eq!(own[0].total(), 2);
+        let feb = store.kills().character("Gandor").since("2024-02-01").until("2024-02-29").list().unwrap();
+        assert_eq!((feb[0].solo, feb[0].assisted, feb[0].killed_by), (1, 0, 1));
+        assert_eq!(feb[0].first.as_deref(), Some("2024-02-01 10"));
+
+        let march = store.kills().since("2024-03-01").list().unwrap();
+        assert_eq!(march.len(), 1);
+        assert_eq!((march[0].character.as_str(), march[0].killed_by), ("Gandy", 1));
+
+        let everyone = store.kills().creature("vermine").list().unwrap();
+        assert_eq!(everyone.len(), 1);
+        assert_eq!(everyone[0].character, "Ashwin");
+        assert!(matches!(store.kills().character("Nobody").list(), Err(Error::CharacterNotFound { .. })));
+        assert!(matches!(store.kills().since("March").list(), Err(Error::InvalidDate(_))));
+    }
+
+    #[test]
+    fn trainers_characters_and_read_only() {
+        let (_dir, store) = fixture();
+        let trainers = store.trainers().character("Gandor").list().unwrap();
+        assert_eq!(trainers.iter().map(|t| (t.trainer.as_str(), t.effective)).collect::<Vec<_>>(), [("Histia", 2), ("Atkus", 1)]);
+        assert!(trainers[0].maxed);
+        assert_eq!(store.trainers().character("Gandor").maxed_only().list().unwrap().len(), 1);
+
+        let characters = store.characters().unwrap();
+        assert_eq!(characters.len(), 3);
+        let gandy = store.character("gandy").unwrap().unwrap();
+        assert_eq!(gandy.merged_into, store.character("Gandor").unwrap().map(|c| c.id));
+        assert!(store.coins().character("Ashwin").list().unwrap().is_empty());
+        assert!(store.sessions().character("Ashwin").limit(5).list().unwrap().is_empty());
+        assert!(store.conn.execute("DELETE FROM kills", []).is_err());
+    }
+
+    #[test]
+    fn trainer_effective_matches_core() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.db");
+        let db = Database::open(&path.to_string_lossy()).unwrap();
+        let gandor = db.get_or_create_character("Gandor").unwrap();
+        let gandy = db.get_or_create_character("Gandy").unwrap();
+        let modes = ["modifier", "override", "override_until_date"];
+        db.merge_characters(&[gandy], gandor).unwrap();
+        // Overrides block a merge, so they are set afterwards; the ranks that follow are the
+        // post-cutoff counts a scan would add.
+        for (i, mode) in modes.into_iter().enumerate() {
+            let trainer = format!("Trainer{i}");
+            db.set_rank_override(gandor, &trainer, mode, 7, None).unwrap();
+            for char_id in [gandor, gandy] {
+                db.upsert_trainer_rank(char_id, &trainer, "2024-01-01 10:00:00", 1.0).unwrap();
+                db.upsert_apply_learning(char_id, &trainer, "2024-01-02 10:00:00", 10, 1.0).unwrap();
+            }
+        }
+        let mut core: Vec<(String, i64)> = db
+            .get_trainers_merged(gandor)
+            .unwrap()
+            .iter()
+            .map(|t| (t.trainer_name.clone(), t.effective_ranks()))
+            .collect();
+        drop(db);
+
+        let store = Store::open(&path).unwrap();
+        let mut api: Vec<(String, i64)> =
+            store.trainers().character("Gandor").list().unwrap().into_iter().map(|t| (t.trainer, t.effective)).collect();
+        core.sort();
+        api.sort();
+        assert_eq!(api, core);
+        assert_eq!(api.len(), 3);
+    }
+}
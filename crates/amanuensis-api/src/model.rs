@@ -0,0 +1,90 @@
+//! Row types returned by the queries. All are `#[non_exhaustive]` so fields can be added in
+//! minor releases; construct them only through [`Store`](crate::Store).
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct Character {
+    pub id: i64,
+    pub name: String,
+    /// The user's profession override when set, otherwise the one detected from logs.
+    pub profession: String,
+    pub logins: i64,
+    pub deaths: i64,
+    pub departs: i64,
+    pub coin_level: i64,
+    pub start_date: Option<String>,
+    /// Id of the character this one was merged into, if any.
+    pub merged_into: Option<i64>,
+}
+
+/// Kill totals for one creature.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct KillRecord {
+    pub character: String,
+    pub creature: String,
+    /// Killed, slaughtered, vanquished and dispatched.
+    pub solo: i64,
+    /// Kills shared with other exiles.
+    pub assisted: i64,
+    /// Times the creature killed the character. With a date range, counted from the dated
+    /// falls, so databases scanned before falls were recorded report 0 there; creatures that
+    /// only killed the character in the range are not listed.
+    pub killed_by: i64,
+    pub creature_value: i64,
+    /// First and last kill. With a date range these are hour-granular (`YYYY-MM-DD HH`).
+    pub first: Option<String>,
+    pub last: Option<String>,
+}
+
+impl KillRecord {
+    pub fn total(&self) -> i64 {
+        self.solo + self.assisted
+    }
+}
+
+/// Ranks with one trainer.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct TrainerRanks {
+    pub character: String,
+    pub trainer: String,
+    /// Ranks seen in logs.
+    pub ranks: i64,
+    /// Ranks from Apply Learning.
+    pub applied: i64,
+    /// Manual ranks entered by the user.
+    pub manual: i64,
+    /// `modifier`, `override` or `override_until_date`.
+    pub rank_mode: String,
+    /// The total the app shows: `manual` alone in override mode, otherwise all three summed.
+    pub effective: i64,
+    pub maxed: bool,
+    pub last_rank: Option<String>,
+}
+
+/// Coins from one source on one day.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct CoinDay {
+    pub character: String,
+    pub day: String,
+    pub source: String,
+    pub amount: i64,
+}
+
+/// One play session (one log file's worth of activity).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct Session {
+    pub character: String,
+    pub log_file: String,
+    pub started_at: String,
+    pub ended_at: String,
+    pub kills: i64,
+    pub ranks: i64,
+    pub coins: i64,
+    pub deaths: i64,
+}
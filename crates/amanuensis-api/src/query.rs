@@ -0,0 +1,443 @@
+//! Builder-style queries. Each builder borrows the [`Store`](crate::Store), collects filters,
+//! and runs when `list()` is called.
+//!
+//! Filtering to a character includes the characters merged into it, the same totals the app
+//! shows, unless `include_merged(false)` is given; merged rows are reported under the
+//! requested character's name. Date filters are inclusive `YYYY-MM-DD` days.
+
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, OptionalExtension};
+
+use crate::error::{Error, Result};
+use crate::model::{CoinDay, KillRecord, Session, TrainerRanks};
+
+/// Filters shared by the builders.
+#[derive(Debug, Clone)]
+struct Scope {
+    character: Option<String>,
+    include_merged: bool,
+    since: Option<String>,
+    until: Option<String>,
+    limit: Option<usize>,
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self { character: None, include_merged: true, since: None, until: None, limit: None }
+    }
+}
+
+/// SQL fragments and their bound values, built up from a `Scope`.
+struct Sql {
+    clauses: Vec<String>,
+    params: Vec<Value>,
+    /// Set when filtering to one character: merged rows are reported under this name.
+    name: Option<String>,
+    /// The id of that character, whose settings win over its merged characters'.
+    primary: Option<i64>,
+}
+
+impl Sql {
+    fn bind(&mut self, value: impl Into<Value>) -> String {
+        self.params.push(value.into());
+        format!("?{}", self.params.len())
+    }
+
+    fn push(&mut self, clause: String) {
+        self.clauses.push(clause);
+    }
+
+    fn where_clause(&self) -> String {
+        if self.clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", self.clauses.join(" AND "))
+        }
+    }
+
+    /// The column to report as the character name: the requested name, or `name_col`.
+    fn name_expr(&mut self, name_col: &str) -> String {
+        match self.name.clone() {
+            Some(name) => self.bind(name),
+            None => name_col.to_string(),
+        }
+    }
+}
+
+impl Scope {
+    fn has_dates(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    /// Start the SQL for this scope: the character filter on `char_col` and the date range
+    /// on the first ten characters of `date_col`.
+    fn sql(&self, conn: &Connection, char_col: &str, date_col: Option<&str>) -> Result<Sql> {
+        let mut sql = Sql { clauses: Vec::new(), params: Vec::new(), name: None, primary: None };
+        sql.clauses = self.filter(conn, &mut sql, char_col, date_col)?;
+        Ok(sql)
+    }
+
+    /// The clauses `sql` starts with, binding their values into `sql`; also usable for a
+    /// subquery over another table.
+    fn filter(&self, conn: &Connection, sql: &mut Sql, char_col: &str, date_col: Option<&str>) -> Result<Vec<String>> {
+        let mut clauses = Vec::new();
+        if let Some(name) = &self.character {
+            let (id, name): (i64, String) = conn
+                .query_row("SELECT id, name FROM characters WHERE name = ?1 COLLATE NOCASE", [name], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .optional()?
+                .ok_or_else(|| Error::CharacterNotFound { name: name.clone() })?;
+            let mut ids = vec![id];
+            if self.include_merged {
+                let mut stmt = conn.prepare("SELECT id FROM characters WHERE merged_into = ?1")?;
+                let merged = stmt.query_map([id], |row| row.get(0))?;
+                ids.extend(merged.collect::<std::result::Result<Vec<i64>, _>>()?);
+            }
+            let placeholders: Vec<String> = ids.into_iter().map(|id| sql.bind(id)).collect();
+            clauses.push(format!("{} IN ({})", char_col, placeholders.join(",")));
+            sql.name = Some(name);
+            sql.primary = Some(id);
+        }
+        if let Some(col) = date_col {
+            for (date, op) in [(&self.since, ">="), (&self.until, "<=")] {
+                if let Some(date) = date {
+                    let p = sql.bind(check_date(date)?);
+                    clauses.push(format!("substr({}, 1, 10) {} {}", col, op, p));
+                }
+            }
+        }
+        Ok(clauses)
+    }
+
+    fn limit_clause(&self) -> String {
+        self.limit.map(|n| format!("LIMIT {}", n)).unwrap_or_default()
+    }
+}
+
+fn check_date(date: &str) -> Result<String> {
+    let b = date.as_bytes();
+    let ok = b.len() == 10
+        && b.iter().enumerate().all(|(i, c)| if i == 4 || i == 7 { *c == b'-' } else { c.is_ascii_digit() });
+    if ok {
+        Ok(date.to_string())
+    } else {
+        Err(Error::InvalidDate(date.to_string()))
+    }
+}
+
+/// `SUM(p.a + p.b + ...)`.
+fn sum_columns(prefix: &str, columns: &[&str]) -> String {
+    let terms: Vec<String> = columns.iter().map(|c| format!("{}.{}", prefix, c)).collect();
+    format!("SUM({})", terms.join(" + "))
+}
+
+macro_rules! scope_methods {
+    () => {
+        /// Only this character (case-insensitive), plus the characters merged into it.
+        pub fn character(mut self, name: impl Into<String>) -> Self {
+            self.scope.character = Some(name.into());
+            self
+        }
+
+        /// Whether a `character` filter takes in characters merged into it (default `true`).
+        pub fn include_merged(mut self, include: bool) -> Self {
+            self.scope.include_merged = include;
+            self
+        }
+
+        /// At most `n` rows.
+        pub fn limit(mut self, n: usize) -> Self {
+            self.scope.limit = Some(n);
+            self
+        }
+    };
+}
+
+macro_rules! date_methods {
+    () => {
+        /// From this day on (`YYYY-MM-DD`, inclusive).
+        pub fn since(mut self, day: impl Into<String>) -> Self {
+            self.scope.since = Some(day.into());
+            self
+        }
+
+        /// Up to and including this day (`YYYY-MM-DD`).
+        pub fn until(mut self, day: impl Into<String>) -> Self {
+            self.scope.until = Some(day.into());
+            self
+        }
+    };
+}
+
+fn collect<T>(
+    conn: &Connection,
+    sql: &str,
+    params: Vec<Value>,
+    f: impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+) -> Result<Vec<T>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params_from_iter(params), f)?;
+    Ok(rows.collect::<std::result::Result<Vec<T>, _>>()?)
+}
+
+/// Kill totals per creature, most kills first. Built by [`Store::kills`](crate::Store::kills).
+#[derive(Debug, Clone)]
+pub struct KillQuery<'a> {
+    conn: &'a Connection,
+    scope: Scope,
+    creature: Option<String>,
+}
+
+impl<'a> KillQuery<'a> {
+    pub(crate) fn new(conn: &'a Connection) -> Self {
+        Self { conn, scope: Scope::default(), creature: None }
+    }
+
+    scope_methods!();
+    date_methods!();
+
+    /// Only this creature (case-insensitive).
+    pub fn creature(mut self, name: impl Into<String>) -> Self {
+        self.creature = Some(name.into());
+        self
+    }
+
+    pub fn list(&self) -> Result<Vec<KillRecord>> {
+        // Date ranges need the hourly buckets, and count killed-by from the dated falls
+        let dated = self.scope.has_dates();
+        let mut sql = if dated {
+            self.scope.sql(self.conn, "h.character_id", Some("h.hour"))?
+        } else {
+            self.scope.sql(self.conn, "k.character_id", None)?
+        };
+        let group = if sql.name.is_some() { "" } else { "c.id, " };
+        let name = sql.name_expr("MIN(c.name)");
+        let prefix = if dated { "h" } else { "k" };
+        let solo = sum_columns(prefix, &["killed_count", "slaughtered_count", "vanquished_count", "dispatched_count"]);
+        let assisted = sum_columns(
+            prefix,
+            &["assisted_kill_count", "assisted_slaughter_count", "assisted_vanquish_count", "assisted_dispatch_count"],
+        );
+        let killed_by = if dated {
+            let mut deaths = self.scope.filter(self.conn, &mut sql, "d.character_id", Some("d.date"))?;
+            if sql.name.is_none() {
+                deaths.push("d.character_id = c.id".to_string());
+            }
+            format!(
+                "(SELECT COUNT(*) FROM deaths d WHERE d.cause = h.creature_name AND {})",
+                deaths.join(" AND ")
+            )
+        } else {
+            "SUM(k.killed_by_count)".to_string()
+        };
+        if let Some(creature) = &self.creature {
+            let p = sql.bind(creature.clone());
+            sql.push(format!("{}.creature_name = {} COLLATE NOCASE", prefix, p));
+        }
+        let query = if dated {
+            format!(
+                "SELECT {name}, h.creature_name, {solo}, {assisted}, {killed_by},
+                        COALESCE(MAX(k.creature_value), 0), MIN(h.hour), MAX(h.hour)
+                 FROM kill_hourly h
+                 JOIN characters c ON c.id = h.character_id
+                 LEFT JOIN kills k ON k.character_id = h.character_id AND k.creature_name = h.creature_name
+                 {where_} GROUP BY {group}h.creature_name
+                 ORDER BY {solo} + {assisted} DESC, h.creature_name {limit}",
+                where_ = sql.where_clause(),
+                limit = self.scope.limit_clause(),
+            )
+        } else {
+            format!(
+                "SELECT {name}, k.creature_name, {solo}, {assisted}, {killed_by},
+                        MAX(k.creature_value), MIN(k.date_first), MAX(k.date_last)
+                 FROM kills k
+                 JOIN characters c ON c.id = k.character_id
+                 {where_} GROUP BY {group}k.creature_name
+                 ORDER BY {solo} + {assisted} DESC, k.creature_name {limit}",
+                where_ = sql.where_clause(),
+                limit = self.scope.limit_clause(),
+            )
+        };
+        collect(self.conn, &query, sql.params, |row| {
+            Ok(KillRecord {
+                character: row.get(0)?,
+                creature: row.get(1)?,
+                solo: row.get(2)?,
+                assisted: row.get(3)?,
+                killed_by: row.get(4)?,
+                creature_value: row.get(5)?,
+                first: row.get(6)?,
+                last: row.get(7)?,
+            })
+        })
+    }
+}
+
+/// Trainer ranks, highest effective ranks first. Built by [`Store::trainers`](crate::Store::trainers).
+#[derive(Debug, Clone)]
+pub struct TrainerQuery<'a> {
+    conn: &'a Connection,
+    scope: Scope,
+    trainer: Option<String>,
+    maxed_only: bool,
+}
+
+impl<'a> TrainerQuery<'a> {
+    pub(crate) fn new(conn: &'a Connection) -> Self {
+        Self { conn, scope: Scope::default(), trainer: None, maxed_only: false }
+    }
+
+    scope_methods!();
+
+    /// Only this trainer (case-insensitive).
+    pub fn trainer(mut self, name: impl Into<String>) -> Self {
+        self.trainer = Some(name.into());
+        self
+    }
+
+    /// Only trainers the character has maxed out.
+    pub fn maxed_only(mut self) -> Self {
+        self.maxed_only = true;
+        self
+    }
+
+    pub fn list(&self) -> Result<Vec<TrainerRanks>> {
+        let mut sql = self.scope.sql(self.conn, "t.character_id", None)?;
+        // Merged characters share one row per trainer; the rank mode is the primary's
+        let (group, mode) = match sql.primary {
+            Some(id) => {
+                let p = sql.bind(id);
+                ("", format!("COALESCE(MAX(CASE WHEN t.character_id = {} THEN t.rank_mode END), 'modifier')", p))
+            }
+            None => ("c.id, ", "MAX(t.rank_mode)".to_string()),
+        };
+        let name = sql.name_expr("MIN(c.name)");
+        if let Some(trainer) = &self.trainer {
+            let p = sql.bind(trainer.clone());
+            sql.push(format!("t.trainer_name = {} COLLATE NOCASE", p));
+        }
+        let having = if self.maxed_only { "HAVING MAX(t.maxed) > 0" } else { "" };
+        let query = format!(
+            "SELECT {name}, t.trainer_name, SUM(t.ranks), SUM(t.apply_learning_ranks), SUM(t.modified_ranks),
+                    {mode}, MAX(t.maxed), MAX(t.date_of_last_rank)
+             FROM trainers t
+             JOIN characters c ON c.id = t.character_id
+             {where_} GROUP BY {group}t.trainer_name {having}",
+            where_ = sql.where_clause(),
+        );
+        let mut rows = collect(self.conn, &query, sql.params, |row| {
+            let (ranks, applied, manual): (i64, i64, i64) = (row.get(2)?, row.get(3)?, row.get(4)?);
+            let rank_mode: String = row.get(5)?;
+            let effective = if rank_mode == "override" { manual } else { ranks + applied + manual };
+            Ok(TrainerRanks {
+                character: row.get(0)?,
+                trainer: row.get(1)?,
+                ranks,
+                applied,
+                manual,
+                rank_mode,
+                effective,
+                maxed: row.get::<_, i64>(6)? != 0,
+                last_rank: row.get(7)?,
+            })
+        })?;
+        rows.retain(|r| r.effective > 0 || r.maxed);
+        rows.sort_by(|a, b| b.effective.cmp(&a.effective).then_with(|| a.trainer.cmp(&b.trainer)));
+        if let Some(n) = self.scope.limit {
+            rows.truncate(n);
+        }
+        Ok(rows)
+    }
+}
+
+/// Daily coin income by source, oldest day first. Built by [`Store::coins`](crate::Store::coins).
+#[derive(Debug, Clone)]
+pub struct CoinQuery<'a> {
+    conn: &'a Connection,
+    scope: Scope,
+    source: Option<String>,
+}
+
+impl<'a> CoinQuery<'a> {
+    pub(crate) fn new(conn: &'a Connection) -> Self {
+        Self { conn, scope: Scope::default(), source: None }
+    }
+
+    scope_methods!();
+    date_methods!();
+
+    /// Only this income source (`loot`, `chest`, `bounty`, ...).
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn list(&self) -> Result<Vec<CoinDay>> {
+        let mut sql = self.scope.sql(self.conn, "d.character_id", Some("d.day"))?;
+        let group = if sql.name.is_some() { "" } else { "c.id, " };
+        let name = sql.name_expr("MIN(c.name)");
+        if let Some(source) = &self.source {
+            let p = sql.bind(source.clone());
+            sql.push(format!("d.source = {}", p));
+        }
+        let query = format!(
+            "SELECT {name}, d.day, d.source, SUM(d.amount)
+             FROM coin_daily d
+             JOIN characters c ON c.id = d.character_id
+             {where_} GROUP BY {group}d.day, d.source
+             ORDER BY d.day, d.source {limit}",
+            where_ = sql.where_clause(),
+            limit = self.scope.limit_clause(),
+        );
+        collect(self.conn, &query, sql.params, |row| {
+            Ok(CoinDay { character: row.get(0)?, day: row.get(1)?, source: row.get(2)?, amount: row.get(3)? })
+        })
+    }
+
+    /// Sum of `amount` over the matching days.
+    pub fn total(&self) -> Result<i64> {
+        Ok(self.list()?.iter().map(|d| d.amount).sum())
+    }
+}
+
+/// Play sessions, most recent first. Built by [`Store::sessions`](crate::Store::sessions).
+#[derive(Debug, Clone)]
+pub struct SessionQuery<'a> {
+    conn: &'a Connection,
+    scope: Scope,
+}
+
+impl<'a> SessionQuery<'a> {
+    pub(crate) fn new(conn: &'a Connection) -> Self {
+        Self { conn, scope: Scope::default() }
+    }
+
+    scope_methods!();
+    date_methods!();
+
+    pub fn list(&self) -> Result<Vec<Session>> {
+        let mut sql = self.scope.sql(self.conn, "s.character_id", Some("s.started_at"))?;
+        let name = sql.name_expr("c.name");
+        let query = format!(
+            "SELECT {name}, s.log_file, s.started_at, s.ended_at, s.kills, s.ranks, s.coins, s.deaths
+             FROM sessions s
+             JOIN characters c ON c.id = s.character_id
+             {where_} ORDER BY s.started_at DESC {limit}",
+            where_ = sql.where_clause(),
+            limit = self.scope.limit_clause(),
+        );
+        collect(self.conn, &query, sql.params, |row| {
+            Ok(Session {
+                character: row.get(0)?,
+                log_file: row.get(1)?,
+                started_at: row.get(2)?,
+                ended_at: row.get(3)?,
+                kills: row.get(4)?,
+                ranks: row.get(5)?,
+                coins: row.get(6)?,
+                deaths: row.get(7)?,
+            })
+        })
+    }
+}
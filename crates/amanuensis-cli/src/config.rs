@@ -0,0 +1,115 @@
+//! Optional config file with per-user defaults.
+//!
+//! Read from `--config`, else `$AMANUENSIS_CONFIG`, else `$XDG_CONFIG_HOME/amanuensis/config.toml`
+//! (`~/.config/amanuensis/config.toml`; `%APPDATA%\amanuensis\config.toml` on Windows). Every
+//! key is optional and command-line flags win over it:
+//!
+//! ```toml
+//! db = "~/Documents/amanuensis.db"          # instead of --db
+//! log_folder = "~/Documents/Clan Lord/Text Logs"  # when scan/update/rescan/watch/pending get no folder
+//! index = false                              # like --no-index; --index overrides (default: the database's setting)
+//! format = "json"                            # instead of --format
+//! trainer_db = "~/Documents/trainers.csv"   # instead of --trainer-db
+//! creature_db = "~/Documents/creatures.csv" # instead of --creature-db
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::output::OutputFormat;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub db: Option<String>,
+    pub log_folder: Option<PathBuf>,
+    /// Build the search index when scanning (default: the database's `index_log_lines`
+    /// setting, which is on unless turned off).
+    pub index: Option<bool>,
+    pub format: Option<OutputFormat>,
+    /// Trainer catalog CSV applied over the bundled trainer list.
+    pub trainer_db: Option<PathBuf>,
+    /// Creature catalog CSV applied over the bundled bestiary.
+    pub creature_db: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn from_toml_str(text: &str) -> Result<Self, toml::de::Error> {
+        let mut config: Config = toml::from_str(text)?;
+        config.db = config.db.map(|db| expand_home(Path::new(&db)).to_string_lossy().into_owned());
+        config.log_folder = config.log_folder.map(|f| expand_home(&f));
+        config.trainer_db = config.trainer_db.map(|f| expand_home(&f));
+        config.creature_db = config.creature_db.map(|f| expand_home(&f));
+        Ok(config)
+    }
+
+    /// Load the config file at `path`, or the default location when None. A missing default
+    /// file is an empty config; a missing explicit one, or a malformed file, is an error.
+    pub fn load(path: Option<&Path>) -> amanuensis_core::Result<(Self, Option<PathBuf>)> {
+        let (path, explicit) = match path {
+            Some(p) => (p.to_path_buf(), true),
+            None => match default_path() {
+                Some(p) => (p, false),
+                None => return Ok((Config::default(), None)),
+            },
+        };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if !explicit && e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok((Config::default(), None));
+            }
+            Err(source) => return Err(amanuensis_core::AmanuensisError::FileUnreadable { path, source }),
+        };
+        let config = Config::from_toml_str(&text)
+            .map_err(|e| amanuensis_core::AmanuensisError::Parse(format!("{}: {}", path.display(), e)))?;
+        Ok((config, Some(path)))
+    }
+
+    /// The folder scan uses when none is given.
+    pub fn default_log_folder(&self) -> amanuensis_core::Result<PathBuf> {
+        self.log_folder.clone().ok_or_else(|| {
+            amanuensis_core::AmanuensisError::Data(
+                "No log folder given and no `log_folder` in the config file".to_string(),
+            )
+        })
+    }
+
+    /// `given`, or the config file's log folder when the command line named none.
+    pub fn log_folders(&self, given: Vec<PathBuf>) -> amanuensis_core::Result<Vec<PathBuf>> {
+        if given.is_empty() {
+            Ok(vec![self.default_log_folder()?])
+        } else {
+            Ok(given)
+        }
+    }
+
+    /// Whether a scan skips the search index: `--no-index` / `--index` first, then `index` in
+    /// the config file, then the database's `index_log_lines` setting (`db_indexing`).
+    pub fn skip_index(&self, no_index: bool, index: bool, db_indexing: bool) -> bool {
+        no_index || (!index && !self.index.unwrap_or(db_indexing))
+    }
+}
+
+/// `$AMANUENSIS_CONFIG`, or `amanuensis/config.toml` in the platform config directory.
+pub fn default_path() -> Option<PathBuf> {
+    if let Some(p) = std::env::var_os("AMANUENSIS_CONFIG") {
+        return Some(PathBuf::from(p));
+    }
+    #[cfg(target_os = "windows")]
+    let base = std::env::var_os("APPDATA").map(PathBuf::from);
+    #[cfg(not(target_os = "windows"))]
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")));
+    base.map(|b| b.join("amanuensis").join("config.toml"))
+}
+
+/// Replace a leading `~` with the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
@@ -1,34 +1,92 @@
 use std::collections::HashMap;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
 use clap::{Parser, Subcommand};
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Table, ContentArrangement};
 
-use amanuensis_core::{Database, LogParser, TrainerDb, import_scribius, compute_fighter_stats};
+use amanuensis_core::{CreatureDb, CreatureNameRules, Database, LeaderboardMetric, LogParser, LogSearchOptions, SearchExportFormat, ProgressionCriteria, TrainerDb, import_scribius, compute_fighter_stats_with, simulate_stats_with, FighterModifiers};
 use amanuensis_core::models::RankMode;
+use amanuensis_core::parser::events::LogEvent;
+use amanuensis_core::parser::line_classifier::classify_text;
+use amanuensis_core::{data_dir, demo, reconcile};
+use amanuensis_core::log_paths::{self, PathAlias};
+
+mod config;
+mod output;
+mod progress;
+mod serve;
+mod ws;
+use config::Config;
+use output::{print_json, print_message, OutputFormat};
+use progress::ScanProgress;
 
 #[derive(Parser)]
 #[command(name = "amanuensis", version, about = "Clan Lord log parser and stat tracker")]
 struct Cli {
-    /// Path to the SQLite database file
-    #[arg(long, default_value = "amanuensis.db")]
-    db: String,
+    /// Path to the SQLite database file [default: `db` from the config file, else amanuensis.db
+    /// in the platform data directory, shared with the GUI]
+    #[arg(long)]
+    db: Option<String>,
 
-    /// Use the GUI's default database location instead of --db
+    /// Config file with defaults (db, log_folder, index, format) [default:
+    /// ~/.config/amanuensis/config.toml, or $AMANUENSIS_CONFIG]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Use the default database location even if the config file sets `db`
     #[arg(long, conflicts_with = "db")]
     gui_db: bool,
 
+    /// Trainer catalog CSV (columns: message, trainer, profession, multiplier,
+    /// combo_components, retired, replaced_by) that extends or overrides the bundled
+    /// trainer list [default: `trainer_db` from the config file]
+    #[arg(long, value_name = "FILE")]
+    trainer_db: Option<PathBuf>,
+
+    /// Creature catalog CSV (columns: name, value, family, location, rarity) that adds
+    /// creatures or overrides values in the bundled bestiary; `watch` reloads it when it
+    /// changes [default: `creature_db` from the config file]
+    #[arg(long, value_name = "FILE")]
+    creature_db: Option<PathBuf>,
+
+    /// Output format: table (default), json, or csv (kills, frequency, export and catalog-diff only).
+    /// Export defaults to json
+    #[arg(long, global = true, value_enum)]
+    format: Option<OutputFormat>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+// The subcommands are split into flattened groups: one enum holding all of them made the
+// derived parser's builder a single stack frame too large for a 2 MiB test thread in debug
+// builds. Grouping doesn't show in the CLI; `amanuensis --help` lists every subcommand.
 #[derive(Subcommand)]
 enum Commands {
+    #[command(flatten)]
+    Data(DataCommands),
+    #[command(flatten)]
+    Stats(StatsCommands),
+    #[command(flatten)]
+    Ranks(RankCommands),
+    #[command(flatten)]
+    Text(TextCommands),
+    #[command(flatten)]
+    Share(ShareCommands),
+    #[command(flatten)]
+    Catalog(CatalogCommands),
+}
+
+/// Fill the database from logs, keep it current, and maintain it.
+#[derive(Subcommand)]
+enum DataCommands {
     /// Scan log files from a folder and store in database
     Scan {
-        /// Path to the log folder (containing character subdirectories)
-        folder: PathBuf,
+        /// Path to the log folder (containing character subdirectories) [default: `log_folder`
+        /// from the config file]
+        folder: Option<PathBuf>,
         /// Force re-scan of already-read files
         #[arg(long)]
         force: bool,
@@ -38,6 +96,30 @@ enum Commands {
         /// Skip FTS5 full-text indexing of log lines
         #[arg(long)]
         no_index: bool,
+        /// Build the search index even if the config file turns indexing off
+        #[arg(long, conflicts_with = "no_index")]
+        index: bool,
+        /// Low-priority scan: one file at a time with a pause between files and a small
+        /// SQLite cache, so a background scan doesn't slow down the game
+        #[arg(long)]
+        nice: bool,
+        /// JSON hook config: run external commands for selected events (see `amanuensis_core::hooks`)
+        #[arg(long, value_name = "FILE")]
+        hooks: Option<PathBuf>,
+        /// Stream each parsed event as NDJSON to PATH (`-` for stdout) while scanning
+        #[arg(long, value_name = "PATH")]
+        emit_events: Option<PathBuf>,
+        /// Also count other players' falls seen in the logs (see `falls`); off by default
+        /// since it grows the database
+        #[arg(long)]
+        witnessed_falls: bool,
+        /// Also count system messages no pattern recognizes (see `unknowns`); off by default
+        #[arg(long)]
+        unknown_lines: bool,
+        /// Also record who said what (speech and thoughts) in a chat table (see `chat`);
+        /// off by default
+        #[arg(long)]
+        chat: bool,
     },
     /// Scan individual log files
     ScanFiles {
@@ -50,7 +132,249 @@ enum Commands {
         /// Skip FTS5 full-text indexing of log lines
         #[arg(long)]
         no_index: bool,
+        /// Build the search index even if the config file turns indexing off
+        #[arg(long, conflicts_with = "no_index")]
+        index: bool,
+        /// Low-priority scan: one file at a time with a pause between files and a small
+        /// SQLite cache, so a background scan doesn't slow down the game
+        #[arg(long)]
+        nice: bool,
+    },
+    /// List log files whose last scan failed (read errors, or the line a scan stopped at)
+    ScanErrors {
+        /// Scan the failed files again; the ones that succeed drop off the list
+        #[arg(long)]
+        retry: bool,
+    },
+    /// Merge characters (rename consolidation)
+    Merge {
+        /// Name of the primary character (whose name is kept)
+        target: String,
+        /// Names of the source characters to merge into the primary
+        #[arg(required = true)]
+        sources: Vec<String>,
+    },
+    /// Unmerge a previously merged character
+    Unmerge {
+        /// Name of the character to unmerge
+        name: String,
+    },
+    /// Delete one character and all its kills, trainers, lastys, pets, scanned-file records
+    /// and search lines. Characters merged into it become separate characters again.
+    DeleteCharacter {
+        /// Name of the character to delete
+        name: String,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Import data from a Scribius (Core Data) database
+    Import {
+        /// Path to the Scribius Model.sqlite file
+        source: PathBuf,
+        /// Output Amanuensis database path [default: the --db database]
+        #[arg(long)]
+        output: Option<String>,
+        /// Overwrite existing data in the output database
+        #[arg(long)]
+        force: bool,
+    },
+    /// Reset derived log data (kills, trainers, coins, ...) while PRESERVING rank overrides and
+    /// trainer notes. Unlike `reset`, this keeps the database file and your manual configuration
+    /// — the same override-preserving reset the GUI performs.
+    ResetLogs {
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Take back one scanned file: the other scanned files are replayed without it
+    Unscan {
+        /// Log file, as it was scanned
+        file: PathBuf,
+    },
+    /// Show or change log path root aliases, which let one archive reached through different
+    /// mount points (e.g. a share from macOS and Windows) count as the same files
+    PathAliases {
+        /// Store paths under FROM as under TO (repeatable)
+        #[arg(long, value_name = "FROM=TO")]
+        add: Vec<String>,
+        /// Drop the alias for FROM (repeatable)
+        #[arg(long, value_name = "FROM")]
+        remove: Vec<String>,
+        /// Drop every alias
+        #[arg(long, conflicts_with_all = ["add", "remove"])]
+        clear: bool,
+    },
+    /// Show or change creature-name rules, which roll variants such as "Large Vermine" up
+    /// into one creature for kill counts
+    CreatureNames {
+        /// Replace the rules with those in a JSON rules file (see `amanuensis_core::creature_names`)
+        #[arg(long, value_name = "FILE")]
+        load: Option<PathBuf>,
+        /// Apply the rules to scans and kill lists
+        #[arg(long)]
+        enable: bool,
+        /// Stop applying the rules (kill lists show names as stored)
+        #[arg(long, conflicts_with = "enable")]
+        disable: bool,
+        /// Drop every rule
+        #[arg(long, conflicts_with = "load")]
+        clear: bool,
+        /// Show how the stored kill rows would regroup under the rules (those from `--load`,
+        /// which are then not saved, or the stored ones)
+        #[arg(long, conflicts_with_all = ["enable", "disable", "clear"])]
+        preview: bool,
+    },
+    /// Delete all data and reset the database
+    Reset {
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Reset derived data and re-scan the given folder(s) from scratch (safe; no double-counting).
+    /// Pass ALL of your log folders — rescan wipes derived data first, so any folder you omit
+    /// will not be represented afterward. Manual rank overrides are preserved.
+    Rescan {
+        /// One or more log folders to re-scan [default: `log_folder` from the config file]
+        folders: Vec<PathBuf>,
+        /// Recurse into subdirectories of each folder
+        #[arg(long)]
+        recursive: bool,
+        /// Skip building the full-text search index
+        #[arg(long)]
+        no_index: bool,
+        /// Build the search index even if the config file turns indexing off
+        #[arg(long, conflicts_with = "no_index")]
+        index: bool,
+        /// Low-priority scan: one file at a time with a pause between files and a small
+        /// SQLite cache, so a background scan doesn't slow down the game
+        #[arg(long)]
+        nice: bool,
+    },
+    /// Incrementally process new and grown logs WITHOUT resetting (mirrors the GUI's
+    /// "Update Logs"). New files are scanned, grown files are tail-scanned, unchanged
+    /// files are skipped. Safe to run repeatedly.
+    Update {
+        /// One or more log folders to update from [default: `log_folder` from the config file]
+        folders: Vec<PathBuf>,
+        /// Recurse into subdirectories of each folder
+        #[arg(long)]
+        recursive: bool,
+        /// Skip building the full-text search index
+        #[arg(long)]
+        no_index: bool,
+        /// Build the search index even if the config file turns indexing off
+        #[arg(long, conflicts_with = "no_index")]
+        index: bool,
+        /// Low-priority scan: one file at a time with a pause between files and a small
+        /// SQLite cache, so a background scan doesn't slow down the game
+        #[arg(long)]
+        nice: bool,
+        /// JSON hook config: run external commands for selected events (see `amanuensis_core::hooks`)
+        #[arg(long, value_name = "FILE")]
+        hooks: Option<PathBuf>,
+        /// Also count other players' falls seen in the logs (see `falls`); off by default
+        /// since it grows the database
+        #[arg(long)]
+        witnessed_falls: bool,
+        /// Also count system messages no pattern recognizes (see `unknowns`); off by default
+        #[arg(long)]
+        unknown_lines: bool,
+        /// Also record who said what (speech and thoughts) in a chat table (see `chat`);
+        /// off by default
+        #[arg(long)]
+        chat: bool,
+    },
+    /// Keep the database current while you play: poll the log folders and tail-scan new
+    /// lines as they are written (runs until interrupted with Ctrl-C)
+    Watch {
+        /// One or more log folders to watch [default: `log_folder` from the config file]
+        folders: Vec<PathBuf>,
+        /// Recurse into subdirectories of each folder
+        #[arg(long)]
+        recursive: bool,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Skip building the full-text search index
+        #[arg(long)]
+        no_index: bool,
+        /// Build the search index even if the config file turns indexing off
+        #[arg(long, conflicts_with = "no_index")]
+        index: bool,
+        /// Low-priority scan: one file at a time with a pause between files and a small
+        /// SQLite cache, so a background scan doesn't slow down the game
+        #[arg(long)]
+        nice: bool,
+        /// JSON hook config: run external commands for selected events (see `amanuensis_core::hooks`)
+        #[arg(long, value_name = "FILE")]
+        hooks: Option<PathBuf>,
+        /// Push each parsed event to WebSocket clients connected to this address
+        /// (e.g. 127.0.0.1:7878), as the JSON `scan --emit-events` writes
+        #[arg(long, value_name = "ADDR")]
+        ws: Option<String>,
+        /// JSON notify config: post notable events to a Discord webhook (see `amanuensis_core::notify`)
+        #[arg(long, value_name = "FILE")]
+        notify: Option<PathBuf>,
+    },
+    /// Run an incremental Update every `--interval` until stopped, logging each pass: for
+    /// running in the background under launchd or systemd
+    Daemon {
+        /// Time between passes: seconds, or a number with s/m/h/d (e.g. 90s, 15m, 1h)
+        #[arg(long, default_value = "15m", value_parser = parse_interval)]
+        interval: std::time::Duration,
+        /// Log folder to update from; repeatable [default: `log_folder` from the config file]
+        #[arg(long = "folder", value_name = "PATH")]
+        folders: Vec<PathBuf>,
+        /// Recurse into subdirectories of each folder
+        #[arg(long)]
+        recursive: bool,
+        /// Skip building the full-text search index
+        #[arg(long)]
+        no_index: bool,
+        /// Build the search index even if the config file turns indexing off
+        #[arg(long, conflicts_with = "no_index")]
+        index: bool,
+        /// Low-priority scan: one file at a time with a pause between files and a small
+        /// SQLite cache, so a background scan doesn't slow down the game
+        #[arg(long)]
+        nice: bool,
+        /// JSON hook config: run external commands for selected events (see `amanuensis_core::hooks`)
+        #[arg(long, value_name = "FILE")]
+        hooks: Option<PathBuf>,
+        /// Push each parsed event to WebSocket clients connected to this address
+        /// (e.g. 127.0.0.1:7878), as the JSON `scan --emit-events` writes
+        #[arg(long, value_name = "ADDR")]
+        ws: Option<String>,
+        /// JSON notify config: post notable events to a Discord webhook (see `amanuensis_core::notify`)
+        #[arg(long, value_name = "FILE")]
+        notify: Option<PathBuf>,
+    },
+    /// Report how many log files an incremental Update would process right now (the GUI's
+    /// "Update Logs (N)" badge count), without modifying the database.
+    Pending {
+        /// One or more log folders to check [default: `log_folder` from the config file]
+        folders: Vec<PathBuf>,
+        /// Recurse into subdirectories of each folder
+        #[arg(long)]
+        recursive: bool,
+        /// List each pending file path, not just the count
+        #[arg(long)]
+        list: bool,
     },
+    /// Compare two log archives (e.g. Text Logs folders copied from two computers) by file
+    /// content and report which logs are unique to each side, before scanning (no DB needed)
+    CompareArchives {
+        /// First archive folder
+        folder_a: PathBuf,
+        /// Second archive folder
+        folder_b: PathBuf,
+    },
+}
+
+/// Per-character statistics read from the database.
+#[derive(Subcommand)]
+enum StatsCommands {
     /// List all detected characters
     Characters,
     /// Show character summary
@@ -71,9 +395,6 @@ enum Commands {
         /// Include per-verb breakdown columns (csv only)
         #[arg(long)]
         by_verb: bool,
-        /// Output format: table, csv, json
-        #[arg(long, default_value = "table")]
-        format: String,
         /// Limit number of rows
         #[arg(long)]
         limit: Option<usize>,
@@ -97,49 +418,195 @@ enum Commands {
         /// Only show creatures flagged is_seasonal
         #[arg(long)]
         seasonal: bool,
-        /// Output format: table, csv
-        #[arg(long, default_value = "table")]
-        format: String,
+        /// Split kills by the area they happened in (inferred as for `zones`)
+        #[arg(long)]
+        by_location: bool,
+    },
+    /// Show melee hit rate per creature (from "You hit" / "You miss" lines)
+    Accuracy {
+        /// Character name
+        name: String,
+    },
+    /// Show which creatures hit you most (from "The X hits you" lines), with killed-by counts
+    Damage {
+        /// Character name
+        name: String,
+    },
+    /// Show top hunting partners (players you shared with or who fell / were raised nearby)
+    Companions {
+        /// Character name
+        name: String,
+        /// Limit number of companions shown
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show heals given and received per player
+    Heals {
+        /// Character name
+        name: String,
+    },
+    /// Show other players' falls seen in the character's logs (needs `scan --witnessed-falls`)
+    Falls {
+        /// Character name
+        name: String,
+        /// Group by cause of death instead of by player
+        #[arg(long)]
+        by_cause: bool,
+        /// Limit number of rows shown
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show who has dragged or raised the character, and whom it has rescued
+    Rescues {
+        /// Character name
+        name: String,
+    },
+    /// Show who you exchange private thoughts with, and how recently
+    Correspondents {
+        /// Character name
+        name: String,
+        /// Limit number of correspondents (most thoughts first)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show where play time goes, per area (approximate: inferred from the creatures fought)
+    Zones {
+        /// Character name
+        name: String,
+        /// Limit number of areas (most time first)
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+    },
+    /// List kill milestones (100th/500th/1000th kill of a creature, first solo boss kills)
+    Milestones {
+        /// Character name
+        name: String,
+    },
+    /// Show stat boosts and blessings (temporary and permanent), per stat
+    Boosts {
+        /// Character name
+        name: String,
+        /// List each temporary boost's effect window (taken to worn off, by hour) instead
+        #[arg(long)]
+        windows: bool,
+    },
+    /// Show the daily stats snapshots taken after each scan, and what changed between them
+    Snapshots {
+        /// Character name
+        name: String,
+        /// Only show the change from the last snapshot on or before this date (e.g. 2024-03-01)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+    },
+    /// Show arena and tournament results: bouts won and lost, per-opponent records
+    Arena {
+        /// Character name
+        name: String,
+        /// Limit number of opponents shown
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show the character's deaths by killer, area and time of day, and whether a depart or rescue followed
+    Deaths {
+        /// Character name
+        name: String,
+        /// Limit number of killers and areas shown
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show who landed the final blow on the character's assisted kills
+    Assists {
+        /// Character name
+        name: String,
+        /// Only this creature
+        creature: Option<String>,
     },
     /// Show trainer rank progression
     Trainers {
         /// Character name
         name: String,
     },
-    /// Show pet information
+    /// Show ranks gained per month with one trainer
+    TrainerHistory {
+        /// Character name
+        name: String,
+        /// Trainer name (case-insensitive)
+        trainer: String,
+    },
+    /// Project when a trainer reaches a target rank count from the recent training pace
+    Eta {
+        /// Character name
+        name: String,
+        /// Trainer name (case-insensitive)
+        trainer: String,
+        /// Target effective rank count
+        #[arg(long)]
+        target: i64,
+    },
+    /// Show pets with their history: adoption, ranks, feedings, deaths and release
     Pets {
         /// Character name
         name: String,
     },
+    /// Show play sessions (login to disconnect) with kills, ranks, coins and deaths
+    Sessions {
+        /// Character name
+        name: String,
+        /// Limit number of sessions (most recent first)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Write a Markdown hunt ledger for one session: party, kills, loot worth vs share, deaths
+    HuntReport {
+        /// Character name
+        name: String,
+        /// Session id (the ID column of `amanuensis sessions`)
+        #[arg(long)]
+        session: i64,
+        /// Output file (prints to stdout when omitted)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
     /// Show lasty (creature training) progress
     Lastys {
         /// Character name
         name: String,
     },
-    /// Merge characters (rename consolidation)
-    Merge {
-        /// Name of the primary character (whose name is kept)
-        target: String,
-        /// Names of the source characters to merge into the primary
-        #[arg(required = true)]
-        sources: Vec<String>,
+    /// Personal bestiary: every creature the character has killed, assisted on, died to or
+    /// studied, with first/last seen dates (`bestiary` looks up one creature's record)
+    Glossary {
+        /// Character name
+        name: String,
     },
-    /// Unmerge a previously merged character
-    Unmerge {
-        /// Name of the character to unmerge
+    /// Show hunting tier: the highest-value creature reliably soloed, and when it rose
+    Progression {
+        /// Character name
         name: String,
+        /// Solo kills of a creature needed before it counts toward the tier
+        #[arg(long, default_value_t = 50)]
+        min_kills: i64,
+        /// Highest killed-by / solo-kill ratio still considered reliable
+        #[arg(long, default_value_t = 0.1)]
+        max_killed_by_ratio: f64,
     },
-    /// Import data from a Scribius (Core Data) database
-    Import {
-        /// Path to the Scribius Model.sqlite file
-        source: PathBuf,
-        /// Output Amanuensis database path
-        #[arg(long, default_value = "amanuensis.db")]
-        output: String,
-        /// Overwrite existing data in the output database
+    /// Show coin and loot statistics
+    Coins {
+        /// Character name
+        name: String,
+        /// Show coin income and spending per period instead (daily, weekly, monthly or yearly)
         #[arg(long)]
-        force: bool,
+        timeline: Option<String>,
+    },
+    /// Show casino winnings and losses by game, with the biggest single win and loss
+    Casino {
+        /// Character name
+        name: String,
     },
+}
+
+/// Fighter stats, rank planning and rank corrections.
+#[derive(Subcommand)]
+enum RankCommands {
     /// Set modified ranks for a trainer
     SetRanks {
         /// Character name
@@ -149,6 +616,29 @@ enum Commands {
         /// Modified rank count to set
         ranks: i64,
     },
+    /// Compare a pasted rank listing (one "Trainer: ranks" per line) with the database and
+    /// propose set-ranks modifiers for trainers the logs undercount
+    Reconcile {
+        /// Character name
+        name: String,
+        /// File with the pasted listing ("-" reads stdin)
+        #[arg(long, value_name = "FILE")]
+        paste: PathBuf,
+        /// Write the proposed modifiers instead of only listing them
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Set modified ranks for many trainers from a CSV of trainer,modified_ranks rows, all in
+    /// one transaction (the file is validated against the trainer catalog first)
+    SetRanksBulk {
+        /// Character name
+        name: String,
+        /// CSV file ("-" reads stdin)
+        file: PathBuf,
+        /// Show the changes without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Set or clear a freeform note on a trainer row (mirrors the GUI's trainer note field)
     SetTrainerNote {
         /// Character name
@@ -164,55 +654,62 @@ enum Commands {
         #[arg(long)]
         yes: bool,
     },
-    /// Reset derived log data (kills, trainers, coins, ...) while PRESERVING rank overrides and
-    /// trainer notes. Unlike `reset`, this keeps the database file and your manual configuration
-    /// — the same override-preserving reset the GUI performs.
-    ResetLogs {
-        /// Skip confirmation prompt
+    /// Show computed fighter statistics (Gorvin's Calculator)
+    FighterStats {
+        /// Character name
+        name: String,
+        /// Race (default: the character's saved loadout, else Human)
         #[arg(long)]
-        yes: bool,
-    },
-    /// Search log text (requires FTS5 index; scan without --no-index first)
-    Search {
-        /// Search query (FTS5 syntax)
-        query: String,
-        /// Filter to a specific character
+        race: Option<String>,
+        /// Weapon (default: the saved loadout, else Roguewood Club)
         #[arg(long)]
-        character: Option<String>,
-        /// Max results
-        #[arg(long, default_value = "50")]
-        limit: i64,
-    },
-    /// Delete all data and reset the database
-    Reset {
-        /// Skip confirmation prompt
+        weapon: Option<String>,
+        /// Equipped item; repeat for several. Replaces the saved items
+        #[arg(long = "item")]
+        items: Vec<String>,
+        /// Save this loadout for the character
+        #[arg(long, conflicts_with = "clear")]
+        save: bool,
+        /// Forget the character's saved loadout
         #[arg(long)]
-        yes: bool,
+        clear: bool,
     },
-    /// Show the built-in trainer catalog
-    TrainerCatalog {
-        /// Filter by profession (fighter, healer, mystic, ranger, bloodmage, champion)
+    /// Break down each trainer's ranks by source (trainer messages, apply-learning, manual),
+    /// or list one trainer's rank events with their log files
+    RankSources {
+        /// Character name
+        name: String,
+        /// List this trainer's recorded rank events
         #[arg(long)]
-        profession: Option<String>,
+        trainer: Option<String>,
     },
-    /// Show coin and loot statistics
-    Coins {
+    /// Suggest trainers to untrain and retrain toward a stat profile (uses the saved loadout)
+    UntrainPlan {
         /// Character name
         name: String,
+        /// Stat to raise, e.g. accuracy (repeatable or comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        more: Vec<String>,
+        /// Stat to lower, e.g. balance (repeatable or comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        less: Vec<String>,
+        /// Most ranks to untrain and retrain
+        #[arg(long, default_value_t = 10)]
+        ranks: i64,
+        /// Untrainus's fee per rank, in coins (costs are left out when not given)
+        #[arg(long, default_value_t = 0)]
+        coins_per_rank: i64,
+        /// Most coins to spend on untraining
+        #[arg(long)]
+        budget: Option<i64>,
     },
-    /// Show computed fighter statistics (Gorvin's Calculator)
-    FighterStats {
+    /// Show how fighter stats would change with extra trainer ranks (uses the saved loadout)
+    Simulate {
         /// Character name
         name: String,
-    },
-    /// Show process logs from the last scan (warnings, errors, override skips)
-    Logs {
-        /// Filter by level: error, warn, info
-        #[arg(long)]
-        level: Option<String>,
-        /// Limit number of entries shown
-        #[arg(long, default_value = "200")]
-        limit: usize,
+        /// Ranks to add per trainer, e.g. "Evus:50,Darkus:20" (negative to remove)
+        #[arg(long, allow_hyphen_values = true)]
+        add: String,
     },
     /// Show trainer rank checkpoints for a character
     Checkpoints {
@@ -247,49 +744,260 @@ enum Commands {
         /// Profession: fighter, healer, mystic, ranger, bloodmage, champion — or "auto" to clear
         profession: String,
     },
-    /// Reset derived data and re-scan the given folder(s) from scratch (safe; no double-counting).
-    /// Pass ALL of your log folders — rescan wipes derived data first, so any folder you omit
-    /// will not be represented afterward. Manual rank overrides are preserved.
-    Rescan {
-        /// One or more log folders to re-scan
-        #[arg(required = true)]
-        folders: Vec<PathBuf>,
-        /// Recurse into subdirectories of each folder
+}
+
+/// Search and read log text, and see what the parser made of it.
+#[derive(Subcommand)]
+enum TextCommands {
+    /// Search log text (requires FTS5 index; scan without --no-index or run `index rebuild` first)
+    Search {
+        /// Search query (FTS5 syntax)
+        query: String,
+        /// Filter to a specific character
         #[arg(long)]
-        recursive: bool,
-        /// Skip building the full-text search index
+        character: Option<String>,
+        /// Max results [default: 50, or every match with --output]
         #[arg(long)]
-        no_index: bool,
+        limit: Option<i64>,
+        /// Skip this many results (newest files first), to page through large result sets
+        #[arg(long, default_value = "0")]
+        offset: i64,
+        /// Save the results to a .csv or .jsonl file instead of printing them
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Only lines dated on or after this (e.g. 2024-03-01 or "2024-03-01 18:00:00")
+        #[arg(long)]
+        from: Option<String>,
+        /// Only lines dated on or before this (a bare date includes the whole day)
+        #[arg(long)]
+        to: Option<String>,
+        /// Show N lines before and after each match, from the same file
+        #[arg(long, value_name = "N", default_value = "0")]
+        context: i64,
+        /// Treat the query as a case-insensitive regular expression (scans the indexed text
+        /// instead of using FTS5, so it is slower)
+        #[arg(long)]
+        regex: bool,
     },
-    /// Incrementally process new and grown logs WITHOUT resetting (mirrors the GUI's
-    /// "Update Logs"). New files are scanned, grown files are tail-scanned, unchanged
-    /// files are skipped. Safe to run repeatedly.
-    Update {
-        /// One or more log folders to update from
-        #[arg(required = true)]
-        folders: Vec<PathBuf>,
-        /// Recurse into subdirectories of each folder
+    /// Print the raw transcript around a moment in a log file (e.g. a search hit), to read a
+    /// conversation as it happened
+    Context {
+        /// Log file (a search result's file path)
+        file: PathBuf,
+        /// Time of the hit: "2024-01-01 13:01:00" (as search shows it) or "1/1/24 1:01:00p"
+        timestamp: String,
+        /// Lines to show before and after the hit
+        #[arg(long, default_value = "30")]
+        lines: usize,
+    },
+    /// Show or set which lines the search index keeps (applies to later scans; run
+    /// `prune-index` to apply it to lines already indexed)
+    IndexRetention {
+        /// Keep lines from the last N years only
         #[arg(long)]
-        recursive: bool,
-        /// Skip building the full-text search index
+        years: Option<u32>,
+        /// Keep lines of every year
+        #[arg(long, conflicts_with = "years")]
+        all_years: bool,
+        /// Keep only lines recognized as events (kills, ranks, loot, thoughts, ...): true/false
         #[arg(long)]
-        no_index: bool,
+        events_only: Option<bool>,
     },
-    /// Report how many log files an incremental Update would process right now (the GUI's
-    /// "Update Logs (N)" badge count), without modifying the database.
-    Pending {
-        /// One or more log folders to check
-        #[arg(required = true)]
-        folders: Vec<PathBuf>,
-        /// Recurse into subdirectories of each folder
+    /// Delete indexed lines the retention policy no longer keeps, and compact the database
+    PruneIndex,
+    /// Maintain the search index: rebuild it from the scanned files, optimize it, or show
+    /// its size
+    Index {
+        #[command(subcommand)]
+        action: IndexAction,
+    },
+    /// Who said what: speech and thoughts recorded by `scan --chat`, oldest first
+    Chat {
+        /// Only lines from this speaker (case-insensitive)
         #[arg(long)]
-        recursive: bool,
-        /// List each pending file path, not just the count
+        speaker: Option<String>,
+        /// Only lines whose text contains this (case-insensitive)
         #[arg(long)]
-        list: bool,
+        contains: Option<String>,
+        /// Only lines heard in this character's logs [default: every character]
+        #[arg(long)]
+        character: Option<String>,
+        /// Most recent lines shown
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+    /// Most common system messages no pattern recognizes (needs `scan --unknown-lines`),
+    /// to see which formats the parser is missing
+    Unknowns {
+        /// Number of message formats shown
+        #[arg(long, default_value_t = 50)]
+        top: usize,
+        /// Only this character's logs [default: every character]
+        #[arg(long)]
+        character: Option<String>,
+    },
+    /// Run the line classifier over a file or pasted text and print the event each line
+    /// parses as, to see why a rank or kill wasn't counted
+    Classify {
+        /// Text or log file ("-" reads stdin)
+        file: PathBuf,
+        /// Only show lines that parse as an event (hide Ignored)
+        #[arg(long)]
+        matched: bool,
+    },
+    /// Show process logs from the last scan (warnings, errors, override skips)
+    Logs {
+        /// Filter by level: error, warn, info
+        #[arg(long)]
+        level: Option<String>,
+        /// Limit number of entries shown
+        #[arg(long, default_value = "200")]
+        limit: usize,
+    },
+}
+
+/// Exports, reports and endpoints for sharing outside the app.
+#[derive(Subcommand)]
+enum ShareCommands {
+    /// Export characters, kills, trainers, lastys, pets, clan history and companions to JSON
+    /// (or, with `--format csv`, CSV) files
+    Export {
+        /// Table to export: characters, kills, trainers, lastys, pets, clan_history,
+        /// companions, or all
+        #[arg(long, default_value = "all")]
+        table: String,
+        /// Export a single character (merged view) instead of every character
+        #[arg(long)]
+        character: Option<String>,
+        /// Directory to write `<table>.<format>` files into
+        #[arg(long, short = 'o', default_value = ".")]
+        output: PathBuf,
+        /// Make the files safe to post: other players' names become "Player N" (the same
+        /// player keeps one number across files) and trainer notes are left out
+        #[arg(long)]
+        anonymize: bool,
+    },
+    /// Write a digest of new ranks, kills, deaths and lasty progress across all characters,
+    /// for running from cron / Task Scheduler after a scheduled scan
+    Digest {
+        /// Period start: "last" (the logs scanned since the previous `--since last` digest;
+        /// the last 7 days of logs the first time), "all", or a date (YYYY-MM-DD)
+        #[arg(long, default_value = "last")]
+        since: String,
+        /// Output file (prints to stdout when omitted)
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Also POST the digest to this webhook URL (Discord/Slack-compatible JSON) instead
+        /// of the saved one
+        #[arg(long)]
+        webhook: Option<String>,
+        /// Save --webhook as the default for future digests
+        #[arg(long, requires = "webhook")]
+        save_webhook: bool,
+        /// Forget the saved webhook
+        #[arg(long, conflicts_with_all = ["webhook", "save_webhook"])]
+        clear_webhook: bool,
+    },
+    /// Rank every character in the database by one metric (merged alts count once)
+    Leaderboard {
+        /// Metric: kills, ranks, coins, deaths, depart-rate
+        #[arg(long, default_value = "kills")]
+        metric: String,
+        /// Show only the top N characters
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Compare two characters side by side: effective ranks per trainer, kills per creature,
+    /// and totals and fighter stats with the difference (second minus first)
+    Compare {
+        /// First character
+        name1: String,
+        /// Second character
+        name2: String,
+        /// Creatures shown in the kills table, most killed first
+        #[arg(long, default_value_t = 20)]
+        kills: usize,
+    },
+    /// Write a compact, versioned JSON profile summary for embedding in clan website widgets
+    Widget {
+        /// Character name
+        name: String,
+        /// Output file (prints to stdout when omitted)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Show, attach or remove a character's portrait (included in widgets and roster reports)
+    Portrait {
+        /// Character name
+        name: String,
+        /// Attach this PNG, JPEG, GIF or WebP image as the portrait
+        #[arg(long, conflicts_with = "clear")]
+        set: Option<PathBuf>,
+        /// Remove the stored portrait
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Write the roster report (every character's widget) into a folder as HTML and JSON
+    Roster {
+        /// Destination folder
+        folder: PathBuf,
+        /// Write only the HTML page
+        #[arg(long, conflicts_with = "json_only")]
+        html_only: bool,
+        /// Write only the JSON document
+        #[arg(long)]
+        json_only: bool,
+    },
+    /// Serve read-only JSON endpoints over HTTP until stopped: /characters,
+    /// /characters/{name}/kills and /search?q= (speech and thoughts left out)
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on. There is no authentication: any other address lets everyone
+        /// who can reach it read every character's logs
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+    },
+}
+
+/// Built-in catalogs, the demo and setup helpers.
+#[derive(Subcommand)]
+enum CatalogCommands {
+    /// Show the built-in trainer catalog
+    TrainerCatalog {
+        /// Filter by profession (fighter, healer, mystic, ranger, bloodmage, champion)
+        #[arg(long)]
+        profession: Option<String>,
+        /// Character whose ranks, maxed trainers and coverage to show
+        #[arg(long)]
+        character: Option<String>,
+    },
+    /// List every bestiary creature with its value; with --character, what you have killed,
+    /// been killed by, or never encountered
+    CreatureCatalog {
+        /// Character whose bestiary completion to show
+        #[arg(long)]
+        character: Option<String>,
+    },
+    /// List creatures in the kills table that the bestiary (and --creature-db) can't value,
+    /// most encountered first; `--format csv` prints a creatures CSV to fill in
+    CatalogDiff {
+        /// Only this character's kills [default: every character]
+        #[arg(long)]
+        character: Option<String>,
+    },
+    /// Build a demo database from bundled sample logs in a temporary directory, then run a
+    /// command against it (e.g. `demo summary Ashwin`), or list commands to try
+    Demo {
+        /// Command (and its arguments) to run against the demo database
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, value_name = "COMMAND")]
+        args: Vec<String>,
     },
-    /// Print the path to the GUI's default database file
+    /// Print the default database path (the platform data directory, shared with the GUI)
     GuiDbPath,
+    /// Show the config file location and the defaults it sets
+    Config,
     /// Scan log files and extract item usage command help blocks (no DB needed)
     #[command(name = "useitem-help")]
     UseItemHelp {
@@ -321,149 +1029,435 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum IndexAction {
+    /// Rebuild the index from the files already scanned (e.g. after scanning with --no-index)
+    Rebuild,
+    /// Merge the index into one segment for faster searches
+    Optimize,
+    /// Show indexed lines, files and characters, and the estimated index size
+    Stats,
+}
+
 fn main() {
     env_logger::init();
     let cli = Cli::parse();
 
     if let Err(e) = run(cli) {
         eprintln!("Error: {}", e);
+        if let Some(hint) = remedy(&e) {
+            eprintln!("Hint: {}", hint);
+        }
         std::process::exit(1);
     }
 }
 
-/// Return the path to the GUI's default database file.
-/// Mirrors Tauri's app_data_dir() for identifier "com.dfsw.Amanuensis".
+/// Suggested next step for errors the user can fix themselves.
+fn remedy(e: &amanuensis_core::AmanuensisError) -> Option<&'static str> {
+    use amanuensis_core::AmanuensisError as E;
+    match e {
+        E::CharacterNotFound { .. } => {
+            Some("run `amanuensis characters` to list known names (names are case-sensitive)")
+        }
+        E::FileUnreadable { .. } => Some("check that the file exists and is readable by your user"),
+        E::SchemaMismatch { .. } => {
+            Some("check the path points at the right database (--db for Amanuensis, the Scribius .sqlite for import)")
+        }
+        E::FtsUnavailable { .. } => {
+            Some("rebuild the search index with `amanuensis scan <folder> --force` (without --no-index)")
+        }
+        _ => None,
+    }
+}
+
+/// The trainer catalog loaded from `--trainer-db` / the config file's `trainer_db`, if any.
+static TRAINER_DB: OnceLock<Arc<TrainerDb>> = OnceLock::new();
+
+/// The configured trainer catalog, else the bundled one.
+fn trainer_db() -> amanuensis_core::Result<Arc<TrainerDb>> {
+    match TRAINER_DB.get() {
+        Some(tdb) => Ok(tdb.clone()),
+        None => Ok(Arc::new(TrainerDb::bundled()?)),
+    }
+}
+
+/// The creature catalog CSV from `--creature-db` / the config file's `creature_db`, if any.
+/// Kept as a path so `watch` can reload it.
+static CREATURE_CATALOG: OnceLock<PathBuf> = OnceLock::new();
+
+/// The bundled bestiary with the configured creature catalog applied.
+fn creature_db() -> amanuensis_core::Result<CreatureDb> {
+    CreatureDb::load(CREATURE_CATALOG.get().map(PathBuf::as_path))
+}
+
+/// A parser using the configured trainer and creature catalogs.
+fn log_parser(db: Database) -> amanuensis_core::Result<LogParser> {
+    let parser = LogParser::with_trainer_db(db, trainer_db()?)?;
+    if CREATURE_CATALOG.get().is_some() {
+        parser.set_creature_db(creature_db()?);
+    }
+    Ok(parser)
+}
+
+/// Reload the creature catalog into `parser` when its file's modification time differs from
+/// `seen`. A catalog that no longer loads is reported and the previous one kept.
+fn reload_creature_catalog(parser: &LogParser, seen: &mut Option<std::time::SystemTime>) {
+    let Some(path) = CREATURE_CATALOG.get() else { return };
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    if modified == *seen {
+        return;
+    }
+    *seen = modified;
+    match creature_db() {
+        Ok(creatures) => {
+            parser.set_creature_db(creatures);
+            eprintln!("Reloaded creature catalog {}", path.display());
+        }
+        Err(e) => eprintln!("Keeping the previous creature catalog: {}", e),
+    }
+}
+
+/// The default database file in the platform data directory, shared with the GUI.
 fn gui_db_path() -> Option<PathBuf> {
-    #[cfg(target_os = "macos")]
-    {
-        // ~/Library/Application Support/com.dfsw.Amanuensis/amanuensis.db
-        let home = std::env::var("HOME").ok()?;
-        Some(PathBuf::from(home)
-            .join("Library")
-            .join("Application Support")
-            .join("com.dfsw.Amanuensis")
-            .join("amanuensis.db"))
-    }
-    #[cfg(target_os = "windows")]
-    {
-        // %APPDATA%\com.dfsw.Amanuensis\amanuensis.db
-        let appdata = std::env::var("APPDATA").ok()?;
-        Some(PathBuf::from(appdata)
-            .join("com.dfsw.Amanuensis")
-            .join("amanuensis.db"))
+    data_dir::default_db_path()
+}
+
+/// `--db`, else the config file's `db`, else the platform data directory (see
+/// `amanuensis_core::data_dir`). `--gui-db` skips the config file's `db`.
+fn resolve_db_path(cli: &Cli, config: &Config) -> amanuensis_core::Result<String> {
+    if let Some(db) = &cli.db {
+        return Ok(db.clone());
     }
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        // $XDG_DATA_HOME/com.dfsw.Amanuensis/amanuensis.db
-        // or ~/.local/share/com.dfsw.Amanuensis/amanuensis.db
-        let data_home = std::env::var("XDG_DATA_HOME").ok()
-            .map(PathBuf::from)
-            .or_else(|| {
-                let home = std::env::var("HOME").ok()?;
-                Some(PathBuf::from(home).join(".local").join("share"))
-            })?;
-        Some(data_home.join("com.dfsw.Amanuensis").join("amanuensis.db"))
+    if let (Some(db), false) = (&config.db, cli.gui_db) {
+        return Ok(db.clone());
+    }
+    let default = gui_db_path().ok_or_else(|| amanuensis_core::AmanuensisError::Data(
+        "Could not determine the data directory on this platform; pass --db".to_string()
+    ))?;
+    if let Some(legacy) = std::env::current_dir().ok().and_then(|cwd| data_dir::legacy_db_to_migrate(&cwd, &default)) {
+        return migrate_legacy_db(&legacy, &default);
     }
+    Ok(default.to_string_lossy().into_owned())
 }
 
-fn resolve_db_path(cli: &Cli) -> amanuensis_core::Result<String> {
-    if cli.gui_db {
-        gui_db_path()
-            .map(|p| p.to_string_lossy().into_owned())
-            .ok_or_else(|| amanuensis_core::AmanuensisError::Data(
-                "Could not determine GUI database path on this platform".to_string()
-            ))
-    } else {
-        Ok(cli.db.clone())
+/// Offer to move an `amanuensis.db` left in the working directory by older versions to the
+/// data directory. Declining (or a non-interactive stdin) keeps using it where it is.
+fn migrate_legacy_db(legacy: &Path, default: &Path) -> amanuensis_core::Result<String> {
+    use std::io::IsTerminal;
+    let keep = || Ok(legacy.to_string_lossy().into_owned());
+    if !io::stdin().is_terminal() {
+        eprintln!(
+            "Using {} from the current directory; run interactively to move it to {}, or pass --db.",
+            legacy.display(),
+            default.display()
+        );
+        return keep();
+    }
+    eprint!(
+        "Found {} in the current directory. The database now lives in {}.\nMove it there? [y/N] ",
+        legacy.display(),
+        default.display()
+    );
+    let _ = io::stderr().flush();
+    let input = read_stdin_line()?;
+    if !input.trim().eq_ignore_ascii_case("y") {
+        eprintln!("Keeping {}; pass --db or set `db` in the config file to stop this prompt.", legacy.display());
+        return keep();
+    }
+    data_dir::migrate_db(legacy, default)?;
+    eprintln!("Moved database to {}", default.display());
+    Ok(default.to_string_lossy().into_owned())
+}
+
+/// Reject a `--format` the command can't produce, before any work is done.
+fn check_format(command: &Commands, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let supported = match format {
+        OutputFormat::Table => !matches!(command, Commands::Share(ShareCommands::Export { .. })),
+        OutputFormat::Json => !matches!(command, Commands::Catalog(CatalogCommands::UseItemHelp { .. }) | Commands::Catalog(CatalogCommands::UpdateBestiary { .. })),
+        OutputFormat::Csv => matches!(
+            command,
+            Commands::Stats(StatsCommands::Kills { .. }) | Commands::Stats(StatsCommands::Frequency { .. }) | Commands::Share(ShareCommands::Export { .. }) | Commands::Catalog(CatalogCommands::CatalogDiff { .. })
+        ),
+    };
+    if supported {
+        return Ok(());
     }
+    let hint = match format {
+        OutputFormat::Table => "export writes json or csv files",
+        OutputFormat::Json => "useitem-help and update-bestiary only print text",
+        OutputFormat::Csv => "csv is only available for kills, frequency, export and catalog-diff",
+    };
+    Err(amanuensis_core::AmanuensisError::Data(format!(
+        "--format {} is not supported by this command ({})",
+        format.as_str(),
+        hint
+    )))
 }
 
 fn run(cli: Cli) -> amanuensis_core::Result<()> {
+    let (config, config_path) = Config::load(cli.config.as_deref())?;
+    // The demo runs its command through `run` again, which checks the format then
+    if let Commands::Catalog(CatalogCommands::Demo { args }) = &cli.command {
+        return cmd_demo(args, cli.format);
+    }
+    if let Some(format) = cli.format {
+        check_format(&cli.command, format)?;
+    }
+    // The config file's format only applies where the command can produce it.
+    let format = cli
+        .format
+        .or(config.format.filter(|f| check_format(&cli.command, *f).is_ok()))
+        .unwrap_or_default();
+
+    if matches!(cli.command, Commands::Catalog(CatalogCommands::Config)) {
+        return cmd_config(&config, config_path.as_deref(), format);
+    }
+    // Loaded up front so a bad catalog is reported before any work starts.
+    if let Some(catalog) = cli.trainer_db.as_ref().or(config.trainer_db.as_ref()) {
+        let _ = TRAINER_DB.set(Arc::new(TrainerDb::load(Some(catalog))?));
+    }
+    if let Some(catalog) = cli.creature_db.as_ref().or(config.creature_db.as_ref()) {
+        CreatureDb::load(Some(catalog))?;
+        let _ = CREATURE_CATALOG.set(catalog.clone());
+    }
+
     // Handle commands that don't need a DB before resolving the db path
-    if matches!(cli.command, Commands::GuiDbPath) {
+    if matches!(cli.command, Commands::Catalog(CatalogCommands::GuiDbPath)) {
         match gui_db_path() {
+            Some(p) if format.is_json() => print_json(&p)?,
             Some(p) => println!("{}", p.display()),
             None => {
-                eprintln!("Could not determine GUI database path on this platform.");
+                eprintln!("Could not determine the data directory on this platform.");
                 std::process::exit(1);
             }
         }
         return Ok(());
     }
-    if let Commands::UseItemHelp { folder, recursive } = &cli.command {
+    if let Commands::Catalog(CatalogCommands::UseItemHelp { folder, recursive }) = &cli.command {
         return cmd_useitem_help(folder, *recursive);
     }
-    if let Commands::UpdateBestiary { xml_path, aliases, output, dry_run } = &cli.command {
+    if let Commands::Catalog(CatalogCommands::UpdateBestiary { xml_path, aliases, output, dry_run }) = &cli.command {
         return cmd_update_bestiary(xml_path, aliases.as_deref(), output.as_deref(), *dry_run);
     }
-    if let Commands::Bestiary { name } = &cli.command {
-        return cmd_bestiary(name);
+    if let Commands::Catalog(CatalogCommands::Bestiary { name }) = &cli.command {
+        return cmd_bestiary(name, format);
+    }
+    if let Commands::Data(DataCommands::CompareArchives { folder_a, folder_b }) = &cli.command {
+        return cmd_compare_archives(folder_a, folder_b, format);
     }
 
-    let db_path = resolve_db_path(&cli)?;
+    let db_path = resolve_db_path(&cli, &config)?;
+    // The data directory may not exist yet on first run
+    if let Some(dir) = gui_db_path().filter(|p| Path::new(&db_path) == p).as_deref().and_then(Path::parent) {
+        std::fs::create_dir_all(dir)?;
+    }
     if cli.gui_db {
         eprintln!("Using GUI database: {}", db_path);
     }
 
+    // The database's `index_log_lines` setting, the default below the flags and config file.
+    let db_indexing = || Database::open(&db_path).and_then(|db| db.indexing_enabled());
+
     match cli.command {
-        Commands::Scan { folder, force, recursive, no_index } => {
-            cmd_scan(&db_path, &folder, force, recursive, no_index)
+        Commands::Data(DataCommands::Scan {
+            folder,
+            force,
+            recursive,
+            no_index,
+            index,
+            nice,
+            hooks,
+            emit_events,
+            witnessed_falls,
+            unknown_lines,
+            chat,
+        }) => {
+            let folder = match folder {
+                Some(folder) => folder,
+                None => config.default_log_folder()?,
+            };
+            let no_index = config.skip_index(no_index, index, db_indexing()?);
+            cmd_scan(
+                &db_path,
+                &folder,
+                force,
+                recursive,
+                no_index,
+                nice,
+                ScanTracking { witnessed_falls, unknown_lines, chat },
+                hooks.as_deref(),
+                emit_events.as_deref(),
+                format,
+            )
+        }
+        Commands::Data(DataCommands::Update { folders, recursive, no_index, index, nice, hooks, witnessed_falls, unknown_lines, chat }) => {
+            let folders = config.log_folders(folders)?;
+            let no_index = config.skip_index(no_index, index, db_indexing()?);
+            let tracking = ScanTracking { witnessed_falls, unknown_lines, chat };
+            cmd_update(&db_path, &folders, recursive, no_index, nice, tracking, hooks.as_deref(), format)
+        }
+        Commands::Data(DataCommands::Watch { folders, recursive, interval, no_index, index, nice, hooks, ws, notify }) => {
+            let folders = config.log_folders(folders)?;
+            let no_index = config.skip_index(no_index, index, db_indexing()?);
+            let live = LiveOutputs { hooks: hooks.as_deref(), ws: ws.as_deref(), notify: notify.as_deref() };
+            cmd_watch(&db_path, &folders, recursive, interval, no_index, nice, live, format)
+        }
+        Commands::Data(DataCommands::Daemon { interval, folders, recursive, no_index, index, nice, hooks, ws, notify }) => {
+            let folders = config.log_folders(folders)?;
+            let no_index = config.skip_index(no_index, index, db_indexing()?);
+            let live = LiveOutputs { hooks: hooks.as_deref(), ws: ws.as_deref(), notify: notify.as_deref() };
+            cmd_daemon(&db_path, &folders, recursive, interval, no_index, nice, live, format)
+        }
+        Commands::Share(ShareCommands::Serve { port, bind }) => cmd_serve(&db_path, &bind, port),
+        Commands::Data(DataCommands::Pending { folders, recursive, list }) => {
+            cmd_pending(&db_path, &config.log_folders(folders)?, recursive, list, format)
+        }
+        Commands::Data(DataCommands::Rescan { folders, recursive, no_index, index, nice }) => {
+            let folders = config.log_folders(folders)?;
+            let no_index = config.skip_index(no_index, index, db_indexing()?);
+            cmd_rescan(&db_path, &folders, recursive, no_index, nice, format)
+        }
+        Commands::Data(DataCommands::ScanFiles { files, force, no_index, index, nice }) => {
+            cmd_scan_files(&db_path, &files, force, config.skip_index(no_index, index, db_indexing()?), nice, format)
+        }
+        Commands::Data(DataCommands::ScanErrors { retry }) => cmd_scan_errors(&db_path, retry, config.skip_index(false, false, db_indexing()?), format),
+        Commands::Stats(StatsCommands::Characters) => cmd_characters(&db_path, format),
+        Commands::Stats(StatsCommands::Summary { name }) => cmd_summary(&db_path, &name, format),
+        Commands::Stats(StatsCommands::Frequency { name, bin, solo, by_verb, limit }) => {
+            cmd_frequency(&db_path, &name, &bin, solo, by_verb, format, limit)
+        }
+        Commands::Stats(StatsCommands::Kills { name, sort, limit, family, rarity, seasonal, by_location }) => {
+            cmd_kills(&db_path, &name, &sort, limit, family, rarity, seasonal, by_location, format)
+        }
+        Commands::Stats(StatsCommands::Trainers { name }) => cmd_trainers(&db_path, &name, format),
+        Commands::Stats(StatsCommands::TrainerHistory { name, trainer }) => cmd_trainer_history(&db_path, &name, &trainer, format),
+        Commands::Stats(StatsCommands::Eta { name, trainer, target }) => cmd_eta(&db_path, &name, &trainer, target, format),
+        Commands::Stats(StatsCommands::Pets { name }) => cmd_pets(&db_path, &name, format),
+        Commands::Stats(StatsCommands::Sessions { name, limit }) => cmd_sessions(&db_path, &name, limit, format),
+        Commands::Stats(StatsCommands::HuntReport { name, session, out }) => {
+            cmd_hunt_report(&db_path, &name, session, out.as_deref(), format)
+        }
+        Commands::Stats(StatsCommands::Accuracy { name }) => cmd_accuracy(&db_path, &name, format),
+        Commands::Stats(StatsCommands::Damage { name }) => cmd_damage(&db_path, &name, format),
+        Commands::Stats(StatsCommands::Companions { name, limit }) => cmd_companions(&db_path, &name, limit, format),
+        Commands::Stats(StatsCommands::Heals { name }) => cmd_heals(&db_path, &name, format),
+        Commands::Stats(StatsCommands::Rescues { name }) => cmd_rescues(&db_path, &name, format),
+        Commands::Stats(StatsCommands::Falls { name, by_cause, limit }) => cmd_falls(&db_path, &name, by_cause, limit, format),
+        Commands::Stats(StatsCommands::Correspondents { name, limit }) => cmd_correspondents(&db_path, &name, limit, format),
+        Commands::Stats(StatsCommands::Zones { name, top }) => cmd_zones(&db_path, &name, top, format),
+        Commands::Stats(StatsCommands::Milestones { name }) => cmd_milestones(&db_path, &name, format),
+        Commands::Stats(StatsCommands::Boosts { name, windows }) => cmd_boosts(&db_path, &name, windows, format),
+        Commands::Stats(StatsCommands::Snapshots { name, since }) => cmd_snapshots(&db_path, &name, since.as_deref(), format),
+        Commands::Stats(StatsCommands::Arena { name, limit }) => cmd_arena(&db_path, &name, limit, format),
+        Commands::Stats(StatsCommands::Deaths { name, limit }) => cmd_deaths(&db_path, &name, limit, format),
+        Commands::Stats(StatsCommands::Assists { name, creature }) => cmd_assists(&db_path, &name, creature.as_deref(), format),
+        Commands::Stats(StatsCommands::Lastys { name }) => cmd_lastys(&db_path, &name, format),
+        Commands::Data(DataCommands::Merge { target, sources }) => cmd_merge(&db_path, &target, &sources, format),
+        Commands::Data(DataCommands::Unmerge { name }) => cmd_unmerge(&db_path, &name, format),
+        Commands::Data(DataCommands::DeleteCharacter { name, yes }) => cmd_delete_character(&db_path, &name, yes, format),
+        Commands::Data(DataCommands::Import { source, output, force }) => cmd_import(&source, output.as_deref().unwrap_or(&db_path), force, format),
+        Commands::Ranks(RankCommands::SetTrainerNote { name, trainer, note }) => {
+            cmd_set_trainer_note(&db_path, &name, &trainer, note.as_deref(), format)
+        }
+        Commands::Ranks(RankCommands::ClearRankOverrides { yes }) => cmd_clear_rank_overrides(&db_path, yes, format),
+        Commands::Data(DataCommands::ResetLogs { yes }) => cmd_reset_logs(&db_path, yes, format),
+        Commands::Data(DataCommands::Unscan { file }) => cmd_unscan(&db_path, &file, format),
+        Commands::Ranks(RankCommands::SetRanks { name, trainer, ranks }) => {
+            cmd_set_ranks(&db_path, &name, &trainer, ranks, format)
+        }
+        Commands::Ranks(RankCommands::SetRanksBulk { name, file, dry_run }) => cmd_set_ranks_bulk(&db_path, &name, &file, dry_run, format),
+        Commands::Ranks(RankCommands::Reconcile { name, paste, apply }) => cmd_reconcile(&db_path, &name, &paste, apply, format),
+        Commands::Text(TextCommands::Search { query, character, limit, offset, output, from, to, context, regex }) => {
+            let default_limit = if output.is_some() { i64::MAX } else { 50 };
+            let options = LogSearchOptions {
+                include_speech: true,
+                lines_before: context,
+                lines_after: context,
+                from: search_date_bound(from.as_deref(), "--from", false)?,
+                to: search_date_bound(to.as_deref(), "--to", true)?,
+                regex,
+                offset,
+                limit: limit.unwrap_or(default_limit),
+                ..Default::default()
+            };
+            cmd_search(&db_path, &query, character.as_deref(), options, output.as_deref(), format)
+        }
+        Commands::Text(TextCommands::Context { file, timestamp, lines }) => cmd_context(&db_path, &file, &timestamp, lines, format),
+        Commands::Text(TextCommands::IndexRetention { years, all_years, events_only }) => {
+            cmd_index_retention(&db_path, years, all_years, events_only, format)
+        }
+        Commands::Text(TextCommands::PruneIndex) => cmd_prune_index(&db_path, format),
+        Commands::Text(TextCommands::Index { action }) => cmd_index(&db_path, action, format),
+        Commands::Data(DataCommands::PathAliases { add, remove, clear }) => cmd_path_aliases(&db_path, &add, &remove, clear, format),
+        Commands::Data(DataCommands::CreatureNames { load, enable, disable, clear, preview }) => {
+            cmd_creature_names(&db_path, load.as_deref(), enable, disable, clear, preview, format)
+        }
+        Commands::Share(ShareCommands::Export { table, character, output, anonymize }) => {
+            let format = cli.format.unwrap_or(OutputFormat::Json);
+            cmd_export(&db_path, &table, format, character.as_deref(), &output, anonymize)
+        }
+        Commands::Data(DataCommands::Reset { yes }) => cmd_reset(&db_path, yes, format),
+        Commands::Catalog(CatalogCommands::TrainerCatalog { profession, character }) => {
+            cmd_trainer_catalog(&db_path, profession.as_deref(), character.as_deref(), format)
         }
-        Commands::Update { folders, recursive, no_index } => {
-            cmd_update(&db_path, &folders, recursive, no_index)
+        Commands::Catalog(CatalogCommands::CreatureCatalog { character }) => {
+            cmd_creature_catalog(&db_path, character.as_deref(), format)
         }
-        Commands::Pending { folders, recursive, list } => {
-            cmd_pending(&db_path, &folders, recursive, list)
+        Commands::Catalog(CatalogCommands::CatalogDiff { character }) => cmd_catalog_diff(&db_path, character.as_deref(), format),
+        Commands::Text(TextCommands::Chat { speaker, contains, character, limit }) => {
+            cmd_chat(&db_path, speaker, contains, character.as_deref(), limit, format)
         }
-        Commands::Rescan { folders, recursive, no_index } => {
-            cmd_rescan(&db_path, &folders, recursive, no_index)
+        Commands::Text(TextCommands::Unknowns { top, character }) => cmd_unknowns(&db_path, top, character.as_deref(), format),
+        Commands::Text(TextCommands::Classify { file, matched }) => cmd_classify(&file, matched, format),
+        Commands::Stats(StatsCommands::Glossary { name }) => cmd_glossary(&db_path, &name, format),
+        Commands::Stats(StatsCommands::Progression { name, min_kills, max_killed_by_ratio }) => {
+            cmd_progression(&db_path, &name, min_kills, max_killed_by_ratio, format)
         }
-        Commands::ScanFiles { files, force, no_index } => {
-            cmd_scan_files(&db_path, &files, force, no_index)
+        Commands::Share(ShareCommands::Digest { since, out, webhook, save_webhook, clear_webhook }) => {
+            cmd_digest(&db_path, &since, out.as_deref(), webhook.as_deref(), save_webhook, clear_webhook, format)
         }
-        Commands::Characters => cmd_characters(&db_path),
-        Commands::Summary { name } => cmd_summary(&db_path, &name),
-        Commands::Frequency { name, bin, solo, by_verb, format, limit } => {
-            cmd_frequency(&db_path, &name, &bin, solo, by_verb, &format, limit)
+        Commands::Share(ShareCommands::Leaderboard { metric, limit }) => {
+            cmd_leaderboard(&db_path, &metric, limit, format)
         }
-        Commands::Kills { name, sort, limit, family, rarity, seasonal, format } => {
-            cmd_kills(&db_path, &name, &sort, limit, family, rarity, seasonal, &format)
+        Commands::Share(ShareCommands::Compare { name1, name2, kills }) => cmd_compare(&db_path, &name1, &name2, kills, format),
+        Commands::Share(ShareCommands::Widget { name, out }) => cmd_widget(&db_path, &name, out.as_deref()),
+        Commands::Share(ShareCommands::Portrait { name, set, clear }) => {
+            cmd_portrait(&db_path, &name, set.as_deref(), clear, format)
         }
-        Commands::Trainers { name } => cmd_trainers(&db_path, &name),
-        Commands::Pets { name } => cmd_pets(&db_path, &name),
-        Commands::Lastys { name } => cmd_lastys(&db_path, &name),
-        Commands::Merge { target, sources } => cmd_merge(&db_path, &target, &sources),
-        Commands::Unmerge { name } => cmd_unmerge(&db_path, &name),
-        Commands::Import { source, output, force } => cmd_import(&source, &output, force),
-        Commands::SetTrainerNote { name, trainer, note } => {
-            cmd_set_trainer_note(&db_path, &name, &trainer, note.as_deref())
+        Commands::Share(ShareCommands::Roster { folder, html_only, json_only }) => {
+            cmd_roster(&db_path, &folder, !json_only, !html_only, format)
         }
-        Commands::ClearRankOverrides { yes } => cmd_clear_rank_overrides(&db_path, yes),
-        Commands::ResetLogs { yes } => cmd_reset_logs(&db_path, yes),
-        Commands::SetRanks { name, trainer, ranks } => {
-            cmd_set_ranks(&db_path, &name, &trainer, ranks)
+        Commands::Stats(StatsCommands::Coins { name, timeline }) => match timeline {
+            Some(period) => cmd_coin_timeline(&db_path, &name, &period, format),
+            None => cmd_coins(&db_path, &name, format),
+        },
+        Commands::Stats(StatsCommands::Casino { name }) => cmd_casino(&db_path, &name, format),
+        Commands::Ranks(RankCommands::FighterStats { name, race, weapon, items, save, clear }) => {
+            cmd_fighter_stats(&db_path, &name, race, weapon, items, save, clear, format)
         }
-        Commands::Search { query, character, limit } => {
-            cmd_search(&db_path, &query, character.as_deref(), limit)
+        Commands::Ranks(RankCommands::RankSources { name, trainer }) => cmd_rank_sources(&db_path, &name, trainer.as_deref(), format),
+        Commands::Ranks(RankCommands::Simulate { name, add }) => cmd_simulate(&db_path, &name, &add, format),
+        Commands::Ranks(RankCommands::UntrainPlan { name, more, less, ranks, coins_per_rank, budget }) => {
+            let cost = amanuensis_core::untrain::UntrainCost { coins_per_rank, budget };
+            cmd_untrain_plan(&db_path, &name, &more, &less, ranks, cost, format)
         }
-        Commands::Reset { yes } => cmd_reset(&db_path, yes),
-        Commands::TrainerCatalog { profession } => cmd_trainer_catalog(profession.as_deref()),
-        Commands::Coins { name } => cmd_coins(&db_path, &name),
-        Commands::FighterStats { name } => cmd_fighter_stats(&db_path, &name),
-        Commands::Logs { level, limit } => cmd_logs(&db_path, level.as_deref(), limit),
-        Commands::Checkpoints { name, all, trainer } => {
-            cmd_checkpoints(&db_path, &name, all, trainer.as_deref())
+        Commands::Text(TextCommands::Logs { level, limit }) => cmd_logs(&db_path, level.as_deref(), limit, format),
+        Commands::Ranks(RankCommands::Checkpoints { name, all, trainer }) => {
+            cmd_checkpoints(&db_path, &name, all, trainer.as_deref(), format)
         }
-        Commands::SetRankMode { name, trainer, mode, ranks, date } => {
-            cmd_set_rank_mode(&db_path, &name, &trainer, &mode, ranks, date.as_deref())
+        Commands::Ranks(RankCommands::SetRankMode { name, trainer, mode, ranks, date }) => {
+            cmd_set_rank_mode(&db_path, &name, &trainer, &mode, ranks, date.as_deref(), format)
         }
-        Commands::SetProfession { name, profession } => {
-            cmd_set_profession(&db_path, &name, &profession)
+        Commands::Ranks(RankCommands::SetProfession { name, profession }) => {
+            cmd_set_profession(&db_path, &name, &profession, format)
         }
-        Commands::GuiDbPath => unreachable!("handled above"),
-        Commands::UseItemHelp { folder, recursive } => cmd_useitem_help(&folder, recursive),
-        Commands::UpdateBestiary { .. } => unreachable!("handled above"),
-        Commands::Bestiary { .. } => unreachable!("handled above"),
+        Commands::Catalog(CatalogCommands::Demo { .. }) => unreachable!("handled above"),
+        Commands::Catalog(CatalogCommands::GuiDbPath) => unreachable!("handled above"),
+        Commands::Catalog(CatalogCommands::Config) => unreachable!("handled above"),
+        Commands::Catalog(CatalogCommands::UseItemHelp { folder, recursive }) => cmd_useitem_help(&folder, recursive),
+        Commands::Catalog(CatalogCommands::UpdateBestiary { .. }) => unreachable!("handled above"),
+        Commands::Catalog(CatalogCommands::Bestiary { .. }) => unreachable!("handled above"),
+        Commands::Data(DataCommands::CompareArchives { .. }) => unreachable!("handled above"),
     }
 }
 
@@ -471,7 +1465,7 @@ fn run(cli: Cli) -> amanuensis_core::Result<()> {
 fn resolve_character(db: &Database, name: &str) -> amanuensis_core::Result<amanuensis_core::models::Character> {
     let char = db
         .get_character(name)?
-        .ok_or_else(|| amanuensis_core::AmanuensisError::Data(format!("Character '{}' not found", name)))?;
+        .ok_or_else(|| amanuensis_core::AmanuensisError::CharacterNotFound { name: name.to_string() })?;
     let char_id = char.id.unwrap();
     if let Some(target_name) = db.get_merged_into_name(char_id)? {
         return Err(amanuensis_core::AmanuensisError::Data(format!(
@@ -482,103 +1476,439 @@ fn resolve_character(db: &Database, name: &str) -> amanuensis_core::Result<amanu
     Ok(char)
 }
 
+/// Trainer name, annotated when the trainer has been removed from the game.
+fn retired_label(name: &str, retired: bool, replaced_by: Option<&str>) -> String {
+    match (retired, replaced_by) {
+        (false, _) => name.to_string(),
+        (true, Some(r)) => format!("{} (retired, now {})", name, r),
+        (true, None) => format!("{} (retired)", name),
+    }
+}
+
 /// Build a multiplier map from TrainerDb metadata.
 fn build_multiplier_map() -> HashMap<String, f64> {
-    let tdb = TrainerDb::bundled().expect("Failed to load bundled trainer data");
+    let tdb = trainer_db().expect("Failed to load trainer data");
     let meta = tdb.all_trainer_metadata();
     meta.into_iter().map(|m| (m.name, m.multiplier)).collect()
 }
 
-fn print_scan_result(result: &amanuensis_core::parser::ScanResult) {
-    println!();
-    println!("Scan complete:");
-    println!("  Characters found:  {}", result.characters);
-    println!("  Files scanned:     {}", result.files_scanned);
-    println!("  Files skipped:     {}", result.skipped);
-    println!("  Lines parsed:      {}", result.lines_parsed);
-    println!("  Events recorded:   {}", result.events_found);
+fn print_scan_result(result: &amanuensis_core::parser::ScanResult, format: OutputFormat) -> amanuensis_core::Result<()> {
+    write_scan_result(&mut io::stdout(), result, format)
+}
+
+fn write_scan_result(
+    out: &mut dyn Write,
+    result: &amanuensis_core::parser::ScanResult,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    if format.is_json() {
+        writeln!(out, "{}", serde_json::to_string_pretty(result)?)?;
+        return Ok(());
+    }
+    writeln!(out)?;
+    writeln!(out, "Scan complete:")?;
+    writeln!(out, "  Characters found:  {}", result.characters)?;
+    writeln!(out, "  Files scanned:     {}", result.files_scanned)?;
+    writeln!(out, "  Files skipped:     {}", result.skipped)?;
+    writeln!(out, "  Lines parsed:      {}", result.lines_parsed)?;
+    writeln!(out, "  Events recorded:   {}", result.events_found)?;
     if result.errors > 0 {
-        println!("  Errors:            {}", result.errors);
+        writeln!(out, "  Errors:            {} (review with `amanuensis scan-errors`)", result.errors)?;
+        for e in &result.error_details {
+            let name = Path::new(&e.file_path).file_name().map_or(e.file_path.clone(), |n| n.to_string_lossy().to_string());
+            match e.line {
+                Some(line) => writeln!(out, "    {} line {}: {}", name, line, e.message)?,
+                None => writeln!(out, "    {}: {}", name, e.message)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Attach the hook config at `path`, if one was given, to `parser`.
+fn attach_hooks(parser: &LogParser, path: Option<&Path>) -> amanuensis_core::Result<()> {
+    if let Some(path) = path {
+        let config = amanuensis_core::HookConfig::load(path)?;
+        eprintln!("Loaded {} hook(s) from {}", config.hooks.len(), path.display());
+        parser.set_hooks(Some(config));
+    }
+    Ok(())
+}
+
+/// Where `watch` and `daemon` send events besides the database.
+#[derive(Clone, Copy)]
+struct LiveOutputs<'a> {
+    hooks: Option<&'a Path>,
+    ws: Option<&'a str>,
+    notify: Option<&'a Path>,
+}
+
+/// Opt-in extras `scan` and `update` record on top of the usual events.
+#[derive(Clone, Copy)]
+struct ScanTracking {
+    witnessed_falls: bool,
+    unknown_lines: bool,
+    chat: bool,
+}
+
+impl ScanTracking {
+    fn apply(self, parser: &LogParser) {
+        parser.set_track_witnessed_falls(self.witnessed_falls);
+        parser.set_track_unknown_lines(self.unknown_lines);
+        parser.set_track_chat(self.chat);
+    }
+}
+
+fn load_notify(path: Option<&Path>) -> amanuensis_core::Result<Option<amanuensis_core::NotifyConfig>> {
+    let Some(path) = path else { return Ok(None) };
+    let config = amanuensis_core::NotifyConfig::load(path)?;
+    eprintln!("Loaded {} notify trigger(s) from {}", config.triggers.len(), path.display());
+    Ok(Some(config))
+}
+
+/// Post a pass's queued notifications. A failed post is reported and dropped, like a
+/// failed hook; the scan carries on.
+fn send_notifications(config: &amanuensis_core::NotifyConfig, messages: Vec<String>) {
+    if messages.is_empty() {
+        return;
+    }
+    let client = reqwest::blocking::Client::new();
+    for message in messages {
+        let sent = client
+            .post(&config.webhook_url)
+            .json(&config.discord_payload(&message))
+            .send()
+            .and_then(|r| r.error_for_status());
+        if let Err(e) = sent {
+            eprintln!("[{}] notification failed: {}", amanuensis_core::date::now(), e);
+        }
+    }
+}
+
+/// Start the `--ws` event server, if asked for.
+fn bind_ws(addr: Option<&str>) -> amanuensis_core::Result<Option<ws::WsBroadcaster>> {
+    let Some(addr) = addr else { return Ok(None) };
+    let broadcaster = ws::WsBroadcaster::bind(addr).map_err(|e| {
+        io::Error::new(e.kind(), format!("Can't serve WebSocket events on {}: {}", addr, e))
+    })?;
+    eprintln!("Serving live events at ws://{}", broadcaster.local_addr());
+    Ok(Some(broadcaster))
+}
+
+fn cmd_serve(db_path: &str, bind: &str, port: u16) -> amanuensis_core::Result<()> {
+    let listener = std::net::TcpListener::bind((bind, port)).map_err(|e| {
+        io::Error::new(e.kind(), format!("Can't listen on {}:{}: {}", bind, port, e))
+    })?;
+    // Create or migrate the schema once; requests then share one read-only connection.
+    Database::open(db_path)?;
+    let db = Database::open_read_only(db_path)?;
+    let addr = listener.local_addr()?;
+    if !addr.ip().is_loopback() {
+        eprintln!("Warning: {} is reachable from other machines, with no authentication", addr);
     }
+    eprintln!("Serving {} at http://{} (Ctrl-C to stop)", db_path, addr);
+    serve::run(listener, &db)?;
+    Ok(())
 }
 
-fn cmd_scan(db_path: &str, folder: &Path, force: bool, recursive: bool, no_index: bool) -> amanuensis_core::Result<()> {
-    println!("Scanning logs in: {}", folder.display());
+#[allow(clippy::too_many_arguments)]
+fn cmd_scan(
+    db_path: &str,
+    folder: &Path,
+    force: bool,
+    recursive: bool,
+    no_index: bool,
+    nice: bool,
+    tracking: ScanTracking,
+    hooks: Option<&Path>,
+    emit_events: Option<&Path>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    // With `--emit-events -` stdout carries only the event stream; everything else goes to stderr.
+    let events_to_stdout = emit_events.is_some_and(|p| p.as_os_str() == "-");
+    if !format.is_json() {
+        if events_to_stdout {
+            eprintln!("Scanning logs in: {}", folder.display());
+        } else {
+            println!("Scanning logs in: {}", folder.display());
+        }
+    }
 
     let db = Database::open(db_path)?;
-    let parser = LogParser::new(db)?;
+    let parser = log_parser(db)?;
+    parser.set_nice(nice);
+    tracking.apply(&parser);
+    attach_hooks(&parser, hooks)?;
+    match emit_events {
+        Some(_) if events_to_stdout => parser.set_event_stream(Some(Box::new(io::stdout()))),
+        Some(path) => {
+            let file = std::fs::File::create(path)?;
+            parser.set_event_stream(Some(Box::new(io::BufWriter::new(file))));
+        }
+        None => {}
+    }
     let index_lines = !no_index;
 
-    let progress = |current: usize, total: usize, filename: &str| {
-        eprint!("\r[{}/{}] {}", current + 1, total, filename);
-        let _ = io::stderr().flush();
-    };
+    let progress = ScanProgress::attach(&parser);
 
     let result = if recursive {
-        parser.scan_recursive_with_progress(folder, force, index_lines, progress)?
+        parser.scan_recursive_with_progress(folder, force, index_lines, |c, t, f| progress.file(c, t, f))?
     } else {
-        parser.scan_folder_with_progress(folder, force, index_lines, progress)?
+        parser.scan_folder_with_progress(folder, force, index_lines, |c, t, f| progress.file(c, t, f))?
     };
-    eprintln!();
+    progress.finish();
 
     parser.finalize_characters()?;
-    print_scan_result(&result);
-
-    Ok(())
+    // Flushes a buffered event file
+    parser.set_event_stream(None);
+    if events_to_stdout {
+        write_scan_result(&mut io::stderr(), &result, format)
+    } else {
+        print_scan_result(&result, format)
+    }
 }
 
-fn cmd_rescan(db_path: &str, folders: &[PathBuf], recursive: bool, no_index: bool) -> amanuensis_core::Result<()> {
-    println!("Resetting derived data and re-scanning {} folder(s)...", folders.len());
-    for f in folders {
-        println!("  - {}", f.display());
+fn cmd_rescan(
+    db_path: &str,
+    folders: &[PathBuf],
+    recursive: bool,
+    no_index: bool,
+    nice: bool,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    if !format.is_json() {
+        println!("Resetting derived data and re-scanning {} folder(s)...", folders.len());
+        for f in folders {
+            println!("  - {}", f.display());
+        }
     }
     let db = Database::open(db_path)?;
-    let parser = LogParser::new(db)?;
+    let parser = log_parser(db)?;
+    parser.set_nice(nice);
     let index_lines = !no_index;
 
     let sources: Vec<(PathBuf, bool)> = folders.iter().map(|f| (f.clone(), recursive)).collect();
 
-    let progress = |current: usize, total: usize, filename: &str| {
-        eprint!("\r[{}/{}] {}", current + 1, total, filename);
-        let _ = io::stderr().flush();
-    };
+    let progress = ScanProgress::attach(&parser);
 
-    let result = parser.rescan_sources(&sources, index_lines, progress)?;
-    eprintln!();
-    print_scan_result(&result);
-    Ok(())
+    let result = parser.rescan_sources(&sources, index_lines, |c, t, f| progress.file(c, t, f))?;
+    progress.finish();
+    print_scan_result(&result, format)
 }
 
-fn cmd_update(db_path: &str, folders: &[PathBuf], recursive: bool, no_index: bool) -> amanuensis_core::Result<()> {
-    println!("Updating from {} folder(s) (incremental, no reset)...", folders.len());
-    for f in folders {
-        println!("  - {}", f.display());
+#[allow(clippy::too_many_arguments)]
+fn cmd_update(
+    db_path: &str,
+    folders: &[PathBuf],
+    recursive: bool,
+    no_index: bool,
+    nice: bool,
+    tracking: ScanTracking,
+    hooks: Option<&Path>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    if !format.is_json() {
+        println!("Updating from {} folder(s) (incremental, no reset)...", folders.len());
+        for f in folders {
+            println!("  - {}", f.display());
+        }
     }
     let db = Database::open(db_path)?;
-    let parser = LogParser::new(db)?;
+    let parser = log_parser(db)?;
+    parser.set_nice(nice);
+    tracking.apply(&parser);
+    attach_hooks(&parser, hooks)?;
     let index_lines = !no_index;
 
     let sources: Vec<(PathBuf, bool)> = folders.iter().map(|f| (f.clone(), recursive)).collect();
 
-    let progress = |current: usize, total: usize, filename: &str| {
-        eprint!("\r[{}/{}] {}", current + 1, total, filename);
-        let _ = io::stderr().flush();
-    };
+    let progress = ScanProgress::attach(&parser);
 
-    let result = parser.update_sources(&sources, index_lines, progress)?;
-    eprintln!();
-    if result.files_scanned == 0 && result.errors == 0 {
+    let result = parser.update_sources(&sources, index_lines, |c, t, f| progress.file(c, t, f))?;
+    progress.finish();
+    if result.files_scanned == 0 && result.errors == 0 && !format.is_json() {
         println!("Already up to date — no new or grown logs found.");
-    } else {
-        print_scan_result(&result);
+        return Ok(());
+    }
+    print_scan_result(&result, format)
+}
+
+/// Polls rather than using filesystem notifications: FSEvents silently drops events on
+/// external/USB volumes (see CLAUDE.md), and offset-resume makes an unchanged-file poll a
+/// cheap stat. Partial trailing lines are left for the next pass. With `--format json` each
+/// pass that found something prints one line of JSON.
+#[allow(clippy::too_many_arguments)]
+fn cmd_watch(
+    db_path: &str,
+    folders: &[PathBuf],
+    recursive: bool,
+    interval: u64,
+    no_index: bool,
+    nice: bool,
+    live: LiveOutputs,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    for f in folders {
+        if !f.is_dir() {
+            return Err(amanuensis_core::AmanuensisError::Data(
+                format!("'{}' is not a directory", f.display())
+            ));
+        }
+    }
+    let db = Database::open(db_path)?;
+    let parser = log_parser(db)?;
+    parser.set_nice(nice);
+    parser.set_complete_lines_only(true);
+    attach_hooks(&parser, live.hooks)?;
+    if let Some(broadcaster) = bind_ws(live.ws)? {
+        parser.subscribe_events(broadcaster.subscriber());
+    }
+    let notify = load_notify(live.notify)?;
+    parser.set_notifier(notify.clone());
+    let index_lines = !no_index;
+    let sources: Vec<(PathBuf, bool)> = folders.iter().map(|f| (f.clone(), recursive)).collect();
+    let interval = std::time::Duration::from_secs(interval.max(1));
+    let mut catalog_modified = CREATURE_CATALOG.get().and_then(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok());
+
+    eprintln!("Watching {} folder(s); press Ctrl-C to stop.", folders.len());
+    for f in folders {
+        eprintln!("  - {}", f.display());
+    }
+    loop {
+        reload_creature_catalog(&parser, &mut catalog_modified);
+        if !amanuensis_core::pending_complete_files(parser.db(), &sources)?.is_empty() {
+            let result = parser.update_sources(&sources, index_lines, |_, _, _| {})?;
+            if let Some(notify) = &notify {
+                send_notifications(notify, parser.take_notifications());
+            }
+            if format.is_json() && (result.files_scanned > 0 || result.errors > 0) {
+                let pass = serde_json::json!({ "at": amanuensis_core::date::now(), "result": result });
+                println!("{}", serde_json::to_string(&pass)?);
+            } else if result.files_scanned > 0 || result.errors > 0 {
+                println!(
+                    "[{}] {} file(s), {} line(s), {} event(s){}",
+                    amanuensis_core::date::now(),
+                    result.files_scanned,
+                    result.lines_parsed,
+                    result.events_found,
+                    if result.errors > 0 { format!(", {} error(s)", result.errors) } else { String::new() },
+                );
+            }
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// `--interval` value: plain seconds, or a number suffixed with s, m, h or d.
+fn parse_interval(value: &str) -> Result<std::time::Duration, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().map_err(|_| format!("invalid interval '{value}' (try 15m)"))?;
+    let seconds = match unit.trim() {
+        "" | "s" => number,
+        "m" => number * 60,
+        "h" => number * 3600,
+        "d" => number * 86400,
+        other => return Err(format!("unknown interval unit '{other}' (use s, m, h or d)")),
+    };
+    if seconds == 0 {
+        return Err("interval must be greater than zero".to_string());
+    }
+    Ok(std::time::Duration::from_secs(seconds))
+}
+
+/// Each pass is an ordinary `update_sources` with a freshly opened database, so changes
+/// the GUI or other commands made in between (resets, merges, settings) are picked up, and
+/// offset-resume keeps passes from counting a line twice. Lines still being written are left
+/// for the next pass, as in `watch`. A failed pass is logged and the next one runs on
+/// schedule; only bad arguments stop the daemon.
+#[allow(clippy::too_many_arguments)]
+fn cmd_daemon(
+    db_path: &str,
+    folders: &[PathBuf],
+    recursive: bool,
+    interval: std::time::Duration,
+    no_index: bool,
+    nice: bool,
+    live: LiveOutputs,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    for f in folders {
+        if !f.is_dir() {
+            return Err(amanuensis_core::AmanuensisError::Data(
+                format!("'{}' is not a directory", f.display())
+            ));
+        }
+    }
+    let hooks = live.hooks.map(amanuensis_core::HookConfig::load).transpose()?;
+    let broadcaster = bind_ws(live.ws)?;
+    let notify = load_notify(live.notify)?;
+    let sources: Vec<(PathBuf, bool)> = folders.iter().map(|f| (f.clone(), recursive)).collect();
+
+    eprintln!(
+        "[{}] Updating {} every {}s from {} folder(s):",
+        amanuensis_core::date::now(),
+        db_path,
+        interval.as_secs(),
+        folders.len()
+    );
+    for f in folders {
+        eprintln!("  - {}", f.display());
+    }
+    loop {
+        let pass = (|| {
+            let parser = log_parser(Database::open(db_path)?)?;
+            parser.set_nice(nice);
+            parser.set_complete_lines_only(true);
+            parser.set_hooks(hooks.clone());
+            if let Some(broadcaster) = &broadcaster {
+                parser.subscribe_events(broadcaster.subscriber());
+            }
+            parser.set_notifier(notify.clone());
+            let result = parser.update_sources(&sources, !no_index, |_, _, _| {});
+            if let Some(notify) = &notify {
+                send_notifications(notify, parser.take_notifications());
+            }
+            result
+        })();
+        let at = amanuensis_core::date::now();
+        match pass {
+            Ok(result) if format.is_json() => {
+                println!("{}", serde_json::to_string(&serde_json::json!({ "at": at, "result": result }))?);
+            }
+            Ok(result) if result.files_scanned == 0 && result.errors == 0 => println!("[{}] up to date", at),
+            Ok(result) => println!(
+                "[{}] {} file(s), {} line(s), {} event(s){}",
+                at,
+                result.files_scanned,
+                result.lines_parsed,
+                result.events_found,
+                if result.errors > 0 { format!(", {} error(s)", result.errors) } else { String::new() },
+            ),
+            Err(e) if format.is_json() => {
+                println!("{}", serde_json::to_string(&serde_json::json!({ "at": at, "error": e.to_string() }))?);
+            }
+            Err(e) => eprintln!("[{}] pass failed: {}", at, e),
+        }
+        let _ = io::stdout().flush();
+        std::thread::sleep(interval);
     }
-    Ok(())
 }
 
-fn cmd_pending(db_path: &str, folders: &[PathBuf], recursive: bool, list: bool) -> amanuensis_core::Result<()> {
+fn cmd_pending(
+    db_path: &str,
+    folders: &[PathBuf],
+    recursive: bool,
+    list: bool,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let sources: Vec<(PathBuf, bool)> = folders.iter().map(|f| (f.clone(), recursive)).collect();
     let pending = amanuensis_core::pending_files(&db, &sources)?;
+    if format.is_json() {
+        return print_json(&pending);
+    }
     println!("{} file(s) pending an incremental Update.", pending.len());
     if list {
         for p in &pending {
@@ -588,30 +1918,193 @@ fn cmd_pending(db_path: &str, folders: &[PathBuf], recursive: bool, list: bool)
     Ok(())
 }
 
-fn cmd_scan_files(db_path: &str, files: &[PathBuf], force: bool, no_index: bool) -> amanuensis_core::Result<()> {
-    println!("Scanning {} file(s)...", files.len());
+/// Rebuild the demo database, then run `args` against it or suggest commands.
+fn cmd_demo(args: &[String], format: Option<OutputFormat>) -> amanuensis_core::Result<()> {
+    if args.first().is_some_and(|a| a == "demo") {
+        return Err(amanuensis_core::AmanuensisError::Data("demo can't run itself".to_string()));
+    }
+    let setup = demo::build_demo(&demo::default_demo_dir())?;
+    let db_path = setup.db_path.to_string_lossy().into_owned();
+    if !args.is_empty() {
+        let mut argv = vec!["amanuensis".to_string(), "--db".to_string(), db_path];
+        if let Some(format) = format {
+            argv.extend(["--format".to_string(), format.as_str().to_string()]);
+        }
+        argv.extend(args.iter().cloned());
+        return run(Cli::try_parse_from(argv).unwrap_or_else(|e| e.exit()));
+    }
+    if format.is_some_and(|f| f.is_json()) {
+        return print_json(&setup);
+    }
+
+    println!(
+        "Demo database built from {} sample logs ({} events) at {}",
+        setup.files_scanned,
+        setup.events_found,
+        setup.db_path.display()
+    );
+    println!("Characters: {}", setup.characters.join(", "));
+    println!();
+    println!("Try:");
+    for command in ["summary", "kills", "trainers", "lastys", "coins", "sessions"] {
+        println!("  amanuensis demo {} {}", command, demo::DEMO_CHARACTER);
+    }
+    println!("  amanuensis demo leaderboard");
+    println!();
+    println!("Each `demo` run starts from a fresh copy. To explore it with other tools, use");
+    println!("  amanuensis --db \"{}\" <command>", setup.db_path.display());
+    println!("The GUI offers the same dataset (\"Try the demo\") while no characters are loaded.");
+    Ok(())
+}
+
+fn cmd_config(config: &Config, path: Option<&Path>, format: OutputFormat) -> amanuensis_core::Result<()> {
+    if format.is_json() {
+        return print_json(&serde_json::json!({ "path": path, "config": config }));
+    }
+    match (path, config::default_path()) {
+        (Some(path), _) => println!("Config file: {}", path.display()),
+        (None, Some(default)) => println!("Config file: {} (not found; using built-in defaults)", default.display()),
+        (None, None) => println!("Config file: none (no home directory)"),
+    }
+    let unset = "(not set)".to_string();
+    println!("db:          {}", config.db.clone().unwrap_or(unset.clone()));
+    println!(
+        "log_folder:  {}",
+        config.log_folder.as_ref().map(|f| f.display().to_string()).unwrap_or(unset.clone())
+    );
+    println!("index:       {}", config.index.map(|i| i.to_string()).unwrap_or(unset.clone()));
+    println!("format:      {}", config.format.map(|f| f.as_str().to_string()).unwrap_or(unset.clone()));
+    println!(
+        "trainer_db:  {}",
+        config.trainer_db.as_ref().map(|f| f.display().to_string()).unwrap_or(unset.clone())
+    );
+    println!(
+        "creature_db: {}",
+        config.creature_db.as_ref().map(|f| f.display().to_string()).unwrap_or(unset)
+    );
+    Ok(())
+}
+
+fn cmd_compare_archives(folder_a: &Path, folder_b: &Path, format: OutputFormat) -> amanuensis_core::Result<()> {
+    for f in [folder_a, folder_b] {
+        if !f.is_dir() {
+            return Err(amanuensis_core::AmanuensisError::Data(
+                format!("'{}' is not a directory", f.display())
+            ));
+        }
+    }
+    let cmp = amanuensis_core::compare_archives(folder_a, folder_b)?;
+    if format.is_json() {
+        return print_json(&cmp);
+    }
+
+    println!("A: {} ({} log files)", folder_a.display(), cmp.files_a);
+    println!("B: {} ({} log files)", folder_b.display(), cmp.files_b);
+    println!();
+    println!("  Shared (identical content): {}", cmp.shared);
+    println!("  Only in A:                  {}", cmp.only_in_a.len());
+    println!("  Only in B:                  {}", cmp.only_in_b.len());
+    println!("  Diverged (same name):       {}", cmp.diverged.len());
+
+    if !cmp.only_in_a.is_empty() {
+        println!();
+        println!("Only in A:");
+        for p in &cmp.only_in_a {
+            println!("  {}", p.display());
+        }
+    }
+    if !cmp.only_in_b.is_empty() {
+        println!();
+        println!("Only in B:");
+        for p in &cmp.only_in_b {
+            println!("  {}", p.display());
+        }
+    }
+    if !cmp.diverged.is_empty() {
+        println!();
+        println!("Diverged (same relative path, different content — keep the longer copy):");
+        for (pa, pb) in &cmp.diverged {
+            println!("  A: {}", pa.display());
+            println!("  B: {}", pb.display());
+        }
+    }
+    Ok(())
+}
+
+fn cmd_scan_files(
+    db_path: &str,
+    files: &[PathBuf],
+    force: bool,
+    no_index: bool,
+    nice: bool,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    if !format.is_json() {
+        println!("Scanning {} file(s)...", files.len());
+    }
 
     let db = Database::open(db_path)?;
-    let parser = LogParser::new(db)?;
+    let parser = log_parser(db)?;
+    parser.set_nice(nice);
     let index_lines = !no_index;
 
-    let progress = |current: usize, total: usize, filename: &str| {
-        eprint!("\r[{}/{}] {}", current + 1, total, filename);
-        let _ = io::stderr().flush();
-    };
+    let progress = ScanProgress::attach(&parser);
 
-    let result = parser.scan_files_with_progress(files, force, index_lines, progress)?;
-    eprintln!();
+    let result = parser.scan_files_with_progress(files, force, index_lines, |c, t, f| progress.file(c, t, f))?;
+    progress.finish();
 
     parser.finalize_characters()?;
-    print_scan_result(&result);
+    print_scan_result(&result, format)
+}
 
+fn cmd_scan_errors(db_path: &str, retry: bool, no_index: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let errors = Database::open(db_path)?.get_scan_errors()?;
+    if retry {
+        let (present, missing): (Vec<PathBuf>, Vec<PathBuf>) =
+            errors.iter().map(|e| PathBuf::from(&e.file_path)).partition(|p| p.exists());
+        for path in &missing {
+            eprintln!("Skipping {} (no longer exists)", path.display());
+        }
+        if present.is_empty() {
+            return print_message(format, "No failed files to retry.");
+        }
+        return cmd_scan_files(db_path, &present, false, no_index, false, format);
+    }
+
+    if format.is_json() {
+        return print_json(&errors);
+    }
+    if errors.is_empty() {
+        println!("No scan errors: every file read so far scanned cleanly.");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["File", "Line", "Kind", "Error", "When"]);
+    for e in &errors {
+        table.add_row(vec![
+            e.file_path.clone(),
+            e.line.map(|l| l.to_string()).unwrap_or_default(),
+            e.kind.clone(),
+            e.message.clone(),
+            e.created_at.to_string(),
+        ]);
+    }
+    println!("{table}");
+    println!("{} file(s) failed. `amanuensis scan-errors --retry` scans them again.", errors.len());
     Ok(())
 }
 
-fn cmd_characters(db_path: &str) -> amanuensis_core::Result<()> {
+fn cmd_characters(db_path: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let chars = db.list_characters()?;
+    if format.is_json() {
+        return print_json(&chars);
+    }
 
     if chars.is_empty() {
         println!("No characters found. Run 'amanuensis scan <folder>' first.");
@@ -639,42 +2132,27 @@ fn cmd_characters(db_path: &str) -> amanuensis_core::Result<()> {
     Ok(())
 }
 
-fn cmd_summary(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
+fn cmd_summary(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let base_char = resolve_character(&db, name)?;
 
-    let char_id = base_char.id.unwrap();
-    let char = db.get_character_merged(char_id)?.unwrap_or(base_char);
-    let kills = db.get_kills_merged(char_id)?;
-    let trainers = db.get_trainers_merged(char_id)?;
-    let lastys = db.get_lastys_merged(char_id)?;
-    let pets = db.get_pets_merged(char_id)?;
-
-    let total_solo: i64 = kills.iter().map(|k| k.total_solo()).sum();
-    let total_assisted: i64 = kills.iter().map(|k| k.total_assisted()).sum();
-    let total_killed_by: i64 = kills.iter().map(|k| k.killed_by_count).sum();
-    let total_ranks: i64 = trainers.iter().map(|t| t.ranks).sum();
-
-    // Effective ranks via multipliers (respects rank_mode and apply_learning)
-    let effective_ranks: f64 = trainers.iter().map(|t| {
-        t.effective_ranks() as f64 * t.effective_multiplier
-    }).sum();
-    let effective_ranks = (effective_ranks * 10.0).round() / 10.0;
-
-    // Find highest value kill (nemesis)
-    let nemesis = kills
-        .iter()
-        .filter(|k| k.total_all() > 0)
-        .max_by_key(|k| k.total_all());
-
-    let merge_sources = db.get_merge_sources(char_id)?;
+    let summary = db.character_summary(base_char.id.unwrap())?.ok_or_else(|| {
+        amanuensis_core::AmanuensisError::CharacterNotFound { name: name.to_string() }
+    })?;
+    if format.is_json() {
+        return print_json(&summary);
+    }
+    let char = &summary.character;
+    let clan = &summary.clan;
 
     println!("=== {} ===", char.name);
-    if !merge_sources.is_empty() {
-        let names: Vec<&str> = merge_sources.iter().map(|s| s.name.as_str()).collect();
-        println!("Merged from:    {}", names.join(", "));
+    if !summary.merged_from.is_empty() {
+        println!("Merged from:    {}", summary.merged_from.join(", "));
     }
     println!("Profession:     {}", char.profession);
+    if let Some(ref c) = summary.current_circle {
+        println!("Current Circle: {} ({}, passed {})", c.circle, c.profession, c.date);
+    }
     if let Some(ref start) = char.start_date {
         println!("Start Date:     {}", start);
     }
@@ -693,43 +2171,61 @@ fn cmd_summary(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
     if char.esteem > 0 {
         println!("Esteem:         {}", char.esteem);
     }
+    if let Some(ref name) = clan.current_clan {
+        match clan.member_since {
+            Some(ref since) => println!("Clan:           {} (since {})", name, since),
+            None => println!("Clan:           {}", name),
+        }
+    }
+    if !clan.titles.is_empty() {
+        println!("Titles:         {}", clan.titles.join(", "));
+    }
     println!();
+    if !clan.history.is_empty() {
+        println!("--- Clan History ---");
+        for e in &clan.history {
+            println!("{}  {:<6} {}", e.date, e.event_type, e.value);
+        }
+        println!();
+    }
+    if summary.circles.len() > 1 {
+        println!("--- Circle Tests ---");
+        for c in &summary.circles {
+            println!("{}  {} circle {}", c.date, c.profession, c.circle);
+        }
+        println!();
+    }
     println!("--- Kills ---");
-    println!("Solo kills:     {}", total_solo);
-    println!("Assisted kills: {}", total_assisted);
-    println!("Killed by:      {}", total_killed_by);
-    println!("Unique creatures: {}", kills.len());
-    if let Some(n) = nemesis {
-        println!(
-            "Most killed:    {} ({}x)",
-            n.creature_name,
-            n.total_all()
-        );
+    println!("Solo kills:     {}", summary.solo_kills);
+    println!("Assisted kills: {}", summary.assisted_kills);
+    println!("Killed by:      {}", summary.killed_by);
+    println!("Unique creatures: {}", summary.unique_creatures);
+    if let Some(ref n) = summary.most_killed {
+        println!("Most killed:    {} ({}x)", n.creature, n.count);
     }
     println!();
     println!("--- Ranks ---");
-    println!("Total ranks:    {}", total_ranks);
-    println!("Effective ranks: {}", effective_ranks);
-    println!("Trainers visited: {}", trainers.len());
+    println!("Total ranks:    {}", summary.total_ranks);
+    println!("Effective ranks: {}", summary.effective_ranks);
+    println!("Trainers visited: {}", summary.trainers_visited);
     if char.untraining_count > 0 {
         println!("Untrained:      {}x", char.untraining_count);
     }
     println!();
 
     // Survival stats
-    let total_exits = char.deaths + char.departs;
-    if total_exits > 0 {
-        let depart_rate = char.departs as f64 / total_exits as f64 * 100.0;
+    if let Some(depart_rate) = summary.depart_rate {
         println!("--- Survival ---");
         println!("Depart Rate:    {:.1}%", depart_rate);
-        let total_chains = char.chains_used + char.chains_broken;
-        if total_chains > 0 {
-            let chain_break_rate = char.chains_broken as f64 / total_chains as f64 * 100.0;
+        if let Some(chain_break_rate) = summary.chain_break_rate {
             println!("Chain Break Rate: {:.1}%", chain_break_rate);
         }
         if char.eps_broken > 0 {
             println!("EPS Broken:     {}", char.eps_broken);
         }
+        if let Some(ref r) = summary.top_rescuer {
+            println!("Top rescuer:    {} ({}x)", r.name, r.count);
+        }
         println!();
     }
 
@@ -738,20 +2234,21 @@ fn cmd_summary(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
     println!("Fur shares:     {}", char.fur_coins);
     println!("Blood shares:   {}", char.blood_coins);
     println!("Mandible shares: {}", char.mandible_coins);
-    if !lastys.is_empty() || !pets.is_empty() {
+    if summary.lastys > 0 || summary.pets > 0 {
         println!();
         println!("--- Lastys & Pets ---");
-        if !lastys.is_empty() {
-            let finished = lastys.iter().filter(|l| l.finished).count();
-            let active = lastys.len() - finished;
-            println!("Lastys:         {} total ({} active, {} completed)", lastys.len(), active, finished);
+        if summary.lastys > 0 {
+            println!(
+                "Lastys:         {} total ({} active, {} completed)",
+                summary.lastys, summary.lastys_active, summary.lastys_completed
+            );
         }
-        if !pets.is_empty() {
-            println!("Pets:           {}", pets.len());
+        if summary.pets > 0 {
+            println!("Pets:           {}", summary.pets);
         }
     }
     if char.bells_broken > 0 || char.chains_broken > 0 || char.shieldstones_used > 0
-        || char.purgatory_pendant > 0
+        || char.purgatory_pendant > 0 || !summary.stones.is_empty()
     {
         println!();
         println!("--- Equipment ---");
@@ -773,6 +2270,9 @@ fn cmd_summary(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
         if char.purgatory_pendant > 0 {
             println!("Purgatory pendant: {}", char.purgatory_pendant);
         }
+        for s in &summary.stones {
+            println!("{}", stone_usage_line(s));
+        }
     }
     if char.ore_found > 0 || char.wood_taken > 0 {
         println!();
@@ -795,6 +2295,7 @@ fn cmd_summary(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_kills(
     db_path: &str,
     name: &str,
@@ -803,9 +2304,9 @@ fn cmd_kills(
     family: Option<String>,
     rarity: Option<String>,
     seasonal: bool,
-    format: &str,
+    by_location: bool,
+    format: OutputFormat,
 ) -> amanuensis_core::Result<()> {
-    use amanuensis_core::data::CreatureDb;
     use amanuensis_core::db::queries::{filter_kills, KillsFilter};
 
     let db = Database::open(db_path)?;
@@ -815,7 +2316,7 @@ fn cmd_kills(
     let mut kills = db.get_kills_merged(char_id)?;
 
     if family.is_some() || rarity.is_some() || seasonal {
-        let creature_db = CreatureDb::bundled()?;
+        let creature_db = creature_db()?;
         kills = filter_kills(
             &kills,
             &creature_db,
@@ -827,6 +2328,10 @@ fn cmd_kills(
         );
     }
 
+    if by_location {
+        return print_kills_by_location(&db, char_id, name, &kills, sort, limit, format);
+    }
+
     // Sort
     match sort {
         "solo" => kills.sort_by_key(|k| std::cmp::Reverse(k.total_solo())),
@@ -840,12 +2345,15 @@ fn cmd_kills(
         kills.truncate(limit);
     }
 
+    if format.is_json() {
+        return print_json(&kills);
+    }
     if kills.is_empty() {
         println!("No kills found for {}.", name);
         return Ok(());
     }
 
-    if format == "csv" {
+    if format == OutputFormat::Csv {
         use amanuensis_core::export::{format_kills_export, ExportFormat};
         let freq = db.kill_frequency_merged_with(char_id, true)?;
         print!("{}", format_kills_export(&kills, &freq, ExportFormat::Csv));
@@ -869,8 +2377,8 @@ fn cmd_kills(
             k.total_all().to_string(),
             k.killed_by_count.to_string(),
             k.creature_value.to_string(),
-            k.date_first.clone().unwrap_or_default(),
-            k.date_last.clone().unwrap_or_default(),
+            k.date_first.map(|d| d.to_string()).unwrap_or_default(),
+            k.date_last.map(|d| d.to_string()).unwrap_or_default(),
         ]);
     }
 
@@ -879,13 +2387,73 @@ fn cmd_kills(
     Ok(())
 }
 
+/// `kills --by-location`: one row per (creature, area), for the creatures left in `kills`
+/// after the family/rarity/seasonal filters.
+fn print_kills_by_location(
+    db: &Database,
+    char_id: i64,
+    name: &str,
+    kills: &[amanuensis_core::models::Kill],
+    sort: &str,
+    limit: Option<usize>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let mut rows = db.get_kills_by_location_merged(char_id, None)?;
+    rows.retain(|r| kills.iter().any(|k| k.creature_name == r.creature_name));
+    match sort {
+        "solo" => rows.sort_by_key(|r| std::cmp::Reverse(r.solo)),
+        "assisted" => rows.sort_by_key(|r| std::cmp::Reverse(r.assisted)),
+        "name" => rows.sort_by(|a, b| a.creature_name.cmp(&b.creature_name).then_with(|| a.area.cmp(&b.area))),
+        _ => {} // Already most kills first.
+    }
+    if let Some(limit) = limit {
+        rows.truncate(limit);
+    }
+
+    if format.is_json() {
+        return print_json(&rows);
+    }
+    if rows.is_empty() {
+        println!("No kills with a known area for {}. (Rescan to backfill older logs.)", name);
+        return Ok(());
+    }
+    if format == OutputFormat::Csv {
+        let cell = |s: &str| if s.contains(',') || s.contains('"') { format!("\"{}\"", s.replace('"', "\"\"")) } else { s.to_string() };
+        println!("creature,area,solo,assisted,total,killed_by");
+        for r in &rows {
+            println!("{},{},{},{},{},{}", cell(&r.creature_name), cell(&r.area), r.solo, r.assisted, r.total(), r.killed_by);
+        }
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Creature", "Area", "Solo", "Assisted", "Total", "Killed By"]);
+    for r in &rows {
+        table.add_row(vec![
+            r.creature_name.clone(),
+            r.area.clone(),
+            r.solo.to_string(),
+            r.assisted.to_string(),
+            r.total().to_string(),
+            r.killed_by.to_string(),
+        ]);
+    }
+    println!("Kills by area for {}:", name);
+    println!("{table}");
+    Ok(())
+}
+
 fn cmd_frequency(
     db_path: &str,
     name: &str,
     bin: &str,
     solo: bool,
     by_verb: bool,
-    format: &str,
+    format: OutputFormat,
     limit: Option<usize>,
 ) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
@@ -896,7 +2464,7 @@ fn cmd_frequency(
     if let Some(limit) = limit {
         freq.truncate(limit);
     }
-    if freq.is_empty() {
+    if freq.is_empty() && !format.is_json() {
         println!("No kill-frequency data for {}. (Run `amanuensis rescan <folder...>`, or the GUI's Rescan Logs, to backfill.)", name);
         return Ok(());
     }
@@ -913,7 +2481,7 @@ fn cmd_frequency(
     };
 
     match format {
-        "csv" => {
+        OutputFormat::Csv => {
             let mut header = vec!["creature".to_string()];
             if show_day { header.push("best_day_count".into()); header.push("best_day_date".into()); }
             if show_2h { header.push("best_2h_count".into()); header.push("best_2h_start".into()); }
@@ -944,10 +2512,8 @@ fn cmd_frequency(
                 println!("{}", escaped.join(","));
             }
         }
-        "json" => {
-            println!("{}", serde_json::to_string_pretty(&freq)?);
-        }
-        _ => {
+        OutputFormat::Json => print_json(&freq)?,
+        OutputFormat::Table => {
             let mut header = vec!["Creature"];
             if show_day { header.push("Best Day"); header.push("Day Date"); }
             if show_2h { header.push("Best 2h"); header.push("2h Start"); }
@@ -976,19 +2542,24 @@ fn cmd_frequency(
     Ok(())
 }
 
-fn cmd_trainers(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
+fn cmd_trainers(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let char = resolve_character(&db, name)?;
 
     let char_id = char.id.unwrap();
     let trainers = db.get_trainers_merged(char_id)?;
 
+    if format.is_json() {
+        return print_json(&trainers);
+    }
     if trainers.is_empty() {
         println!("No trainer ranks found for {}.", name);
         return Ok(());
     }
 
     let has_overrides = trainers.iter().any(|t| t.rank_mode != RankMode::Modifier.as_str());
+    let maxed = trainers.iter().filter(|t| t.maxed).count();
+    let tdb = trainer_db()?;
 
     let mut table = Table::new();
     table
@@ -996,23 +2567,32 @@ fn cmd_trainers(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
         .apply_modifier(UTF8_ROUND_CORNERS)
         .set_content_arrangement(ContentArrangement::Dynamic);
 
+    let mut header = vec!["Trainer", "Counted", "Applied", "Applied (est.)", "Manual", "Effective"];
     if has_overrides {
-        table.set_header(vec!["Trainer", "Ranks", "Modified", "Apply", "Effective", "Mode", "Last Rank"]);
-    } else {
-        table.set_header(vec!["Trainer", "Ranks", "Modified", "Apply", "Effective", "Last Rank"]);
+        header.push("Mode");
+    }
+    if maxed > 0 {
+        header.push("Maxed");
     }
+    header.push("Last Rank");
+    table.set_header(header);
 
     let mut total_effective: f64 = 0.0;
 
     for t in &trainers {
+        let label = retired_label(
+            &t.trainer_name,
+            tdb.is_retired(&t.trainer_name),
+            tdb.get_replacement(&t.trainer_name),
+        );
         let eff = t.effective_ranks();
         let effective = eff as f64 * t.effective_multiplier;
         total_effective += effective;
 
-        let apply_str = if t.apply_learning_unknown_count > 0 {
-            format!("{}+{}?", t.apply_learning_ranks, t.apply_learning_unknown_count)
+        let estimated_str = if t.apply_learning_unknown_count > 0 {
+            format!("~{} ({}x)", t.apply_learning_estimated_ranks, t.apply_learning_unknown_count)
         } else {
-            t.apply_learning_ranks.to_string()
+            String::new()
         };
 
         let effective_str = if (t.effective_multiplier - 1.0).abs() < f64::EPSILON {
@@ -1021,49 +2601,57 @@ fn cmd_trainers(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
             format!("{:.1}", effective)
         };
 
+        let mut row = vec![
+            label,
+            t.ranks.to_string(),
+            t.apply_learning_ranks.to_string(),
+            estimated_str,
+            t.modified_ranks.to_string(),
+            effective_str,
+        ];
         if has_overrides {
-            let mode_str = if t.rank_mode == RankMode::Modifier.as_str() {
+            row.push(if t.rank_mode == RankMode::Modifier.as_str() {
                 String::new()
             } else if let Some(ref d) = t.override_date {
                 format!("{} ({})", t.rank_mode, d)
             } else {
                 t.rank_mode.clone()
-            };
-            table.add_row(vec![
-                t.trainer_name.clone(),
-                t.ranks.to_string(),
-                t.modified_ranks.to_string(),
-                apply_str,
-                effective_str,
-                mode_str,
-                t.date_of_last_rank.clone().unwrap_or_default(),
-            ]);
-        } else {
-            table.add_row(vec![
-                t.trainer_name.clone(),
-                t.ranks.to_string(),
-                t.modified_ranks.to_string(),
-                apply_str,
-                effective_str,
-                t.date_of_last_rank.clone().unwrap_or_default(),
-            ]);
+            });
+        }
+        if maxed > 0 {
+            row.push(if t.maxed { "maxed".to_string() } else { String::new() });
         }
+        row.push(t.date_of_last_rank.map(|d| d.to_string()).unwrap_or_default());
+        table.add_row(row);
     }
 
     total_effective = (total_effective * 10.0).round() / 10.0;
     let total_ranks: i64 = trainers.iter().map(|t| t.ranks).sum();
     println!("Trainers for {} ({} total ranks, {} effective):", name, total_ranks, total_effective);
+    println!(
+        "  counted {}, applied {}, applied estimate ~{}, manual {}",
+        total_ranks,
+        trainers.iter().map(|t| t.apply_learning_ranks).sum::<i64>(),
+        trainers.iter().map(|t| t.apply_learning_estimated_ranks).sum::<i64>(),
+        trainers.iter().map(|t| t.modified_ranks).sum::<i64>(),
+    );
+    if maxed > 0 {
+        println!("  {} trainer(s) have nothing more to teach", maxed);
+    }
     println!("{table}");
     Ok(())
 }
 
-fn cmd_lastys(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
+fn cmd_lastys(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let char = resolve_character(&db, name)?;
 
     let char_id = char.id.unwrap();
     let lastys = db.get_lastys_merged(char_id)?;
 
+    if format.is_json() {
+        return print_json(&lastys);
+    }
     if lastys.is_empty() {
         println!("No lastys found for {}.", name);
         return Ok(());
@@ -1092,8 +2680,8 @@ fn cmd_lastys(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
             l.lasty_type.clone(),
             l.message_count.to_string(),
             status,
-            l.first_seen_date.clone().unwrap_or_default(),
-            l.last_seen_date.clone().unwrap_or_default(),
+            l.first_seen_date.map(|d| d.to_string()).unwrap_or_default(),
+            l.last_seen_date.map(|d| d.to_string()).unwrap_or_default(),
         ]);
     }
 
@@ -1103,11 +2691,16 @@ fn cmd_lastys(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
     Ok(())
 }
 
-fn cmd_import(source: &Path, output: &str, force: bool) -> amanuensis_core::Result<()> {
-    println!("Importing from: {}", source.display());
-    println!("Output database: {}", output);
+fn cmd_import(source: &Path, output: &str, force: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    if !format.is_json() {
+        println!("Importing from: {}", source.display());
+        println!("Output database: {}", output);
+    }
 
     let result = import_scribius(source, output, force)?;
+    if format.is_json() {
+        return print_json(&result);
+    }
 
     println!();
     println!("Import complete:");
@@ -1129,22 +2722,25 @@ fn cmd_import(source: &Path, output: &str, force: bool) -> amanuensis_core::Resu
     Ok(())
 }
 
-fn cmd_merge(db_path: &str, target: &str, sources: &[String]) -> amanuensis_core::Result<()> {
+fn cmd_merge(db_path: &str, target: &str, sources: &[String], format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let target_char = db
         .get_character(target)?
-        .ok_or_else(|| amanuensis_core::AmanuensisError::Data(format!("Target character '{}' not found", target)))?;
+        .ok_or_else(|| amanuensis_core::AmanuensisError::CharacterNotFound { name: target.to_string() })?;
     let target_id = target_char.id.unwrap();
 
     let mut source_ids = Vec::new();
     for name in sources {
         let source_char = db
             .get_character(name)?
-            .ok_or_else(|| amanuensis_core::AmanuensisError::Data(format!("Source character '{}' not found", name)))?;
+            .ok_or_else(|| amanuensis_core::AmanuensisError::CharacterNotFound { name: name.to_string() })?;
         source_ids.push(source_char.id.unwrap());
     }
 
     db.merge_characters(&source_ids, target_id)?;
+    if format.is_json() {
+        return print_message(format, &format!("Merged {} into {}", sources.join(", "), target));
+    }
 
     println!("Merged {} into {}:", sources.join(", "), target);
     println!("  {} is now the primary character", target);
@@ -1155,28 +2751,65 @@ fn cmd_merge(db_path: &str, target: &str, sources: &[String]) -> amanuensis_core
     Ok(())
 }
 
-fn cmd_unmerge(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
+fn cmd_unmerge(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
 
     // The character might be hidden (merged), so use the variant that doesn't filter.
     let char = db
         .get_character_including_merged(name)?
-        .ok_or_else(|| amanuensis_core::AmanuensisError::Data(format!("Character '{}' not found", name)))?;
+        .ok_or_else(|| amanuensis_core::AmanuensisError::CharacterNotFound { name: name.to_string() })?;
 
     db.unmerge_character(char.id.unwrap())?;
 
-    println!("Unmerged '{}' — it is now a separate character again.", name);
+    print_message(format, &format!("Unmerged '{}' — it is now a separate character again.", name))
+}
+
+fn cmd_delete_character(db_path: &str, name: &str, yes: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+
+    // Merged characters can be deleted too, so look past the merge.
+    let char = db
+        .get_character_including_merged(name)?
+        .ok_or_else(|| amanuensis_core::AmanuensisError::CharacterNotFound { name: name.to_string() })?;
+
+    if !yes {
+        eprint!("This will permanently delete '{}' and all of its data. Continue? [y/N] ", char.name);
+        let _ = io::stderr().flush();
+        let input = read_stdin_line()?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            return print_message(format, "Aborted.");
+        }
+    }
+
+    let deletion = db.delete_character(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&deletion);
+    }
+    println!("Deleted '{}':", deletion.name);
+    println!("  Kill rows: {}", deletion.kills);
+    println!("  Trainers: {}", deletion.trainers);
+    println!("  Scanned log files: {}", deletion.log_files);
+    println!("  Other rows: {}", deletion.other_rows);
+    if !deletion.unmerged.is_empty() {
+        println!("Unmerged (now separate characters): {}", deletion.unmerged.join(", "));
+    }
+    if deletion.log_files > 0 {
+        println!("Its log files will be scanned again unless you move them out of the log folder.");
+    }
 
     Ok(())
 }
 
-fn cmd_pets(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
+fn cmd_pets(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let char = resolve_character(&db, name)?;
 
     let char_id = char.id.unwrap();
     let pets = db.get_pets_merged(char_id)?;
 
+    if format.is_json() {
+        return print_json(&pets);
+    }
     if pets.is_empty() {
         println!("No pets found for {}.", name);
         return Ok(());
@@ -1187,10 +2820,24 @@ fn cmd_pets(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
         .load_preset(UTF8_FULL)
         .apply_modifier(UTF8_ROUND_CORNERS)
         .set_content_arrangement(ContentArrangement::Dynamic)
-        .set_header(vec!["Pet Name", "Creature"]);
+        .set_header(vec![
+            "Pet Name", "Creature", "Status", "Adopted", "Ranks", "Last Trained", "Fed", "Last Fed", "Deaths", "Released",
+        ]);
 
+    let or_dash = |d: &Option<amanuensis_core::date::Timestamp>| d.map_or("-".to_string(), |d| d.to_string());
     for p in &pets {
-        table.add_row(vec![p.pet_name.clone(), p.creature_name.clone()]);
+        table.add_row(vec![
+            p.pet_name.clone(),
+            if p.creature_name.is_empty() { "-".to_string() } else { p.creature_name.clone() },
+            p.status.clone(),
+            or_dash(&p.adopted_date),
+            p.ranks.to_string(),
+            or_dash(&p.last_trained_date),
+            p.feed_count.to_string(),
+            or_dash(&p.last_fed_date),
+            p.death_count.to_string(),
+            or_dash(&p.released_date),
+        ]);
     }
 
     println!("Pets for {}:", name);
@@ -1198,88 +2845,111 @@ fn cmd_pets(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
     Ok(())
 }
 
-fn cmd_set_ranks(db_path: &str, name: &str, trainer: &str, ranks: i64) -> amanuensis_core::Result<()> {
+fn cmd_sessions(db_path: &str, name: &str, limit: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let char = resolve_character(&db, name)?;
+
     let char_id = char.id.unwrap();
+    let sessions = db.get_sessions_merged(char_id)?;
 
-    db.set_modified_ranks(char_id, trainer, ranks)?;
-    println!("Set modified ranks for {} with {}: {}", name, trainer, ranks);
+    if format.is_json() {
+        return print_json(&sessions[..sessions.len().min(limit)]);
+    }
+    if sessions.is_empty() {
+        println!("No sessions found for {}. (Run `amanuensis rescan <folder...>`, or the GUI's Rescan Logs, to backfill.)", name);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["ID", "Started", "Duration", "Kills", "Ranks", "Coins", "Deaths"]);
+
+    for s in sessions.iter().take(limit) {
+        table.add_row(vec![
+            s.id.unwrap_or_default().to_string(),
+            s.started_at.to_string(),
+            format!("{}h {:02}m", s.duration_secs / 3600, (s.duration_secs / 60) % 60),
+            s.kills.to_string(),
+            s.ranks.to_string(),
+            s.coins.to_string(),
+            s.deaths.to_string(),
+        ]);
+    }
 
+    println!("Sessions for {} ({} of {}):", name, sessions.len().min(limit), sessions.len());
+    println!("{table}");
     Ok(())
 }
 
-fn cmd_set_trainer_note(db_path: &str, name: &str, trainer: &str, note: Option<&str>) -> amanuensis_core::Result<()> {
+fn cmd_hunt_report(db_path: &str, name: &str, session_id: i64, out: Option<&Path>, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let char = resolve_character(&db, name)?;
-    let char_id = char.id.unwrap();
-
-    // An empty string clears the note, matching the GUI's behavior.
-    let note = note.filter(|n| !n.trim().is_empty());
-    db.set_trainer_note(char_id, trainer, note)?;
-    match note {
-        Some(n) => println!("Set note for {} ({}): {}", trainer, name, n),
-        None => println!("Cleared note for {} ({})", trainer, name),
+    let report = db.hunt_report(char.id.unwrap(), session_id)?;
+    let text = if format.is_json() {
+        serde_json::to_string_pretty(&report)? + "\n"
+    } else {
+        report.to_markdown()
+    };
+    match out {
+        Some(path) => {
+            std::fs::write(path, text)?;
+            println!("Wrote hunt report for session {} to {}", session_id, path.display());
+        }
+        None => print!("{text}"),
     }
     Ok(())
 }
 
-fn cmd_clear_rank_overrides(db_path: &str, yes: bool) -> amanuensis_core::Result<()> {
-    if !yes {
-        eprint!("This will clear ALL rank overrides/modifiers for every character. Continue? [y/N] ");
-        let _ = io::stderr().flush();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).map_err(|e| {
-            amanuensis_core::AmanuensisError::Data(format!("Failed to read input: {}", e))
-        })?;
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("Aborted.");
-            return Ok(());
-        }
-    }
+fn cmd_accuracy(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
-    db.clear_rank_overrides()?;
-    println!("All rank overrides cleared.");
-    Ok(())
-}
+    let char = resolve_character(&db, name)?;
 
-fn cmd_reset_logs(db_path: &str, yes: bool) -> amanuensis_core::Result<()> {
-    if !yes {
-        eprint!("This will clear derived log data (kills, trainers, coins, ...) but KEEP rank overrides and notes. Continue? [y/N] ");
-        let _ = io::stderr().flush();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).map_err(|e| {
-            amanuensis_core::AmanuensisError::Data(format!("Failed to read input: {}", e))
-        })?;
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("Aborted.");
-            return Ok(());
-        }
+    let accuracy = db.get_accuracy_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&accuracy);
     }
-    let db = Database::open(db_path)?;
-    db.reset_log_data()?;
-    println!("Derived log data reset (rank overrides and notes preserved). Re-scan your log folders to repopulate.");
+    if accuracy.is_empty() {
+        println!("No hits or misses recorded for {}.", name);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Creature", "Hits", "Misses", "Hit Rate"]);
+
+    for a in &accuracy {
+        table.add_row(vec![
+            a.creature_name.clone(),
+            a.hits.to_string(),
+            a.misses.to_string(),
+            format!("{:.1}%", a.hit_rate()),
+        ]);
+    }
+    let hits: i64 = accuracy.iter().map(|a| a.hits).sum();
+    let swings: i64 = accuracy.iter().map(|a| a.swings()).sum();
+
+    println!("Accuracy for {}:", name);
+    println!("{table}");
+    println!("Overall: {} of {} swings hit ({:.1}%)", hits, swings, hits as f64 / swings as f64 * 100.0);
     Ok(())
 }
 
-fn cmd_search(db_path: &str, query: &str, character: Option<&str>, limit: i64) -> amanuensis_core::Result<()> {
+fn cmd_damage(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
 
-    let char_id = if let Some(name) = character {
-        let char = resolve_character(&db, name)?;
-        Some(char.id.unwrap())
-    } else {
-        None
-    };
-
-    let results = db.search_log_lines(query, char_id, limit, true, 0, 0)?;
-
-    if results.is_empty() {
-        println!("No results found for '{}'.", query);
-        let line_count = db.log_line_count()?;
-        if line_count == 0 {
-            println!("Hint: The FTS5 index is empty. Scan logs without --no-index to populate it.");
-        }
+    let taken = db.get_damage_taken_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&taken);
+    }
+    if taken.is_empty() {
+        println!("No damage taken recorded for {}.", name);
         return Ok(());
     }
 
@@ -1288,970 +2958,3879 @@ fn cmd_search(db_path: &str, query: &str, character: Option<&str>, limit: i64) -
         .load_preset(UTF8_FULL)
         .apply_modifier(UTF8_ROUND_CORNERS)
         .set_content_arrangement(ContentArrangement::Dynamic)
-        .set_header(vec!["File", "Character", "Content"]);
-
-    for r in &results {
-        // Strip path to just filename for readability
-        let filename = Path::new(&r.file_path)
-            .file_name()
-            .map(|f| f.to_string_lossy().to_string())
-            .unwrap_or_else(|| r.file_path.clone());
-
-        // Strip <mark> tags from snippet for terminal display
-        let content = r.snippet.replace("<mark>", "").replace("</mark>", "");
+        .set_header(vec!["Creature", "Hits Taken", "Killed By"]);
 
-        table.add_row(vec![filename, r.character_name.clone(), content]);
+    for d in &taken {
+        table.add_row(vec![d.creature_name.clone(), d.hits.to_string(), d.killed_by.to_string()]);
     }
 
-    println!("Search results for '{}' ({} matches):", query, results.len());
+    println!("Damage taken by {}:", name);
     println!("{table}");
     Ok(())
 }
 
-fn cmd_reset(db_path: &str, yes: bool) -> amanuensis_core::Result<()> {
-    if !yes {
-        eprint!("This will delete all data in '{}'. Continue? [y/N] ", db_path);
-        let _ = io::stderr().flush();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).map_err(|e| {
-            amanuensis_core::AmanuensisError::Data(format!("Failed to read input: {}", e))
-        })?;
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("Aborted.");
-            return Ok(());
-        }
-    }
+fn cmd_companions(db_path: &str, name: &str, limit: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
 
-    let path = Path::new(db_path);
-    if path.exists() {
-        std::fs::remove_file(path).map_err(|e| {
-            amanuensis_core::AmanuensisError::Data(format!("Failed to delete '{}': {}", db_path, e))
-        })?;
+    let companions = db.get_companions_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&companions[..companions.len().min(limit)]);
+    }
+    if companions.is_empty() {
+        println!("No hunting companions recorded for {}.", name);
+        return Ok(());
     }
 
-    // Re-create empty database (schema is created on open)
-    let _db = Database::open(db_path)?;
-    println!("Database '{}' has been reset.", db_path);
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Player", "Days", "Events", "First Seen", "Last Seen"]);
 
+    for c in companions.iter().take(limit) {
+        table.add_row(vec![
+            c.name.clone(),
+            c.days.to_string(),
+            c.events.to_string(),
+            c.first_seen.clone(),
+            c.last_seen.clone(),
+        ]);
+    }
+
+    println!("Hunting companions of {} ({} of {}):", name, companions.len().min(limit), companions.len());
+    println!("{table}");
     Ok(())
 }
 
-fn cmd_trainer_catalog(profession_filter: Option<&str>) -> amanuensis_core::Result<()> {
-    let tdb = TrainerDb::bundled()?;
-    let mut trainers = tdb.all_trainer_metadata();
+fn cmd_heals(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
 
-    if let Some(prof) = profession_filter {
-        let prof_lower = prof.to_lowercase();
-        trainers.retain(|t| {
-            t.profession
-                .as_ref()
-                .map(|p: &String| p.to_lowercase() == prof_lower)
-                .unwrap_or(false)
-        });
+    let heals = db.get_heals_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&heals);
     }
-
-    if trainers.is_empty() {
-        println!("No trainers found.");
+    if heals.is_empty() {
+        println!("No heals recorded for {}.", name);
         return Ok(());
     }
 
-    let has_combos = trainers.iter().any(|t| t.is_combo);
-
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_content_arrangement(ContentArrangement::Dynamic);
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Player", "Given", "Received", "First", "Last"]);
 
-    if has_combos {
-        table.set_header(vec!["Name", "Profession", "Multiplier", "Combo", "Components"]);
-    } else {
-        table.set_header(vec!["Name", "Profession", "Multiplier"]);
+    for h in &heals {
+        table.add_row(vec![
+            h.name.clone(),
+            h.given.to_string(),
+            h.received.to_string(),
+            h.first_date.to_string(),
+            h.last_date.to_string(),
+        ]);
     }
+    let given: i64 = heals.iter().map(|h| h.given).sum();
+    let received: i64 = heals.iter().map(|h| h.received).sum();
 
-    for t in &trainers {
-        let prof = t.profession.as_deref().unwrap_or("-");
-        let mult = if (t.multiplier - 1.0).abs() < f64::EPSILON {
-            "1.0".to_string()
-        } else {
-            format!("{:.1}", t.multiplier)
-        };
+    println!("Heals for {}:", name);
+    println!("{table}");
+    println!("Total: {} given, {} received", given, received);
+    Ok(())
+}
 
-        if has_combos {
-            let combo = if t.is_combo { "Yes" } else { "" };
-            let components = t.combo_components.join(", ");
-            table.add_row(vec![
-                t.name.clone(),
-                prof.to_string(),
-                mult,
-                combo.to_string(),
-                components,
-            ]);
-        } else {
-            table.add_row(vec![t.name.clone(), prof.to_string(), mult]);
-        }
+fn cmd_rescues(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+
+    let rescues = db.get_rescues_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&rescues);
+    }
+    if rescues.is_empty() {
+        println!("No rescues recorded for {}.", name);
+        return Ok(());
     }
 
-    println!("Trainer Catalog ({} trainers):", trainers.len());
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Player", "Dragged You", "Raised You", "You Dragged", "You Raised", "First", "Last"]);
+
+    for r in &rescues {
+        table.add_row(vec![
+            r.name.clone(),
+            r.dragged_you.to_string(),
+            r.raised_you.to_string(),
+            r.you_dragged.to_string(),
+            r.you_raised.to_string(),
+            r.first_date.to_string(),
+            r.last_date.to_string(),
+        ]);
+    }
+    let received: i64 = rescues.iter().map(|r| r.received()).sum();
+    let given: i64 = rescues.iter().map(|r| r.given()).sum();
+
+    println!("Rescues for {}:", name);
     println!("{table}");
+    println!("Total: {} received, {} given", received, given);
     Ok(())
 }
 
-fn cmd_coins(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
+fn cmd_falls(db_path: &str, name: &str, by_cause: bool, limit: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
-    let base_char = resolve_character(&db, name)?;
-    let char_id = base_char.id.unwrap();
-    let char = db.get_character_merged(char_id)?.unwrap_or(base_char);
+    let char = resolve_character(&db, name)?;
 
-    println!("=== Coins for {} ===", char.name);
-    println!("Coin Level:      {}", char.coin_level);
-    println!("Coins Picked Up: {}", char.coins_picked_up);
-    println!("Fur Shares:      {}  (worth: {})", char.fur_coins, char.fur_worth);
-    println!("Blood Shares:    {}  (worth: {})", char.blood_coins, char.blood_worth);
-    println!("Mandible Shares: {}  (worth: {})", char.mandible_coins, char.mandible_worth);
-    if char.casino_won > 0 || char.casino_lost > 0 {
-        println!("Casino Won:      {}", char.casino_won);
-        println!("Casino Lost:     {}", char.casino_lost);
-    }
-    if char.chest_coins > 0 {
-        println!("Chest Coins:     {}", char.chest_coins);
+    let tallies = db.get_witnessed_falls_merged(char.id.unwrap(), by_cause)?;
+    if format.is_json() {
+        return print_json(&tallies[..tallies.len().min(limit)]);
     }
-    if char.bounty_coins > 0 {
-        println!("Bounty Coins:    {}", char.bounty_coins);
+    if tallies.is_empty() {
+        println!("No witnessed falls recorded for {} (scan with --witnessed-falls to track them).", name);
+        return Ok(());
     }
-    if char.darkstone > 0 {
-        println!("Darkstone:       {}", char.darkstone);
+
+    let header = if by_cause {
+        vec!["Cause", "Falls", "Players", "Fell Most", "First", "Last"]
+    } else {
+        vec!["Player", "Falls", "Causes", "Top Cause", "First", "Last"]
+    };
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(header);
+    for t in tallies.iter().take(limit) {
+        table.add_row(vec![
+            t.key.clone(),
+            t.falls.to_string(),
+            t.distinct.to_string(),
+            t.top.clone(),
+            t.first_date.to_string(),
+            t.last_date.to_string(),
+        ]);
     }
+    let total: i64 = tallies.iter().map(|t| t.falls).sum();
 
+    println!("Falls witnessed by {}:", name);
+    println!("{table}");
+    println!("Total: {} falls", total);
     Ok(())
 }
 
-fn cmd_logs(db_path: &str, level: Option<&str>, limit: usize) -> amanuensis_core::Result<()> {
+fn cmd_correspondents(db_path: &str, name: &str, limit: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
-    let all_logs = db.get_process_logs()?;
-
-    let logs: Vec<_> = all_logs
-        .iter()
-        .filter(|l| level.map(|lv| l.level == lv).unwrap_or(true))
-        .rev()
-        .take(limit)
-        .collect();
+    let char = resolve_character(&db, name)?;
 
-    if logs.is_empty() {
-        if all_logs.is_empty() {
-            println!("No process logs found. Run 'amanuensis scan' first.");
-        } else {
-            println!("No logs matching level filter '{}'.", level.unwrap_or(""));
-        }
+    let correspondents = db.get_correspondents_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&correspondents[..correspondents.len().min(limit)]);
+    }
+    if correspondents.is_empty() {
+        println!("No private thoughts recorded for {}.", name);
         return Ok(());
     }
 
-    let errors = logs.iter().filter(|l| l.level == "error").count();
-    let warns = logs.iter().filter(|l| l.level == "warn").count();
-    let infos = logs.iter().filter(|l| l.level == "info").count();
-    println!(
-        "Process logs ({} shown: {} error, {} warn, {} info):",
-        logs.len(), errors, warns, infos
-    );
-    println!();
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Player", "Sent", "Received", "Days", "First", "Last"]);
 
-    for log in &logs {
-        let prefix = match log.level.as_str() {
-            "error" => "[ERROR]",
-            "warn"  => "[WARN] ",
-            _       => "[INFO] ",
-        };
-        println!("{}  {}  {}", prefix, log.created_at, log.message);
+    for c in correspondents.iter().take(limit) {
+        table.add_row(vec![
+            c.name.clone(),
+            c.sent.to_string(),
+            c.received.to_string(),
+            c.days.to_string(),
+            c.first_seen.clone(),
+            c.last_seen.clone(),
+        ]);
     }
 
+    println!(
+        "Correspondents for {} ({} of {}):",
+        name,
+        correspondents.len().min(limit),
+        correspondents.len()
+    );
+    println!("{table}");
     Ok(())
 }
 
-fn cmd_checkpoints(
-    db_path: &str,
-    name: &str,
-    all: bool,
-    trainer_filter: Option<&str>,
-) -> amanuensis_core::Result<()> {
+fn cmd_zones(db_path: &str, name: &str, top: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let char = resolve_character(&db, name)?;
-    let char_id = char.id.unwrap();
-
-    let mut checkpoints = if all {
-        db.get_all_trainer_checkpoints(char_id)?
-    } else {
-        db.get_latest_trainer_checkpoints(char_id)?
-    };
 
-    if let Some(filter) = trainer_filter {
-        let filter_lc = filter.to_lowercase();
-        checkpoints.retain(|c| c.trainer_name.to_lowercase().contains(&filter_lc));
+    let zones = db.get_zones_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&zones[..zones.len().min(top)]);
     }
-
-    if checkpoints.is_empty() {
-        println!("No trainer checkpoints found for {}.", name);
-        println!("Hint: Checkpoints are recorded when a trainer greets you with a rank-status message.");
+    if zones.is_empty() {
+        println!("No area visits recorded for {}.", name);
         return Ok(());
     }
+    let total: i64 = zones.iter().map(|z| z.seconds).sum();
 
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .apply_modifier(UTF8_ROUND_CORNERS)
         .set_content_arrangement(ContentArrangement::Dynamic)
-        .set_header(vec!["Trainer", "Min Ranks", "Max Ranks", "Timestamp"]);
+        .set_header(vec!["Area", "Time", "Share", "Visits", "Sessions", "Last Seen"]);
 
-    for c in &checkpoints {
-        let max_str = c.rank_max.map(|v| v.to_string()).unwrap_or_else(|| "maxed".to_string());
+    for z in zones.iter().take(top) {
+        let share = if total > 0 { z.seconds as f64 * 100.0 / total as f64 } else { 0.0 };
         table.add_row(vec![
-            c.trainer_name.clone(),
-            c.rank_min.to_string(),
-            max_str,
-            c.timestamp.clone(),
+            z.area.clone(),
+            format!("{}h {:02}m", z.seconds / 3600, (z.seconds / 60) % 60),
+            format!("{:.1}%", share),
+            z.visits.to_string(),
+            z.sessions.to_string(),
+            z.last_seen.to_string(),
         ]);
     }
 
-    let label = if all { "all checkpoints" } else { "latest checkpoints" };
-    println!("Trainer checkpoints for {} ({}, {} entries):", name, label, checkpoints.len());
+    println!("Areas for {} ({} of {}):", name, zones.len().min(top), zones.len());
     println!("{table}");
-
+    println!("Areas are approximate: inferred from the bestiary locations of creatures fought, not from zone messages.");
     Ok(())
 }
 
-fn cmd_set_rank_mode(
-    db_path: &str,
-    name: &str,
-    trainer: &str,
-    mode: &str,
-    ranks: i64,
-    date: Option<&str>,
-) -> amanuensis_core::Result<()> {
-    // Validate mode early for a friendly error message
-    RankMode::parse(mode).ok_or_else(|| {
-        amanuensis_core::AmanuensisError::Data(
-            format!("Invalid mode '{}'. Must be: modifier, override, override_until_date", mode)
-        )
-    })?;
-
-    if mode == "override_until_date" && date.is_none() {
-        return Err(amanuensis_core::AmanuensisError::Data(
-            "override_until_date mode requires --date <M/D/YY>".to_string()
-        ));
-    }
-
+fn cmd_milestones(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let char = resolve_character(&db, name)?;
-    let char_id = char.id.unwrap();
 
-    db.set_rank_override(char_id, trainer, mode, ranks, date)?;
+    let milestones = db.get_milestones_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&milestones);
+    }
+    if milestones.is_empty() {
+        println!("No milestones recorded for {}.", name);
+        return Ok(());
+    }
 
-    match mode {
-        "modifier" => println!("Set {} / {} to modifier mode (+{} adjusted ranks)", name, trainer, ranks),
-        "override" => println!("Set {} / {} to override mode ({} manual ranks)", name, trainer, ranks),
-        "override_until_date" => println!(
-            "Set {} / {} to override_until_date mode ({} baseline ranks, cutoff: {})",
-            name, trainer, ranks, date.unwrap_or("")
-        ),
-        _ => unreachable!(),
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Date", "Creature", "Milestone"]);
+
+    for m in &milestones {
+        table.add_row(vec![m.achieved_at.to_string(), m.creature.clone(), m.description()]);
     }
-    println!("Run 'amanuensis scan --force' to rebuild log-derived rank counts.");
 
+    println!("Milestones for {} ({}):", name, milestones.len());
+    println!("{table}");
     Ok(())
 }
 
-fn cmd_set_profession(db_path: &str, name: &str, profession: &str) -> amanuensis_core::Result<()> {
+fn cmd_boosts(db_path: &str, name: &str, windows: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
     let char = resolve_character(&db, name)?;
     let char_id = char.id.unwrap();
 
-    let override_value = if profession.eq_ignore_ascii_case("auto") {
-        None
-    } else {
-        // Validate
-        let valid = ["fighter", "healer", "mystic", "ranger", "bloodmage", "champion"];
-        if !valid.contains(&profession.to_lowercase().as_str()) {
-            return Err(amanuensis_core::AmanuensisError::Data(format!(
-                "Invalid profession '{}'. Must be one of: {} — or 'auto' to clear",
-                profession,
-                valid.join(", ")
-            )));
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    if windows {
+        let windows = db.boost_windows(char_id)?;
+        if format.is_json() {
+            return print_json(&windows);
         }
-        // Capitalize first letter
-        let mut s = profession.to_lowercase();
-        if let Some(c) = s.get_mut(0..1) {
-            c.make_ascii_uppercase();
+        if windows.is_empty() {
+            println!("No temporary boosts recorded for {}.", name);
+            return Ok(());
         }
-        Some(s)
-    };
+        table.set_header(vec!["Stat", "Taken", "Worn off"]);
+        for w in &windows {
+            table.add_row(vec![
+                w.stat.clone(),
+                format!("{}:00", w.start_hour),
+                w.end_hour.as_ref().map_or("-".to_string(), |h| format!("{}:00", h)),
+            ]);
+        }
+        println!("Boost windows for {} ({}):", name, windows.len());
+        println!("{table}");
+        return Ok(());
+    }
 
-    db.set_profession_override(char_id, override_value.as_deref())?;
+    let boosts = db.stat_boosts(char_id)?;
+    if format.is_json() {
+        return print_json(&boosts);
+    }
+    if boosts.is_empty() {
+        println!("No stat boosts recorded for {}.", name);
+        return Ok(());
+    }
+    table.set_header(vec!["Stat", "Kind", "Count", "Worn off", "First", "Last"]);
+    for b in &boosts {
+        table.add_row(vec![
+            b.stat.clone(),
+            if b.permanent { "permanent" } else { "temporary" }.to_string(),
+            b.count.to_string(),
+            if b.permanent { "-".to_string() } else { b.faded.to_string() },
+            format!("{}:00", b.first_hour),
+            format!("{}:00", b.last_hour),
+        ]);
+    }
+    println!("Stat boosts for {}:", name);
+    println!("{table}");
+    Ok(())
+}
 
-    if let Some(ref prof) = override_value {
-        println!("Set profession for {} to {} (manual override).", name, prof);
-    } else {
-        println!("Cleared profession override for {} — auto-detection will apply.", name);
+fn cmd_snapshots(db_path: &str, name: &str, since: Option<&str>, format: OutputFormat) -> amanuensis_core::Result<()> {
+    use amanuensis_core::date;
+
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
+    let snapshots = db.get_snapshots(char_id)?;
+
+    if let Some(since) = since {
+        let day = date::normalize(since)
+            .map(|d| d[..10].to_string())
+            .ok_or_else(|| amanuensis_core::AmanuensisError::Data(format!(
+                "Invalid --since '{}': expected a date like 2024-03-01",
+                since
+            )))?;
+        let (Some(base), Some(latest)) = (db.get_snapshot_on_or_before(char_id, &day)?, snapshots.last()) else {
+            if format.is_json() {
+                return print_json(&serde_json::Value::Null);
+            }
+            println!("No snapshot of {} on or before {}.", name, day);
+            return Ok(());
+        };
+        let change = [
+            ("logins", latest.logins - base.logins),
+            ("deaths", latest.deaths - base.deaths),
+            ("departs", latest.departs - base.departs),
+            ("solo_kills", latest.solo_kills - base.solo_kills),
+            ("assisted_kills", latest.assisted_kills - base.assisted_kills),
+            ("total_ranks", latest.total_ranks - base.total_ranks),
+            ("coins_picked_up", latest.coins_picked_up - base.coins_picked_up),
+            ("esteem", latest.esteem - base.esteem),
+            ("coin_level", latest.coin_level - base.coin_level),
+            ("lastys_completed", latest.lastys_completed - base.lastys_completed),
+        ];
+        if format.is_json() {
+            let change: serde_json::Map<String, serde_json::Value> =
+                change.iter().map(|(k, v)| (k.to_string(), (*v).into())).collect();
+            return print_json(&serde_json::json!({ "from": base, "to": latest, "change": change }));
+        }
+        println!("{} from {} to {}:", name, base.day, latest.day);
+        for (field, delta) in change.iter().filter(|(_, d)| *d != 0) {
+            println!("  {:<18} {:+}", field.replace('_', " "), delta);
+        }
+        if change.iter().all(|(_, d)| *d == 0) {
+            println!("  No change.");
+        }
+        return Ok(());
     }
-    println!("Run 'amanuensis scan --force' to recompute profession from logs.");
 
+    if format.is_json() {
+        return print_json(&snapshots);
+    }
+    if snapshots.is_empty() {
+        println!("No snapshots of {} yet; one is taken after each scan.", name);
+        return Ok(());
+    }
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Day", "Logins", "Deaths", "Kills", "Ranks", "Coins", "Esteem", "Lastys"]);
+    let mut previous: Option<&amanuensis_core::db::queries::StatsSnapshot> = None;
+    for s in &snapshots {
+        let cell = |now: i64, before: Option<i64>| match before {
+            Some(b) if now != b => format!("{now} ({:+})", now - b),
+            _ => now.to_string(),
+        };
+        let kills = s.solo_kills + s.assisted_kills;
+        table.add_row(vec![
+            s.day.clone(),
+            cell(s.logins, previous.map(|p| p.logins)),
+            cell(s.deaths, previous.map(|p| p.deaths)),
+            cell(kills, previous.map(|p| p.solo_kills + p.assisted_kills)),
+            cell(s.total_ranks, previous.map(|p| p.total_ranks)),
+            cell(s.coins_picked_up, previous.map(|p| p.coins_picked_up)),
+            cell(s.esteem, previous.map(|p| p.esteem)),
+            cell(s.lastys_completed, previous.map(|p| p.lastys_completed)),
+        ]);
+        previous = Some(s);
+    }
+    println!("Snapshots of {} ({}):", name, snapshots.len());
+    println!("{table}");
     Ok(())
 }
 
-fn cmd_fighter_stats(db_path: &str, name: &str) -> amanuensis_core::Result<()> {
+fn cmd_arena(db_path: &str, name: &str, limit: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
     let db = Database::open(db_path)?;
-    let base_char = resolve_character(&db, name)?;
-    let char_id = base_char.id.unwrap();
-    let trainers = db.get_trainers_merged(char_id)?;
+    let char = resolve_character(&db, name)?;
 
-    // Build ranks map: trainer_name -> ranks + modified_ranks
-    let mut ranks: HashMap<String, i64> = HashMap::new();
-    for t in &trainers {
-        let total = t.ranks + t.modified_ranks;
-        if total > 0 {
-            ranks.insert(t.trainer_name.clone(), total);
-        }
+    let summary = db.get_arena_summary_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&summary);
+    }
+    if summary.history.is_empty() {
+        println!("No arena or tournament events recorded for {}.", name);
+        return Ok(());
     }
 
-    let multiplier_map = build_multiplier_map();
-    let stats = compute_fighter_stats(&ranks, &multiplier_map);
+    println!("Arena record for {}:", name);
+    println!("  Arena entries:     {}", summary.entries);
+    println!("  Bouts won:         {}", summary.wins);
+    println!("  Bouts lost:        {}", summary.losses);
+    if summary.wins + summary.losses > 0 {
+        println!("  Win rate:          {:.1}%", summary.win_rate() * 100.0);
+    }
+    println!("  Tournaments won:   {}", summary.tournaments_won);
 
-    println!("=== Fighter Stats for {} ===", name);
-    println!("(Human / Roguewood Club / No Items)");
-    println!();
-    println!("Trained Ranks:    {}", stats.trained_ranks);
-    println!("Effective Ranks:  {}", stats.effective_ranks);
-    println!("Slaughter Points: {}", stats.slaughter_points);
-    println!();
-    println!("--- Offense ---");
-    println!("Accuracy:         {}", stats.accuracy);
-    println!("Damage:           {} - {}", stats.damage_min, stats.damage_max);
-    println!("Offense:          {}", stats.offense);
-    println!("Balance/Swing:    {}", stats.balance_per_swing);
-    println!();
-    println!("--- Defense ---");
-    println!("Defense:          {}", stats.defense);
-    println!("Balance:          {}", stats.balance);
-    println!("Balance Regen:    {} ({:.1}/frame)", stats.balance_regen, stats.balance_per_frame);
-    println!("Health:           {}", stats.health);
-    println!("Health Regen:     {} ({:.1}/frame)", stats.health_regen, stats.health_per_frame);
-    println!("Spirit:           {}", stats.spirit);
-    println!("Spirit Regen:     {} ({:.1}/frame)", stats.spirit_regen, stats.spirit_per_frame);
+    if summary.opponents.is_empty() {
+        return Ok(());
+    }
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Opponent", "Won", "Lost", "Last Bout"]);
+    for o in summary.opponents.iter().take(limit) {
+        table.add_row(vec![o.name.clone(), o.wins.to_string(), o.losses.to_string(), o.last_date.to_string()]);
+    }
     println!();
-    println!("--- Other ---");
-    println!("Heal Receptivity: {}", stats.heal_receptivity);
-    println!("Shieldstone Drain: {}", stats.shieldstone_drain);
-
+    println!("Opponents ({} of {}):", summary.opponents.len().min(limit), summary.opponents.len());
+    println!("{table}");
     Ok(())
 }
 
-// ── useitem-help ─────────────────────────────────────────────────────────────
+fn cmd_deaths(db_path: &str, name: &str, limit: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
 
-/// Replace Mac Roman smart quotes with ASCII equivalents.
-fn normalize_quotes(s: &str) -> String {
-    s.replace(['\u{201C}', '\u{201D}'], "\"")
-     .replace(['\u{2018}', '\u{2019}'], "'")
-}
+    let report = db.get_death_report_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&report);
+    }
+    let total = &report.total;
+    if total.deaths == 0 {
+        println!("No deaths recorded for {}.", name);
+        return Ok(());
+    }
 
-fn find_log_files(dir: &Path, recursive: bool) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-    let Ok(entries) = std::fs::read_dir(dir) else { return files };
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() && recursive {
-            files.extend(find_log_files(&path, true));
-        } else if path.extension().and_then(|e| e.to_str()) == Some("txt") {
-            files.push(path);
+    println!("Deaths for {}:", name);
+    println!("  Deaths:            {}", total.deaths);
+    println!("  Departed:          {}", total.departed);
+    println!("  Rescued:           {}", total.rescued);
+    println!("  Unresolved:        {}", total.deaths - total.departed - total.rescued);
+    let outcomes = &report.outcomes;
+    let minutes = |secs: f64| format!("{}m {:02}s", secs as i64 / 60, secs as i64 % 60);
+    if let Some(rate) = outcomes.rescue_rate {
+        println!("  Rescue rate:       {:.1}%", rate);
+    }
+    if let Some(secs) = outcomes.avg_downtime_secs {
+        println!("  Avg downtime:      {}", minutes(secs));
+    }
+    if let Some(secs) = outcomes.avg_rescue_secs {
+        println!("  Avg wait (rescue): {}", minutes(secs));
+    }
+    println!("  Chains per fall:   {:.2}", outcomes.chains_per_fall);
+
+    let sections = [
+        ("Killer", "By killer", &report.by_creature),
+        ("Area", "By area", &report.by_area),
+        ("Time of Day", "By time of day", &report.by_time_of_day),
+    ];
+    for (header, title, groups) in sections {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![header, "Deaths", "Departed", "Rescued", "Last"]);
+        for g in groups.iter().filter(|g| g.deaths > 0).take(limit) {
+            let key = if g.key.is_empty() { "(unknown)".to_string() } else { g.key.clone() };
+            table.add_row(vec![
+                key,
+                g.deaths.to_string(),
+                g.departed.to_string(),
+                g.rescued.to_string(),
+                g.last_date.map(|d| d.to_string()).unwrap_or_default(),
+            ]);
         }
+        println!();
+        println!("{}:", title);
+        println!("{table}");
     }
-    files
+    Ok(())
 }
 
-/// Split a CL log line into `(timestamp_slice, message_slice)`.
-/// Returns `("", line)` if no timestamp prefix is found.
-/// Handles both 12-hour (`8:38:19p`) and 24-hour (`15:25:42`) formats.
-fn split_timestamp(line: &str) -> (&str, &str) {
-    let bytes = line.as_bytes();
-    let mut i = 0;
-    // date part: digits '/' digits '/' digits
-    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
-    if i == 0 || i >= bytes.len() || bytes[i] != b'/' { return ("", line); }
-    i += 1;
-    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
-    if i >= bytes.len() || bytes[i] != b'/' { return ("", line); }
-    i += 1;
-    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
-    if i >= bytes.len() || bytes[i] != b' ' { return ("", line); }
-    i += 1;
-    // time part: digits:digits:digits[ap]?
-    let time_start = i;
-    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
-    if i == time_start || i >= bytes.len() || bytes[i] != b':' { return ("", line); }
-    i += 1;
-    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
-    if i >= bytes.len() || bytes[i] != b':' { return ("", line); }
-    i += 1;
-    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
-    // optional a/p suffix
-    if i < bytes.len() && (bytes[i] == b'a' || bytes[i] == b'p') { i += 1; }
-    if i >= bytes.len() || bytes[i] != b' ' { return ("", line); }
-    (&line[..i], &line[i + 1..])
+fn cmd_assists(db_path: &str, name: &str, creature: Option<&str>, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
+
+    let credits = db.get_assists_merged(char_id, creature)?;
+    if format.is_json() {
+        return print_json(&credits);
+    }
+    let total_assisted: i64 = db
+        .get_kills_merged(char_id)?
+        .iter()
+        .filter(|k| creature.is_none_or(|c| k.creature_name.eq_ignore_ascii_case(c)))
+        .map(|k| k.total_assisted())
+        .sum();
+    if credits.is_empty() {
+        println!("No attributed assists for {} ({} assisted kills).", name, total_assisted);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Creature", "Finished By", "Assists", "Last"]);
+
+    for a in &credits {
+        table.add_row(vec![a.creature.clone(), a.player.clone(), a.count.to_string(), a.last_date.to_string()]);
+    }
+
+    let attributed: i64 = credits.iter().map(|a| a.count).sum();
+    println!("Assists for {}:", name);
+    println!("{table}");
+    println!(
+        "{} of {} assisted kills attributed (killer's line within {}s of yours).",
+        attributed,
+        total_assisted,
+        amanuensis_core::parser::ASSIST_WINDOW_SECS
+    );
+    Ok(())
 }
 
+fn cmd_set_ranks(db_path: &str, name: &str, trainer: &str, ranks: i64, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
 
-/// Convert a CL timestamp string ("M/D/YY H:MM:SSa") to seconds since midnight.
-/// Used to determine whether an equip event and a help block are close in time,
-/// so we can avoid attributing a delayed help response to the wrong last_equipped item.
-fn ts_to_seconds(ts: &str) -> Option<u32> {
-    // Find the space separating date from time
-    let space = ts.rfind(' ')?;
-    let time_part = &ts[space + 1..];
-    let (time_str, pm, has_ampm) = if let Some(t) = time_part.strip_suffix('p') {
-        (t, true, true)
-    } else if let Some(t) = time_part.strip_suffix('a') {
-        (t, false, true)
-    } else {
-        (time_part, false, false) // 24-hour or no indicator
-    };
-    let mut parts = time_str.splitn(3, ':');
-    let h: u32 = parts.next()?.parse().ok()?;
-    let m: u32 = parts.next()?.parse().ok()?;
-    let s: u32 = parts.next()?.parse().ok()?;
-    let h24 = if has_ampm {
-        if h == 12 && !pm { 0 } else if h != 12 && pm { h + 12 } else { h }
+    db.set_modified_ranks(char_id, trainer, ranks)?;
+    print_message(format, &format!("Set modified ranks for {} with {}: {}", name, trainer, ranks))
+}
+
+/// Read `path`, or stdin when it is "-".
+fn read_file_or_stdin(path: &Path) -> amanuensis_core::Result<String> {
+    if path.as_os_str() == "-" {
+        io::read_to_string(io::stdin()).map_err(|e| amanuensis_core::AmanuensisError::file_unreadable("<stdin>", e))
     } else {
-        h
-    };
-    Some(h24 * 3600 + m * 60 + s)
+        std::fs::read_to_string(path).map_err(|e| amanuensis_core::AmanuensisError::file_unreadable(path, e))
+    }
 }
 
-fn extract_item_name(message: &str) -> Option<String> {
-    // 1. /useitem "name" — quoted, highest confidence
-    if let Some(rest) = message.strip_prefix("/useitem \"") {
-        if let Some(end) = rest.find('"') {
-            return Some(rest[..end].to_string());
-        }
+/// One line typed at a confirmation prompt.
+fn read_stdin_line() -> amanuensis_core::Result<String> {
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| amanuensis_core::AmanuensisError::file_unreadable("<stdin>", e))?;
+    Ok(input)
+}
+
+/// Trainer names accepted in rank files: the bundled catalog plus the character's own
+/// trainers (which may predate a catalog rename).
+fn known_trainer_names(own: &[amanuensis_core::models::Trainer]) -> amanuensis_core::Result<Vec<String>> {
+    let mut known: Vec<String> = trainer_db()?.all_trainer_metadata().into_iter().map(|m| m.name).collect();
+    known.extend(own.iter().map(|t| t.trainer_name.clone()));
+    Ok(known)
+}
+
+fn cmd_set_ranks_bulk(db_path: &str, name: &str, file: &Path, dry_run: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let text = read_file_or_stdin(file)?;
+    let db = Database::open(db_path)?;
+    let char_id = resolve_character(&db, name)?.id.unwrap();
+    let own = db.get_trainers(char_id)?;
+    let rows = reconcile::parse_ranks_csv(&text, &known_trainer_names(&own)?)?;
+    if rows.is_empty() {
+        return Err(amanuensis_core::AmanuensisError::Data(format!("No trainer,modified_ranks rows in {}", file.display())));
     }
-    // 2. /useitem word — unquoted (e.g., /useitem sungem /add <name>...)
-    if let Some(rest) = message.strip_prefix("/useitem ") {
-        if !rest.starts_with('"') {
-            let word: String = rest.chars().take_while(|c| !c.is_whitespace()).collect();
-            if !word.is_empty() {
-                return Some(word);
-            }
-        }
+    if !dry_run {
+        let ranks: Vec<(String, i64)> = rows.iter().map(|r| (r.trainer.clone(), r.ranks)).collect();
+        db.set_modified_ranks_bulk(char_id, &ranks)?;
     }
-    // 3. * The <name> allows
-    if let Some(rest) = message.strip_prefix("* The ") {
-        if let Some(pos) = rest.find(" allows ") {
-            return Some(rest[..pos].to_string());
-        }
+    if format.is_json() {
+        return print_json(&rows);
     }
-    // 4. The <name> allows
-    if let Some(rest) = message.strip_prefix("The ") {
-        if let Some(pos) = rest.find(" allows ") {
-            return Some(rest[..pos].to_string());
-        }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Trainer", "Manual Before", "Manual After"]);
+    for row in &rows {
+        let before = own.iter().find(|t| t.trainer_name == row.trainer).map(|t| t.modified_ranks).unwrap_or(0);
+        table.add_row(vec![row.trainer.clone(), before.to_string(), row.ranks.to_string()]);
     }
-    // 5. Your <name> allows you to (e.g., "Your sunstone allows you to think...")
-    if let Some(rest) = message.strip_prefix("Your ") {
-        if let Some(pos) = rest.find(" allows you to") {
-            return Some(rest[..pos].to_string());
-        }
+    println!("{table}");
+    if dry_run {
+        println!("Dry run: {} trainer(s) would be set for {}; nothing written.", rows.len(), name);
+    } else {
+        println!("Set modified ranks for {} trainer(s) for {}.", rows.len(), name);
     }
-    // 6. This <name> helps/allows — skip generic single words
-    if let Some(rest) = message.strip_prefix("This ") {
-        let keyword_pos = rest.find(" helps").or_else(|| rest.find(" allows"));
-        if let Some(pos) = keyword_pos {
-            let candidate = &rest[..pos];
-            if candidate.contains(' ') {
-                return Some(candidate.to_string());
+    Ok(())
+}
+
+fn cmd_reconcile(db_path: &str, name: &str, paste: &Path, apply: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let text = read_file_or_stdin(paste)?;
+    let db = Database::open(db_path)?;
+    let char_id = resolve_character(&db, name)?.id.unwrap();
+    let merged = db.get_trainers_merged(char_id)?;
+    let own = db.get_trainers(char_id)?;
+    let listing = reconcile::parse_rank_listing(&text, &known_trainer_names(&own)?);
+    if listing.entries.is_empty() {
+        return Err(amanuensis_core::AmanuensisError::Data(format!(
+            "No trainer ranks found in {} (expected lines like \"Histia: 150\")",
+            paste.display()
+        )));
+    }
+    let result = reconcile::reconcile(&merged, &own, &listing);
+    if apply {
+        for row in result.proposals() {
+            let override_date = own.iter().find(|t| t.trainer_name == row.trainer).and_then(|t| t.override_date.as_deref());
+            db.set_rank_override(char_id, &row.trainer, &row.rank_mode, row.proposed_modified_ranks.unwrap_or(0), override_date)?;
+        }
+    }
+    if format.is_json() {
+        return print_json(&result);
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Trainer", "Listed", "Database", "Difference", "Manual", "Proposed Manual"]);
+    for row in &result.rows {
+        table.add_row(vec![
+            row.trainer.clone(),
+            row.listed.to_string(),
+            row.database.to_string(),
+            if row.difference == 0 { "=".to_string() } else { format!("{:+}", row.difference) },
+            row.modified_ranks.to_string(),
+            row.proposed_modified_ranks.map(|n| n.to_string()).unwrap_or_default(),
+        ]);
+    }
+    let source = if paste.as_os_str() == "-" { "stdin".into() } else { paste.display().to_string() };
+    println!("=== Reconcile {} against {} ===", name, source);
+    println!("{table}");
+
+    let proposals: Vec<_> = result.proposals().collect();
+    if proposals.is_empty() {
+        println!("The database has at least the listed ranks for every trainer; nothing to adjust.");
+    } else if apply {
+        println!("Set manual ranks for {} trainer(s).", proposals.len());
+    } else {
+        println!("Run again with --apply to set these, or one at a time:");
+        for row in &proposals {
+            let n = row.proposed_modified_ranks.unwrap_or(0);
+            if row.rank_mode == RankMode::Modifier.as_str() {
+                println!("  amanuensis set-ranks {} \"{}\" {}", name, row.trainer, n);
+            } else {
+                println!("  ({}: {} mode, set manual ranks to {} in the GUI)", row.trainer, row.rank_mode, n);
             }
         }
     }
-    None
+    let over = result.rows.iter().filter(|r| r.difference < 0).count();
+    if over > 0 {
+        println!("{} trainer(s) have more ranks in the database than listed; check for a stale listing or misattributed messages.", over);
+    }
+    if !result.not_listed.is_empty() {
+        println!("Not in the listing: {}", result.not_listed.join(", "));
+    }
+    for line in &result.unparsed {
+        eprintln!("Skipped line: {}", line);
+    }
+    Ok(())
 }
 
-/// True if `s` looks like `Type <quote>/...` where quote may be ASCII " or Mac Roman curly quotes.
-fn starts_with_type_slash(s: &str) -> bool {
-    // "Type " followed by a quote char then "/"
-    if let Some(rest) = s.strip_prefix("Type ") {
-        // skip optional opening quote: ASCII " or ' or Mac Roman smart quotes U+201C/U+2018
-        // Strip optional opening quote: ASCII ", ASCII ', or Mac Roman smart quotes U+201C/U+2018
-        let inner = rest.trim_start_matches(['"', '\'', '\u{201C}', '\u{2018}']);
-        return inner.starts_with('/');
-    }
-    false
+fn cmd_set_trainer_note(db_path: &str, name: &str, trainer: &str, note: Option<&str>, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
+
+    // An empty string clears the note, matching the GUI's behavior.
+    let note = note.filter(|n| !n.trim().is_empty());
+    db.set_trainer_note(char_id, trainer, note)?;
+    let message = match note {
+        Some(n) => format!("Set note for {} ({}): {}", trainer, name, n),
+        None => format!("Cleared note for {} ({})", trainer, name),
+    };
+    print_message(format, &message)
 }
 
-/// True if `message` starts with an unquoted `/useitem <word>` command.
-fn starts_with_useitem_unquoted(message: &str) -> bool {
-    if let Some(rest) = message.strip_prefix("/useitem ") {
-        !rest.starts_with('"') && rest.chars().next().is_some_and(|c| c.is_alphanumeric())
-    } else {
-        false
+fn cmd_clear_rank_overrides(db_path: &str, yes: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    if !yes {
+        eprint!("This will clear ALL rank overrides/modifiers for every character. Continue? [y/N] ");
+        let _ = io::stderr().flush();
+        let input = read_stdin_line()?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            return print_message(format, "Aborted.");
+        }
     }
+    let db = Database::open(db_path)?;
+    db.clear_rank_overrides()?;
+    print_message(format, "All rank overrides cleared.")
 }
 
-fn is_help_trigger(message: &str) -> bool {
-    if starts_with_type_slash(message) { return true; }
-    if message.starts_with("/useitem \"") { return true; }
-    if starts_with_useitem_unquoted(message) { return true; }
-    if message.starts_with("* /") { return true; }
-    if message.starts_with("* The ") && message.contains(" allows ") { return true; }
-    if message.starts_with("The ") && message.contains(" allows ") { return true; }
-    if message.starts_with("Your ") && message.contains(" allows you to") { return true; }
-    if message.starts_with("This ") && (message.contains(" helps") || message.contains(" allows")) { return true; }
-    false
+fn cmd_reset_logs(db_path: &str, yes: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    if !yes {
+        eprint!("This will clear derived log data (kills, trainers, coins, ...) but KEEP rank overrides and notes. Continue? [y/N] ");
+        let _ = io::stderr().flush();
+        let input = read_stdin_line()?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            return print_message(format, "Aborted.");
+        }
+    }
+    let db = Database::open(db_path)?;
+    db.reset_log_data()?;
+    print_message(
+        format,
+        "Derived log data reset (rank overrides and notes preserved). Re-scan your log folders to repopulate.",
+    )
 }
 
-fn is_help_continuation(message: &str) -> bool {
-    if starts_with_type_slash(message) { return true; }
-    if message.starts_with("/useitem \"") { return true; }
-    if starts_with_useitem_unquoted(message) { return true; }
-    if message.starts_with("* /") { return true; }
-    if message.starts_with("* Hot tip:") { return true; }
-    if message.starts_with("* You can currently hold") { return true; }
-    if message.starts_with("* Your ") && message.contains(" can hold") { return true; }
-    // /command : or /command < (catches /THINK <msg>, /examine :, etc.)
-    if let Some(rest) = message.strip_prefix('/') {
-        if rest.chars().next().is_some_and(|c| c.is_alphabetic())
-            && (rest.contains(" :") || rest.contains(" <")) { return true; }
+fn cmd_unscan(db_path: &str, file: &Path, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    // Scans record the path they were given, as typed or absolute; match each form by key.
+    let as_typed = file.to_string_lossy().to_string();
+    let mut candidates = vec![as_typed.clone()];
+    candidates.extend(std::path::absolute(file).ok().map(|p| p.to_string_lossy().to_string()));
+    candidates.extend(std::fs::canonicalize(file).ok().map(|p| p.to_string_lossy().to_string()));
+    let mut path = db.log_path_key(&as_typed)?;
+    for candidate in &candidates {
+        let key = db.log_path_key(candidate)?;
+        if db.is_log_scanned(&key)? {
+            path = key;
+            break;
+        }
     }
-    // Type A/B description lines
-    if message.starts_with("This ") && (message.contains(" helps") || message.contains(" allows")) { return true; }
-    if message.starts_with("* The ") && message.contains(" allows ") { return true; }
-    if message.starts_with("The ") && message.contains(" allows ") { return true; }
-    if message.starts_with("Your ") && message.contains(" allows you to") { return true; }
-    false
+
+    let parser = log_parser(db)?;
+    let progress = ScanProgress::attach(&parser);
+    let summary = parser.unscan_file(&path, |c, t, f| progress.file(c, t, f));
+    progress.finish();
+    let summary = summary?;
+    if format.is_json() {
+        return print_json(&summary);
+    }
+    println!("Unscanned {}:", path);
+    println!("  Other files replayed: {}", summary.files_replayed);
+    println!("  Sessions removed: {}", summary.sessions);
+    println!("  Search lines removed: {}", summary.log_lines);
+    println!("The file will be picked up again by the next scan unless you move it out of the log folder.");
+    Ok(())
 }
 
-/// Use `last_equipped` as an item name fallback only when the equip event happened
-/// within 15 seconds of the current help block.  This prevents delayed server
-/// responses (e.g. gossamer's /use ? arriving 30 s after the player switched to
-/// Ethereal Boots) from being attributed to whatever happened to be equipped last.
-fn equip_fallback(
-    last_equipped: &Option<String>,
-    last_equipped_ts: Option<u32>,
-    block_ts: Option<&str>,
-) -> Option<String> {
-    const MAX_SECS: u32 = 15;
-    let equip_secs = last_equipped_ts?;
-    let block_secs = ts_to_seconds(block_ts?)?;
-    // Handle midnight rollover
-    let delta = if block_secs >= equip_secs {
-        block_secs - equip_secs
-    } else {
-        block_secs + 86400 - equip_secs
-    };
-    if delta <= MAX_SECS { last_equipped.clone() } else { None }
+fn cmd_index_retention(
+    db_path: &str,
+    years: Option<u32>,
+    all_years: bool,
+    events_only: Option<bool>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let mut policy = db.retention_policy()?;
+    if years.is_some() || all_years || events_only.is_some() {
+        if years.is_some() || all_years {
+            policy.max_age_years = years;
+        }
+        if let Some(events_only) = events_only {
+            policy.events_only = events_only;
+        }
+        db.set_retention_policy(&policy)?;
+    }
+    if format.is_json() {
+        return print_json(&policy);
+    }
+    match policy.max_age_years {
+        Some(years) => println!("Years kept:  last {}", years),
+        None => println!("Years kept:  all"),
+    }
+    println!("Lines kept:  {}", if policy.events_only { "events only" } else { "all" });
+    Ok(())
 }
 
-fn cmd_useitem_help(folder: &str, recursive: bool) -> amanuensis_core::Result<()> {
-    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+fn cmd_prune_index(db_path: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let removed = db.prune_log_lines(&*trainer_db()?, amanuensis_core::date::now_naive())?;
+    if format.is_json() {
+        return print_json(&serde_json::json!({ "removed": removed, "remaining": db.log_line_count()? }));
+    }
+    println!("Removed {} indexed line(s); {} remain.", removed, db.log_line_count()?);
+    Ok(())
+}
 
-    let dir = Path::new(folder);
-    if !dir.is_dir() {
-        return Err(amanuensis_core::AmanuensisError::Data(
-            format!("'{}' is not a directory", folder)
-        ));
+fn cmd_index(db_path: &str, action: IndexAction, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    match action {
+        IndexAction::Rebuild => {
+            let parser = log_parser(db)?;
+            let progress = ScanProgress::attach(&parser);
+            let result = parser.rebuild_search_index(|c, t, f| progress.file(c, t, f))?;
+            progress.finish();
+            if format.is_json() {
+                return print_json(&result);
+            }
+            println!("Indexed {} line(s) from {} file(s).", result.lines, result.files);
+            if !result.missing.is_empty() {
+                println!("{} scanned file(s) could not be read (their indexed lines were kept):", result.missing.len());
+                for (path, reason) in &result.missing {
+                    println!("  {}: {}", path, reason);
+                }
+            }
+        }
+        IndexAction::Optimize => {
+            db.optimize_log_lines()?;
+            print_message(format, "Search index optimized.")?;
+        }
+        IndexAction::Stats => {
+            let status = db.index_status()?;
+            if format.is_json() {
+                return print_json(&status);
+            }
+            if !status.available {
+                println!("Search index unavailable: this SQLite build lacks FTS5.");
+                return Ok(());
+            }
+            println!("Indexing:    {}", if status.enabled { "on" } else { "off" });
+            println!("Lines:       {}", status.lines);
+            println!("Characters:  {}", status.characters);
+            println!("Files:       {} of {} scanned", status.files, status.scanned_files);
+            println!("Size:        ~{:.1} MB", status.estimated_bytes as f64 / (1024.0 * 1024.0));
+        }
     }
+    Ok(())
+}
 
-    let mut files = find_log_files(dir, recursive);
-    files.sort();
-    let total_files = files.len();
+fn cmd_path_aliases(
+    db_path: &str,
+    add: &[String],
+    remove: &[String],
+    clear: bool,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let mut aliases = db.log_path_aliases()?;
+    let mut changed = None;
+    if clear || !add.is_empty() || !remove.is_empty() {
+        if clear {
+            aliases.clear();
+        }
+        for from in remove {
+            let from = log_paths::normalize(from);
+            aliases.retain(|a| !log_paths::normalize(&a.from).eq_ignore_ascii_case(&from));
+        }
+        for spec in add {
+            let (from, to) = spec
+                .split_once('=')
+                .ok_or_else(|| amanuensis_core::AmanuensisError::Parse(format!("Expected FROM=TO, got '{}'", spec)))?;
+            let from = log_paths::normalize(from.trim());
+            aliases.retain(|a| !log_paths::normalize(&a.from).eq_ignore_ascii_case(&from));
+            aliases.push(PathAlias { from, to: log_paths::normalize(to.trim()) });
+        }
+        changed = Some(db.set_log_path_aliases(&aliases)?);
+    }
+    if format.is_json() {
+        return print_json(&serde_json::json!({ "aliases": aliases, "changed": changed }));
+    }
+    if aliases.is_empty() {
+        println!("No path aliases.");
+    }
+    for alias in &aliases {
+        println!("{}  ->  {}", alias.from, alias.to);
+    }
+    if let Some(changed) = changed {
+        println!(
+            "Re-keyed {} scanned file(s); merged {} duplicate(s).",
+            changed.rewritten, changed.duplicates_removed
+        );
+    }
+    Ok(())
+}
 
-    // item_name -> file_path -> set of lines seen for that item in that file.
-    // Using per-file sets lets us apply majority voting: a line belongs to an item
-    // only if it appeared in the majority of files where that item was observed.
-    // This drops contaminated lines (e.g. gossamer lines that sneak into belt of the wild
-    // because of delayed server responses) without any special-casing.
-    let mut item_file_lines: HashMap<String, HashMap<PathBuf, HashSet<String>>> = HashMap::new();
+fn cmd_creature_names(
+    db_path: &str,
+    load: Option<&Path>,
+    enable: bool,
+    disable: bool,
+    clear: bool,
+    preview: bool,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let loaded = load.map(CreatureNameRules::load).transpose()?;
 
-    for path in &files {
-        let bytes = match std::fs::read(path) {
-            Ok(b) => b,
-            Err(_) => continue,
+    if preview {
+        let rules = match loaded {
+            Some(rules) => rules,
+            None => CreatureNameRules::new(db.creature_name_rules()?)?,
         };
-        let content = amanuensis_core::encoding::decode_log_bytes(&bytes);
-        let lines: Vec<&str> = content.lines().collect();
+        let groups = db.preview_creature_regroup(&rules)?;
+        if format.is_json() {
+            return print_json(&groups);
+        }
+        if groups.is_empty() {
+            println!("The rules would not change any stored creature names.");
+            return Ok(());
+        }
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec!["Creature", "Kills", "Stored As"]);
+        for g in &groups {
+            let variants: Vec<String> = g.variants.iter().map(|(name, kills)| format!("{name} ({kills})")).collect();
+            table.add_row(vec![g.canonical.clone(), g.kills.to_string(), variants.join(", ")]);
+        }
+        println!("{} creature(s) would regroup:", groups.len());
+        println!("{table}");
+        return Ok(());
+    }
 
-        // State machine
-        let mut last_equipped: Option<String> = None;
-        // Timestamp when last_equipped was set — used to reject delayed help responses
-        // that arrive long after the equip event (gossamer contamination prevention).
-        let mut last_equipped_ts: Option<u32> = None;
-        let mut in_block = false;
-        let mut block_item: Option<String> = None;
-        let mut block_lines: Vec<String> = Vec::new();
-        // Timestamp of the most recently added block line ("same-timestamp = same server burst")
-        let mut block_last_ts: Option<String> = None;
+    if clear {
+        db.set_creature_name_rules(&[])?;
+    }
+    if let Some(rules) = &loaded {
+        db.set_creature_name_rules(rules.rules())?;
+    }
+    if enable || disable {
+        db.set_creature_name_rules_enabled(enable)?;
+    }
+    let rules = db.creature_name_rules()?;
+    let enabled = db.creature_name_rules_enabled()?;
+    if format.is_json() {
+        return print_json(&serde_json::json!({ "enabled": enabled, "rules": rules }));
+    }
+    if rules.is_empty() {
+        println!("No creature-name rules.");
+        return Ok(());
+    }
+    for rule in &rules {
+        println!("{}  ->  {}", rule.pattern, rule.name);
+    }
+    if enabled {
+        println!("Rules are on; scans record the names on the right.");
+    } else {
+        println!("Rules are off (turn them on with --enable).");
+    }
+    Ok(())
+}
 
-        // Trim same-timestamp noise from block edges and record lines into item_file_lines.
-        let finalize = |block_item: &Option<String>,
-                            block_lines: &mut Vec<String>,
-                            item_file_lines: &mut HashMap<String, HashMap<PathBuf, HashSet<String>>>| {
-            if let Some(item) = block_item {
-                // Trim non-help lines from both edges (blank lines, loot messages, etc.
-                // that share a timestamp with the help burst but aren't part of it).
-                while let Some(last) = block_lines.last() {
-                    if is_help_trigger(last) || is_help_continuation(last) { break; }
-                    block_lines.pop();
-                }
-                while !block_lines.is_empty() {
-                    if is_help_trigger(&block_lines[0]) || is_help_continuation(&block_lines[0]) { break; }
-                    block_lines.remove(0);
-                }
-                if !block_lines.is_empty() {
-                    let file_set = item_file_lines
-                        .entry(item.clone()).or_default()
-                        .entry(path.clone()).or_default();
-                    for line in block_lines.drain(..) {
-                        file_set.insert(line);
-                    }
-                }
-            }
-            block_lines.clear();
-        };
+/// A `--from`/`--to` search date in storage form. With `end_of_day`, a bare date covers
+/// the whole day.
+fn search_date_bound(value: Option<&str>, flag: &str, end_of_day: bool) -> amanuensis_core::Result<Option<String>> {
+    use amanuensis_core::date;
+
+    let Some(value) = value else { return Ok(None) };
+    let parsed = date::parse(value).ok_or_else(|| amanuensis_core::AmanuensisError::Data(format!(
+        "Invalid {} '{}': expected a date like 2024-03-01",
+        flag, value
+    )))?;
+    if end_of_day && !value.contains(':') {
+        return Ok(Some(format!("{} 23:59:59", &date::to_storage(&parsed)[..10])));
+    }
+    Ok(Some(date::to_storage(&parsed)))
+}
 
-        let mut i = 0;
-        while i < lines.len() {
-            let raw = lines[i];
-            let (ts, message) = split_timestamp(raw);
-            let cur_ts = if ts.is_empty() { None } else { Some(ts) };
+fn cmd_search(
+    db_path: &str,
+    query: &str,
+    character: Option<&str>,
+    mut options: LogSearchOptions,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
 
-            // Equip pattern always takes priority over same-timestamp logic
-            if let Some(item_name) = message.strip_prefix("You equip your ").and_then(|s| s.strip_suffix('.')) {
-                finalize(&block_item, &mut block_lines, &mut item_file_lines);
+    if let Some(name) = character {
+        let char = resolve_character(&db, name)?;
+        options.char_id = char.id;
+    }
+
+    if let Some(path) = output {
+        let export_format = SearchExportFormat::from_path(path).ok_or_else(|| {
+            amanuensis_core::AmanuensisError::Data(format!(
+                "Can't tell the format of '{}': use a .csv or .jsonl file name",
+                path.display()
+            ))
+        })?;
+        let mut out = io::BufWriter::new(std::fs::File::create(path)?);
+        let written = db.export_search_results(query, &options, export_format, &mut out)?;
+        out.flush()?;
+        if format.is_json() {
+            return print_json(&serde_json::json!({ "written": written, "path": path }));
+        }
+        println!("Saved {} result(s) for '{}' to {}.", written, query, path.display());
+        return Ok(());
+    }
+
+    let page = db.search_log_lines_page(query, &options)?;
+    let results = page.results;
+    if format.is_json() {
+        return print_json(&results);
+    }
+
+    if results.is_empty() {
+        println!("No results found for '{}'.", query);
+        let line_count = db.log_line_count()?;
+        if line_count == 0 {
+            println!("Hint: The FTS5 index is empty. Run `amanuensis index rebuild` (or scan without --no-index) to populate it.");
+        }
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["File", "Character", "Content"]);
+
+    for r in &results {
+        // Strip path to just filename for readability
+        let filename = Path::new(&r.file_path)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| r.file_path.clone());
+
+        // Strip <mark> tags from snippet for terminal display
+        let mut content = r.snippet.replace("<mark>", "").replace("</mark>", "");
+        if !r.context_before.is_empty() || !r.context_after.is_empty() {
+            // Whole lines with context: the match is marked with `>`.
+            let lines: Vec<String> = r
+                .context_before
+                .iter()
+                .map(|l| format!("  {l}"))
+                .chain([format!("> {}", r.content)])
+                .chain(r.context_after.iter().map(|l| format!("  {l}")))
+                .collect();
+            content = lines.join("\n");
+        }
+
+        table.add_row(vec![filename, r.character_name.clone(), content]);
+    }
+
+    if page.total > results.len() as i64 {
+        println!(
+            "Search results for '{}' ({}-{} of {} matches):",
+            query,
+            page.offset + 1,
+            page.offset + results.len() as i64,
+            page.total
+        );
+    } else {
+        println!("Search results for '{}' ({} matches):", query, results.len());
+    }
+    println!("{table}");
+    if page.has_more {
+        println!("More results: --offset {}", page.offset + results.len() as i64);
+    }
+    Ok(())
+}
+
+fn cmd_context(db_path: &str, file: &Path, timestamp: &str, lines: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
+    use amanuensis_core::transcript::{parse_transcript_time, transcript_around};
+
+    let at = parse_transcript_time(timestamp).ok_or_else(|| amanuensis_core::AmanuensisError::Data(format!(
+        "Invalid timestamp '{}': expected e.g. \"2024-01-01 13:01:00\" or \"1/1/24 1:01:00p\"",
+        timestamp
+    )))?;
+    // A search result's path is a stored key, which a root alias may have rewritten; read the
+    // file through the path it was scanned from.
+    let file = if file.exists() {
+        file.to_path_buf()
+    } else {
+        PathBuf::from(Database::open(db_path)?.log_file_source(&file.to_string_lossy())?)
+    };
+    let transcript = transcript_around(&file, at, lines)?;
+    if format.is_json() {
+        return print_json(&transcript);
+    }
+    for line in &transcript.lines {
+        println!("{:>6} {} {}", line.number, if line.hit { ">" } else { " " }, line.text);
+    }
+    Ok(())
+}
+
+fn cmd_export(
+    db_path: &str,
+    table: &str,
+    format: OutputFormat,
+    character: Option<&str>,
+    output: &Path,
+    anonymize: bool,
+) -> amanuensis_core::Result<()> {
+    use amanuensis_core::{Anonymizer, ExportFormat, ExportTable};
+
+    let format = match format {
+        OutputFormat::Csv => ExportFormat::Csv,
+        _ => ExportFormat::Json,
+    };
+    let tables: Vec<ExportTable> = if table.eq_ignore_ascii_case("all") {
+        ExportTable::ALL.to_vec()
+    } else {
+        vec![ExportTable::parse(table).ok_or_else(|| {
+            amanuensis_core::AmanuensisError::Data(format!(
+                "Invalid table '{}'. Must be: characters, kills, trainers, lastys, pets, clan_history, companions, all",
+                table
+            ))
+        })?]
+    };
+
+    let db = Database::open(db_path)?;
+    let char_id = match character {
+        Some(name) => Some(resolve_character(&db, name)?.id.unwrap()),
+        None => None,
+    };
+
+    std::fs::create_dir_all(output)?;
+    // One anonymizer for every file, so a player is the same "Player N" throughout.
+    let mut anonymizer = Anonymizer::new();
+    for t in tables {
+        let contents = if anonymize {
+            db.export_table_anonymized(t, char_id, format, &mut anonymizer)?
+        } else {
+            db.export_table(t, char_id, format)?
+        };
+        let path = output.join(format!("{}.{}", t.as_str(), format.extension()));
+        std::fs::write(&path, contents)?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn cmd_reset(db_path: &str, yes: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    if !yes {
+        eprint!("This will delete all data in '{}'. Continue? [y/N] ", db_path);
+        let _ = io::stderr().flush();
+        let input = read_stdin_line()?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            return print_message(format, "Aborted.");
+        }
+    }
+
+    let path = Path::new(db_path);
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| {
+            io::Error::new(e.kind(), format!("Failed to delete '{}': {}", db_path, e))
+        })?;
+    }
+
+    // Re-create empty database (schema is created on open)
+    let _db = Database::open(db_path)?;
+    print_message(format, &format!("Database '{}' has been reset.", db_path))
+}
+
+fn cmd_creature_catalog(db_path: &str, character: Option<&str>, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let creatures = creature_db()?;
+    let db = Database::open(db_path)?;
+    let char_id = match character {
+        Some(name) => resolve_character(&db, name)?.id,
+        None => None,
+    };
+    let catalog = db.creature_catalog(&creatures, char_id)?;
+
+    if format.is_json() {
+        return print_json(&catalog);
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    if char_id.is_some() {
+        table.set_header(vec!["Creature", "Value", "Kills", "Killed By", "Status"]);
+    } else {
+        table.set_header(vec!["Creature", "Value"]);
+    }
+    for e in &catalog.creatures {
+        let mut row = vec![e.name.clone(), e.value.to_string()];
+        if let Some(encounter) = e.encounter {
+            row.extend([e.kills.to_string(), e.killed_by.to_string(), encounter.as_label().to_string()]);
+        }
+        table.add_row(row);
+    }
+    println!("{table}");
+    match character {
+        Some(name) => println!(
+            "{} has killed {} of {} creatures ({:.1}%, bestiary v{}).",
+            name,
+            catalog.killed,
+            catalog.creatures.len(),
+            catalog.completion(),
+            catalog.bestiary_version
+        ),
+        None => println!("{} creatures (bestiary v{}).", catalog.creatures.len(), catalog.bestiary_version),
+    }
+    Ok(())
+}
+
+fn cmd_catalog_diff(db_path: &str, character: Option<&str>, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let creatures = creature_db()?;
+    let db = Database::open(db_path)?;
+    let char_id = match character {
+        Some(name) => resolve_character(&db, name)?.id,
+        None => None,
+    };
+    let missing = db.catalog_diff(&creatures, char_id)?;
+
+    if format.is_json() {
+        return print_json(&missing);
+    }
+    if format == OutputFormat::Csv {
+        // Ready for --creature-db once the values are filled in.
+        println!("{}", amanuensis_core::CREATURE_CSV_COLUMNS.join(","));
+        for m in &missing {
+            println!("\"{}\",,,,", m.name.replace('"', "\"\""));
+        }
+        return Ok(());
+    }
+    if missing.is_empty() {
+        println!("Every creature in the kills table is in the catalog (bestiary v{}).", creatures.bestiary_version());
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Creature", "Kills", "Killed By", "Characters", "First", "Last"]);
+    for m in &missing {
+        table.add_row(vec![
+            m.name.clone(),
+            m.kills.to_string(),
+            m.killed_by.to_string(),
+            m.characters.to_string(),
+            m.first_date.map(|d| d.to_string()).unwrap_or_default(),
+            m.last_date.map(|d| d.to_string()).unwrap_or_default(),
+        ]);
+    }
+    println!("{table}");
+    println!(
+        "{} creature(s) missing from bestiary v{}. `--format csv` writes a creatures CSV to fill in for --creature-db.",
+        missing.len(),
+        creatures.bestiary_version()
+    );
+    Ok(())
+}
+
+fn cmd_chat(
+    db_path: &str,
+    speaker: Option<String>,
+    contains: Option<String>,
+    character: Option<&str>,
+    limit: usize,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char_id = match character {
+        Some(name) => resolve_character(&db, name)?.id,
+        None => None,
+    };
+    let filter = amanuensis_core::db::queries::ChatFilter { char_id, speaker, contains, limit };
+    let lines = db.get_chat(&filter)?;
+
+    if format.is_json() {
+        return print_json(&lines);
+    }
+    if lines.is_empty() {
+        println!("No chat lines found (scan or update with --chat to record them).");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Time", "Speaker", "Verb", "Text"]);
+    for line in &lines {
+        table.add_row(vec![line.timestamp.to_string(), line.speaker.clone(), line.verb.clone(), line.text.clone()]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+fn cmd_unknowns(db_path: &str, top: usize, character: Option<&str>, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char_id = match character {
+        Some(name) => resolve_character(&db, name)?.id,
+        None => None,
+    };
+    let unknowns = db.get_unknown_lines(char_id, top)?;
+
+    if format.is_json() {
+        return print_json(&unknowns);
+    }
+    if unknowns.is_empty() {
+        println!("No unrecognized lines recorded (scan or update with --unknown-lines to collect them).");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Message", "Count", "Characters", "First", "Last", "Example"]);
+    for u in &unknowns {
+        table.add_row(vec![
+            u.message.clone(),
+            u.count.to_string(),
+            u.characters.to_string(),
+            u.first_seen.clone(),
+            u.last_seen.clone(),
+            u.example.clone(),
+        ]);
+    }
+    println!("{table}");
+    println!("Digit runs are shown as #; each row is one message format.");
+    Ok(())
+}
+
+fn cmd_classify(file: &Path, matched: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let bytes = if file.as_os_str() == "-" {
+        let mut bytes = Vec::new();
+        io::Read::read_to_end(&mut io::stdin(), &mut bytes)?;
+        bytes
+    } else {
+        std::fs::read(file)?
+    };
+    // Decoded like a log file, so Mac Roman ¥ and • come through.
+    let text = amanuensis_core::encoding::decode_log_bytes(&bytes);
+    let lines = classify_text(&text, &*trainer_db()?);
+    let total = lines.len();
+    let shown: Vec<_> =
+        lines.into_iter().filter(|l| !matched || !matches!(l.event, LogEvent::Ignored)).collect();
+
+    if format.is_json() {
+        return print_json(&shown);
+    }
+    for l in &shown {
+        println!("{:>5}  {:?}", l.line, l.event);
+        println!("       {}", l.message);
+    }
+    let recognized = shown.iter().filter(|l| !matches!(l.event, LogEvent::Ignored)).count();
+    println!("{} line(s), {} parsed as an event.", total, recognized);
+    Ok(())
+}
+
+fn cmd_glossary(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let creatures = creature_db()?;
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let glossary = db.glossary_merged(&creatures, char.id.unwrap())?;
+
+    if format.is_json() {
+        return print_json(&glossary);
+    }
+    if glossary.is_empty() {
+        println!("No creatures encountered by {}.", name);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Creature", "Family", "Value", "Killed", "Assisted", "Killed By", "Studied", "First Seen", "Last Seen",
+        ]);
+    for e in &glossary {
+        let studied = e
+            .studied
+            .iter()
+            .map(|s| if s.finished { format!("{} ✓", s.lasty_type) } else { s.lasty_type.clone() })
+            .collect::<Vec<_>>()
+            .join(", ");
+        table.add_row(vec![
+            e.creature.clone(),
+            e.family.clone().unwrap_or_else(|| "-".to_string()),
+            if e.value > 0 { e.value.to_string() } else { "-".to_string() },
+            e.killed.to_string(),
+            e.assisted.to_string(),
+            e.killed_by.to_string(),
+            if studied.is_empty() { "-".to_string() } else { studied },
+            e.first_seen.map_or("-".to_string(), |d| d.to_string()),
+            e.last_seen.map_or("-".to_string(), |d| d.to_string()),
+        ]);
+    }
+    println!("{table}");
+    println!("{} creatures encountered.", glossary.len());
+    Ok(())
+}
+
+fn cmd_trainer_history(db_path: &str, name: &str, trainer: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let months = db.get_trainer_rank_history_merged(char.id.unwrap(), trainer)?;
+
+    if format.is_json() {
+        return print_json(&months);
+    }
+    if months.is_empty() {
+        println!(
+            "No rank history for {} with {}. Ranks scanned before rank history was recorded need a 'rescan'.",
+            name, trainer
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Month", "Ranks", "Total"]);
+    for m in &months {
+        table.add_row(vec![m.month.clone(), m.ranks.to_string(), m.cumulative.to_string()]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+fn cmd_eta(db_path: &str, name: &str, trainer: &str, target: i64, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let eta = db.training_eta_merged(char.id.unwrap(), trainer, target, amanuensis_core::date::now_naive())?;
+
+    if format.is_json() {
+        return print_json(&eta);
+    }
+
+    println!("=== {} with {} ===", char.name, eta.trainer);
+    println!("Ranks:        {} of {}", eta.current_ranks, eta.target);
+    println!(
+        "Pace:         {:.1} ranks/week ({} ranks in the last {} days)",
+        eta.ranks_per_week, eta.ranks_in_window, eta.window_days
+    );
+    if let Some(last) = &eta.last_rank {
+        println!("Last rank:    {}", last);
+    }
+    match (&eta.eta, eta.weeks_remaining) {
+        _ if eta.remaining == 0 => println!("Target reached."),
+        (Some(day), Some(weeks)) => {
+            println!("Remaining:    {} ranks, about {:.1} weeks", eta.remaining, weeks);
+            println!("ETA:          {}", day);
+        }
+        _ => println!(
+            "Remaining:    {} ranks; no ranks in the last {} days, so no ETA.",
+            eta.remaining, eta.window_days
+        ),
+    }
+    Ok(())
+}
+
+/// Discord rejects webhook messages longer than this.
+const WEBHOOK_CONTENT_LIMIT: usize = 2000;
+
+fn cmd_digest(
+    db_path: &str,
+    since: &str,
+    out: Option<&Path>,
+    webhook: Option<&str>,
+    save_webhook: bool,
+    clear_webhook: bool,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    use amanuensis_core::date;
+    use amanuensis_core::db::queries::SETTING_DIGEST_WEBHOOK;
+
+    let db = Database::open(db_path)?;
+    if clear_webhook {
+        db.delete_setting(SETTING_DIGEST_WEBHOOK)?;
+    }
+    if save_webhook {
+        if let Some(url) = webhook {
+            db.set_setting(SETTING_DIGEST_WEBHOOK, url)?;
+        }
+    }
+
+    // `last` covers the files read since the previous digest; the position is recorded below.
+    let until = db.latest_log_time()?.map(|t| t.to_string()).unwrap_or_default();
+    let (digest, position) = match since {
+        "last" => {
+            let (digest, position) = db.digest_since_last()?;
+            (digest, Some(position))
+        }
+        "all" => (db.digest(None, &until)?, None),
+        other => {
+            let since = date::normalize(other).ok_or_else(|| {
+                amanuensis_core::AmanuensisError::Data(format!(
+                    "Invalid --since '{}': expected \"last\", \"all\" or a date like 2024-03-01",
+                    other
+                ))
+            })?;
+            (db.digest(Some(&since), &until)?, None)
+        }
+    };
+
+    let markdown = digest.to_markdown();
+    let text = if format.is_json() {
+        serde_json::to_string_pretty(&digest)? + "\n"
+    } else {
+        markdown.clone()
+    };
+    match out {
+        Some(path) => {
+            std::fs::write(path, &text)?;
+            println!("Wrote digest to {}", path.display());
+        }
+        None => print!("{text}"),
+    }
+
+    if let Some(url) = webhook.map(str::to_string).or(db.get_setting(SETTING_DIGEST_WEBHOOK)?) {
+        post_digest(&url, &digest, &markdown)?;
+        if out.is_some() {
+            println!("Posted digest to webhook");
+        }
+    }
+
+    // Only a delivered `--since last` digest moves the marker, so a failed run is retried.
+    if let Some(position) = position {
+        db.record_digest(&position)?;
+    }
+    Ok(())
+}
+
+/// POST a digest as `{"content", "text", "digest"}`: Discord reads `content`, Slack `text`.
+fn post_digest(url: &str, digest: &amanuensis_core::Digest, markdown: &str) -> amanuensis_core::Result<()> {
+    let content = if markdown.chars().count() > WEBHOOK_CONTENT_LIMIT {
+        markdown.chars().take(WEBHOOK_CONTENT_LIMIT - 1).collect::<String>() + "…"
+    } else {
+        markdown.to_string()
+    };
+    let payload = serde_json::json!({ "content": content, "text": markdown, "digest": digest });
+    reqwest::blocking::Client::new()
+        .post(url)
+        .json(&payload)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| amanuensis_core::AmanuensisError::Data(format!("Webhook post failed: {e}")))?;
+    Ok(())
+}
+
+fn cmd_trainer_catalog(
+    db_path: &str,
+    profession_filter: Option<&str>,
+    character: Option<&str>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let tdb = trainer_db()?;
+    let mut coverage = match character {
+        Some(name) => {
+            let db = Database::open(db_path)?;
+            let char_id = resolve_character(&db, name)?.id.unwrap();
+            Some(db.trainer_coverage(&tdb, char_id)?)
+        }
+        None => None,
+    };
+    let mut trainers = tdb.all_trainer_metadata();
+
+    if let Some(prof) = profession_filter {
+        let prof_lower = prof.to_lowercase();
+        let keep = |p: &Option<String>| p.as_ref().map(|p| p.to_lowercase() == prof_lower).unwrap_or(false);
+        trainers.retain(|t| keep(&t.profession));
+        if let Some(c) = coverage.as_mut() {
+            c.trainers.retain(|t| keep(&t.trainer.profession));
+            c.trained = c.trainers.iter().filter(|t| t.ranks > 0).count();
+            c.maxed = c.trainers.iter().filter(|t| t.maxed).count();
+        }
+    }
+
+    if format.is_json() {
+        return match &coverage {
+            Some(c) => print_json(c),
+            None => print_json(&trainers),
+        };
+    }
+    if trainers.is_empty() {
+        println!("No trainers found.");
+        return Ok(());
+    }
+
+    let has_combos = trainers.iter().any(|t| t.is_combo);
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    let mut header = vec!["Name", "Profession", "Multiplier"];
+    if has_combos {
+        header.extend(["Combo", "Components"]);
+    }
+    if coverage.is_some() {
+        header.extend(["Ranks", "Maxed"]);
+    }
+    table.set_header(header);
+
+    for (i, t) in trainers.iter().enumerate() {
+        let name = retired_label(&t.name, t.retired, t.replaced_by.as_deref());
+        let prof = t.profession.as_deref().unwrap_or("-");
+        let mult = if (t.multiplier - 1.0).abs() < f64::EPSILON {
+            "1.0".to_string()
+        } else {
+            format!("{:.1}", t.multiplier)
+        };
+
+        let mut row = vec![name, prof.to_string(), mult];
+        if has_combos {
+            let combo = if t.is_combo { "Yes" } else { "" };
+            row.extend([combo.to_string(), t.combo_components.join(", ")]);
+        }
+        if let Some(c) = &coverage {
+            let entry = &c.trainers[i];
+            row.push(if entry.ranks > 0 { entry.ranks.to_string() } else { String::new() });
+            row.push(if entry.maxed { "maxed".to_string() } else { String::new() });
+        }
+        table.add_row(row);
+    }
+
+    println!("Trainer Catalog ({} trainers):", trainers.len());
+    if let (Some(c), Some(name)) = (&coverage, character) {
+        println!(
+            "{}: trained {} of {} ({:.0}%), {} maxed",
+            name,
+            c.trained,
+            c.trainers.len(),
+            c.coverage(),
+            c.maxed
+        );
+    }
+    println!("{table}");
+    Ok(())
+}
+
+fn cmd_coins(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let base_char = resolve_character(&db, name)?;
+    let char_id = base_char.id.unwrap();
+    let char = db.get_character_merged(char_id)?.unwrap_or(base_char);
+    let stones = db.get_stone_usage_merged(char_id)?;
+    if format.is_json() {
+        return print_json(&serde_json::json!({
+            "name": char.name,
+            "coin_level": char.coin_level,
+            "coins_picked_up": char.coins_picked_up,
+            "fur_coins": char.fur_coins,
+            "fur_worth": char.fur_worth,
+            "blood_coins": char.blood_coins,
+            "blood_worth": char.blood_worth,
+            "mandible_coins": char.mandible_coins,
+            "mandible_worth": char.mandible_worth,
+            "casino_won": char.casino_won,
+            "casino_lost": char.casino_lost,
+            "chest_coins": char.chest_coins,
+            "bounty_coins": char.bounty_coins,
+            "darkstone": char.darkstone,
+            "stones": stones,
+        }));
+    }
+
+    println!("=== Coins for {} ===", char.name);
+    println!("Coin Level:      {}", char.coin_level);
+    println!("Coins Picked Up: {}", char.coins_picked_up);
+    println!("Fur Shares:      {}  (worth: {})", char.fur_coins, char.fur_worth);
+    println!("Blood Shares:    {}  (worth: {})", char.blood_coins, char.blood_worth);
+    println!("Mandible Shares: {}  (worth: {})", char.mandible_coins, char.mandible_worth);
+    if char.casino_won > 0 || char.casino_lost > 0 {
+        println!("Casino Won:      {}", char.casino_won);
+        println!("Casino Lost:     {}", char.casino_lost);
+    }
+    if char.chest_coins > 0 {
+        println!("Chest Coins:     {}", char.chest_coins);
+    }
+    if char.bounty_coins > 0 {
+        println!("Bounty Coins:    {}", char.bounty_coins);
+    }
+    if char.darkstone > 0 {
+        println!("Darkstone:       {}", char.darkstone);
+    }
+    for s in &stones {
+        println!("{}", stone_usage_line(s));
+    }
+
+    Ok(())
+}
+
+fn cmd_coin_timeline(db_path: &str, name: &str, period: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+
+    let periods = db.get_coin_timeline_merged(char.id.unwrap(), period)?;
+    if format.is_json() {
+        return print_json(&periods);
+    }
+    if periods.is_empty() {
+        println!("No coin activity recorded for {}.", name);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Period", "Income", "Spent", "Net", "Balance"]);
+    for p in &periods {
+        table.add_row(vec![
+            p.period.clone(),
+            p.income.to_string(),
+            p.spent.to_string(),
+            format!("{:+}", p.net),
+            p.balance.to_string(),
+        ]);
+    }
+
+    println!("Coin timeline for {} ({}):", name, period);
+    println!("{table}");
+    Ok(())
+}
+
+fn cmd_casino(db_path: &str, name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+
+    let summary = db.get_casino_summary_merged(char.id.unwrap())?;
+    if format.is_json() {
+        return print_json(&summary);
+    }
+    if summary.games.is_empty() {
+        println!("No casino games recorded for {}.", name);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Game", "Bets", "Wagered", "Wins", "Won", "Losses", "Lost", "Net"]);
+    for g in &summary.games {
+        table.add_row(vec![
+            g.game.clone(),
+            g.bets.to_string(),
+            g.wagered.to_string(),
+            g.wins.to_string(),
+            g.won.to_string(),
+            g.losses.to_string(),
+            g.lost.to_string(),
+            format!("{:+}", g.net()),
+        ]);
+    }
+
+    println!("Casino for {}:", name);
+    println!("{table}");
+    println!("Net: {:+} coins", summary.net);
+    if let Some(w) = &summary.biggest_win {
+        println!("Biggest win:  {} coins at {} ({})", w.amount, w.game, w.date);
+    }
+    if let Some(l) = &summary.biggest_loss {
+        println!("Biggest loss: {} coins at {} ({})", l.amount, l.game, l.date);
+    }
+    Ok(())
+}
+
+/// "Darkstone: 3 used, 1 charged, 2 charges left" for `summary` and `coins`.
+fn stone_usage_line(s: &amanuensis_core::db::queries::StoneUsage) -> String {
+    let mut line = format!("{}{}: {} used, {} charged", s.stone[..1].to_uppercase(), &s.stone[1..], s.used, s.charged);
+    if s.expired > 0 {
+        line += &format!(", {} expired", s.expired);
+    }
+    if let Some(left) = s.charges_left {
+        line += &format!(", {} charges left", left);
+    }
+    line
+}
+
+fn cmd_progression(
+    db_path: &str,
+    name: &str,
+    min_kills: i64,
+    max_killed_by_ratio: f64,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
+    let criteria = ProgressionCriteria { min_solo_kills: min_kills, max_killed_by_ratio };
+    let progression = db.hunting_progression_merged(char_id, &criteria)?;
+
+    if format.is_json() {
+        return print_json(&progression);
+    }
+
+    println!("=== Hunting Tier for {} ===", char.name);
+    println!(
+        "(creatures with >= {} solo kills and killed-by ratio <= {})",
+        min_kills, max_killed_by_ratio
+    );
+    match &progression.current {
+        Some(c) => println!(
+            "Current tier: {} ({}, {} solo kills, killed by {})",
+            c.creature_value, c.creature_name, c.solo_kills, c.killed_by
+        ),
+        None => {
+            println!("No creature is reliably soloed yet.");
+            return Ok(());
+        }
+    }
+
+    if progression.milestones.is_empty() {
+        println!();
+        println!("No tier history. (Run `amanuensis rescan <folder...>`, or the GUI's Rescan Logs, to backfill.)");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Reached", "Tier", "Creature"]);
+    for m in &progression.milestones {
+        table.add_row(vec![
+            m.day.clone(),
+            m.creature_value.to_string(),
+            m.creature_name.clone(),
+        ]);
+    }
+    println!();
+    println!("{table}");
+
+    Ok(())
+}
+
+fn cmd_leaderboard(
+    db_path: &str,
+    metric: &str,
+    limit: Option<usize>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let metric = LeaderboardMetric::parse(metric).ok_or_else(|| {
+        amanuensis_core::AmanuensisError::Data(format!(
+            "Invalid metric '{}'. Must be: kills, ranks, coins, deaths, depart-rate",
+            metric
+        ))
+    })?;
+    let db = Database::open(db_path)?;
+    let mut board = db.leaderboard(metric)?;
+    if let Some(n) = limit {
+        board.truncate(n);
+    }
+
+    if format.is_json() {
+        return print_json(&board);
+    }
+    if board.is_empty() {
+        println!("No characters to rank.");
+        return Ok(());
+    }
+
+    let value_header = match metric {
+        LeaderboardMetric::DepartRate => "Depart Rate",
+        LeaderboardMetric::Kills => "Kills",
+        LeaderboardMetric::Ranks => "Ranks",
+        LeaderboardMetric::Coins => "Coins",
+        LeaderboardMetric::Deaths => "Deaths",
+    };
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["#", "Character", "Profession", value_header]);
+    for e in &board {
+        let value = match metric {
+            LeaderboardMetric::DepartRate => format!("{:.1}%", e.value),
+            _ => format!("{}", e.value as i64),
+        };
+        table.add_row(vec![e.position.to_string(), e.name.clone(), e.profession.clone(), value]);
+    }
+    println!("{table}");
+
+    Ok(())
+}
+
+fn cmd_compare(db_path: &str, name1: &str, name2: &str, kills: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let left = resolve_character(&db, name1)?.id.unwrap();
+    let right = resolve_character(&db, name2)?.id.unwrap();
+    let trainer_db = trainer_db()?;
+    let mut comparison = db.compare_characters(left, right, &trainer_db)?;
+    comparison.kills.truncate(kills);
+
+    if format.is_json() {
+        return print_json(&comparison);
+    }
+
+    let new_table = |first: &str| {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![first, comparison.left.as_str(), comparison.right.as_str(), "Difference"]);
+        table
+    };
+    // Whole numbers print bare; rates and per-frame stats keep two decimals.
+    let number = |v: f64| if v.fract() == 0.0 { format!("{}", v as i64) } else { format!("{v:.2}") };
+    let delta = |v: f64| if v.fract() == 0.0 { format!("{:+}", v as i64) } else { format!("{v:+.2}") };
+
+    let mut trainers = new_table("Trainer");
+    for t in &comparison.trainers {
+        trainers.add_row(vec![t.trainer.clone(), t.left.to_string(), t.right.to_string(), format!("{:+}", t.right - t.left)]);
+    }
+    let mut creatures = new_table("Creature");
+    for k in &comparison.kills {
+        creatures.add_row(vec![k.creature.clone(), k.left.to_string(), k.right.to_string(), format!("{:+}", k.right - k.left)]);
+    }
+    let mut stats = new_table("Stat");
+    for s in &comparison.stats {
+        stats.add_row(vec![s.stat.replace('_', " "), number(s.left), number(s.right), delta(s.delta)]);
+    }
+
+    println!("=== {} vs {} ===", comparison.left, comparison.right);
+    if !comparison.trainers.is_empty() {
+        println!("{trainers}");
+    }
+    if !comparison.kills.is_empty() {
+        println!("{creatures}");
+    }
+    println!("{stats}");
+    Ok(())
+}
+
+fn cmd_widget(db_path: &str, name: &str, out: Option<&Path>) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let widget = db.widget_merged(char.id.unwrap())?.ok_or_else(|| {
+        amanuensis_core::AmanuensisError::CharacterNotFound { name: name.to_string() }
+    })?;
+    let json = serde_json::to_string_pretty(&widget)?;
+    match out {
+        Some(path) => {
+            std::fs::write(path, json)?;
+            println!(
+                "Wrote widget for {} (schema v{}) to {}",
+                widget.name, widget.schema_version, path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+fn cmd_portrait(
+    db_path: &str,
+    name: &str,
+    set: Option<&Path>,
+    clear: bool,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
+    if clear {
+        let message = if db.clear_character_portrait(char_id)? {
+            format!("Removed the portrait of {}.", char.name)
+        } else {
+            format!("{} has no portrait.", char.name)
+        };
+        return print_message(format, &message);
+    }
+    let portrait = match set {
+        Some(path) => Some(db.attach_portrait_file(char_id, path)?),
+        None => db.get_character_portrait(char_id)?,
+    };
+    if format.is_json() {
+        return print_json(&portrait);
+    }
+    match portrait {
+        Some(p) => {
+            println!("Portrait of {}: {}, {} KB", char.name, p.mime, p.data.len().div_ceil(1024));
+            if let Some(source) = &p.source {
+                println!("Source: {source}");
+            }
+            println!("Updated: {}", p.updated_at);
+        }
+        None => println!("{} has no portrait. Attach one with --set <image>.", char.name),
+    }
+    Ok(())
+}
+
+fn cmd_roster(db_path: &str, folder: &Path, html: bool, json: bool, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let written = db.write_roster_report(folder, html, json)?;
+    if format.is_json() {
+        return print_json(&written);
+    }
+    for path in written {
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn cmd_logs(db_path: &str, level: Option<&str>, limit: usize, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let all_logs = db.get_process_logs()?;
+
+    let logs: Vec<_> = all_logs
+        .iter()
+        .filter(|l| level.map(|lv| l.level == lv).unwrap_or(true))
+        .rev()
+        .take(limit)
+        .collect();
+
+    if format.is_json() {
+        return print_json(&logs);
+    }
+    if logs.is_empty() {
+        if all_logs.is_empty() {
+            println!("No process logs found. Run 'amanuensis scan' first.");
+        } else {
+            println!("No logs matching level filter '{}'.", level.unwrap_or(""));
+        }
+        return Ok(());
+    }
+
+    let errors = logs.iter().filter(|l| l.level == "error").count();
+    let warns = logs.iter().filter(|l| l.level == "warn").count();
+    let infos = logs.iter().filter(|l| l.level == "info").count();
+    println!(
+        "Process logs ({} shown: {} error, {} warn, {} info):",
+        logs.len(), errors, warns, infos
+    );
+    println!();
+
+    for log in &logs {
+        let prefix = match log.level.as_str() {
+            "error" => "[ERROR]",
+            "warn"  => "[WARN] ",
+            _       => "[INFO] ",
+        };
+        println!("{}  {}  {}", prefix, log.created_at, log.message);
+    }
+
+    Ok(())
+}
+
+fn cmd_checkpoints(
+    db_path: &str,
+    name: &str,
+    all: bool,
+    trainer_filter: Option<&str>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
+
+    let mut checkpoints = if all {
+        db.get_all_trainer_checkpoints_merged(char_id)?
+    } else {
+        db.get_latest_trainer_checkpoints_merged(char_id)?
+    };
+
+    if let Some(filter) = trainer_filter {
+        let filter_lc = filter.to_lowercase();
+        checkpoints.retain(|c| c.trainer_name.to_lowercase().contains(&filter_lc));
+    }
+
+    if format.is_json() {
+        return print_json(&checkpoints);
+    }
+    if checkpoints.is_empty() {
+        println!("No trainer checkpoints found for {}.", name);
+        println!("Hint: Checkpoints are recorded when a trainer greets you with a rank-status message.");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Trainer", "Min Ranks", "Max Ranks", "Timestamp"]);
+
+    for c in &checkpoints {
+        let max_str = c.rank_max.map(|v| v.to_string()).unwrap_or_else(|| "maxed".to_string());
+        table.add_row(vec![
+            c.trainer_name.clone(),
+            c.rank_min.to_string(),
+            max_str,
+            c.timestamp.to_string(),
+        ]);
+    }
+
+    let label = if all { "all checkpoints" } else { "latest checkpoints" };
+    println!("Trainer checkpoints for {} ({}, {} entries):", name, label, checkpoints.len());
+    println!("{table}");
+
+    Ok(())
+}
+
+fn cmd_set_rank_mode(
+    db_path: &str,
+    name: &str,
+    trainer: &str,
+    mode: &str,
+    ranks: i64,
+    date: Option<&str>,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    // Validate mode early for a friendly error message
+    RankMode::parse(mode).ok_or_else(|| {
+        amanuensis_core::AmanuensisError::Data(
+            format!("Invalid mode '{}'. Must be: modifier, override, override_until_date", mode)
+        )
+    })?;
+
+    if mode == "override_until_date" && date.is_none() {
+        return Err(amanuensis_core::AmanuensisError::Data(
+            "override_until_date mode requires --date <M/D/YY>".to_string()
+        ));
+    }
+
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
+
+    db.set_rank_override(char_id, trainer, mode, ranks, date)?;
+
+    let message = match mode {
+        "modifier" => format!("Set {} / {} to modifier mode (+{} adjusted ranks)", name, trainer, ranks),
+        "override" => format!("Set {} / {} to override mode ({} manual ranks)", name, trainer, ranks),
+        "override_until_date" => format!(
+            "Set {} / {} to override_until_date mode ({} baseline ranks, cutoff: {})",
+            name, trainer, ranks, date.unwrap_or("")
+        ),
+        _ => unreachable!(),
+    };
+    print_message(format, &message)?;
+    if !format.is_json() {
+        println!("Run 'amanuensis scan --force' to rebuild log-derived rank counts.");
+    }
+
+    Ok(())
+}
+
+fn cmd_set_profession(db_path: &str, name: &str, profession: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char = resolve_character(&db, name)?;
+    let char_id = char.id.unwrap();
+
+    let override_value = if profession.eq_ignore_ascii_case("auto") {
+        None
+    } else {
+        // Validate
+        let valid = ["fighter", "healer", "mystic", "ranger", "bloodmage", "champion"];
+        if !valid.contains(&profession.to_lowercase().as_str()) {
+            return Err(amanuensis_core::AmanuensisError::Data(format!(
+                "Invalid profession '{}'. Must be one of: {} — or 'auto' to clear",
+                profession,
+                valid.join(", ")
+            )));
+        }
+        // Capitalize first letter
+        let mut s = profession.to_lowercase();
+        if let Some(c) = s.get_mut(0..1) {
+            c.make_ascii_uppercase();
+        }
+        Some(s)
+    };
+
+    db.set_profession_override(char_id, override_value.as_deref())?;
+
+    let message = match override_value {
+        Some(ref prof) => format!("Set profession for {} to {} (manual override).", name, prof),
+        None => format!("Cleared profession override for {} — auto-detection will apply.", name),
+    };
+    print_message(format, &message)?;
+    if !format.is_json() {
+        println!("Run 'amanuensis scan --force' to recompute profession from logs.");
+    }
+
+    Ok(())
+}
+
+/// Ranks map for the fighter calculator (`amanuensis_core::fighter_rank_map`, as the GUI uses).
+fn fighter_rank_map(db: &Database, char_id: i64) -> amanuensis_core::Result<HashMap<String, i64>> {
+    Ok(amanuensis_core::fighter_rank_map(&db.get_trainers_merged(char_id)?))
+}
+
+fn cmd_simulate(db_path: &str, name: &str, add: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    use amanuensis_core::fighter_stats::parse_rank_deltas;
+    let db = Database::open(db_path)?;
+    let char_id = resolve_character(&db, name)?.id.unwrap();
+
+    // Trainer names as the trainer database spells them
+    let known: Vec<String> = trainer_db()?.all_trainer_metadata().into_iter().map(|m| m.name).collect();
+    let mut deltas = std::collections::BTreeMap::new();
+    for (trainer, n) in parse_rank_deltas(add)? {
+        let canonical = known
+            .iter()
+            .find(|k| k.eq_ignore_ascii_case(&trainer))
+            .ok_or_else(|| amanuensis_core::AmanuensisError::Data(format!("Unknown trainer '{}'", trainer)))?;
+        *deltas.entry(canonical.clone()).or_insert(0) += n;
+    }
+
+    let loadout = db.get_fighter_loadout(char_id)?.unwrap_or_default();
+    let resolved = FighterModifiers::bundled()?.resolve(&loadout)?;
+    let sim = simulate_stats_with(&fighter_rank_map(&db, char_id)?, &deltas, &build_multiplier_map(), &resolved);
+    if format.is_json() {
+        return print_json(&sim);
+    }
+
+    let added: Vec<String> = sim.deltas.iter().map(|(t, n)| format!("{} {:+}", t, n)).collect();
+    println!("=== What if {} trained {} ===", name, added.join(", "));
+    println!("({})", sim.after.loadout);
+    if sim.changes.is_empty() {
+        println!("No fighter stats change.");
+        return Ok(());
+    }
+    let fmt = |v: f64| if v.fract() == 0.0 { format!("{}", v as i64) } else { format!("{:.2}", v) };
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Stat", "Now", "After", "Change"]);
+    for c in &sim.changes {
+        let sign = if c.delta > 0.0 { "+" } else { "" };
+        table.add_row(vec![
+            c.stat.replace('_', " "),
+            fmt(c.before),
+            fmt(c.after),
+            format!("{}{}", sign, fmt(c.delta)),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+fn cmd_rank_sources(db_path: &str, name: &str, trainer: Option<&str>, format: OutputFormat) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char_id = resolve_character(&db, name)?.id.unwrap();
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    if let Some(trainer) = trainer {
+        let events = db.get_rank_source_events_merged(char_id, trainer)?;
+        if format.is_json() {
+            return print_json(&events);
+        }
+        if events.is_empty() {
+            println!("No recorded rank events with {} for {}.", trainer, name);
+            return Ok(());
+        }
+        table.set_header(vec!["Date", "Source", "Ranks", "Log File"]);
+        for e in &events {
+            let ranks = if e.source == amanuensis_core::db::queries::RANK_SOURCE_APPLY_LEARNING_PARTIAL {
+                "1-9".to_string()
+            } else {
+                e.ranks.to_string()
+            };
+            let file = e
+                .log_file
+                .as_deref()
+                .map(|f| Path::new(f).file_name().map_or(f.to_string(), |n| n.to_string_lossy().into_owned()))
+                .unwrap_or_default();
+            table.add_row(vec![e.ranked_at.to_string(), e.source.replace('_', " "), ranks, file]);
+        }
+        println!("Rank events with {} for {}:", events[0].trainer_name, name);
+        println!("{table}");
+        return Ok(());
+    }
+
+    let rows = db.rank_source_breakdown(char_id)?;
+    if format.is_json() {
+        return print_json(&rows);
+    }
+    if rows.is_empty() {
+        println!("No trainer ranks found for {}.", name);
+        return Ok(());
+    }
+    table.set_header(vec![
+        "Trainer", "Counted", "Unrecorded", "Applied", "Partial Applies", "Manual", "Mode", "Effective", "Log Files",
+    ]);
+    for r in &rows {
+        let partial = if r.apply_learning_partial_events > 0 {
+            format!("{} (~{})", r.apply_learning_partial_events, r.apply_learning_estimated_ranks)
+        } else {
+            String::new()
+        };
+        let mode = if r.rank_mode == RankMode::Modifier.as_str() { String::new() } else { r.rank_mode.clone() };
+        table.add_row(vec![
+            r.trainer_name.clone(),
+            r.counted.to_string(),
+            (r.counted - r.counted_recorded).to_string(),
+            format!("{} ({}x)", r.apply_learning_ranks, r.apply_learning_events),
+            partial,
+            r.modified_ranks.to_string(),
+            mode,
+            r.effective_ranks.to_string(),
+            r.log_files.to_string(),
+        ]);
+    }
+    println!("Rank sources for {}:", name);
+    println!("{table}");
+    println!("Unrecorded: counted ranks scanned before rank events were recorded (Rescan Logs to fill in).");
+    Ok(())
+}
+
+fn cmd_untrain_plan(
+    db_path: &str,
+    name: &str,
+    more: &[String],
+    less: &[String],
+    max_ranks: i64,
+    cost: amanuensis_core::untrain::UntrainCost,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let char_id = resolve_character(&db, name)?.id.unwrap();
+    let loadout = db.get_fighter_loadout(char_id)?.unwrap_or_default();
+    let resolved = FighterModifiers::bundled()?.resolve(&loadout)?;
+    let retired = trainer_db()?.all_trainer_metadata().into_iter().filter(|t| t.retired).map(|t| t.name).collect();
+    let plan = amanuensis_core::untrain::plan_untrain(
+        &fighter_rank_map(&db, char_id)?,
+        &build_multiplier_map(),
+        &retired,
+        &resolved,
+        more,
+        less,
+        max_ranks,
+        cost,
+    )?;
+    if format.is_json() {
+        return print_json(&plan);
+    }
+
+    let mut goal: Vec<String> = plan.more.iter().map(|s| format!("more {}", s)).collect();
+    goal.extend(plan.less.iter().map(|s| format!("less {}", s)));
+    println!("=== Untrain plan for {} ({}) ===", name, goal.join(", "));
+    if plan.untrain.is_empty() {
+        println!("Nothing to untrain: every trained rank already helps this profile, or no trainer improves it.");
+        return Ok(());
+    }
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Step", "Trainer", "Ranks", "Score"]);
+    for m in &plan.untrain {
+        table.add_row(vec!["Untrain".to_string(), m.trainer.clone(), format!("-{}", m.ranks), format!("{:.2}", m.score)]);
+    }
+    for m in &plan.retrain {
+        table.add_row(vec!["Retrain".to_string(), m.trainer.clone(), format!("+{}", m.ranks), format!("{:.2}", m.score)]);
+    }
+    println!("{table}");
+    let coins = if plan.cost.coins_per_rank > 0 { format!(", {} coins", plan.coins) } else { String::new() };
+    println!(
+        "Cost: {} rank(s) untrained and retrained, {} Untrainus visit(s){}.",
+        plan.ranks_moved, plan.untrainus_visits, coins
+    );
+    let changes: Vec<String> = plan
+        .simulation
+        .changes
+        .iter()
+        .filter(|c| !matches!(c.stat, "trained_ranks" | "effective_ranks"))
+        .map(|c| format!("{} {:+}", c.stat.replace('_', " "), c.delta))
+        .collect();
+    println!("Result: {}", changes.join(", "));
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_fighter_stats(
+    db_path: &str,
+    name: &str,
+    race: Option<String>,
+    weapon: Option<String>,
+    items: Vec<String>,
+    save: bool,
+    clear: bool,
+    format: OutputFormat,
+) -> amanuensis_core::Result<()> {
+    let db = Database::open(db_path)?;
+    let base_char = resolve_character(&db, name)?;
+    let char_id = base_char.id.unwrap();
+
+    if clear {
+        db.set_fighter_loadout(char_id, None)?;
+    }
+    let mut loadout = db.get_fighter_loadout(char_id)?.unwrap_or_default();
+    if let Some(race) = race {
+        loadout.race = race;
+    }
+    if let Some(weapon) = weapon {
+        loadout.weapon = weapon;
+    }
+    if !items.is_empty() {
+        loadout.items = items;
+    }
+    let resolved = FighterModifiers::bundled()?.resolve(&loadout)?;
+    if save {
+        db.set_fighter_loadout(char_id, Some(&resolved.loadout))?;
+    }
+    let ranks = fighter_rank_map(&db, char_id)?;
+    let multiplier_map = build_multiplier_map();
+    let stats = compute_fighter_stats_with(&ranks, &multiplier_map, &resolved);
+    if format.is_json() {
+        return print_json(&stats);
+    }
+
+    println!("=== Fighter Stats for {} ===", name);
+    println!("({})", stats.loadout);
+    if !stats.unknown_modifiers.is_empty() {
+        println!("Modifiers not known yet for {}; counted as no change.", stats.unknown_modifiers.join(", "));
+    }
+    println!();
+    println!("Trained Ranks:    {}", stats.trained_ranks);
+    println!("Effective Ranks:  {}", stats.effective_ranks);
+    println!("Slaughter Points: {}", stats.slaughter_points);
+    println!();
+    println!("--- Offense ---");
+    println!("Accuracy:         {}", stats.accuracy);
+    println!("Damage:           {} - {}", stats.damage_min, stats.damage_max);
+    println!("Offense:          {}", stats.offense);
+    println!("Balance/Swing:    {}", stats.balance_per_swing);
+    println!();
+    println!("--- Defense ---");
+    println!("Defense:          {}", stats.defense);
+    println!("Balance:          {}", stats.balance);
+    println!("Balance Regen:    {} ({:.1}/frame)", stats.balance_regen, stats.balance_per_frame);
+    println!("Health:           {}", stats.health);
+    println!("Health Regen:     {} ({:.1}/frame)", stats.health_regen, stats.health_per_frame);
+    println!("Spirit:           {}", stats.spirit);
+    println!("Spirit Regen:     {} ({:.1}/frame)", stats.spirit_regen, stats.spirit_per_frame);
+    println!();
+    println!("--- Other ---");
+    println!("Heal Receptivity: {}", stats.heal_receptivity);
+    println!("Shieldstone Drain: {}", stats.shieldstone_drain);
+
+    Ok(())
+}
+
+// ── useitem-help ─────────────────────────────────────────────────────────────
+
+/// Replace Mac Roman smart quotes with ASCII equivalents.
+fn normalize_quotes(s: &str) -> String {
+    s.replace(['\u{201C}', '\u{201D}'], "\"")
+     .replace(['\u{2018}', '\u{2019}'], "'")
+}
+
+fn find_log_files(dir: &Path, recursive: bool) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else { return files };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() && recursive {
+            files.extend(find_log_files(&path, true));
+        } else if path.extension().and_then(|e| e.to_str()) == Some("txt") {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Split a CL log line into `(timestamp_slice, message_slice)`.
+/// Returns `("", line)` if no timestamp prefix is found.
+/// Handles both 12-hour (`8:38:19p`) and 24-hour (`15:25:42`) formats.
+fn split_timestamp(line: &str) -> (&str, &str) {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    // date part: digits '/' digits '/' digits
+    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
+    if i == 0 || i >= bytes.len() || bytes[i] != b'/' { return ("", line); }
+    i += 1;
+    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
+    if i >= bytes.len() || bytes[i] != b'/' { return ("", line); }
+    i += 1;
+    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
+    if i >= bytes.len() || bytes[i] != b' ' { return ("", line); }
+    i += 1;
+    // time part: digits:digits:digits[ap]?
+    let time_start = i;
+    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
+    if i == time_start || i >= bytes.len() || bytes[i] != b':' { return ("", line); }
+    i += 1;
+    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
+    if i >= bytes.len() || bytes[i] != b':' { return ("", line); }
+    i += 1;
+    while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
+    // optional a/p suffix
+    if i < bytes.len() && (bytes[i] == b'a' || bytes[i] == b'p') { i += 1; }
+    if i >= bytes.len() || bytes[i] != b' ' { return ("", line); }
+    (&line[..i], &line[i + 1..])
+}
+
+
+/// Convert a CL timestamp string ("M/D/YY H:MM:SSa") to seconds since midnight.
+/// Used to determine whether an equip event and a help block are close in time,
+/// so we can avoid attributing a delayed help response to the wrong last_equipped item.
+fn ts_to_seconds(ts: &str) -> Option<u32> {
+    // Find the space separating date from time
+    let space = ts.rfind(' ')?;
+    let time_part = &ts[space + 1..];
+    let (time_str, pm, has_ampm) = if let Some(t) = time_part.strip_suffix('p') {
+        (t, true, true)
+    } else if let Some(t) = time_part.strip_suffix('a') {
+        (t, false, true)
+    } else {
+        (time_part, false, false) // 24-hour or no indicator
+    };
+    let mut parts = time_str.splitn(3, ':');
+    let h: u32 = parts.next()?.parse().ok()?;
+    let m: u32 = parts.next()?.parse().ok()?;
+    let s: u32 = parts.next()?.parse().ok()?;
+    let h24 = if has_ampm {
+        if h == 12 && !pm { 0 } else if h != 12 && pm { h + 12 } else { h }
+    } else {
+        h
+    };
+    Some(h24 * 3600 + m * 60 + s)
+}
+
+fn extract_item_name(message: &str) -> Option<String> {
+    // 1. /useitem "name" — quoted, highest confidence
+    if let Some(rest) = message.strip_prefix("/useitem \"") {
+        if let Some(end) = rest.find('"') {
+            return Some(rest[..end].to_string());
+        }
+    }
+    // 2. /useitem word — unquoted (e.g., /useitem sungem /add <name>...)
+    if let Some(rest) = message.strip_prefix("/useitem ") {
+        if !rest.starts_with('"') {
+            let word: String = rest.chars().take_while(|c| !c.is_whitespace()).collect();
+            if !word.is_empty() {
+                return Some(word);
+            }
+        }
+    }
+    // 3. * The <name> allows
+    if let Some(rest) = message.strip_prefix("* The ") {
+        if let Some(pos) = rest.find(" allows ") {
+            return Some(rest[..pos].to_string());
+        }
+    }
+    // 4. The <name> allows
+    if let Some(rest) = message.strip_prefix("The ") {
+        if let Some(pos) = rest.find(" allows ") {
+            return Some(rest[..pos].to_string());
+        }
+    }
+    // 5. Your <name> allows you to (e.g., "Your sunstone allows you to think...")
+    if let Some(rest) = message.strip_prefix("Your ") {
+        if let Some(pos) = rest.find(" allows you to") {
+            return Some(rest[..pos].to_string());
+        }
+    }
+    // 6. This <name> helps/allows — skip generic single words
+    if let Some(rest) = message.strip_prefix("This ") {
+        let keyword_pos = rest.find(" helps").or_else(|| rest.find(" allows"));
+        if let Some(pos) = keyword_pos {
+            let candidate = &rest[..pos];
+            if candidate.contains(' ') {
+                return Some(candidate.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// True if `s` looks like `Type <quote>/...` where quote may be ASCII " or Mac Roman curly quotes.
+fn starts_with_type_slash(s: &str) -> bool {
+    // "Type " followed by a quote char then "/"
+    if let Some(rest) = s.strip_prefix("Type ") {
+        // skip optional opening quote: ASCII " or ' or Mac Roman smart quotes U+201C/U+2018
+        // Strip optional opening quote: ASCII ", ASCII ', or Mac Roman smart quotes U+201C/U+2018
+        let inner = rest.trim_start_matches(['"', '\'', '\u{201C}', '\u{2018}']);
+        return inner.starts_with('/');
+    }
+    false
+}
+
+/// True if `message` starts with an unquoted `/useitem <word>` command.
+fn starts_with_useitem_unquoted(message: &str) -> bool {
+    if let Some(rest) = message.strip_prefix("/useitem ") {
+        !rest.starts_with('"') && rest.chars().next().is_some_and(|c| c.is_alphanumeric())
+    } else {
+        false
+    }
+}
+
+fn is_help_trigger(message: &str) -> bool {
+    if starts_with_type_slash(message) { return true; }
+    if message.starts_with("/useitem \"") { return true; }
+    if starts_with_useitem_unquoted(message) { return true; }
+    if message.starts_with("* /") { return true; }
+    if message.starts_with("* The ") && message.contains(" allows ") { return true; }
+    if message.starts_with("The ") && message.contains(" allows ") { return true; }
+    if message.starts_with("Your ") && message.contains(" allows you to") { return true; }
+    if message.starts_with("This ") && (message.contains(" helps") || message.contains(" allows")) { return true; }
+    false
+}
+
+fn is_help_continuation(message: &str) -> bool {
+    if starts_with_type_slash(message) { return true; }
+    if message.starts_with("/useitem \"") { return true; }
+    if starts_with_useitem_unquoted(message) { return true; }
+    if message.starts_with("* /") { return true; }
+    if message.starts_with("* Hot tip:") { return true; }
+    if message.starts_with("* You can currently hold") { return true; }
+    if message.starts_with("* Your ") && message.contains(" can hold") { return true; }
+    // /command : or /command < (catches /THINK <msg>, /examine :, etc.)
+    if let Some(rest) = message.strip_prefix('/') {
+        if rest.chars().next().is_some_and(|c| c.is_alphabetic())
+            && (rest.contains(" :") || rest.contains(" <")) { return true; }
+    }
+    // Type A/B description lines
+    if message.starts_with("This ") && (message.contains(" helps") || message.contains(" allows")) { return true; }
+    if message.starts_with("* The ") && message.contains(" allows ") { return true; }
+    if message.starts_with("The ") && message.contains(" allows ") { return true; }
+    if message.starts_with("Your ") && message.contains(" allows you to") { return true; }
+    false
+}
+
+/// Use `last_equipped` as an item name fallback only when the equip event happened
+/// within 15 seconds of the current help block.  This prevents delayed server
+/// responses (e.g. gossamer's /use ? arriving 30 s after the player switched to
+/// Ethereal Boots) from being attributed to whatever happened to be equipped last.
+fn equip_fallback(
+    last_equipped: &Option<String>,
+    last_equipped_ts: Option<u32>,
+    block_ts: Option<&str>,
+) -> Option<String> {
+    const MAX_SECS: u32 = 15;
+    let equip_secs = last_equipped_ts?;
+    let block_secs = ts_to_seconds(block_ts?)?;
+    // Handle midnight rollover
+    let delta = if block_secs >= equip_secs {
+        block_secs - equip_secs
+    } else {
+        block_secs + 86400 - equip_secs
+    };
+    if delta <= MAX_SECS { last_equipped.clone() } else { None }
+}
+
+fn cmd_useitem_help(folder: &str, recursive: bool) -> amanuensis_core::Result<()> {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+    let dir = Path::new(folder);
+    if !dir.is_dir() {
+        return Err(amanuensis_core::AmanuensisError::Data(
+            format!("'{}' is not a directory", folder)
+        ));
+    }
+
+    let mut files = find_log_files(dir, recursive);
+    files.sort();
+    let total_files = files.len();
+
+    // item_name -> file_path -> set of lines seen for that item in that file.
+    // Using per-file sets lets us apply majority voting: a line belongs to an item
+    // only if it appeared in the majority of files where that item was observed.
+    // This drops contaminated lines (e.g. gossamer lines that sneak into belt of the wild
+    // because of delayed server responses) without any special-casing.
+    let mut item_file_lines: HashMap<String, HashMap<PathBuf, HashSet<String>>> = HashMap::new();
+
+    for path in &files {
+        let bytes = match std::fs::read(path) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        let content = amanuensis_core::encoding::decode_log_bytes(&bytes);
+        let lines: Vec<&str> = content.lines().collect();
+
+        // State machine
+        let mut last_equipped: Option<String> = None;
+        // Timestamp when last_equipped was set — used to reject delayed help responses
+        // that arrive long after the equip event (gossamer contamination prevention).
+        let mut last_equipped_ts: Option<u32> = None;
+        let mut in_block = false;
+        let mut block_item: Option<String> = None;
+        let mut block_lines: Vec<String> = Vec::new();
+        // Timestamp of the most recently added block line ("same-timestamp = same server burst")
+        let mut block_last_ts: Option<String> = None;
+
+        // Trim same-timestamp noise from block edges and record lines into item_file_lines.
+        let finalize = |block_item: &Option<String>,
+                            block_lines: &mut Vec<String>,
+                            item_file_lines: &mut HashMap<String, HashMap<PathBuf, HashSet<String>>>| {
+            if let Some(item) = block_item {
+                // Trim non-help lines from both edges (blank lines, loot messages, etc.
+                // that share a timestamp with the help burst but aren't part of it).
+                while let Some(last) = block_lines.last() {
+                    if is_help_trigger(last) || is_help_continuation(last) { break; }
+                    block_lines.pop();
+                }
+                while !block_lines.is_empty() {
+                    if is_help_trigger(&block_lines[0]) || is_help_continuation(&block_lines[0]) { break; }
+                    block_lines.remove(0);
+                }
+                if !block_lines.is_empty() {
+                    let file_set = item_file_lines
+                        .entry(item.clone()).or_default()
+                        .entry(path.clone()).or_default();
+                    for line in block_lines.drain(..) {
+                        file_set.insert(line);
+                    }
+                }
+            }
+            block_lines.clear();
+        };
+
+        let mut i = 0;
+        while i < lines.len() {
+            let raw = lines[i];
+            let (ts, message) = split_timestamp(raw);
+            let cur_ts = if ts.is_empty() { None } else { Some(ts) };
+
+            // Equip pattern always takes priority over same-timestamp logic
+            if let Some(item_name) = message.strip_prefix("You equip your ").and_then(|s| s.strip_suffix('.')) {
+                finalize(&block_item, &mut block_lines, &mut item_file_lines);
                 in_block = false;
                 block_item = None;
                 block_last_ts = None;
                 last_equipped = Some(item_name.to_string());
                 last_equipped_ts = cur_ts.and_then(ts_to_seconds);
                 i += 1;
-                continue;
+                continue;
+            }
+
+            if in_block {
+                // CL delivers a complete help block in a single server burst — all lines share
+                // the same timestamp.  Accept same-timestamp lines unconditionally so prose
+                // continuation lines (e.g. "Do not include spaces…") don't break the block.
+                let same_ts = cur_ts.is_some() && cur_ts == block_last_ts.as_deref();
+                if same_ts {
+                    // Same burst: always include regardless of content
+                    if let Some(name) = extract_item_name(message) {
+                        block_item = Some(name);
+                    }
+                    block_lines.push(normalize_quotes(message));
+                    i += 1;
+                } else if is_help_trigger(message) {
+                    // New server response with a trigger: end the current block and re-process
+                    // this line as the start of a new block (don't advance i).
+                    if block_item.is_some() { last_equipped = None; last_equipped_ts = None; }
+                    finalize(&block_item, &mut block_lines, &mut item_file_lines);
+                    in_block = false;
+                    block_item = None;
+                    block_last_ts = None;
+                } else if is_help_continuation(message) {
+                    // A continuation-only line at a new timestamp (e.g. manually queued command)
+                    if let Some(name) = extract_item_name(message) {
+                        block_item = Some(name);
+                    } else if block_item.is_none() {
+                        block_item = equip_fallback(&last_equipped, last_equipped_ts, cur_ts);
+                    }
+                    block_lines.push(normalize_quotes(message));
+                    block_last_ts = cur_ts.map(|s| s.to_string());
+                    i += 1;
+                } else {
+                    // End of block; reset last_equipped so delayed responses from a prior item
+                    // can't pollute the next block.
+                    if block_item.is_some() { last_equipped = None; }
+                    finalize(&block_item, &mut block_lines, &mut item_file_lines);
+                    in_block = false;
+                    block_item = None;
+                    block_last_ts = None;
+                    // Re-process this line without advancing i
+                }
+            } else if is_help_trigger(message) {
+                in_block = true;
+                block_item = extract_item_name(message)
+                    .or_else(|| equip_fallback(&last_equipped, last_equipped_ts, cur_ts));
+                block_lines.push(normalize_quotes(message));
+                block_last_ts = cur_ts.map(|s| s.to_string());
+                i += 1;
+            } else {
+                i += 1;
+            }
+        }
+        // Finalize any open block at EOF
+        finalize(&block_item, &mut block_lines, &mut item_file_lines);
+    }
+
+    // Majority voting: for each item, keep only lines that appeared in at least half
+    // of the files where that item was observed.  This drops contaminated lines that
+    // snuck in from delayed server responses in a small fraction of sessions.
+    // Then deduplicate items whose canonical line sets are identical (misattributed
+    // blocks) by keeping the candidate seen in the most files.
+    let mut items: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut item_file_count: HashMap<String, usize> = HashMap::new();
+
+    for (item, file_to_lines) in &item_file_lines {
+        let file_count = file_to_lines.len();
+        item_file_count.insert(item.clone(), file_count);
+
+        let threshold = file_count.div_ceil(2); // strict majority
+        let mut line_counts: HashMap<&str, usize> = HashMap::new();
+        for lines in file_to_lines.values() {
+            for line in lines {
+                *line_counts.entry(line.as_str()).or_insert(0) += 1;
+            }
+        }
+        let canonical: BTreeSet<String> = line_counts
+            .into_iter()
+            .filter(|(_, count)| *count >= threshold)
+            .map(|(line, _)| line.to_string())
+            .collect();
+        if !canonical.is_empty() {
+            items.insert(item.clone(), canonical);
+        }
+    }
+
+    // Fuzzy dedup: items whose canonical line sets have ≥50% Jaccard similarity are
+    // treated as the same help block misattributed to different names (e.g. gossamer's
+    // "This weapon helps…" block appearing under "pair of Ethereal Boots" because boots
+    // happened to be last_equipped when the delayed response arrived).
+    // Process in descending file-count order so the most-seen name is always kept.
+    {
+        let mut item_names: Vec<String> = items.keys().cloned().collect();
+        item_names.sort_by_key(|n| std::cmp::Reverse(item_file_count.get(n).copied().unwrap_or(0)));
+
+        let mut to_remove: HashSet<String> = HashSet::new();
+        for i in 0..item_names.len() {
+            let a = &item_names[i];
+            if to_remove.contains(a) { continue; }
+            let lines_a = items[a].clone();
+            for b in item_names.iter().skip(i + 1) {
+                if to_remove.contains(b) { continue; }
+                let lines_b = &items[b];
+                let intersection = lines_a.intersection(lines_b).count();
+                if intersection == 0 { continue; }
+                let union = lines_a.union(lines_b).count();
+                if intersection * 2 >= union {
+                    // Jaccard >= 0.5 — b has fewer or equal files, drop it
+                    to_remove.insert(b.clone());
+                }
+            }
+        }
+        for name in &to_remove { items.remove(name); }
+    }
+
+    println!("Found {} item(s) across {} files.", items.len(), total_files);
+    for (item, line_set) in &items {
+        println!();
+        println!("=== {} ===", item);
+        for line in line_set {
+            println!("  {}", line);
+        }
+    }
+
+    Ok(())
+}
+
+// ── update-bestiary / bestiary ────────────────────────────────────────────────
+
+fn cmd_update_bestiary(
+    xml_path: &Path,
+    aliases_override: Option<&Path>,
+    output_override: Option<&Path>,
+    dry_run: bool,
+) -> amanuensis_core::Result<()> {
+    use amanuensis_core::data::{parse_bestiary_xml, BestiaryFile, CreatureDb};
+
+    let xml = std::fs::read(xml_path)?;
+    let mut entries = parse_bestiary_xml(&xml)?;
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    let version = version_from_filename(xml_path);
+
+    let alias_path = aliases_override.map(PathBuf::from).unwrap_or_else(default_alias_path);
+    let alias_bytes = std::fs::read(&alias_path)?;
+
+    // Validate aliases against the new entries by round-tripping through CreatureDb.
+    let file = BestiaryFile { version: version.clone(), entries };
+    let bestiary_bytes = serde_json::to_vec(&file)?;
+    let db = CreatureDb::from_json_bytes(&bestiary_bytes, &alias_bytes)?;
+
+    println!("Bestiary: {} entries (version {})", db.len(), db.bestiary_version());
+    println!("Aliases:  {} loaded from {}", count_aliases(&alias_bytes)?, alias_path.display());
+
+    if dry_run {
+        println!("(dry-run; not writing)");
+        return Ok(());
+    }
+
+    let out_path = output_override.map(PathBuf::from).unwrap_or_else(default_bestiary_path);
+    let pretty = serde_json::to_string_pretty(&file)?;
+    std::fs::write(&out_path, pretty)?;
+    println!("Wrote {}", out_path.display());
+    println!(
+        "Bestiary updated to version {}. Existing databases should run 'amanuensis scan --force <folder>' to refresh kill values.",
+        version
+    );
+    Ok(())
+}
+
+fn cmd_bestiary(name: &str, format: OutputFormat) -> amanuensis_core::Result<()> {
+    use amanuensis_core::data::{canonical_rarity, EntrySource};
+    let db = creature_db()?;
+    match db.get_entry_with_source(name) {
+        None => {
+            eprintln!("No bestiary entry for '{}'", name);
+            std::process::exit(1);
+        }
+        Some((entry, _)) if format.is_json() => print_json(entry)?,
+        Some((entry, source)) => {
+            let src = match source {
+                EntrySource::Bestiary => "bestiary",
+                EntrySource::Alias => "alias → bestiary",
+                EntrySource::InlineAlias => "inline alias",
+            };
+            println!("Name:           {}", entry.name);
+            println!("Source:         {} (bestiary v{})", src, db.bestiary_version());
+            if let Some(f) = &entry.family { println!("Family:         {}", db.canonical_family(f)); }
+            println!("Rarity:         {}", canonical_rarity(entry.rarity.as_deref()).as_label());
+            println!("Exp/taxidermy:  {}", entry.exp_taxidermy);
+            if let Some(l) = &entry.location { println!("Location:       {}", l); }
+            if let Some(i) = &entry.information { println!("Information:    {}", i); }
+            if let Some(d) = &entry.difficulty { println!("Difficulty:     {}", d); }
+            let stats = [
+                ("Attack", entry.attack, entry.attack_measured),
+                ("Defense", entry.defense, entry.defense_measured),
+                ("Damage", entry.damage, entry.damage_measured),
+                ("Health", entry.health, entry.health_measured),
+            ];
+            for (label, val, measured) in stats {
+                if let Some(v) = val {
+                    let suffix = if measured { " (measured)" } else { "" };
+                    println!("{:14}  {}{}", format!("{}:", label), v, suffix);
+                }
+            }
+            if let Some(l) = entry.luck_hits { println!("Luck hits:      {}%", l); }
+            if let Some(fps) = entry.frames_per_swing { println!("Frames/swing:   {}", fps); }
+            if let Some(w) = &entry.worth_range { println!("Worth range:    {}", w); }
+            if entry.is_seasonal { println!("Seasonal:       yes"); }
+        }
+    }
+    Ok(())
+}
+
+fn version_from_filename(path: &Path) -> String {
+    path.file_name()
+        .and_then(|s| s.to_str())
+        .and_then(|s| s.strip_prefix("bestiary_"))
+        .and_then(|s| s.split('_').next())
+        .unwrap_or("00000000")
+        .to_string()
+}
+
+fn default_alias_path() -> PathBuf {
+    PathBuf::from("crates/amanuensis-core/data/bestiary_aliases.json")
+}
+
+fn default_bestiary_path() -> PathBuf {
+    PathBuf::from("crates/amanuensis-core/data/bestiary.json")
+}
+
+fn count_aliases(bytes: &[u8]) -> amanuensis_core::Result<usize> {
+    let parsed: serde_json::Value = serde_json::from_slice(bytes)?;
+    Ok(parsed.as_array().map(|a| a.len()).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    /// clap's own structural consistency check — catches conflicting args, bad defaults,
+    /// duplicate names, etc. across the whole Commands enum.
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_update_command() {
+        let cli = Cli::try_parse_from(["amanuensis", "update", "logs1", "logs2", "--recursive", "--no-index"]).unwrap();
+        match cli.command {
+            Commands::Data(DataCommands::Update { folders, recursive, no_index, index, nice, hooks, witnessed_falls, unknown_lines, chat }) => {
+                assert_eq!(folders.len(), 2);
+                assert!(!witnessed_falls && !unknown_lines && !chat);
+                assert!(!index);
+                assert!(recursive);
+                assert!(no_index);
+                assert!(!nice);
+                assert_eq!(hooks, None);
+            }
+            _ => panic!("expected Update"),
+        }
+    }
+
+    #[test]
+    fn parses_pending_command_with_list() {
+        let cli = Cli::try_parse_from(["amanuensis", "pending", "logs", "--list"]).unwrap();
+        match cli.command {
+            Commands::Data(DataCommands::Pending { folders, recursive, list }) => {
+                assert_eq!(folders, vec![PathBuf::from("logs")]);
+                assert!(!recursive);
+                assert!(list);
+            }
+            _ => panic!("expected Pending"),
+        }
+    }
+
+    #[test]
+    fn update_and_pending_folders_fall_back_to_config() {
+        // Without folders on the command line, the config file's `log_folder` is used.
+        let Commands::Data(DataCommands::Update { folders, .. }) = Cli::try_parse_from(["amanuensis", "update"]).unwrap().command else {
+            panic!("expected Update");
+        };
+        assert!(Config::default().log_folders(folders.clone()).is_err());
+        let config = Config { log_folder: Some(PathBuf::from("logs")), ..Config::default() };
+        assert_eq!(config.log_folders(folders).unwrap(), vec![PathBuf::from("logs")]);
+        assert!(Cli::try_parse_from(["amanuensis", "pending"]).is_ok());
+    }
+
+    #[test]
+    fn missing_input_file_is_unreadable_not_bad_data() {
+        let err = read_file_or_stdin(Path::new("/nonexistent/ranks.csv")).unwrap_err();
+        assert_eq!(err.kind(), "file_unreadable");
+    }
+
+    #[test]
+    fn config_file_sets_defaults_under_flags() {
+        let config = Config::from_toml_str(
+            "db = \"stats.db\"\nlog_folder = \"/logs\"\nindex = false\nformat = \"json\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.format, Some(OutputFormat::Json));
+        assert!(config.skip_index(false, false, true));
+        assert!(!config.skip_index(false, true, true));
+        // Without a config value the database setting decides, and the flags still win.
+        assert!(Config::default().skip_index(false, false, false));
+        assert!(!Config::default().skip_index(false, true, false));
+        assert!(!Config::default().skip_index(false, false, true));
+        assert!(Config::default().skip_index(true, false, true));
+        assert!(Config::from_toml_str("databse = \"typo.db\"").is_err());
+
+        let cli = Cli::try_parse_from(["amanuensis", "characters"]).unwrap();
+        assert_eq!(resolve_db_path(&cli, &config).unwrap(), "stats.db");
+        let cli = Cli::try_parse_from(["amanuensis", "--db", "other.db", "characters"]).unwrap();
+        assert_eq!(resolve_db_path(&cli, &config).unwrap(), "other.db");
+        let default = gui_db_path().unwrap().to_string_lossy().into_owned();
+        assert!(default.ends_with("amanuensis.db") && default.contains("com.dfsw.Amanuensis"));
+        let cli = Cli::try_parse_from(["amanuensis", "characters"]).unwrap();
+        assert_eq!(resolve_db_path(&cli, &Config::default()).unwrap(), default);
+        let cli = Cli::try_parse_from(["amanuensis", "--gui-db", "characters"]).unwrap();
+        assert_eq!(resolve_db_path(&cli, &config).unwrap(), default);
+        assert!(Cli::try_parse_from(["amanuensis", "scan", "--index", "--no-index"]).is_err());
+
+        let config = Config::from_toml_str("trainer_db = \"trainers.csv\"\n").unwrap();
+        assert_eq!(config.trainer_db, Some(PathBuf::from("trainers.csv")));
+        let cli = Cli::try_parse_from(["amanuensis", "--trainer-db", "new.csv", "scan"]).unwrap();
+        assert_eq!(cli.trainer_db, Some(PathBuf::from("new.csv")));
+    }
+
+    #[test]
+    fn parses_set_trainer_note_with_and_without_note() {
+        let with = Cli::try_parse_from(["amanuensis", "set-trainer-note", "Gandor", "Detha", "a note"]).unwrap();
+        match with.command {
+            Commands::Ranks(RankCommands::SetTrainerNote { name, trainer, note }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(trainer, "Detha");
+                assert_eq!(note.as_deref(), Some("a note"));
+            }
+            _ => panic!("expected SetTrainerNote"),
+        }
+        let without = Cli::try_parse_from(["amanuensis", "set-trainer-note", "Gandor", "Detha"]).unwrap();
+        match without.command {
+            Commands::Ranks(RankCommands::SetTrainerNote { note, .. }) => assert!(note.is_none()),
+            _ => panic!("expected SetTrainerNote"),
+        }
+    }
+
+    #[test]
+    fn parses_clear_and_reset_logs_flags() {
+        match Cli::try_parse_from(["amanuensis", "clear-rank-overrides", "--yes"]).unwrap().command {
+            Commands::Ranks(RankCommands::ClearRankOverrides { yes }) => assert!(yes),
+            _ => panic!("expected ClearRankOverrides"),
+        }
+        match Cli::try_parse_from(["amanuensis", "reset-logs"]).unwrap().command {
+            Commands::Data(DataCommands::ResetLogs { yes }) => assert!(!yes),
+            _ => panic!("expected ResetLogs"),
+        }
+    }
+
+    #[test]
+    fn parses_delete_character_command() {
+        match Cli::try_parse_from(["amanuensis", "delete-character", "Mistake", "--yes"]).unwrap().command {
+            Commands::Data(DataCommands::DeleteCharacter { name, yes }) => {
+                assert_eq!(name, "Mistake");
+                assert!(yes);
             }
+            _ => panic!("expected DeleteCharacter"),
+        }
+    }
 
-            if in_block {
-                // CL delivers a complete help block in a single server burst — all lines share
-                // the same timestamp.  Accept same-timestamp lines unconditionally so prose
-                // continuation lines (e.g. "Do not include spaces…") don't break the block.
-                let same_ts = cur_ts.is_some() && cur_ts == block_last_ts.as_deref();
-                if same_ts {
-                    // Same burst: always include regardless of content
-                    if let Some(name) = extract_item_name(message) {
-                        block_item = Some(name);
-                    }
-                    block_lines.push(normalize_quotes(message));
-                    i += 1;
-                } else if is_help_trigger(message) {
-                    // New server response with a trigger: end the current block and re-process
-                    // this line as the start of a new block (don't advance i).
-                    if block_item.is_some() { last_equipped = None; last_equipped_ts = None; }
-                    finalize(&block_item, &mut block_lines, &mut item_file_lines);
-                    in_block = false;
-                    block_item = None;
-                    block_last_ts = None;
-                } else if is_help_continuation(message) {
-                    // A continuation-only line at a new timestamp (e.g. manually queued command)
-                    if let Some(name) = extract_item_name(message) {
-                        block_item = Some(name);
-                    } else if block_item.is_none() {
-                        block_item = equip_fallback(&last_equipped, last_equipped_ts, cur_ts);
-                    }
-                    block_lines.push(normalize_quotes(message));
-                    block_last_ts = cur_ts.map(|s| s.to_string());
-                    i += 1;
-                } else {
-                    // End of block; reset last_equipped so delayed responses from a prior item
-                    // can't pollute the next block.
-                    if block_item.is_some() { last_equipped = None; }
-                    finalize(&block_item, &mut block_lines, &mut item_file_lines);
-                    in_block = false;
-                    block_item = None;
-                    block_last_ts = None;
-                    // Re-process this line without advancing i
-                }
-            } else if is_help_trigger(message) {
-                in_block = true;
-                block_item = extract_item_name(message)
-                    .or_else(|| equip_fallback(&last_equipped, last_equipped_ts, cur_ts));
-                block_lines.push(normalize_quotes(message));
-                block_last_ts = cur_ts.map(|s| s.to_string());
-                i += 1;
-            } else {
-                i += 1;
+    #[test]
+    fn parses_unscan_command() {
+        match Cli::try_parse_from(["amanuensis", "unscan", "logs/CL Log 2024:01:01.txt"]).unwrap().command {
+            Commands::Data(DataCommands::Unscan { file }) => assert_eq!(file, PathBuf::from("logs/CL Log 2024:01:01.txt")),
+            _ => panic!("expected Unscan"),
+        }
+    }
+
+    #[test]
+    fn parses_compare_archives_command() {
+        let cli = Cli::try_parse_from(["amanuensis", "compare-archives", "mac", "pc"]).unwrap();
+        match cli.command {
+            Commands::Data(DataCommands::CompareArchives { folder_a, folder_b }) => {
+                assert_eq!(folder_a, PathBuf::from("mac"));
+                assert_eq!(folder_b, PathBuf::from("pc"));
+            }
+            _ => panic!("expected CompareArchives"),
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "compare-archives", "mac"]).is_err());
+    }
+
+    #[test]
+    fn parses_export_defaults_and_flags() {
+        let cli = Cli::try_parse_from(["amanuensis", "export"]).unwrap();
+        assert!(cli.format.is_none());
+        match cli.command {
+            Commands::Share(ShareCommands::Export { table, character, output, anonymize }) => {
+                assert_eq!(table, "all");
+                assert!(character.is_none() && !anonymize);
+                assert_eq!(output, PathBuf::from("."));
+            }
+            _ => panic!("expected Export"),
+        }
+        let cli = Cli::try_parse_from([
+            "amanuensis", "export", "--table", "kills", "--format", "csv",
+            "--character", "Gandor", "-o", "out", "--anonymize",
+        ]).unwrap();
+        assert_eq!(cli.format, Some(OutputFormat::Csv));
+        match cli.command {
+            Commands::Share(ShareCommands::Export { table, character, output, anonymize }) => {
+                assert_eq!(table, "kills");
+                assert!(anonymize);
+                assert_eq!(character.as_deref(), Some("Gandor"));
+                assert_eq!(output, PathBuf::from("out"));
+            }
+            _ => panic!("expected Export"),
+        }
+    }
+
+    #[test]
+    fn format_is_global_and_checked_per_command() {
+        for args in [
+            &["amanuensis", "--format", "json", "characters"][..],
+            &["amanuensis", "characters", "--format", "json"][..],
+        ] {
+            assert_eq!(Cli::try_parse_from(args).unwrap().format, Some(OutputFormat::Json));
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "characters", "--format", "xml"]).is_err());
+
+        let command = |args: &[&str]| Cli::try_parse_from(args).unwrap().command;
+        assert!(check_format(&command(&["amanuensis", "summary", "Gandor"]), OutputFormat::Json).is_ok());
+        assert!(check_format(&command(&["amanuensis", "kills", "Gandor"]), OutputFormat::Csv).is_ok());
+        assert!(check_format(&command(&["amanuensis", "catalog-diff"]), OutputFormat::Csv).is_ok());
+        assert!(check_format(&command(&["amanuensis", "trainers", "Gandor"]), OutputFormat::Csv).is_err());
+        assert!(check_format(&command(&["amanuensis", "export"]), OutputFormat::Table).is_err());
+        assert!(check_format(&command(&["amanuensis", "useitem-help", "logs"]), OutputFormat::Json).is_err());
+
+        // The --format help names every command check_format accepts csv for.
+        let cli = Cli::command();
+        let help = cli.get_arguments().find(|a| a.get_id() == "format").unwrap().get_help().unwrap().to_string();
+        for (name, args) in [
+            ("kills", &["amanuensis", "kills", "Gandor"][..]),
+            ("frequency", &["amanuensis", "frequency", "Gandor"][..]),
+            ("export", &["amanuensis", "export"][..]),
+            ("catalog-diff", &["amanuensis", "catalog-diff"][..]),
+        ] {
+            assert!(check_format(&command(args), OutputFormat::Csv).is_ok(), "{name}");
+            assert!(help.contains(name), "--format help omits {name}: {help}");
+        }
+    }
+
+    #[test]
+    fn parses_frequency_flags() {
+        let cli = Cli::try_parse_from([
+            "amanuensis", "frequency", "Gandor",
+            "--bin", "2h", "--solo", "--by-verb", "--format", "csv", "--limit", "5",
+        ]).unwrap();
+        assert_eq!(cli.format, Some(OutputFormat::Csv));
+        match cli.command {
+            Commands::Stats(StatsCommands::Frequency { name, bin, solo, by_verb, limit }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(bin, "2h");
+                assert!(solo);
+                assert!(by_verb);
+                assert_eq!(limit, Some(5));
+            }
+            _ => panic!("expected Frequency"),
+        }
+    }
+
+    #[test]
+    fn parses_progression_with_defaults_and_overrides() {
+        match Cli::try_parse_from(["amanuensis", "progression", "Gandor"]).unwrap().command {
+            Commands::Stats(StatsCommands::Progression { name, min_kills, max_killed_by_ratio }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(min_kills, 50);
+                assert_eq!(max_killed_by_ratio, 0.1);
+            }
+            _ => panic!("expected Progression"),
+        }
+        let cli = Cli::try_parse_from([
+            "amanuensis", "progression", "Gandor", "--min-kills", "100", "--max-killed-by-ratio", "0.05",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Stats(StatsCommands::Progression { min_kills, max_killed_by_ratio, .. }) => {
+                assert_eq!(min_kills, 100);
+                assert_eq!(max_killed_by_ratio, 0.05);
+            }
+            _ => panic!("expected Progression"),
+        }
+    }
+
+    #[test]
+    fn parses_watch_command() {
+        match Cli::try_parse_from(["amanuensis", "watch", "logs", "--hooks", "hooks.json", "--nice"]).unwrap().command {
+            Commands::Data(DataCommands::Watch { folders, recursive, interval, no_index, index, nice, hooks, ws, notify }) => {
+                assert_eq!(folders, vec![PathBuf::from("logs")]);
+                assert_eq!(ws, None);
+                assert_eq!(notify, None);
+                assert!(!index);
+                assert!(!recursive);
+                assert_eq!(interval, 2);
+                assert!(!no_index);
+                assert!(nice);
+                assert_eq!(hooks, Some(PathBuf::from("hooks.json")));
+            }
+            _ => panic!("expected Watch"),
+        }
+    }
+
+    #[test]
+    fn parses_hunt_report_command() {
+        match Cli::try_parse_from(["amanuensis", "hunt-report", "Gandor", "--session", "12"]).unwrap().command {
+            Commands::Stats(StatsCommands::HuntReport { name, session, out }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(session, 12);
+                assert_eq!(out, None);
+            }
+            _ => panic!("expected HuntReport"),
+        }
+    }
+
+    #[test]
+    fn parses_correspondents_command() {
+        match Cli::try_parse_from(["amanuensis", "correspondents", "Gandor", "--limit", "5"]).unwrap().command {
+            Commands::Stats(StatsCommands::Correspondents { name, limit }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(limit, 5);
+            }
+            _ => panic!("expected Correspondents"),
+        }
+    }
+
+    #[test]
+    fn parses_assists_command() {
+        match Cli::try_parse_from(["amanuensis", "assists", "Gandor", "Orga Anger"]).unwrap().command {
+            Commands::Stats(StatsCommands::Assists { name, creature }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(creature.as_deref(), Some("Orga Anger"));
+            }
+            _ => panic!("expected Assists"),
+        }
+    }
+
+    #[test]
+    fn parses_milestones_command() {
+        match Cli::try_parse_from(["amanuensis", "milestones", "Gandor"]).unwrap().command {
+            Commands::Stats(StatsCommands::Milestones { name }) => assert_eq!(name, "Gandor"),
+            _ => panic!("expected Milestones"),
+        }
+    }
+
+    #[test]
+    fn parses_boosts_command() {
+        match Cli::try_parse_from(["amanuensis", "boosts", "Gandor", "--windows"]).unwrap().command {
+            Commands::Stats(StatsCommands::Boosts { name, windows }) => {
+                assert_eq!(name, "Gandor");
+                assert!(windows);
+            }
+            _ => panic!("expected Boosts"),
+        }
+    }
+
+    #[test]
+    fn parses_zones_command() {
+        match Cli::try_parse_from(["amanuensis", "zones", "Gandor", "--top", "5"]).unwrap().command {
+            Commands::Stats(StatsCommands::Zones { name, top }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(top, 5);
+            }
+            _ => panic!("expected Zones"),
+        }
+    }
+
+    #[test]
+    fn parses_widget_command() {
+        match Cli::try_parse_from(["amanuensis", "widget", "Gandor", "--out", "widget.json"]).unwrap().command {
+            Commands::Share(ShareCommands::Widget { name, out }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(out, Some(PathBuf::from("widget.json")));
+            }
+            _ => panic!("expected Widget"),
+        }
+    }
+
+    #[test]
+    fn parses_portrait_and_roster_commands() {
+        match Cli::try_parse_from(["amanuensis", "portrait", "Gandor", "--set", "gandor.png"]).unwrap().command {
+            Commands::Share(ShareCommands::Portrait { name, set, clear }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(set, Some(PathBuf::from("gandor.png")));
+                assert!(!clear);
+            }
+            _ => panic!("expected Portrait"),
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "portrait", "Gandor", "--set", "a.png", "--clear"]).is_err());
+        match Cli::try_parse_from(["amanuensis", "roster", "site", "--html-only"]).unwrap().command {
+            Commands::Share(ShareCommands::Roster { folder, html_only, json_only }) => {
+                assert_eq!(folder, PathBuf::from("site"));
+                assert!(html_only && !json_only);
+            }
+            _ => panic!("expected Roster"),
+        }
+    }
+
+    #[test]
+    fn parses_sessions_command() {
+        match Cli::try_parse_from(["amanuensis", "sessions", "Gandor", "--limit", "5"]).unwrap().command {
+            Commands::Stats(StatsCommands::Sessions { name, limit }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(limit, 5);
+            }
+            _ => panic!("expected Sessions"),
+        }
+    }
+
+    #[test]
+    fn parses_catalog_diff_and_creature_db() {
+        let cli = Cli::try_parse_from(["amanuensis", "--creature-db", "creatures.csv", "catalog-diff"]).unwrap();
+        assert_eq!(cli.creature_db, Some(PathBuf::from("creatures.csv")));
+        match cli.command {
+            Commands::Catalog(CatalogCommands::CatalogDiff { character }) => assert!(character.is_none()),
+            _ => panic!("expected CatalogDiff"),
+        }
+    }
+
+    #[test]
+    fn parses_creature_catalog_command() {
+        match Cli::try_parse_from(["amanuensis", "creature-catalog", "--character", "Gandor"]).unwrap().command {
+            Commands::Catalog(CatalogCommands::CreatureCatalog { character }) => assert_eq!(character.as_deref(), Some("Gandor")),
+            _ => panic!("expected CreatureCatalog"),
+        }
+        match Cli::try_parse_from(["amanuensis", "creature-catalog"]).unwrap().command {
+            Commands::Catalog(CatalogCommands::CreatureCatalog { character }) => assert!(character.is_none()),
+            _ => panic!("expected CreatureCatalog"),
+        }
+    }
+
+    #[test]
+    fn parses_trainer_history_command() {
+        match Cli::try_parse_from(["amanuensis", "trainer-history", "Gandor", "Histia"]).unwrap().command {
+            Commands::Stats(StatsCommands::TrainerHistory { name, trainer }) => {
+                assert_eq!(name, "Gandor");
+                assert_eq!(trainer, "Histia");
+            }
+            _ => panic!("expected TrainerHistory"),
+        }
+    }
+
+    #[test]
+    fn parses_eta_command() {
+        match Cli::try_parse_from(["amanuensis", "eta", "Gandor", "Histia", "--target", "300"]).unwrap().command {
+            Commands::Stats(StatsCommands::Eta { name, trainer, target }) => {
+                assert_eq!((name.as_str(), trainer.as_str(), target), ("Gandor", "Histia", 300));
+            }
+            _ => panic!("expected Eta"),
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "eta", "Gandor", "Histia"]).is_err());
+    }
+
+    #[test]
+    fn parses_digest_options() {
+        let cli = Cli::try_parse_from(["amanuensis", "digest"]).unwrap();
+        match cli.command {
+            Commands::Share(ShareCommands::Digest { since, out, webhook, save_webhook, clear_webhook }) => {
+                assert_eq!((since.as_str(), out, webhook), ("last", None, None));
+                assert!(!save_webhook && !clear_webhook);
+            }
+            _ => panic!("expected Digest"),
+        }
+        let cli = Cli::try_parse_from([
+            "amanuensis", "digest", "--since", "2024-03-01", "--webhook", "https://example.com/hook", "--save-webhook",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Share(ShareCommands::Digest { since, webhook, save_webhook, .. }) => {
+                assert_eq!(since, "2024-03-01");
+                assert_eq!(webhook.as_deref(), Some("https://example.com/hook"));
+                assert!(save_webhook);
+            }
+            _ => panic!("expected Digest"),
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "digest", "--save-webhook"]).is_err());
+        assert!(Cli::try_parse_from(["amanuensis", "digest", "--clear-webhook", "--webhook", "x"]).is_err());
+    }
+
+    #[test]
+    fn parses_index_retention_options() {
+        let cli = Cli::try_parse_from(["amanuensis", "index-retention", "--years", "3", "--events-only", "true"]).unwrap();
+        match cli.command {
+            Commands::Text(TextCommands::IndexRetention { years, all_years, events_only }) => {
+                assert_eq!((years, all_years, events_only), (Some(3), false, Some(true)));
+            }
+            _ => panic!("expected IndexRetention"),
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "index-retention", "--years", "3", "--all-years"]).is_err());
+        assert!(matches!(
+            Cli::try_parse_from(["amanuensis", "prune-index"]).unwrap().command,
+            Commands::Text(TextCommands::PruneIndex)
+        ));
+        assert!(matches!(
+            Cli::try_parse_from(["amanuensis", "index", "rebuild"]).unwrap().command,
+            Commands::Text(TextCommands::Index { action: IndexAction::Rebuild })
+        ));
+        assert!(Cli::try_parse_from(["amanuensis", "index"]).is_err());
+    }
+
+    #[test]
+    fn parses_path_alias_options() {
+        let cli = Cli::try_parse_from([
+            "amanuensis", "path-aliases", "--add", "//nas/games=games", "--add", "/Volumes/games=games", "--remove", "x",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Data(DataCommands::PathAliases { add, remove, clear }) => {
+                assert_eq!(add, vec!["//nas/games=games", "/Volumes/games=games"]);
+                assert_eq!((remove, clear), (vec!["x".to_string()], false));
+            }
+            _ => panic!("expected PathAliases"),
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "path-aliases", "--clear", "--add", "a=b"]).is_err());
+    }
+
+    #[test]
+    fn parses_creature_names_options() {
+        match Cli::try_parse_from(["amanuensis", "creature-names", "--load", "rules.json", "--enable"]).unwrap().command {
+            Commands::Data(DataCommands::CreatureNames { load, enable, disable, clear, preview }) => {
+                assert_eq!(load, Some(PathBuf::from("rules.json")));
+                assert_eq!((enable, disable, clear, preview), (true, false, false, false));
+            }
+            _ => panic!("expected CreatureNames"),
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "creature-names", "--enable", "--disable"]).is_err());
+        assert!(Cli::try_parse_from(["amanuensis", "creature-names", "--preview", "--enable"]).is_err());
+    }
+
+    #[test]
+    fn parses_serve_options() {
+        match Cli::try_parse_from(["amanuensis", "serve"]).unwrap().command {
+            Commands::Share(ShareCommands::Serve { port, bind }) => assert_eq!((port, bind.as_str()), (8080, "127.0.0.1")),
+            _ => panic!("expected Serve"),
+        }
+        let Commands::Share(ShareCommands::Serve { port, bind }) =
+            Cli::try_parse_from(["amanuensis", "serve", "--port", "9000", "--bind", "0.0.0.0"]).unwrap().command
+        else {
+            panic!("expected Serve");
+        };
+        assert_eq!((port, bind.as_str()), (9000, "0.0.0.0"));
+    }
+
+    #[test]
+    fn parses_daemon_options() {
+        let cli = Cli::try_parse_from([
+            "amanuensis", "daemon", "--interval", "1h", "--folder", "/a", "--folder", "/b", "--nice",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Data(DataCommands::Daemon { interval, folders, nice, .. }) => {
+                assert_eq!(interval, std::time::Duration::from_secs(3600));
+                assert_eq!(folders, vec![PathBuf::from("/a"), PathBuf::from("/b")]);
+                assert!(nice);
+            }
+            _ => panic!("expected Daemon"),
+        }
+        let Commands::Data(DataCommands::Daemon { interval, folders, .. }) = Cli::try_parse_from(["amanuensis", "daemon"]).unwrap().command else {
+            panic!("expected Daemon");
+        };
+        assert_eq!((interval.as_secs(), folders.len()), (900, 0));
+
+        assert_eq!(parse_interval("90").unwrap().as_secs(), 90);
+        assert_eq!(parse_interval("2d").unwrap().as_secs(), 172_800);
+        for bad in ["0m", "15x", "m", ""] {
+            assert!(parse_interval(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn parses_scan_emit_events() {
+        let cli = Cli::try_parse_from(["amanuensis", "scan", "--emit-events", "-", "/logs"]).unwrap();
+        match cli.command {
+            Commands::Data(DataCommands::Scan { folder, emit_events, .. }) => {
+                assert_eq!(folder, Some(PathBuf::from("/logs")));
+                assert_eq!(emit_events, Some(PathBuf::from("-")));
+            }
+            _ => panic!("expected Scan"),
+        }
+        let Commands::Data(DataCommands::Scan { emit_events, .. }) = Cli::try_parse_from(["amanuensis", "scan"]).unwrap().command else {
+            panic!("expected Scan");
+        };
+        assert!(emit_events.is_none());
+    }
+
+    #[test]
+    fn parses_snapshots_command() {
+        match Cli::try_parse_from(["amanuensis", "snapshots", "Gandor", "--since", "2024-03-01"]).unwrap().command {
+            Commands::Stats(StatsCommands::Snapshots { name, since }) => {
+                assert_eq!((name.as_str(), since.as_deref()), ("Gandor", Some("2024-03-01")))
             }
+            _ => panic!("expected Snapshots"),
         }
-        // Finalize any open block at EOF
-        finalize(&block_item, &mut block_lines, &mut item_file_lines);
     }
 
-    // Majority voting: for each item, keep only lines that appeared in at least half
-    // of the files where that item was observed.  This drops contaminated lines that
-    // snuck in from delayed server responses in a small fraction of sessions.
-    // Then deduplicate items whose canonical line sets are identical (misattributed
-    // blocks) by keeping the candidate seen in the most files.
-    let mut items: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
-    let mut item_file_count: HashMap<String, usize> = HashMap::new();
-
-    for (item, file_to_lines) in &item_file_lines {
-        let file_count = file_to_lines.len();
-        item_file_count.insert(item.clone(), file_count);
-
-        let threshold = file_count.div_ceil(2); // strict majority
-        let mut line_counts: HashMap<&str, usize> = HashMap::new();
-        for lines in file_to_lines.values() {
-            for line in lines {
-                *line_counts.entry(line.as_str()).or_insert(0) += 1;
+    #[test]
+    fn parses_fighter_stats_loadout() {
+        let args = ["amanuensis", "fighter-stats", "Gandor", "--race", "Sylvan", "--item", "Chain Mail", "--item", "Plate Armor", "--save"];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Ranks(RankCommands::FighterStats { race, weapon, items, save, clear, .. }) => {
+                assert_eq!(race.as_deref(), Some("Sylvan"));
+                assert_eq!(weapon, None);
+                assert_eq!(items, vec!["Chain Mail", "Plate Armor"]);
+                assert!(save && !clear);
             }
+            _ => panic!("expected FighterStats"),
         }
-        let canonical: BTreeSet<String> = line_counts
-            .into_iter()
-            .filter(|(_, count)| *count >= threshold)
-            .map(|(line, _)| line.to_string())
-            .collect();
-        if !canonical.is_empty() {
-            items.insert(item.clone(), canonical);
-        }
+        assert!(Cli::try_parse_from(["amanuensis", "fighter-stats", "Gandor", "--save", "--clear"]).is_err());
     }
 
-    // Fuzzy dedup: items whose canonical line sets have ≥50% Jaccard similarity are
-    // treated as the same help block misattributed to different names (e.g. gossamer's
-    // "This weapon helps…" block appearing under "pair of Ethereal Boots" because boots
-    // happened to be last_equipped when the delayed response arrived).
-    // Process in descending file-count order so the most-seen name is always kept.
-    {
-        let mut item_names: Vec<String> = items.keys().cloned().collect();
-        item_names.sort_by_key(|n| std::cmp::Reverse(item_file_count.get(n).copied().unwrap_or(0)));
+    #[test]
+    fn parses_simulate_command() {
+        match Cli::try_parse_from(["amanuensis", "simulate", "Gandor", "--add", "Evus:50,Darkus:20"]).unwrap().command {
+            Commands::Ranks(RankCommands::Simulate { name, add }) => assert_eq!((name.as_str(), add.as_str()), ("Gandor", "Evus:50,Darkus:20")),
+            _ => panic!("expected Simulate"),
+        }
+        assert!(Cli::try_parse_from(["amanuensis", "simulate", "Gandor"]).is_err());
+    }
 
-        let mut to_remove: HashSet<String> = HashSet::new();
-        for i in 0..item_names.len() {
-            let a = &item_names[i];
-            if to_remove.contains(a) { continue; }
-            let lines_a = items[a].clone();
-            for b in item_names.iter().skip(i + 1) {
-                if to_remove.contains(b) { continue; }
-                let lines_b = &items[b];
-                let intersection = lines_a.intersection(lines_b).count();
-                if intersection == 0 { continue; }
-                let union = lines_a.union(lines_b).count();
-                if intersection * 2 >= union {
-                    // Jaccard >= 0.5 — b has fewer or equal files, drop it
-                    to_remove.insert(b.clone());
-                }
+    #[test]
+    fn parses_untrain_plan_command() {
+        let args = ["amanuensis", "untrain-plan", "Gandor", "--more", "accuracy,defense", "--less", "balance"];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Ranks(RankCommands::UntrainPlan { more, less, ranks, coins_per_rank, budget, .. }) => {
+                assert_eq!(more, vec!["accuracy", "defense"]);
+                assert_eq!(less, vec!["balance"]);
+                assert_eq!(ranks, 10);
+                assert_eq!((coins_per_rank, budget), (0, None));
             }
+            _ => panic!("expected UntrainPlan"),
         }
-        for name in &to_remove { items.remove(name); }
     }
 
-    println!("Found {} item(s) across {} files.", items.len(), total_files);
-    for (item, line_set) in &items {
-        println!();
-        println!("=== {} ===", item);
-        for line in line_set {
-            println!("  {}", line);
+    #[test]
+    fn parses_rank_sources_command() {
+        match Cli::try_parse_from(["amanuensis", "rank-sources", "Gandor", "--trainer", "Histia"]).unwrap().command {
+            Commands::Ranks(RankCommands::RankSources { name, trainer }) => {
+                assert_eq!((name.as_str(), trainer.as_deref()), ("Gandor", Some("Histia")))
+            }
+            _ => panic!("expected RankSources"),
         }
     }
 
-    Ok(())
-}
-
-// ── update-bestiary / bestiary ────────────────────────────────────────────────
+    #[test]
+    fn parses_demo_command() {
+        let cli = Cli::try_parse_from(["amanuensis", "demo", "kills", "Ashwin", "--sort", "value"]).unwrap();
+        let Commands::Catalog(CatalogCommands::Demo { args }) = cli.command else { panic!("expected demo") };
+        assert_eq!(args, ["kills", "Ashwin", "--sort", "value"]);
+        let Commands::Catalog(CatalogCommands::Demo { args }) = Cli::try_parse_from(["amanuensis", "demo"]).unwrap().command else {
+            panic!("expected demo")
+        };
+        assert!(args.is_empty());
+    }
 
-fn cmd_update_bestiary(
-    xml_path: &Path,
-    aliases_override: Option<&Path>,
-    output_override: Option<&Path>,
-    dry_run: bool,
-) -> amanuensis_core::Result<()> {
-    use amanuensis_core::data::{parse_bestiary_xml, BestiaryFile, CreatureDb};
+    #[test]
+    fn parses_reconcile_command() {
+        let cli = Cli::try_parse_from(["amanuensis", "reconcile", "Gandor", "--paste", "ranks.txt", "--apply"]).unwrap();
+        let Commands::Ranks(RankCommands::Reconcile { name, paste, apply }) = cli.command else { panic!("expected reconcile") };
+        assert_eq!(name, "Gandor");
+        assert_eq!(paste, PathBuf::from("ranks.txt"));
+        assert!(apply);
+        assert!(Cli::try_parse_from(["amanuensis", "reconcile", "Gandor"]).is_err());
+    }
 
-    let xml = std::fs::read(xml_path)?;
-    let mut entries = parse_bestiary_xml(&xml)?;
-    entries.sort_by(|a, b| a.name.cmp(&b.name));
-    let version = version_from_filename(xml_path);
+    #[test]
+    fn parses_set_ranks_bulk_command() {
+        let cli = Cli::try_parse_from(["amanuensis", "set-ranks-bulk", "Gandor", "ranks.csv", "--dry-run"]).unwrap();
+        let Commands::Ranks(RankCommands::SetRanksBulk { name, file, dry_run }) = cli.command else { panic!("expected set-ranks-bulk") };
+        assert_eq!(name, "Gandor");
+        assert_eq!(file, PathBuf::from("ranks.csv"));
+        assert!(dry_run);
+    }
 
-    let alias_path = aliases_override.map(PathBuf::from).unwrap_or_else(default_alias_path);
-    let alias_bytes = std::fs::read(&alias_path)?;
+    #[test]
+    fn parses_trainer_catalog_character() {
+        let cli = Cli::try_parse_from(["amanuensis", "trainer-catalog", "--character", "Gandor", "--profession", "fighter"]).unwrap();
+        let Commands::Catalog(CatalogCommands::TrainerCatalog { profession, character }) = cli.command else { panic!("expected trainer-catalog") };
+        assert_eq!(profession.as_deref(), Some("fighter"));
+        assert_eq!(character.as_deref(), Some("Gandor"));
+    }
 
-    // Validate aliases against the new entries by round-tripping through CreatureDb.
-    let file = BestiaryFile { version: version.clone(), entries };
-    let bestiary_bytes = serde_json::to_vec(&file)?;
-    let db = CreatureDb::from_json_bytes(&bestiary_bytes, &alias_bytes)?;
+    #[test]
+    fn parses_watch_and_daemon_ws() {
+        let Commands::Data(DataCommands::Watch { ws, .. }) =
+            Cli::try_parse_from(["amanuensis", "watch", "logs", "--ws", "127.0.0.1:7878"]).unwrap().command
+        else {
+            panic!("expected Watch");
+        };
+        assert_eq!(ws.as_deref(), Some("127.0.0.1:7878"));
+        let Commands::Data(DataCommands::Daemon { ws, .. }) =
+            Cli::try_parse_from(["amanuensis", "daemon", "--ws", "0.0.0.0:9000"]).unwrap().command
+        else {
+            panic!("expected Daemon");
+        };
+        assert_eq!(ws.as_deref(), Some("0.0.0.0:9000"));
+    }
 
-    println!("Bestiary: {} entries (version {})", db.len(), db.bestiary_version());
-    println!("Aliases:  {} loaded from {}", count_aliases(&alias_bytes)?, alias_path.display());
+    #[test]
+    fn parses_watch_notify() {
+        let Commands::Data(DataCommands::Watch { notify, .. }) =
+            Cli::try_parse_from(["amanuensis", "watch", "logs", "--notify", "discord.json"]).unwrap().command
+        else {
+            panic!("expected Watch");
+        };
+        assert_eq!(notify, Some(PathBuf::from("discord.json")));
+    }
 
-    if dry_run {
-        println!("(dry-run; not writing)");
-        return Ok(());
+    #[test]
+    fn parses_kills_by_location() {
+        let Commands::Stats(StatsCommands::Kills { by_location, family, .. }) =
+            Cli::try_parse_from(["amanuensis", "kills", "Gandor", "--by-location", "--family", "Orga"]).unwrap().command
+        else {
+            panic!("expected Kills");
+        };
+        assert!(by_location);
+        assert_eq!(family.as_deref(), Some("Orga"));
     }
 
-    let out_path = output_override.map(PathBuf::from).unwrap_or_else(default_bestiary_path);
-    let pretty = serde_json::to_string_pretty(&file)?;
-    std::fs::write(&out_path, pretty)?;
-    println!("Wrote {}", out_path.display());
-    println!(
-        "Bestiary updated to version {}. Existing databases should run 'amanuensis scan --force <folder>' to refresh kill values.",
-        version
-    );
-    Ok(())
-}
+    #[test]
+    fn parses_deaths_command() {
+        match Cli::try_parse_from(["amanuensis", "deaths", "Gandor", "--limit", "5"]).unwrap().command {
+            Commands::Stats(StatsCommands::Deaths { name, limit }) => assert_eq!((name.as_str(), limit), ("Gandor", 5)),
+            _ => panic!("expected Deaths"),
+        }
+    }
 
-fn cmd_bestiary(name: &str) -> amanuensis_core::Result<()> {
-    use amanuensis_core::data::{canonical_rarity, CreatureDb, EntrySource};
-    let db = CreatureDb::bundled()?;
-    match db.get_entry_with_source(name) {
-        None => {
-            eprintln!("No bestiary entry for '{}'", name);
-            std::process::exit(1);
+    #[test]
+    fn parses_rescues_command() {
+        match Cli::try_parse_from(["amanuensis", "rescues", "Gandor"]).unwrap().command {
+            Commands::Stats(StatsCommands::Rescues { name }) => assert_eq!(name, "Gandor"),
+            _ => panic!("expected Rescues"),
         }
-        Some((entry, source)) => {
-            let src = match source {
-                EntrySource::Bestiary => "bestiary",
-                EntrySource::Alias => "alias → bestiary",
-                EntrySource::InlineAlias => "inline alias",
-            };
-            println!("Name:           {}", entry.name);
-            println!("Source:         {} (bestiary v{})", src, db.bestiary_version());
-            if let Some(f) = &entry.family { println!("Family:         {}", db.canonical_family(f)); }
-            println!("Rarity:         {}", canonical_rarity(entry.rarity.as_deref()).as_label());
-            println!("Exp/taxidermy:  {}", entry.exp_taxidermy);
-            if let Some(l) = &entry.location { println!("Location:       {}", l); }
-            if let Some(i) = &entry.information { println!("Information:    {}", i); }
-            if let Some(d) = &entry.difficulty { println!("Difficulty:     {}", d); }
-            let stats = [
-                ("Attack", entry.attack, entry.attack_measured),
-                ("Defense", entry.defense, entry.defense_measured),
-                ("Damage", entry.damage, entry.damage_measured),
-                ("Health", entry.health, entry.health_measured),
-            ];
-            for (label, val, measured) in stats {
-                if let Some(v) = val {
-                    let suffix = if measured { " (measured)" } else { "" };
-                    println!("{:14}  {}{}", format!("{}:", label), v, suffix);
-                }
-            }
-            if let Some(l) = entry.luck_hits { println!("Luck hits:      {}%", l); }
-            if let Some(fps) = entry.frames_per_swing { println!("Frames/swing:   {}", fps); }
-            if let Some(w) = &entry.worth_range { println!("Worth range:    {}", w); }
-            if entry.is_seasonal { println!("Seasonal:       yes"); }
+    }
+
+    #[test]
+    fn parses_falls_and_witnessed_falls_flag() {
+        match Cli::try_parse_from(["amanuensis", "falls", "Gandor", "--by-cause"]).unwrap().command {
+            Commands::Stats(StatsCommands::Falls { name, by_cause, limit }) => assert_eq!((name.as_str(), by_cause, limit), ("Gandor", true, 20)),
+            _ => panic!("expected Falls"),
         }
+        let Commands::Data(DataCommands::Scan { witnessed_falls, .. }) =
+            Cli::try_parse_from(["amanuensis", "scan", "--witnessed-falls"]).unwrap().command
+        else {
+            panic!("expected Scan");
+        };
+        assert!(witnessed_falls);
     }
-    Ok(())
-}
 
-fn version_from_filename(path: &Path) -> String {
-    path.file_name()
-        .and_then(|s| s.to_str())
-        .and_then(|s| s.strip_prefix("bestiary_"))
-        .and_then(|s| s.split('_').next())
-        .unwrap_or("00000000")
-        .to_string()
-}
+    #[test]
+    fn parses_coins_timeline() {
+        match Cli::try_parse_from(["amanuensis", "coins", "Gandor", "--timeline", "monthly"]).unwrap().command {
+            Commands::Stats(StatsCommands::Coins { name, timeline }) => assert_eq!((name.as_str(), timeline.as_deref()), ("Gandor", Some("monthly"))),
+            _ => panic!("expected Coins"),
+        }
+    }
 
-fn default_alias_path() -> PathBuf {
-    PathBuf::from("crates/amanuensis-core/data/bestiary_aliases.json")
-}
+    #[test]
+    fn parses_casino_command() {
+        match Cli::try_parse_from(["amanuensis", "casino", "Gandor"]).unwrap().command {
+            Commands::Stats(StatsCommands::Casino { name }) => assert_eq!(name, "Gandor"),
+            _ => panic!("expected Casino"),
+        }
+    }
 
-fn default_bestiary_path() -> PathBuf {
-    PathBuf::from("crates/amanuensis-core/data/bestiary.json")
-}
+    #[test]
+    fn parses_chat_command() {
+        match Cli::try_parse_from(["amanuensis", "chat", "--speaker", "Fen", "--contains", "hunt"]).unwrap().command {
+            Commands::Text(TextCommands::Chat { speaker, contains, character, limit }) => {
+                assert_eq!(speaker.as_deref(), Some("Fen"));
+                assert_eq!(contains.as_deref(), Some("hunt"));
+                assert_eq!((character, limit), (None, 100));
+            }
+            _ => panic!("expected Chat"),
+        }
+        let Commands::Data(DataCommands::Scan { chat, .. }) = Cli::try_parse_from(["amanuensis", "scan", "logs", "--chat"]).unwrap().command else {
+            panic!("expected Scan");
+        };
+        assert!(chat);
+    }
 
-fn count_aliases(bytes: &[u8]) -> amanuensis_core::Result<usize> {
-    let parsed: serde_json::Value = serde_json::from_slice(bytes)?;
-    Ok(parsed.as_array().map(|a| a.len()).unwrap_or(0))
-}
+    #[test]
+    fn parses_unknowns_command() {
+        match Cli::try_parse_from(["amanuensis", "unknowns", "--top", "10"]).unwrap().command {
+            Commands::Text(TextCommands::Unknowns { top, character }) => assert_eq!((top, character), (10, None)),
+            _ => panic!("expected Unknowns"),
+        }
+        let Commands::Data(DataCommands::Update { unknown_lines, .. }) =
+            Cli::try_parse_from(["amanuensis", "update", "--unknown-lines"]).unwrap().command
+        else {
+            panic!("expected Update");
+        };
+        assert!(unknown_lines);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use clap::CommandFactory;
+    #[test]
+    fn parses_classify_command() {
+        match Cli::try_parse_from(["amanuensis", "classify", "-", "--matched"]).unwrap().command {
+            Commands::Text(TextCommands::Classify { file, matched }) => assert_eq!((file, matched), (PathBuf::from("-"), true)),
+            _ => panic!("expected Classify"),
+        }
+    }
 
-    /// clap's own structural consistency check — catches conflicting args, bad defaults,
-    /// duplicate names, etc. across the whole Commands enum.
     #[test]
-    fn cli_definition_is_valid() {
-        Cli::command().debug_assert();
+    fn parses_scan_errors_command() {
+        match Cli::try_parse_from(["amanuensis", "scan-errors", "--retry"]).unwrap().command {
+            Commands::Data(DataCommands::ScanErrors { retry }) => assert!(retry),
+            _ => panic!("expected ScanErrors"),
+        }
     }
 
     #[test]
-    fn parses_update_command() {
-        let cli = Cli::try_parse_from(["amanuensis", "update", "logs1", "logs2", "--recursive", "--no-index"]).unwrap();
+    fn parses_search_filters() {
+        let cli = Cli::try_parse_from([
+            "amanuensis", "search", "picked up \\d+", "--regex", "--from", "2024-01-01", "--to", "2024-02-01", "--context", "2",
+        ])
+        .unwrap();
         match cli.command {
-            Commands::Update { folders, recursive, no_index } => {
-                assert_eq!(folders.len(), 2);
-                assert!(recursive);
-                assert!(no_index);
+            Commands::Text(TextCommands::Search { query, from, to, context, regex, limit, offset, output, .. }) => {
+                assert_eq!(query, "picked up \\d+");
+                assert_eq!((from.as_deref(), to.as_deref()), (Some("2024-01-01"), Some("2024-02-01")));
+                assert_eq!((context, regex, limit, offset, output), (2, true, None, 0, None));
             }
-            _ => panic!("expected Update"),
+            _ => panic!("expected Search"),
+        }
+        assert_eq!(search_date_bound(Some("2024-02-01"), "--to", true).unwrap().as_deref(), Some("2024-02-01 23:59:59"));
+        assert_eq!(search_date_bound(Some("2024-02-01"), "--from", false).unwrap().as_deref(), Some("2024-02-01 00:00:00"));
+        assert!(search_date_bound(Some("soon"), "--from", false).is_err());
+        let cli = Cli::try_parse_from(["amanuensis", "search", "rat", "--offset", "100", "--output", "hits.csv"]).unwrap();
+        match cli.command {
+            Commands::Text(TextCommands::Search { offset, output, .. }) => assert_eq!((offset, output), (100, Some(PathBuf::from("hits.csv")))),
+            _ => panic!("expected Search"),
         }
     }
 
     #[test]
-    fn parses_pending_command_with_list() {
-        let cli = Cli::try_parse_from(["amanuensis", "pending", "logs", "--list"]).unwrap();
+    fn parses_context_command() {
+        let cli = Cli::try_parse_from(["amanuensis", "context", "log.txt", "2024-01-01 13:01:00", "--lines", "5"]).unwrap();
         match cli.command {
-            Commands::Pending { folders, recursive, list } => {
-                assert_eq!(folders, vec![PathBuf::from("logs")]);
-                assert!(!recursive);
-                assert!(list);
+            Commands::Text(TextCommands::Context { file, timestamp, lines }) => {
+                assert_eq!((file, timestamp.as_str(), lines), (PathBuf::from("log.txt"), "2024-01-01 13:01:00", 5));
             }
-            _ => panic!("expected Pending"),
+            _ => panic!("expected Context"),
         }
     }
 
     #[test]
-    fn update_and_pending_require_a_folder() {
-        assert!(Cli::try_parse_from(["amanuensis", "update"]).is_err());
-        assert!(Cli::try_parse_from(["amanuensis", "pending"]).is_err());
+    fn parses_arena_command() {
+        match Cli::try_parse_from(["amanuensis", "arena", "Gandor", "--limit", "5"]).unwrap().command {
+            Commands::Stats(StatsCommands::Arena { name, limit }) => assert_eq!((name.as_str(), limit), ("Gandor", 5)),
+            _ => panic!("expected Arena"),
+        }
     }
 
     #[test]
-    fn parses_set_trainer_note_with_and_without_note() {
-        let with = Cli::try_parse_from(["amanuensis", "set-trainer-note", "Gandor", "Detha", "a note"]).unwrap();
-        match with.command {
-            Commands::SetTrainerNote { name, trainer, note } => {
-                assert_eq!(name, "Gandor");
-                assert_eq!(trainer, "Detha");
-                assert_eq!(note.as_deref(), Some("a note"));
-            }
-            _ => panic!("expected SetTrainerNote"),
-        }
-        let without = Cli::try_parse_from(["amanuensis", "set-trainer-note", "Gandor", "Detha"]).unwrap();
-        match without.command {
-            Commands::SetTrainerNote { note, .. } => assert!(note.is_none()),
-            _ => panic!("expected SetTrainerNote"),
+    fn parses_glossary_command() {
+        match Cli::try_parse_from(["amanuensis", "glossary", "Gandor"]).unwrap().command {
+            Commands::Stats(StatsCommands::Glossary { name }) => assert_eq!(name, "Gandor"),
+            _ => panic!("expected Glossary"),
         }
     }
 
     #[test]
-    fn parses_clear_and_reset_logs_flags() {
-        match Cli::try_parse_from(["amanuensis", "clear-rank-overrides", "--yes"]).unwrap().command {
-            Commands::ClearRankOverrides { yes } => assert!(yes),
-            _ => panic!("expected ClearRankOverrides"),
-        }
-        match Cli::try_parse_from(["amanuensis", "reset-logs"]).unwrap().command {
-            Commands::ResetLogs { yes } => assert!(!yes),
-            _ => panic!("expected ResetLogs"),
+    fn parses_compare_command() {
+        match Cli::try_parse_from(["amanuensis", "compare", "Gandor", "Fen", "--kills", "5"]).unwrap().command {
+            Commands::Share(ShareCommands::Compare { name1, name2, kills }) => {
+                assert_eq!((name1.as_str(), name2.as_str(), kills), ("Gandor", "Fen", 5));
+            }
+            _ => panic!("expected Compare"),
         }
+        assert!(Cli::try_parse_from(["amanuensis", "compare", "Gandor"]).is_err());
     }
 
     #[test]
-    fn parses_frequency_flags() {
+    fn parses_leaderboard_metric_and_limit() {
+        let cli = Cli::try_parse_from(["amanuensis", "leaderboard"]).unwrap();
+        assert!(cli.format.is_none());
+        match cli.command {
+            Commands::Share(ShareCommands::Leaderboard { metric, limit }) => {
+                assert_eq!(metric, "kills");
+                assert_eq!(limit, None);
+            }
+            _ => panic!("expected Leaderboard"),
+        }
         let cli = Cli::try_parse_from([
-            "amanuensis", "frequency", "Gandor",
-            "--bin", "2h", "--solo", "--by-verb", "--format", "csv", "--limit", "5",
-        ]).unwrap();
+            "amanuensis", "leaderboard", "--metric", "depart-rate", "--limit", "10",
+        ])
+        .unwrap();
         match cli.command {
-            Commands::Frequency { name, bin, solo, by_verb, format, limit } => {
-                assert_eq!(name, "Gandor");
-                assert_eq!(bin, "2h");
-                assert!(solo);
-                assert!(by_verb);
-                assert_eq!(format, "csv");
-                assert_eq!(limit, Some(5));
+            Commands::Share(ShareCommands::Leaderboard { metric, limit, .. }) => {
+                assert_eq!(metric, "depart-rate");
+                assert_eq!(limit, Some(10));
             }
-            _ => panic!("expected Frequency"),
+            _ => panic!("expected Leaderboard"),
         }
     }
 }
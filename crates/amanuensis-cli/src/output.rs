@@ -0,0 +1,52 @@
+//! Output layer behind the global `--format` flag.
+//!
+//! Every command prints comfy-table text by default. With `--format json` each command prints
+//! one JSON document on stdout instead (serialized straight from the core structs), so scripts
+//! never have to scrape tables; progress and prompts stay on stderr. `--format csv` is only
+//! understood by the commands that already wrote CSV.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable tables and text
+    #[default]
+    Table,
+    /// One JSON document on stdout (JSON Lines for `watch`)
+    Json,
+    /// Comma-separated values (`kills`, `frequency`, `export` and `catalog-diff` only)
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn is_json(self) -> bool {
+        self == OutputFormat::Json
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputFormat::Table => "table",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Pretty-print `value` as JSON on stdout.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> amanuensis_core::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print a command's plain confirmation: the text as-is, or `{"message": ...}` as JSON.
+pub fn print_message(format: OutputFormat, message: &str) -> amanuensis_core::Result<()> {
+    if format.is_json() {
+        print_json(&serde_json::json!({ "message": message }))
+    } else {
+        println!("{message}");
+        Ok(())
+    }
+}
+
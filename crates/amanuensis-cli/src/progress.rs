@@ -0,0 +1,74 @@
+//! Scan progress on stderr: a bar over the scan's files with files/s and an ETA, plus a
+//! lines/s rate and the position inside the current file, fed by
+//! `LogParser::set_line_progress` so one huge file still moves the display. Nothing is
+//! drawn when stderr isn't a terminal.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use amanuensis_core::LogParser;
+use indicatif::{ProgressBar, ProgressStyle};
+
+const TEMPLATE: &str = "{spinner} {bar:25} {pos}/{len} files, {per_sec}, ETA {eta} | {prefix} | {wide_msg}";
+
+#[derive(Clone)]
+pub struct ScanProgress {
+    bar: ProgressBar,
+    /// Lines scanned so far, across files.
+    lines: Arc<AtomicU64>,
+    /// Lines of the current file already counted in `lines`.
+    file_lines: Arc<AtomicU64>,
+    filename: Arc<Mutex<String>>,
+}
+
+impl ScanProgress {
+    /// Start the display and have `parser` report its line counts to it.
+    pub fn attach(parser: &LogParser) -> Self {
+        let bar = ProgressBar::new(0);
+        bar.set_style(
+            ProgressStyle::with_template(TEMPLATE)
+                .expect("valid progress template")
+                .progress_chars("=> "),
+        );
+        bar.enable_steady_tick(Duration::from_millis(200));
+        let progress = Self {
+            bar,
+            lines: Arc::new(AtomicU64::new(0)),
+            file_lines: Arc::new(AtomicU64::new(0)),
+            filename: Arc::new(Mutex::new(String::new())),
+        };
+        let for_lines = progress.clone();
+        parser.set_line_progress(Some(Box::new(move |done, total| for_lines.lines(done, total))));
+        progress
+    }
+
+    /// The per-file callback for the `LogParser` `*_with_progress` scans, called with the
+    /// 1-based number of the file about to be scanned.
+    pub fn file(&self, current: usize, total: usize, filename: &str) {
+        self.bar.set_length(total as u64);
+        self.bar.set_position(current.saturating_sub(1).min(total) as u64);
+        self.file_lines.store(0, Ordering::Relaxed);
+        *self.filename.lock().unwrap() = filename.to_string();
+        self.bar.set_message(filename.to_string());
+    }
+
+    fn lines(&self, done: usize, total: usize) {
+        let counted = self.file_lines.swap(done as u64, Ordering::Relaxed);
+        let added = (done as u64).saturating_sub(counted);
+        let scanned = self.lines.fetch_add(added, Ordering::Relaxed) + added;
+        let secs = self.bar.elapsed().as_secs_f64().max(0.001);
+        self.bar.set_prefix(format!("{:.0} lines/s", scanned as f64 / secs));
+        let filename = self.filename.lock().unwrap();
+        if done < total {
+            self.bar.set_message(format!("{}% of {}", done * 100 / total, filename));
+        } else {
+            self.bar.set_message(filename.clone());
+        }
+    }
+
+    /// Remove the bar once the scan is over.
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
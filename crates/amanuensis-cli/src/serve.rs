@@ -0,0 +1,292 @@
+//! `serve`: read-only REST endpoints over the database, answered as JSON.
+//!
+//! A minimal HTTP/1.1 server on a std listener. Requests are answered one at a time over a
+//! single read-only database connection, and each socket is closed after its response; the
+//! timeouts keep a stalled client from holding up the rest. Only GET is served:
+//!
+//! - `/characters` — every visible character
+//! - `/characters/{name}/kills` — the character's kills (merged view), most first
+//! - `/search?q=...[&character=][&limit=]` — matching log lines, without speech or thoughts
+//!
+//! Errors come back as `{"error": "...", "kind": "..."}` with a matching status: 400 for a
+//! bad request, 404 for an unknown endpoint or character, 500 for anything on the server side.
+
+use std::cmp::Reverse;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use amanuensis_core::{AmanuensisError, Database};
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Value};
+
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Most search results one request can ask for.
+const MAX_SEARCH_LIMIT: i64 = 200;
+
+/// Answer requests on `listener` from `db` (see `Database::open_read_only`) until the process
+/// is interrupted. A failed client socket is dropped; only the listener failing ends the loop.
+pub fn run(listener: TcpListener, db: &Database) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let _ = handle(stream?, db);
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, db: &Database) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Headers are read and ignored; GET requests carry no body.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => route(db, target),
+        (Some(_), Some(_)) => (405, json!({ "error": "Only GET is supported", "kind": "method_not_allowed" })),
+        _ => (400, json!({ "error": "Malformed request", "kind": "bad_request" })),
+    };
+    let body = serde_json::to_string(&body)?;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// The status and JSON body for a GET of `target` (path plus query string).
+fn route(db: &Database, target: &str) -> (u16, Value) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let segments: Vec<String> =
+        path.split('/').filter(|s| !s.is_empty()).map(|s| percent_decode_str(s).decode_utf8_lossy().into_owned()).collect();
+    let result = match segments.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["characters"] => db.list_characters().and_then(to_json),
+        ["characters", name, "kills"] => character_kills(db, name),
+        ["search"] => search(db, &query_params(query)),
+        _ => return (404, json!({ "error": format!("No endpoint at {path}"), "kind": "not_found" })),
+    };
+    match result {
+        Ok(body) => (200, body),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// `resolve_character`, with naming a merged character reported as the client's mistake
+/// (its only `Data` error) rather than a server failure.
+fn resolve(db: &Database, name: &str) -> amanuensis_core::Result<amanuensis_core::models::Character> {
+    crate::resolve_character(db, name).map_err(|e| match e {
+        AmanuensisError::Data(msg) => AmanuensisError::Parse(msg),
+        e => e,
+    })
+}
+
+fn character_kills(db: &Database, name: &str) -> amanuensis_core::Result<Value> {
+    let char_id = resolve(db, name)?.id.unwrap();
+    let mut kills = db.get_kills_merged(char_id)?;
+    kills.sort_by_key(|k| Reverse(k.total_all()));
+    to_json(kills)
+}
+
+fn search(db: &Database, params: &[(String, String)]) -> amanuensis_core::Result<Value> {
+    let param = |key: &str| params.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+    let number = |key: &str, default: i64| -> amanuensis_core::Result<i64> {
+        param(key).map_or(Ok(default), |v| {
+            v.parse().map_err(|_| AmanuensisError::Parse(format!("'{key}' must be a number, got '{v}'")))
+        })
+    };
+    let query = param("q").filter(|q| !q.trim().is_empty()).ok_or_else(|| {
+        AmanuensisError::Parse("Missing search query: /search?q=...".to_string())
+    })?;
+    let char_id = match param("character") {
+        Some(name) => resolve(db, name)?.id,
+        None => None,
+    };
+    let limit = number("limit", 50)?.clamp(1, MAX_SEARCH_LIMIT);
+    to_json(db.search_log_lines(query, char_id, limit, false, 0, 0)?)
+}
+
+/// Decoded `key=value` pairs of a query string (`+` is a space).
+fn query_params(query: &str) -> Vec<(String, String)> {
+    let decode = |s: &str| percent_decode_str(&s.replace('+', " ")).decode_utf8_lossy().into_owned();
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn to_json(value: impl serde::Serialize) -> amanuensis_core::Result<Value> {
+    Ok(serde_json::to_value(value)?)
+}
+
+fn error_response(e: &AmanuensisError) -> (u16, Value) {
+    let status = match e {
+        AmanuensisError::CharacterNotFound { .. } => 404,
+        AmanuensisError::Parse(_) => 400,
+        _ => 500,
+    };
+    (status, json!({ "error": e.to_string(), "kind": e.kind() }))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn fixture(db: &Database) {
+        let fen = db.get_or_create_character("Fen").unwrap();
+        let mary = db.get_or_create_character("Mary Ann").unwrap();
+        for id in [fen, mary] {
+            db.increment_character_field(id, "logins", 1).unwrap();
+        }
+        db.upsert_kill(fen, "Rat", "killed_count", 0, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(fen, "Vermine", "killed_count", 0, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(fen, "Vermine", "slaughtered_count", 0, "2024-01-01 11:00:00").unwrap();
+        db.upsert_kill(mary, "Orga", "killed_count", 0, "2024-01-02 10:00:00").unwrap();
+        db.insert_log_lines(&[
+            (fen, "You slaughtered a Rat.", "2024-01-01 10:00:00", "a.txt"),
+            (fen, "Fen says, \"a rat!\"", "2024-01-01 10:00:01", "a.txt"),
+            (fen, "Mary Ann thinks to you, \"rat at the gate\"", "2024-01-01 10:00:02", "a.txt"),
+            (fen, "Mary Ann whispers, \"rat\"", "2024-01-01 10:00:03", "a.txt"),
+            (mary, "You think to Fen, \"rat here too\"", "2024-01-02 10:00:01", "b.txt"),
+            (mary, "You killed a Rat.", "2024-01-02 10:00:00", "b.txt"),
+        ])
+        .unwrap();
+    }
+
+    fn kind(body: &Value) -> &str {
+        body["kind"].as_str().unwrap()
+    }
+
+    #[test]
+    fn routes_answer_with_json() {
+        let db = Database::open_in_memory().unwrap();
+        fixture(&db);
+
+        let (status, body) = route(&db, "/characters");
+        assert_eq!(status, 200);
+        assert_eq!(body.as_array().unwrap().len(), 2);
+
+        let (status, body) = route(&db, "/characters/Fen/kills/");
+        assert_eq!(status, 200);
+        let creatures: Vec<&str> = body.as_array().unwrap().iter().map(|k| k["creature_name"].as_str().unwrap()).collect();
+        assert_eq!(creatures, ["Vermine", "Rat"]);
+        assert_eq!(route(&db, "/characters/Mary%20Ann/kills").1[0]["creature_name"], "Orga");
+
+        // Speech and thoughts are never searched; `character` narrows to one character.
+        let (status, body) = route(&db, "/search?q=rat");
+        assert_eq!(status, 200);
+        assert_eq!(body.as_array().unwrap().len(), 2);
+        let (_, body) = route(&db, "/search?q=rat&character=Mary+Ann&limit=1");
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["content"], "You killed a Rat.");
+    }
+
+    #[test]
+    fn bad_requests_get_matching_statuses() {
+        let db = Database::open_in_memory().unwrap();
+        fixture(&db);
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.merge_characters(&[alt], db.get_character("Fen").unwrap().unwrap().id.unwrap()).unwrap();
+
+        for (target, status, expected) in [
+            ("/nowhere", 404, "not_found"),
+            ("/characters/Nobody/kills", 404, "character_not_found"),
+            ("/search?character=Nobody&q=rat", 404, "character_not_found"),
+            ("/search", 400, "parse"),
+            ("/search?q=+", 400, "parse"),
+            ("/search?q=rat&limit=lots", 400, "parse"),
+            ("/characters/Alt/kills", 400, "parse"),
+            ("/search?q=rat&character=Alt", 400, "parse"),
+        ] {
+            let (got, body) = route(&db, target);
+            assert_eq!((got, kind(&body)), (status, expected), "{target}");
+        }
+    }
+
+    #[test]
+    fn server_failures_are_500_not_client_errors() {
+        let cases = [
+            (AmanuensisError::Parse("bad".into()), 400),
+            (AmanuensisError::CharacterNotFound { name: "Nobody".into() }, 404),
+            (AmanuensisError::Data("corrupt row".into()), 500),
+            (io::Error::new(io::ErrorKind::PermissionDenied, "denied").into(), 500),
+            (AmanuensisError::FtsUnavailable { reason: "no such module: fts5".into() }, 500),
+        ];
+        for (e, status) in cases {
+            assert_eq!(error_response(&e).0, status, "{e}");
+        }
+        // A database that can no longer be read is a server failure too.
+        let db = Database::open_in_memory().unwrap();
+        db.conn().execute_batch("DROP TABLE kills").unwrap();
+        db.get_or_create_character("Fen").unwrap();
+        assert_eq!(route(&db, "/characters/Fen/kills").0, 500);
+    }
+
+    #[test]
+    fn query_params_are_decoded() {
+        assert_eq!(
+            query_params("q=big+rat&character=Mary%20Ann&flag&&limit=5%25"),
+            [
+                ("q".to_string(), "big rat".to_string()),
+                ("character".to_string(), "Mary Ann".to_string()),
+                ("flag".to_string(), String::new()),
+                ("limit".to_string(), "5%".to_string()),
+            ]
+        );
+        assert!(query_params("").is_empty());
+    }
+
+    #[test]
+    fn serves_requests_in_turn_from_a_read_only_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("serve.db").to_string_lossy().into_owned();
+        fixture(&Database::open(&path).unwrap());
+        let db = Database::open_read_only(&path).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || run(listener, &db));
+
+        let request = |raw: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(raw.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let ok = request("GET /characters HTTP/1.1\r\nHost: test\r\n\r\n");
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"), "{ok}");
+        assert!(ok.contains("Content-Type: application/json\r\n") && ok.contains("\"Mary Ann\""), "{ok}");
+        for target in ["/characters/Fen/kills", "/search?q=rat&character=Fen"] {
+            let response = request(&format!("GET {target} HTTP/1.1\r\n\r\n"));
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        }
+        assert!(request("POST /characters HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 405 "));
+        assert!(request("HELLO\r\n\r\n").starts_with("HTTP/1.1 400 "));
+        assert!(request("GET /nowhere HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404 "));
+    }
+}
@@ -0,0 +1,76 @@
+//! `watch --ws` / `daemon --ws`: push parsed events to WebSocket clients.
+//!
+//! A listener thread accepts connections and completes the handshake; the scan then
+//! publishes each event, the same JSON object `scan --emit-events` writes per line, as one
+//! text message to every connected client. Clients only listen (anything they send is
+//! ignored). One that disconnects, or blocks a write for longer than `WRITE_TIMEOUT`, is
+//! dropped so a stuck overlay can't stall the scan.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use amanuensis_core::parser::event_bus::EventSubscriber;
+use amanuensis_core::parser::events::StreamedEvent;
+use tungstenite::{Message, WebSocket};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+type Clients = Arc<Mutex<Vec<WebSocket<TcpStream>>>>;
+
+/// Accepts WebSocket clients in the background for as long as the process runs.
+pub struct WsBroadcaster {
+    clients: Clients,
+    addr: SocketAddr,
+}
+
+impl WsBroadcaster {
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let clients: Clients = Arc::default();
+        let accepted = Arc::clone(&clients);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // Handshake off the accept loop, so one slow client doesn't hold up the rest
+                let clients = Arc::clone(&accepted);
+                std::thread::spawn(move || {
+                    let _ = stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT));
+                    let Ok(ws) = tungstenite::accept(stream) else { return };
+                    let _ = ws.get_ref().set_read_timeout(None);
+                    let _ = ws.get_ref().set_write_timeout(Some(WRITE_TIMEOUT));
+                    if let Ok(mut clients) = clients.lock() {
+                        clients.push(ws);
+                    }
+                });
+            }
+        });
+        Ok(Self { clients, addr })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// A subscriber for `LogParser::subscribe_events` that sends to the current clients.
+    pub fn subscriber(&self) -> Box<dyn EventSubscriber> {
+        Box::new(WsSubscriber(Arc::clone(&self.clients)))
+    }
+}
+
+struct WsSubscriber(Clients);
+
+impl EventSubscriber for WsSubscriber {
+    /// Never fails: losing every client just leaves nobody to send to until one connects.
+    fn publish(&mut self, event: &StreamedEvent<'_>) -> io::Result<()> {
+        let Ok(mut clients) = self.0.lock() else { return Ok(()) };
+        if clients.is_empty() {
+            return Ok(());
+        }
+        let json = serde_json::to_string(event)?;
+        clients.retain_mut(|ws| ws.send(Message::text(json.clone())).is_ok());
+        Ok(())
+    }
+}
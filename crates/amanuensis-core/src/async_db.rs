@@ -0,0 +1,190 @@
+//! Async facade over [`Database`] (feature `async`).
+//!
+//! `Database` wraps a single blocking SQLite connection, so async callers (an HTTP server, a
+//! chat bot, the Tauri backend) would each have to hand-roll `spawn_blocking` plumbing.
+//! `AsyncDatabase` does it once: it keeps a small pool of connections to one database file,
+//! and each call checks a connection out, runs the query on tokio's blocking thread pool, and
+//! returns the connection afterwards. Calls can run concurrently; SQLite serializes writers
+//! (the connections wait out each other's write locks, see `Database::open`).
+//!
+//! Needs a tokio runtime. The wrappers cover the main read APIs; anything else goes through
+//! [`AsyncDatabase::run`].
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::db::{Database, LogSearchResult};
+use crate::error::{AmanuensisError, Result};
+use crate::leaderboard::{LeaderboardEntry, LeaderboardMetric};
+use crate::models::{Character, Kill, Lasty, Pet, ProcessLog, Trainer};
+use crate::summary::CharacterSummary;
+
+/// Idle connections kept open for reuse; busier moments open extra ones that are closed
+/// when returned.
+pub const DEFAULT_MAX_IDLE: usize = 4;
+
+struct Pool {
+    path: PathBuf,
+    max_idle: usize,
+    idle: Mutex<Vec<Database>>,
+}
+
+impl Pool {
+    fn checkout(&self) -> Result<Database> {
+        if let Some(db) = self.idle.lock().map_err(|e| AmanuensisError::Data(format!("Lock poisoned: {e}")))?.pop() {
+            return Ok(db);
+        }
+        Database::open(&self.path.to_string_lossy())
+    }
+
+    fn checkin(&self, db: Database) {
+        if let Ok(mut idle) = self.idle.lock() {
+            if idle.len() < self.max_idle {
+                idle.push(db);
+            }
+        }
+    }
+}
+
+/// A cloneable, `Send + Sync` handle to a database file for async code.
+#[derive(Clone)]
+pub struct AsyncDatabase {
+    pool: Arc<Pool>,
+}
+
+impl AsyncDatabase {
+    /// Open (or create) the database at `path`. The first connection is opened here, so
+    /// schema creation and migrations happen (and fail) up front.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_max_idle(path, DEFAULT_MAX_IDLE).await
+    }
+
+    /// [`open`](Self::open) keeping at most `max_idle` connections open between calls.
+    pub async fn open_with_max_idle(path: impl AsRef<Path>, max_idle: usize) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let first = {
+            let path = path.clone();
+            join(tokio::task::spawn_blocking(move || Database::open(&path.to_string_lossy())).await)??
+        };
+        let pool = Pool { path, max_idle, idle: Mutex::new(Vec::new()) };
+        pool.checkin(first);
+        Ok(Self { pool: Arc::new(pool) })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.pool.path
+    }
+
+    /// Run `f` with a pooled connection on the blocking thread pool.
+    pub async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Database) -> Result<T> + Send + 'static,
+    {
+        let pool = Arc::clone(&self.pool);
+        join(
+            tokio::task::spawn_blocking(move || {
+                let db = pool.checkout()?;
+                let result = f(&db);
+                pool.checkin(db);
+                result
+            })
+            .await,
+        )?
+    }
+
+    pub async fn list_characters(&self) -> Result<Vec<Character>> {
+        self.run(|db| db.list_characters()).await
+    }
+
+    pub async fn get_character(&self, name: &str) -> Result<Option<Character>> {
+        let name = name.to_string();
+        self.run(move |db| db.get_character(&name)).await
+    }
+
+    pub async fn character_summary(&self, char_id: i64) -> Result<Option<CharacterSummary>> {
+        self.run(move |db| db.character_summary(char_id)).await
+    }
+
+    pub async fn get_kills(&self, char_id: i64) -> Result<Vec<Kill>> {
+        self.run(move |db| db.get_kills_merged(char_id)).await
+    }
+
+    pub async fn get_trainers(&self, char_id: i64) -> Result<Vec<Trainer>> {
+        self.run(move |db| db.get_trainers_merged(char_id)).await
+    }
+
+    pub async fn get_pets(&self, char_id: i64) -> Result<Vec<Pet>> {
+        self.run(move |db| db.get_pets_merged(char_id)).await
+    }
+
+    pub async fn get_lastys(&self, char_id: i64) -> Result<Vec<Lasty>> {
+        self.run(move |db| db.get_lastys_merged(char_id)).await
+    }
+
+    pub async fn leaderboard(&self, metric: LeaderboardMetric) -> Result<Vec<LeaderboardEntry>> {
+        self.run(move |db| db.leaderboard(metric)).await
+    }
+
+    /// See `Database::search_log_lines`; no context lines.
+    pub async fn search_log_lines(
+        &self,
+        query: &str,
+        char_id: Option<i64>,
+        limit: i64,
+        include_speech: bool,
+    ) -> Result<Vec<LogSearchResult>> {
+        let query = query.to_string();
+        self.run(move |db| db.search_log_lines(&query, char_id, limit, include_speech, 0, 0)).await
+    }
+
+    pub async fn get_process_logs(&self) -> Result<Vec<ProcessLog>> {
+        self.run(|db| db.get_process_logs()).await
+    }
+}
+
+/// A blocking task that panicked or was cancelled becomes an error instead of a panic.
+fn join<T>(joined: std::result::Result<T, tokio::task::JoinError>) -> Result<T> {
+    joined.map_err(|e| AmanuensisError::Data(format!("Database task failed: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread().build().unwrap()
+    }
+
+    #[test]
+    fn concurrent_calls_share_pooled_connections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("async.db");
+        runtime().block_on(async {
+            let db = AsyncDatabase::open_with_max_idle(&path, 2).await.unwrap();
+            db.run(|db| {
+                let c = db.get_or_create_character("Gandor")?;
+                db.increment_character_field(c, "logins", 1)?;
+                db.upsert_kill(c, "Rat", "killed_count", 2, "2024-01-01 10:00:00")
+            })
+            .await
+            .unwrap();
+
+            let handles: Vec<_> = (0..6)
+                .map(|_| {
+                    let db = db.clone();
+                    tokio::spawn(async move { db.get_character("Gandor").await })
+                })
+                .collect();
+            for h in handles {
+                assert_eq!(h.await.unwrap().unwrap().unwrap().name, "Gandor");
+            }
+            assert!(db.pool.idle.lock().unwrap().len() <= 2);
+
+            let id = db.list_characters().await.unwrap()[0].id.unwrap();
+            assert_eq!(db.get_kills(id).await.unwrap()[0].killed_count, 1);
+            let err = db.run(|_| -> Result<()> { panic!("boom") }).await.unwrap_err();
+            assert!(err.to_string().contains("Database task failed"));
+        });
+    }
+}
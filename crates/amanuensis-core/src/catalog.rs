@@ -0,0 +1,324 @@
+//! Creature catalog ("bestiary completion").
+//!
+//! Every creature in the bundled bestiary with its value and, for a character, whether they
+//! have killed it, been killed by it, both, or never met it. Kill rows are matched to
+//! bestiary entries through the same alias lookup the scanner uses, so "the Ramandu" and
+//! renamed creatures land on their bestiary entry. Inline-alias creatures (no bestiary
+//! entry) are not listed.
+//!
+//! `trainer_coverage` is the trainer-side counterpart: every catalog trainer with a
+//! character's effective ranks and whether the trainer has nothing more to teach them.
+//!
+//! `catalog_diff` goes the other way: creatures in the kills table that the catalog can't
+//! value, for contributing bestiary entries (or a creatures CSV, see `CreatureDb::apply_csv`).
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::date::Timestamp;
+use crate::data::{CreatureDb, TrainerDb, TrainerMeta};
+use crate::db::Database;
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Encounter {
+    /// Killed (solo or assisted) and never died to it.
+    Killed,
+    /// Died to it without ever killing it.
+    KilledBy,
+    /// Both killed it and died to it.
+    Both,
+    Never,
+}
+
+impl Encounter {
+    pub fn as_label(self) -> &'static str {
+        match self {
+            Encounter::Killed => "killed",
+            Encounter::KilledBy => "killed by",
+            Encounter::Both => "killed + killed by",
+            Encounter::Never => "never",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub value: i32,
+    /// Solo + assisted kills; 0 without a character.
+    pub kills: i64,
+    pub killed_by: i64,
+    /// None when the catalog was built without a character.
+    pub encounter: Option<Encounter>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CreatureCatalog {
+    pub bestiary_version: String,
+    /// Alphabetical by name, ignoring case.
+    pub creatures: Vec<CatalogEntry>,
+    /// Creatures the character has killed at least once (0 without a character).
+    pub killed: usize,
+}
+
+impl CreatureCatalog {
+    /// Share of catalog creatures killed, in percent.
+    pub fn completion(&self) -> f64 {
+        if self.creatures.is_empty() {
+            return 0.0;
+        }
+        self.killed as f64 * 100.0 / self.creatures.len() as f64
+    }
+}
+
+/// A creature met in the logs that the catalog has no entry for.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MissingCreature {
+    /// As logged.
+    pub name: String,
+    /// Solo + assisted kills.
+    pub kills: i64,
+    pub killed_by: i64,
+    /// Characters that have met it.
+    pub characters: i64,
+    pub first_date: Option<Timestamp>,
+    pub last_date: Option<Timestamp>,
+}
+
+/// A catalog trainer with a character's standing.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainerCoverageEntry {
+    #[serde(flatten)]
+    pub trainer: TrainerMeta,
+    /// Effective ranks (merged across linked characters).
+    pub ranks: i64,
+    pub maxed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainerCoverage {
+    /// Catalog order (alphabetical).
+    pub trainers: Vec<TrainerCoverageEntry>,
+    /// Catalog trainers with at least one rank.
+    pub trained: usize,
+    /// Catalog trainers that have nothing more to teach.
+    pub maxed: usize,
+}
+
+impl TrainerCoverage {
+    /// Share of catalog trainers trained, in percent.
+    pub fn coverage(&self) -> f64 {
+        if self.trainers.is_empty() {
+            return 0.0;
+        }
+        self.trained as f64 * 100.0 / self.trainers.len() as f64
+    }
+}
+
+impl Database {
+    /// The trainer catalog annotated with a (possibly merged) character's ranks and maxed
+    /// trainers. Trainers missing from the catalog are not listed.
+    pub fn trainer_coverage(&self, trainer_db: &TrainerDb, char_id: i64) -> Result<TrainerCoverage> {
+        let own: HashMap<String, (i64, bool)> = self
+            .get_trainers_merged(char_id)?
+            .into_iter()
+            .map(|t| (t.trainer_name.clone(), (t.effective_ranks(), t.maxed)))
+            .collect();
+        let trainers: Vec<TrainerCoverageEntry> = trainer_db
+            .all_trainer_metadata()
+            .into_iter()
+            .map(|meta| {
+                let (ranks, maxed) = own.get(&meta.name).copied().unwrap_or_default();
+                TrainerCoverageEntry { trainer: meta, ranks, maxed }
+            })
+            .collect();
+        Ok(TrainerCoverage {
+            trained: trainers.iter().filter(|t| t.ranks > 0).count(),
+            maxed: trainers.iter().filter(|t| t.maxed).count(),
+            trainers,
+        })
+    }
+
+    /// Creatures in the kills table that `creatures` has no entry for (by the same lookup the
+    /// scanner uses), for one (possibly merged) character or, with None, every character.
+    /// Most encounters first.
+    pub fn catalog_diff(&self, creatures: &CreatureDb, char_id: Option<i64>) -> Result<Vec<MissingCreature>> {
+        let char_ids = match char_id {
+            Some(id) => vec![id],
+            None => self.list_characters()?.into_iter().filter_map(|c| c.id).collect(),
+        };
+        let mut missing: HashMap<String, MissingCreature> = HashMap::new();
+        for id in char_ids {
+            for kill in self.get_kills_merged(id)? {
+                if kill.total_all() + kill.killed_by_count == 0 || creatures.get_entry(&kill.creature_name).is_some() {
+                    continue;
+                }
+                let m = missing.entry(kill.creature_name.clone()).or_insert_with(|| MissingCreature {
+                    name: kill.creature_name.clone(),
+                    kills: 0,
+                    killed_by: 0,
+                    characters: 0,
+                    first_date: None,
+                    last_date: None,
+                });
+                m.kills += kill.total_all();
+                m.killed_by += kill.killed_by_count;
+                m.characters += 1;
+                m.first_date = m.first_date.take().into_iter().chain(kill.date_first).min();
+                m.last_date = m.last_date.take().into_iter().chain(kill.date_last).max();
+            }
+        }
+        let mut missing: Vec<MissingCreature> = missing.into_values().collect();
+        missing.sort_by(|a, b| (b.kills + b.killed_by).cmp(&(a.kills + a.killed_by)).then(a.name.cmp(&b.name)));
+        Ok(missing)
+    }
+
+    /// The catalog of `creatures`, annotated with a (possibly merged) character's kills and
+    /// deaths when `char_id` is given.
+    pub fn creature_catalog(&self, creatures: &CreatureDb, char_id: Option<i64>) -> Result<CreatureCatalog> {
+        // Bestiary name -> (kills, killed_by)
+        let mut tally: HashMap<&str, (i64, i64)> = HashMap::new();
+        let kills = match char_id {
+            Some(id) => self.get_kills_merged(id)?,
+            None => Vec::new(),
+        };
+        for kill in &kills {
+            if let Some(entry) = creatures.get_entry(&kill.creature_name) {
+                let t = tally.entry(entry.name.as_str()).or_default();
+                t.0 += kill.total_all();
+                t.1 += kill.killed_by_count;
+            }
+        }
+
+        let mut entries: Vec<CatalogEntry> = creatures
+            .entries()
+            .map(|e| {
+                let (kills, killed_by) = tally.get(e.name.as_str()).copied().unwrap_or_default();
+                let encounter = char_id.map(|_| match (kills > 0, killed_by > 0) {
+                    (true, true) => Encounter::Both,
+                    (true, false) => Encounter::Killed,
+                    (false, true) => Encounter::KilledBy,
+                    (false, false) => Encounter::Never,
+                });
+                CatalogEntry { name: e.name.clone(), value: e.exp_taxidermy, kills, killed_by, encounter }
+            })
+            .collect();
+        entries.sort_by_cached_key(|e| e.name.to_lowercase());
+        let killed = entries.iter().filter(|e| e.kills > 0).count();
+
+        Ok(CreatureCatalog {
+            bestiary_version: creatures.bestiary_version().to_string(),
+            creatures: entries,
+            killed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bestiary::{BestiaryEntry, BestiaryFile};
+
+    #[test]
+    fn catalog_marks_encounters_through_aliases() {
+        let file = BestiaryFile {
+            version: "20260101".into(),
+            entries: [("Rat", 2), ("Orga", 40), ("Ramandu", 300), ("Vermine", 5)]
+                .iter()
+                .map(|(name, value)| BestiaryEntry {
+                    name: (*name).into(),
+                    exp_taxidermy: *value,
+                    ..BestiaryEntry::default()
+                })
+                .collect(),
+        };
+        let creatures = CreatureDb::from_json_bytes(
+            &serde_json::to_vec(&file).unwrap(),
+            br#"[{"log_name": "Orga Warrior", "resolves_to": "Orga"}]"#,
+        )
+        .unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.upsert_kill(c, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(c, "Orga Warrior", "assisted_kill_count", 40, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(c, "Orga Warrior", "killed_by_count", 40, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(c, "the Ramandu", "killed_by_count", 300, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(c, "Dream Beast", "killed_count", 0, "2024-01-01 10:00:00").unwrap();
+
+        let catalog = db.creature_catalog(&creatures, Some(c)).unwrap();
+        let rows: Vec<(&str, i64, Option<Encounter>)> =
+            catalog.creatures.iter().map(|e| (e.name.as_str(), e.kills, e.encounter)).collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Orga", 1, Some(Encounter::Both)),
+                ("Ramandu", 0, Some(Encounter::KilledBy)),
+                ("Rat", 1, Some(Encounter::Killed)),
+                ("Vermine", 0, Some(Encounter::Never)),
+            ]
+        );
+        assert_eq!(catalog.killed, 2);
+        assert_eq!(catalog.completion(), 50.0);
+
+        let plain = db.creature_catalog(&creatures, None).unwrap();
+        assert!(plain.creatures.iter().all(|e| e.encounter.is_none() && e.kills == 0));
+        assert_eq!(plain.creatures[2].value, 2);
+    }
+
+    #[test]
+    fn catalog_diff_lists_creatures_the_catalog_cannot_value() {
+        let mut creatures = CreatureDb::from_json_bytes(
+            &serde_json::to_vec(&BestiaryFile {
+                version: "20260101".into(),
+                entries: vec![BestiaryEntry { name: "Rat".into(), exp_taxidermy: 2, ..BestiaryEntry::default() }],
+            })
+            .unwrap(),
+            b"[]",
+        )
+        .unwrap();
+        let db = Database::open_in_memory().unwrap();
+        for name in ["Gandor", "Fen"] {
+            let c = db.get_or_create_character(name).unwrap();
+            db.increment_character_field(c, "logins", 1).unwrap();
+            db.upsert_kill(c, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+            db.upsert_kill(c, "Dream Beast", "killed_count", 0, "2024-01-02 10:00:00").unwrap();
+        }
+        let fen = db.get_character("Fen").unwrap().unwrap().id.unwrap();
+        db.upsert_kill(fen, "Glass Wyrm", "killed_by_count", 0, "2024-01-03 10:00:00").unwrap();
+
+        let all: Vec<(String, i64, i64, i64)> = db
+            .catalog_diff(&creatures, None)
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.name, m.kills, m.killed_by, m.characters))
+            .collect();
+        assert_eq!(all, vec![("Dream Beast".into(), 2, 0, 2), ("Glass Wyrm".into(), 0, 1, 1)]);
+        assert_eq!(db.catalog_diff(&creatures, Some(fen)).unwrap().len(), 2);
+
+        creatures.apply_csv("name,value\nDream Beast,40\n").unwrap();
+        assert_eq!(db.catalog_diff(&creatures, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn trainer_coverage_counts_trained_and_maxed() {
+        let tdb = TrainerDb::bundled().unwrap();
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.upsert_trainer_rank(c, "Histia", "2024-01-01 10:00:00", 1.0).unwrap();
+        db.set_trainer_maxed(c, "Histia", 1.0).unwrap();
+        db.set_modified_ranks(c, "Atkus", 5).unwrap();
+        db.set_trainer_maxed(c, "Not A Trainer", 1.0).unwrap();
+
+        let coverage = db.trainer_coverage(&tdb, c).unwrap();
+        assert_eq!(coverage.trainers.len(), tdb.all_trainer_metadata().len());
+        assert_eq!(coverage.trained, 2);
+        assert_eq!(coverage.maxed, 1);
+        let histia = coverage.trainers.iter().find(|t| t.trainer.name == "Histia").unwrap();
+        assert_eq!((histia.ranks, histia.maxed), (1, true));
+        assert!(coverage.coverage() > 0.0);
+    }
+}
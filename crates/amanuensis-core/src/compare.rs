@@ -0,0 +1,169 @@
+//! Side-by-side comparison of two characters.
+//!
+//! Both characters are read through the merged views (an alt folded into a main counts
+//! under the main), so the comparison matches what each character's own pages show. Deltas
+//! are always `right - left`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::data::TrainerDb;
+use crate::db::Database;
+use crate::error::{AmanuensisError, Result};
+use crate::fighter_stats::{compute_fighter_stats_with, FighterModifiers, FighterStats};
+
+/// One trainer's effective ranks (respecting each side's rank mode).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TrainerComparison {
+    pub trainer: String,
+    pub left: i64,
+    pub right: i64,
+}
+
+/// One creature's solo + assisted kill totals.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KillComparison {
+    pub creature: String,
+    pub left: i64,
+    pub right: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StatComparison {
+    pub stat: &'static str,
+    pub left: f64,
+    pub right: f64,
+    pub delta: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CharacterComparison {
+    pub left: String,
+    pub right: String,
+    /// Trainers either character has ranks with, most ranks (on either side) first.
+    pub trainers: Vec<TrainerComparison>,
+    /// Creatures either character has killed, most kills (on either side) first.
+    pub kills: Vec<KillComparison>,
+    /// Character totals, then the fighter stats for each side's saved loadout.
+    pub stats: Vec<StatComparison>,
+}
+
+struct Side {
+    name: String,
+    ranks: BTreeMap<String, i64>,
+    kills: BTreeMap<String, i64>,
+    totals: [(&'static str, f64); 6],
+    fighter: FighterStats,
+}
+
+impl Database {
+    /// Compare two (possibly merged) characters. Multipliers come from `trainer_db`.
+    pub fn compare_characters(&self, left: i64, right: i64, trainer_db: &TrainerDb) -> Result<CharacterComparison> {
+        let modifiers = FighterModifiers::bundled()?;
+        let left = self.comparison_side(left, trainer_db, &modifiers)?;
+        let right = self.comparison_side(right, trainer_db, &modifiers)?;
+
+        let mut trainers: Vec<TrainerComparison> = paired(&left.ranks, &right.ranks)
+            .map(|(trainer, left, right)| TrainerComparison { trainer, left, right })
+            .collect();
+        trainers.sort_by(|a, b| b.left.max(b.right).cmp(&a.left.max(a.right)).then(a.trainer.cmp(&b.trainer)));
+        let mut kills: Vec<KillComparison> = paired(&left.kills, &right.kills)
+            .map(|(creature, left, right)| KillComparison { creature, left, right })
+            .collect();
+        kills.sort_by(|a, b| b.left.max(b.right).cmp(&a.left.max(a.right)).then(a.creature.cmp(&b.creature)));
+
+        let stats = left
+            .totals
+            .iter()
+            .chain(left.fighter.numeric_fields().iter())
+            .zip(right.totals.iter().chain(right.fighter.numeric_fields().iter()))
+            .map(|(&(stat, l), &(_, r))| StatComparison { stat, left: l, right: r, delta: r - l })
+            .collect();
+
+        Ok(CharacterComparison { left: left.name, right: right.name, trainers, kills, stats })
+    }
+
+    fn comparison_side(&self, char_id: i64, trainer_db: &TrainerDb, modifiers: &FighterModifiers) -> Result<Side> {
+        let c = self
+            .get_character_merged(char_id)?
+            .ok_or_else(|| AmanuensisError::CharacterNotFound { name: char_id.to_string() })?;
+
+        let mut ranks = BTreeMap::new();
+        let mut multipliers = HashMap::new();
+        for t in self.get_trainers_merged(char_id)? {
+            let effective = t.effective_ranks();
+            if effective > 0 {
+                multipliers.insert(t.trainer_name.clone(), trainer_db.get_multiplier(&t.trainer_name));
+                ranks.insert(t.trainer_name, effective);
+            }
+        }
+        let mut kills = BTreeMap::new();
+        for k in self.get_kills_merged(char_id)? {
+            let total = k.total_all();
+            if total > 0 {
+                *kills.entry(k.creature_name).or_insert(0) += total;
+            }
+        }
+
+        let loadout = modifiers.resolve(&self.get_fighter_loadout(char_id)?.unwrap_or_default())?;
+        let rank_map: HashMap<String, i64> = ranks.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        let fighter = compute_fighter_stats_with(&rank_map, &multipliers, &loadout);
+        let totals = [
+            ("logins", c.logins as f64),
+            ("deaths", c.deaths as f64),
+            ("departs", c.departs as f64),
+            ("esteem", c.esteem as f64),
+            ("coins_picked_up", c.coins_picked_up as f64),
+            ("kills", kills.values().sum::<i64>() as f64),
+        ];
+        Ok(Side { name: c.name, ranks, kills, totals, fighter })
+    }
+}
+
+/// Every key of either map with both sides' values (0 when missing).
+fn paired<'a>(
+    left: &'a BTreeMap<String, i64>,
+    right: &'a BTreeMap<String, i64>,
+) -> impl Iterator<Item = (String, i64, i64)> + 'a {
+    let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter().map(|k| (k.clone(), left.get(k).copied().unwrap_or(0), right.get(k).copied().unwrap_or(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_ranks_kills_and_stats_as_right_minus_left() {
+        let db = Database::open_in_memory().unwrap();
+        let trainer_db = TrainerDb::bundled().unwrap();
+        let a = db.get_or_create_character("Alpha").unwrap();
+        let b = db.get_or_create_character("Beta").unwrap();
+        for _ in 0..3 {
+            db.upsert_trainer_rank(a, "Bangus Anmash", "2024-01-01 10:00:00", 1.0).unwrap();
+        }
+        db.upsert_trainer_rank(b, "Regia", "2024-01-01 10:00:00", 1.0).unwrap();
+        db.upsert_kill(a, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(b, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(b, "Rat", "slaughtered_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.increment_character_field(b, "deaths", 4).unwrap();
+
+        let cmp = db.compare_characters(a, b, &trainer_db).unwrap();
+        assert_eq!((cmp.left.as_str(), cmp.right.as_str()), ("Alpha", "Beta"));
+        let trainers: Vec<(&str, i64, i64)> = cmp.trainers.iter().map(|t| (t.trainer.as_str(), t.left, t.right)).collect();
+        assert_eq!(trainers, [("Bangus Anmash", 3, 0), ("Regia", 0, 1)]);
+        assert_eq!(cmp.kills, [KillComparison { creature: "Rat".into(), left: 1, right: 2 }]);
+
+        let stat = |name: &str| cmp.stats.iter().find(|s| s.stat == name).unwrap();
+        assert_eq!((stat("deaths").left, stat("deaths").right, stat("deaths").delta), (0.0, 4.0, 4.0));
+        assert_eq!(stat("kills").delta, 1.0);
+        assert_eq!((stat("trained_ranks").left, stat("trained_ranks").right), (3.0, 1.0));
+        assert_eq!(stat("trained_ranks").delta, -2.0);
+
+        let err = db.compare_characters(a, 9999, &trainer_db).unwrap_err();
+        assert_eq!(err.kind(), "character_not_found");
+    }
+}
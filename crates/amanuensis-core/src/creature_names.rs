@@ -0,0 +1,317 @@
+//! User rules that fold creature-name variants onto one canonical name.
+//!
+//! Creatures show up with size, rank and color forms ("Large Vermine", "Greater Death",
+//! "Blue Arachnoid") that some players want counted as one creature and others want kept
+//! apart. A rules file lists regexes, each matched against the whole name, and the name to
+//! use instead:
+//!
+//! ```json
+//! { "rules": [
+//!     { "pattern": "(?:Large|Giant) (Vermine|Rat)", "name": "$1" },
+//!     { "pattern": "(?:Red|Blue|Green) Arachnoid", "name": "Arachnoid" }
+//! ] }
+//! ```
+//!
+//! The first matching rule wins; `$1`-style references take capture groups. Rules are stored
+//! with the database (`SETTING_CREATURE_NAME_RULES`) and only apply while turned on
+//! (`SETTING_CREATURE_NAME_RULES_ENABLED`): scans then record kills, attacks and deaths under
+//! the canonical name, and `get_kills_merged` folds rows already stored under a variant.
+//! Turning them off shows stored rows as they are again.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::date::Timestamp;
+use crate::db::queries::{SETTING_CREATURE_NAME_RULES, SETTING_CREATURE_NAME_RULES_ENABLED};
+use crate::db::Database;
+use crate::error::{AmanuensisError, Result};
+use crate::models::Kill;
+use crate::parser::events::LogEvent;
+
+/// Creature names matching `pattern` (the whole name) are recorded as `name`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CreatureNameRule {
+    pub pattern: String,
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+struct RulesFile {
+    rules: Vec<CreatureNameRule>,
+}
+
+/// A compiled rule list.
+#[derive(Debug, Clone, Default)]
+pub struct CreatureNameRules {
+    rules: Vec<CreatureNameRule>,
+    compiled: Vec<Regex>,
+}
+
+/// Stored kill rows that a rule list would count as one creature.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CreatureRegroup {
+    pub canonical: String,
+    /// Kills (solo + assisted, all characters) across the variants.
+    pub kills: i64,
+    /// Stored names and their kills, most kills first.
+    pub variants: Vec<(String, i64)>,
+}
+
+impl CreatureNameRules {
+    pub fn new(rules: Vec<CreatureNameRule>) -> Result<Self> {
+        let compiled = rules
+            .iter()
+            .map(|r| {
+                Regex::new(&format!("^(?:{})$", r.pattern)).map_err(|e| {
+                    AmanuensisError::Data(format!("Invalid creature name pattern '{}': {}", r.pattern, e))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules, compiled })
+    }
+
+    /// Read a rules file (see the module docs).
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| AmanuensisError::file_unreadable(path, e))?;
+        let file: RulesFile = serde_json::from_str(&text)?;
+        Self::new(file.rules)
+    }
+
+    pub fn rules(&self) -> &[CreatureNameRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `name` as the first matching rule rewrites it, or unchanged.
+    pub fn canonical<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        for (rule, re) in self.rules.iter().zip(&self.compiled) {
+            if re.is_match(name) {
+                return Cow::Owned(re.replace(name, rule.name.as_str()).into_owned());
+            }
+        }
+        Cow::Borrowed(name)
+    }
+
+    /// Rewrite the creature names an event carries (kills, attacks, damage, deaths).
+    pub fn canonicalize_event(&self, event: &mut LogEvent) {
+        let name = match event {
+            LogEvent::SoloKill { creature, .. }
+            | LogEvent::AssistedKill { creature, .. }
+            | LogEvent::OtherKill { creature, .. }
+            | LogEvent::AttackHit { creature }
+            | LogEvent::AttackMiss { creature }
+            | LogEvent::DamageTaken { creature } => creature,
+            LogEvent::Fallen { cause, .. } => cause,
+            _ => return,
+        };
+        if let Cow::Owned(canonical) = self.canonical(name) {
+            *name = canonical;
+        }
+    }
+
+    /// Fold kill rows whose names share a canonical name into one row per creature, most
+    /// kills first. Counts add up; first dates take the earliest, last dates the latest.
+    pub fn fold_kills(&self, kills: Vec<Kill>) -> Vec<Kill> {
+        let mut folded: Vec<Kill> = Vec::with_capacity(kills.len());
+        let mut index: BTreeMap<String, usize> = BTreeMap::new();
+        for mut kill in kills {
+            let canonical = self.canonical(&kill.creature_name).into_owned();
+            match index.get(&canonical) {
+                Some(&i) => add_kill(&mut folded[i], kill),
+                None => {
+                    kill.creature_name = canonical.clone();
+                    index.insert(canonical, folded.len());
+                    folded.push(kill);
+                }
+            }
+        }
+        folded.sort_by_key(|k| std::cmp::Reverse(k.total_all()));
+        folded
+    }
+
+    /// Group `(stored name, kills)` pairs by canonical name, keeping only groups the rules
+    /// change (a renamed creature, or several names rolled into one). Most kills first.
+    pub fn regroup(&self, names: impl IntoIterator<Item = (String, i64)>) -> Vec<CreatureRegroup> {
+        let mut groups: BTreeMap<String, Vec<(String, i64)>> = BTreeMap::new();
+        for (name, kills) in names {
+            groups.entry(self.canonical(&name).into_owned()).or_default().push((name, kills));
+        }
+        let mut out: Vec<CreatureRegroup> = groups
+            .into_iter()
+            .filter(|(canonical, variants)| variants.len() > 1 || variants[0].0 != *canonical)
+            .map(|(canonical, mut variants)| {
+                variants.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                CreatureRegroup { canonical, kills: variants.iter().map(|v| v.1).sum(), variants }
+            })
+            .collect();
+        out.sort_by(|a, b| b.kills.cmp(&a.kills).then_with(|| a.canonical.cmp(&b.canonical)));
+        out
+    }
+}
+
+fn add_kill(into: &mut Kill, k: Kill) {
+    fn earliest(a: &mut Option<Timestamp>, b: Option<Timestamp>) {
+        if let Some(b) = b {
+            if a.as_ref().is_none_or(|a| b < *a) {
+                *a = Some(b);
+            }
+        }
+    }
+    fn latest(a: &mut Option<Timestamp>, b: Option<Timestamp>) {
+        if let Some(b) = b {
+            if a.as_ref().is_none_or(|a| b > *a) {
+                *a = Some(b);
+            }
+        }
+    }
+    into.id = None;
+    into.killed_count += k.killed_count;
+    into.slaughtered_count += k.slaughtered_count;
+    into.vanquished_count += k.vanquished_count;
+    into.dispatched_count += k.dispatched_count;
+    into.assisted_kill_count += k.assisted_kill_count;
+    into.assisted_slaughter_count += k.assisted_slaughter_count;
+    into.assisted_vanquish_count += k.assisted_vanquish_count;
+    into.assisted_dispatch_count += k.assisted_dispatch_count;
+    into.killed_by_count += k.killed_by_count;
+    earliest(&mut into.date_first, k.date_first);
+    latest(&mut into.date_last, k.date_last);
+    into.creature_value = into.creature_value.max(k.creature_value);
+    earliest(&mut into.date_first_killed, k.date_first_killed);
+    earliest(&mut into.date_first_slaughtered, k.date_first_slaughtered);
+    earliest(&mut into.date_first_vanquished, k.date_first_vanquished);
+    earliest(&mut into.date_first_dispatched, k.date_first_dispatched);
+    latest(&mut into.date_last_killed, k.date_last_killed);
+    latest(&mut into.date_last_slaughtered, k.date_last_slaughtered);
+    latest(&mut into.date_last_vanquished, k.date_last_vanquished);
+    latest(&mut into.date_last_dispatched, k.date_last_dispatched);
+    if k.best_loot_value > into.best_loot_value {
+        into.best_loot_value = k.best_loot_value;
+        into.best_loot_item = k.best_loot_item;
+    }
+}
+
+impl Database {
+    /// The stored rules, whether or not they are turned on.
+    pub fn creature_name_rules(&self) -> Result<Vec<CreatureNameRule>> {
+        Ok(self
+            .get_setting(SETTING_CREATURE_NAME_RULES)?
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default())
+    }
+
+    /// Replace the stored rules (empty clears them). Invalid patterns are rejected.
+    pub fn set_creature_name_rules(&self, rules: &[CreatureNameRule]) -> Result<()> {
+        CreatureNameRules::new(rules.to_vec())?;
+        if rules.is_empty() {
+            self.delete_setting(SETTING_CREATURE_NAME_RULES)
+        } else {
+            self.set_setting(SETTING_CREATURE_NAME_RULES, &serde_json::to_string(rules)?)
+        }
+    }
+
+    /// Whether the stored rules apply. Off unless turned on.
+    pub fn creature_name_rules_enabled(&self) -> Result<bool> {
+        Ok(self.get_setting(SETTING_CREATURE_NAME_RULES_ENABLED)?.as_deref() == Some("true"))
+    }
+
+    pub fn set_creature_name_rules_enabled(&self, enabled: bool) -> Result<()> {
+        self.set_setting(SETTING_CREATURE_NAME_RULES_ENABLED, if enabled { "true" } else { "false" })
+    }
+
+    /// The rules scans and kill queries apply: None when turned off or empty.
+    pub fn active_creature_name_rules(&self) -> Result<Option<CreatureNameRules>> {
+        if !self.creature_name_rules_enabled()? {
+            return Ok(None);
+        }
+        let rules = CreatureNameRules::new(self.creature_name_rules()?)?;
+        Ok((!rules.is_empty()).then_some(rules))
+    }
+
+    /// How `rules` would regroup the kill rows stored for every character.
+    pub fn preview_creature_regroup(&self, rules: &CreatureNameRules) -> Result<Vec<CreatureRegroup>> {
+        let mut stmt = self.conn().prepare(
+            "SELECT creature_name,
+                    SUM(killed_count + slaughtered_count + vanquished_count + dispatched_count
+                        + assisted_kill_count + assisted_slaughter_count + assisted_vanquish_count
+                        + assisted_dispatch_count)
+             FROM kills GROUP BY creature_name",
+        )?;
+        let names = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect::<Vec<_>>();
+        Ok(rules.regroup(names))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> CreatureNameRules {
+        CreatureNameRules::new(vec![
+            CreatureNameRule { pattern: "(?:Large|Giant) (Vermine|Rat)".into(), name: "$1".into() },
+            CreatureNameRule { pattern: "(?:Red|Blue) Arachnoid".into(), name: "Arachnoid".into() },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn canonical_matches_whole_names_in_order() {
+        let rules = rules();
+        assert_eq!(rules.canonical("Large Vermine"), "Vermine");
+        assert_eq!(rules.canonical("Blue Arachnoid"), "Arachnoid");
+        assert!(matches!(rules.canonical("Very Large Vermine"), Cow::Borrowed(_)));
+        assert!(matches!(rules.canonical("Rat"), Cow::Borrowed(_)));
+
+        let bad = CreatureNameRules::new(vec![CreatureNameRule { pattern: "(".into(), name: "x".into() }]);
+        assert_eq!(bad.unwrap_err().kind(), "data");
+    }
+
+    #[test]
+    fn kills_fold_and_regroup_under_canonical_names() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Fen").unwrap();
+        for (creature, field, date) in [
+            ("Vermine", "killed_count", "2024-01-02 10:00:00"),
+            ("Large Vermine", "slaughtered_count", "2024-01-01 10:00:00"),
+            ("Large Vermine", "slaughtered_count", "2024-01-03 10:00:00"),
+            ("Rat", "killed_count", "2024-01-01 10:00:00"),
+        ] {
+            db.upsert_kill(c, creature, field, 5, date).unwrap();
+        }
+        let rules = rules();
+
+        let preview = db.preview_creature_regroup(&rules).unwrap();
+        assert_eq!(preview.len(), 1);
+        assert_eq!(preview[0].canonical, "Vermine");
+        assert_eq!(preview[0].variants, vec![("Large Vermine".to_string(), 2), ("Vermine".to_string(), 1)]);
+
+        // Off by default: kills are listed as stored
+        db.set_creature_name_rules(rules.rules()).unwrap();
+        assert_eq!(db.get_kills_merged(c).unwrap().len(), 3);
+        db.set_creature_name_rules_enabled(true).unwrap();
+        let kills = db.get_kills_merged(c).unwrap();
+        assert_eq!(kills.len(), 2);
+        let vermine = &kills[0];
+        assert_eq!(vermine.creature_name, "Vermine");
+        assert_eq!((vermine.killed_count, vermine.slaughtered_count), (1, 2));
+        assert_eq!(vermine.date_first.unwrap(), "2024-01-01 10:00:00");
+        assert_eq!(vermine.date_last.unwrap(), "2024-01-03 10:00:00");
+
+        assert!(db
+            .set_creature_name_rules(&[CreatureNameRule { pattern: "[".into(), name: "x".into() }])
+            .is_err());
+        db.set_creature_name_rules(&[]).unwrap();
+        assert!(db.active_creature_name_rules().unwrap().is_none());
+    }
+}
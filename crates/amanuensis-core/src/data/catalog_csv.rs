@@ -0,0 +1,102 @@
+//! Reading user-supplied catalog CSVs (`TrainerDb::apply_csv`, `CreatureDb::apply_csv`).
+
+use std::collections::HashMap;
+
+/// One data row: column name -> trimmed value, and its 1-based line number.
+pub(crate) type CatalogRow<'c> = (usize, HashMap<&'c str, String>);
+
+/// Read a catalog CSV whose header names some of `columns`, in any order. Every `required`
+/// column must be present and non-empty in each row, and the `key` column (after `normalize`,
+/// which also replaces the stored value) must be unique. Blank lines are skipped. Problems go
+/// to `problems` with line numbers so the caller can add its own and report them together;
+/// rows with a problem are left out.
+pub(crate) fn read_catalog_csv<'c>(
+    text: &str,
+    columns: &[&'c str],
+    required: &[&str],
+    key: &'c str,
+    normalize: fn(&str) -> String,
+    problems: &mut Vec<String>,
+) -> Vec<CatalogRow<'c>> {
+    let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        problems.push("catalog is empty".to_string());
+        return Vec::new();
+    };
+    let mut header_columns: Vec<&'c str> = Vec::new();
+    for name in split_csv_line(header) {
+        match columns.iter().find(|c| c.eq_ignore_ascii_case(&name)) {
+            Some(c) => header_columns.push(c),
+            None => problems.push(format!("unknown column '{}' (expected: {})", name, columns.join(", "))),
+        }
+    }
+    for column in required {
+        if !header_columns.contains(column) {
+            problems.push(format!("missing column '{}'", column));
+        }
+    }
+    if !problems.is_empty() {
+        return Vec::new();
+    }
+
+    let mut rows = Vec::new();
+    let mut first_line: HashMap<String, usize> = HashMap::new();
+    for (i, line) in lines {
+        let line_no = i + 1;
+        let fields = split_csv_line(line);
+        if fields.len() != header_columns.len() {
+            problems.push(format!(
+                "line {}: expected {} fields, found {}",
+                line_no,
+                header_columns.len(),
+                fields.len()
+            ));
+            continue;
+        }
+        let mut row: HashMap<&'c str, String> = header_columns.iter().copied().zip(fields).collect();
+        let value = normalize(&row[key]);
+        row.insert(key, value.clone());
+        if required.iter().any(|c| row[c].is_empty()) {
+            problems.push(format!("line {}: {} are required", line_no, required.join(" and ")));
+            continue;
+        }
+        if let Some(first) = first_line.get(&value) {
+            problems.push(format!("line {}: duplicate {} '{}' (first on line {})", line_no, key, value, first));
+            continue;
+        }
+        first_line.insert(value, line_no);
+        rows.push((line_no, row));
+    }
+    rows
+}
+
+/// Split one CSV line into trimmed fields. Double-quoted fields may contain commas and
+/// `""` for a literal quote.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// A CSV boolean; empty is false.
+pub(crate) fn parse_flag(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "" | "false" | "no" | "0" => Some(false),
+        "true" | "yes" | "1" => Some(true),
+        _ => None,
+    }
+}
@@ -1,8 +1,25 @@
 use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Serialize;
 
 use crate::data::bestiary::{BestiaryAlias, BestiaryEntry, BestiaryFile, EntrySource, InlineEntry};
+use crate::data::catalog_csv::read_catalog_csv;
 use crate::error::{AmanuensisError, Result};
 
+/// Columns of a creature catalog CSV (see [`CreatureDb::apply_csv`]). `name` and `value`
+/// are required.
+pub const CREATURE_CSV_COLUMNS: &[&str] = &["name", "value", "family", "location", "rarity"];
+
+/// What a creature catalog CSV changed in the catalog it was applied to.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CreatureCatalogUpdate {
+    /// Creatures the catalog didn't know.
+    pub added: usize,
+    /// Bestiary entries or aliases the CSV replaced.
+    pub replaced: usize,
+}
+
 /// In-memory bestiary lookup, loaded from bestiary.json + bestiary_aliases.json.
 #[derive(Debug)]
 pub struct CreatureDb {
@@ -91,6 +108,73 @@ impl CreatureDb {
         )
     }
 
+    /// The bundled bestiary with the creature catalog CSV at `catalog` applied on top.
+    pub fn load(catalog: Option<&Path>) -> Result<Self> {
+        let mut db = Self::bundled()?;
+        if let Some(path) = catalog {
+            let text = std::fs::read_to_string(path).map_err(|e| AmanuensisError::file_unreadable(path, e))?;
+            let update = db.apply_csv(&text).map_err(|e| match e {
+                AmanuensisError::Data(msg) => AmanuensisError::Data(format!("{}: {}", path.display(), msg)),
+                e => e,
+            })?;
+            log::info!(
+                "Creature catalog {}: {} creatures added, {} replaced",
+                path.display(),
+                update.added,
+                update.replaced
+            );
+        }
+        Ok(db)
+    }
+
+    /// Add creatures or override values from CSV text with a header row naming some of
+    /// [`CREATURE_CSV_COLUMNS`] (fields may be quoted; blank lines are skipped). `name` is the
+    /// creature as logged. A bestiary entry of that name keeps its other details, with any
+    /// non-empty CSV field replacing them; an alias of that name is dropped so the CSV entry
+    /// wins. Nothing is applied unless the whole file is valid: unknown or missing columns, a
+    /// name listed twice and non-numeric values are reported together as one error.
+    pub fn apply_csv(&mut self, text: &str) -> Result<CreatureCatalogUpdate> {
+        let mut problems = Vec::new();
+        let rows = read_catalog_csv(
+            text,
+            CREATURE_CSV_COLUMNS,
+            &["name", "value"],
+            "name",
+            |n| n.trim().to_string(),
+            &mut problems,
+        );
+        for (line_no, row) in &rows {
+            if row["value"].parse::<i32>().is_err() {
+                problems.push(format!("line {}: value '{}' is not a whole number", line_no, row["value"]));
+            }
+        }
+        if !problems.is_empty() {
+            return Err(AmanuensisError::Data(problems.join("; ")));
+        }
+
+        let mut update = CreatureCatalogUpdate::default();
+        for (_, row) in rows {
+            let name = row["name"].clone();
+            let field = |column: &str| row.get(column).filter(|v| !v.is_empty()).cloned();
+            let was_alias = self.aliases.remove(&name).is_some();
+            if was_alias || self.by_name.contains_key(&name) {
+                update.replaced += 1;
+            } else {
+                update.added += 1;
+            }
+            let entry = self
+                .by_name
+                .entry(name.clone())
+                .or_insert_with(|| BestiaryEntry { name, ..BestiaryEntry::default() });
+            entry.exp_taxidermy = row["value"].parse().unwrap_or_default();
+            entry.family = field("family").or(entry.family.take());
+            entry.location = field("location").or(entry.location.take());
+            entry.rarity = field("rarity").or(entry.rarity.take());
+        }
+        self.family_canonical = build_family_canonical(self.by_name.values());
+        Ok(update)
+    }
+
     /// Look up a creature's exp_taxidermy value by log name.
     /// Lookup order: aliases → bestiary direct → strip "the " and retry.
     pub fn get_value(&self, log_name: &str) -> Option<i32> {
@@ -102,6 +186,34 @@ impl CreatureDb {
         self.get_entry_with_source(log_name).map(|(e, _)| e)
     }
 
+    /// The broad area a creature is found in, from the head of its bestiary `location`
+    /// ("Eastern Mountains (Windweft and Foghaven Glacier)" → "Eastern Mountains").
+    /// `None` for creatures without a location or found "everywhere", which say nothing
+    /// about where the character is.
+    pub fn get_area(&self, log_name: &str) -> Option<String> {
+        let location = self.get_entry(log_name)?.location.as_deref()?;
+        let head = location
+            .split([',', ';', '.'])
+            .next()
+            .and_then(|s| s.split(" (").next())
+            .unwrap_or("")
+            .trim();
+        let lower = head.to_ascii_lowercase();
+        if head.is_empty() || lower == "everywhere" || lower == "nearly everywhere" {
+            return None;
+        }
+        Some(head.to_string())
+    }
+
+    /// Whether the bestiary marks the creature as a boss ("Unique (Boss)", "Unique (Mini-Boss)").
+    /// Boss spawns and entries noted as "not a boss" don't count.
+    pub fn is_boss(&self, log_name: &str) -> bool {
+        self.get_entry(log_name)
+            .and_then(|e| e.rarity.as_deref())
+            .map(|r| r.to_ascii_lowercase())
+            .is_some_and(|r| r.contains("boss") && !r.contains("boss spawn") && !r.contains("not a boss"))
+    }
+
     /// Look up an entry and report where it came from.
     pub fn get_entry_with_source(&self, log_name: &str) -> Option<(&BestiaryEntry, EntrySource)> {
         if let Some(hit) = self.lookup(log_name) {
@@ -262,6 +374,29 @@ mod tests {
         assert_eq!(source, EntrySource::Alias);
     }
 
+    #[test]
+    fn catalog_csv_adds_creatures_and_overrides_values() {
+        let mut db = make_db(
+            &[("Bar", 999), ("Rat", 2)],
+            r#"[{"log_name": "Foo", "resolves_to": "Bar"}]"#,
+        );
+        let update = db
+            .apply_csv("name,value,location\nRat,3,\nFoo,50,\"Tenebrion, deep\"\nNew Beast,120,Lok'Groton\n")
+            .unwrap();
+        assert_eq!(update, CreatureCatalogUpdate { added: 1, replaced: 2 });
+        assert_eq!(db.get_value("Rat"), Some(3));
+        assert_eq!(db.get_entry_with_source("Foo").unwrap().1, EntrySource::Bestiary);
+        assert_eq!(db.get_value("Foo"), Some(50));
+        assert_eq!(db.get_value("Bar"), Some(999));
+        assert_eq!(db.get_area("New Beast").as_deref(), Some("Lok'Groton"));
+
+        let err = db.apply_csv("name,value,hp\nRat,x,1\n").unwrap_err().to_string();
+        assert!(err.contains("unknown column 'hp'"), "{}", err);
+        let err = db.apply_csv("name,value\nRat,x\nRat,4\n").unwrap_err().to_string();
+        assert!(err.contains("line 2: value 'x'") && err.contains("line 3: duplicate name 'Rat'"), "{}", err);
+        assert_eq!(db.get_value("Rat"), Some(3));
+    }
+
     #[test]
     fn inline_alias_returns_synthetic_entry() {
         let db = make_db(
@@ -375,4 +510,23 @@ mod tests {
         // Inline alias.
         assert_eq!(db.get_value("Fumehorn Colossus"), Some(1510));
     }
+
+    #[test]
+    fn area_is_location_head_and_skips_everywhere() {
+        let db = CreatureDb::bundled().unwrap();
+        assert_eq!(db.get_area("Orga Anger").as_deref(), Some("Orga territory"));
+        assert_eq!(db.get_area("(Super) Crookbeak Kestrel").as_deref(), Some("Eastern Mountains"));
+        assert_eq!(db.get_area("Rat"), None);
+        assert_eq!(db.get_area("Nonexistent"), None);
+    }
+
+    #[test]
+    fn bosses_exclude_spawns() {
+        let db = CreatureDb::bundled().unwrap();
+        assert!(db.is_boss("Kolar Bear"));
+        assert!(db.is_boss("Darshak Senior Officer"));
+        assert!(!db.is_boss("Chosen Host"));
+        assert!(!db.is_boss("Juliosaur"));
+        assert!(!db.is_boss("Rat"));
+    }
 }
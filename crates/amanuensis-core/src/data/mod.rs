@@ -1,5 +1,6 @@
 pub mod bestiary;
 pub mod bestiary_import;
+mod catalog_csv;
 pub mod creatures;
 pub mod rarity;
 pub mod trainer_checkpoints;
@@ -7,7 +8,7 @@ pub mod trainers;
 
 pub use bestiary::{BestiaryEntry, BestiaryAlias, InlineEntry, EntrySource, BestiaryFile};
 pub use bestiary_import::parse_bestiary_xml;
-pub use creatures::CreatureDb;
+pub use creatures::{CreatureCatalogUpdate, CreatureDb, CREATURE_CSV_COLUMNS};
 pub use rarity::{canonical_rarity, Rarity};
-pub use trainer_checkpoints::lookup_checkpoint_message;
-pub use trainers::{TrainerDb, TrainerMeta};
+pub use trainer_checkpoints::{is_maxed_message, lookup_checkpoint_message};
+pub use trainers::{TrainerCatalogUpdate, TrainerDb, TrainerMeta, TRAINER_CSV_COLUMNS};
@@ -82,6 +82,15 @@ static CHECKPOINT_MESSAGES: Lazy<HashMap<&'static str, CheckpointRange>> = Lazy:
     m
 });
 
+/// Trainer lines that mean the character has reached this trainer's cap. Kept out of the
+/// checkpoint table because caps differ per trainer (see above).
+const MAXED_MESSAGES: [&str; 2] = ["There is nothing I can teach you.", "I have nothing more to teach you."];
+
+/// Whether a trainer's spoken message says there is nothing left to teach.
+pub fn is_maxed_message(msg: &str) -> bool {
+    MAXED_MESSAGES.contains(&msg)
+}
+
 /// Look up a trainer checkpoint message and return the rank range it implies.
 /// Returns `None` if the message is not a known checkpoint message.
 pub fn lookup_checkpoint_message(msg: &str) -> Option<CheckpointRange> {
@@ -99,6 +108,8 @@ mod tests {
         assert_eq!(lookup_checkpoint_message("There is nothing I can teach you."), None);
         assert_eq!(lookup_checkpoint_message("You feel tolerably skilled."), Some((10, Some(19))));
         assert_eq!(lookup_checkpoint_message("You are a credit to your craft."), Some((650, Some(699))));
+        assert!(is_maxed_message("There is nothing I can teach you."));
+        assert!(!is_maxed_message("You keep me on my toes."));
     }
 
     #[test]
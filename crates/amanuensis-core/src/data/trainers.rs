@@ -1,8 +1,24 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use serde::Serialize;
 
-use crate::error::Result;
+use crate::data::catalog_csv::{parse_flag, read_catalog_csv};
+use crate::error::{AmanuensisError, Result};
+
+/// Columns of a trainer catalog CSV (see [`TrainerDb::apply_csv`]). `message` and `trainer`
+/// are required; `combo_components` is `;`-separated.
+pub const TRAINER_CSV_COLUMNS: &[&str] =
+    &["message", "trainer", "profession", "multiplier", "combo_components", "retired", "replaced_by"];
+
+/// What a trainer catalog CSV changed in the catalog it was applied to.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TrainerCatalogUpdate {
+    /// Messages the catalog didn't have.
+    pub added: usize,
+    /// Messages that already mapped to a trainer and now map to the CSV's.
+    pub replaced: usize,
+}
 
 /// Metadata about a trainer, including effective rank multiplier and combo info.
 #[derive(Debug, Clone, Serialize)]
@@ -12,6 +28,10 @@ pub struct TrainerMeta {
     pub multiplier: f64,
     pub is_combo: bool,
     pub combo_components: Vec<String>,
+    /// Trainer has been removed from the game; ranks still count but can't be added to.
+    pub retired: bool,
+    /// Trainer that took over a retired trainer's stat, if any.
+    pub replaced_by: Option<String>,
 }
 
 /// In-memory trainer message -> trainer name lookup, loaded from trainers.json.
@@ -26,6 +46,8 @@ pub struct TrainerDb {
     multipliers: HashMap<String, f64>,
     /// Map from trainer name to combo component trainer names
     combo_components: HashMap<String, Vec<String>>,
+    /// Map from retired trainer name to its replacement trainer (if any)
+    retired: HashMap<String, Option<String>>,
 }
 
 impl TrainerDb {
@@ -37,6 +59,7 @@ impl TrainerDb {
         let mut professions = HashMap::new();
         let mut multipliers = HashMap::new();
         let mut combo_components = HashMap::new();
+        let mut retired = HashMap::new();
 
         for (key, value) in raw {
             if let Some(trainer_name) = value.get("trainer").and_then(|v| v.as_str()) {
@@ -68,21 +91,29 @@ impl TrainerDb {
                         combo_components.insert(trainer_name.to_string(), names);
                     }
                 }
+
+                // Store retired flag (and optional replacement) if present
+                if value.get("retired").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    let replacement = value.get("replaced_by").and_then(|v| v.as_str()).map(String::from);
+                    retired.insert(trainer_name.to_string(), replacement);
+                }
             }
         }
 
         log::info!(
-            "Loaded {} trainer messages, {} profession mappings, {} multipliers, {} combos",
+            "Loaded {} trainer messages, {} profession mappings, {} multipliers, {} combos, {} retired",
             trainers.len(),
             professions.len(),
             multipliers.len(),
-            combo_components.len()
+            combo_components.len(),
+            retired.len()
         );
         Ok(Self {
             trainers,
             professions,
             multipliers,
             combo_components,
+            retired,
         })
     }
 
@@ -91,6 +122,87 @@ impl TrainerDb {
         Self::from_json_bytes(include_bytes!("../../data/trainers.json"))
     }
 
+    /// The bundled catalog with the trainer catalog CSV at `catalog` applied on top, so
+    /// trainers added to the game can be recognized without a new release.
+    pub fn load(catalog: Option<&Path>) -> Result<Self> {
+        let mut db = Self::bundled()?;
+        if let Some(path) = catalog {
+            let text = std::fs::read_to_string(path).map_err(|e| AmanuensisError::file_unreadable(path, e))?;
+            let update = db.apply_csv(&text).map_err(|e| match e {
+                AmanuensisError::Data(msg) => AmanuensisError::Data(format!("{}: {}", path.display(), msg)),
+                e => e,
+            })?;
+            log::info!(
+                "Trainer catalog {}: {} messages added, {} replaced",
+                path.display(),
+                update.added,
+                update.replaced
+            );
+        }
+        Ok(db)
+    }
+
+    /// Add or override catalog entries from CSV text with a header row naming some of
+    /// [`TRAINER_CSV_COLUMNS`] (fields may be quoted; blank lines are skipped). A message
+    /// already in the catalog is re-pointed at the CSV's trainer, and a non-empty field
+    /// replaces that trainer's profession, multiplier, combo components or retirement.
+    /// Nothing is applied unless the whole file is valid: unknown or missing columns, a
+    /// message listed twice and unreadable values are all reported together, with line
+    /// numbers, as one error.
+    pub fn apply_csv(&mut self, text: &str) -> Result<TrainerCatalogUpdate> {
+        let mut problems = Vec::new();
+        let rows = read_catalog_csv(
+            text,
+            TRAINER_CSV_COLUMNS,
+            &["message", "trainer"],
+            "message",
+            |m| m.strip_prefix('¥').unwrap_or(m).trim().to_string(),
+            &mut problems,
+        );
+        for (line_no, row) in &rows {
+            if row.get("multiplier").is_some_and(|m| !m.is_empty() && m.parse::<f64>().is_err()) {
+                problems.push(format!("line {}: multiplier '{}' is not a number", line_no, row["multiplier"]));
+            }
+            if row.get("retired").is_some_and(|r| parse_flag(r).is_none()) {
+                problems.push(format!("line {}: retired '{}' is not true or false", line_no, row["retired"]));
+            }
+        }
+        if !problems.is_empty() {
+            return Err(AmanuensisError::Data(problems.join("; ")));
+        }
+
+        let mut update = TrainerCatalogUpdate::default();
+        for (_, row) in rows {
+            let trainer = row["trainer"].clone();
+            let field = |name: &str| row.get(name).filter(|v| !v.is_empty());
+            match self.trainers.insert(row["message"].clone(), trainer.clone()) {
+                Some(_) => update.replaced += 1,
+                None => update.added += 1,
+            }
+            if let Some(profession) = field("profession") {
+                self.professions.insert(trainer.clone(), profession.clone());
+            }
+            if let Some(mult) = field("multiplier").and_then(|m| m.parse::<f64>().ok()) {
+                self.multipliers.insert(trainer.clone(), mult);
+            }
+            if let Some(components) = field("combo_components") {
+                let names: Vec<String> =
+                    components.split(';').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+                self.combo_components.insert(trainer.clone(), names);
+            }
+            match field("retired").and_then(|r| parse_flag(r)) {
+                Some(true) => {
+                    self.retired.insert(trainer.clone(), field("replaced_by").cloned());
+                }
+                Some(false) => {
+                    self.retired.remove(&trainer);
+                }
+                None => {}
+            }
+        }
+        Ok(update)
+    }
+
     /// Look up a trainer name by message text (without ¥ prefix).
     /// Tries exact match first, then with/without trailing period, for robustness.
     pub fn get_trainer(&self, message: &str) -> Option<&str> {
@@ -135,6 +247,16 @@ impl TrainerDb {
             .unwrap_or(&[])
     }
 
+    /// Check if a trainer has been removed from the game.
+    pub fn is_retired(&self, name: &str) -> bool {
+        self.retired.contains_key(name)
+    }
+
+    /// Get the trainer that replaced a retired trainer, if one is recorded.
+    pub fn get_replacement(&self, name: &str) -> Option<&str> {
+        self.retired.get(name).and_then(|r| r.as_deref())
+    }
+
     pub fn len(&self) -> usize {
         self.trainers.len()
     }
@@ -161,6 +283,8 @@ impl TrainerDb {
                     multiplier: self.get_multiplier(trainer_name),
                     is_combo: !components.is_empty(),
                     combo_components: components,
+                    retired: self.is_retired(trainer_name),
+                    replaced_by: self.get_replacement(trainer_name).map(String::from),
                 });
             }
         }
@@ -173,6 +297,38 @@ impl TrainerDb {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_catalog_csv_extends_and_overrides() {
+        let mut db = TrainerDb::bundled().unwrap();
+        let before = db.len();
+        let csv = "message,trainer,profession,multiplier,combo_components\n\
+                   \"¥You feel more in tune, somehow.\",Tunus,Bard,0.5,\n\
+                   Your combat ability improves.,Bangus Anmash,Fighter,,\n\
+                   You feel doubly skilled.,Duo,Fighter,,Atkus; Evus\n";
+        let update = db.apply_csv(csv).unwrap();
+        assert_eq!(update, TrainerCatalogUpdate { added: 2, replaced: 1 });
+        assert_eq!(db.len(), before + 2);
+        assert_eq!(db.get_trainer("You feel more in tune, somehow."), Some("Tunus"));
+        assert_eq!(db.get_profession("Tunus"), Some("Bard"));
+        assert_eq!(db.get_multiplier("Tunus"), 0.5);
+        assert_eq!(db.get_combo_components("Duo"), ["Atkus", "Evus"]);
+    }
+
+    #[test]
+    fn test_catalog_csv_reports_all_problems() {
+        let mut db = TrainerDb::bundled().unwrap();
+        let err = db.apply_csv("message,trainer,stat\nA,B,C\n").unwrap_err().to_string();
+        assert!(err.contains("unknown column 'stat'"), "{}", err);
+        let err = db
+            .apply_csv("trainer,message,multiplier\nTunus,Hum.,x\nTunus,Hum.,1\nTunus\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 2: multiplier 'x'"), "{}", err);
+        assert!(err.contains("line 3: duplicate message 'Hum.' (first on line 2)"), "{}", err);
+        assert!(err.contains("line 4: expected 3 fields"), "{}", err);
+        assert_eq!(db.get_trainer("Hum."), None);
+    }
+
     #[test]
     fn test_load_bundled_trainers() {
         let db = TrainerDb::bundled().unwrap();
@@ -368,4 +524,23 @@ mod tests {
         assert!(db.is_combo("TestCombo"));
         assert_eq!(db.get_combo_components("TestCombo"), &["A", "B"]);
     }
+
+    #[test]
+    fn test_from_json_with_retired() {
+        let json = r#"{
+            "¥Old msg.": { "trainer": "Oldie", "retired": true, "replaced_by": "Newbie" },
+            "¥Gone msg.": { "trainer": "Gone", "retired": true },
+            "¥New msg.": { "trainer": "Newbie", "retired": false }
+        }"#;
+        let db = TrainerDb::from_json_bytes(json.as_bytes()).unwrap();
+        assert!(db.is_retired("Oldie") && db.is_retired("Gone") && !db.is_retired("Newbie"));
+        assert_eq!(db.get_replacement("Oldie"), Some("Newbie"));
+        assert_eq!(db.get_replacement("Gone"), None);
+
+        let meta = db.all_trainer_metadata();
+        let oldie = meta.iter().find(|m| m.name == "Oldie").unwrap();
+        assert!(oldie.retired);
+        assert_eq!(oldie.replaced_by.as_deref(), Some("Newbie"));
+        assert!(!meta.iter().find(|m| m.name == "Newbie").unwrap().retired);
+    }
 }
@@ -0,0 +1,113 @@
+//! Default database location, shared by the CLI and the GUI.
+//!
+//! The database lives in the platform data directory under the GUI's bundle identifier, which
+//! is where Tauri's `app_data_dir()` points: `~/Library/Application Support/com.dfsw.Amanuensis`
+//! on macOS, `%APPDATA%\com.dfsw.Amanuensis` on Windows, and
+//! `$XDG_DATA_HOME/com.dfsw.Amanuensis` (`~/.local/share/...`) elsewhere. Older CLI versions
+//! defaulted to `amanuensis.db` in the working directory; `legacy_db_to_migrate` finds such a
+//! file so it can be moved once with `migrate_db`.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AmanuensisError, Result};
+
+/// The GUI's bundle identifier, used as the data directory name.
+pub const APP_IDENTIFIER: &str = "com.dfsw.Amanuensis";
+
+/// Database file name, in the data directory and (formerly) the working directory.
+pub const DB_FILE_NAME: &str = "amanuensis.db";
+
+/// SQLite files that travel with the database.
+const SIDECAR_SUFFIXES: [&str; 2] = ["-wal", "-shm"];
+
+/// The platform data directory for Amanuensis, if the platform has one.
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join(APP_IDENTIFIER))
+}
+
+/// The default database path: `DB_FILE_NAME` in [`data_dir`].
+pub fn default_db_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join(DB_FILE_NAME))
+}
+
+/// A database left in `cwd` by an older version that should move to `default`: the legacy
+/// file exists, the default does not, and they are not the same file.
+pub fn legacy_db_to_migrate(cwd: &Path, default: &Path) -> Option<PathBuf> {
+    let legacy = cwd.join(DB_FILE_NAME);
+    if !legacy.is_file() || default.exists() {
+        return None;
+    }
+    match (legacy.canonicalize(), default.parent().and_then(|p| p.canonicalize().ok())) {
+        (Ok(l), Some(dir)) if l == dir.join(DB_FILE_NAME) => None,
+        _ => Some(legacy),
+    }
+}
+
+/// Move the database at `from` (with its `-wal`/`-shm` files) to `to`, creating the parent
+/// directory. Refuses to overwrite an existing `to`. Falls back to copy-and-delete when the
+/// two are on different filesystems.
+pub fn migrate_db(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() {
+        return Err(AmanuensisError::Data(format!("{} already exists", to.display())));
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    move_file(from, to)?;
+    for suffix in SIDECAR_SUFFIXES {
+        let side_from = with_suffix(from, suffix);
+        if side_from.exists() {
+            move_file(&side_from, &with_suffix(to, suffix))?;
+        }
+    }
+    Ok(())
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_db_moved_with_sidecars_once() {
+        let cwd = tempfile::tempdir().unwrap();
+        let data = tempfile::tempdir().unwrap();
+        let default = data.path().join(APP_IDENTIFIER).join(DB_FILE_NAME);
+        assert_eq!(legacy_db_to_migrate(cwd.path(), &default), None);
+
+        std::fs::write(cwd.path().join(DB_FILE_NAME), b"db").unwrap();
+        std::fs::write(cwd.path().join("amanuensis.db-wal"), b"wal").unwrap();
+        let legacy = legacy_db_to_migrate(cwd.path(), &default).unwrap();
+        migrate_db(&legacy, &default).unwrap();
+
+        assert_eq!(std::fs::read(&default).unwrap(), b"db");
+        assert_eq!(std::fs::read(with_suffix(&default, "-wal")).unwrap(), b"wal");
+        assert!(!legacy.exists());
+        // Once the default exists there is nothing left to migrate, and no overwriting
+        std::fs::write(cwd.path().join(DB_FILE_NAME), b"other").unwrap();
+        assert_eq!(legacy_db_to_migrate(cwd.path(), &default), None);
+        assert!(migrate_db(&cwd.path().join(DB_FILE_NAME), &default).is_err());
+    }
+
+    #[test]
+    fn cwd_inside_data_dir_is_not_legacy() {
+        let data = tempfile::tempdir().unwrap();
+        let default = data.path().join(DB_FILE_NAME);
+        std::fs::write(&default, b"db").unwrap();
+        assert_eq!(legacy_db_to_migrate(data.path(), &default), None);
+    }
+}
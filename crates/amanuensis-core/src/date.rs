@@ -0,0 +1,313 @@
+//! Date normalization layer.
+//!
+//! Every date the database stores is TEXT in one canonical form, `"YYYY-MM-DD HH:MM:SS"`
+//! (`STORAGE_FORMAT`). That form sorts lexically in chronological order, which is what the
+//! `MIN(...)` / `MAX(...)` upserts and merged getters rely on. Historically a few writers
+//! produced other shapes — the Scribius importer wrote bare `"YYYY-MM-DD"` dates — and a bare
+//! date compares *before* any time on the same day, so mixed rows misordered silently.
+//!
+//! All writers go through `to_storage` / `now`, anything parsed from outside (imports, user
+//! input, legacy rows) goes through `parse` / `normalize`, and `schema::migrate_tables`
+//! rewrites legacy rows in place via `normalize`.
+//!
+//! Models and query results carry dates as [`Timestamp`], a chrono `NaiveDateTime` that reads
+//! and writes the canonical string (SQLite and serde alike), so JSON output keeps its shape.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime, SubsecRound, Utc};
+use rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::AmanuensisError;
+
+/// The canonical stored date format.
+pub const STORAGE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Format a datetime in the canonical storage form.
+pub fn to_storage(dt: &NaiveDateTime) -> String {
+    dt.format(STORAGE_FORMAT).to_string()
+}
+
+/// The current UTC time in the canonical storage form (for `date_read`, `created_at`, ...).
+pub fn now() -> String {
+    to_storage(&now_naive())
+}
+
+/// The current UTC time, for computations relative to "now" (e.g. training ETAs).
+pub fn now_naive() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+/// Parse any date shape Amanuensis has stored or accepted:
+/// - `"YYYY-MM-DD HH:MM:SS"` (canonical), optionally with fractional seconds
+/// - ISO 8601 `"YYYY-MM-DDTHH:MM:SS"`, optionally with fractional seconds and a `Z` / offset
+/// - bare `"YYYY-MM-DD"` (treated as midnight)
+/// - Clan Lord `"M/D/YY"` or `"M/D/YYYY"` (treated as midnight; `YY` is 2000-based, matching
+///   `parse_timestamp`, except 90-99 which are 1990s)
+///
+/// Returns None for anything else.
+pub fn parse(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f") {
+        return Some(dt);
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(dt.naive_utc());
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(dt);
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return d.and_hms_opt(0, 0, 0);
+    }
+    let mut parts = s.split('/');
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    let year_part = parts.next()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let year: i32 = year_part.parse().ok()?;
+    let year = match (year_part.len(), year) {
+        (4, y) => y,
+        (1 | 2, y) if y >= 90 => 1900 + y,
+        (1 | 2, y) => 2000 + y,
+        _ => return None,
+    };
+    NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(0, 0, 0)
+}
+
+/// Rewrite a date string in the canonical storage form. Returns None if it can't be parsed.
+pub fn normalize(s: &str) -> Option<String> {
+    parse(s).map(|dt| to_storage(&dt))
+}
+
+/// Seconds since the Unix epoch for a stored date (any shape `parse` accepts), treating the
+/// stored value as UTC.
+pub fn to_epoch(s: &str) -> Option<i64> {
+    parse(s).map(|dt| dt.and_utc().timestamp())
+}
+
+/// A stored date. Ordering is chronological; `Display`, serde and SQLite all use the canonical
+/// `STORAGE_FORMAT` string, and reading accepts every shape `parse` does.
+///
+/// A stored value no shape matches (an empty date from before dates were normalized, or a
+/// hand-edited row) reads as [`Timestamp::UNKNOWN`] rather than failing, so the row it is in
+/// is still listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub NaiveDateTime);
+
+impl Timestamp {
+    /// A date that couldn't be read. Sorts before every real date and displays, serializes and
+    /// stores as an empty string, as such dates did when they were plain strings.
+    pub const UNKNOWN: Timestamp = Timestamp(NaiveDateTime::MIN);
+
+    /// False for [`Timestamp::UNKNOWN`].
+    pub fn is_known(&self) -> bool {
+        *self != Self::UNKNOWN
+    }
+
+    /// Parse any shape `parse` accepts.
+    pub fn parse(s: &str) -> Option<Self> {
+        parse(s).map(Self)
+    }
+
+    /// The current UTC time, to the second (as `now` stores it).
+    pub fn now() -> Self {
+        Self(now_naive().trunc_subsecs(0))
+    }
+
+    pub fn naive(&self) -> NaiveDateTime {
+        self.0
+    }
+
+    /// The calendar day (`"YYYY-MM-DD"`), empty when unknown.
+    pub fn day(&self) -> String {
+        if !self.is_known() {
+            return String::new();
+        }
+        self.0.format("%Y-%m-%d").to_string()
+    }
+
+    pub fn to_epoch(&self) -> i64 {
+        self.0.and_utc().timestamp()
+    }
+}
+
+impl From<NaiveDateTime> for Timestamp {
+    fn from(dt: NaiveDateTime) -> Self {
+        Self(dt)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_known() {
+            return Ok(());
+        }
+        write!(f, "{}", self.0.format(STORAGE_FORMAT))
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = AmanuensisError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| AmanuensisError::Parse(format!("Not a date: '{s}'")))
+    }
+}
+
+impl PartialEq<str> for Timestamp {
+    fn eq(&self, other: &str) -> bool {
+        parse(other) == Some(self.0)
+    }
+}
+
+impl PartialEq<&str> for Timestamp {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s.is_empty() {
+            return Ok(Self::UNKNOWN);
+        }
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl ToSql for Timestamp {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for Timestamp {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Ok(Self::parse(value.as_str()?).unwrap_or(Self::UNKNOWN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    /// Datetimes across 1995-2034, any day of the month and any second of the day.
+    fn datetime() -> impl Strategy<Value = NaiveDateTime> {
+        (1995i32..2035, 1u32..=12, 1u32..=31, 0u32..86_400).prop_filter_map("valid date", |(y, m, d, secs)| {
+            NaiveDate::from_ymd_opt(y, m, d)?.and_hms_opt(secs / 3600, (secs / 60) % 60, secs % 60)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn storage_round_trips(dt in datetime()) {
+            let s = to_storage(&dt);
+            prop_assert_eq!(parse(&s), Some(dt));
+            prop_assert_eq!(normalize(&s), Some(s.clone()));
+            prop_assert_eq!(s.parse::<Timestamp>().unwrap(), Timestamp(dt));
+        }
+
+        #[test]
+        fn storage_strings_sort_chronologically(a in datetime(), b in datetime()) {
+            prop_assert_eq!(a.cmp(&b), to_storage(&a).cmp(&to_storage(&b)));
+            prop_assert_eq!(Timestamp(a).cmp(&Timestamp(b)), to_storage(&a).cmp(&to_storage(&b)));
+        }
+
+        #[test]
+        fn normalized_mixed_shapes_sort_chronologically(dt in datetime()) {
+            // Every shape a writer has produced normalizes to the same canonical string, and a
+            // bare date normalizes to midnight so it never sorts after a time on the same day.
+            let shapes = [
+                to_storage(&dt),
+                dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                dt.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                dt.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            ];
+            for s in &shapes {
+                prop_assert_eq!(normalize(s), Some(to_storage(&dt)));
+            }
+            let bare = dt.format("%Y-%m-%d").to_string();
+            let midnight = dt.date().and_hms_opt(0, 0, 0).unwrap();
+            prop_assert_eq!(parse(&bare), Some(midnight));
+            let cl = format!("{}/{}/{}", dt.format("%-m"), dt.format("%-d"), dt.format("%y"));
+            prop_assert_eq!(parse(&cl), Some(midnight));
+            prop_assert!(normalize(&bare).unwrap() <= to_storage(&dt));
+        }
+
+        #[test]
+        fn epoch_matches_chrono(dt in datetime()) {
+            prop_assert_eq!(to_epoch(&to_storage(&dt)), Some(dt.and_utc().timestamp()));
+            prop_assert_eq!(Timestamp(dt).to_epoch(), dt.and_utc().timestamp());
+        }
+
+        #[test]
+        fn timestamp_serializes_as_storage_string(dt in datetime()) {
+            let json = serde_json::to_string(&Timestamp(dt)).unwrap();
+            prop_assert_eq!(&json, &format!("\"{}\"", to_storage(&dt)));
+            prop_assert_eq!(serde_json::from_str::<Timestamp>(&json).unwrap(), Timestamp(dt));
+        }
+    }
+
+    #[test]
+    fn epoch_of_bare_date() {
+        assert_eq!(to_epoch("1970-01-01"), Some(0));
+    }
+
+    #[test]
+    fn timestamp_sql_round_trip_reads_legacy_shapes() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let ts = Timestamp::parse("2024-01-15 08:30:00").unwrap();
+        let stored: String = conn.query_row("SELECT ?1", [ts], |r| r.get(0)).unwrap();
+        assert_eq!(stored, "2024-01-15 08:30:00");
+        let bare: Timestamp = conn.query_row("SELECT '2024-01-15'", [], |r| r.get(0)).unwrap();
+        assert_eq!(bare, "2024-01-15 00:00:00");
+    }
+
+    #[test]
+    fn unparseable_stored_dates_read_as_unknown_and_keep_their_row() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (d TEXT); INSERT INTO t VALUES ('soon'), (''), ('2024-01-15 08:30:00');")
+            .unwrap();
+        let mut stmt = conn.prepare("SELECT d FROM t ORDER BY rowid").unwrap();
+        let dates: Vec<Timestamp> = stmt.query_map([], |r| r.get(0)).unwrap().filter_map(|r| r.ok()).collect();
+        assert_eq!(dates.len(), 3);
+        assert_eq!((dates[0], dates[1]), (Timestamp::UNKNOWN, Timestamp::UNKNOWN));
+        assert!(!dates[0].is_known() && dates[2].is_known());
+        assert!(dates[0] < dates[2]);
+        assert_eq!(dates[0].to_string(), "");
+        let json = serde_json::to_string(&dates[0]).unwrap();
+        assert_eq!(json, "\"\"");
+        assert_eq!(serde_json::from_str::<Timestamp>(&json).unwrap(), Timestamp::UNKNOWN);
+    }
+
+    #[test]
+    fn cl_two_digit_years() {
+        assert_eq!(normalize("1/15/25").as_deref(), Some("2025-01-15 00:00:00"));
+        assert_eq!(normalize("12/31/99").as_deref(), Some("1999-12-31 00:00:00"));
+        assert_eq!(normalize("2/3/2024").as_deref(), Some("2024-02-03 00:00:00"));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for s in ["", "  ", "yesterday", "2024-13-01", "2/30/24", "1/2/3/4", "2024-01-01 25:00:00"] {
+            assert_eq!(parse(s), None, "{s:?}");
+        }
+    }
+}
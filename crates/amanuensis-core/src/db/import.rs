@@ -34,10 +34,10 @@ pub fn import_scribius(
 ) -> Result<ImportResult> {
     // Validate source exists
     if !scribius_path.exists() {
-        return Err(AmanuensisError::Data(format!(
-            "Scribius database not found: {}",
-            scribius_path.display()
-        )));
+        return Err(AmanuensisError::file_unreadable(
+            scribius_path,
+            std::io::Error::new(std::io::ErrorKind::NotFound, "Scribius database not found"),
+        ));
     }
 
     // Check if output already has data
@@ -59,6 +59,22 @@ pub fn import_scribius(
 
     // Open source read-only
     let src = Connection::open_with_flags(scribius_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let has_characters: bool = src
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'ZMODELCHARACTERS'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|n| n > 0)
+        .unwrap_or(false);
+    if !has_characters {
+        return Err(AmanuensisError::SchemaMismatch {
+            detail: format!(
+                "{} is not a Scribius database (no ZMODELCHARACTERS table)",
+                scribius_path.display()
+            ),
+        });
+    }
 
     // Create fresh target
     let dst = crate::db::Database::open(output_db_path)?;
@@ -162,14 +178,15 @@ fn find_characters_with_data(src: &Connection) -> Result<HashMap<i64, bool>> {
     Ok(has_data)
 }
 
-/// Convert Core Data timestamp (seconds since 2001-01-01) to YYYY-MM-DD string.
+/// Convert Core Data timestamp (seconds since 2001-01-01) to a stored date string.
+/// Scribius only records the day, so the time is midnight.
 fn coredata_timestamp_to_date(ts: f64) -> Option<String> {
     if ts == 0.0 || ts.is_nan() {
         return None;
     }
     let unix = ts + COREDATA_EPOCH_OFFSET;
     chrono::DateTime::from_timestamp(unix as i64, 0)
-        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .map(|dt| crate::date::to_storage(&dt.date_naive().and_hms_opt(0, 0, 0).unwrap()))
 }
 
 /// Map Scribius profession string to Amanuensis profession.
@@ -605,7 +622,7 @@ mod tests {
         // 2024-01-15 = 726969600 seconds after 2001-01-01
         assert_eq!(
             coredata_timestamp_to_date(726969600.0),
-            Some("2024-01-15".to_string())
+            Some("2024-01-15 00:00:00".parse().unwrap())
         );
         assert_eq!(coredata_timestamp_to_date(0.0), None);
         assert_eq!(coredata_timestamp_to_date(f64::NAN), None);
@@ -630,4 +647,18 @@ mod tests {
         assert_eq!(map_profession(""), Profession::Unknown);
         assert_eq!(map_profession("Healer"), Profession::Healer);
     }
+
+    #[test]
+    fn test_import_rejects_non_scribius_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("other.sqlite");
+        Connection::open(&src).unwrap().execute_batch("CREATE TABLE t (x);").unwrap();
+        let out = dir.path().join("out.db");
+
+        let err = import_scribius(&src, out.to_str().unwrap(), false).unwrap_err();
+        assert_eq!(err.kind(), "schema_mismatch");
+        let err = import_scribius(&dir.path().join("missing.sqlite"), out.to_str().unwrap(), false)
+            .unwrap_err();
+        assert_eq!(err.kind(), "file_unreadable");
+    }
 }
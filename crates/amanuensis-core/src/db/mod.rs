@@ -2,4 +2,4 @@ pub mod import;
 pub mod queries;
 pub mod schema;
 
-pub use queries::{Database, LogSearchResult, KillsFilter, filter_kills};
+pub use queries::{CharacterCache, Database, IndexStatus, LogSearchOptions, LogSearchPage, LogSearchResult, SearchSegment, KillsFilter, filter_kills};
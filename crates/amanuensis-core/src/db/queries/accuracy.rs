@@ -0,0 +1,91 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+
+/// Observed melee hit rate against one creature.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CreatureAccuracy {
+    pub creature_name: String,
+    pub hits: i64,
+    pub misses: i64,
+}
+
+impl CreatureAccuracy {
+    pub fn swings(&self) -> i64 {
+        self.hits + self.misses
+    }
+
+    /// Hits as a percentage of swings (0.0 when there were none).
+    pub fn hit_rate(&self) -> f64 {
+        if self.swings() == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.swings() as f64 * 100.0
+        }
+    }
+}
+
+impl Database {
+    /// Count one melee swing at `creature`: a hit when `hit` is true, otherwise a miss.
+    pub fn record_attack(&self, char_id: i64, creature: &str, hit: bool) -> Result<()> {
+        let (hits, misses) = if hit { (1, 0) } else { (0, 1) };
+        self.conn.execute(
+            "INSERT INTO creature_accuracy (character_id, creature_name, hits, misses)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(character_id, creature_name)
+             DO UPDATE SET hits = hits + excluded.hits, misses = misses + excluded.misses",
+            rusqlite::params![char_id, creature, hits, misses],
+        )?;
+        Ok(())
+    }
+
+    /// Per-creature hit/miss totals for a (possibly merged) character, most swings first.
+    pub fn get_accuracy_merged(&self, char_id: i64) -> Result<Vec<CreatureAccuracy>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT creature_name, SUM(hits), SUM(misses) FROM creature_accuracy
+             WHERE character_id IN ({placeholders})
+             GROUP BY creature_name
+             ORDER BY SUM(hits) + SUM(misses) DESC, creature_name",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(CreatureAccuracy {
+                creature_name: row.get(0)?,
+                hits: row.get(1)?,
+                misses: row.get(2)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accuracy_sums_merged_sources_and_is_cleared_by_reset() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Main").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.record_attack(main, "Rat", true).unwrap();
+        db.record_attack(main, "Rat", false).unwrap();
+        db.record_attack(alt, "Rat", true).unwrap();
+        db.record_attack(alt, "Rat", true).unwrap();
+        db.record_attack(main, "Vermine", false).unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let acc = db.get_accuracy_merged(main).unwrap();
+        assert_eq!(acc.len(), 2);
+        assert_eq!((acc[0].creature_name.as_str(), acc[0].hits, acc[0].misses), ("Rat", 3, 1));
+        assert_eq!(acc[0].hit_rate(), 75.0);
+        assert_eq!(acc[1].hit_rate(), 0.0);
+
+        db.reset_log_data().unwrap();
+        assert!(db.get_accuracy_merged(main).unwrap().is_empty());
+    }
+}
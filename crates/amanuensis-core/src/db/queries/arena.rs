@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use rusqlite::{params, params_from_iter};
+use serde::Serialize;
+
+use crate::error::{AmanuensisError, Result};
+use super::Database;
+use crate::date::Timestamp;
+
+/// Values of `tournaments.kind`: entering an arena, a bout won or lost against another
+/// player, and winning a whole tournament.
+pub const TOURNAMENT_EVENT_KINDS: &[&str] = &["entered", "win", "loss", "tournament_win"];
+
+/// One arena record, dated by its log line.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ArenaEvent {
+    /// One of [`TOURNAMENT_EVENT_KINDS`].
+    pub kind: String,
+    /// The arena entered, or the one last entered before a bout (empty if none was seen).
+    pub arena: String,
+    /// The other player in a bout (empty otherwise).
+    pub opponent: String,
+    pub date: Timestamp,
+}
+
+/// Bouts against one opponent.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ArenaOpponent {
+    pub name: String,
+    pub wins: i64,
+    pub losses: i64,
+    pub last_date: Timestamp,
+}
+
+/// Arena record for a (possibly merged) character, derived from `tournaments`.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ArenaSummary {
+    pub entries: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub tournaments_won: i64,
+    /// Most bouts first.
+    pub opponents: Vec<ArenaOpponent>,
+    /// Oldest first.
+    pub history: Vec<ArenaEvent>,
+}
+
+impl ArenaSummary {
+    /// Share of bouts won, 0.0 with no bouts.
+    pub fn win_rate(&self) -> f64 {
+        let bouts = self.wins + self.losses;
+        if bouts == 0 { 0.0 } else { self.wins as f64 / bouts as f64 }
+    }
+}
+
+impl Database {
+    /// Record an arena entry, bout or tournament win. Re-scanning the same line is a no-op.
+    pub fn record_tournament_event(
+        &self,
+        char_id: i64,
+        kind: &str,
+        arena: &str,
+        opponent: &str,
+        date: &str,
+    ) -> Result<()> {
+        if !TOURNAMENT_EVENT_KINDS.contains(&kind) {
+            return Err(AmanuensisError::Data(format!("Unknown tournament event kind: {}", kind)));
+        }
+        self.conn.execute(
+            "INSERT OR IGNORE INTO tournaments (character_id, kind, arena, opponent, date)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![char_id, kind, arena, opponent, date],
+        )?;
+        Ok(())
+    }
+
+    /// Arena history for a character and all its merge sources, oldest first.
+    pub fn get_arena_history_merged(&self, char_id: i64) -> Result<Vec<ArenaEvent>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT kind, arena, opponent, date FROM tournaments
+             WHERE character_id IN ({placeholders})
+             ORDER BY date, id",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let events = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(ArenaEvent {
+                kind: row.get(0)?,
+                arena: row.get(1)?,
+                opponent: row.get(2)?,
+                date: row.get(3)?,
+            })
+        })?;
+        Ok(events.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Totals and per-opponent records for a (possibly merged) character.
+    pub fn get_arena_summary_merged(&self, char_id: i64) -> Result<ArenaSummary> {
+        let history = self.get_arena_history_merged(char_id)?;
+        let mut summary = ArenaSummary::default();
+        let mut opponents: HashMap<String, ArenaOpponent> = HashMap::new();
+        for e in &history {
+            match e.kind.as_str() {
+                "entered" => summary.entries += 1,
+                "tournament_win" => summary.tournaments_won += 1,
+                "win" | "loss" => {
+                    let won = e.kind == "win";
+                    if won { summary.wins += 1 } else { summary.losses += 1 }
+                    let record = opponents
+                        .entry(e.opponent.to_lowercase())
+                        .or_insert_with(|| ArenaOpponent {
+                            name: e.opponent.clone(),
+                            wins: 0,
+                            losses: 0,
+                            last_date: e.date,
+                        });
+                    if won { record.wins += 1 } else { record.losses += 1 }
+                    record.last_date = e.date;
+                }
+                _ => {}
+            }
+        }
+        summary.opponents = opponents.into_values().collect();
+        summary.opponents.sort_by(|a, b| {
+            (b.wins + b.losses).cmp(&(a.wins + a.losses)).then_with(|| a.name.cmp(&b.name))
+        });
+        summary.history = history;
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+
+    #[test]
+    fn arena_summary_tallies_bouts_per_opponent() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Fen").unwrap();
+        let alt = db.get_or_create_character("Fenny").unwrap();
+        db.record_tournament_event(main, "entered", "Puddleby Arena", "", "2024-01-01 10:00:00").unwrap();
+        db.record_tournament_event(main, "win", "Puddleby Arena", "Gandor", "2024-01-01 10:01:00").unwrap();
+        db.record_tournament_event(main, "win", "Puddleby Arena", "Gandor", "2024-01-01 10:01:00").unwrap();
+        db.record_tournament_event(main, "loss", "Puddleby Arena", "Squib", "2024-01-01 10:05:00").unwrap();
+        db.record_tournament_event(alt, "loss", "", "gandor", "2024-02-01 10:00:00").unwrap();
+        db.record_tournament_event(alt, "tournament_win", "", "", "2024-02-01 11:00:00").unwrap();
+        assert!(db.record_tournament_event(main, "draw", "", "Gandor", "2024-01-01 10:02:00").is_err());
+        db.merge_characters(&[alt], main).unwrap();
+
+        let summary = db.get_arena_summary_merged(main).unwrap();
+        assert_eq!((summary.entries, summary.wins, summary.losses, summary.tournaments_won), (1, 1, 2, 1));
+        assert_eq!(summary.history.len(), 5);
+        let gandor = &summary.opponents[0];
+        assert_eq!((gandor.name.as_str(), gandor.wins, gandor.losses), ("Gandor", 1, 1));
+        assert_eq!(gandor.last_date, "2024-02-01 10:00:00");
+        assert_eq!(summary.opponents[1].name, "Squib");
+        assert!((summary.win_rate() - 1.0 / 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,93 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// Assisted kills of one creature finished off by one player.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AssistCredit {
+    pub creature: String,
+    /// The player who landed the final blow.
+    pub player: String,
+    pub count: i64,
+    pub last_date: Timestamp,
+}
+
+impl Database {
+    /// Count one assisted kill of `creature` finished by `player`.
+    pub fn record_assist(&self, char_id: i64, creature: &str, player: &str, date: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO assists (character_id, creature_name, player_name, count, first_date, last_date)
+             VALUES (?1, ?2, ?3, 1, ?4, ?4)
+             ON CONFLICT(character_id, creature_name, player_name) DO UPDATE SET
+                 count = count + 1,
+                 first_date = MIN(first_date, excluded.first_date),
+                 last_date = MAX(last_date, excluded.last_date)",
+            rusqlite::params![char_id, creature, player, date],
+        )?;
+        Ok(())
+    }
+
+    /// Attributed assists of a (possibly merged) character, optionally for one creature,
+    /// most first.
+    pub fn get_assists_merged(&self, char_id: i64, creature: Option<&str>) -> Result<Vec<AssistCredit>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let creature_filter = if creature.is_some() { "AND creature_name = ? COLLATE NOCASE" } else { "" };
+        let sql = format!(
+            "SELECT creature_name, player_name, SUM(count), MAX(last_date)
+             FROM assists
+             WHERE character_id IN ({placeholders}) {creature_filter}
+             GROUP BY creature_name, player_name
+             ORDER BY SUM(count) DESC, creature_name, player_name",
+        );
+        let mut params: Vec<rusqlite::types::Value> = ids.iter().map(|&id| id.into()).collect();
+        if let Some(c) = creature {
+            params.push(c.to_string().into());
+        }
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params), |row| {
+            Ok(AssistCredit {
+                creature: row.get(0)?,
+                player: row.get(1)?,
+                count: row.get(2)?,
+                last_date: row.get(3)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assists_group_by_creature_and_player_across_merges() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Main").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.record_assist(main, "Rat", "Gandor", "2024-01-01 10:00:00").unwrap();
+        db.record_assist(alt, "Rat", "Gandor", "2024-01-02 10:00:00").unwrap();
+        db.record_assist(main, "Rat", "Fen", "2024-01-01 11:00:00").unwrap();
+        db.record_assist(main, "Orga Anger", "Fen", "2024-01-01 12:00:00").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let all = db.get_assists_merged(main, None).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(
+            all[0],
+            AssistCredit {
+                creature: "Rat".into(),
+                player: "Gandor".into(),
+                count: 2,
+                last_date: "2024-01-02 10:00:00".parse().unwrap(),
+            }
+        );
+        let orga = db.get_assists_merged(main, Some("orga anger")).unwrap();
+        assert_eq!(orga.len(), 1);
+        assert_eq!(orga[0].player, "Fen");
+    }
+}
@@ -0,0 +1,182 @@
+//! Casino bets, wins and losses per game.
+//!
+//! No casino message is parsed yet: none has been captured from a real log, so nothing is
+//! matched until one is. Recording a win or loss also counts it in `characters.casino_won` /
+//! `casino_lost` and the coin ledger, so every reader sees the same casino totals.
+
+use std::collections::BTreeMap;
+
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::{AmanuensisError, Result};
+use super::Database;
+use crate::date::Timestamp;
+
+/// Values of `casino_events.kind`.
+pub const CASINO_EVENT_KINDS: &[&str] = &["bet", "win", "loss"];
+
+/// One casino bet, win or loss.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CasinoEvent {
+    /// Lowercased game name as logged ("slots", "dice", ...).
+    pub game: String,
+    /// One of [`CASINO_EVENT_KINDS`].
+    pub kind: String,
+    pub amount: i64,
+    pub date: Timestamp,
+}
+
+/// Bets, wins and losses at one game.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CasinoGame {
+    pub game: String,
+    pub bets: i64,
+    pub wagered: i64,
+    pub wins: i64,
+    pub won: i64,
+    pub losses: i64,
+    pub lost: i64,
+    /// Largest single win / loss at this game.
+    pub biggest_win: i64,
+    pub biggest_loss: i64,
+}
+
+impl CasinoGame {
+    pub fn net(&self) -> i64 {
+        self.won - self.lost
+    }
+}
+
+/// Casino results per game for a character.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CasinoSummary {
+    /// Most coins won plus lost first.
+    pub games: Vec<CasinoGame>,
+    pub net: i64,
+    pub biggest_win: Option<CasinoEvent>,
+    pub biggest_loss: Option<CasinoEvent>,
+}
+
+impl Database {
+    /// Record a casino event; a new win or loss is also added to `casino_won` / `casino_lost`
+    /// and to the coin ledger source of the same name (bets only matter through their outcome). Returns false when the
+    /// same line was already recorded (a re-scan), which changes nothing.
+    pub fn record_casino_event(&self, char_id: i64, game: &str, kind: &str, amount: i64, date: &str) -> Result<bool> {
+        if !CASINO_EVENT_KINDS.contains(&kind) {
+            return Err(AmanuensisError::Data(format!("Unknown casino event: {}", kind)));
+        }
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO casino_events (character_id, game, kind, amount, date)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![char_id, game, kind, amount, date],
+        )? > 0;
+        // The character counter and the ledger source share a name.
+        let outcome = match kind {
+            "win" => Some(("casino_won", amount)),
+            "loss" => Some(("casino_lost", -amount)),
+            _ => None,
+        };
+        if let Some((field, ledger_amount)) = outcome.filter(|_| inserted) {
+            self.increment_character_field(char_id, field, amount)?;
+            self.upsert_coin_daily(char_id, field, &date[..date.len().min(10)], ledger_amount)?;
+        }
+        Ok(inserted)
+    }
+
+    /// Casino events of a (possibly merged) character, oldest first.
+    pub fn get_casino_events_merged(&self, char_id: i64) -> Result<Vec<CasinoEvent>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT game, kind, amount, date FROM casino_events
+             WHERE character_id IN ({placeholders})
+             ORDER BY date, id",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(CasinoEvent { game: row.get(0)?, kind: row.get(1)?, amount: row.get(2)?, date: row.get(3)? })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Net winnings by game and the biggest single win and loss for a (possibly merged)
+    /// character. Ties for biggest go to the earliest.
+    pub fn get_casino_summary_merged(&self, char_id: i64) -> Result<CasinoSummary> {
+        let events = self.get_casino_events_merged(char_id)?;
+        let mut games: BTreeMap<&str, CasinoGame> = BTreeMap::new();
+        let mut summary = CasinoSummary::default();
+        for e in &events {
+            let g = games.entry(e.game.as_str()).or_insert_with(|| CasinoGame { game: e.game.clone(), ..Default::default() });
+            let biggest = match e.kind.as_str() {
+                "bet" => {
+                    g.bets += 1;
+                    g.wagered += e.amount;
+                    continue;
+                }
+                "win" => {
+                    g.wins += 1;
+                    g.won += e.amount;
+                    g.biggest_win = g.biggest_win.max(e.amount);
+                    &mut summary.biggest_win
+                }
+                _ => {
+                    g.losses += 1;
+                    g.lost += e.amount;
+                    g.biggest_loss = g.biggest_loss.max(e.amount);
+                    &mut summary.biggest_loss
+                }
+            };
+            if biggest.as_ref().is_none_or(|b| e.amount > b.amount) {
+                *biggest = Some(e.clone());
+            }
+        }
+        summary.games = games.into_values().collect();
+        summary.games.sort_by_key(|g| std::cmp::Reverse(g.won + g.lost));
+        summary.net = summary.games.iter().map(CasinoGame::net).sum();
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn casino_summary_nets_by_game_with_biggest_win_and_loss() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        assert!(db.record_casino_event(c, "slots", "bet", 10, "2024-01-01 10:00:00").unwrap());
+        assert!(db.record_casino_event(c, "slots", "win", 50, "2024-01-01 10:00:05").unwrap());
+        assert!(!db.record_casino_event(c, "slots", "win", 50, "2024-01-01 10:00:05").unwrap());
+        db.record_casino_event(c, "slots", "loss", 10, "2024-01-01 10:01:00").unwrap();
+        db.record_casino_event(c, "dice", "loss", 80, "2024-01-02 10:00:00").unwrap();
+        db.record_casino_event(c, "dice", "win", 20, "2024-01-02 11:00:00").unwrap();
+        assert!(db.record_casino_event(c, "dice", "push", 20, "2024-01-02 11:00:00").is_err());
+
+        let s = db.get_casino_summary_merged(c).unwrap();
+        assert_eq!(s.net, -20);
+        assert_eq!(
+            s.games[0],
+            CasinoGame {
+                game: "dice".into(),
+                bets: 0,
+                wagered: 0,
+                wins: 1,
+                won: 20,
+                losses: 1,
+                lost: 80,
+                biggest_win: 20,
+                biggest_loss: 80,
+            }
+        );
+        assert_eq!((s.games[1].game.as_str(), s.games[1].bets, s.games[1].net()), ("slots", 1, 40));
+        assert_eq!(s.biggest_win.map(|e| (e.game, e.amount)), Some(("slots".into(), 50)));
+        assert_eq!(s.biggest_loss.map(|e| (e.game, e.amount)), Some(("dice".into(), 80)));
+        let ch = db.get_character("Gandor").unwrap().unwrap();
+        assert_eq!((ch.casino_won, ch.casino_lost), (70, 90));
+        let months = db.get_coin_timeline_merged(c, "monthly").unwrap();
+        // Wins and losses stay separate in the ledger, even on the same day.
+        assert_eq!((months[0].income, months[0].spent), (70, 90));
+    }
+}
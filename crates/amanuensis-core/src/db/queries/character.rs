@@ -1,32 +1,75 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
 use rusqlite::params;
+use serde::Serialize;
 
 use crate::error::Result;
 use crate::models::*;
 use super::{CHARACTER_COLUMNS, map_character_row, Database};
 
-impl Database {
-    /// Get or create a character by name. Returns the character ID.
-    pub fn get_or_create_character(&self, name: &str) -> Result<i64> {
-        // Try to find existing
-        let existing: Option<i64> = self
-            .conn
-            .query_row(
-                "SELECT id FROM characters WHERE name = ?1",
-                params![name],
-                |row| row.get(0),
-            )
-            .ok();
-
-        if let Some(id) = existing {
+/// A character-list row: the character plus the badges the list screen shows, all
+/// including merged sources. Serializes flat (character fields alongside the extras).
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterListEntry {
+    /// `total_ranks` here includes merged sources.
+    #[serde(flatten)]
+    pub character: Character,
+    /// Solo + assisted kills.
+    pub total_kills: i64,
+    /// End of the latest session, falling back to the latest kill when no sessions
+    /// have been recorded yet.
+    pub last_played: Option<String>,
+    /// Log files an Update would read for this character (new or grown), from the `pending`
+    /// counts given to `list_characters_with_stats`.
+    pub unscanned_files: i64,
+}
+
+/// Name → character ID cache, shared (cheaply cloned) between everything that resolves
+/// characters during a scan, including worker threads with their own connections. Entries
+/// are only added after the row exists, so a hit never needs the database. Clear it when
+/// characters may have been deleted (each scan starts with a cleared cache).
+#[derive(Debug, Clone, Default)]
+pub struct CharacterCache {
+    ids: Arc<Mutex<HashMap<String, i64>>>,
+}
+
+impl CharacterCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The character's ID, creating the row on first sight. Idempotent across threads and
+    /// connections: concurrent callers for one name all get the same ID.
+    pub fn get_or_create(&self, db: &Database, name: &str) -> Result<i64> {
+        let mut ids = self.ids.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&id) = ids.get(name) {
             return Ok(id);
         }
+        let id = db.get_or_create_character(name)?;
+        ids.insert(name.to_string(), id);
+        Ok(id)
+    }
+
+    pub fn clear(&self) {
+        self.ids.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
 
-        // Create new
+impl Database {
+    /// Get or create a character by name. Returns the character ID.
+    /// A single upsert against `characters.name UNIQUE`, so two connections racing on the
+    /// same new name both get the one row instead of a constraint error or a duplicate.
+    pub fn get_or_create_character(&self, name: &str) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO characters (name) VALUES (?1)",
+            "INSERT INTO characters (name) VALUES (?1) ON CONFLICT(name) DO NOTHING",
             params![name],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        Ok(self.conn.query_row(
+            "SELECT id FROM characters WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )?)
     }
 
     /// Get a character by name.
@@ -59,6 +102,62 @@ impl Database {
         Ok(chars.filter_map(|r| r.ok()).collect())
     }
 
+    /// `list_characters` with per-character aggregates, computed in one query so the GUI
+    /// list doesn't need a follow-up call per character. `pending` is pending log files per
+    /// character id (`parser::pending_files_by_character`), summed over merged sources.
+    pub fn list_characters_with_stats(&self, pending: &HashMap<i64, i64>) -> Result<Vec<CharacterListEntry>> {
+        let sql = format!(
+            "WITH grp AS (
+                 SELECT id AS target, id AS member FROM characters WHERE merged_into IS NULL
+                 UNION ALL
+                 SELECT merged_into, id FROM characters WHERE merged_into IS NOT NULL
+             ),
+             rank_totals AS (
+                 SELECT g.target, SUM(t.ranks + t.apply_learning_ranks + t.modified_ranks) AS n
+                 FROM grp g JOIN trainers t ON t.character_id = g.member GROUP BY g.target
+             ),
+             kill_totals AS (
+                 SELECT g.target,
+                        SUM(k.killed_count + k.slaughtered_count + k.vanquished_count + k.dispatched_count
+                            + k.assisted_kill_count + k.assisted_slaughter_count
+                            + k.assisted_vanquish_count + k.assisted_dispatch_count) AS n,
+                        MAX(k.date_last) AS last
+                 FROM grp g JOIN kills k ON k.character_id = g.member GROUP BY g.target
+             ),
+             session_totals AS (
+                 SELECT g.target, MAX(s.ended_at) AS last
+                 FROM grp g JOIN sessions s ON s.character_id = g.member GROUP BY g.target
+             )
+             SELECT {CHARACTER_COLUMNS},
+                    COALESCE(r.n, 0), COALESCE(k.n, 0), COALESCE(s.last, k.last)
+             FROM characters
+             LEFT JOIN rank_totals r ON r.target = characters.id
+             LEFT JOIN kill_totals k ON k.target = characters.id
+             LEFT JOIN session_totals s ON s.target = characters.id
+             WHERE merged_into IS NULL AND logins > 0
+             ORDER BY name"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let entries = stmt.query_map([], |row| {
+            let mut character = map_character_row(row)?;
+            character.total_ranks = row.get(48)?;
+            Ok(CharacterListEntry {
+                character,
+                total_kills: row.get(49)?,
+                last_played: row.get(50)?,
+                unscanned_files: 0,
+            })
+        })?;
+        let mut entries: Vec<CharacterListEntry> = entries.filter_map(|r| r.ok()).collect();
+        if !pending.is_empty() {
+            for entry in &mut entries {
+                let Some(id) = entry.character.id else { continue };
+                entry.unscanned_files = self.char_ids_for_merged(id)?.iter().filter_map(|m| pending.get(m)).sum();
+            }
+        }
+        Ok(entries)
+    }
+
     /// Increment a character counter field.
     pub fn increment_character_field(&self, char_id: i64, field: &str, amount: i64) -> Result<()> {
         // Only allow known fields to prevent SQL injection
@@ -88,6 +187,7 @@ impl Database {
             field, field
         );
         self.conn.execute(&sql, params![amount, char_id])?;
+        self.record_contribution(char_id, "character", "", field, amount)?;
         Ok(())
     }
 
@@ -0,0 +1,126 @@
+use rusqlite::types::Value;
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// One line of speech or thought from the opt-in `chat` table. The same line heard in
+/// several characters' logs is one row.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ChatLine {
+    pub speaker: String,
+    /// "says", "yells in Thoom", "thinks to you", ...
+    pub verb: String,
+    pub text: String,
+    pub timestamp: Timestamp,
+    /// A log the line was read from (the first by path when several logs have it).
+    pub file_path: String,
+}
+
+/// Filters for [`Database::get_chat`].
+#[derive(Debug, Clone)]
+pub struct ChatFilter {
+    /// Only lines from this (possibly merged) character's logs.
+    pub char_id: Option<i64>,
+    /// Speaker name, case-insensitive.
+    pub speaker: Option<String>,
+    /// Text containing this, case-insensitive.
+    pub contains: Option<String>,
+    pub limit: usize,
+}
+
+impl Default for ChatFilter {
+    fn default() -> Self {
+        Self { char_id: None, speaker: None, contains: None, limit: 100 }
+    }
+}
+
+impl Database {
+    /// Store one speech line heard by `char_id` (scans with chat recording on).
+    pub fn record_chat(
+        &self,
+        char_id: i64,
+        speaker: &str,
+        verb: &str,
+        text: &str,
+        timestamp: &str,
+        file_path: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO chat (character_id, speaker, verb, text, timestamp, file_path)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![char_id, speaker, verb, text, timestamp, file_path],
+        )?;
+        Ok(())
+    }
+
+    /// The most recent `filter.limit` chat lines matching `filter`, oldest first.
+    pub fn get_chat(&self, filter: &ChatFilter) -> Result<Vec<ChatLine>> {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Value> = Vec::new();
+        if let Some(id) = filter.char_id {
+            let ids = self.char_ids_for_merged(id)?;
+            conditions.push(format!("character_id IN ({})", ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")));
+            params.extend(ids.into_iter().map(Value::Integer));
+        }
+        if let Some(speaker) = &filter.speaker {
+            conditions.push("speaker = ? COLLATE NOCASE".to_string());
+            params.push(Value::Text(speaker.clone()));
+        }
+        if let Some(contains) = &filter.contains {
+            conditions.push("instr(lower(text), lower(?)) > 0".to_string());
+            params.push(Value::Text(contains.clone()));
+        }
+        let filter_sql =
+            if conditions.is_empty() { String::new() } else { format!("WHERE {}", conditions.join(" AND ")) };
+        let sql = format!(
+            "SELECT speaker, verb, text, timestamp, MIN(file_path) FROM chat {filter_sql}
+             GROUP BY speaker, verb, text, timestamp
+             ORDER BY timestamp DESC, MIN(id) DESC
+             LIMIT {}",
+            filter.limit
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params), |row| {
+            Ok(ChatLine {
+                speaker: row.get(0)?,
+                verb: row.get(1)?,
+                text: row.get(2)?,
+                timestamp: row.get(3)?,
+                file_path: row.get(4)?,
+            })
+        })?;
+        let mut lines: Vec<ChatLine> = rows.filter_map(|r| r.ok()).collect();
+        lines.reverse();
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chat_filters_and_merges_lines_heard_twice() {
+        let db = Database::open_in_memory().unwrap();
+        let gandor = db.get_or_create_character("Gandor").unwrap();
+        let histia = db.get_or_create_character("Histia").unwrap();
+        db.record_chat(gandor, "Fen", "says", "Hunt at the docks?", "2024-01-01 13:00:00", "g.txt").unwrap();
+        db.record_chat(histia, "Fen", "says", "Hunt at the docks?", "2024-01-01 13:00:00", "h.txt").unwrap();
+        db.record_chat(gandor, "Pip", "yells", "HUNTING!", "2024-01-01 13:01:00", "g.txt").unwrap();
+        db.record_chat(histia, "Fen", "thinks to you", "bring a chain", "2024-01-01 13:02:00", "h.txt").unwrap();
+
+        let all = db.get_chat(&ChatFilter::default()).unwrap();
+        assert_eq!(all.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(), ["Hunt at the docks?", "HUNTING!", "bring a chain"]);
+        assert_eq!(all[0].file_path, "g.txt");
+
+        let fen_hunt = ChatFilter { speaker: Some("fen".into()), contains: Some("hunt".into()), ..Default::default() };
+        assert_eq!(db.get_chat(&fen_hunt).unwrap().len(), 1);
+        let heard_by_gandor = ChatFilter { char_id: Some(gandor), ..Default::default() };
+        assert_eq!(db.get_chat(&heard_by_gandor).unwrap().len(), 2);
+        let latest = db.get_chat(&ChatFilter { limit: 1, ..Default::default() }).unwrap();
+        assert_eq!(latest[0].text, "bring a chain");
+    }
+}
@@ -1,4 +1,4 @@
-use rusqlite::params;
+use rusqlite::{params, params_from_iter};
 
 use crate::error::Result;
 use crate::models::TrainerCheckpoint;
@@ -77,6 +77,40 @@ impl Database {
         Ok(rows.filter_map(|r| r.ok()).collect())
     }
 
+    /// Every checkpoint of a (possibly merged) character, by timestamp.
+    pub fn get_all_trainer_checkpoints_merged(&self, char_id: i64) -> Result<Vec<TrainerCheckpoint>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT id, character_id, trainer_name, rank_min, rank_max, timestamp
+             FROM trainer_checkpoints
+             WHERE character_id IN ({placeholders})
+             ORDER BY timestamp ASC, id ASC",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(TrainerCheckpoint {
+                id: Some(row.get(0)?),
+                character_id: row.get(1)?,
+                trainer_name: row.get(2)?,
+                rank_min: row.get(3)?,
+                rank_max: row.get(4)?,
+                timestamp: row.get(5)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// The most recent checkpoint per trainer across a (possibly merged) character's
+    /// sources, by trainer name.
+    pub fn get_latest_trainer_checkpoints_merged(&self, char_id: i64) -> Result<Vec<TrainerCheckpoint>> {
+        let mut latest: std::collections::BTreeMap<String, TrainerCheckpoint> = std::collections::BTreeMap::new();
+        for checkpoint in self.get_all_trainer_checkpoints_merged(char_id)? {
+            latest.insert(checkpoint.trainer_name.clone(), checkpoint);
+        }
+        Ok(latest.into_values().collect())
+    }
+
     /// Get full checkpoint history for a specific trainer and character.
     pub fn get_trainer_checkpoint_history(
         &self,
@@ -157,6 +191,24 @@ mod tests {
         assert_eq!(checkpoints[2].rank_min, 20, "Should be returned in ascending timestamp order");
     }
 
+    #[test]
+    fn test_merged_checkpoints_span_sources() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Fen").unwrap();
+        let alt = db.get_or_create_character("Fenn").unwrap();
+        db.insert_trainer_checkpoint(main, "Histia", 10, Some(19), "2024-01-02 12:00:00").unwrap();
+        db.insert_trainer_checkpoint(alt, "Histia", 20, Some(29), "2024-01-03 12:00:00").unwrap();
+        db.insert_trainer_checkpoint(alt, "Detha", 0, Some(9), "2024-01-01 12:00:00").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let all = db.get_all_trainer_checkpoints_merged(main).unwrap();
+        assert_eq!(all.iter().map(|c| c.rank_min).collect::<Vec<_>>(), vec![0, 10, 20]);
+        let latest = db.get_latest_trainer_checkpoints_merged(main).unwrap();
+        let latest: Vec<(&str, i64)> = latest.iter().map(|c| (c.trainer_name.as_str(), c.rank_min)).collect();
+        assert_eq!(latest, vec![("Detha", 0), ("Histia", 20)]);
+        assert_eq!(db.get_latest_trainer_checkpoints(main).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_rank_max_none_roundtrips() {
         let db = Database::open_in_memory().unwrap();
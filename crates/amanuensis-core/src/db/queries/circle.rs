@@ -0,0 +1,72 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// A circle test the character passed.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CircleTest {
+    /// "Fighter", "Healer", "Mystic", ... as announced.
+    pub profession: String,
+    pub circle: i64,
+    /// Date of the announcement (the earliest, if it was seen more than once).
+    pub date: Timestamp,
+}
+
+impl Database {
+    /// Record a passed circle test. Seeing the announcement again keeps the earliest date.
+    pub fn record_circle(&self, char_id: i64, profession: &str, circle: i64, date: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO circles (character_id, profession, circle, date)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(character_id, profession, circle) DO UPDATE SET
+                 date = MIN(date, excluded.date)",
+            rusqlite::params![char_id, profession, circle, date],
+        )?;
+        Ok(())
+    }
+
+    /// Circle tests passed by a (possibly merged) character, oldest first.
+    pub fn get_circles_merged(&self, char_id: i64) -> Result<Vec<CircleTest>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT profession, circle, MIN(date)
+             FROM circles
+             WHERE character_id IN ({placeholders})
+             GROUP BY profession, circle
+             ORDER BY MIN(date), circle",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(CircleTest { profession: row.get(0)?, circle: row.get(1)?, date: row.get(2)? })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circles_keep_earliest_date_across_merges() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Camo").unwrap();
+        let alt = db.get_or_create_character("Camo Alt").unwrap();
+        db.record_circle(main, "Fighter", 2, "2024-02-01 10:00:00").unwrap();
+        db.record_circle(main, "Fighter", 1, "2024-01-01 10:00:00").unwrap();
+        db.record_circle(main, "Fighter", 1, "2024-01-05 10:00:00").unwrap();
+        db.record_circle(alt, "Fighter", 2, "2024-01-20 10:00:00").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let circles: Vec<(i64, String)> =
+            db.get_circles_merged(main).unwrap().into_iter().map(|c| (c.circle, c.date.to_string())).collect();
+        assert_eq!(
+            circles,
+            [(1, "2024-01-01 10:00:00".to_string()), (2, "2024-01-20 10:00:00".to_string())]
+        );
+    }
+}
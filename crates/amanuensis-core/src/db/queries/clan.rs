@@ -0,0 +1,116 @@
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::date::Timestamp;
+use crate::error::Result;
+use crate::models::ClanEvent;
+use super::Database;
+
+/// Event types stored in `clan_history`.
+pub const CLAN_EVENT_TYPES: &[&str] = &["join", "leave", "title"];
+
+/// Clan membership and titles for a (possibly merged) character, derived from `clan_history`.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ClanSummary {
+    /// The clan most recently joined and not since left.
+    pub current_clan: Option<String>,
+    /// Date of the join that started the current membership.
+    pub member_since: Option<Timestamp>,
+    /// Titles earned, oldest first (each listed once).
+    pub titles: Vec<String>,
+    pub history: Vec<ClanEvent>,
+}
+
+impl Database {
+    /// Record a clan join/leave or title. Re-scanning the same line is a no-op.
+    pub fn record_clan_event(&self, char_id: i64, event_type: &str, value: &str, date: &str) -> Result<()> {
+        if !CLAN_EVENT_TYPES.contains(&event_type) {
+            return Err(crate::error::AmanuensisError::Data(format!(
+                "Unknown clan event type: {}",
+                event_type
+            )));
+        }
+        self.conn.execute(
+            "INSERT OR IGNORE INTO clan_history (character_id, event_type, value, date)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![char_id, event_type, value, date],
+        )?;
+        Ok(())
+    }
+
+    /// Clan history for a character and all its merge sources, oldest first.
+    pub fn get_clan_history_merged(&self, char_id: i64) -> Result<Vec<ClanEvent>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT id, character_id, event_type, value, date FROM clan_history
+             WHERE character_id IN ({placeholders})
+             ORDER BY date, id",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let events = stmt.query_map(rusqlite::params_from_iter(ids.iter()), |row| {
+            Ok(ClanEvent {
+                id: Some(row.get(0)?),
+                character_id: row.get(1)?,
+                event_type: row.get(2)?,
+                value: row.get(3)?,
+                date: row.get(4)?,
+            })
+        })?;
+        Ok(events.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Current clan and titles for a (possibly merged) character.
+    pub fn get_clan_summary_merged(&self, char_id: i64) -> Result<ClanSummary> {
+        let history = self.get_clan_history_merged(char_id)?;
+        let mut summary = ClanSummary::default();
+        for e in &history {
+            match e.event_type.as_str() {
+                "join" => {
+                    summary.current_clan = Some(e.value.clone());
+                    summary.member_since = Some(e.date);
+                }
+                "leave" if summary.current_clan.as_deref() == Some(e.value.as_str()) => {
+                    summary.current_clan = None;
+                    summary.member_since = None;
+                }
+                "title" if !summary.titles.contains(&e.value) => summary.titles.push(e.value.clone()),
+                _ => {}
+            }
+        }
+        summary.history = history;
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::queries::Database;
+
+    #[test]
+    fn summary_tracks_current_clan_and_titles() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.record_clan_event(c, "join", "Order of the Thorn", "2023-05-01 10:00:00").unwrap();
+        db.record_clan_event(c, "title", "Rat Catcher", "2023-06-01 10:00:00").unwrap();
+        db.record_clan_event(c, "leave", "Order of the Thorn", "2024-01-01 10:00:00").unwrap();
+        db.record_clan_event(c, "join", "Night Watch", "2024-02-01 10:00:00").unwrap();
+        db.record_clan_event(c, "join", "Night Watch", "2024-02-01 10:00:00").unwrap(); // rescan
+
+        let s = db.get_clan_summary_merged(c).unwrap();
+        assert_eq!(s.current_clan.as_deref(), Some("Night Watch"));
+        assert_eq!(s.member_since.unwrap(), "2024-02-01 10:00:00");
+        assert_eq!(s.titles, vec!["Rat Catcher"]);
+        assert_eq!(s.history.len(), 4);
+    }
+
+    #[test]
+    fn leaving_current_clan_clears_membership() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.record_clan_event(c, "join", "Night Watch", "2024-02-01 10:00:00").unwrap();
+        db.record_clan_event(c, "leave", "Night Watch", "2024-03-01 10:00:00").unwrap();
+        assert!(db.get_clan_summary_merged(c).unwrap().current_clan.is_none());
+        assert!(db.record_clan_event(c, "rank", "x", "2024-03-01 10:00:00").is_err());
+    }
+}
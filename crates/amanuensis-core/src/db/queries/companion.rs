@@ -0,0 +1,90 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+
+/// A player seen hunting alongside the character (sharing, falling or being raised).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Companion {
+    pub name: String,
+    /// Distinct days the player showed up in this character's logs.
+    pub days: i64,
+    /// Sharing / fall / recovery lines naming the player.
+    pub events: i64,
+    /// "YYYY-MM-DD"
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+impl Database {
+    /// Count one companion sighting of `player` on `day` ("YYYY-MM-DD").
+    pub fn record_companion(&self, char_id: i64, player: &str, day: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO companions (character_id, player_name, day, events)
+             VALUES (?1, ?2, ?3, 1)
+             ON CONFLICT(character_id, player_name, day) DO UPDATE SET events = events + 1",
+            rusqlite::params![char_id, player, day],
+        )?;
+        Ok(())
+    }
+
+    /// Hunting partners of a (possibly merged) character, most days together first.
+    /// The character's own merged names never count as companions.
+    pub fn get_companions_merged(&self, char_id: i64) -> Result<Vec<Companion>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT player_name, COUNT(DISTINCT day), SUM(events), MIN(day), MAX(day)
+             FROM companions
+             WHERE character_id IN ({placeholders})
+               AND player_name NOT IN (SELECT name FROM characters WHERE id IN ({placeholders}))
+             GROUP BY player_name
+             ORDER BY COUNT(DISTINCT day) DESC, SUM(events) DESC, player_name",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter().chain(ids.iter())), |row| {
+            Ok(Companion {
+                name: row.get(0)?,
+                days: row.get(1)?,
+                events: row.get(2)?,
+                first_seen: row.get(3)?,
+                last_seen: row.get(4)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn companions_rank_by_days_and_skip_own_alts() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Main").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.record_companion(main, "Bramble", "2024-01-01").unwrap();
+        db.record_companion(main, "Bramble", "2024-01-01").unwrap();
+        db.record_companion(alt, "Bramble", "2024-01-05").unwrap();
+        for _ in 0..5 {
+            db.record_companion(main, "Corvid", "2024-01-03").unwrap();
+        }
+        db.record_companion(main, "Alt", "2024-01-02").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let companions = db.get_companions_merged(main).unwrap();
+        let rows: Vec<(&str, i64, i64, &str, &str)> = companions
+            .iter()
+            .map(|c| (c.name.as_str(), c.days, c.events, c.first_seen.as_str(), c.last_seen.as_str()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Bramble", 2, 3, "2024-01-01", "2024-01-05"),
+                ("Corvid", 1, 5, "2024-01-03", "2024-01-03"),
+            ]
+        );
+    }
+}
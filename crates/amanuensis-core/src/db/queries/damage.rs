@@ -0,0 +1,74 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+
+/// How often one creature landed a blow, alongside how often it finished the job.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DamageTaken {
+    pub creature_name: String,
+    pub hits: i64,
+    /// `killed_by_count` from the kills table (the nemesis stat).
+    pub killed_by: i64,
+}
+
+impl Database {
+    /// Count one "The {creature} hits you." line.
+    pub fn record_damage_taken(&self, char_id: i64, creature: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO damage_taken (character_id, creature_name, hits)
+             VALUES (?1, ?2, 1)
+             ON CONFLICT(character_id, creature_name) DO UPDATE SET hits = hits + 1",
+            rusqlite::params![char_id, creature],
+        )?;
+        Ok(())
+    }
+
+    /// Hits taken per creature for a (possibly merged) character, hardest hitters first.
+    pub fn get_damage_taken_merged(&self, char_id: i64) -> Result<Vec<DamageTaken>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT d.creature_name, SUM(d.hits),
+                    COALESCE((SELECT SUM(k.killed_by_count) FROM kills k
+                              WHERE k.character_id IN ({placeholders}) AND k.creature_name = d.creature_name), 0)
+             FROM damage_taken d
+             WHERE d.character_id IN ({placeholders})
+             GROUP BY d.creature_name
+             ORDER BY SUM(d.hits) DESC, d.creature_name",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        // Two IN (?) lists: the killed_by subquery and the main WHERE.
+        let rows = stmt.query_map(params_from_iter(ids.iter().chain(ids.iter())), |row| {
+            Ok(DamageTaken {
+                creature_name: row.get(0)?,
+                hits: row.get(1)?,
+                killed_by: row.get(2)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damage_taken_merges_and_joins_killed_by() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Main").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.record_damage_taken(main, "Rat").unwrap();
+        db.record_damage_taken(alt, "Orga Anger").unwrap();
+        db.record_damage_taken(alt, "Orga Anger").unwrap();
+        db.upsert_kill(alt, "Orga Anger", "killed_by_count", 0, "2024-01-01 12:00:00").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let taken = db.get_damage_taken_merged(main).unwrap();
+        let rows: Vec<(&str, i64, i64)> =
+            taken.iter().map(|d| (d.creature_name.as_str(), d.hits, d.killed_by)).collect();
+        assert_eq!(rows, vec![("Orga Anger", 2, 1), ("Rat", 1, 0)]);
+    }
+}
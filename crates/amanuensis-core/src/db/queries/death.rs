@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use chrono::Timelike;
+use rusqlite::{params, params_from_iter};
+use serde::Serialize;
+
+use crate::error::{AmanuensisError, Result};
+use super::Database;
+use crate::date::Timestamp;
+
+/// Values of `deaths.outcome`: what followed a fall. A fall is `unresolved` until the
+/// character departs or is raised later in the same log file.
+pub const DEATH_OUTCOMES: &[&str] = &["unresolved", "departed", "rescued"];
+
+/// Parts of the day deaths are grouped into, by the hour they start at.
+const TIME_OF_DAY: [(&str, u32); 4] = [("night", 0), ("morning", 6), ("afternoon", 12), ("evening", 18)];
+
+/// One fall of the character, dated by its log line.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Death {
+    pub cause: String,
+    /// Area the character was in (see `ZoneVisit`), empty when unknown.
+    pub area: String,
+    pub date: Timestamp,
+    /// One of [`DEATH_OUTCOMES`].
+    pub outcome: String,
+    /// Date of the depart or recovery line; None while unresolved.
+    pub resolved_at: Option<Timestamp>,
+    /// Times the character was chain-dragged while fallen.
+    pub drags: i64,
+}
+
+impl Death {
+    /// Seconds spent fallen, when the fall was resolved and both lines were timestamped.
+    pub fn downtime_secs(&self) -> Option<i64> {
+        Some(self.resolved_at?.to_epoch() - self.date.to_epoch()).filter(|s| *s >= 0)
+    }
+}
+
+/// How the character's falls ended: each fall paired with the depart or recovery that
+/// followed it in the same log file.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct FallOutcomes {
+    pub falls: i64,
+    pub departed: i64,
+    pub rescued: i64,
+    pub unresolved: i64,
+    /// Rescued as a percentage of resolved falls; None before the first resolved fall.
+    pub rescue_rate: Option<f64>,
+    /// Mean seconds from fall to depart or recovery, over resolved falls with timestamps.
+    pub avg_downtime_secs: Option<f64>,
+    /// Mean seconds from fall to recovery, over rescued falls with timestamps.
+    pub avg_rescue_secs: Option<f64>,
+    /// Chain drags of the character per fall.
+    pub chains_per_fall: f64,
+}
+
+impl FallOutcomes {
+    fn from_deaths(deaths: &[Death]) -> Self {
+        let count = |outcome: &str| deaths.iter().filter(|d| d.outcome == outcome).count() as i64;
+        let mean = |outcome: Option<&str>| {
+            let secs: Vec<i64> = deaths
+                .iter()
+                .filter(|d| outcome.is_none_or(|o| d.outcome == o))
+                .filter_map(Death::downtime_secs)
+                .collect();
+            (!secs.is_empty()).then(|| secs.iter().sum::<i64>() as f64 / secs.len() as f64)
+        };
+        let (departed, rescued) = (count("departed"), count("rescued"));
+        let falls = deaths.len() as i64;
+        FallOutcomes {
+            falls,
+            departed,
+            rescued,
+            unresolved: falls - departed - rescued,
+            rescue_rate: (departed + rescued > 0).then(|| rescued as f64 / (departed + rescued) as f64 * 100.0),
+            avg_downtime_secs: mean(None),
+            avg_rescue_secs: mean(Some("rescued")),
+            chains_per_fall: if falls > 0 { deaths.iter().map(|d| d.drags).sum::<i64>() as f64 / falls as f64 } else { 0.0 },
+        }
+    }
+}
+
+/// Deaths sharing a cause, area or time of day, split by outcome.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct DeathGroup {
+    pub key: String,
+    pub deaths: i64,
+    pub departed: i64,
+    pub rescued: i64,
+    /// None for a group with no deaths.
+    pub last_date: Option<Timestamp>,
+}
+
+impl DeathGroup {
+    fn add(&mut self, death: &Death) {
+        self.deaths += 1;
+        match death.outcome.as_str() {
+            "departed" => self.departed += 1,
+            "rescued" => self.rescued += 1,
+            _ => {}
+        }
+        self.last_date = self.last_date.max(Some(death.date));
+    }
+}
+
+/// Deaths of a (possibly merged) character broken down by who, where and when.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct DeathReport {
+    /// All deaths, with outcome totals.
+    pub total: DeathGroup,
+    /// By killer, most deaths first.
+    pub by_creature: Vec<DeathGroup>,
+    /// By area, most deaths first; deaths with no known area are keyed "".
+    pub by_area: Vec<DeathGroup>,
+    /// Rescue rate, downtime and chains per fall.
+    pub outcomes: FallOutcomes,
+    /// Night, morning, afternoon and evening (local log time), in that order.
+    pub by_time_of_day: Vec<DeathGroup>,
+    /// Deaths per hour of day, 0–23.
+    pub by_hour: Vec<i64>,
+}
+
+fn grouped(deaths: &[Death], key: impl Fn(&Death) -> &str) -> Vec<DeathGroup> {
+    let mut groups: HashMap<&str, DeathGroup> = HashMap::new();
+    for d in deaths {
+        let k = key(d);
+        groups.entry(k).or_insert_with(|| DeathGroup { key: k.to_string(), ..Default::default() }).add(d);
+    }
+    let mut groups: Vec<DeathGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.deaths.cmp(&a.deaths).then_with(|| a.key.cmp(&b.key)));
+    groups
+}
+
+impl Database {
+    /// Record a fall of the character. Re-scanning the same line is a no-op.
+    pub fn record_death(&self, char_id: i64, cause: &str, area: Option<&str>, date: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO deaths (character_id, cause, area, date) VALUES (?1, ?2, ?3, ?4)",
+            params![char_id, cause, area.unwrap_or(""), date],
+        )?;
+        Ok(())
+    }
+
+    /// Set what followed the fall at `date` (a [`DEATH_OUTCOMES`] value), and when.
+    pub fn resolve_death(&self, char_id: i64, date: &str, outcome: &str, resolved_at: &str) -> Result<()> {
+        if !DEATH_OUTCOMES.contains(&outcome) {
+            return Err(AmanuensisError::Data(format!("Unknown death outcome: {}", outcome)));
+        }
+        self.conn.execute(
+            "UPDATE deaths SET outcome = ?3, resolved_at = ?4 WHERE character_id = ?1 AND date = ?2",
+            params![char_id, date, outcome, resolved_at],
+        )?;
+        Ok(())
+    }
+
+    /// Count a chain drag of the character during the fall at `date`.
+    pub fn record_death_drag(&self, char_id: i64, date: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE deaths SET drags = drags + 1 WHERE character_id = ?1 AND date = ?2",
+            params![char_id, date],
+        )?;
+        Ok(())
+    }
+
+    /// Rescue rate, downtime and chains per fall for a (possibly merged) character.
+    pub fn fall_outcomes(&self, char_id: i64) -> Result<FallOutcomes> {
+        Ok(FallOutcomes::from_deaths(&self.get_deaths_merged(char_id)?))
+    }
+
+    /// Deaths of a character and all its merge sources, oldest first.
+    pub fn get_deaths_merged(&self, char_id: i64) -> Result<Vec<Death>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT cause, area, date, outcome, resolved_at, drags FROM deaths
+             WHERE character_id IN ({placeholders})
+             ORDER BY date, id",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(Death {
+                cause: row.get(0)?,
+                area: row.get(1)?,
+                date: row.get(2)?,
+                outcome: row.get(3)?,
+                resolved_at: row.get(4)?,
+                drags: row.get(5)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Deaths by killer, area and time of day for a (possibly merged) character.
+    pub fn get_death_report_merged(&self, char_id: i64) -> Result<DeathReport> {
+        let deaths = self.get_deaths_merged(char_id)?;
+        let mut report = DeathReport { by_hour: vec![0; 24], ..Default::default() };
+        report.total.key = "all".into();
+        let mut by_time: Vec<DeathGroup> = TIME_OF_DAY
+            .iter()
+            .map(|(name, _)| DeathGroup { key: name.to_string(), ..Default::default() })
+            .collect();
+        for d in &deaths {
+            report.total.add(d);
+            let hour = d.date.naive().hour();
+            report.by_hour[hour as usize] += 1;
+            let period = TIME_OF_DAY.iter().rposition(|(_, start)| hour >= *start).unwrap_or(0);
+            by_time[period].add(d);
+        }
+        report.by_creature = grouped(&deaths, |d| d.cause.as_str());
+        report.by_area = grouped(&deaths, |d| d.area.as_str());
+        report.by_time_of_day = by_time;
+        report.outcomes = FallOutcomes::from_deaths(&deaths);
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_groups_by_cause_area_and_time_with_outcomes() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Fen").unwrap();
+        let alt = db.get_or_create_character("Fenn").unwrap();
+        db.record_death(main, "Orga Anger", Some("Orga territory"), "2024-01-01 02:00:00").unwrap();
+        db.record_death(main, "Orga Anger", Some("Orga territory"), "2024-01-01 02:00:00").unwrap();
+        db.record_death(main, "Orga Anger", Some("Orga territory"), "2024-01-02 13:30:00").unwrap();
+        db.record_death(alt, "Large Vermine", None, "2024-01-03 19:00:00").unwrap();
+        db.resolve_death(main, "2024-01-01 02:00:00", "departed", "2024-01-01 02:01:00").unwrap();
+        db.resolve_death(main, "2024-01-02 13:30:00", "rescued", "2024-01-02 13:33:00").unwrap();
+        assert!(db.resolve_death(main, "2024-01-02 13:30:00", "exploded", "2024-01-02 13:33:00").is_err());
+        db.merge_characters(&[alt], main).unwrap();
+
+        let report = db.get_death_report_merged(main).unwrap();
+        assert_eq!((report.total.deaths, report.total.departed, report.total.rescued), (3, 1, 1));
+        assert_eq!(report.by_creature[0].key, "Orga Anger");
+        assert_eq!(report.by_creature[0].deaths, 2);
+        assert_eq!(report.by_creature[0].last_date.unwrap(), "2024-01-02 13:30:00");
+        let areas: Vec<(&str, i64)> = report.by_area.iter().map(|g| (g.key.as_str(), g.deaths)).collect();
+        assert_eq!(areas, [("Orga territory", 2), ("", 1)]);
+        let periods: Vec<(&str, i64)> = report.by_time_of_day.iter().map(|g| (g.key.as_str(), g.deaths)).collect();
+        assert_eq!(periods, [("night", 1), ("morning", 0), ("afternoon", 1), ("evening", 1)]);
+        assert_eq!((report.by_hour[2], report.by_hour[13], report.by_hour[19]), (1, 1, 1));
+    }
+
+    #[test]
+    fn fall_outcomes_rate_downtime_and_chains() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Fen").unwrap();
+        assert_eq!(db.fall_outcomes(c).unwrap(), FallOutcomes::default());
+
+        for date in ["2024-01-01 10:00:00", "2024-01-01 11:00:00", "2024-01-01 12:00:00", "2024-01-01 13:00:00"] {
+            db.record_death(c, "Orga Anger", None, date).unwrap();
+        }
+        db.resolve_death(c, "2024-01-01 10:00:00", "departed", "2024-01-01 10:00:30").unwrap();
+        db.resolve_death(c, "2024-01-01 11:00:00", "rescued", "2024-01-01 11:02:00").unwrap();
+        db.resolve_death(c, "2024-01-01 12:00:00", "rescued", "2024-01-01 12:04:00").unwrap();
+        db.record_death_drag(c, "2024-01-01 11:00:00").unwrap();
+        db.record_death_drag(c, "2024-01-01 12:00:00").unwrap();
+        db.record_death_drag(c, "2024-01-01 12:00:00").unwrap();
+
+        let o = db.fall_outcomes(c).unwrap();
+        assert_eq!((o.falls, o.departed, o.rescued, o.unresolved), (4, 1, 2, 1));
+        assert!((o.rescue_rate.unwrap() - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(o.avg_downtime_secs, Some(130.0));
+        assert_eq!(o.avg_rescue_secs, Some(180.0));
+        assert_eq!(o.chains_per_fall, 0.75);
+    }
+}
@@ -0,0 +1,173 @@
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::error::{AmanuensisError, Result};
+use super::Database;
+
+/// Tables whose rows belong to one character via `character_id`. `log_lines` (the optional
+/// FTS index) is handled separately.
+const CHARACTER_TABLES: &[&str] = &[
+    "kills",
+    "kill_hourly",
+    "coin_daily",
+    "history_events",
+    "sessions",
+    "clan_history",
+    "tournaments",
+    "snapshots",
+    "creature_accuracy",
+    "damage_taken",
+    "companions",
+    "heals",
+    "rescues",
+    "falls_witnessed",
+    "circles",
+    "stone_events",
+    "casino_events",
+    "unknown_lines",
+    "chat",
+    "thoughts",
+    "zone_visits",
+    "kills_by_location",
+    "deaths",
+    "scan_contributions",
+    "milestones",
+    "assists",
+    "trainers",
+    "trainer_checkpoints",
+    "trainer_rank_history",
+    "lastys",
+    "pet_events",
+    "pet_ranks",
+    "pets",
+    "log_files",
+    "character_meta",
+];
+
+/// What `delete_character` removed.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CharacterDeletion {
+    pub name: String,
+    pub kills: i64,
+    pub trainers: i64,
+    pub log_files: i64,
+    /// Rows removed from every other character table, search lines included.
+    pub other_rows: i64,
+    /// Characters that were merged into the deleted one; they are unmerged, not deleted.
+    pub unmerged: Vec<String>,
+}
+
+impl Database {
+    /// Delete one character and everything recorded for it. Characters merged into it are
+    /// unmerged and left standalone; if it was itself merged, its former target's stats are
+    /// recalculated. Runs in a transaction for atomicity.
+    pub fn delete_character(&self, char_id: i64) -> Result<CharacterDeletion> {
+        self.begin_transaction()?;
+        match self.delete_character_inner(char_id) {
+            Ok(deletion) => { self.commit_transaction()?; Ok(deletion) }
+            Err(e) => { let _ = self.rollback_transaction(); Err(e) }
+        }
+    }
+
+    fn delete_character_inner(&self, char_id: i64) -> Result<CharacterDeletion> {
+        let character = self.get_character_by_id(char_id)?.ok_or_else(|| {
+            AmanuensisError::CharacterNotFound { name: char_id.to_string() }
+        })?;
+        let merged_into: Option<i64> = self.conn.query_row(
+            "SELECT merged_into FROM characters WHERE id = ?1",
+            params![char_id],
+            |row| row.get(0),
+        )?;
+        let mut deletion = CharacterDeletion { name: character.name, ..Default::default() };
+
+        for source in self.get_merge_sources(char_id)? {
+            self.conn.execute(
+                "UPDATE characters SET merged_into = NULL WHERE id = ?1",
+                params![source.id],
+            )?;
+            deletion.unmerged.push(source.name);
+        }
+
+        for table in CHARACTER_TABLES {
+            let removed = self
+                .conn
+                .execute(&format!("DELETE FROM {table} WHERE character_id = ?1"), params![char_id])?
+                as i64;
+            match *table {
+                "kills" => deletion.kills = removed,
+                "trainers" => deletion.trainers = removed,
+                "log_files" => deletion.log_files = removed,
+                _ => deletion.other_rows += removed,
+            }
+        }
+        // The search index is optional (FTS5 may be unavailable).
+        deletion.other_rows += self
+            .conn
+            .execute("DELETE FROM log_lines WHERE character_id = ?1", params![char_id])
+            .unwrap_or(0) as i64;
+
+        self.conn.execute("DELETE FROM characters WHERE id = ?1", params![char_id])?;
+        if let Some(target) = merged_into {
+            self.recalculate_merged_stats(target)?;
+        }
+        Ok(deletion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_character_removes_only_that_character() {
+        let db = Database::open_in_memory().unwrap();
+        let keep = db.get_or_create_character("Keeper").unwrap();
+        let gone = db.get_or_create_character("Mistake").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        for c in [keep, gone] {
+            db.increment_character_field(c, "logins", 1).unwrap();
+            db.upsert_kill(c, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+            db.upsert_trainer_rank(c, "Histia", "2024-01-01", 1.0).unwrap();
+            db.upsert_lasty(c, "Maha Ruknee", "Befriend", "2024-01-01").unwrap();
+        }
+        db.mark_log_scanned(gone, "mistake.txt", "h1", 10, "2024-01-01").unwrap();
+        db.insert_log_lines(&[(gone, "hello", "2024-01-01 10:00:00", "mistake.txt")]).unwrap();
+        db.merge_characters(&[alt], gone).unwrap();
+
+        let deletion = db.delete_character(gone).unwrap();
+        assert_eq!(
+            (deletion.name.as_str(), deletion.kills, deletion.trainers, deletion.log_files),
+            ("Mistake", 1, 1, 1)
+        );
+        assert_eq!(deletion.unmerged, vec!["Alt"]);
+        assert!(db.get_character_by_id(gone).unwrap().is_none());
+        assert!(db.get_kills(gone).unwrap().is_empty());
+        assert!(!db.is_log_scanned("mistake.txt").unwrap());
+        assert_eq!(db.log_line_count().unwrap(), 0);
+        assert_eq!(db.get_merged_into_name(alt).unwrap(), None);
+        assert_eq!(db.get_kills(keep).unwrap().len(), 1);
+        assert_eq!(db.get_trainers(keep).unwrap().len(), 1);
+
+        assert_eq!(db.delete_character(gone).unwrap_err().kind(), "character_not_found");
+    }
+
+    #[test]
+    fn every_table_with_a_character_id_is_cleared() {
+        let db = Database::open_in_memory().unwrap();
+        let mut stmt = db
+            .conn
+            .prepare(
+                "SELECT m.name FROM sqlite_master m
+                 WHERE m.type = 'table'
+                   AND EXISTS (SELECT 1 FROM pragma_table_info(m.name) WHERE name = 'character_id')
+                 ORDER BY m.name",
+            )
+            .unwrap();
+        let tables: Vec<String> = stmt.query_map([], |row| row.get(0)).unwrap().map(|r| r.unwrap()).collect();
+        assert!(tables.contains(&"kills".to_string()));
+        // log_lines (the search index) is cleared separately since FTS5 may be unavailable.
+        let missing: Vec<&String> =
+            tables.iter().filter(|t| *t != "log_lines" && !CHARACTER_TABLES.contains(&t.as_str())).collect();
+        assert!(missing.is_empty(), "tables not cleared by delete_character: {missing:?}");
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+
+/// Ledger sources written by the scanner. Income is stored positive, spending negative.
+/// Casino wins and losses are separate sources so a day's results are never netted.
+pub const COIN_SOURCES: &[&str] =
+    &["picked_up", "fur", "blood", "mandible", "bounty", "study", "casino_won", "casino_lost"];
+
+/// Period lengths accepted by [`Database::get_coin_timeline_merged`].
+pub const COIN_TIMELINE_PERIODS: &[&str] = &["daily", "weekly", "monthly", "yearly"];
+
+/// Lifetime coin counters for a (possibly merged) character.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CoinTotals {
+    pub coins_picked_up: i64,
+    pub fur_coins: i64,
+    pub blood_coins: i64,
+    pub mandible_coins: i64,
+    pub fur_worth: i64,
+    pub blood_worth: i64,
+    pub mandible_worth: i64,
+    pub bounty_coins: i64,
+    pub chest_coins: i64,
+    pub casino_won: i64,
+    pub casino_lost: i64,
+}
+
+/// Net coins from one ledger source within the requested window.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CoinBucket {
+    pub source: String,
+    pub amount: i64,
+}
+
+/// One day of coin activity: the day's net change and the running balance at its end.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BalancePoint {
+    pub day: String,
+    pub amount: i64,
+    pub balance: i64,
+}
+
+/// Coin income and spending over one period ("2024-03", "2024-W11", ...).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CoinPeriod {
+    pub period: String,
+    pub income: i64,
+    /// Coins spent, as a positive number.
+    pub spent: i64,
+    pub net: i64,
+    /// Running ledger balance at the end of the period.
+    pub balance: i64,
+}
+
+/// Everything the economy dashboard shows, in one payload.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct Economy {
+    pub totals: CoinTotals,
+    pub buckets: Vec<CoinBucket>,
+    pub history: Vec<BalancePoint>,
+}
+
+impl Database {
+    /// Add `amount` coins from `source` to the (character, day) ledger row.
+    /// `day` is "YYYY-MM-DD"; `source` must be one of `COIN_SOURCES`.
+    pub fn upsert_coin_daily(&self, char_id: i64, source: &str, day: &str, amount: i64) -> Result<()> {
+        if !COIN_SOURCES.contains(&source) {
+            return Err(crate::error::AmanuensisError::Data(format!(
+                "Unknown coin source: {}",
+                source
+            )));
+        }
+        self.conn.execute(
+            "INSERT INTO coin_daily (character_id, day, source, amount)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(character_id, day, source) DO UPDATE SET amount = amount + excluded.amount",
+            params![char_id, day, source, amount],
+        )?;
+        Ok(())
+    }
+
+    /// Economy payload for a (possibly merged) character. `days` limits buckets and history
+    /// to the last N days of recorded activity (counted back from the character's latest
+    /// ledger day, since logs are historical); None covers the whole ledger. Totals are
+    /// always lifetime, and include casino/chest counters that imports also fill in.
+    pub fn get_economy_merged(&self, char_id: i64, days: Option<i64>) -> Result<Economy> {
+        let totals = match self.get_character_merged(char_id)? {
+            Some(c) => CoinTotals {
+                coins_picked_up: c.coins_picked_up,
+                fur_coins: c.fur_coins,
+                blood_coins: c.blood_coins,
+                mandible_coins: c.mandible_coins,
+                fur_worth: c.fur_worth,
+                blood_worth: c.blood_worth,
+                mandible_worth: c.mandible_worth,
+                bounty_coins: c.bounty_coins,
+                chest_coins: c.chest_coins,
+                casino_won: c.casino_won,
+                casino_lost: c.casino_lost,
+            },
+            None => return Ok(Economy::default()),
+        };
+
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT day, source, SUM(amount) FROM coin_daily
+             WHERE character_id IN ({placeholders})
+             GROUP BY day, source
+             ORDER BY day",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows: Vec<(String, String, i64)> = stmt
+            .query_map(rusqlite::params_from_iter(ids.iter()), |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        // Window start as a "YYYY-MM-DD" string; zero-padded days compare lexically.
+        let since = match (days, rows.last()) {
+            (Some(n), Some((latest, _, _))) => NaiveDate::parse_from_str(latest, "%Y-%m-%d")
+                .ok()
+                .map(|d| (d - Duration::days(n.max(1) - 1)).format("%Y-%m-%d").to_string()),
+            _ => None,
+        };
+
+        let mut by_source: BTreeMap<String, i64> = BTreeMap::new();
+        let mut by_day: BTreeMap<String, i64> = BTreeMap::new();
+        for (day, source, amount) in rows {
+            if since.as_deref().is_some_and(|s| day.as_str() < s) {
+                continue;
+            }
+            *by_source.entry(source).or_default() += amount;
+            *by_day.entry(day).or_default() += amount;
+        }
+
+        let mut buckets: Vec<CoinBucket> = by_source
+            .into_iter()
+            .map(|(source, amount)| CoinBucket { source, amount })
+            .collect();
+        buckets.sort_by(|a, b| b.amount.abs().cmp(&a.amount.abs()).then(a.source.cmp(&b.source)));
+
+        let mut balance = 0;
+        let history = by_day
+            .into_iter()
+            .map(|(day, amount)| {
+                balance += amount;
+                BalancePoint { day, amount, balance }
+            })
+            .collect();
+
+        Ok(Economy { totals, buckets, history })
+    }
+
+    /// The coin ledger of a (possibly merged) character rolled up into periods, oldest
+    /// first. `period` is one of [`COIN_TIMELINE_PERIODS`]; weeks are ISO weeks.
+    pub fn get_coin_timeline_merged(&self, char_id: i64, period: &str) -> Result<Vec<CoinPeriod>> {
+        let format = match period {
+            "daily" => "%Y-%m-%d",
+            "weekly" => "%G-W%V",
+            "monthly" => "%Y-%m",
+            "yearly" => "%Y",
+            _ => {
+                return Err(crate::error::AmanuensisError::Data(format!(
+                    "Unknown timeline period '{}' (expected one of: {})",
+                    period,
+                    COIN_TIMELINE_PERIODS.join(", ")
+                )))
+            }
+        };
+
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT day, source, SUM(amount) FROM coin_daily
+             WHERE character_id IN ({placeholders})
+             GROUP BY day, source
+             ORDER BY day",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows: Vec<(String, i64)> = stmt
+            .query_map(rusqlite::params_from_iter(ids.iter()), |row| Ok((row.get(0)?, row.get(2)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        // Every source is all income or all spending, so a (day, source) total keeps its sign.
+        let mut by_period: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+        for (day, amount) in rows {
+            let Ok(date) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") else { continue };
+            let entry = by_period.entry(date.format(format).to_string()).or_default();
+            if amount >= 0 {
+                entry.0 += amount;
+            } else {
+                entry.1 -= amount;
+            }
+        }
+
+        let mut balance = 0;
+        Ok(by_period
+            .into_iter()
+            .map(|(period, (income, spent))| {
+                balance += income - spent;
+                CoinPeriod { period, income, spent, net: income - spent, balance }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::queries::Database;
+
+    #[test]
+    fn ledger_accumulates_per_day_and_source() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.upsert_coin_daily(c, "picked_up", "2024-01-01", 10).unwrap();
+        db.upsert_coin_daily(c, "picked_up", "2024-01-01", 5).unwrap();
+        db.upsert_coin_daily(c, "fur", "2024-01-01", 40).unwrap();
+        db.upsert_coin_daily(c, "study", "2024-01-03", -30).unwrap();
+        db.increment_character_field(c, "coins_picked_up", 15).unwrap();
+        db.increment_character_field(c, "casino_won", 7).unwrap();
+
+        let eco = db.get_economy_merged(c, None).unwrap();
+        assert_eq!(eco.totals.coins_picked_up, 15);
+        assert_eq!(eco.totals.casino_won, 7);
+        let sources: Vec<(&str, i64)> =
+            eco.buckets.iter().map(|b| (b.source.as_str(), b.amount)).collect();
+        assert_eq!(sources, vec![("fur", 40), ("study", -30), ("picked_up", 15)]);
+        let history: Vec<(&str, i64, i64)> =
+            eco.history.iter().map(|p| (p.day.as_str(), p.amount, p.balance)).collect();
+        assert_eq!(history, vec![("2024-01-01", 55, 55), ("2024-01-03", -30, 25)]);
+    }
+
+    #[test]
+    fn range_counts_back_from_latest_day() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.upsert_coin_daily(c, "bounty", "2024-01-01", 100).unwrap();
+        db.upsert_coin_daily(c, "bounty", "2024-01-25", 20).unwrap();
+        db.upsert_coin_daily(c, "bounty", "2024-01-31", 3).unwrap();
+
+        let eco = db.get_economy_merged(c, Some(7)).unwrap();
+        assert_eq!(eco.buckets.len(), 1);
+        assert_eq!(eco.buckets[0].amount, 23);
+        let days: Vec<&str> = eco.history.iter().map(|p| p.day.as_str()).collect();
+        assert_eq!(days, vec!["2024-01-25", "2024-01-31"]);
+        assert_eq!(eco.history.last().unwrap().balance, 23);
+    }
+
+    #[test]
+    fn merged_sources_share_ledger_days() {
+        let db = Database::open_in_memory().unwrap();
+        let a = db.get_or_create_character("Alpha").unwrap();
+        let b = db.get_or_create_character("Beta").unwrap();
+        db.upsert_coin_daily(a, "picked_up", "2024-01-01", 4).unwrap();
+        db.upsert_coin_daily(b, "picked_up", "2024-01-01", 6).unwrap();
+        db.merge_characters(&[b], a).unwrap();
+
+        let eco = db.get_economy_merged(a, None).unwrap();
+        assert_eq!(eco.history.len(), 1);
+        assert_eq!(eco.history[0].amount, 10);
+    }
+
+    #[test]
+    fn unknown_source_rejected() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        assert!(db.upsert_coin_daily(c, "lottery", "2024-01-01", 1).is_err());
+    }
+
+    #[test]
+    fn timeline_splits_income_and_spending_per_period() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.upsert_coin_daily(c, "fur", "2024-01-05", 100).unwrap();
+        db.upsert_coin_daily(c, "study", "2024-01-20", -30).unwrap();
+        db.upsert_coin_daily(c, "casino_lost", "2024-01-20", -10).unwrap();
+        db.upsert_coin_daily(c, "picked_up", "2024-02-01", 7).unwrap();
+
+        let months: Vec<(String, i64, i64, i64, i64)> = db
+            .get_coin_timeline_merged(c, "monthly")
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.period, p.income, p.spent, p.net, p.balance))
+            .collect();
+        assert_eq!(
+            months,
+            vec![("2024-01".into(), 100, 40, 60, 60), ("2024-02".into(), 7, 0, 7, 67)]
+        );
+        let weeks = db.get_coin_timeline_merged(c, "weekly").unwrap();
+        assert_eq!(weeks[0].period, "2024-W01");
+        assert_eq!(db.get_coin_timeline_merged(c, "yearly").unwrap().len(), 1);
+        assert!(db.get_coin_timeline_merged(c, "hourly").is_err());
+    }
+}
@@ -25,14 +25,7 @@ pub struct CreatureFrequency {
 /// time component the stored value is date-only, which we treat as midnight so the
 /// 24h metric still works (the 2h metric collapses such events to one instant).
 fn parse_ts(s: &str) -> NaiveDateTime {
-    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
-        .or_else(|_| {
-            chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
-                .map(|d| d.and_hms_opt(0, 0, 0).unwrap())
-        })
-        .unwrap_or_else(|_| {
-            NaiveDateTime::parse_from_str("1970-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap()
-        })
+    crate::date::parse(s).unwrap_or_default()
 }
 
 struct Bucket {
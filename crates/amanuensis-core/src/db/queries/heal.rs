@@ -0,0 +1,100 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// Values of `heals.direction`.
+pub const HEAL_DIRECTIONS: &[&str] = &["given", "received"];
+
+/// Heals exchanged with one player.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HealSummary {
+    pub name: String,
+    /// "You heal {name}."
+    pub given: i64,
+    /// "{name} heals you."
+    pub received: i64,
+    /// Earliest / latest heal in either direction.
+    pub first_date: Timestamp,
+    pub last_date: Timestamp,
+}
+
+impl Database {
+    /// Count one heal exchanged with `player`. `direction` is "given" or "received".
+    pub fn record_heal(&self, char_id: i64, player: &str, direction: &str, date: &str) -> Result<()> {
+        if !HEAL_DIRECTIONS.contains(&direction) {
+            return Err(crate::error::AmanuensisError::Data(format!(
+                "Unknown heal direction: {}",
+                direction
+            )));
+        }
+        self.conn.execute(
+            "INSERT INTO heals (character_id, player_name, direction, count, first_date, last_date)
+             VALUES (?1, ?2, ?3, 1, ?4, ?4)
+             ON CONFLICT(character_id, player_name, direction) DO UPDATE SET
+                 count = count + 1,
+                 first_date = MIN(first_date, excluded.first_date),
+                 last_date = MAX(last_date, excluded.last_date)",
+            rusqlite::params![char_id, player, direction, date],
+        )?;
+        Ok(())
+    }
+
+    /// Heals given and received per player for a (possibly merged) character, most heals first.
+    pub fn get_heals_merged(&self, char_id: i64) -> Result<Vec<HealSummary>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT player_name,
+                    SUM(CASE WHEN direction = 'given' THEN count ELSE 0 END),
+                    SUM(CASE WHEN direction = 'received' THEN count ELSE 0 END),
+                    MIN(first_date), MAX(last_date)
+             FROM heals
+             WHERE character_id IN ({placeholders})
+             GROUP BY player_name
+             ORDER BY SUM(count) DESC, player_name",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(HealSummary {
+                name: row.get(0)?,
+                given: row.get(1)?,
+                received: row.get(2)?,
+                first_date: row.get(3)?,
+                last_date: row.get(4)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heals_pivot_directions_and_span_dates() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Healer").unwrap();
+        db.record_heal(c, "Bramble", "given", "2024-01-02 10:00:00").unwrap();
+        db.record_heal(c, "Bramble", "given", "2024-01-01 09:00:00").unwrap();
+        db.record_heal(c, "Bramble", "received", "2024-01-03 09:00:00").unwrap();
+        db.record_heal(c, "Corvid", "received", "2024-01-04 09:00:00").unwrap();
+        assert!(db.record_heal(c, "Corvid", "stolen", "2024-01-04 09:00:00").is_err());
+
+        let heals = db.get_heals_merged(c).unwrap();
+        assert_eq!(
+            heals[0],
+            HealSummary {
+                name: "Bramble".into(),
+                given: 2,
+                received: 1,
+                first_date: "2024-01-01 09:00:00".parse().unwrap(),
+                last_date: "2024-01-03 09:00:00".parse().unwrap(),
+            }
+        );
+        assert_eq!((heals[1].name.as_str(), heals[1].given, heals[1].received), ("Corvid", 0, 1));
+    }
+}
@@ -0,0 +1,401 @@
+use rusqlite::params_from_iter;
+use rusqlite::types::Value;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+
+/// Event types recorded in `history_events`, one row per logged event.
+/// `kill` / `assisted_kill` are kills (subject = creature, value = its creature value; the
+/// fast per-hour counts stay in `kill_hourly`), `coins` is a coin-ledger movement (subject =
+/// the `coin_daily` source, value = signed amount), `boost` is a temporary stat boost,
+/// `permanent_boost` a lasting one and `boost_faded` a temporary boost wearing off.
+pub const HISTORY_EVENT_TYPES: &[&str] = &[
+    "rank",
+    "death",
+    "depart",
+    "kill",
+    "assisted_kill",
+    "coins",
+    "boost",
+    "permanent_boost",
+    "boost_faded",
+];
+
+/// One logged event.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HistoryEvent {
+    /// "YYYY-MM-DD HH:MM:SS"
+    pub date: String,
+    pub event_type: String,
+    /// Trainer for ranks, killer for deaths, creature for kills, ledger source for coins,
+    /// stat for boosts; empty when the event has no subject.
+    pub subject: String,
+    /// Creature value (kills, deaths) or coin amount; 0 for events without one.
+    pub value: i64,
+}
+
+/// One (hour, event type, subject) bucket of the event history.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HistoryEntry {
+    /// "YYYY-MM-DD HH"
+    pub hour: String,
+    pub event_type: String,
+    /// Trainer for ranks, killer for deaths, stat for boosts; empty when the event has no subject.
+    pub subject: String,
+    pub count: i64,
+    /// Summed value (creature values, coin amounts) — 0 for events without one.
+    pub value: i64,
+}
+
+/// Events of one kind on one calendar day.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DailyCount {
+    /// "YYYY-MM-DD"
+    pub day: String,
+    pub count: i64,
+    /// Summed value of the day's events (creature values, coin amounts); 0 otherwise.
+    pub value: i64,
+}
+
+/// Lifetime count of one stat boost, with the hours it was first and last seen so its
+/// effect windows can be lined up against kill or rank history.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StatBoostSummary {
+    /// Lowercase adjective from "You feel {stat}.", e.g. "stronger".
+    pub stat: String,
+    /// Temporary and permanent boosts of the same stat are summarised separately.
+    pub permanent: bool,
+    pub count: i64,
+    /// Times a temporary boost of this stat was seen wearing off; 0 for permanent boosts.
+    pub faded: i64,
+    /// "YYYY-MM-DD HH"
+    pub first_hour: String,
+    pub last_hour: String,
+}
+
+/// When one temporary boost was in effect, to the hour.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BoostWindow {
+    pub stat: String,
+    /// "YYYY-MM-DD HH" the boost was taken.
+    pub start_hour: String,
+    /// Hour of the first fade of this stat logged after the boost (taking the boost again
+    /// before then just refreshes it); None if it never was, e.g. the log ended first.
+    pub end_hour: Option<String>,
+}
+
+impl Database {
+    /// Record one `event_type` event logged at `line` of the file being scanned. Each line is
+    /// recorded once however often the file is re-scanned.
+    pub fn record_history_event(
+        &self,
+        char_id: i64,
+        event_type: &str,
+        subject: &str,
+        value: i64,
+        date: &str,
+        line: usize,
+    ) -> Result<()> {
+        if !HISTORY_EVENT_TYPES.contains(&event_type) {
+            return Err(crate::error::AmanuensisError::Data(format!(
+                "Unknown history event type: {}",
+                event_type
+            )));
+        }
+        let log_file = self.provenance.borrow().clone().unwrap_or_default();
+        self.conn.execute(
+            "INSERT OR IGNORE INTO history_events (character_id, date, event_type, subject, value, log_file, line)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![char_id, date, event_type, subject, value, log_file, line as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Every event of one type for a (possibly merged) character, oldest first.
+    pub fn history_events(&self, char_id: i64, event_type: &str) -> Result<Vec<HistoryEvent>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT date, event_type, subject, value FROM history_events
+             WHERE character_id IN ({placeholders}) AND event_type = ?
+             ORDER BY date, log_file, line",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let args = ids
+            .iter()
+            .map(|&id| Value::Integer(id))
+            .chain(std::iter::once(Value::Text(event_type.to_string())));
+        let rows = stmt.query_map(params_from_iter(args), |row| {
+            Ok(HistoryEvent {
+                date: row.get(0)?,
+                event_type: row.get(1)?,
+                subject: row.get(2)?,
+                value: row.get(3)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Hourly history of one event type for a (possibly merged) character, oldest first.
+    pub fn event_history(&self, char_id: i64, event_type: &str) -> Result<Vec<HistoryEntry>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT substr(date, 1, 13) AS hour, event_type, subject, COUNT(*), SUM(value) FROM history_events
+             WHERE character_id IN ({placeholders}) AND event_type = ?
+             GROUP BY hour, event_type, subject
+             ORDER BY hour, subject",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let args = ids
+            .iter()
+            .map(|&id| Value::Integer(id))
+            .chain(std::iter::once(Value::Text(event_type.to_string())));
+        let rows = stmt.query_map(params_from_iter(args), |row| {
+            Ok(HistoryEntry {
+                hour: row.get(0)?,
+                event_type: row.get(1)?,
+                subject: row.get(2)?,
+                count: row.get(3)?,
+                value: row.get(4)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Per-day totals of one `history_events` event type, merged sources, oldest first.
+    pub fn events_per_day(&self, char_id: i64, event_type: &str) -> Result<Vec<DailyCount>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT substr(date, 1, 10) AS day, COUNT(*), SUM(value) FROM history_events
+             WHERE character_id IN ({placeholders}) AND event_type = ?
+             GROUP BY day
+             ORDER BY day",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let args = ids
+            .iter()
+            .map(|&id| Value::Integer(id))
+            .chain(std::iter::once(Value::Text(event_type.to_string())));
+        let rows = stmt.query_map(params_from_iter(args), map_daily_count)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Kills per day from `kill_hourly`, merged sources, oldest first. `value` sums each
+    /// kill's creature value. `include_assisted=false` counts solo kills only.
+    pub fn kills_per_day(&self, char_id: i64, include_assisted: bool) -> Result<Vec<DailyCount>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let assisted = if include_assisted {
+            " + h.assisted_kill_count + h.assisted_slaughter_count
+              + h.assisted_vanquish_count + h.assisted_dispatch_count"
+        } else {
+            ""
+        };
+        let sql = format!(
+            "SELECT substr(h.hour, 1, 10) AS day,
+                    SUM(h.killed_count + h.slaughtered_count + h.vanquished_count + h.dispatched_count{assisted}),
+                    SUM((h.killed_count + h.slaughtered_count + h.vanquished_count + h.dispatched_count{assisted})
+                        * COALESCE(k.creature_value, 0))
+             FROM kill_hourly h
+             LEFT JOIN kills k ON k.character_id = h.character_id AND k.creature_name = h.creature_name
+             WHERE h.character_id IN ({placeholders})
+             GROUP BY day
+             HAVING SUM(h.killed_count + h.slaughtered_count + h.vanquished_count + h.dispatched_count{assisted}) > 0
+             ORDER BY day",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), map_daily_count)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Trainer ranks earned per day (all trainers), merged sources. Apply-learning bonus
+    /// ranks are not included.
+    pub fn ranks_per_day(&self, char_id: i64) -> Result<Vec<DailyCount>> {
+        self.events_per_day(char_id, "rank")
+    }
+
+    /// Deaths per day, merged sources. `value` sums the killers' creature values.
+    pub fn deaths_per_day(&self, char_id: i64) -> Result<Vec<DailyCount>> {
+        self.events_per_day(char_id, "death")
+    }
+
+    /// Stat boosts (consumables and shrine blessings) per stat and permanence, merged
+    /// sources, most frequent first. Per-hour detail is `event_history(char_id, "boost")`
+    /// (or `"permanent_boost"` / `"boost_faded"`).
+    pub fn stat_boosts(&self, char_id: i64) -> Result<Vec<StatBoostSummary>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT h.subject, h.event_type = 'permanent_boost', COUNT(*),
+                    substr(MIN(h.date), 1, 13), substr(MAX(h.date), 1, 13),
+                    (SELECT COUNT(*) FROM history_events f
+                     WHERE f.character_id IN ({placeholders}) AND f.event_type = 'boost_faded'
+                       AND f.subject = h.subject AND h.event_type = 'boost')
+             FROM history_events h
+             WHERE h.character_id IN ({placeholders}) AND h.event_type IN ('boost', 'permanent_boost')
+             GROUP BY h.subject, h.event_type
+             ORDER BY COUNT(*) DESC, h.subject, h.event_type",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter().chain(ids.iter())), |row| {
+            Ok(StatBoostSummary {
+                stat: row.get(0)?,
+                permanent: row.get(1)?,
+                count: row.get(2)?,
+                faded: row.get(5)?,
+                first_hour: row.get(3)?,
+                last_hour: row.get(4)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Effect windows of every temporary boost, merged sources, oldest first. Boost and
+    /// fade rows are walked in log order (date, then file and line), and each fade ends
+    /// the boosts of its stat still open before it.
+    pub fn boost_windows(&self, char_id: i64) -> Result<Vec<BoostWindow>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT event_type = 'boost_faded', subject, date FROM history_events
+             WHERE character_id IN ({placeholders}) AND event_type IN ('boost', 'boost_faded')
+             ORDER BY date, log_file, line",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok((row.get::<_, bool>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+
+        let mut windows: Vec<BoostWindow> = Vec::new();
+        let mut open: Vec<usize> = Vec::new();
+        for (faded, stat, date) in rows.filter_map(|r| r.ok()) {
+            let hour = date.get(..13).unwrap_or(&date).to_string();
+            if faded {
+                open.retain(|&i| {
+                    if windows[i].stat != stat {
+                        return true;
+                    }
+                    windows[i].end_hour = Some(hour.clone());
+                    false
+                });
+            } else {
+                open.push(windows.len());
+                windows.push(BoostWindow { stat, start_hour: hour, end_hour: None });
+            }
+        }
+        Ok(windows)
+    }
+}
+
+fn map_daily_count(row: &rusqlite::Row<'_>) -> rusqlite::Result<DailyCount> {
+    Ok(DailyCount {
+        day: row.get(0)?,
+        count: row.get(1)?,
+        value: row.get(2)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::queries::Database;
+
+    #[test]
+    fn events_are_rows_rolled_up_by_hour_and_day() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.record_history_event(c, "rank", "Detha", 0, "2024-01-01 10:00:00", 1).unwrap();
+        db.record_history_event(c, "rank", "Detha", 0, "2024-01-01 10:00:00", 2).unwrap();
+        db.record_history_event(c, "rank", "Bodrus", 0, "2024-01-01 22:00:00", 3).unwrap();
+        db.record_history_event(c, "rank", "Detha", 0, "2024-01-03 09:00:00", 4).unwrap();
+        db.record_history_event(c, "death", "Orga", 40, "2024-01-01 11:00:00", 5).unwrap();
+        // The same line scanned again is the same event.
+        db.record_history_event(c, "rank", "Detha", 0, "2024-01-01 10:00:00", 1).unwrap();
+
+        let history = db.event_history(c, "rank").unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!((history[0].hour.as_str(), history[0].subject.as_str(), history[0].count), ("2024-01-01 10", "Detha", 2));
+
+        let ranks: Vec<(String, i64)> =
+            db.ranks_per_day(c).unwrap().into_iter().map(|d| (d.day, d.count)).collect();
+        assert_eq!(ranks, vec![("2024-01-01".to_string(), 3), ("2024-01-03".to_string(), 1)]);
+        let deaths = db.deaths_per_day(c).unwrap();
+        assert_eq!((deaths[0].count, deaths[0].value), (1, 40));
+        let rows = db.history_events(c, "rank").unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!((rows[3].date.as_str(), rows[3].subject.as_str()), ("2024-01-03 09:00:00", "Detha"));
+    }
+
+    #[test]
+    fn kills_per_day_weights_by_creature_value() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        for (creature, field, value, ts) in [
+            ("Rat", "killed_count", 2, "2024-01-01 10:00:00"),
+            ("Rat", "assisted_kill_count", 2, "2024-01-01 11:00:00"),
+            ("Orga", "slaughtered_count", 40, "2024-01-02 10:00:00"),
+        ] {
+            db.upsert_kill(c, creature, field, value, ts).unwrap();
+            db.upsert_kill_hourly(c, creature, field, &ts[..13]).unwrap();
+        }
+
+        let all: Vec<(String, i64, i64)> =
+            db.kills_per_day(c, true).unwrap().into_iter().map(|d| (d.day, d.count, d.value)).collect();
+        assert_eq!(all, vec![("2024-01-01".to_string(), 2, 4), ("2024-01-02".to_string(), 1, 40)]);
+        let solo = db.kills_per_day(c, false).unwrap();
+        assert_eq!(solo[0].count, 1);
+    }
+
+    #[test]
+    fn stat_boosts_summarise_first_and_last_hour() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.record_history_event(c, "boost", "stronger", 0, "2024-01-01 10:00:00", 6).unwrap();
+        db.record_history_event(c, "boost", "stronger", 0, "2024-01-04 20:00:00", 7).unwrap();
+        db.record_history_event(c, "boost", "blessed", 0, "2024-01-02 08:00:00", 8).unwrap();
+
+        db.record_history_event(c, "permanent_boost", "stronger", 0, "2024-01-03 09:00:00", 9).unwrap();
+        db.record_history_event(c, "boost_faded", "stronger", 0, "2024-01-01 11:00:00", 10).unwrap();
+
+        let boosts = db.stat_boosts(c).unwrap();
+        assert_eq!(boosts.len(), 3);
+        assert_eq!((boosts[0].stat.as_str(), boosts[0].permanent), ("stronger", false));
+        assert_eq!((boosts[0].count, boosts[0].faded), (2, 1));
+        assert_eq!((boosts[0].first_hour.as_str(), boosts[0].last_hour.as_str()), ("2024-01-01 10", "2024-01-04 20"));
+        let permanent = boosts.iter().find(|b| b.permanent).unwrap();
+        assert_eq!((permanent.stat.as_str(), permanent.count, permanent.faded), ("stronger", 1, 0));
+    }
+
+    #[test]
+    fn boost_windows_end_at_the_next_fade_of_their_stat() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.record_history_event(c, "boost", "stronger", 0, "2024-01-01 10:00:00", 11).unwrap();
+        db.record_history_event(c, "boost_faded", "stronger", 0, "2024-01-01 11:00:00", 12).unwrap();
+        db.record_history_event(c, "boost", "stronger", 0, "2024-01-02 10:00:00", 13).unwrap();
+        db.record_history_event(c, "boost_faded", "blessed", 0, "2024-01-03 10:00:00", 14).unwrap();
+        // A fade earlier in the same hour belongs to no later boost.
+        db.record_history_event(c, "boost_faded", "wiser", 0, "2024-01-04 10:05:00", 15).unwrap();
+        db.record_history_event(c, "boost", "wiser", 0, "2024-01-04 10:30:00", 16).unwrap();
+
+        let windows: Vec<(String, Option<String>)> =
+            db.boost_windows(c).unwrap().into_iter().map(|w| (w.start_hour, w.end_hour)).collect();
+        assert_eq!(
+            windows,
+            vec![
+                ("2024-01-01 10".to_string(), Some("2024-01-01 11".to_string())),
+                ("2024-01-02 10".to_string(), None),
+                ("2024-01-04 10".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_event_type_rejected() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        assert!(db.record_history_event(c, "lottery", "", 2, "2024-01-01 10:00:00", 15).is_err());
+    }
+}
@@ -141,6 +141,7 @@ impl Database {
                 params![char_id, creature_name, creature_value, date],
             )?;
         }
+        self.record_contribution(char_id, "kill", creature_name, field, 1)?;
         Ok(())
     }
 
@@ -171,6 +172,7 @@ impl Database {
              ON CONFLICT(character_id, creature_name, hour) DO UPDATE SET {field} = {field} + 1",
         );
         self.conn.execute(&sql, params![char_id, creature_name, hour])?;
+        self.record_contribution(char_id, "kill_hourly", &format!("{creature_name}\u{1f}{hour}"), field, 1)?;
         Ok(())
     }
 
@@ -344,6 +346,15 @@ impl Database {
         Ok(out)
     }
 
+    /// `get_encountered_creatures` across a (possibly merged) character's sources.
+    pub fn get_encountered_creatures_merged(&self, char_id: i64) -> Result<std::collections::HashSet<String>> {
+        let mut out = std::collections::HashSet::new();
+        for id in self.char_ids_for_merged(char_id)? {
+            out.extend(self.get_encountered_creatures(id)?);
+        }
+        Ok(out)
+    }
+
     /// Get the nemesis (creature that killed the character the most).
     /// Returns (creature_name, killed_by_count).
     pub fn get_nemesis(&self, char_id: i64) -> Result<Option<(String, i64)>> {
@@ -564,10 +575,10 @@ mod tests {
 
         let kills = db.get_kills(char_id).unwrap();
         let rat = kills.iter().find(|k| k.creature_name == "Rat").unwrap();
-        assert_eq!(rat.date_first_killed.as_deref(), Some("2024-02-02 02:00:00"));
-        assert_eq!(rat.date_first_slaughtered.as_deref(), Some("2024-01-01 09:00:00"));
-        assert_eq!(rat.date_first_vanquished.as_deref(), Some("2024-05-05 12:00:00"));
-        assert_eq!(rat.date_first_dispatched.as_deref(), Some("2024-06-06 06:00:00"));
+        assert_eq!(rat.date_first_killed.unwrap(), "2024-02-02 02:00:00");
+        assert_eq!(rat.date_first_slaughtered.unwrap(), "2024-01-01 09:00:00");
+        assert_eq!(rat.date_first_vanquished.unwrap(), "2024-05-05 12:00:00");
+        assert_eq!(rat.date_first_dispatched.unwrap(), "2024-06-06 06:00:00");
     }
 
     #[test]
@@ -593,5 +604,12 @@ mod tests {
         assert!(encountered.contains("Wolf"));
         assert!(encountered.contains("Tesla"));
         assert!(!encountered.contains("Bat"));
+
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.upsert_kill(alt, "Orga", "killed_by_count", 40, "2024-01-03").unwrap();
+        db.merge_characters(&[alt], char_id).unwrap();
+        let merged = db.get_encountered_creatures_merged(char_id).unwrap();
+        assert_eq!(merged.len(), 4);
+        assert!(merged.contains("Orga") && !db.get_encountered_creatures(char_id).unwrap().contains("Orga"));
     }
 }
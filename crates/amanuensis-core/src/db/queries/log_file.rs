@@ -1,11 +1,163 @@
-use rusqlite::params;
+use regex::{Regex, RegexBuilder};
+use rusqlite::{params, OptionalExtension};
+use rusqlite::types::Value;
+use serde::Serialize;
 
-use crate::error::Result;
-use super::{Database, LogSearchResult};
+use crate::error::{AmanuensisError, Result};
+use crate::parser::patterns::SPEECH_VERBS;
+use super::{Database, LogSearchOptions, LogSearchPage, LogSearchResult, SearchSegment};
+
+/// Missing `log_lines` table or FTS5 module means the index can't be searched at all,
+/// which callers report differently from an ordinary query failure.
+fn fts_error(e: rusqlite::Error) -> AmanuensisError {
+    let msg = e.to_string();
+    if msg.contains("no such table: log_lines") || msg.contains("no such module: fts5") {
+        AmanuensisError::FtsUnavailable { reason: msg }
+    } else {
+        AmanuensisError::Database(e)
+    }
+}
+
+/// The WHERE clause for a search and its parameters: the `MATCH` query for a literal phrase
+/// (left out in regex mode, which filters the rows afterwards), plus the character, speech
+/// and date filters.
+fn search_filter(query: &str, options: &LogSearchOptions) -> (String, Vec<Value>) {
+    let mut params = Vec::new();
+    let mut filter = String::from("1 = 1");
+    if !options.regex {
+        // Escape double quotes in the query and wrap for literal matching
+        params.push(Value::Text(format!("\"{}\"", query.replace('"', "\"\""))));
+        filter.push_str(" AND log_lines MATCH ?1");
+    }
+    if let Some(cid) = options.char_id {
+        params.push(Value::Integer(cid));
+        filter.push_str(&format!(" AND CAST(l.character_id AS INTEGER) = ?{}", params.len()));
+    }
+    if !options.include_speech {
+        filter.push_str(&speech_exclusion());
+    }
+    for (bound, op) in [(&options.from, ">="), (&options.to, "<=")] {
+        if let Some(date) = bound {
+            params.push(Value::Text(date.clone()));
+            filter.push_str(&format!(" AND l.timestamp <> '' AND l.timestamp {op} ?{}", params.len()));
+        }
+    }
+    (filter, params)
+}
+
+/// The search filter that leaves out actions (lines starting with "* ") and everything said
+/// or thought: "… <verb>, "…"", "… <verb> in <language>, "…"" and "… <verb> to <whom>, "…""
+/// for every `SPEECH_VERBS` verb, and the owner's own "You think to …", with straight or
+/// curly quotes.
+fn speech_exclusion() -> String {
+    let mut filter = String::from(" AND l.content NOT LIKE '* %'");
+    for verb in SPEECH_VERBS.iter().copied().chain(["think"]) {
+        for quote in ['"', '\u{201C}'] {
+            for shape in [format!("% {verb}, {quote}%"), format!("% {verb} in %, {quote}%"), format!("% {verb} to %, {quote}%")] {
+                filter.push_str(&format!(" AND l.content NOT LIKE '{shape}'"));
+            }
+        }
+    }
+    filter
+}
+
+/// Compile a regex-mode search query.
+fn search_regex(query: &str) -> Result<Regex> {
+    RegexBuilder::new(query)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| AmanuensisError::Parse(format!("Invalid search regex: {e}")))
+}
+
+/// `highlight_segments` for a regex-mode search: each match of `re` is highlighted.
+fn regex_segments(content: &str, re: &Regex) -> Vec<SearchSegment> {
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for m in re.find_iter(content).filter(|m| !m.is_empty()) {
+        if m.start() > pos {
+            segments.push(SearchSegment { text: content[pos..m.start()].to_string(), highlight: false });
+        }
+        segments.push(SearchSegment { text: m.as_str().to_string(), highlight: true });
+        pos = m.end();
+    }
+    if pos < content.len() {
+        segments.push(SearchSegment { text: content[pos..].to_string(), highlight: false });
+    }
+    segments
+}
+
+/// Split `content` into plain and matching runs for highlighting: occurrences of the whole
+/// query (ASCII case-insensitive), or failing that of its individual words, the way the FTS
+/// tokenizer matched them.
+pub(crate) fn highlight_segments(content: &str, query: &str) -> Vec<SearchSegment> {
+    let phrase = query.trim();
+    let words: Vec<&str> = phrase.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    let mut ranges = find_all(content, phrase);
+    if ranges.is_empty() {
+        ranges = words.iter().flat_map(|w| find_all(content, w)).collect();
+        ranges.sort();
+    }
+
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for (start, end) in ranges {
+        if start < pos {
+            continue;
+        }
+        if start > pos {
+            segments.push(SearchSegment { text: content[pos..start].to_string(), highlight: false });
+        }
+        segments.push(SearchSegment { text: content[start..end].to_string(), highlight: true });
+        pos = end;
+    }
+    if pos < content.len() {
+        segments.push(SearchSegment { text: content[pos..].to_string(), highlight: false });
+    }
+    segments
+}
+
+/// Byte ranges of non-overlapping, ASCII case-insensitive occurrences of `needle`.
+fn find_all(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let mut found = Vec::new();
+    if needle.is_empty() {
+        return found;
+    }
+    let mut i = 0;
+    while i + needle.len() <= haystack.len() {
+        match haystack.get(i..i + needle.len()) {
+            Some(candidate) if candidate.eq_ignore_ascii_case(needle) => {
+                found.push((i, i + needle.len()));
+                i += needle.len();
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+/// State of the full-text search index, for the settings screen.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct IndexStatus {
+    /// Whether scans should index lines (`SETTING_INDEX_LOG_LINES`).
+    pub enabled: bool,
+    /// False when this SQLite build lacks FTS5; the other counts are then 0.
+    pub available: bool,
+    pub lines: i64,
+    /// Characters with at least one indexed line.
+    pub characters: i64,
+    /// Scanned files with at least one indexed line, and all scanned files.
+    pub files: i64,
+    pub scanned_files: i64,
+    /// Rough on-disk size of the index: twice the indexed text (content plus the FTS5
+    /// inverted index).
+    pub estimated_bytes: i64,
+}
 
 impl Database {
-    /// Check if a log file has already been scanned (by path or content hash).
+    /// Check if a log file has already been scanned (by path or content hash). Paths match in
+    /// any form that maps to the same key (see `log_paths`).
     pub fn is_log_scanned(&self, file_path: &str) -> Result<bool> {
+        let file_path = self.log_path_key(file_path)?;
         let count: i64 = self.conn.query_row(
             "SELECT COUNT(*) FROM log_files WHERE file_path = ?1",
             params![file_path],
@@ -28,6 +180,7 @@ impl Database {
     /// `byte_len` is the number of bytes consumed at the last scan (0 for legacy rows
     /// recorded before offset-resume existed). Returns None if the path was never scanned.
     pub fn get_log_scan_state(&self, file_path: &str) -> Result<Option<(i64, String)>> {
+        let file_path = self.log_path_key(file_path)?;
         let res = self.conn.query_row(
             "SELECT byte_len, content_hash FROM log_files WHERE file_path = ?1",
             params![file_path],
@@ -40,9 +193,19 @@ impl Database {
         }
     }
 
+    /// The character a scanned log file was recorded under, by any form of its path.
+    pub fn log_file_character(&self, file_path: &str) -> Result<Option<i64>> {
+        let file_path = self.log_path_key(file_path)?;
+        Ok(self
+            .conn
+            .query_row("SELECT character_id FROM log_files WHERE file_path = ?1", params![file_path], |row| row.get(0))
+            .optional()?)
+    }
+
     /// Mark a log file as scanned, recording its content hash and the byte length
     /// consumed so far. Upserts by path so an appended file's offset is advanced
-    /// (rather than ignored) on re-scan.
+    /// (rather than ignored) on re-scan. `file_path` is the path the file was read through;
+    /// it is stored under its key, and as given in `source_path` for reading it back.
     pub fn mark_log_scanned(
         &self,
         char_id: i64,
@@ -51,18 +214,44 @@ impl Database {
         byte_len: i64,
         date_read: &str,
     ) -> Result<()> {
+        let key = self.log_path_key(file_path)?;
         self.conn.execute(
-            "INSERT INTO log_files (character_id, file_path, content_hash, byte_len, date_read)
-             VALUES (?1, ?2, ?3, ?4, ?5)
+            "INSERT INTO log_files (character_id, file_path, content_hash, byte_len, date_read, source_path)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
              ON CONFLICT(file_path) DO UPDATE SET
                 content_hash = excluded.content_hash,
                 byte_len = excluded.byte_len,
-                date_read = excluded.date_read",
-            params![char_id, file_path, content_hash, byte_len, date_read],
+                date_read = excluded.date_read,
+                source_path = excluded.source_path",
+            params![char_id, key, content_hash, byte_len, date_read, file_path],
         )?;
         Ok(())
     }
 
+    /// Every scanned file as (character_id, file_path, source path, byte_len), in path order.
+    /// The source path is where to read the file (see `log_file_source`).
+    pub fn scanned_log_files(&self) -> Result<Vec<(i64, String, String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT character_id, file_path, COALESCE(source_path, file_path), byte_len FROM log_files ORDER BY file_path",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
+    /// Where to read the scanned file stored under `path` (any form of it, or its key): the
+    /// path it was last scanned through. Stored keys are for matching only, and a root alias
+    /// may have rewritten them into something that isn't a path on this machine. Falls back
+    /// to `path` itself for files scanned before source paths were recorded, or not at all.
+    pub fn log_file_source(&self, path: &str) -> Result<String> {
+        let key = self.log_path_key(path)?;
+        let source: Option<String> = self
+            .conn
+            .query_row("SELECT source_path FROM log_files WHERE file_path = ?1", params![key], |row| row.get(0))
+            .optional()?
+            .flatten();
+        Ok(source.unwrap_or_else(|| path.to_string()))
+    }
+
     /// Get count of scanned log files.
     pub fn scanned_log_count(&self) -> Result<i64> {
         let count: i64 = self.conn.query_row(
@@ -74,14 +263,41 @@ impl Database {
     }
 
     /// Clear all log-derived data while preserving user rank overrides.
-    /// Deletes kills, lastys, pets, log_files, log_lines and resets all stat
+    /// Deletes kills, lastys, pets (and their ranks), log_files, log_lines and resets all stat
     /// columns on characters/trainers to zero. Does NOT touch modified_ranks,
-    /// rank_mode, or override_date.
+    /// rank_mode, or override_date, nor the stats snapshots (history a rescan can't rebuild).
     pub fn reset_log_data(&self) -> Result<()> {
         self.conn.execute_batch(
             "DELETE FROM kills;
              DELETE FROM kill_hourly;
+             DELETE FROM coin_daily;
+             DELETE FROM history_events;
+             DELETE FROM sessions;
+             DELETE FROM clan_history;
+             DELETE FROM tournaments;
+             DELETE FROM creature_accuracy;
+             DELETE FROM damage_taken;
+             DELETE FROM companions;
+             DELETE FROM heals;
+             DELETE FROM rescues;
+             DELETE FROM falls_witnessed;
+             DELETE FROM circles;
+             DELETE FROM stone_events;
+             DELETE FROM casino_events;
+             DELETE FROM unknown_lines;
+             DELETE FROM chat;
+             DELETE FROM scan_errors;
+             DELETE FROM thoughts;
+             DELETE FROM zone_visits;
+             DELETE FROM kills_by_location;
+             DELETE FROM deaths;
+             DELETE FROM scan_contributions;
+             DELETE FROM milestones;
+             DELETE FROM assists;
+             DELETE FROM trainer_rank_history;
              DELETE FROM lastys;
+             DELETE FROM pet_events;
+             DELETE FROM pet_ranks;
              DELETE FROM pets;
              DELETE FROM log_files;
              DELETE FROM log_lines;
@@ -99,7 +315,7 @@ impl Database {
                eps_broken=0, untraining_count=0, profession='Unknown';
              UPDATE trainers SET
                ranks=0, apply_learning_ranks=0, apply_learning_unknown_count=0,
-               date_of_last_rank=NULL;",
+               date_of_last_rank=NULL, maxed=0;",
         )?;
         Ok(())
     }
@@ -110,13 +326,42 @@ impl Database {
         self.conn.execute_batch(
             "DELETE FROM kills;
              DELETE FROM kill_hourly;
+             DELETE FROM coin_daily;
+             DELETE FROM history_events;
+             DELETE FROM sessions;
+             DELETE FROM clan_history;
+             DELETE FROM tournaments;
+             DELETE FROM snapshots;
+             DELETE FROM creature_accuracy;
+             DELETE FROM damage_taken;
+             DELETE FROM companions;
+             DELETE FROM heals;
+             DELETE FROM rescues;
+             DELETE FROM falls_witnessed;
+             DELETE FROM circles;
+             DELETE FROM stone_events;
+             DELETE FROM casino_events;
+             DELETE FROM unknown_lines;
+             DELETE FROM chat;
+             DELETE FROM scan_errors;
+             DELETE FROM thoughts;
+             DELETE FROM zone_visits;
+             DELETE FROM kills_by_location;
+             DELETE FROM deaths;
+             DELETE FROM scan_contributions;
+             DELETE FROM milestones;
+             DELETE FROM assists;
+             DELETE FROM trainer_rank_history;
              DELETE FROM lastys;
+             DELETE FROM pet_events;
+             DELETE FROM pet_ranks;
              DELETE FROM pets;
              DELETE FROM log_files;
              DELETE FROM log_lines;
              DELETE FROM process_logs;
              DELETE FROM trainer_checkpoints;
              DELETE FROM trainers;
+             DELETE FROM character_meta;
              DELETE FROM characters;",
         )?;
         Ok(())
@@ -171,25 +416,124 @@ impl Database {
         lines_before: i64,
         lines_after: i64,
     ) -> Result<Vec<LogSearchResult>> {
-        // Escape double quotes in the query and wrap for literal matching
-        let escaped = query.replace('"', "\"\"");
-        let fts_query = format!("\"{}\"", escaped);
+        let options = LogSearchOptions { char_id, include_speech, lines_before, lines_after, limit, ..Default::default() };
+        self.search_rows(query, &options)
+    }
 
-        // Speech/action filter: exclude lines starting with "* " (actions) or matching speech pattern
-        // We use a NOT LIKE filter on content when include_speech = false
-        let speech_filter = if include_speech {
-            ""
+    /// One page of `search_log_lines` results, with the total match count for paging.
+    pub fn search_log_lines_page(&self, query: &str, options: &LogSearchOptions) -> Result<LogSearchPage> {
+        let (results, total) = if options.regex {
+            let (matches, total) = self.regex_rows(query, options)?;
+            (self.with_context(matches, options)?, total)
         } else {
-            " AND l.content NOT LIKE '* %' AND l.content NOT LIKE '%says, \"%' AND l.content NOT LIKE '%says in %'"
+            (self.search_rows(query, options)?, self.count_log_matches(query, options)?)
         };
+        let offset = options.offset.max(0);
+        Ok(LogSearchPage { has_more: offset + (results.len() as i64) < total, results, total, offset })
+    }
+
+    /// Lines matching a search across all pages (`offset` and `limit` are ignored).
+    pub fn count_log_matches(&self, query: &str, options: &LogSearchOptions) -> Result<i64> {
+        if options.regex {
+            let count_only = LogSearchOptions { offset: 0, limit: 0, ..options.clone() };
+            return Ok(self.regex_rows(query, &count_only)?.1);
+        }
+        let (filter, params) = search_filter(query, options);
+        let total = self
+            .conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM log_lines l WHERE {filter}"),
+                rusqlite::params_from_iter(params),
+                |row| row.get(0),
+            )
+            .map_err(fts_error)?;
+        Ok(total)
+    }
+
+    /// Regex-mode matches for one page, in result order, with their rowids, and the total
+    /// match count. Every line passing the SQL filters is tested against the regex.
+    fn regex_rows(&self, query: &str, options: &LogSearchOptions) -> Result<(Vec<(LogSearchResult, i64)>, i64)> {
+        let re = search_regex(query)?;
+        let (filter, params) = search_filter(query, options);
+        let sql = format!(
+            "SELECT l.content, l.character_id, l.timestamp, l.file_path,
+                    COALESCE(c.name, 'Unknown') AS character_name, l.rowid
+             FROM log_lines l
+             LEFT JOIN characters c ON CAST(l.character_id AS INTEGER) = c.id
+             WHERE {filter}
+             ORDER BY l.file_path DESC, l.rowid DESC"
+        );
+        let mut stmt = self.conn.prepare(&sql).map_err(fts_error)?;
+        let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
+        let (skip, limit) = (options.offset.max(0), options.limit.max(0));
+        let mut matches = Vec::new();
+        let mut total = 0;
+        while let Some(row) = rows.next()? {
+            let content: String = row.get(0)?;
+            if !re.is_match(&content) {
+                continue;
+            }
+            total += 1;
+            if total <= skip || total > skip + limit {
+                continue;
+            }
+            let segments = regex_segments(&content, &re);
+            let snippet = segments
+                .iter()
+                .map(|s| if s.highlight { format!("<mark>{}</mark>", s.text) } else { s.text.clone() })
+                .collect();
+            let character_id: i64 = row.get::<_, i64>(1).or_else(|_| {
+                row.get::<_, String>(1).map(|s| s.parse().unwrap_or(0))
+            })?;
+            matches.push((
+                LogSearchResult {
+                    content,
+                    segments,
+                    character_id,
+                    timestamp: row.get(2)?,
+                    file_path: row.get(3)?,
+                    snippet,
+                    character_name: row.get(4)?,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                },
+                row.get(5)?,
+            ));
+        }
+        Ok((matches, total))
+    }
+
+    fn search_rows(&self, query: &str, options: &LogSearchOptions) -> Result<Vec<LogSearchResult>> {
+        if options.regex {
+            let (matches, _) = self.regex_rows(query, options)?;
+            return self.with_context(matches, options);
+        }
+        let (filter, mut params) = search_filter(query, options);
+        let limit_param = params.len() + 1;
+        params.push(Value::Integer(options.limit));
+        params.push(Value::Integer(options.offset.max(0)));
+        let sql = format!(
+            "SELECT l.content, l.character_id, l.timestamp, l.file_path,
+                    snippet(log_lines, 0, '<mark>', '</mark>', '...', 64) AS snippet,
+                    COALESCE(c.name, 'Unknown') AS character_name,
+                    l.rowid
+             FROM log_lines l
+             LEFT JOIN characters c ON CAST(l.character_id AS INTEGER) = c.id
+             WHERE {filter}
+             ORDER BY l.file_path DESC, l.rowid DESC
+             LIMIT ?{limit_param} OFFSET ?{offset_param}",
+            offset_param = limit_param + 1,
+        );
 
         let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<(LogSearchResult, i64)> {
             let character_id: i64 = row.get::<_, i64>(1).or_else(|_| {
                 row.get::<_, String>(1).map(|s| s.parse().unwrap_or(0))
             })?;
             let rowid: i64 = row.get(6)?;
+            let content: String = row.get(0)?;
             Ok((LogSearchResult {
-                content: row.get(0)?,
+                segments: highlight_segments(&content, query),
+                content,
                 character_id,
                 timestamp: row.get(2)?,
                 file_path: row.get(3)?,
@@ -200,44 +544,17 @@ impl Database {
             }, rowid))
         };
 
-        let sql_with_char = format!(
-            "SELECT l.content, l.character_id, l.timestamp, l.file_path,
-                    snippet(log_lines, 0, '<mark>', '</mark>', '...', 64) AS snippet,
-                    COALESCE(c.name, 'Unknown') AS character_name,
-                    l.rowid
-             FROM log_lines l
-             LEFT JOIN characters c ON CAST(l.character_id AS INTEGER) = c.id
-             WHERE log_lines MATCH ?1 AND CAST(l.character_id AS INTEGER) = ?2{speech_filter}
-             ORDER BY l.file_path DESC, l.rowid DESC
-             LIMIT ?3"
-        );
-        let sql_all = format!(
-            "SELECT l.content, l.character_id, l.timestamp, l.file_path,
-                    snippet(log_lines, 0, '<mark>', '</mark>', '...', 64) AS snippet,
-                    COALESCE(c.name, 'Unknown') AS character_name,
-                    l.rowid
-             FROM log_lines l
-             LEFT JOIN characters c ON CAST(l.character_id AS INTEGER) = c.id
-             WHERE log_lines MATCH ?1{speech_filter}
-             ORDER BY l.file_path DESC, l.rowid DESC
-             LIMIT ?2"
-        );
-
-        let raw_results: Vec<(LogSearchResult, i64)> = if let Some(cid) = char_id {
-            let mut stmt = self.conn.prepare(&sql_with_char)?;
-            let rows: Vec<_> = stmt.query_map(params![fts_query, cid, limit], row_mapper)?
-                .filter_map(|r| r.ok())
-                .collect();
-            rows
-        } else {
-            let mut stmt = self.conn.prepare(&sql_all)?;
-            let rows: Vec<_> = stmt.query_map(params![fts_query, limit], row_mapper)?
-                .filter_map(|r| r.ok())
-                .collect();
-            rows
-        };
+        let mut stmt = self.conn.prepare(&sql).map_err(fts_error)?;
+        let raw_results: Vec<(LogSearchResult, i64)> = stmt
+            .query_map(rusqlite::params_from_iter(params), row_mapper)?
+            .filter_map(|r| r.ok())
+            .collect();
+        self.with_context(raw_results, options)
+    }
 
-        // Fetch context lines if requested
+    /// Fill in the lines around each result (same file, by rowid) when requested.
+    fn with_context(&self, raw_results: Vec<(LogSearchResult, i64)>, options: &LogSearchOptions) -> Result<Vec<LogSearchResult>> {
+        let (lines_before, lines_after) = (options.lines_before, options.lines_after);
         if lines_before == 0 && lines_after == 0 {
             return Ok(raw_results.into_iter().map(|(r, _)| r).collect());
         }
@@ -276,4 +593,71 @@ impl Database {
         )?;
         Ok(count)
     }
+
+    /// Line, character and size figures for the search index.
+    pub fn index_status(&self) -> Result<IndexStatus> {
+        let enabled = self.indexing_enabled()?;
+        let scanned_files = self.scanned_log_count()?;
+        let counts = self.conn.query_row(
+            "SELECT COUNT(*), COUNT(DISTINCT character_id), COUNT(DISTINCT file_path),
+                    COALESCE(SUM(LENGTH(content) + LENGTH(timestamp) + LENGTH(file_path)), 0)
+             FROM log_lines",
+            [],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?, row.get::<_, i64>(3)?)),
+        );
+        match counts.map_err(fts_error) {
+            Ok((lines, characters, files, text_bytes)) => Ok(IndexStatus {
+                enabled,
+                available: true,
+                lines,
+                characters,
+                files,
+                scanned_files,
+                estimated_bytes: text_bytes * 2,
+            }),
+            Err(AmanuensisError::FtsUnavailable { .. }) => {
+                Ok(IndexStatus { enabled, scanned_files, ..Default::default() })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Merge the index's b-tree segments into one (FTS5 `optimize`), which speeds up searches
+    /// after many incremental scans.
+    pub fn optimize_log_lines(&self) -> Result<()> {
+        self.conn
+            .execute("INSERT INTO log_lines (log_lines) VALUES ('optimize')", [])
+            .map_err(fts_error)?;
+        Ok(())
+    }
+
+    /// Drop every indexed line and compact the database file so the space is returned.
+    /// Returns the number of lines removed.
+    pub fn clear_log_lines(&self) -> Result<i64> {
+        let removed = self.delete_log_lines_except(&[])?;
+        if removed > 0 {
+            self.vacuum()?;
+        }
+        Ok(removed)
+    }
+
+    /// Drop the indexed lines of every file but `keep` (stored keys), without compacting, so
+    /// it can run inside a transaction. Returns the number of lines removed.
+    pub(crate) fn delete_log_lines_except(&self, keep: &[String]) -> Result<i64> {
+        let placeholders = vec!["?"; keep.len()].join(", ");
+        let removed = self
+            .conn
+            .execute(
+                &format!("DELETE FROM log_lines WHERE file_path NOT IN ({placeholders})"),
+                rusqlite::params_from_iter(keep),
+            )
+            .map_err(fts_error)?;
+        Ok(removed as i64)
+    }
+
+    /// Compact the database file. Can't run inside a transaction.
+    pub(crate) fn vacuum(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM")?;
+        Ok(())
+    }
 }
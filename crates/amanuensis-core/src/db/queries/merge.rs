@@ -1,9 +1,24 @@
 use rusqlite::params;
+use serde::Serialize;
 
 use crate::error::Result;
-use crate::models::{Character, Kill, Lasty, Pet, Trainer};
+use crate::models::{Character, Kill, Lasty, Pet, Trainer, APPLY_LEARNING_PARTIAL_ESTIMATE};
 use super::{CHARACTER_COLUMNS, map_character_row, Database};
 
+/// What a merge would produce, computed without changing the database.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergePreview {
+    /// The target as it is now (including any sources already merged into it).
+    pub before: Character,
+    /// The target with the new sources merged in.
+    pub after: Character,
+    pub kills: Vec<Kill>,
+    pub trainers: Vec<Trainer>,
+    /// Trained + user-modified + apply-learning ranks, before and after.
+    pub total_ranks_before: i64,
+    pub total_ranks_after: i64,
+}
+
 impl Database {
     /// Get all character IDs that have been merged into the given target.
     fn merged_source_ids(&self, target_id: i64) -> Result<Vec<i64>> {
@@ -43,7 +58,7 @@ impl Database {
             |row| row.get(0),
         ).ok();
         let target_merged = target_merged.ok_or_else(|| {
-            crate::error::AmanuensisError::Data(format!("Target character {} not found", target_id))
+            crate::error::AmanuensisError::CharacterNotFound { name: target_id.to_string() }
         })?;
         if target_merged.is_some() {
             return Err(crate::error::AmanuensisError::Data(
@@ -82,9 +97,7 @@ impl Database {
                 |row| row.get(0),
             ).ok();
             let source_merged = source_merged.ok_or_else(|| {
-                crate::error::AmanuensisError::Data(format!(
-                    "Source character {} not found", source_id
-                ))
+                crate::error::AmanuensisError::CharacterNotFound { name: source_id.to_string() }
             })?;
             if source_merged.is_some() {
                 return Err(crate::error::AmanuensisError::Data(format!(
@@ -103,6 +116,41 @@ impl Database {
         Ok(())
     }
 
+    /// Preview merging `source_ids` into `target_id`. The merge runs inside a transaction that
+    /// is always rolled back, so validation and stat recalculation match `merge_characters`
+    /// exactly and the same errors are returned.
+    pub fn preview_merge(&self, source_ids: &[i64], target_id: i64) -> Result<MergePreview> {
+        let total_ranks = |trainers: &[Trainer]| -> i64 {
+            trainers
+                .iter()
+                .map(|t| t.ranks + t.modified_ranks + t.apply_learning_ranks)
+                .sum()
+        };
+        let before = self.get_character_merged(target_id)?.ok_or_else(|| {
+            crate::error::AmanuensisError::CharacterNotFound { name: target_id.to_string() }
+        })?;
+        let total_ranks_before = total_ranks(&self.get_trainers_merged(target_id)?);
+
+        self.begin_transaction()?;
+        let preview = self.merge_characters_inner(source_ids, target_id).and_then(|()| {
+            let after = self.get_character_merged(target_id)?.unwrap_or_else(|| before.clone());
+            let kills = self.get_kills_merged(target_id)?;
+            let trainers = self.get_trainers_merged(target_id)?;
+            Ok((after, kills, trainers))
+        });
+        self.rollback_transaction()?;
+        let (after, kills, trainers) = preview?;
+
+        Ok(MergePreview {
+            before,
+            total_ranks_after: total_ranks(&trainers),
+            after,
+            kills,
+            trainers,
+            total_ranks_before,
+        })
+    }
+
     /// Unmerge a character (clear its merged_into). Recalculates the former target's stats.
     /// Runs in a transaction for atomicity.
     pub fn unmerge_character(&self, source_id: i64) -> Result<()> {
@@ -119,7 +167,7 @@ impl Database {
             params![source_id],
             |row| row.get(0),
         ).map_err(|_| {
-            crate::error::AmanuensisError::Data(format!("Character {} not found", source_id))
+            crate::error::AmanuensisError::CharacterNotFound { name: source_id.to_string() }
         })?;
 
         let former_target = former_target.ok_or_else(|| {
@@ -161,7 +209,7 @@ impl Database {
 
     /// Recalculate a target character's coin_level after merge/unmerge.
     /// Coin level = max creature_value across all personal kills for this character and its sources.
-    fn recalculate_merged_stats(&self, target_id: i64) -> Result<()> {
+    pub(super) fn recalculate_merged_stats(&self, target_id: i64) -> Result<()> {
         let all_ids = self.char_ids_for_merged(target_id)?;
         let coin_level = self.compute_coin_level_for_char_ids(&all_ids)?;
         self.update_coin_level(target_id, coin_level)?;
@@ -172,7 +220,16 @@ impl Database {
 
     /// Get kills aggregated across a character and all its merge sources.
     /// For the same creature, counts are summed; dates take min(first) and max(last).
+    /// With creature-name rules turned on, variants are folded the same way.
     pub fn get_kills_merged(&self, char_id: i64) -> Result<Vec<Kill>> {
+        let kills = self.get_kills_merged_as_stored(char_id)?;
+        Ok(match self.active_creature_name_rules()? {
+            Some(rules) => rules.fold_kills(kills),
+            None => kills,
+        })
+    }
+
+    fn get_kills_merged_as_stored(&self, char_id: i64) -> Result<Vec<Kill>> {
         let all_ids = self.char_ids_for_merged(char_id)?;
         if all_ids.len() == 1 {
             return self.get_kills(char_id);
@@ -245,7 +302,8 @@ impl Database {
                     MAX(CASE WHEN character_id = {cid} THEN rank_mode ELSE 'modifier' END),
                     MAX(CASE WHEN character_id = {cid} THEN override_date ELSE NULL END),
                     MAX(effective_multiplier),
-                    MAX(CASE WHEN character_id = {cid} THEN notes ELSE NULL END)
+                    MAX(CASE WHEN character_id = {cid} THEN notes ELSE NULL END),
+                    MAX(maxed)
              FROM trainers WHERE character_id IN ({placeholders})
              GROUP BY trainer_name
              ORDER BY SUM(ranks) DESC",
@@ -262,16 +320,19 @@ impl Database {
                 date_of_last_rank: row.get(5)?,
                 apply_learning_ranks: row.get(6)?,
                 apply_learning_unknown_count: row.get(7)?,
+                apply_learning_estimated_ranks: row.get::<_, i64>(7)? * APPLY_LEARNING_PARTIAL_ESTIMATE,
                 rank_mode: row.get(8)?,
                 override_date: row.get(9)?,
                 effective_multiplier: row.get(10)?,
                 notes: row.get(11)?,
+                maxed: row.get(12)?,
             })
         })?;
         Ok(trainers.filter_map(|r| r.ok()).collect())
     }
 
     /// Get pets aggregated across a character and all its merge sources (distinct by pet_name).
+    /// Counts and ranks are summed; the pet is active if it is active under any source.
     pub fn get_pets_merged(&self, char_id: i64) -> Result<Vec<Pet>> {
         let all_ids = self.char_ids_for_merged(char_id)?;
         if all_ids.len() == 1 {
@@ -279,21 +340,21 @@ impl Database {
         }
         let placeholders = all_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
         let sql = format!(
-            "SELECT MIN(id), {}, pet_name, creature_name
-             FROM pets WHERE character_id IN ({})
-             GROUP BY pet_name
-             ORDER BY pet_name",
+            "SELECT MIN(p.id), {}, p.pet_name, MAX(p.creature_name),
+                    CASE WHEN SUM(p.status = 'active') > 0 THEN 'active'
+                         WHEN SUM(p.status = 'dead') > 0 THEN 'dead'
+                         ELSE 'released' END,
+                    MIN(p.adopted_date), SUM(p.death_count), SUM(p.feed_count), MAX(p.last_fed_date),
+                    MAX(p.released_date), COALESCE(SUM(r.ranks), 0), COALESCE(SUM(r.trainings), 0),
+                    MAX(MAX(COALESCE(r.last_rank_date, ''), COALESCE(r.last_training_date, '')))
+             FROM pets p LEFT JOIN pet_ranks r ON r.pet_id = p.id
+             WHERE p.character_id IN ({})
+             GROUP BY p.pet_name
+             ORDER BY COALESCE(MIN(p.adopted_date), ''), p.pet_name",
             char_id, placeholders
         );
         let mut stmt = self.conn.prepare(&sql)?;
-        let pets = stmt.query_map(rusqlite::params_from_iter(all_ids.iter()), |row| {
-            Ok(Pet {
-                id: Some(row.get(0)?),
-                character_id: row.get(1)?,
-                pet_name: row.get(2)?,
-                creature_name: row.get(3)?,
-            })
-        })?;
+        let pets = stmt.query_map(rusqlite::params_from_iter(all_ids.iter()), super::pet::pet_from_row)?;
         Ok(pets.filter_map(|r| r.ok()).collect())
     }
 
@@ -384,7 +445,7 @@ impl Database {
                 // Take earlier start_date
                 if let Some(ref source_date) = source.start_date {
                     if merged.start_date.is_none() || merged.start_date.as_ref().unwrap() > source_date {
-                        merged.start_date = Some(source_date.clone());
+                        merged.start_date = Some(*source_date);
                     }
                 }
             }
@@ -0,0 +1,208 @@
+use std::path::Path;
+
+use base64::Engine;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use crate::error::{AmanuensisError, Result};
+use crate::fighter_stats::FighterLoadout;
+use super::Database;
+use crate::date::Timestamp;
+
+/// Largest portrait accepted, in bytes. Portraits are embedded in exports as data URLs.
+pub const MAX_PORTRAIT_BYTES: usize = 2 * 1024 * 1024;
+
+/// A character's portrait image, stored in `character_meta`. User data: it survives
+/// `reset_log_data` and rescans, like rank overrides and trainer notes.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Portrait {
+    /// "image/png", "image/jpeg", "image/gif" or "image/webp".
+    pub mime: String,
+    #[serde(skip)]
+    pub data: Vec<u8>,
+    /// Where the image came from (a file path or URL), if known.
+    pub source: Option<String>,
+    pub updated_at: Timestamp,
+}
+
+impl Portrait {
+    /// The image as a `data:` URL, for `<img src>` in HTML exports and the GUI.
+    pub fn data_url(&self) -> String {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&self.data);
+        format!("data:{};base64,{}", self.mime, encoded)
+    }
+}
+
+/// The image type of `data`, from its magic bytes.
+fn image_mime(data: &[u8]) -> Option<&'static str> {
+    match data {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+impl Database {
+    /// Store `data` as the character's portrait, replacing any previous one. The image type
+    /// is detected from its contents; anything but PNG, JPEG, GIF or WebP is rejected.
+    pub fn set_character_portrait(&self, char_id: i64, data: &[u8], source: Option<&str>) -> Result<Portrait> {
+        let mime = image_mime(data).ok_or_else(|| {
+            AmanuensisError::Data("Portrait must be a PNG, JPEG, GIF or WebP image".to_string())
+        })?;
+        if data.len() > MAX_PORTRAIT_BYTES {
+            return Err(AmanuensisError::Data(format!(
+                "Portrait is {} KB; the limit is {} KB",
+                data.len() / 1024,
+                MAX_PORTRAIT_BYTES / 1024
+            )));
+        }
+        let updated_at = Timestamp::now();
+        self.conn.execute(
+            "INSERT INTO character_meta (character_id, portrait, portrait_mime, portrait_source, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(character_id) DO UPDATE SET
+               portrait = excluded.portrait,
+               portrait_mime = excluded.portrait_mime,
+               portrait_source = excluded.portrait_source,
+               updated_at = excluded.updated_at",
+            params![char_id, data, mime, source, updated_at],
+        )?;
+        Ok(Portrait {
+            mime: mime.to_string(),
+            data: data.to_vec(),
+            source: source.map(str::to_string),
+            updated_at,
+        })
+    }
+
+    /// Read an image file and store it as the character's portrait.
+    pub fn attach_portrait_file(&self, char_id: i64, path: &Path) -> Result<Portrait> {
+        let data = std::fs::read(path).map_err(|source| AmanuensisError::FileUnreadable {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.set_character_portrait(char_id, &data, Some(&path.to_string_lossy()))
+    }
+
+    /// The character's portrait, if one was attached.
+    pub fn get_character_portrait(&self, char_id: i64) -> Result<Option<Portrait>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT portrait, portrait_mime, portrait_source, updated_at
+                 FROM character_meta
+                 WHERE character_id = ?1 AND portrait IS NOT NULL",
+                params![char_id],
+                |row| {
+                    Ok(Portrait {
+                        data: row.get(0)?,
+                        mime: row.get(1)?,
+                        source: row.get(2)?,
+                        updated_at: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Remove the character's portrait. Returns whether there was one.
+    pub fn clear_character_portrait(&self, char_id: i64) -> Result<bool> {
+        let cleared = self.conn.execute(
+            "UPDATE character_meta
+             SET portrait = NULL, portrait_mime = NULL, portrait_source = NULL, updated_at = ?2
+             WHERE character_id = ?1 AND portrait IS NOT NULL",
+            params![char_id, crate::date::now()],
+        )?;
+        Ok(cleared > 0)
+    }
+
+    /// The race, weapon and items stored for the fighter calculator, if set. User data like
+    /// the portrait.
+    pub fn get_fighter_loadout(&self, char_id: i64) -> Result<Option<FighterLoadout>> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT fighter_loadout FROM character_meta WHERE character_id = ?1",
+                params![char_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        json.map(|j| {
+            serde_json::from_str(&j)
+                .map_err(|e| AmanuensisError::Data(format!("Invalid stored fighter loadout: {e}")))
+        })
+        .transpose()
+    }
+
+    /// Store (or clear, with `None`) the character's fighter loadout. Names are stored as
+    /// given; resolve them with `FighterModifiers::resolve` first to validate.
+    pub fn set_fighter_loadout(&self, char_id: i64, loadout: Option<&FighterLoadout>) -> Result<()> {
+        let json = loadout
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| AmanuensisError::Data(format!("Could not store fighter loadout: {e}")))?;
+        self.conn.execute(
+            "INSERT INTO character_meta (character_id, fighter_loadout) VALUES (?1, ?2)
+             ON CONFLICT(character_id) DO UPDATE SET fighter_loadout = excluded.fighter_loadout",
+            params![char_id, json],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fighter_loadout_stored_alongside_portrait() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        assert_eq!(db.get_fighter_loadout(c).unwrap(), None);
+        db.set_character_portrait(c, PNG, None).unwrap();
+        let loadout = FighterLoadout { race: "Dwarf".to_string(), weapon: "Axe".to_string(), items: vec![] };
+        db.set_fighter_loadout(c, Some(&loadout)).unwrap();
+        assert_eq!(db.get_fighter_loadout(c).unwrap(), Some(loadout));
+        assert!(db.get_character_portrait(c).unwrap().is_some());
+        db.reset_log_data().unwrap();
+        assert!(db.get_fighter_loadout(c).unwrap().is_some());
+        db.set_fighter_loadout(c, None).unwrap();
+        assert_eq!(db.get_fighter_loadout(c).unwrap(), None);
+    }
+
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0];
+
+    #[test]
+    fn portrait_attached_replaced_and_cleared() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        assert!(db.get_character_portrait(c).unwrap().is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gandor.png");
+        std::fs::write(&path, PNG).unwrap();
+        db.attach_portrait_file(c, &path).unwrap();
+        let p = db.get_character_portrait(c).unwrap().unwrap();
+        assert_eq!((p.mime.as_str(), p.data.as_slice()), ("image/png", PNG));
+        assert_eq!(p.source.as_deref(), Some(path.to_string_lossy().as_ref()));
+        assert!(p.data_url().starts_with("data:image/png;base64,iVBORw0KGgo"));
+
+        // Survives a log-data reset; replaced in place.
+        db.reset_log_data().unwrap();
+        db.set_character_portrait(c, &[0xFF, 0xD8, 0xFF, 0xE0], None).unwrap();
+        assert_eq!(db.get_character_portrait(c).unwrap().unwrap().mime, "image/jpeg");
+
+        assert_eq!(
+            db.set_character_portrait(c, b"not an image", None).unwrap_err().kind(),
+            "data"
+        );
+        assert!(db.attach_portrait_file(c, &dir.path().join("missing.png")).is_err());
+
+        assert!(db.clear_character_portrait(c).unwrap());
+        assert!(db.get_character_portrait(c).unwrap().is_none());
+        assert!(!db.clear_character_portrait(c).unwrap());
+    }
+}
@@ -0,0 +1,156 @@
+use rusqlite::{params, params_from_iter, OptionalExtension};
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// Kill counts (solo + assisted) of a single creature that earn a milestone.
+pub const KILL_MILESTONES: &[i64] = &[100, 500, 1000];
+
+/// Values of `milestones.kind`: the Nth kill of a creature, or the first solo kill of a boss.
+pub const MILESTONE_KINDS: &[&str] = &["kills", "first_solo_boss"];
+
+/// One achievement moment, dated by the log line that reached it.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Milestone {
+    pub creature: String,
+    /// One of [`MILESTONE_KINDS`].
+    pub kind: String,
+    /// The kill count reached (1 for `first_solo_boss`).
+    pub threshold: i64,
+    pub achieved_at: Timestamp,
+}
+
+impl Milestone {
+    /// "100th kill", "First solo boss kill".
+    pub fn description(&self) -> String {
+        match self.kind.as_str() {
+            "first_solo_boss" => "First solo boss kill".to_string(),
+            _ => format!("{} kill", ordinal(self.threshold)),
+        }
+    }
+}
+
+fn ordinal(n: i64) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+impl Database {
+    /// Record the milestones a just-recorded kill of `creature` reached and return the new
+    /// ones. Call after `upsert_kill`. Counts are per character row, so a merged alt's kills
+    /// count toward the alt's milestones. A milestone is only recorded once; rescans keep the
+    /// original date.
+    pub fn record_kill_milestones(
+        &self,
+        char_id: i64,
+        creature: &str,
+        solo: bool,
+        boss: bool,
+        date: &str,
+    ) -> Result<Vec<Milestone>> {
+        let (solo_total, assisted_total): (i64, i64) = self
+            .conn
+            .query_row(
+                "SELECT killed_count + slaughtered_count + vanquished_count + dispatched_count,
+                        assisted_kill_count + assisted_slaughter_count + assisted_vanquish_count
+                            + assisted_dispatch_count
+                 FROM kills WHERE character_id = ?1 AND creature_name = ?2",
+                params![char_id, creature],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .unwrap_or((0, 0));
+
+        let mut reached = Vec::new();
+        let total = solo_total + assisted_total;
+        if KILL_MILESTONES.contains(&total) {
+            reached.push(("kills", total));
+        }
+        if boss && solo && solo_total == 1 {
+            reached.push(("first_solo_boss", 1));
+        }
+
+        let mut recorded = Vec::new();
+        for (kind, threshold) in reached {
+            let inserted = self.conn.execute(
+                "INSERT INTO milestones (character_id, creature_name, kind, threshold, achieved_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(character_id, creature_name, kind, threshold) DO NOTHING",
+                params![char_id, creature, kind, threshold, date],
+            )?;
+            if inserted > 0 {
+                recorded.push(Milestone {
+                    creature: creature.to_string(),
+                    kind: kind.to_string(),
+                    threshold,
+                    achieved_at: date.parse()?,
+                });
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Milestones of a (possibly merged) character, oldest first.
+    pub fn get_milestones_merged(&self, char_id: i64) -> Result<Vec<Milestone>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT creature_name, kind, threshold, achieved_at
+             FROM milestones
+             WHERE character_id IN ({placeholders})
+             ORDER BY achieved_at, id",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(Milestone {
+                creature: row.get(0)?,
+                kind: row.get(1)?,
+                threshold: row.get(2)?,
+                achieved_at: row.get(3)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn milestones_recorded_once_at_thresholds() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        let mut new = Vec::new();
+        for i in 0..100 {
+            let date = format!("2024-01-01 10:{:02}:{:02}", i / 60, i % 60);
+            let field = if i % 2 == 0 { "slaughtered_count" } else { "assisted_kill_count" };
+            db.upsert_kill(c, "Rat", field, 2, &date).unwrap();
+            new.extend(db.record_kill_milestones(c, "Rat", i % 2 == 0, false, &date).unwrap());
+        }
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].description(), "100th kill");
+        assert_eq!(new[0].achieved_at, "2024-01-01 10:01:39");
+
+        // An assisted boss kill doesn't count; the first solo one does, once.
+        db.upsert_kill(c, "Kolar Bear", "assisted_kill_count", 500, "2024-01-02 10:00:00").unwrap();
+        assert!(db.record_kill_milestones(c, "Kolar Bear", false, true, "2024-01-02 10:00:00").unwrap().is_empty());
+        db.upsert_kill(c, "Kolar Bear", "killed_count", 500, "2024-01-03 10:00:00").unwrap();
+        assert_eq!(db.record_kill_milestones(c, "Kolar Bear", true, true, "2024-01-03 10:00:00").unwrap().len(), 1);
+        db.upsert_kill(c, "Kolar Bear", "killed_count", 500, "2024-01-04 10:00:00").unwrap();
+        assert!(db.record_kill_milestones(c, "Kolar Bear", true, true, "2024-01-04 10:00:00").unwrap().is_empty());
+
+        let all = db.get_milestones_merged(c).unwrap();
+        let labels: Vec<String> = all.iter().map(|m| format!("{} {}", m.creature, m.description())).collect();
+        assert_eq!(labels, vec!["Rat 100th kill", "Kolar Bear First solo boss kill"]);
+        assert_eq!((ordinal(1), ordinal(12), ordinal(22), ordinal(1000)), ("1st".into(), "12th".into(), "22nd".into(), "1000th".into()));
+    }
+}
@@ -1,22 +1,88 @@
-use rusqlite::{Connection, Row};
+use std::cell::RefCell;
+
+use rusqlite::{Connection, OpenFlags, Row};
 use serde::Serialize;
 
 use crate::error::Result;
 use crate::models::*;
 
+mod accuracy;
+mod arena;
+mod assist;
+mod casino;
 mod character;
+mod chat;
 mod checkpoint;
+mod circle;
+mod clan;
+mod companion;
+mod damage;
+mod death;
+mod delete;
+mod economy;
 mod frequency;
+mod heal;
+mod history;
 mod kill;
 pub mod trainer;
 mod lasty;
 mod pet;
 mod log_file;
 mod merge;
+mod meta;
+mod milestone;
 mod process_log;
-
+mod provenance;
+mod rank_history;
+mod rescue;
+mod scan_error;
+mod session;
+mod settings;
+mod snapshot;
+mod stone;
+mod thought;
+mod unknown_line;
+mod witnessed_fall;
+mod zone;
+
+pub use accuracy::CreatureAccuracy;
+pub use arena::{ArenaEvent, ArenaOpponent, ArenaSummary, TOURNAMENT_EVENT_KINDS};
+pub use assist::AssistCredit;
+pub use casino::{CasinoEvent, CasinoGame, CasinoSummary, CASINO_EVENT_KINDS};
+pub use character::{CharacterCache, CharacterListEntry};
+pub use chat::{ChatFilter, ChatLine};
+pub use circle::CircleTest;
+pub use clan::{ClanSummary, CLAN_EVENT_TYPES};
+pub use companion::Companion;
+pub use damage::DamageTaken;
+pub use death::{Death, DeathGroup, DeathReport, FallOutcomes, DEATH_OUTCOMES};
+pub use delete::CharacterDeletion;
+pub use economy::{BalancePoint, CoinBucket, CoinPeriod, CoinTotals, Economy, COIN_SOURCES, COIN_TIMELINE_PERIODS};
 pub use frequency::CreatureFrequency;
+pub use heal::{HealSummary, HEAL_DIRECTIONS};
+pub use history::{BoostWindow, DailyCount, HistoryEntry, HistoryEvent, StatBoostSummary, HISTORY_EVENT_TYPES};
 pub use kill::{KillsFilter, filter_kills};
+pub use log_file::IndexStatus;
+pub use merge::MergePreview;
+pub use meta::{Portrait, MAX_PORTRAIT_BYTES};
+pub use milestone::{Milestone, KILL_MILESTONES, MILESTONE_KINDS};
+pub use provenance::{FileContribution, UnscanSummary, CONTRIBUTION_TARGETS};
+pub use rank_history::{
+    RankMonth, RankSourceBreakdown, RankSourceEvent, RankSourceTotal, RANK_SOURCE_APPLY_LEARNING, RANK_SOURCE_APPLY_LEARNING_PARTIAL,
+    RANK_SOURCE_TRAINER,
+};
+pub use rescue::{RescueSummary, RESCUE_DIRECTIONS, RESCUE_KINDS};
+pub use settings::{
+    SETTING_CREATURE_NAME_RULES, SETTING_CREATURE_NAME_RULES_ENABLED, SETTING_DIGEST_WEBHOOK,
+    SETTING_INDEX_EVENTS_ONLY, SETTING_INDEX_LOG_LINES, SETTING_INDEX_RETENTION_YEARS, SETTING_LAST_DIGEST,
+    SETTING_LOG_PATH_ALIASES,
+};
+pub use snapshot::{StatsSnapshot, SNAPSHOT_DAILY_DAYS};
+pub use stone::{StoneEvent, StoneUsage, STONE_ACTIONS, STONE_KINDS};
+pub use thought::{Correspondent, THOUGHT_DIRECTIONS};
+pub use unknown_line::UnknownLineCount;
+pub use witnessed_fall::WitnessedFallTally;
+pub use zone::{KillLocation, ZoneTime, ZoneVisit};
 
 // ---------------------------------------------------------------------------
 // Shared character projection
@@ -97,6 +163,8 @@ fn map_character_row(row: &Row<'_>) -> rusqlite::Result<Character> {
 #[derive(Debug, Serialize)]
 pub struct LogSearchResult {
     pub content: String,
+    /// `content` split into runs, with the ones matching the query marked for highlighting.
+    pub segments: Vec<SearchSegment>,
     pub character_id: i64,
     pub timestamp: String,
     pub file_path: String,
@@ -106,18 +174,88 @@ pub struct LogSearchResult {
     pub context_after: Vec<String>,
 }
 
+/// A run of a search result's text.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SearchSegment {
+    pub text: String,
+    pub highlight: bool,
+}
+
+/// Filters and paging for `Database::search_log_lines_page`.
+#[derive(Debug, Clone)]
+pub struct LogSearchOptions {
+    pub char_id: Option<i64>,
+    /// Also match speech and actions.
+    pub include_speech: bool,
+    pub lines_before: i64,
+    pub lines_after: i64,
+    /// Only lines dated from / up to these (storage form, both inclusive); undated lines are
+    /// left out when either is set.
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Treat the query as a case-insensitive regular expression and scan the indexed text
+    /// with it instead of running an FTS5 match.
+    pub regex: bool,
+    /// Results to skip (newest files first).
+    pub offset: i64,
+    pub limit: i64,
+}
+
+impl Default for LogSearchOptions {
+    fn default() -> Self {
+        Self {
+            char_id: None,
+            include_speech: false,
+            lines_before: 0,
+            lines_after: 0,
+            from: None,
+            to: None,
+            regex: false,
+            offset: 0,
+            limit: 200,
+        }
+    }
+}
+
+/// One page of search results.
+#[derive(Debug, Serialize)]
+pub struct LogSearchPage {
+    pub results: Vec<LogSearchResult>,
+    /// Matches across all pages.
+    pub total: i64,
+    pub offset: i64,
+    pub has_more: bool,
+}
+
+/// How long a write waits for another connection's write lock before failing.
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Database wrapper with CRUD operations.
 pub struct Database {
     conn: Connection,
+    /// Log file whose scan is writing counters, for `scan_contributions`.
+    provenance: RefCell<Option<String>>,
 }
 
 impl Database {
     /// Open (or create) a SQLite database at the given path.
     pub fn open(path: &str) -> Result<Self> {
         let conn = Connection::open(path)?;
+        // Other connections (scan workers, the GUI, a CLI watch) may hold the write lock
+        // briefly; wait for it rather than failing with SQLITE_BUSY.
+        conn.busy_timeout(BUSY_TIMEOUT)?;
         crate::db::schema::create_tables(&conn)?;
         crate::db::schema::migrate_tables(&conn)?;
-        Ok(Self { conn })
+        Ok(Self { conn, provenance: RefCell::new(None) })
+    }
+
+    /// Open an existing database read-only, without creating or migrating tables, for
+    /// long-lived readers such as `amanuensis serve`. Open it once with `open` first so the
+    /// schema is current; any write through this connection fails.
+    pub fn open_read_only(path: &str) -> Result<Self> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(Self { conn, provenance: RefCell::new(None) })
     }
 
     /// Open an in-memory database (for testing).
@@ -125,7 +263,7 @@ impl Database {
         let conn = Connection::open_in_memory()?;
         crate::db::schema::create_tables(&conn)?;
         crate::db::schema::migrate_tables(&conn)?;
-        Ok(Self { conn })
+        Ok(Self { conn, provenance: RefCell::new(None) })
     }
 
     pub fn conn(&self) -> &Connection {
@@ -162,6 +300,19 @@ impl Database {
         Ok(())
     }
 
+    /// PRAGMAs for a low-priority (nice) scan: WAL like a bulk scan, but with a small page
+    /// cache and no memory map, so the scan doesn't compete for memory.
+    pub fn set_nice_scan_pragmas(&self) -> Result<()> {
+        self.conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             PRAGMA cache_size = -4000;
+             PRAGMA temp_store = DEFAULT;
+             PRAGMA mmap_size = 0;",
+        )?;
+        Ok(())
+    }
+
     /// Reset PRAGMAs to safe defaults after scanning.
     pub fn reset_pragmas(&self) -> Result<()> {
         self.conn.execute_batch(
@@ -187,6 +338,56 @@ mod tests {
         assert_ne!(id1, id3, "Different names should return different IDs");
     }
 
+    #[test]
+    fn concurrent_character_creation_yields_one_row_per_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("race.db").to_string_lossy().to_string();
+        Database::open(&path).unwrap();
+        let cache = CharacterCache::new();
+        let names = ["Fen", "Pip", "Gandor", "Mary Ann"];
+
+        let ids: Vec<Vec<i64>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..8)
+                .map(|i| {
+                    let (path, cache) = (&path, cache.clone());
+                    scope.spawn(move || {
+                        let db = Database::open(path).unwrap();
+                        names
+                            .iter()
+                            .map(|n| {
+                                // Half the workers bypass the cache to race on the table itself.
+                                if i % 2 == 0 {
+                                    cache.get_or_create(&db, n).unwrap()
+                                } else {
+                                    db.get_or_create_character(n).unwrap()
+                                }
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+
+        assert!(ids.iter().all(|worker| worker == &ids[0]));
+        let db = Database::open(&path).unwrap();
+        let count: i64 = db.conn().query_row("SELECT COUNT(*) FROM characters", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, names.len() as i64);
+        assert_eq!(cache.get_or_create(&db, "Fen").unwrap(), ids[0][0]);
+    }
+
+    #[test]
+    fn read_only_open_reads_but_never_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ro.db").to_string_lossy().to_string();
+        Database::open(&path).unwrap().get_or_create_character("Fen").unwrap();
+
+        let db = Database::open_read_only(&path).unwrap();
+        assert_eq!(db.get_character("Fen").unwrap().unwrap().name, "Fen");
+        assert!(db.get_or_create_character("Pip").is_err());
+        assert!(Database::open_read_only(&dir.path().join("missing.db").to_string_lossy()).is_err());
+    }
+
     #[test]
     fn test_get_character() {
         let db = Database::open_in_memory().unwrap();
@@ -252,8 +453,8 @@ mod tests {
         assert_eq!(kills[0].creature_name, "Rat");
         assert_eq!(kills[0].slaughtered_count, 2);
         assert_eq!(kills[0].killed_count, 1);
-        assert_eq!(kills[0].date_first, Some("2024-01-01".to_string()));
-        assert_eq!(kills[0].date_last, Some("2024-01-03".to_string()));
+        assert_eq!(kills[0].date_first, Some("2024-01-01".parse().unwrap()));
+        assert_eq!(kills[0].date_last, Some("2024-01-03".parse().unwrap()));
     }
 
     #[test]
@@ -278,8 +479,8 @@ mod tests {
         let orga = kills.iter().find(|k| k.creature_name == "Orga Fury").unwrap();
         assert_eq!(orga.killed_count, 1);
         assert_eq!(orga.killed_by_count, 1);
-        assert_eq!(orga.date_first, Some("2024-02-15".to_string()), "First kill should backfill date_first");
-        assert_eq!(orga.date_last, Some("2024-02-15".to_string()));
+        assert_eq!(orga.date_first, Some("2024-02-15".parse().unwrap()), "First kill should backfill date_first");
+        assert_eq!(orga.date_last, Some("2024-02-15".parse().unwrap()));
 
         // Another death — dates should NOT change
         db.upsert_kill(id, "Orga Fury", "killed_by_count", 10, "2024-03-01")
@@ -287,16 +488,16 @@ mod tests {
         let kills = db.get_kills(id).unwrap();
         let orga = kills.iter().find(|k| k.creature_name == "Orga Fury").unwrap();
         assert_eq!(orga.killed_by_count, 2);
-        assert_eq!(orga.date_first, Some("2024-02-15".to_string()), "Death should not change date_first");
-        assert_eq!(orga.date_last, Some("2024-02-15".to_string()), "Death should not change date_last");
+        assert_eq!(orga.date_first, Some("2024-02-15".parse().unwrap()), "Death should not change date_first");
+        assert_eq!(orga.date_last, Some("2024-02-15".parse().unwrap()), "Death should not change date_last");
 
         // Another kill — date_last should update but date_first stays
         db.upsert_kill(id, "Orga Fury", "slaughtered_count", 10, "2024-04-01")
             .unwrap();
         let kills = db.get_kills(id).unwrap();
         let orga = kills.iter().find(|k| k.creature_name == "Orga Fury").unwrap();
-        assert_eq!(orga.date_first, Some("2024-02-15".to_string()), "date_first should stay at first kill");
-        assert_eq!(orga.date_last, Some("2024-04-01".to_string()), "date_last should update to latest kill");
+        assert_eq!(orga.date_first, Some("2024-02-15".parse().unwrap()), "date_first should stay at first kill");
+        assert_eq!(orga.date_last, Some("2024-04-01".parse().unwrap()), "date_last should update to latest kill");
     }
 
     #[test]
@@ -374,6 +575,35 @@ mod tests {
         assert_eq!(pip.total_ranks, 0);
     }
 
+    #[test]
+    fn test_list_characters_with_stats_includes_merged_sources() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Main").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        for id in [main, alt] {
+            db.increment_character_field(id, "logins", 1).unwrap();
+        }
+        db.upsert_trainer_rank(main, "Histia", "2024-01-01", 1.0).unwrap();
+        db.upsert_trainer_rank(alt, "Regia", "2024-01-02", 1.0).unwrap();
+        db.upsert_kill(main, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(alt, "Rat", "assisted_kill_count", 2, "2024-02-01 10:00:00").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        // Pending files count for the character they belong to, merged sources included.
+        let pending = std::collections::HashMap::from([(alt, 2), (main, 1)]);
+        let entries = db.list_characters_with_stats(&pending).unwrap();
+        assert_eq!(entries.len(), 1);
+        let e = &entries[0];
+        assert_eq!(e.character.name, "Main");
+        assert_eq!((e.character.total_ranks, e.total_kills, e.unscanned_files), (2, 2, 3));
+        assert_eq!(e.last_played.as_deref(), Some("2024-02-01 10:00:00"));
+
+        // Badges serialize flat next to the character fields.
+        let json = serde_json::to_value(e).unwrap();
+        assert_eq!(json["name"], "Main");
+        assert_eq!(json["total_kills"], 2);
+    }
+
     #[test]
     fn test_coin_tracking() {
         let db = Database::open_in_memory().unwrap();
@@ -414,8 +644,8 @@ mod tests {
         assert_eq!(maha.lasty_type, "Befriend");
         assert_eq!(maha.message_count, 2);
         assert!(!maha.finished);
-        assert_eq!(maha.first_seen_date, Some("2024-01-01".to_string()));
-        assert_eq!(maha.last_seen_date, Some("2024-01-02".to_string()));
+        assert_eq!(maha.first_seen_date, Some("2024-01-01".parse().unwrap()));
+        assert_eq!(maha.last_seen_date, Some("2024-01-02".parse().unwrap()));
 
         let orga = lastys.iter().find(|l| l.creature_name == "Orga Anger").unwrap();
         assert_eq!(orga.lasty_type, "Morph");
@@ -445,8 +675,8 @@ mod tests {
         assert_eq!(lastys.len(), 1);
         assert!(lastys[0].finished);
         assert_eq!(lastys[0].message_count, 2);
-        assert_eq!(lastys[0].completed_date, Some("2024-01-05".to_string()));
-        assert_eq!(lastys[0].first_seen_date, Some("2024-01-01".to_string()));
+        assert_eq!(lastys[0].completed_date, Some("2024-01-05".parse().unwrap()));
+        assert_eq!(lastys[0].first_seen_date, Some("2024-01-01".parse().unwrap()));
     }
 
     #[test]
@@ -460,7 +690,7 @@ mod tests {
         assert_eq!(lastys.len(), 1);
         assert!(lastys[0].finished);
         assert_eq!(lastys[0].message_count, 1);
-        assert_eq!(lastys[0].completed_date, Some("2024-01-01".to_string()));
+        assert_eq!(lastys[0].completed_date, Some("2024-01-01".parse().unwrap()));
     }
 
     #[test]
@@ -471,7 +701,7 @@ mod tests {
         db.abandon_lasty(id, "Maha Ruknee", "2024-01-02").unwrap();
 
         let lastys = db.get_lastys(id).unwrap();
-        assert_eq!(lastys[0].abandoned_date, Some("2024-01-02".to_string()));
+        assert_eq!(lastys[0].abandoned_date, Some("2024-01-02".parse().unwrap()));
 
         // Clear abandon
         db.clear_lasty_abandon(id, "Maha Ruknee").unwrap();
@@ -547,13 +777,13 @@ mod tests {
         assert_eq!(rat.killed_count, 2); // 1 + 1
         // Per-verb first/last dates must aggregate across merge sources: MIN of first, MAX of last.
         assert_eq!(
-            rat.date_first_killed.as_deref(),
-            Some("2024-01-01"),
+            rat.date_first_killed.unwrap(),
+            "2024-01-01",
             "merged first-killed date must be the MIN across sources (A=01-01, B=01-05)"
         );
         assert_eq!(
-            rat.date_last_killed.as_deref(),
-            Some("2024-01-05"),
+            rat.date_last_killed.unwrap(),
+            "2024-01-05",
             "merged last-killed date must be the MAX across sources"
         );
 
@@ -610,6 +840,31 @@ mod tests {
         assert!(db.merge_characters(&[id_a], 9999).is_err());
     }
 
+    #[test]
+    fn test_preview_merge_leaves_db_unchanged() {
+        let db = Database::open_in_memory().unwrap();
+        let id_a = db.get_or_create_character("CharA").unwrap();
+        let id_b = db.get_or_create_character("CharB").unwrap();
+        db.increment_character_field(id_a, "logins", 2).unwrap();
+        db.increment_character_field(id_b, "logins", 3).unwrap();
+        db.upsert_kill(id_a, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(id_b, "Rat", "killed_count", 2, "2024-01-02 10:00:00").unwrap();
+        db.upsert_trainer_rank(id_b, "Detha", "2024-01-02 10:00:00", 1.0).unwrap();
+
+        let preview = db.preview_merge(&[id_b], id_a).unwrap();
+        assert_eq!((preview.before.logins, preview.after.logins), (2, 5));
+        assert_eq!(preview.kills[0].killed_count, 2);
+        assert_eq!((preview.total_ranks_before, preview.total_ranks_after), (0, 1));
+
+        // Nothing was merged.
+        assert!(db.get_merge_sources(id_a).unwrap().is_empty());
+        assert_eq!(db.get_character_merged(id_a).unwrap().unwrap().logins, 2);
+
+        // Invalid merges report the same error as merge_characters.
+        assert!(db.preview_merge(&[id_a], id_a).is_err());
+        assert!(db.get_merge_sources(id_a).unwrap().is_empty());
+    }
+
     #[test]
     fn test_get_character_by_id() {
         let db = Database::open_in_memory().unwrap();
@@ -720,4 +975,96 @@ mod tests {
         let results = db.search_log_lines("Dragon", None, 10, true, 0, 0).unwrap();
         assert_eq!(results.len(), 0);
     }
+
+    #[test]
+    fn test_search_pages_and_segments() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db.get_or_create_character("Fen").unwrap();
+        let lines: Vec<(i64, String, &str, &str)> = (0..5)
+            .map(|i| (id, format!("You slaughtered a large rat #{i}."), "2024-01-01 13:00:00", "/logs/test.txt"))
+            .chain([(id, "Fen says, \"large rat here\"".to_string(), "2024-01-01 13:05:00", "/logs/test.txt")])
+            .collect();
+        let refs: Vec<(i64, &str, &str, &str)> = lines.iter().map(|(c, l, t, f)| (*c, l.as_str(), *t, *f)).collect();
+        db.insert_log_lines(&refs).unwrap();
+
+        let options = LogSearchOptions { char_id: Some(id), limit: 2, ..Default::default() };
+        let first = db.search_log_lines_page("Large Rat", &options).unwrap();
+        assert_eq!((first.total, first.results.len(), first.has_more), (5, 2, true), "speech excluded");
+        let last = db.search_log_lines_page("Large Rat", &LogSearchOptions { offset: 4, ..options.clone() }).unwrap();
+        assert_eq!((last.results.len(), last.has_more), (1, false));
+        assert_ne!(first.results[0].content, last.results[0].content);
+
+        let segments = &first.results[0].segments;
+        assert_eq!(segments.iter().filter(|s| s.highlight).map(|s| s.text.as_str()).collect::<Vec<_>>(), ["large rat"]);
+        assert_eq!(segments.iter().map(|s| s.text.as_str()).collect::<String>(), first.results[0].content);
+        // Words are highlighted separately when the phrase isn't found verbatim
+        let split = log_file::highlight_segments("Rat, large and angry", "large rat");
+        assert_eq!(split.iter().filter(|s| s.highlight).count(), 2);
+    }
+
+    #[test]
+    fn test_search_leaves_out_every_kind_of_speech() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db.get_or_create_character("Fen").unwrap();
+        let speech = [
+            "1/1/24 1:00:00p Pip says, \"a rat by the docks\"",
+            "1/1/24 1:00:01p Pip yells, \"rat!\"",
+            "1/1/24 1:00:02p Pip yells in Thoom, \"rat\"",
+            "1/1/24 1:00:03p Pip whispers to you, \"the rat\"",
+            "1/1/24 1:00:04p Pip thinks, \"rat again\"",
+            "1/1/24 1:00:05p Pip thinks to you, \"rat\"",
+            "1/1/24 1:00:06p You think to Pip, \"rat\"",
+            "1/1/24 1:00:07p Pip asks, \"rat?\"",
+            "1/1/24 1:00:08p Pip exclaims, \u{201C}rat!\u{201D}",
+            "1/1/24 1:00:09p Pip ponders, \"rat\"",
+        ];
+        let events = ["1/1/24 1:01:00p You slaughtered a Rat.", "1/1/24 1:02:00p Pip has fallen to a Rat."];
+        let lines: Vec<(i64, &str, &str, &str)> =
+            speech.iter().chain(&events).map(|l| (id, *l, "2024-01-01 13:00:00", "/logs/test.txt")).collect();
+        db.insert_log_lines(&lines).unwrap();
+
+        let found: Vec<String> = db
+            .search_log_lines_page("rat", &LogSearchOptions::default())
+            .unwrap()
+            .results
+            .into_iter()
+            .map(|r| r.content)
+            .collect();
+        assert_eq!(found.len(), 2, "{found:?}");
+        assert!(found.iter().all(|c| events.contains(&c.as_str())));
+        let everything = LogSearchOptions { include_speech: true, ..Default::default() };
+        assert_eq!(db.search_log_lines_page("rat", &everything).unwrap().total, 12);
+    }
+
+    #[test]
+    fn test_search_date_range_regex_and_context() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db.get_or_create_character("Fen").unwrap();
+        db.insert_log_lines(&[
+            (id, "You slaughtered a Rat.", "2024-01-01 13:00:00", "/logs/a.txt"),
+            (id, "You picked up 12 coins.", "2024-01-01 13:01:00", "/logs/a.txt"),
+            (id, "You slaughtered a Vermine.", "2024-01-02 09:00:00", "/logs/b.txt"),
+            (id, "You picked up 7 coins.", "2024-01-02 09:01:00", "/logs/b.txt"),
+            (id, "undated slaughtered line", "", "/logs/b.txt"),
+        ])
+        .unwrap();
+
+        let dated = LogSearchOptions { from: Some("2024-01-02 00:00:00".parse().unwrap()), ..Default::default() };
+        let page = db.search_log_lines_page("slaughtered", &dated).unwrap();
+        assert_eq!(page.total, 1);
+        assert!(page.results[0].content.contains("Vermine"));
+        let until = LogSearchOptions { to: Some("2024-01-01 23:59:59".parse().unwrap()), ..Default::default() };
+        assert_eq!(db.search_log_lines_page("slaughtered", &until).unwrap().total, 1);
+
+        let regex = LogSearchOptions { regex: true, lines_before: 1, lines_after: 1, ..Default::default() };
+        let page = db.search_log_lines_page(r"picked up \d{2} COINS", &regex).unwrap();
+        assert_eq!(page.total, 1);
+        let hit = &page.results[0];
+        assert_eq!(hit.snippet, "You <mark>picked up 12 coins</mark>.");
+        assert_eq!(hit.context_before, ["You slaughtered a Rat."]);
+        assert!(hit.context_after.is_empty(), "context stays within the file");
+        let page = db.search_log_lines_page(r"slaughtered a \w+\.", &LogSearchOptions { limit: 1, regex: true, ..Default::default() }).unwrap();
+        assert_eq!((page.total, page.results.len(), page.has_more), (2, 1, true));
+        assert!(db.search_log_lines_page("(", &regex).is_err());
+    }
 }
@@ -1,25 +1,43 @@
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension, Row};
 
 use crate::error::Result;
 use crate::models::Pet;
 use super::Database;
 
+/// Pet columns with their `pet_ranks` totals; select `FROM pets p LEFT JOIN pet_ranks r`.
+pub(super) const PET_COLUMNS: &str =
+    "p.id, p.character_id, p.pet_name, p.creature_name, p.status, p.adopted_date, p.death_count,
+     p.feed_count, p.last_fed_date, p.released_date, COALESCE(r.ranks, 0), COALESCE(r.trainings, 0),
+     MAX(COALESCE(r.last_rank_date, ''), COALESCE(r.last_training_date, ''))";
+
+pub(super) fn pet_from_row(row: &Row) -> rusqlite::Result<Pet> {
+    Ok(Pet {
+        id: Some(row.get(0)?),
+        character_id: row.get(1)?,
+        pet_name: row.get(2)?,
+        creature_name: row.get(3)?,
+        status: row.get(4)?,
+        adopted_date: row.get(5)?,
+        death_count: row.get(6)?,
+        feed_count: row.get(7)?,
+        last_fed_date: row.get(8)?,
+        released_date: row.get(9)?,
+        ranks: row.get(10)?,
+        trainings: row.get(11)?,
+        last_trained_date: row.get::<_, String>(12).map(|d| crate::date::Timestamp::parse(&d))?,
+    })
+}
+
 impl Database {
-    /// Get pets for a character.
+    /// Get pets for a character, in adoption order (pets with no known adoption first).
     pub fn get_pets(&self, char_id: i64) -> Result<Vec<Pet>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, character_id, pet_name, creature_name
-             FROM pets WHERE character_id = ?1 ORDER BY pet_name",
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {PET_COLUMNS}
+             FROM pets p LEFT JOIN pet_ranks r ON r.pet_id = p.id
+             WHERE p.character_id = ?1 ORDER BY COALESCE(p.adopted_date, ''), p.pet_name"
+        ))?;
 
-        let pets = stmt.query_map(params![char_id], |row| {
-            Ok(Pet {
-                id: Some(row.get(0)?),
-                character_id: row.get(1)?,
-                pet_name: row.get(2)?,
-                creature_name: row.get(3)?,
-            })
-        })?;
+        let pets = stmt.query_map(params![char_id], pet_from_row)?;
 
         Ok(pets.filter_map(|r| r.ok()).collect())
     }
@@ -33,4 +51,240 @@ impl Database {
         )?;
         Ok(())
     }
+
+    /// Record an adoption: the pet starts out named after its creature. Re-adopting a
+    /// released or dead pet of that name makes it active again; re-scanning the same
+    /// adoption is a no-op even after the pet was renamed.
+    pub fn adopt_pet(&self, char_id: i64, creature_name: &str, date: &str) -> Result<()> {
+        let seen: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM pets
+             WHERE character_id = ?1 AND creature_name = ?2 COLLATE NOCASE AND adopted_date = ?3)",
+            params![char_id, creature_name, date],
+            |row| row.get(0),
+        )?;
+        if seen {
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT INTO pets (character_id, pet_name, creature_name, status, adopted_date)
+             VALUES (?1, ?2, ?2, 'active', ?3)
+             ON CONFLICT(character_id, pet_name) DO UPDATE SET
+                 status = 'active', adopted_date = excluded.adopted_date, released_date = NULL",
+            params![char_id, creature_name, date],
+        )?;
+        Ok(())
+    }
+
+    /// Rename a pet. Ignored if the character already has a pet called `new_name`.
+    pub fn name_pet(&self, char_id: i64, pet: &str, new_name: &str) -> Result<()> {
+        let id = self.find_or_create_pet(char_id, pet)?;
+        self.conn.execute(
+            "UPDATE OR IGNORE pets SET pet_name = ?2 WHERE id = ?1",
+            params![id, new_name],
+        )?;
+        Ok(())
+    }
+
+    /// Count a feeding logged at `line` of the file being scanned, once however often the
+    /// file is re-scanned. Feeding a dead pet revives it unless the death was logged later.
+    pub fn feed_pet(&self, char_id: i64, pet: &str, date: &str, line: usize) -> Result<()> {
+        let id = self.find_or_create_pet(char_id, pet)?;
+        if self.record_pet_event(char_id, id, "fed", date, line)? {
+            self.conn.execute(
+                "UPDATE pets SET feed_count = feed_count + 1,
+                     last_fed_date = MAX(COALESCE(last_fed_date, ''), ?2),
+                     status = CASE WHEN status = 'dead' AND ?2 >= COALESCE(last_death_date, '')
+                                   THEN 'active' ELSE status END
+                 WHERE id = ?1",
+                params![id, date],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Count a pet death, deduplicated like [`Database::feed_pet`]. The pet stays alive when
+    /// a later feeding is already recorded.
+    pub fn record_pet_death(&self, char_id: i64, pet: &str, date: &str, line: usize) -> Result<()> {
+        let id = self.find_or_create_pet(char_id, pet)?;
+        if self.record_pet_event(char_id, id, "died", date, line)? {
+            self.conn.execute(
+                "UPDATE pets SET death_count = death_count + 1,
+                     last_death_date = MAX(COALESCE(last_death_date, ''), ?2),
+                     status = CASE WHEN status = 'released' OR ?2 < COALESCE(last_fed_date, '')
+                                   THEN status ELSE 'dead' END
+                 WHERE id = ?1",
+                params![id, date],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Mark a pet released.
+    pub fn release_pet(&self, char_id: i64, pet: &str, date: &str) -> Result<()> {
+        let id = self.find_or_create_pet(char_id, pet)?;
+        self.conn.execute(
+            "UPDATE pets SET status = 'released', released_date = ?2 WHERE id = ?1",
+            params![id, date],
+        )?;
+        Ok(())
+    }
+
+    /// Count a pet training session logged at `line` of the file being scanned. Each line
+    /// counts once however often the file is re-scanned; sessions on other lines count even
+    /// when they share a timestamp or arrive out of order.
+    pub fn record_pet_training(&self, char_id: i64, pet: &str, date: &str, line: usize) -> Result<()> {
+        let id = self.ensure_pet_ranks(char_id, pet)?;
+        if self.record_pet_event(char_id, id, "training", date, line)? {
+            self.conn.execute(
+                "UPDATE pet_ranks SET trainings = trainings + 1,
+                     last_training_date = MAX(COALESCE(last_training_date, ''), ?2)
+                 WHERE pet_id = ?1",
+                params![id, date],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Count a rank gained by a pet, deduplicated like [`Database::record_pet_training`].
+    pub fn record_pet_rank(&self, char_id: i64, pet: &str, date: &str, line: usize) -> Result<()> {
+        let id = self.ensure_pet_ranks(char_id, pet)?;
+        if self.record_pet_event(char_id, id, "rank", date, line)? {
+            self.conn.execute(
+                "UPDATE pet_ranks SET ranks = ranks + 1, last_rank_date = MAX(COALESCE(last_rank_date, ''), ?2)
+                 WHERE pet_id = ?1",
+                params![id, date],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Log a pet event with the line it came from. False when that line of that file was
+    /// already recorded, i.e. the file is being scanned again.
+    fn record_pet_event(&self, char_id: i64, pet_id: i64, event: &str, date: &str, line: usize) -> Result<bool> {
+        let log_file = self.provenance.borrow().clone().unwrap_or_default();
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO pet_events (character_id, pet_id, event, date, log_file, line)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![char_id, pet_id, event, date, log_file, line as i64],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// The pet's id, with its `pet_ranks` row created if missing.
+    fn ensure_pet_ranks(&self, char_id: i64, pet: &str) -> Result<i64> {
+        let id = self.find_or_create_pet(char_id, pet)?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO pet_ranks (character_id, pet_id) VALUES (?1, ?2)",
+            params![char_id, id],
+        )?;
+        Ok(id)
+    }
+
+    /// Resolve a pet mentioned in a log line, by name first and then by creature (most
+    /// recently adopted, unreleased pets preferred). A pet never seen being adopted (the
+    /// adoption predates the logs) is created with an unknown creature.
+    fn find_or_create_pet(&self, char_id: i64, pet: &str) -> Result<i64> {
+        let found = self
+            .conn
+            .query_row(
+                "SELECT id FROM pets
+                 WHERE character_id = ?1
+                   AND (pet_name = ?2 COLLATE NOCASE OR creature_name = ?2 COLLATE NOCASE)
+                 ORDER BY pet_name = ?2 COLLATE NOCASE DESC, status = 'released',
+                          COALESCE(adopted_date, '') DESC
+                 LIMIT 1",
+                params![char_id, pet],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(id) = found {
+            return Ok(id);
+        }
+        self.conn.execute(
+            "INSERT INTO pets (character_id, pet_name, creature_name) VALUES (?1, ?2, '')",
+            params![char_id, pet],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+
+    #[test]
+    fn pet_lifecycle_survives_rescan() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Fen").unwrap();
+        for _ in 0..2 {
+            db.adopt_pet(c, "Maha Ruknee", "2024-01-01 10:00:00").unwrap();
+            db.name_pet(c, "Maha Ruknee", "Fluffy").unwrap();
+            db.feed_pet(c, "Fluffy", "2024-01-01 10:05:00", 2).unwrap();
+            db.feed_pet(c, "Maha Ruknee", "2024-01-02 10:05:00", 5).unwrap();
+            db.record_pet_death(c, "Fluffy", "2024-01-03 10:00:00", 8).unwrap();
+        }
+        let pets = db.get_pets(c).unwrap();
+        assert_eq!(pets.len(), 1);
+        let p = &pets[0];
+        assert_eq!((p.pet_name.as_str(), p.creature_name.as_str(), p.status.as_str()), ("Fluffy", "Maha Ruknee", "dead"));
+        assert_eq!((p.feed_count, p.death_count), (2, 1));
+        assert_eq!(p.adopted_date.unwrap(), "2024-01-01 10:00:00");
+
+        // Two feedings in one second both count; one from an older file scanned late counts
+        // without reviving the pet or moving the last feeding back.
+        db.feed_pet(c, "Fluffy", "2024-01-02 10:05:00", 6).unwrap();
+        db.set_scan_provenance(Some("CL Log 2023-12-31.txt"));
+        db.feed_pet(c, "Fluffy", "2023-12-31 10:00:00", 5).unwrap();
+        db.set_scan_provenance(None);
+        let p = &db.get_pets(c).unwrap()[0];
+        assert_eq!((p.feed_count, p.status.as_str()), (4, "dead"));
+        assert_eq!(p.last_fed_date.unwrap(), "2024-01-02 10:05:00");
+
+        // Fed after dying: alive again. Then released.
+        db.feed_pet(c, "Fluffy", "2024-01-04 10:00:00", 11).unwrap();
+        assert_eq!(db.get_pets(c).unwrap()[0].status, "active");
+        db.release_pet(c, "fluffy", "2024-01-05 10:00:00").unwrap();
+        let p = &db.get_pets(c).unwrap()[0];
+        assert_eq!(p.status, "released");
+        assert_eq!(p.released_date.unwrap(), "2024-01-05 10:00:00");
+
+        // A pet adopted before the logs start is created when first mentioned
+        db.feed_pet(c, "Rex", "2024-01-06 10:00:00", 14).unwrap();
+        let rex = db.get_pets(c).unwrap().into_iter().find(|p| p.pet_name == "Rex").unwrap();
+        assert_eq!((rex.creature_name.as_str(), rex.feed_count, rex.adopted_date), ("", 1, None));
+    }
+
+    #[test]
+    fn pet_ranks_follow_renames_and_survive_rescan() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Fen").unwrap();
+        db.adopt_pet(c, "Maha Ruknee", "2024-01-01 10:00:00").unwrap();
+        db.set_scan_provenance(Some("CL Log 2024-01-01.txt"));
+        for _ in 0..2 {
+            db.record_pet_training(c, "Maha Ruknee", "2024-01-01 11:00:00", 3).unwrap();
+            db.record_pet_rank(c, "Maha Ruknee", "2024-01-01 11:01:00", 4).unwrap();
+            db.record_pet_rank(c, "Maha Ruknee", "2024-01-02 11:01:00", 9).unwrap();
+        }
+        db.name_pet(c, "Maha Ruknee", "Fluffy").unwrap();
+        db.record_pet_training(c, "Fluffy", "2024-01-03 09:00:00", 12).unwrap();
+
+        let p = &db.get_pets(c).unwrap()[0];
+        assert_eq!((p.pet_name.as_str(), p.ranks, p.trainings), ("Fluffy", 2, 2));
+        assert_eq!(p.last_trained_date.unwrap(), "2024-01-03 09:00:00");
+
+        // Two ranks in the same second, and one from an older file scanned late, all count;
+        // the newest date stays the last one.
+        db.record_pet_rank(c, "Fluffy", "2024-01-03 09:00:05", 13).unwrap();
+        db.record_pet_rank(c, "Fluffy", "2024-01-03 09:00:05", 14).unwrap();
+        db.set_scan_provenance(Some("CL Log 2023-12-31.txt"));
+        db.record_pet_rank(c, "Fluffy", "2023-12-31 20:00:00", 9).unwrap();
+        db.set_scan_provenance(None);
+        let p = &db.get_pets(c).unwrap()[0];
+        assert_eq!(p.ranks, 5);
+        assert_eq!(p.last_trained_date.unwrap(), "2024-01-03 09:00:05");
+
+        db.adopt_pet(c, "Rat", "2024-01-04 10:00:00").unwrap();
+        let rat = db.get_pets(c).unwrap().into_iter().find(|p| p.pet_name == "Rat").unwrap();
+        assert_eq!((rat.ranks, rat.trainings, rat.last_trained_date), (0, 0, None));
+    }
 }
@@ -7,7 +7,7 @@ use super::Database;
 impl Database {
     /// Insert a process log entry.
     pub fn add_process_log(&self, level: &str, message: &str) -> Result<()> {
-        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let now = crate::date::now();
         self.conn.execute(
             "INSERT INTO process_logs (created_at, level, message) VALUES (?1, ?2, ?3)",
             params![now, level, message],
@@ -0,0 +1,129 @@
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+
+/// Values of `scan_contributions.target`: a `characters` counter, a `kills` count column,
+/// a `kill_hourly` count column, or a `kills_by_location` count column.
+pub const CONTRIBUTION_TARGETS: &[&str] = &["character", "kill", "kill_hourly", "kill_location"];
+
+/// What one scanned file added to one counter.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FileContribution {
+    pub character_id: i64,
+    pub target: String,
+    /// Creature name for kills, "creature\u{1f}hour" for kill_hourly, "creature\u{1f}area" for
+    /// kill_location, empty for character counters.
+    pub key: String,
+    pub field: String,
+    pub amount: i64,
+}
+
+/// What `LogParser::unscan_file` took out.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct UnscanSummary {
+    /// Other scanned files replayed to rebuild the derived data without this one.
+    pub files_replayed: usize,
+    pub sessions: i64,
+    pub log_lines: i64,
+    /// Rows removed from the opt-in `chat` table.
+    pub chat_lines: i64,
+}
+
+impl Database {
+    /// Attribute counter and kill writes to `file_path` until cleared with `None`.
+    pub(crate) fn set_scan_provenance(&self, file_path: Option<&str>) {
+        *self.provenance.borrow_mut() = file_path.map(str::to_string);
+    }
+
+    /// Record a write against the file being scanned. No-op outside a scan.
+    pub(super) fn record_contribution(
+        &self,
+        char_id: i64,
+        target: &str,
+        key: &str,
+        field: &str,
+        amount: i64,
+    ) -> Result<()> {
+        let provenance = self.provenance.borrow();
+        let Some(file_path) = provenance.as_deref() else {
+            return Ok(());
+        };
+        self.conn.execute(
+            "INSERT INTO scan_contributions (file_path, character_id, target, key, field, amount)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(file_path, character_id, target, key, field) DO UPDATE SET
+                 amount = amount + excluded.amount",
+            params![file_path, char_id, target, key, field, amount],
+        )?;
+        Ok(())
+    }
+
+    /// Everything a scanned file contributed, in recording order.
+    pub fn get_file_contributions(&self, file_path: &str) -> Result<Vec<FileContribution>> {
+        let file_path = self.log_path_key(file_path)?;
+        let mut stmt = self.conn.prepare(
+            "SELECT character_id, target, key, field, amount
+             FROM scan_contributions WHERE file_path = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![file_path], |row| {
+            Ok(FileContribution {
+                character_id: row.get(0)?,
+                target: row.get(1)?,
+                key: row.get(2)?,
+                field: row.get(3)?,
+                amount: row.get(4)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// The rows a scanned file (by key) owns, for an unscan summary.
+    pub(crate) fn file_row_counts(&self, file_path: &str) -> Result<UnscanSummary> {
+        let count = |sql: &str| -> Result<i64> { Ok(self.conn.query_row(sql, params![file_path], |row| row.get(0))?) };
+        Ok(UnscanSummary {
+            files_replayed: 0,
+            sessions: count("SELECT COUNT(*) FROM sessions WHERE log_file = ?1")?,
+            // The search index is optional (FTS5 may be unavailable).
+            log_lines: count("SELECT COUNT(*) FROM log_lines WHERE file_path = ?1").unwrap_or(0),
+            chat_lines: count("SELECT COUNT(*) FROM chat WHERE file_path = ?1")?,
+        })
+    }
+
+    /// Whether a (trusted, built-in) table holds any rows.
+    pub(crate) fn has_rows(&self, table: &str) -> Result<bool> {
+        Ok(self.conn.query_row(&format!("SELECT EXISTS(SELECT 1 FROM {table})"), [], |row| row.get(0))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contributions_are_recorded_per_file_only_during_a_scan() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+
+        db.set_scan_provenance(Some("mine.txt"));
+        db.increment_character_field(c, "deaths", 2).unwrap();
+        db.upsert_kill(c, "Rat", "slaughtered_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill_hourly(c, "Rat", "slaughtered_count", "2024-01-01 10").unwrap();
+
+        db.set_scan_provenance(Some("friend.txt"));
+        db.increment_character_field(c, "deaths", 3).unwrap();
+        db.upsert_kill(c, "Orga Anger", "killed_count", 30, "2024-01-02 10:00:00").unwrap();
+        db.upsert_kill(c, "Orga Anger", "killed_count", 30, "2024-01-02 10:05:00").unwrap();
+        db.set_scan_provenance(None);
+        // Writes outside a scan aren't attributed to any file.
+        db.increment_character_field(c, "logins", 1).unwrap();
+
+        assert_eq!(db.get_file_contributions("mine.txt").unwrap().len(), 3);
+        let orga = db.get_file_contributions("friend.txt").unwrap();
+        assert_eq!(orga.len(), 2);
+        assert_eq!((orga[0].field.as_str(), orga[0].amount), ("deaths", 3));
+        assert_eq!((orga[1].key.as_str(), orga[1].amount), ("Orga Anger", 2));
+        assert!(db.get_file_contributions("other.txt").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+use rusqlite::{params, params_from_iter};
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// `trainer_rank_history.source` of a rank message from the trainer.
+pub const RANK_SOURCE_TRAINER: &str = "trainer";
+/// `source` of a confirmed ("much more") apply-learning, worth 10 ranks.
+pub const RANK_SOURCE_APPLY_LEARNING: &str = "apply_learning";
+/// `source` of a partial apply-learning (1-9 ranks; recorded with `ranks` 0).
+pub const RANK_SOURCE_APPLY_LEARNING_PARTIAL: &str = "apply_learning_partial";
+
+/// Recorded ranks from one source with one trainer.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RankSourceTotal {
+    pub trainer_name: String,
+    pub source: String,
+    pub events: i64,
+    /// Known ranks (0 for partial apply-learning).
+    pub ranks: i64,
+    pub first: String,
+    pub last: String,
+}
+
+/// One recorded rank event and the log it came from.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RankSourceEvent {
+    pub trainer_name: String,
+    pub source: String,
+    pub ranks: i64,
+    pub ranked_at: Timestamp,
+    /// None for ranks recorded before log files were tracked, or outside a scan.
+    pub log_file: Option<String>,
+}
+
+/// One trainer's total split by where the ranks came from, for auditing against the in-game
+/// rank output.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RankSourceBreakdown {
+    pub trainer_name: String,
+    /// Ranks counted from trainer messages (`Trainer::ranks`).
+    pub counted: i64,
+    /// Of those, how many have a recorded event; the rest were scanned before events were
+    /// recorded (a Rescan Logs fills them in).
+    pub counted_recorded: i64,
+    pub apply_learning_ranks: i64,
+    pub apply_learning_events: i64,
+    pub apply_learning_partial_events: i64,
+    pub apply_learning_estimated_ranks: i64,
+    /// Manually entered ranks.
+    pub modified_ranks: i64,
+    pub rank_mode: String,
+    pub effective_ranks: i64,
+    /// Distinct log files the recorded events came from.
+    pub log_files: i64,
+}
+
+/// Ranks gained with one trainer in one calendar month.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RankMonth {
+    /// "YYYY-MM".
+    pub month: String,
+    pub ranks: i64,
+    /// Ranks recorded up to and including this month.
+    pub cumulative: i64,
+}
+
+impl Database {
+    /// Record one counted rank with its log timestamp. Called by `upsert_trainer_rank`, so the
+    /// history holds exactly the log-derived ranks (override-skipped ranks aren't recorded).
+    pub(super) fn record_rank_history(&self, char_id: i64, trainer_name: &str, date: &str) -> Result<()> {
+        self.record_rank_source(char_id, trainer_name, date, RANK_SOURCE_TRAINER, 1)
+    }
+
+    /// Record a rank event from `source` with the log file being scanned (if any). Only
+    /// `RANK_SOURCE_TRAINER` rows count toward the rank history, pace and digest queries.
+    pub(super) fn record_rank_source(
+        &self,
+        char_id: i64,
+        trainer_name: &str,
+        date: &str,
+        source: &str,
+        ranks: i64,
+    ) -> Result<()> {
+        let log_file = self.provenance.borrow().clone();
+        self.conn.execute(
+            "INSERT INTO trainer_rank_history (character_id, trainer_name, ranked_at, source, ranks, log_file)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![char_id, trainer_name, date, source, ranks, log_file],
+        )?;
+        Ok(())
+    }
+
+    /// Forget a trainer's rank history, alongside zeroing its log-derived ranks.
+    pub(super) fn clear_rank_history(&self, char_id: i64, trainer_name: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM trainer_rank_history WHERE character_id = ?1 AND trainer_name = ?2",
+            params![char_id, trainer_name],
+        )?;
+        Ok(())
+    }
+
+    /// Ranks per month with a trainer (name matched case-insensitively) for a (possibly
+    /// merged) character, oldest first. Months without ranks are omitted. Empty for ranks
+    /// scanned before the history was recorded, until a Rescan Logs.
+    pub fn get_trainer_rank_history_merged(&self, char_id: i64, trainer_name: &str) -> Result<Vec<RankMonth>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT substr(ranked_at, 1, 7) AS month, COUNT(*)
+             FROM trainer_rank_history
+             WHERE character_id IN ({placeholders}) AND trainer_name = ? COLLATE NOCASE
+               AND source = 'trainer'
+             GROUP BY month
+             ORDER BY month",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let bind = ids
+            .iter()
+            .map(|id| id as &dyn rusqlite::ToSql)
+            .chain(std::iter::once(&trainer_name as &dyn rusqlite::ToSql));
+        let rows = stmt.query_map(params_from_iter(bind), |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+
+        let mut cumulative = 0;
+        let mut months = Vec::new();
+        for (month, ranks) in rows.filter_map(|r| r.ok()) {
+            cumulative += ranks;
+            months.push(RankMonth { month, ranks, cumulative });
+        }
+        Ok(months)
+    }
+
+    /// Timestamps of every recorded rank with a trainer (case-insensitive) for a (possibly
+    /// merged) character, oldest first.
+    pub fn get_trainer_rank_times_merged(&self, char_id: i64, trainer_name: &str) -> Result<Vec<String>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT ranked_at FROM trainer_rank_history
+             WHERE character_id IN ({placeholders}) AND trainer_name = ? COLLATE NOCASE
+               AND source = 'trainer'
+             ORDER BY ranked_at",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let bind = ids
+            .iter()
+            .map(|id| id as &dyn rusqlite::ToSql)
+            .chain(std::iter::once(&trainer_name as &dyn rusqlite::ToSql));
+        let rows = stmt.query_map(params_from_iter(bind), |row| row.get(0))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Ranks recorded per trainer since `since` (inclusive; None for all time) for a
+    /// (possibly merged) character, most ranks first, then by name.
+    pub fn get_ranks_since_merged(&self, char_id: i64, since: Option<&str>) -> Result<Vec<(String, i64)>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT trainer_name, COUNT(*) AS ranks
+             FROM trainer_rank_history
+             WHERE character_id IN ({placeholders}) AND ranked_at >= ? AND source = 'trainer'
+             GROUP BY trainer_name
+             ORDER BY ranks DESC, trainer_name",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let since = since.unwrap_or("");
+        let bind = ids
+            .iter()
+            .map(|id| id as &dyn rusqlite::ToSql)
+            .chain(std::iter::once(&since as &dyn rusqlite::ToSql));
+        let rows = stmt.query_map(params_from_iter(bind), |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Recorded ranks per (trainer, source) for a (possibly merged) character, by trainer
+    /// then source.
+    pub fn get_rank_source_totals_merged(&self, char_id: i64) -> Result<Vec<RankSourceTotal>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT trainer_name, source, COUNT(*), SUM(ranks), MIN(ranked_at), MAX(ranked_at)
+             FROM trainer_rank_history
+             WHERE character_id IN ({placeholders})
+             GROUP BY trainer_name, source
+             ORDER BY trainer_name, source",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(RankSourceTotal {
+                trainer_name: row.get(0)?,
+                source: row.get(1)?,
+                events: row.get(2)?,
+                ranks: row.get(3)?,
+                first: row.get(4)?,
+                last: row.get(5)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Every trainer's ranks split by source (see `RankSourceBreakdown`), most effective
+    /// ranks first.
+    pub fn rank_source_breakdown(&self, char_id: i64) -> Result<Vec<RankSourceBreakdown>> {
+        let totals = self.get_rank_source_totals_merged(char_id)?;
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT trainer_name, COUNT(DISTINCT log_file) FROM trainer_rank_history
+             WHERE character_id IN ({placeholders}) GROUP BY trainer_name",
+        ))?;
+        let files: Vec<(String, i64)> = stmt
+            .query_map(params_from_iter(ids.iter()), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let total = |trainer: &str, source: &str| {
+            totals.iter().find(|t| t.trainer_name == trainer && t.source == source)
+        };
+        let mut rows: Vec<RankSourceBreakdown> = self
+            .get_trainers_merged(char_id)?
+            .into_iter()
+            .map(|t| {
+                let name = t.trainer_name.as_str();
+                RankSourceBreakdown {
+                    counted: t.ranks,
+                    counted_recorded: total(name, RANK_SOURCE_TRAINER).map_or(0, |s| s.events),
+                    apply_learning_ranks: t.apply_learning_ranks,
+                    apply_learning_events: total(name, RANK_SOURCE_APPLY_LEARNING).map_or(0, |s| s.events),
+                    apply_learning_partial_events: t.apply_learning_unknown_count,
+                    apply_learning_estimated_ranks: t.apply_learning_estimated_ranks,
+                    modified_ranks: t.modified_ranks,
+                    effective_ranks: t.effective_ranks(),
+                    log_files: files.iter().find(|(f, _)| f == name).map_or(0, |(_, n)| *n),
+                    rank_mode: t.rank_mode,
+                    trainer_name: t.trainer_name,
+                }
+            })
+            .filter(|b| b.effective_ranks > 0 || b.apply_learning_partial_events > 0)
+            .collect();
+        rows.sort_by(|a, b| b.effective_ranks.cmp(&a.effective_ranks).then(a.trainer_name.cmp(&b.trainer_name)));
+        Ok(rows)
+    }
+
+    /// Every recorded rank event with a trainer (case-insensitive), oldest first, with the
+    /// log file it was scanned from.
+    pub fn get_rank_source_events_merged(&self, char_id: i64, trainer_name: &str) -> Result<Vec<RankSourceEvent>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT trainer_name, source, ranks, ranked_at, log_file
+             FROM trainer_rank_history
+             WHERE character_id IN ({placeholders}) AND trainer_name = ? COLLATE NOCASE
+             ORDER BY ranked_at, id",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let bind = ids
+            .iter()
+            .map(|id| id as &dyn rusqlite::ToSql)
+            .chain(std::iter::once(&trainer_name as &dyn rusqlite::ToSql));
+        let rows = stmt.query_map(params_from_iter(bind), |row| {
+            Ok(RankSourceEvent {
+                trainer_name: row.get(0)?,
+                source: row.get(1)?,
+                ranks: row.get(2)?,
+                ranked_at: row.get(3)?,
+                log_file: row.get(4)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RankMode;
+
+    #[test]
+    fn rank_history_groups_by_month_across_merges() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        for date in ["2024-01-03 10:00:00", "2024-01-20 10:00:00", "2024-03-01 09:00:00"] {
+            db.upsert_trainer_rank(c, "Histia", date, 1.0).unwrap();
+        }
+        db.upsert_trainer_rank(alt, "Histia", "2024-03-02 09:00:00", 1.0).unwrap();
+        db.upsert_trainer_rank(c, "Detha", "2024-02-01 09:00:00", 1.0).unwrap();
+        db.merge_characters(&[alt], c).unwrap();
+
+        let months = db.get_trainer_rank_history_merged(c, "histia").unwrap();
+        assert_eq!(
+            months,
+            vec![
+                RankMonth { month: "2024-01".into(), ranks: 2, cumulative: 2 },
+                RankMonth { month: "2024-03".into(), ranks: 2, cumulative: 4 },
+            ]
+        );
+
+        // Switching to override zeroes log-derived ranks, and their history with them.
+        db.set_rank_override(c, "Histia", RankMode::Override.as_str(), 50, None).unwrap();
+        assert_eq!(db.get_trainer_rank_history_merged(c, "Histia").unwrap().len(), 1);
+        db.reset_log_data().unwrap();
+        assert!(db.get_trainer_rank_history_merged(c, "Detha").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rank_sources_record_apply_learning_and_log_file() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.set_scan_provenance(Some("logs/Gandor/CL Log 2024:01:01.txt"));
+        db.upsert_trainer_rank(c, "Histia", "2024-01-01 10:00:00", 1.0).unwrap();
+        db.upsert_trainer_rank(c, "Histia", "2024-01-01 11:00:00", 1.0).unwrap();
+        db.upsert_apply_learning(c, "Histia", "2024-01-01 12:00:00", 10, 1.0).unwrap();
+        db.set_scan_provenance(None);
+        db.upsert_apply_learning_unknown(c, "Histia", "2024-01-02 12:00:00", 1.0).unwrap();
+
+        let totals = db.get_rank_source_totals_merged(c).unwrap();
+        let summary: Vec<(&str, i64, i64)> =
+            totals.iter().map(|t| (t.source.as_str(), t.events, t.ranks)).collect();
+        assert_eq!(
+            summary,
+            vec![("apply_learning", 1, 10), ("apply_learning_partial", 1, 0), ("trainer", 2, 2)]
+        );
+        let events = db.get_rank_source_events_merged(c, "histia").unwrap();
+        assert_eq!(events[0].log_file.as_deref(), Some("logs/Gandor/CL Log 2024:01:01.txt"));
+        assert_eq!(events[3].log_file, None);
+
+        db.set_modified_ranks(c, "Histia", 3).unwrap();
+        let b = &db.rank_source_breakdown(c).unwrap()[0];
+        assert_eq!(
+            (b.counted, b.counted_recorded, b.apply_learning_ranks, b.apply_learning_partial_events),
+            (2, 2, 10, 1)
+        );
+        assert_eq!((b.modified_ranks, b.effective_ranks, b.log_files), (3, 15, 1));
+
+        // Pace and history still count only trainer ranks
+        assert_eq!(db.get_trainer_rank_times_merged(c, "Histia").unwrap().len(), 2);
+        assert_eq!(db.get_ranks_since_merged(c, None).unwrap(), vec![("Histia".to_string(), 2)]);
+    }
+}
@@ -0,0 +1,126 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// Values of `rescues.direction`.
+pub const RESCUE_DIRECTIONS: &[&str] = &["given", "received"];
+/// Values of `rescues.kind`: a chain drag or a healer's raise.
+pub const RESCUE_KINDS: &[&str] = &["drag", "raise"];
+
+/// Rescues exchanged with one player.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RescueSummary {
+    pub name: String,
+    /// "{name} starts dragging you." / "{name} is dragging you."
+    pub dragged_you: i64,
+    /// "{name} raises you."
+    pub raised_you: i64,
+    /// "You start dragging {name}."
+    pub you_dragged: i64,
+    /// "You raise {name}."
+    pub you_raised: i64,
+    /// Earliest / latest rescue in either direction.
+    pub first_date: Timestamp,
+    pub last_date: Timestamp,
+}
+
+impl RescueSummary {
+    /// Times this player rescued the character.
+    pub fn received(&self) -> i64 {
+        self.dragged_you + self.raised_you
+    }
+
+    /// Times the character rescued this player.
+    pub fn given(&self) -> i64 {
+        self.you_dragged + self.you_raised
+    }
+}
+
+impl Database {
+    /// Count one rescue exchanged with `player`. `direction` is "given" or "received",
+    /// `kind` "drag" or "raise".
+    pub fn record_rescue(&self, char_id: i64, player: &str, direction: &str, kind: &str, date: &str) -> Result<()> {
+        if !RESCUE_DIRECTIONS.contains(&direction) || !RESCUE_KINDS.contains(&kind) {
+            return Err(crate::error::AmanuensisError::Data(format!(
+                "Unknown rescue: {} {}",
+                direction, kind
+            )));
+        }
+        self.conn.execute(
+            "INSERT INTO rescues (character_id, player_name, direction, kind, count, first_date, last_date)
+             VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5)
+             ON CONFLICT(character_id, player_name, direction, kind) DO UPDATE SET
+                 count = count + 1,
+                 first_date = MIN(first_date, excluded.first_date),
+                 last_date = MAX(last_date, excluded.last_date)",
+            rusqlite::params![char_id, player, direction, kind, date],
+        )?;
+        Ok(())
+    }
+
+    /// Rescues given and received per player for a (possibly merged) character, most rescues first.
+    pub fn get_rescues_merged(&self, char_id: i64) -> Result<Vec<RescueSummary>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT player_name,
+                    SUM(CASE WHEN direction = 'received' AND kind = 'drag' THEN count ELSE 0 END),
+                    SUM(CASE WHEN direction = 'received' AND kind = 'raise' THEN count ELSE 0 END),
+                    SUM(CASE WHEN direction = 'given' AND kind = 'drag' THEN count ELSE 0 END),
+                    SUM(CASE WHEN direction = 'given' AND kind = 'raise' THEN count ELSE 0 END),
+                    MIN(first_date), MAX(last_date)
+             FROM rescues
+             WHERE character_id IN ({placeholders})
+             GROUP BY player_name
+             ORDER BY SUM(count) DESC, player_name",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(RescueSummary {
+                name: row.get(0)?,
+                dragged_you: row.get(1)?,
+                raised_you: row.get(2)?,
+                you_dragged: row.get(3)?,
+                you_raised: row.get(4)?,
+                first_date: row.get(5)?,
+                last_date: row.get(6)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rescues_pivot_directions_and_kinds() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.record_rescue(c, "Bramble", "received", "drag", "2024-01-02 10:00:00").unwrap();
+        db.record_rescue(c, "Bramble", "received", "drag", "2024-01-01 09:00:00").unwrap();
+        db.record_rescue(c, "Bramble", "received", "raise", "2024-01-03 09:00:00").unwrap();
+        db.record_rescue(c, "Corvid", "given", "drag", "2024-01-04 09:00:00").unwrap();
+        assert!(db.record_rescue(c, "Corvid", "given", "carry", "2024-01-04 09:00:00").is_err());
+        assert!(db.record_rescue(c, "Corvid", "stolen", "drag", "2024-01-04 09:00:00").is_err());
+
+        let rescues = db.get_rescues_merged(c).unwrap();
+        assert_eq!(
+            rescues[0],
+            RescueSummary {
+                name: "Bramble".into(),
+                dragged_you: 2,
+                raised_you: 1,
+                you_dragged: 0,
+                you_raised: 0,
+                first_date: "2024-01-01 09:00:00".parse().unwrap(),
+                last_date: "2024-01-03 09:00:00".parse().unwrap(),
+            }
+        );
+        assert_eq!((rescues[1].name.as_str(), rescues[1].received(), rescues[1].given()), ("Corvid", 0, 1));
+    }
+}
@@ -0,0 +1,46 @@
+use rusqlite::params;
+
+use crate::error::Result;
+use crate::models::ScanError;
+use super::Database;
+
+impl Database {
+    /// Record a file's failure, replacing any earlier one for the same path.
+    pub fn record_scan_error(&self, error: &ScanError) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO scan_errors (file_path, line, kind, message, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(file_path) DO UPDATE SET
+                line = excluded.line,
+                kind = excluded.kind,
+                message = excluded.message,
+                created_at = excluded.created_at",
+            params![error.file_path, error.line, error.kind, error.message, error.created_at],
+        )?;
+        Ok(())
+    }
+
+    /// Forget a file's failure (it has since scanned cleanly).
+    pub fn clear_scan_error(&self, file_path: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM scan_errors WHERE file_path = ?1", [file_path])?;
+        Ok(())
+    }
+
+    /// Files whose last scan failed, newest failure first.
+    pub fn get_scan_errors(&self) -> Result<Vec<ScanError>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, line, kind, message, created_at FROM scan_errors
+             ORDER BY created_at DESC, file_path",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ScanError {
+                file_path: row.get(0)?,
+                line: row.get(1)?,
+                kind: row.get(2)?,
+                message: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
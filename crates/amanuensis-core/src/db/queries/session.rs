@@ -0,0 +1,150 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::date::Timestamp;
+use crate::error::Result;
+use crate::models::Session;
+use super::Database;
+
+impl Database {
+    /// Add a scanned session's tallies to the `sessions` table, keyed on
+    /// (character, started_at). When `continuation` is set (a tail scan that began after the
+    /// session's welcome line) the tallies are added to the character's latest session in the
+    /// same log file instead; if there is none, `session.started_at` opens a new one.
+    pub fn upsert_session(&self, session: &Session, continuation: bool) -> Result<()> {
+        let mut started_at = session.started_at;
+        if continuation {
+            let latest: Option<Timestamp> = self
+                .conn
+                .query_row(
+                    "SELECT started_at FROM sessions
+                     WHERE character_id = ?1 AND log_file = ?2
+                     ORDER BY started_at DESC LIMIT 1",
+                    params![session.character_id, session.log_file],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(latest) = latest {
+                started_at = latest;
+            }
+        }
+        self.conn.execute(
+            "INSERT INTO sessions (character_id, log_file, started_at, ended_at, kills, ranks, coins, deaths)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(character_id, started_at) DO UPDATE SET
+               ended_at = MAX(ended_at, excluded.ended_at),
+               kills = kills + excluded.kills,
+               ranks = ranks + excluded.ranks,
+               coins = coins + excluded.coins,
+               deaths = deaths + excluded.deaths",
+            params![
+                session.character_id,
+                session.log_file,
+                started_at,
+                session.ended_at,
+                session.kills,
+                session.ranks,
+                session.coins,
+                session.deaths,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get one session by id.
+    pub fn get_session(&self, session_id: i64) -> Result<Option<Session>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT id, character_id, log_file, started_at, ended_at, kills, ranks, coins, deaths
+                 FROM sessions WHERE id = ?1",
+                params![session_id],
+                session_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Get sessions for a character, most recent first.
+    pub fn get_sessions(&self, char_id: i64) -> Result<Vec<Session>> {
+        self.get_sessions_for_char_ids(&[char_id])
+    }
+
+    /// Get sessions for a character and all its merge sources, most recent first.
+    pub fn get_sessions_merged(&self, char_id: i64) -> Result<Vec<Session>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        self.get_sessions_for_char_ids(&ids)
+    }
+
+    fn get_sessions_for_char_ids(&self, char_ids: &[i64]) -> Result<Vec<Session>> {
+        let placeholders = char_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT id, character_id, log_file, started_at, ended_at, kills, ranks, coins, deaths
+             FROM sessions WHERE character_id IN ({placeholders})
+             ORDER BY started_at DESC",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let sessions = stmt.query_map(rusqlite::params_from_iter(char_ids.iter()), session_from_row)?;
+        Ok(sessions.filter_map(|r| r.ok()).collect())
+    }
+}
+
+/// Map a `SELECT id, character_id, log_file, started_at, ended_at, kills, ranks, coins, deaths` row.
+fn session_from_row(row: &rusqlite::Row) -> rusqlite::Result<Session> {
+    let started_at: Timestamp = row.get(3)?;
+    let ended_at: Timestamp = row.get(4)?;
+    let duration_secs = (ended_at.to_epoch() - started_at.to_epoch()).max(0);
+    Ok(Session {
+        id: Some(row.get(0)?),
+        character_id: row.get(1)?,
+        log_file: row.get(2)?,
+        started_at,
+        ended_at,
+        duration_secs,
+        kills: row.get(5)?,
+        ranks: row.get(6)?,
+        coins: row.get(7)?,
+        deaths: row.get(8)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::queries::Database;
+    use crate::models::Session;
+
+    #[test]
+    fn continuation_extends_latest_session_in_file() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        let mut first = Session::new(c, "a.txt", "2024-01-01 10:00:00".parse().unwrap());
+        first.ended_at = "2024-01-01 10:30:00".parse().unwrap();
+        first.kills = 3;
+        db.upsert_session(&first, false).unwrap();
+
+        let mut tail = Session::new(c, "a.txt", "2024-01-01 10:45:00".parse().unwrap());
+        tail.ended_at = "2024-01-01 11:00:00".parse().unwrap();
+        tail.kills = 2;
+        tail.deaths = 1;
+        db.upsert_session(&tail, true).unwrap();
+
+        let sessions = db.get_sessions(c).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].started_at, "2024-01-01 10:00:00");
+        assert_eq!(sessions[0].ended_at, "2024-01-01 11:00:00");
+        assert_eq!(sessions[0].duration_secs, 3600);
+        assert_eq!((sessions[0].kills, sessions[0].deaths), (5, 1));
+    }
+
+    #[test]
+    fn continuation_without_prior_session_opens_one() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        db.upsert_session(&Session::new(c, "b.txt", "2024-01-02 09:00:00".parse().unwrap()), true).unwrap();
+        db.upsert_session(&Session::new(c, "a.txt", "2024-01-01 09:00:00".parse().unwrap()), false).unwrap();
+        let sessions = db.get_sessions(c).unwrap();
+        let starts: Vec<String> = sessions.iter().map(|s| s.started_at.to_string()).collect();
+        assert_eq!(starts, vec!["2024-01-02 09:00:00", "2024-01-01 09:00:00"]);
+        let by_id = db.get_session(sessions[1].id.unwrap()).unwrap().unwrap();
+        assert_eq!(by_id.log_file, "a.txt");
+        assert!(db.get_session(999).unwrap().is_none());
+    }
+}
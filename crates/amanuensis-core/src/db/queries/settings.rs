@@ -0,0 +1,105 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::error::Result;
+use super::Database;
+
+/// `app_settings` key: whether scans store raw lines in the search index ("true"/"false").
+pub const SETTING_INDEX_LOG_LINES: &str = "index_log_lines";
+
+/// `app_settings` key: search index retention in years (absent keeps every year).
+pub const SETTING_INDEX_RETENTION_YEARS: &str = "index_retention_years";
+
+/// `app_settings` key: index only lines that classify as events ("true"/"false").
+pub const SETTING_INDEX_EVENTS_ONLY: &str = "index_events_only";
+
+/// `app_settings` key: end of the last recorded digest (storage date), for `--since last`.
+pub const SETTING_LAST_DIGEST: &str = "last_digest_at";
+
+/// `app_settings` key: URL digests are posted to.
+pub const SETTING_DIGEST_WEBHOOK: &str = "digest_webhook";
+
+/// `app_settings` key: log path root aliases, a JSON list of `log_paths::PathAlias`.
+pub const SETTING_LOG_PATH_ALIASES: &str = "log_path_aliases";
+
+/// `app_settings` key: creature-name canonicalization rules, a JSON list of
+/// `creature_names::CreatureNameRule`.
+pub const SETTING_CREATURE_NAME_RULES: &str = "creature_name_rules";
+
+/// `app_settings` key: whether the creature-name rules apply ("true"/"false").
+pub const SETTING_CREATURE_NAME_RULES_ENABLED: &str = "creature_name_rules_enabled";
+
+impl Database {
+    /// A stored setting, or None if never set. Settings live with the database, so they
+    /// survive resets and follow the file to another machine.
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM app_settings WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()?)
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_setting(&self, key: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM app_settings WHERE key = ?1", params![key])?;
+        Ok(())
+    }
+
+    /// Whether scans should fill the search index. On unless turned off.
+    pub fn indexing_enabled(&self) -> Result<bool> {
+        Ok(self.get_setting(SETTING_INDEX_LOG_LINES)?.as_deref() != Some("false"))
+    }
+
+    /// Turn search indexing on or off. Turning it off also drops the existing index (and
+    /// compacts the file), which is the point: a small database. Returns the lines removed.
+    pub fn set_indexing_enabled(&self, enabled: bool) -> Result<i64> {
+        self.set_setting(SETTING_INDEX_LOG_LINES, if enabled { "true" } else { "false" })?;
+        if enabled {
+            Ok(0)
+        } else {
+            self.clear_log_lines()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexing_toggle_persists_and_clears_index() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.get_or_create_character("Idle").unwrap();
+        db.insert_log_lines(&[
+            (c, "You slaughtered a Rat.", "2024-01-01 10:00:00", "a.txt"),
+            (c, "Welcome to Clan Lord, Gandor!", "2024-01-01 09:00:00", "a.txt"),
+        ])
+        .unwrap();
+
+        let status = db.index_status().unwrap();
+        assert!(status.enabled && status.available);
+        assert_eq!((status.lines, status.characters), (2, 1));
+        assert!(status.estimated_bytes > 0);
+
+        assert_eq!(db.set_indexing_enabled(false).unwrap(), 2);
+        let status = db.index_status().unwrap();
+        assert!(!status.enabled);
+        assert_eq!((status.lines, status.estimated_bytes), (0, 0));
+
+        // Survives a reset; turning it back on keeps an empty index.
+        db.reset_log_data().unwrap();
+        assert!(!db.indexing_enabled().unwrap());
+        assert_eq!(db.set_indexing_enabled(true).unwrap(), 0);
+        assert_eq!(db.get_setting(SETTING_INDEX_LOG_LINES).unwrap().as_deref(), Some("true"));
+        db.delete_setting(SETTING_INDEX_LOG_LINES).unwrap();
+        assert_eq!(db.get_setting(SETTING_INDEX_LOG_LINES).unwrap(), None);
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, SubsecRound};
+use rusqlite::{params, OptionalExtension, Row};
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// Daily snapshots are kept this many days; older ones are thinned to the last of each month.
+pub const SNAPSHOT_DAILY_DAYS: i64 = 90;
+
+/// Totals for one visible character (merge sources folded in) at the end of a day's scans.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StatsSnapshot {
+    /// `YYYY-MM-DD` (UTC) the snapshot was taken.
+    pub day: String,
+    pub taken_at: Timestamp,
+    pub logins: i64,
+    pub deaths: i64,
+    pub departs: i64,
+    pub solo_kills: i64,
+    pub assisted_kills: i64,
+    pub total_ranks: i64,
+    pub effective_ranks: f64,
+    pub coins_picked_up: i64,
+    pub esteem: i64,
+    pub coin_level: i64,
+    pub lastys_completed: i64,
+}
+
+impl StatsSnapshot {
+    fn same_totals(&self, other: &StatsSnapshot) -> bool {
+        StatsSnapshot { day: self.day.clone(), taken_at: self.taken_at, ..other.clone() } == *self
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(StatsSnapshot {
+            day: row.get(0)?,
+            taken_at: row.get(1)?,
+            logins: row.get(2)?,
+            deaths: row.get(3)?,
+            departs: row.get(4)?,
+            solo_kills: row.get(5)?,
+            assisted_kills: row.get(6)?,
+            total_ranks: row.get(7)?,
+            effective_ranks: row.get(8)?,
+            coins_picked_up: row.get(9)?,
+            esteem: row.get(10)?,
+            coin_level: row.get(11)?,
+            lastys_completed: row.get(12)?,
+        })
+    }
+}
+
+const SNAPSHOT_COLUMNS: &str = "day, taken_at, logins, deaths, departs, solo_kills, assisted_kills, total_ranks,
+     effective_ranks, coins_picked_up, esteem, coin_level, lastys_completed";
+
+impl Database {
+    /// Snapshot every visible character's totals, called after each scan. One row per
+    /// character per day (a later scan the same day replaces it); a character whose totals
+    /// have not changed since its last snapshot gets no new row. Applies the retention limits.
+    pub fn record_daily_snapshots(&self) -> Result<usize> {
+        self.record_daily_snapshots_at(crate::date::now_naive())
+    }
+
+    pub(crate) fn record_daily_snapshots_at(&self, now: NaiveDateTime) -> Result<usize> {
+        let day = now.date().format("%Y-%m-%d").to_string();
+        let taken_at = Timestamp::from(now.trunc_subsecs(0));
+        let mut recorded = 0;
+        for c in self.list_characters()? {
+            let char_id = c.id.unwrap();
+            let Some(summary) = self.character_summary(char_id)? else { continue };
+            let snapshot = StatsSnapshot {
+                day: day.clone(),
+                taken_at,
+                logins: summary.character.logins,
+                deaths: summary.character.deaths,
+                departs: summary.character.departs,
+                solo_kills: summary.solo_kills,
+                assisted_kills: summary.assisted_kills,
+                total_ranks: summary.total_ranks,
+                effective_ranks: summary.effective_ranks,
+                coins_picked_up: summary.character.coins_picked_up,
+                esteem: summary.character.esteem,
+                coin_level: summary.character.coin_level,
+                lastys_completed: summary.lastys_completed,
+            };
+            let previous = self
+                .conn
+                .query_row(
+                    &format!(
+                        "SELECT {SNAPSHOT_COLUMNS} FROM snapshots
+                         WHERE character_id = ?1 AND day < ?2 ORDER BY day DESC LIMIT 1"
+                    ),
+                    params![char_id, day],
+                    StatsSnapshot::from_row,
+                )
+                .optional()?;
+            if previous.is_some_and(|p| p.same_totals(&snapshot)) {
+                self.conn.execute(
+                    "DELETE FROM snapshots WHERE character_id = ?1 AND day = ?2",
+                    params![char_id, day],
+                )?;
+                continue;
+            }
+            self.conn.execute(
+                "INSERT INTO snapshots (character_id, day, taken_at, logins, deaths, departs, solo_kills,
+                     assisted_kills, total_ranks, effective_ranks, coins_picked_up, esteem, coin_level,
+                     lastys_completed)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                 ON CONFLICT(character_id, day) DO UPDATE SET
+                     taken_at = excluded.taken_at, logins = excluded.logins, deaths = excluded.deaths,
+                     departs = excluded.departs, solo_kills = excluded.solo_kills,
+                     assisted_kills = excluded.assisted_kills, total_ranks = excluded.total_ranks,
+                     effective_ranks = excluded.effective_ranks, coins_picked_up = excluded.coins_picked_up,
+                     esteem = excluded.esteem, coin_level = excluded.coin_level,
+                     lastys_completed = excluded.lastys_completed",
+                params![
+                    char_id, snapshot.day, snapshot.taken_at, snapshot.logins, snapshot.deaths,
+                    snapshot.departs, snapshot.solo_kills, snapshot.assisted_kills, snapshot.total_ranks,
+                    snapshot.effective_ranks, snapshot.coins_picked_up, snapshot.esteem, snapshot.coin_level,
+                    snapshot.lastys_completed,
+                ],
+            )?;
+            recorded += 1;
+        }
+        self.prune_snapshots(now.date())?;
+        Ok(recorded)
+    }
+
+    /// Drop snapshots older than `SNAPSHOT_DAILY_DAYS` except each character's last one of
+    /// each month.
+    fn prune_snapshots(&self, today: NaiveDate) -> Result<usize> {
+        let cutoff = (today - Duration::days(SNAPSHOT_DAILY_DAYS)).format("%Y-%m-%d").to_string();
+        let removed = self.conn.execute(
+            "DELETE FROM snapshots
+             WHERE day < ?1
+               AND day <> (SELECT MAX(s.day) FROM snapshots s
+                           WHERE s.character_id = snapshots.character_id
+                             AND substr(s.day, 1, 7) = substr(snapshots.day, 1, 7))",
+            params![cutoff],
+        )?;
+        Ok(removed)
+    }
+
+    /// A character's snapshots, oldest first. Snapshots are taken of visible characters, so
+    /// for a merge target they already include its sources.
+    pub fn get_snapshots(&self, char_id: i64) -> Result<Vec<StatsSnapshot>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {SNAPSHOT_COLUMNS} FROM snapshots WHERE character_id = ?1 ORDER BY day"
+        ))?;
+        let rows = stmt.query_map(params![char_id], StatsSnapshot::from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// The latest snapshot taken on or before `day` (`YYYY-MM-DD`), e.g. the baseline for
+    /// "what changed since the start of the month".
+    pub fn get_snapshot_on_or_before(&self, char_id: i64, day: &str) -> Result<Option<StatsSnapshot>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {SNAPSHOT_COLUMNS} FROM snapshots
+                     WHERE character_id = ?1 AND day <= ?2 ORDER BY day DESC LIMIT 1"
+                ),
+                params![char_id, day],
+                StatsSnapshot::from_row,
+            )
+            .optional()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: &str) -> NaiveDateTime {
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap().and_hms_opt(12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn daily_snapshots_skip_unchanged_days_and_thin_old_ones() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Fen").unwrap();
+        db.increment_character_field(c, "logins", 1).unwrap();
+
+        assert_eq!(db.record_daily_snapshots_at(at("2024-01-10")).unwrap(), 1);
+        // Nothing changed: no new row the next day
+        assert_eq!(db.record_daily_snapshots_at(at("2024-01-11")).unwrap(), 0);
+        db.upsert_kill(c, "Rat", "killed_count", 1, "2024-01-12 10:00:00").unwrap();
+        db.record_daily_snapshots_at(at("2024-01-12")).unwrap();
+        db.upsert_kill(c, "Rat", "killed_count", 1, "2024-01-12 11:00:00").unwrap();
+        db.record_daily_snapshots_at(at("2024-01-12")).unwrap();
+        db.increment_character_field(c, "deaths", 1).unwrap();
+        db.record_daily_snapshots_at(at("2024-01-20")).unwrap();
+
+        let days: Vec<(String, i64)> =
+            db.get_snapshots(c).unwrap().into_iter().map(|s| (s.day, s.solo_kills)).collect();
+        assert_eq!(
+            days,
+            vec![("2024-01-10".parse().unwrap(), 0), ("2024-01-12".parse().unwrap(), 2), ("2024-01-20".parse().unwrap(), 2)]
+        );
+        assert_eq!(db.get_snapshot_on_or_before(c, "2024-01-15").unwrap().unwrap().day, "2024-01-12");
+
+        // Past the daily window only January's last snapshot stays
+        db.increment_character_field(c, "deaths", 1).unwrap();
+        db.record_daily_snapshots_at(at("2024-06-01")).unwrap();
+        let days: Vec<String> = db.get_snapshots(c).unwrap().into_iter().map(|s| s.day).collect();
+        assert_eq!(days, vec!["2024-01-20", "2024-06-01"]);
+    }
+}
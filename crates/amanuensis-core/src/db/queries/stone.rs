@@ -0,0 +1,143 @@
+//! Darkstone and moonstone charges, uses and expiries.
+//!
+//! No stone message is parsed yet: none has been captured from a real log, and the shapes the
+//! game uses for them aren't known. The table and queries are ready for when one is; each
+//! darkstone use recorded also counts toward `characters.darkstone`, the counter Scribius
+//! imports fill.
+
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::{AmanuensisError, Result};
+use super::Database;
+use crate::date::Timestamp;
+
+/// Values of `stone_events.stone`.
+pub const STONE_KINDS: &[&str] = &["darkstone", "moonstone"];
+/// Values of `stone_events.action`.
+pub const STONE_ACTIONS: &[&str] = &["charge", "use", "expire"];
+
+/// One darkstone or moonstone message.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StoneEvent {
+    /// One of [`STONE_KINDS`].
+    pub stone: String,
+    /// One of [`STONE_ACTIONS`].
+    pub action: String,
+    /// Charges the stone holds after the event, when the message says.
+    pub charges: Option<i64>,
+    pub date: Timestamp,
+}
+
+/// Charges, uses and expiries of one kind of stone.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StoneUsage {
+    pub stone: String,
+    pub charged: i64,
+    pub used: i64,
+    pub expired: i64,
+    /// Charges left as of the latest event that reported them (0 after an expiry); None
+    /// when no message has said.
+    pub charges_left: Option<i64>,
+    pub first_date: Timestamp,
+    pub last_date: Timestamp,
+}
+
+impl Database {
+    /// Record a darkstone or moonstone event; a new darkstone use also bumps
+    /// `characters.darkstone`. Re-scanning the same line is a no-op.
+    pub fn record_stone_event(&self, char_id: i64, stone: &str, action: &str, charges: Option<i64>, date: &str) -> Result<()> {
+        if !STONE_KINDS.contains(&stone) || !STONE_ACTIONS.contains(&action) {
+            return Err(AmanuensisError::Data(format!("Unknown stone event: {} {}", stone, action)));
+        }
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO stone_events (character_id, stone, action, charges, date)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![char_id, stone, action, charges, date],
+        )?;
+        if inserted > 0 && stone == "darkstone" && action == "use" {
+            self.increment_character_field(char_id, "darkstone", 1)?;
+        }
+        Ok(())
+    }
+
+    /// Stone events of a (possibly merged) character, oldest first.
+    pub fn get_stone_events_merged(&self, char_id: i64) -> Result<Vec<StoneEvent>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT stone, action, charges, date FROM stone_events
+             WHERE character_id IN ({placeholders})
+             ORDER BY date, id",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(StoneEvent { stone: row.get(0)?, action: row.get(1)?, charges: row.get(2)?, date: row.get(3)? })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Per-stone totals for a (possibly merged) character, in [`STONE_KINDS`] order; stones
+    /// never seen are left out.
+    pub fn get_stone_usage_merged(&self, char_id: i64) -> Result<Vec<StoneUsage>> {
+        let events = self.get_stone_events_merged(char_id)?;
+        let mut usage = Vec::new();
+        for stone in STONE_KINDS {
+            let mine: Vec<&StoneEvent> = events.iter().filter(|e| e.stone == *stone).collect();
+            let (Some(first), Some(last)) = (mine.first(), mine.last()) else {
+                continue;
+            };
+            let count = |action: &str| mine.iter().filter(|e| e.action == action).count() as i64;
+            let charges_left = mine
+                .iter()
+                .rev()
+                .find_map(|e| if e.action == "expire" { Some(Some(0)) } else { e.charges.map(Some) })
+                .flatten();
+            usage.push(StoneUsage {
+                stone: stone.to_string(),
+                charged: count("charge"),
+                used: count("use"),
+                expired: count("expire"),
+                charges_left,
+                first_date: first.date,
+                last_date: last.date,
+            });
+        }
+        Ok(usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stone_usage_counts_actions_and_tracks_charges() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.record_stone_event(c, "darkstone", "charge", Some(5), "2024-01-01 10:00:00").unwrap();
+        db.record_stone_event(c, "darkstone", "use", Some(4), "2024-01-01 11:00:00").unwrap();
+        db.record_stone_event(c, "darkstone", "use", Some(4), "2024-01-01 11:00:00").unwrap();
+        db.record_stone_event(c, "darkstone", "use", None, "2024-01-01 12:00:00").unwrap();
+        db.record_stone_event(c, "moonstone", "use", Some(2), "2024-01-02 10:00:00").unwrap();
+        db.record_stone_event(c, "moonstone", "expire", None, "2024-01-03 10:00:00").unwrap();
+        assert!(db.record_stone_event(c, "bloodstone", "use", None, "2024-01-03 10:00:00").is_err());
+
+        let usage = db.get_stone_usage_merged(c).unwrap();
+        assert_eq!(
+            usage[0],
+            StoneUsage {
+                stone: "darkstone".into(),
+                charged: 1,
+                used: 2,
+                expired: 0,
+                charges_left: Some(4),
+                first_date: "2024-01-01 10:00:00".parse().unwrap(),
+                last_date: "2024-01-01 12:00:00".parse().unwrap(),
+            }
+        );
+        assert_eq!((usage[1].used, usage[1].expired, usage[1].charges_left), (1, 1, Some(0)));
+        // The two distinct darkstone uses feed the existing counter; the repeat doesn't.
+        assert_eq!(db.get_character("Gandor").unwrap().unwrap().darkstone, 2);
+    }
+}
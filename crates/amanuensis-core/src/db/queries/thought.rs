@@ -0,0 +1,106 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+
+/// Values of `thoughts.direction`.
+pub const THOUGHT_DIRECTIONS: &[&str] = &["sent", "received"];
+
+/// Private thoughts exchanged with one player.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Correspondent {
+    pub name: String,
+    /// "You think to {name}, …"
+    pub sent: i64,
+    /// "{name} thinks to you, …"
+    pub received: i64,
+    /// Distinct days with at least one thought either way.
+    pub days: i64,
+    /// "YYYY-MM-DD"
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+impl Database {
+    /// Count one thought exchanged with `correspondent` on `day` ("YYYY-MM-DD").
+    /// `direction` is "sent" or "received".
+    pub fn record_thought(&self, char_id: i64, correspondent: &str, direction: &str, day: &str) -> Result<()> {
+        if !THOUGHT_DIRECTIONS.contains(&direction) {
+            return Err(crate::error::AmanuensisError::Data(format!(
+                "Unknown thought direction: {}",
+                direction
+            )));
+        }
+        self.conn.execute(
+            "INSERT INTO thoughts (character_id, correspondent, direction, day, count)
+             VALUES (?1, ?2, ?3, ?4, 1)
+             ON CONFLICT(character_id, correspondent, direction, day) DO UPDATE SET count = count + 1",
+            rusqlite::params![char_id, correspondent, direction, day],
+        )?;
+        Ok(())
+    }
+
+    /// Thought partners of a (possibly merged) character, most thoughts first.
+    pub fn get_correspondents_merged(&self, char_id: i64) -> Result<Vec<Correspondent>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT correspondent,
+                    SUM(CASE WHEN direction = 'sent' THEN count ELSE 0 END),
+                    SUM(CASE WHEN direction = 'received' THEN count ELSE 0 END),
+                    COUNT(DISTINCT day), MIN(day), MAX(day)
+             FROM thoughts
+             WHERE character_id IN ({placeholders})
+             GROUP BY correspondent
+             ORDER BY SUM(count) DESC, MAX(day) DESC, correspondent",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(Correspondent {
+                name: row.get(0)?,
+                sent: row.get(1)?,
+                received: row.get(2)?,
+                days: row.get(3)?,
+                first_seen: row.get(4)?,
+                last_seen: row.get(5)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correspondents_pivot_directions_across_merged_days() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Main").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.record_thought(main, "Bramble", "sent", "2024-01-01").unwrap();
+        db.record_thought(main, "Bramble", "sent", "2024-01-01").unwrap();
+        db.record_thought(alt, "Bramble", "received", "2024-02-01").unwrap();
+        db.record_thought(main, "Corvid", "received", "2024-01-05").unwrap();
+        assert!(db.record_thought(main, "Corvid", "shouted", "2024-01-05").is_err());
+        db.merge_characters(&[alt], main).unwrap();
+
+        let rows = db.get_correspondents_merged(main).unwrap();
+        assert_eq!(
+            rows[0],
+            Correspondent {
+                name: "Bramble".into(),
+                sent: 2,
+                received: 1,
+                days: 2,
+                first_seen: "2024-01-01".parse().unwrap(),
+                last_seen: "2024-02-01".parse().unwrap(),
+            }
+        );
+        assert_eq!((rows[1].name.as_str(), rows[1].sent, rows[1].received), ("Corvid", 0, 1));
+
+        db.reset_log_data().unwrap();
+        assert!(db.get_correspondents_merged(main).unwrap().is_empty());
+    }
+}
@@ -4,8 +4,9 @@ use rusqlite::params;
 
 use crate::data::TrainerDb;
 use crate::error::Result;
-use crate::models::{RankMode, Trainer};
+use crate::models::{RankMode, Trainer, APPLY_LEARNING_PARTIAL_ESTIMATE};
 use super::Database;
+use super::rank_history::{RANK_SOURCE_APPLY_LEARNING, RANK_SOURCE_APPLY_LEARNING_PARTIAL};
 
 /// Compute weighted effective ranks from a trainer slice, skipping combo trainers
 /// whose components are already present (avoids double-counting).
@@ -33,6 +34,7 @@ pub fn coin_level_from_trainers(trainers: &[Trainer], trainer_db: &TrainerDb) ->
 impl Database {
     /// Upsert a trainer rank.
     /// Uses INSERT...ON CONFLICT for single-statement upsert performance.
+    /// Also records the rank in `trainer_rank_history`.
     pub fn upsert_trainer_rank(
         &self,
         char_id: i64,
@@ -49,6 +51,7 @@ impl Database {
                 effective_multiplier = excluded.effective_multiplier",
             params![char_id, trainer_name, date, multiplier],
         )?;
+        self.record_rank_history(char_id, trainer_name, date)?;
         Ok(())
     }
 
@@ -57,7 +60,7 @@ impl Database {
         let mut stmt = self.conn.prepare(
             "SELECT id, character_id, trainer_name, ranks, modified_ranks, date_of_last_rank,
                     apply_learning_ranks, apply_learning_unknown_count, rank_mode, override_date,
-                    effective_multiplier, notes
+                    effective_multiplier, notes, maxed
              FROM trainers WHERE character_id = ?1 ORDER BY ranks DESC",
         )?;
 
@@ -71,10 +74,12 @@ impl Database {
                 date_of_last_rank: row.get(5)?,
                 apply_learning_ranks: row.get(6)?,
                 apply_learning_unknown_count: row.get(7)?,
+                apply_learning_estimated_ranks: row.get::<_, i64>(7)? * APPLY_LEARNING_PARTIAL_ESTIMATE,
                 rank_mode: row.get(8)?,
                 override_date: row.get(9)?,
                 effective_multiplier: row.get(10)?,
                 notes: row.get(11)?,
+                maxed: row.get(12)?,
             })
         })?;
 
@@ -98,6 +103,18 @@ impl Database {
         Ok(())
     }
 
+    /// Mark a trainer as having nothing more to teach the character.
+    /// Creates the trainer record if it doesn't exist.
+    pub fn set_trainer_maxed(&self, char_id: i64, trainer_name: &str, multiplier: f64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO trainers (character_id, trainer_name, maxed, effective_multiplier)
+             VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT(character_id, trainer_name) DO UPDATE SET maxed = 1",
+            params![char_id, trainer_name, multiplier],
+        )?;
+        Ok(())
+    }
+
     /// Upsert apply-learning confirmed ranks (10 per "much more" event).
     pub fn upsert_apply_learning(
         &self,
@@ -116,6 +133,7 @@ impl Database {
                 effective_multiplier = excluded.effective_multiplier",
             params![char_id, trainer_name, amount, date, multiplier],
         )?;
+        self.record_rank_source(char_id, trainer_name, date, RANK_SOURCE_APPLY_LEARNING, amount)?;
         Ok(())
     }
 
@@ -136,6 +154,7 @@ impl Database {
                 effective_multiplier = excluded.effective_multiplier",
             params![char_id, trainer_name, date, multiplier],
         )?;
+        self.record_rank_source(char_id, trainer_name, date, RANK_SOURCE_APPLY_LEARNING_PARTIAL, 0)?;
         Ok(())
     }
 
@@ -151,6 +170,22 @@ impl Database {
         self.set_rank_override(char_id, trainer_name, RankMode::Modifier.as_str(), modified_ranks, None)
     }
 
+    /// `set_modified_ranks` for several trainers in one transaction: either every row is
+    /// written or, on the first error, none are.
+    pub fn set_modified_ranks_bulk(&self, char_id: i64, ranks: &[(String, i64)]) -> Result<()> {
+        self.begin_transaction()?;
+        let written = ranks
+            .iter()
+            .try_for_each(|(trainer, modified)| self.set_modified_ranks(char_id, trainer, *modified));
+        match written {
+            Ok(()) => self.commit_transaction(),
+            Err(e) => {
+                let _ = self.rollback_transaction();
+                Err(e)
+            }
+        }
+    }
+
     /// Set rank override mode for a specific trainer record.
     /// Creates the trainer record if it doesn't exist.
     /// When switching TO override or override_until_date, zeros ranks and apply_learning_ranks
@@ -201,8 +236,55 @@ impl Database {
                  WHERE character_id = ?1 AND trainer_name = ?2",
                 params![char_id, trainer_name],
             )?;
+            self.clear_rank_history(char_id, trainer_name)?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bulk_modified_ranks_all_or_nothing() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.set_modified_ranks_bulk(c, &[("Histia".into(), 40), ("Atkus".into(), 12)]).unwrap();
+        let modified = |name: &str| {
+            db.get_trainers(c).unwrap().into_iter().find(|t| t.trainer_name == name).map(|t| t.modified_ranks)
+        };
+        assert_eq!(modified("Histia"), Some(40));
+        assert_eq!(modified("Atkus"), Some(12));
+
+        db.conn()
+            .execute_batch(
+                "CREATE TRIGGER no_bad BEFORE INSERT ON trainers WHEN NEW.trainer_name = 'Bad'
+                 BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+            )
+            .unwrap();
+        let err = db.set_modified_ranks_bulk(c, &[("Histia".into(), 99), ("Bad".into(), 1)]);
+        assert!(err.is_err());
+        assert_eq!(modified("Histia"), Some(40));
+    }
+
+    #[test]
+    fn rank_sources_kept_apart() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.upsert_trainer_rank(c, "Histia", "2024-01-01 10:00:00", 1.0).unwrap();
+        db.upsert_apply_learning(c, "Histia", "2024-01-02 10:00:00", 10, 1.0).unwrap();
+        db.upsert_apply_learning_unknown(c, "Histia", "2024-01-03 10:00:00", 1.0).unwrap();
+        db.upsert_apply_learning_unknown(c, "Histia", "2024-01-04 10:00:00", 1.0).unwrap();
+        db.set_modified_ranks(c, "Histia", 7).unwrap();
+
+        let t = &db.get_trainers_merged(c).unwrap()[0];
+        assert_eq!(
+            (t.ranks, t.apply_learning_ranks, t.apply_learning_estimated_ranks, t.modified_ranks),
+            (1, 10, 2 * APPLY_LEARNING_PARTIAL_ESTIMATE, 7)
+        );
+        // The estimate is shown, not counted
+        assert_eq!(t.effective_ranks(), 18);
+    }
+}
@@ -0,0 +1,96 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+
+/// A system-message format the classifier doesn't recognize, with how often it was seen
+/// (unlike the diagnostics [`crate::UnknownLine`] samples, counted across every scanned line).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UnknownLineCount {
+    /// The message with digit runs replaced by `#`.
+    pub message: String,
+    /// One line as logged.
+    pub example: String,
+    pub count: i64,
+    /// Characters whose logs contain it.
+    pub characters: i64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+impl Database {
+    /// Count one unrecognized line of shape `message` (see
+    /// `parser::line_classifier::unrecognized_system_message`).
+    pub fn record_unknown_line(&self, char_id: i64, message: &str, example: &str, date: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO unknown_lines (character_id, message, example, count, first_seen, last_seen)
+             VALUES (?1, ?2, ?3, 1, ?4, ?4)
+             ON CONFLICT(character_id, message) DO UPDATE SET
+                count = count + 1,
+                first_seen = MIN(first_seen, excluded.first_seen),
+                last_seen = MAX(last_seen, excluded.last_seen)",
+            rusqlite::params![char_id, message, example, date],
+        )?;
+        Ok(())
+    }
+
+    /// The `limit` most common unrecognized formats for a (possibly merged) character, or
+    /// across every character when `char_id` is None.
+    pub fn get_unknown_lines(&self, char_id: Option<i64>, limit: usize) -> Result<Vec<UnknownLineCount>> {
+        let ids = match char_id {
+            Some(id) => self.char_ids_for_merged(id)?,
+            None => Vec::new(),
+        };
+        let filter = match char_id {
+            Some(_) => format!(
+                "WHERE character_id IN ({})",
+                ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
+            ),
+            None => String::new(),
+        };
+        let sql = format!(
+            "SELECT message, MIN(example), SUM(count), COUNT(DISTINCT character_id), MIN(first_seen), MAX(last_seen)
+             FROM unknown_lines {filter}
+             GROUP BY message
+             ORDER BY SUM(count) DESC, message
+             LIMIT {limit}",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(UnknownLineCount {
+                message: row.get(0)?,
+                example: row.get(1)?,
+                count: row.get(2)?,
+                characters: row.get(3)?,
+                first_seen: row.get(4)?,
+                last_seen: row.get(5)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_lines_group_by_shape_across_characters() {
+        let db = Database::open_in_memory().unwrap();
+        let a = db.get_or_create_character("Gandor").unwrap();
+        let b = db.get_or_create_character("Fen").unwrap();
+        db.record_unknown_line(a, "¥You hear # chimes.", "¥You hear 3 chimes.", "2024-01-02 10:00:00").unwrap();
+        db.record_unknown_line(a, "¥You hear # chimes.", "¥You hear 5 chimes.", "2024-01-01 10:00:00").unwrap();
+        db.record_unknown_line(b, "¥You hear # chimes.", "¥You hear 3 chimes.", "2024-01-03 10:00:00").unwrap();
+        db.record_unknown_line(b, "* The wind howls.", "* The wind howls.", "2024-01-03 11:00:00").unwrap();
+
+        let all = db.get_unknown_lines(None, 50).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!((all[0].count, all[0].characters), (3, 2));
+        assert_eq!((all[0].first_seen.as_str(), all[0].last_seen.as_str()), ("2024-01-01 10:00:00", "2024-01-03 10:00:00"));
+        assert_eq!(db.get_unknown_lines(None, 1).unwrap().len(), 1);
+        let gandor = db.get_unknown_lines(Some(a), 50).unwrap();
+        assert_eq!((gandor.len(), gandor[0].count), (1, 2));
+    }
+}
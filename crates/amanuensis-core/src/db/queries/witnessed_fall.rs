@@ -0,0 +1,107 @@
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// Witnessed falls grouped by player or by cause.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WitnessedFallTally {
+    /// Player name, or cause when grouped by cause.
+    pub key: String,
+    pub falls: i64,
+    /// Distinct causes (grouped by player) or players (grouped by cause).
+    pub distinct: i64,
+    /// The cause (or player) with the most falls; ties go to the alphabetically first.
+    pub top: String,
+    pub first_date: Timestamp,
+    pub last_date: Timestamp,
+}
+
+impl Database {
+    /// Count one fall of another player, seen in this character's log.
+    pub fn record_witnessed_fall(&self, char_id: i64, player: &str, cause: &str, date: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO falls_witnessed (character_id, player_name, cause, count, first_date, last_date)
+             VALUES (?1, ?2, ?3, 1, ?4, ?4)
+             ON CONFLICT(character_id, player_name, cause) DO UPDATE SET
+                 count = count + 1,
+                 first_date = MIN(first_date, excluded.first_date),
+                 last_date = MAX(last_date, excluded.last_date)",
+            rusqlite::params![char_id, player, cause, date],
+        )?;
+        Ok(())
+    }
+
+    /// Other players' falls witnessed by a (possibly merged) character, grouped by player
+    /// (or by cause when `by_cause`), most falls first.
+    pub fn get_witnessed_falls_merged(&self, char_id: i64, by_cause: bool) -> Result<Vec<WitnessedFallTally>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let (key, other) = if by_cause { ("cause", "player_name") } else { ("player_name", "cause") };
+        let sql = format!(
+            "WITH pairs AS (
+                 SELECT {key} AS k, {other} AS o, SUM(count) AS n,
+                        MIN(first_date) AS first_date, MAX(last_date) AS last_date
+                 FROM falls_witnessed
+                 WHERE character_id IN ({placeholders})
+                 GROUP BY {key}, {other}
+             )
+             SELECT k, SUM(n), COUNT(*),
+                    (SELECT o FROM pairs p2 WHERE p2.k = pairs.k ORDER BY n DESC, o LIMIT 1),
+                    MIN(first_date), MAX(last_date)
+             FROM pairs
+             GROUP BY k
+             ORDER BY SUM(n) DESC, k",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(WitnessedFallTally {
+                key: row.get(0)?,
+                falls: row.get(1)?,
+                distinct: row.get(2)?,
+                top: row.get(3)?,
+                first_date: row.get(4)?,
+                last_date: row.get(5)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn witnessed_falls_group_by_player_or_cause() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Healer").unwrap();
+        let alt = db.get_or_create_character("Healer Alt").unwrap();
+        db.record_witnessed_fall(main, "Bramble", "Orga Anger", "2024-01-02 10:00:00").unwrap();
+        db.record_witnessed_fall(main, "Bramble", "Orga Anger", "2024-01-01 09:00:00").unwrap();
+        db.record_witnessed_fall(alt, "Bramble", "Rat", "2024-01-03 09:00:00").unwrap();
+        db.record_witnessed_fall(alt, "Corvid", "Orga Anger", "2024-01-04 09:00:00").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let by_player = db.get_witnessed_falls_merged(main, false).unwrap();
+        assert_eq!(
+            by_player[0],
+            WitnessedFallTally {
+                key: "Bramble".into(),
+                falls: 3,
+                distinct: 2,
+                top: "Orga Anger".into(),
+                first_date: "2024-01-01 09:00:00".parse().unwrap(),
+                last_date: "2024-01-03 09:00:00".parse().unwrap(),
+            }
+        );
+        assert_eq!((by_player[1].key.as_str(), by_player[1].falls), ("Corvid", 1));
+
+        let by_cause = db.get_witnessed_falls_merged(main, true).unwrap();
+        let causes: Vec<(&str, i64, i64, &str)> =
+            by_cause.iter().map(|t| (t.key.as_str(), t.falls, t.distinct, t.top.as_str())).collect();
+        assert_eq!(causes, [("Orga Anger", 3, 2, "Bramble"), ("Rat", 1, 1, "Bramble")]);
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use rusqlite::params_from_iter;
+use serde::Serialize;
+
+use crate::error::Result;
+use super::Database;
+use crate::date::Timestamp;
+
+/// One stay in an area, as inferred from the creatures the character fought there.
+///
+/// This is an approximation: logs carry no zone-entry lines, so an area is the head of a
+/// fought creature's bestiary `location`. Time spent travelling or not fighting counts
+/// toward the last area a creature placed the character in, and creatures living in
+/// several areas place it in the first one listed.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ZoneVisit {
+    pub area: String,
+    /// First combat line against a creature of the area.
+    pub entered_at: Timestamp,
+    /// First combat line in the next area, or the last one here when the session ended.
+    pub left_at: Timestamp,
+    /// Combat lines that placed the character in the area.
+    pub events: i64,
+}
+
+impl ZoneVisit {
+    pub fn new(area: String, entered_at: Timestamp) -> Self {
+        Self { area, entered_at, left_at: entered_at, events: 1 }
+    }
+
+    pub fn duration_secs(&self) -> i64 {
+        (self.left_at.to_epoch() - self.entered_at.to_epoch()).max(0)
+    }
+}
+
+/// Time a character spent in one area, summed from inferred `ZoneVisit`s (an
+/// approximation — see there).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ZoneTime {
+    pub area: String,
+    pub seconds: i64,
+    pub visits: i64,
+    /// Distinct play sessions with at least one visit.
+    pub sessions: i64,
+    pub last_seen: Timestamp,
+}
+
+/// Kills of one creature in one area (`kills_by_location`), with the area inferred as for
+/// `ZoneVisit`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KillLocation {
+    pub creature_name: String,
+    pub area: String,
+    pub solo: i64,
+    pub assisted: i64,
+    /// Times the creature killed the character there.
+    pub killed_by: i64,
+}
+
+impl KillLocation {
+    pub fn total(&self) -> i64 {
+        self.solo + self.assisted
+    }
+}
+
+/// Count columns of `kills_by_location`.
+const KILL_LOCATION_FIELDS: [&str; 3] = ["solo_count", "assisted_count", "killed_by_count"];
+
+impl Database {
+    /// Add one visit to the movement log. A tail scan that re-opens a visit at the same
+    /// instant extends it rather than adding a duplicate.
+    pub fn record_zone_visit(&self, char_id: i64, visit: &ZoneVisit) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO zone_visits (character_id, area, entered_at, left_at, events)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(character_id, area, entered_at) DO UPDATE SET
+                 left_at = MAX(left_at, excluded.left_at),
+                 events = events + excluded.events",
+            rusqlite::params![char_id, visit.area, visit.entered_at, visit.left_at, visit.events],
+        )?;
+        Ok(())
+    }
+
+    /// Count one kill (or, with `killed_by_count`, one death) against a creature in `area`.
+    pub fn upsert_kill_location(&self, char_id: i64, creature_name: &str, area: &str, field: &str) -> Result<()> {
+        if !KILL_LOCATION_FIELDS.contains(&field) {
+            return Err(crate::error::AmanuensisError::Data(format!(
+                "Unknown kills_by_location field: {}",
+                field
+            )));
+        }
+        self.conn.execute(
+            &format!(
+                "INSERT INTO kills_by_location (character_id, creature_name, area, {field})
+                 VALUES (?1, ?2, ?3, 1)
+                 ON CONFLICT(character_id, creature_name, area) DO UPDATE SET {field} = {field} + 1",
+            ),
+            rusqlite::params![char_id, creature_name, area],
+        )?;
+        self.record_contribution(char_id, "kill_location", &format!("{creature_name}\u{1f}{area}"), field, 1)
+    }
+
+    /// Kills per (creature, area) for a (possibly merged) character, most kills first.
+    /// `creature` (case-insensitive) limits it to one creature: where it is killed most.
+    pub fn get_kills_by_location_merged(&self, char_id: i64, creature: Option<&str>) -> Result<Vec<KillLocation>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let mut params: Vec<rusqlite::types::Value> = ids.into_iter().map(Into::into).collect();
+        let creature_filter = match creature {
+            Some(name) => {
+                params.push(name.to_string().into());
+                " AND creature_name = ? COLLATE NOCASE"
+            }
+            None => "",
+        };
+        let sql = format!(
+            "SELECT creature_name, area, SUM(solo_count), SUM(assisted_count), SUM(killed_by_count)
+             FROM kills_by_location
+             WHERE character_id IN ({placeholders}){creature_filter}
+             GROUP BY creature_name, area
+             ORDER BY SUM(solo_count + assisted_count) DESC, SUM(killed_by_count) DESC, creature_name, area",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params.iter()), |row| {
+            Ok(KillLocation {
+                creature_name: row.get(0)?,
+                area: row.get(1)?,
+                solo: row.get(2)?,
+                assisted: row.get(3)?,
+                killed_by: row.get(4)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// The movement log of a (possibly merged) character, oldest first.
+    pub fn get_zone_visits_merged(&self, char_id: i64) -> Result<Vec<ZoneVisit>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT area, entered_at, left_at, events
+             FROM zone_visits
+             WHERE character_id IN ({placeholders})
+             ORDER BY entered_at, id",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(ZoneVisit {
+                area: row.get(0)?,
+                entered_at: row.get(1)?,
+                left_at: row.get(2)?,
+                events: row.get(3)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Visits that began during session `session_id`, oldest first.
+    pub fn get_session_zones(&self, session_id: i64) -> Result<Vec<ZoneVisit>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT v.area, v.entered_at, v.left_at, v.events
+             FROM zone_visits v
+             JOIN sessions s ON s.character_id = v.character_id
+                AND v.entered_at BETWEEN s.started_at AND s.ended_at
+             WHERE s.id = ?1
+             ORDER BY v.entered_at, v.id",
+        )?;
+        let rows = stmt.query_map([session_id], |row| {
+            Ok(ZoneVisit {
+                area: row.get(0)?,
+                entered_at: row.get(1)?,
+                left_at: row.get(2)?,
+                events: row.get(3)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Time per area for a (possibly merged) character, longest first. Areas and times are
+    /// inferred from combat lines (`ZoneVisit`), not read from zone messages.
+    pub fn get_zones_merged(&self, char_id: i64) -> Result<Vec<ZoneTime>> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT v.area, v.entered_at, v.left_at,
+                    (SELECT s.id FROM sessions s
+                     WHERE s.character_id = v.character_id
+                       AND v.entered_at BETWEEN s.started_at AND s.ended_at
+                     LIMIT 1)
+             FROM zone_visits v
+             WHERE v.character_id IN ({placeholders})",
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok((
+                ZoneVisit { area: row.get(0)?, entered_at: row.get(1)?, left_at: row.get(2)?, events: 0 },
+                row.get::<_, Option<i64>>(3)?,
+            ))
+        })?;
+
+        let mut by_area: HashMap<String, (ZoneTime, Vec<i64>)> = HashMap::new();
+        for (visit, session_id) in rows.filter_map(|r| r.ok()) {
+            let seconds = visit.duration_secs();
+            let (zone, sessions) = by_area.entry(visit.area.clone()).or_insert_with(|| {
+                (
+                    ZoneTime { area: visit.area, seconds: 0, visits: 0, sessions: 0, last_seen: visit.left_at },
+                    Vec::new(),
+                )
+            });
+            zone.seconds += seconds;
+            zone.visits += 1;
+            zone.last_seen = zone.last_seen.max(visit.left_at);
+            if let Some(id) = session_id {
+                if !sessions.contains(&id) {
+                    sessions.push(id);
+                }
+            }
+        }
+        let mut zones: Vec<ZoneTime> = by_area
+            .into_values()
+            .map(|(mut zone, sessions)| {
+                zone.sessions = sessions.len() as i64;
+                zone
+            })
+            .collect();
+        zones.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.area.cmp(&b.area)));
+        Ok(zones)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Session;
+
+    fn visit(area: &str, entered_at: &str, left_at: &str) -> ZoneVisit {
+        ZoneVisit { area: area.into(), entered_at: entered_at.parse().unwrap(), left_at: left_at.parse().unwrap(), events: 2 }
+    }
+
+    #[test]
+    fn zones_sum_time_per_area_across_sessions() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Main").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        for (c, start, end) in [
+            (main, "2024-01-01 10:00:00", "2024-01-01 12:00:00"),
+            (alt, "2024-01-02 10:00:00", "2024-01-02 12:00:00"),
+        ] {
+            let mut s = Session::new(c, "log.txt", start.parse().unwrap());
+            s.ended_at = end.parse().unwrap();
+            db.upsert_session(&s, false).unwrap();
+        }
+        db.record_zone_visit(main, &visit("Orga territory", "2024-01-01 10:05:00", "2024-01-01 10:35:00")).unwrap();
+        db.record_zone_visit(main, &visit("Ash Island", "2024-01-01 10:35:00", "2024-01-01 10:45:00")).unwrap();
+        db.record_zone_visit(main, &visit("Orga territory", "2024-01-01 11:00:00", "2024-01-01 11:10:00")).unwrap();
+        db.record_zone_visit(alt, &visit("Orga territory", "2024-01-02 10:00:00", "2024-01-02 10:20:00")).unwrap();
+        // A tail scan extending a visit folds into the existing row.
+        db.record_zone_visit(main, &visit("Ash Island", "2024-01-01 10:35:00", "2024-01-01 10:50:00")).unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let zones = db.get_zones_merged(main).unwrap();
+        assert_eq!(
+            zones,
+            vec![
+                ZoneTime {
+                    area: "Orga territory".into(),
+                    seconds: 60 * 60,
+                    visits: 3,
+                    sessions: 2,
+                    last_seen: "2024-01-02 10:20:00".parse().unwrap(),
+                },
+                ZoneTime {
+                    area: "Ash Island".into(),
+                    seconds: 15 * 60,
+                    visits: 1,
+                    sessions: 1,
+                    last_seen: "2024-01-01 10:50:00".parse().unwrap(),
+                },
+            ]
+        );
+        assert_eq!(db.get_zone_visits_merged(main).unwrap()[1].events, 4);
+
+        let first = db.get_sessions(main).unwrap().into_iter().find(|s| s.started_at.day() == "2024-01-01").unwrap();
+        let areas: Vec<String> = db.get_session_zones(first.id.unwrap()).unwrap().into_iter().map(|v| v.area).collect();
+        assert_eq!(areas, vec!["Orga territory", "Ash Island", "Orga territory"]);
+
+        db.reset_log_data().unwrap();
+        assert!(db.get_zones_merged(main).unwrap().is_empty());
+    }
+}
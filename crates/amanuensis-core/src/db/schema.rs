@@ -95,6 +95,322 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
             UNIQUE(character_id, creature_name, hour)
         );
 
+        CREATE TABLE IF NOT EXISTS coin_daily (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            day TEXT NOT NULL,
+            source TEXT NOT NULL,
+            amount INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, day, source)
+        );
+
+        DROP TABLE IF EXISTS event_hourly;
+        CREATE TABLE IF NOT EXISTS history_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            subject TEXT NOT NULL DEFAULT '',
+            value INTEGER NOT NULL DEFAULT 0,
+            log_file TEXT NOT NULL DEFAULT '',
+            line INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, event_type, date, log_file, line)
+        );
+        CREATE INDEX IF NOT EXISTS idx_history_events_lookup
+            ON history_events (character_id, event_type, date);
+
+        CREATE TABLE IF NOT EXISTS sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            log_file TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT NOT NULL,
+            kills INTEGER NOT NULL DEFAULT 0,
+            ranks INTEGER NOT NULL DEFAULT 0,
+            coins INTEGER NOT NULL DEFAULT 0,
+            deaths INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, started_at)
+        );
+
+        CREATE TABLE IF NOT EXISTS creature_accuracy (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            creature_name TEXT NOT NULL,
+            hits INTEGER NOT NULL DEFAULT 0,
+            misses INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, creature_name)
+        );
+
+        CREATE TABLE IF NOT EXISTS damage_taken (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            creature_name TEXT NOT NULL,
+            hits INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, creature_name)
+        );
+
+        CREATE TABLE IF NOT EXISTS companions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            player_name TEXT NOT NULL,
+            day TEXT NOT NULL,
+            events INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, player_name, day)
+        );
+
+        CREATE TABLE IF NOT EXISTS heals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            player_name TEXT NOT NULL,
+            direction TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            first_date TEXT NOT NULL,
+            last_date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, player_name, direction)
+        );
+
+        CREATE TABLE IF NOT EXISTS rescues (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            player_name TEXT NOT NULL,
+            direction TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            first_date TEXT NOT NULL,
+            last_date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, player_name, direction, kind)
+        );
+
+        CREATE TABLE IF NOT EXISTS falls_witnessed (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            player_name TEXT NOT NULL,
+            cause TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            first_date TEXT NOT NULL,
+            last_date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, player_name, cause)
+        );
+
+        CREATE TABLE IF NOT EXISTS circles (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            profession TEXT NOT NULL,
+            circle INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, profession, circle)
+        );
+
+        CREATE TABLE IF NOT EXISTS stone_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            stone TEXT NOT NULL,
+            action TEXT NOT NULL,
+            charges INTEGER,
+            date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, stone, action, date)
+        );
+
+        CREATE TABLE IF NOT EXISTS casino_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            game TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            amount INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, game, kind, amount, date)
+        );
+
+        CREATE TABLE IF NOT EXISTS unknown_lines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            message TEXT NOT NULL,
+            example TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            first_seen TEXT NOT NULL,
+            last_seen TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, message)
+        );
+
+        CREATE TABLE IF NOT EXISTS chat (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            speaker TEXT NOT NULL,
+            verb TEXT NOT NULL,
+            text TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_chat_speaker ON chat(speaker COLLATE NOCASE);
+
+        CREATE TABLE IF NOT EXISTS scan_errors (
+            file_path TEXT PRIMARY KEY,
+            line INTEGER,
+            kind TEXT NOT NULL,
+            message TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS thoughts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            correspondent TEXT NOT NULL,
+            direction TEXT NOT NULL,
+            day TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, correspondent, direction, day)
+        );
+
+        CREATE TABLE IF NOT EXISTS zone_visits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            area TEXT NOT NULL,
+            entered_at TEXT NOT NULL,
+            left_at TEXT NOT NULL,
+            events INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, area, entered_at)
+        );
+
+        CREATE TABLE IF NOT EXISTS kills_by_location (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            creature_name TEXT NOT NULL,
+            area TEXT NOT NULL,
+            solo_count INTEGER NOT NULL DEFAULT 0,
+            assisted_count INTEGER NOT NULL DEFAULT 0,
+            killed_by_count INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, creature_name, area)
+        );
+
+        CREATE TABLE IF NOT EXISTS deaths (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            cause TEXT NOT NULL,
+            area TEXT NOT NULL DEFAULT '',
+            date TEXT NOT NULL,
+            outcome TEXT NOT NULL DEFAULT 'unresolved',
+            resolved_at TEXT,
+            drags INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, cause, date)
+        );
+
+        CREATE TABLE IF NOT EXISTS scan_contributions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            file_path TEXT NOT NULL,
+            character_id INTEGER NOT NULL,
+            target TEXT NOT NULL,
+            key TEXT NOT NULL,
+            field TEXT NOT NULL,
+            amount INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(file_path, character_id, target, key, field)
+        );
+
+        CREATE TABLE IF NOT EXISTS assists (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            creature_name TEXT NOT NULL,
+            player_name TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            first_date TEXT NOT NULL,
+            last_date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, creature_name, player_name)
+        );
+
+        CREATE TABLE IF NOT EXISTS milestones (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            creature_name TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            threshold INTEGER NOT NULL,
+            achieved_at TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, creature_name, kind, threshold)
+        );
+
+        CREATE TABLE IF NOT EXISTS trainer_rank_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            trainer_name TEXT NOT NULL,
+            ranked_at TEXT NOT NULL,
+            source TEXT NOT NULL DEFAULT 'trainer',
+            ranks INTEGER NOT NULL DEFAULT 1,
+            log_file TEXT,
+            FOREIGN KEY (character_id) REFERENCES characters(id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_trainer_rank_history_lookup
+            ON trainer_rank_history (character_id, trainer_name, ranked_at);
+
+        CREATE TABLE IF NOT EXISTS character_meta (
+            character_id INTEGER PRIMARY KEY,
+            portrait BLOB,
+            portrait_mime TEXT,
+            portrait_source TEXT,
+            updated_at TEXT,
+            fighter_loadout TEXT,
+            FOREIGN KEY (character_id) REFERENCES characters(id)
+        );
+
+        CREATE TABLE IF NOT EXISTS clan_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            event_type TEXT NOT NULL,
+            value TEXT NOT NULL,
+            date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, event_type, value, date)
+        );
+
+        CREATE TABLE IF NOT EXISTS tournaments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            arena TEXT NOT NULL DEFAULT '',
+            opponent TEXT NOT NULL DEFAULT '',
+            date TEXT NOT NULL,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, kind, opponent, date)
+        );
+
+        CREATE TABLE IF NOT EXISTS snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            day TEXT NOT NULL,
+            taken_at TEXT NOT NULL,
+            logins INTEGER NOT NULL DEFAULT 0,
+            deaths INTEGER NOT NULL DEFAULT 0,
+            departs INTEGER NOT NULL DEFAULT 0,
+            solo_kills INTEGER NOT NULL DEFAULT 0,
+            assisted_kills INTEGER NOT NULL DEFAULT 0,
+            total_ranks INTEGER NOT NULL DEFAULT 0,
+            effective_ranks REAL NOT NULL DEFAULT 0,
+            coins_picked_up INTEGER NOT NULL DEFAULT 0,
+            esteem INTEGER NOT NULL DEFAULT 0,
+            coin_level INTEGER NOT NULL DEFAULT 0,
+            lastys_completed INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            UNIQUE(character_id, day)
+        );
+
         CREATE TABLE IF NOT EXISTS trainers (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             character_id INTEGER NOT NULL,
@@ -123,10 +439,43 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
             character_id INTEGER NOT NULL,
             pet_name TEXT NOT NULL,
             creature_name TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'active',
+            adopted_date TEXT,
+            death_count INTEGER NOT NULL DEFAULT 0,
+            last_death_date TEXT,
+            feed_count INTEGER NOT NULL DEFAULT 0,
+            last_fed_date TEXT,
+            released_date TEXT,
             FOREIGN KEY (character_id) REFERENCES characters(id),
             UNIQUE(character_id, pet_name)
         );
 
+        CREATE TABLE IF NOT EXISTS pet_ranks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            pet_id INTEGER NOT NULL,
+            ranks INTEGER NOT NULL DEFAULT 0,
+            last_rank_date TEXT,
+            trainings INTEGER NOT NULL DEFAULT 0,
+            last_training_date TEXT,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            FOREIGN KEY (pet_id) REFERENCES pets(id),
+            UNIQUE(pet_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS pet_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            character_id INTEGER NOT NULL,
+            pet_id INTEGER NOT NULL,
+            event TEXT NOT NULL,
+            date TEXT NOT NULL,
+            log_file TEXT NOT NULL DEFAULT '',
+            line INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (character_id) REFERENCES characters(id),
+            FOREIGN KEY (pet_id) REFERENCES pets(id),
+            UNIQUE(pet_id, event, date, log_file, line)
+        );
+
         CREATE TABLE IF NOT EXISTS log_files (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             character_id INTEGER NOT NULL,
@@ -144,6 +493,11 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
             tokenize='unicode61'
         );
 
+        CREATE TABLE IF NOT EXISTS app_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+
         CREATE TABLE IF NOT EXISTS process_logs (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             created_at TEXT NOT NULL,
@@ -206,6 +560,9 @@ pub fn migrate_tables(conn: &Connection) -> Result<()> {
         // daily logs that grow as the player keeps playing. Legacy rows default to 0
         // (length unknown) and are treated as fully-scanned until the next full rescan.
         "ALTER TABLE log_files ADD COLUMN byte_len INTEGER NOT NULL DEFAULT 0",
+        // The path a file was last scanned through, for reading it back: `file_path` is a
+        // matching key that root aliases may have rewritten. NULL on rows scanned before.
+        "ALTER TABLE log_files ADD COLUMN source_path TEXT",
         "ALTER TABLE characters ADD COLUMN coin_level_interim INTEGER NOT NULL DEFAULT 0",
         "ALTER TABLE characters ADD COLUMN ore_found INTEGER NOT NULL DEFAULT 0",
         "ALTER TABLE characters ADD COLUMN tin_ore_found INTEGER NOT NULL DEFAULT 0",
@@ -222,6 +579,21 @@ pub fn migrate_tables(conn: &Connection) -> Result<()> {
         // Marks rows inserted after the character-name filter was added.
         // Existing rows (recorded before the filter) default to 0 and are purged below.
         "ALTER TABLE trainer_checkpoints ADD COLUMN name_filtered INTEGER NOT NULL DEFAULT 0",
+        // Healer pet lifecycle (adoption, feeding, deaths, release)
+        "ALTER TABLE pets ADD COLUMN status TEXT NOT NULL DEFAULT 'active'",
+        "ALTER TABLE pets ADD COLUMN adopted_date TEXT",
+        "ALTER TABLE pets ADD COLUMN death_count INTEGER NOT NULL DEFAULT 0",
+        "ALTER TABLE pets ADD COLUMN last_death_date TEXT",
+        "ALTER TABLE pets ADD COLUMN feed_count INTEGER NOT NULL DEFAULT 0",
+        "ALTER TABLE pets ADD COLUMN last_fed_date TEXT",
+        "ALTER TABLE pets ADD COLUMN released_date TEXT",
+        "ALTER TABLE character_meta ADD COLUMN fighter_loadout TEXT",
+        "ALTER TABLE trainer_rank_history ADD COLUMN source TEXT NOT NULL DEFAULT 'trainer'",
+        "ALTER TABLE trainer_rank_history ADD COLUMN ranks INTEGER NOT NULL DEFAULT 1",
+        "ALTER TABLE trainer_rank_history ADD COLUMN log_file TEXT",
+        "ALTER TABLE trainers ADD COLUMN maxed INTEGER NOT NULL DEFAULT 0",
+        "ALTER TABLE deaths ADD COLUMN resolved_at TEXT",
+        "ALTER TABLE deaths ADD COLUMN drags INTEGER NOT NULL DEFAULT 0",
     ];
 
     for sql in &migrations {
@@ -315,6 +687,158 @@ pub fn migrate_tables(conn: &Connection) -> Result<()> {
             ON trainer_checkpoints (character_id, trainer_name, timestamp DESC, id DESC);",
     )?;
 
+    run_once(conn, DATES_NORMALIZED, normalize_stored_dates)?;
+    run_once(conn, LOG_HASHES_UPGRADED, rehash_log_files)?;
+    // After the rehash, which reads files through their stored (pre-alias) paths.
+    crate::log_paths::normalize_stored_log_paths(conn)?;
+
+    Ok(())
+}
+
+/// One-time data migrations are numbered and tracked in SQLite's `PRAGMA user_version`: a
+/// migration runs only while the stored version is below its number, in one transaction with
+/// the version bump, so it is skipped on every later open.
+const DATES_NORMALIZED: i64 = 1;
+const LOG_HASHES_UPGRADED: i64 = 2;
+
+fn run_once(conn: &Connection, version: i64, migration: fn(&Connection) -> Result<()>) -> Result<()> {
+    let current: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if current >= version {
+        return Ok(());
+    }
+    let tx = conn.unchecked_transaction()?;
+    migration(&tx)?;
+    tx.pragma_update(None, "user_version", version)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Replace the unstable 16-digit `DefaultHasher` hashes older versions stored in
+/// `log_files.content_hash` with the SHA-256 `hash_bytes` now produces. A row is re-hashed
+/// only when its file still holds the exact bytes that were scanned (the first `byte_len`
+/// bytes, or the whole file for legacy `byte_len = 0` rows) — checked against the legacy hash
+/// itself. Rows that can't be verified (file moved, unreadable, rewritten, or hashed by a
+/// different Rust release) keep their legacy hash: it never matches a duplicate, and a grown
+/// file is reported as changed rather than tail-scanned, until a Rescan Logs replaces the row.
+/// Legacy rows are the only 16-character hashes. Runs once per database (`LOG_HASHES_UPGRADED`).
+fn rehash_log_files(conn: &Connection) -> Result<()> {
+    let mut stmt = match conn
+        .prepare("SELECT rowid, file_path, byte_len, content_hash FROM log_files WHERE length(content_hash) = 16")
+    {
+        Ok(stmt) => stmt,
+        Err(_) => return Ok(()),
+    };
+    let legacy: Vec<(i64, String, i64, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (rowid, file_path, byte_len, old_hash) in legacy {
+        let Some(new_hash) = std::fs::read(&file_path)
+            .ok()
+            .and_then(|bytes| {
+                let scanned = if byte_len == 0 { bytes.len() } else { byte_len as usize };
+                bytes.get(..scanned).map(|prefix| prefix.to_vec())
+            })
+            .filter(|scanned| crate::parser::legacy_hash_bytes(scanned) == old_hash)
+            .map(|scanned| crate::parser::hash_bytes(&scanned))
+        else {
+            continue;
+        };
+        conn.execute(
+            "UPDATE log_files SET content_hash = ?1 WHERE rowid = ?2",
+            rusqlite::params![new_hash, rowid],
+        )?;
+    }
+    Ok(())
+}
+
+/// Every stored full-timestamp column, as `(table, column)`. `trainers.override_date` is
+/// deliberately absent: it holds the user's cutoff exactly as entered (CL `M/D/YY`) and is
+/// only ever compared through `crate::date::parse`. So are the hour and day bucket keys
+/// (`kill_hourly.hour`, `coin_daily.day`, `companions.day`, `thoughts.day`, `snapshots.day`),
+/// which are deliberately truncated.
+const DATE_COLUMNS: &[(&str, &str)] = &[
+    ("characters", "start_date"),
+    ("kills", "date_first"),
+    ("kills", "date_last"),
+    ("kills", "date_first_killed"),
+    ("kills", "date_first_slaughtered"),
+    ("kills", "date_first_vanquished"),
+    ("kills", "date_first_dispatched"),
+    ("kills", "date_last_killed"),
+    ("kills", "date_last_slaughtered"),
+    ("kills", "date_last_vanquished"),
+    ("kills", "date_last_dispatched"),
+    ("trainers", "date_of_last_rank"),
+    ("lastys", "first_seen_date"),
+    ("lastys", "last_seen_date"),
+    ("lastys", "completed_date"),
+    ("lastys", "abandoned_date"),
+    ("log_files", "date_read"),
+    ("process_logs", "created_at"),
+    ("trainer_checkpoints", "timestamp"),
+    ("sessions", "started_at"),
+    ("sessions", "ended_at"),
+    ("heals", "first_date"),
+    ("heals", "last_date"),
+    ("rescues", "first_date"),
+    ("rescues", "last_date"),
+    ("falls_witnessed", "first_date"),
+    ("falls_witnessed", "last_date"),
+    ("assists", "first_date"),
+    ("assists", "last_date"),
+    ("zone_visits", "entered_at"),
+    ("zone_visits", "left_at"),
+    ("deaths", "date"),
+    ("deaths", "resolved_at"),
+    ("circles", "date"),
+    ("clan_history", "date"),
+    ("tournaments", "date"),
+    ("milestones", "achieved_at"),
+    ("trainer_rank_history", "ranked_at"),
+    ("stone_events", "date"),
+    ("casino_events", "date"),
+    ("chat", "timestamp"),
+    ("scan_errors", "created_at"),
+    ("character_meta", "updated_at"),
+    ("snapshots", "taken_at"),
+    ("pets", "adopted_date"),
+    ("pets", "last_death_date"),
+    ("pets", "last_fed_date"),
+    ("pets", "released_date"),
+    ("pet_ranks", "last_rank_date"),
+    ("pet_ranks", "last_training_date"),
+    ("pet_events", "date"),
+    ("history_events", "date"),
+];
+
+/// Rewrite any stored date that isn't already canonical `"YYYY-MM-DD HH:MM:SS"` (e.g. the bare
+/// `"YYYY-MM-DD"` dates older Scribius imports wrote) via `crate::date::normalize`, so lexical
+/// MIN/MAX comparisons order correctly. Every writer stores the canonical form, so this runs
+/// once per database (`DATES_NORMALIZED`). Values that can't be parsed are left untouched;
+/// tables or columns that don't exist are skipped.
+fn normalize_stored_dates(conn: &Connection) -> Result<()> {
+    const CANONICAL: &str =
+        "[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]";
+    for (table, column) in DATE_COLUMNS {
+        let select = format!(
+            "SELECT rowid, {column} FROM {table} WHERE {column} IS NOT NULL AND {column} NOT GLOB '{CANONICAL}'"
+        );
+        let mut stmt = match conn.prepare(&select) {
+            Ok(stmt) => stmt,
+            Err(_) => continue,
+        };
+        let stale: Vec<(i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        let update = format!("UPDATE {table} SET {column} = ?1 WHERE rowid = ?2");
+        for (rowid, value) in stale {
+            if let Some(normalized) = crate::date::normalize(&value) {
+                conn.execute(&update, rusqlite::params![normalized, rowid])?;
+            }
+        }
+    }
     Ok(())
 }
 
@@ -464,4 +988,103 @@ mod tests {
         ).unwrap();
         assert_eq!(kept, 1, "Row with name_filtered=1 should still be present");
     }
+
+    #[test]
+    fn test_migrate_rehashes_legacy_log_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.txt");
+        let grown = dir.path().join("grown.txt");
+        std::fs::write(&kept, "Welcome to Clan Lord, Gandor!\n").unwrap();
+        std::fs::write(&grown, "first line\nsecond line\n").unwrap();
+        let kept_legacy = crate::parser::legacy_hash_bytes(&std::fs::read(&kept).unwrap());
+        let grown_legacy = crate::parser::legacy_hash_bytes(b"first line\n");
+
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        migrate_tables(&conn).unwrap();
+        conn.execute("INSERT INTO characters (id, name) VALUES (1, 'Gandor')", []).unwrap();
+        for (path, byte_len, hash) in [
+            (kept.to_string_lossy().to_string(), 0, kept_legacy),
+            (grown.to_string_lossy().to_string(), 11, grown_legacy),
+            ("missing.txt".to_string(), 10, "0123456789abcdef".to_string()),
+        ] {
+            conn.execute(
+                "INSERT INTO log_files (character_id, file_path, content_hash, byte_len, date_read)
+                 VALUES (1, ?1, ?2, ?3, '2024-01-01 00:00:00')",
+                rusqlite::params![path, hash, byte_len],
+            )
+            .unwrap();
+        }
+        conn.execute_batch("PRAGMA user_version = 0").unwrap();
+
+        migrate_tables(&conn).unwrap();
+
+        let hashes: Vec<String> = conn
+            .prepare("SELECT content_hash FROM log_files ORDER BY id")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(hashes[0], crate::parser::hash_bytes(&std::fs::read(&kept).unwrap()));
+        // The scanned prefix is re-hashed, so the next scan still tail-scans the growth.
+        assert_eq!(hashes[1], crate::parser::hash_bytes(b"first line\n"));
+        // The file can't be read, so the legacy hash is kept rather than lost.
+        assert_eq!(hashes[2], "0123456789abcdef");
+    }
+
+    #[test]
+    fn test_migrate_normalizes_mixed_date_shapes() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        migrate_tables(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO characters (id, name, start_date) VALUES (1, 'Gandor', '2024-01-15');
+             INSERT INTO kills (character_id, creature_name, date_first, date_last)
+                VALUES (1, 'Rat', '2024-01-15', '2024-01-16T08:30:00');
+             INSERT INTO trainers (character_id, trainer_name, override_date)
+                VALUES (1, 'Histia', '1/15/25');
+             INSERT INTO sessions (character_id, log_file, started_at, ended_at)
+                VALUES (1, 'a.txt', '2024-01-15', '2024-01-15 09:00:00');
+             PRAGMA user_version = 0;",
+        )
+        .unwrap();
+
+        migrate_tables(&conn).unwrap();
+
+        let start: String = conn
+            .query_row("SELECT start_date FROM characters WHERE id = 1", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(start, "2024-01-15 00:00:00");
+        let (first, last): (String, String) = conn
+            .query_row("SELECT date_first, date_last FROM kills", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!(first, "2024-01-15 00:00:00");
+        assert_eq!(last, "2024-01-16 08:30:00");
+        // The user-entered override cutoff is left exactly as typed.
+        let cutoff: String = conn
+            .query_row("SELECT override_date FROM trainers", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(cutoff, "1/15/25");
+        let started: String = conn.query_row("SELECT started_at FROM sessions", [], |r| r.get(0)).unwrap();
+        assert_eq!(started, "2024-01-15 00:00:00");
+    }
+
+    #[test]
+    fn test_date_normalization_runs_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        migrate_tables(&conn).unwrap();
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0)).unwrap();
+        assert_eq!(version, LOG_HASHES_UPGRADED);
+
+        // Rows written after the one-time pass are not rewritten by later opens.
+        conn.execute("INSERT INTO characters (id, name, start_date) VALUES (1, 'Gandor', '2024-01-15')", [])
+            .unwrap();
+        migrate_tables(&conn).unwrap();
+        let start: String = conn
+            .query_row("SELECT start_date FROM characters WHERE id = 1", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(start, "2024-01-15");
+    }
 }
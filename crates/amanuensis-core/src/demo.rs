@@ -0,0 +1,102 @@
+//! Demo dataset.
+//!
+//! Bundles the anonymized sample logs from `tests/fixtures/golden_logs` (three characters,
+//! one per client variant) so new users can try every view before pointing Amanuensis at
+//! their own logs. `build_demo` writes them into a scratch directory and scans them into a
+//! fresh database there, exactly like a normal first scan.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::parser::{LogParser, ScanResult};
+
+/// Bundled sample logs: (character folder, file name, raw bytes). The bytes are kept
+/// as-is, since they exercise the Mac Roman / CRLF / CR decoding paths.
+pub const DEMO_LOGS: [(&str, &str, &[u8]); 3] = [
+    (
+        "Ashwin",
+        "CL Log 2024-03-01 18.00.00.txt",
+        include_bytes!("../tests/fixtures/golden_logs/Ashwin/CL Log 2024-03-01 18.00.00.txt"),
+    ),
+    (
+        "Bramble",
+        "CL Log 2024-03-01 09.00.00.txt",
+        include_bytes!("../tests/fixtures/golden_logs/Bramble/CL Log 2024-03-01 09.00.00.txt"),
+    ),
+    (
+        "Corvid",
+        "CL Log 2024-03-02 23.30.00.txt",
+        include_bytes!("../tests/fixtures/golden_logs/Corvid/CL Log 2024-03-02 23.30.00.txt"),
+    ),
+];
+
+/// The character with the most going on in the sample logs, for suggested commands.
+pub const DEMO_CHARACTER: &str = "Ashwin";
+
+/// A freshly built demo database.
+#[derive(Debug, Clone, Serialize)]
+pub struct DemoSetup {
+    pub db_path: PathBuf,
+    pub log_folder: PathBuf,
+    pub characters: Vec<String>,
+    pub files_scanned: usize,
+    pub events_found: usize,
+}
+
+/// Default scratch directory for the demo: `amanuensis-demo` in the system temp directory.
+pub fn default_demo_dir() -> PathBuf {
+    std::env::temp_dir().join("amanuensis-demo")
+}
+
+/// Write the sample logs to `<dir>/logs` and scan them into `<dir>/demo.db`, replacing
+/// whatever an earlier demo left there.
+pub fn build_demo(dir: &Path) -> Result<DemoSetup> {
+    let log_folder = dir.join("logs");
+    let db_path = dir.join("demo.db");
+    if log_folder.exists() {
+        std::fs::remove_dir_all(&log_folder)?;
+    }
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = db_path.as_os_str().to_owned();
+        file.push(suffix);
+        let file = PathBuf::from(file);
+        if file.exists() {
+            std::fs::remove_file(file)?;
+        }
+    }
+    for (character, name, bytes) in DEMO_LOGS {
+        let folder = log_folder.join(character);
+        std::fs::create_dir_all(&folder)?;
+        std::fs::write(folder.join(name), bytes)?;
+    }
+
+    let parser = LogParser::new(Database::open(&db_path.to_string_lossy())?)?;
+    let ScanResult { files_scanned, events_found, .. } = parser.scan_folder(&log_folder, false)?;
+    parser.finalize_characters()?;
+    let characters = parser.db().list_characters()?.into_iter().map(|c| c.name).collect();
+    Ok(DemoSetup { db_path, log_folder, characters, files_scanned, events_found })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demo_builds_and_rebuilds_from_scratch() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = build_demo(dir.path()).unwrap();
+        assert_eq!(first.files_scanned, DEMO_LOGS.len());
+        assert!(first.characters.iter().any(|c| c == DEMO_CHARACTER));
+
+        // A second run starts over instead of skipping already-scanned files
+        let second = build_demo(dir.path()).unwrap();
+        assert_eq!(second.files_scanned, DEMO_LOGS.len());
+        assert_eq!(second.characters, first.characters);
+        let db = Database::open(&second.db_path.to_string_lossy()).unwrap();
+        let ashwin = db.get_character(DEMO_CHARACTER).unwrap().unwrap();
+        assert!(!db.get_kills(ashwin.id.unwrap()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,278 @@
+//! Diagnostics bundle for bug reports.
+//!
+//! "It didn't count my ranks" is hard to act on without seeing what the scanner saw. A
+//! [`Diagnostics`] snapshot gathers the warnings and errors from the last scan, a sample of
+//! indexed lines the classifier did not recognize, the schema and settings, and table sizes;
+//! [`Diagnostics::write_zip`] packs it into a zip a user can attach to a report.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use serde::Serialize;
+use sha2::{Digest as _, Sha256};
+
+use crate::data::TrainerDb;
+use crate::db::queries::SETTING_DIGEST_WEBHOOK;
+use crate::db::Database;
+use crate::error::{AmanuensisError, Result};
+use crate::models::ProcessLog;
+use crate::parser::events::LogEvent;
+use crate::parser::line_classifier::classify_line;
+use crate::parser::patterns;
+use crate::parser::timestamp::parse_timestamp;
+
+/// How many of the most recently indexed lines are classified when sampling unknown lines.
+pub const UNKNOWN_LINE_SCAN_WINDOW: i64 = 20_000;
+
+/// Settings whose values are replaced with `<redacted>` (they may hold credentials).
+const REDACTED_SETTINGS: &[&str] = &[SETTING_DIGEST_WEBHOOK];
+
+/// Tables whose row counts are reported.
+const COUNTED_TABLES: &[&str] =
+    &["characters", "log_files", "log_lines", "kills", "trainers", "trainer_checkpoints", "lastys", "pets"];
+
+/// An indexed line that is not speech or an emote and matched no pattern.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UnknownLine {
+    pub timestamp: String,
+    pub file_path: String,
+    pub content: String,
+}
+
+/// Everything in a diagnostics bundle.
+#[derive(Debug, Serialize)]
+pub struct Diagnostics {
+    pub generated_at: String,
+    /// Versions, platform, and anything the caller adds (app version, database path, ...).
+    pub environment: BTreeMap<String, String>,
+    /// Short hash of the database schema; equal hashes mean identical tables and indexes.
+    pub schema_version: String,
+    pub settings: BTreeMap<String, String>,
+    pub table_counts: BTreeMap<String, i64>,
+    /// Warnings and errors from the last scan, newest first.
+    pub scan_errors: Vec<ProcessLog>,
+    /// Most recent first, one per distinct message.
+    pub unknown_lines: Vec<UnknownLine>,
+}
+
+impl Database {
+    /// Warnings and errors from the last scan, newest first.
+    pub fn get_recent_errors(&self, limit: usize) -> Result<Vec<ProcessLog>> {
+        Ok(self
+            .get_process_logs()?
+            .into_iter()
+            .filter(|l| l.level == "warn" || l.level == "error")
+            .take(limit)
+            .collect())
+    }
+
+    /// Hash of every table, index and trigger definition, as 12 hex digits.
+    pub fn schema_version(&self) -> Result<String> {
+        let mut stmt = self
+            .conn()
+            .prepare("SELECT sql FROM sqlite_master WHERE sql IS NOT NULL ORDER BY type, name")?;
+        let mut hasher = Sha256::new();
+        for sql in stmt.query_map([], |row| row.get::<_, String>(0))?.filter_map(|r| r.ok()) {
+            hasher.update(sql.as_bytes());
+            hasher.update(b"\n");
+        }
+        Ok(hasher.finalize().iter().take(6).map(|b| format!("{b:02x}")).collect())
+    }
+
+    /// Up to `limit` distinct indexed lines, newest first, that classify as nothing and are
+    /// not speech or emotes. Only the last [`UNKNOWN_LINE_SCAN_WINDOW`] lines are examined;
+    /// an empty search index yields no samples.
+    pub fn unknown_line_samples(&self, trainer_db: &TrainerDb, limit: usize) -> Result<Vec<UnknownLine>> {
+        let mut stmt = self.conn().prepare(
+            "SELECT content, timestamp, file_path FROM log_lines ORDER BY rowid DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([UNKNOWN_LINE_SCAN_WINDOW], |row| {
+            Ok(UnknownLine { content: row.get(0)?, timestamp: row.get(1)?, file_path: row.get(2)? })
+        })?;
+        let mut seen = std::collections::HashSet::new();
+        let mut samples = Vec::new();
+        for line in rows.filter_map(|r| r.ok()) {
+            if samples.len() >= limit {
+                break;
+            }
+            let message = parse_timestamp(&line.content).map(|(_, m)| m).unwrap_or(&line.content).trim();
+            if message.is_empty()
+                || patterns::SPEECH.is_match(message)
+                || patterns::EMOTE.is_match(message)
+                || !matches!(classify_line(message, trainer_db), LogEvent::Ignored)
+            {
+                continue;
+            }
+            if seen.insert(message.to_string()) {
+                samples.push(line);
+            }
+        }
+        Ok(samples)
+    }
+
+    /// Gather a diagnostics snapshot with up to `sample_limit` unknown lines.
+    pub fn diagnostics(&self, trainer_db: &TrainerDb, sample_limit: usize) -> Result<Diagnostics> {
+        let mut environment = BTreeMap::new();
+        environment.insert("core_version".to_string(), env!("CARGO_PKG_VERSION").to_string());
+        environment.insert("os".to_string(), std::env::consts::OS.to_string());
+        environment.insert("arch".to_string(), std::env::consts::ARCH.to_string());
+        environment.insert("sqlite_version".to_string(), rusqlite::version().to_string());
+
+        let mut settings = BTreeMap::new();
+        let mut stmt = self.conn().prepare("SELECT key, value FROM app_settings ORDER BY key")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        for (key, value) in rows.filter_map(|r| r.ok()) {
+            let value = if REDACTED_SETTINGS.contains(&key.as_str()) { "<redacted>".to_string() } else { value };
+            settings.insert(key, value);
+        }
+
+        let mut table_counts = BTreeMap::new();
+        for table in COUNTED_TABLES {
+            let count: i64 = self.conn().query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0))?;
+            table_counts.insert(table.to_string(), count);
+        }
+
+        Ok(Diagnostics {
+            generated_at: crate::date::now(),
+            environment,
+            schema_version: self.schema_version()?,
+            settings,
+            table_counts,
+            scan_errors: self.get_recent_errors(usize::MAX)?,
+            unknown_lines: self.unknown_line_samples(trainer_db, sample_limit)?,
+        })
+    }
+}
+
+impl Diagnostics {
+    /// Write the bundle as a zip at `dest`: `diagnostics.json` with everything, plus
+    /// `scan_errors.txt` and `unknown_lines.txt` for reading at a glance.
+    pub fn write_zip(&self, dest: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| AmanuensisError::Data(format!("Could not serialize diagnostics: {e}")))?;
+        let errors: String = self
+            .scan_errors
+            .iter()
+            .map(|l| format!("[{}] {}  {}\n", l.level.to_uppercase(), l.created_at, l.message))
+            .collect();
+        let unknown: String = self
+            .unknown_lines
+            .iter()
+            .map(|l| format!("{}\t{}\t{}\n", l.timestamp, l.file_path, l.content))
+            .collect();
+        let file = std::fs::File::create(dest)?;
+        write_stored_zip(
+            std::io::BufWriter::new(file),
+            &[
+                ("diagnostics.json", json.as_bytes()),
+                ("scan_errors.txt", errors.as_bytes()),
+                ("unknown_lines.txt", unknown.as_bytes()),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+/// Write `files` as an uncompressed (stored) zip archive. The bundle is small text, so
+/// compression is not worth a dependency.
+fn write_stored_zip<W: Write>(mut out: W, files: &[(&str, &[u8])]) -> std::io::Result<()> {
+    // DOS date 1980-01-01 00:00; bit 11 marks UTF-8 names.
+    const DOS_DATE: u16 = (1 << 5) | 1;
+    const FLAGS: u16 = 1 << 11;
+    let mut central = Vec::new();
+    let mut offset = 0u32;
+    for (name, data) in files {
+        let crc = crc32(data);
+        let mut header = Vec::new();
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header.extend_from_slice(&20u16.to_le_bytes());
+        header.extend_from_slice(&FLAGS.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // stored
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(name.as_bytes());
+        out.write_all(&header)?;
+        out.write_all(data)?;
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes());
+        central.extend_from_slice(&header[4..30]);
+        central.extend_from_slice(&[0; 10]); // comment length, disk, attributes
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+        offset += (header.len() + data.len()) as u32;
+    }
+    out.write_all(&central)?;
+    out.write_all(&0x0605_4b50u32.to_le_bytes())?;
+    out.write_all(&[0; 4])?; // disk numbers
+    out.write_all(&(files.len() as u16).to_le_bytes())?;
+    out.write_all(&(files.len() as u16).to_le_bytes())?;
+    out.write_all(&(central.len() as u32).to_le_bytes())?;
+    out.write_all(&offset.to_le_bytes())?;
+    out.write_all(&0u16.to_le_bytes())?;
+    out.flush()
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics_bundle_samples_unknown_lines_and_zips() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Fen").unwrap();
+        db.insert_log_lines(&[
+            (c, "1/1/24 10:00:00a You slaughtered a Rat.", "2024-01-01 10:00:00", "a.txt"),
+            (c, "1/1/24 10:01:00a Fen says, \"hello\"", "2024-01-01 10:01:00", "a.txt"),
+            (c, "1/1/24 10:02:00a The sky flickers strangely.", "2024-01-01 10:02:00", "a.txt"),
+            (c, "1/1/24 10:03:00a The sky flickers strangely.", "2024-01-01 10:03:00", "a.txt"),
+        ])
+        .unwrap();
+        db.add_process_log("info", "scanned 1 file").unwrap();
+        db.add_process_log("warn", "skipped: could not determine character (x.txt)").unwrap();
+        db.set_setting(SETTING_DIGEST_WEBHOOK, "https://example.com/secret").unwrap();
+
+        let diagnostics = db.diagnostics(&TrainerDb::bundled().unwrap(), 10).unwrap();
+        let unknown: Vec<&str> = diagnostics.unknown_lines.iter().map(|l| l.timestamp.as_str()).collect();
+        assert_eq!(unknown, vec!["2024-01-01 10:03:00"]);
+        assert_eq!(diagnostics.scan_errors.len(), 1);
+        assert_eq!(diagnostics.settings[SETTING_DIGEST_WEBHOOK], "<redacted>");
+        assert_eq!(diagnostics.table_counts["log_lines"], 4);
+        assert_eq!(diagnostics.schema_version.len(), 12);
+        assert_eq!(diagnostics.schema_version, Database::open_in_memory().unwrap().schema_version().unwrap());
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("diagnostics.zip");
+        diagnostics.write_zip(&dest).unwrap();
+        let bytes = std::fs::read(&dest).unwrap();
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+        let eocd = &bytes[bytes.len() - 22..];
+        assert_eq!(&eocd[..4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 3);
+        let central = u32::from_le_bytes([eocd[16], eocd[17], eocd[18], eocd[19]]) as usize;
+        assert_eq!(&bytes[central..central + 4], b"PK\x01\x02");
+        assert!(bytes.windows(17).any(|w| w == b"unknown_lines.txt"));
+    }
+
+    #[test]
+    fn crc32_matches_reference() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,311 @@
+//! Activity digest across all characters.
+//!
+//! What every visible character did since a point in time: ranks per trainer (from
+//! `trainer_rank_history`), kills, deaths and coins (from the sessions that started since),
+//! creatures killed for the first time, kill milestones, and lastys studied. Meant to be
+//! produced by a scheduled job after a scheduled scan. `SETTING_LAST_DIGEST` remembers the scan
+//! position the previous digest covered (the latest `log_files.date_read`), so the next one
+//! covers the files read since, whatever their log dates; the period itself is in log time.
+
+use std::fmt::Write;
+
+use chrono::Duration;
+use serde::Serialize;
+
+use crate::date::Timestamp;
+use crate::db::queries::{Milestone, SETTING_LAST_DIGEST};
+use crate::db::Database;
+use crate::error::Result;
+
+/// Days covered by the first `since last` digest, before one has been recorded.
+pub const FIRST_DIGEST_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DigestLasty {
+    pub creature: String,
+    pub lasty_type: String,
+    pub finished: bool,
+    /// Lasty messages seen in total (not only in the period).
+    pub messages: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CharacterDigest {
+    pub name: String,
+    /// (trainer, ranks), most ranks first.
+    pub ranks: Vec<(String, i64)>,
+    pub sessions: i64,
+    /// Solo and assisted kills in those sessions.
+    pub kills: i64,
+    pub deaths: i64,
+    pub coins: i64,
+    /// Creatures killed for the first time, alphabetical.
+    pub first_kills: Vec<String>,
+    pub milestones: Vec<Milestone>,
+    /// Lastys with a message in the period.
+    pub lastys: Vec<DigestLasty>,
+}
+
+impl CharacterDigest {
+    pub fn total_ranks(&self) -> i64 {
+        self.ranks.iter().map(|(_, n)| n).sum()
+    }
+
+    /// Nothing happened in the period.
+    pub fn is_empty(&self) -> bool {
+        self.ranks.is_empty()
+            && self.sessions == 0
+            && self.first_kills.is_empty()
+            && self.milestones.is_empty()
+            && self.lastys.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Digest {
+    /// Start of the period (storage date); None for all time.
+    pub since: Option<String>,
+    pub until: String,
+    /// Characters with activity in the period, alphabetical.
+    pub characters: Vec<CharacterDigest>,
+}
+
+impl Digest {
+    /// Compact Markdown rendering, one section per active character.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Amanuensis digest");
+        let _ = writeln!(out);
+        match &self.since {
+            Some(since) => {
+                let _ = writeln!(out, "{} to {}", since, self.until);
+            }
+            None => {
+                let _ = writeln!(out, "Everything up to {}", self.until);
+            }
+        }
+        if self.characters.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "No new activity.");
+        }
+        for c in &self.characters {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## {}", c.name);
+            if !c.ranks.is_empty() {
+                let trainers: Vec<String> = c.ranks.iter().map(|(t, n)| format!("{t} {n}")).collect();
+                let _ = writeln!(out, "- {} ranks: {}", c.total_ranks(), trainers.join(", "));
+            }
+            if c.sessions > 0 {
+                let _ = writeln!(
+                    out,
+                    "- {} kills, {} deaths, {} coins in {} session{}",
+                    c.kills,
+                    c.deaths,
+                    c.coins,
+                    c.sessions,
+                    if c.sessions == 1 { "" } else { "s" }
+                );
+            }
+            if !c.first_kills.is_empty() {
+                let _ = writeln!(out, "- First kills: {}", c.first_kills.join(", "));
+            }
+            for m in &c.milestones {
+                let _ = writeln!(out, "- {}: {} ({})", m.description(), m.creature, m.achieved_at);
+            }
+            for l in &c.lastys {
+                let state = if l.finished { "finished".to_string() } else { format!("{} messages", l.messages) };
+                let _ = writeln!(out, "- Lasty: {} {} ({})", l.creature, l.lasty_type, state);
+            }
+        }
+        out
+    }
+}
+
+impl Database {
+    /// Digest of what the files read since the previous recorded digest hold, and the scan
+    /// position to pass to `record_digest` once it's delivered.
+    ///
+    /// The period runs from the first session of those files to the latest log time, so a
+    /// backlog of old logs scanned late is still covered. A file that grew is covered from its
+    /// first session again. The first digest covers the `FIRST_DIGEST_DAYS` of log time before
+    /// the latest session.
+    pub fn digest_since_last(&self) -> Result<(Digest, String)> {
+        let conn = self.conn();
+        let position: Option<String> = conn.query_row("SELECT MAX(date_read) FROM log_files", [], |r| r.get(0))?;
+        let position = position.unwrap_or_default();
+        let latest = self.latest_log_time()?;
+        let since = match (latest, self.get_setting(SETTING_LAST_DIGEST)?) {
+            (None, _) => None,
+            (Some(latest), None) => Some(Timestamp(latest.0 - Duration::days(FIRST_DIGEST_DAYS))),
+            (Some(_), Some(marker)) => conn.query_row(
+                "SELECT MIN(s.started_at) FROM sessions s JOIN log_files f ON f.file_path = s.log_file
+                 WHERE f.date_read > ?1",
+                [&marker],
+                |r| r.get(0),
+            )?,
+        };
+        let until = latest.map(|t| t.to_string()).unwrap_or_default();
+        let Some(since) = since else {
+            // Nothing has been read since the previous digest.
+            let empty = Digest {
+                since: Some(until.clone()),
+                until,
+                characters: Vec::new(),
+            };
+            return Ok((empty, position));
+        };
+        let since = since.to_string();
+        Ok((self.digest(Some(&since), &until)?, position))
+    }
+
+    /// When the latest session ended, in log time: the `until` of a digest. None before any
+    /// scan.
+    pub fn latest_log_time(&self) -> Result<Option<Timestamp>> {
+        Ok(self.conn().query_row("SELECT MAX(ended_at) FROM sessions", [], |r| r.get(0))?)
+    }
+
+    /// Remember that a digest covering the files read up to scan `position` was delivered, so
+    /// the next `since last` digest starts after them.
+    pub fn record_digest(&self, position: &str) -> Result<()> {
+        self.set_setting(SETTING_LAST_DIGEST, position)
+    }
+
+    /// Digest of every visible character's activity from `since` (inclusive; None for all
+    /// time) up to `until`. Characters without activity are left out.
+    pub fn digest(&self, since: Option<&str>, until: &str) -> Result<Digest> {
+        let start = since.map(str::parse::<Timestamp>).transpose()?;
+        let in_period = |d: Option<Timestamp>| d.is_some_and(|d| start.is_none_or(|s| d >= s));
+        let mut characters = Vec::new();
+        for listed in self.list_characters()? {
+            let Some(id) = listed.id else { continue };
+            let mut c = CharacterDigest {
+                name: listed.name.clone(),
+                ranks: self.get_ranks_since_merged(id, since)?,
+                ..Default::default()
+            };
+            for s in self.get_sessions_merged(id)? {
+                if in_period(Some(s.started_at)) {
+                    c.sessions += 1;
+                    c.kills += s.kills;
+                    c.deaths += s.deaths;
+                    c.coins += s.coins;
+                }
+            }
+            c.first_kills = self
+                .get_kills_merged(id)?
+                .into_iter()
+                .filter(|k| k.total_all() > 0 && in_period(k.date_first))
+                .map(|k| k.creature_name)
+                .collect();
+            c.first_kills.sort_by_cached_key(|name| name.to_lowercase());
+            c.milestones = self
+                .get_milestones_merged(id)?
+                .into_iter()
+                .filter(|m| in_period(Some(m.achieved_at)))
+                .collect();
+            c.lastys = self
+                .get_lastys_merged(id)?
+                .into_iter()
+                .filter(|l| in_period(l.last_seen_date))
+                .map(|l| DigestLasty {
+                    creature: l.creature_name,
+                    lasty_type: l.lasty_type,
+                    finished: l.finished,
+                    messages: l.message_count,
+                })
+                .collect();
+            if !c.is_empty() {
+                characters.push(c);
+            }
+        }
+        characters.sort_by_cached_key(|c| c.name.to_lowercase());
+        Ok(Digest {
+            since: since.map(str::to_string),
+            until: until.to_string(),
+            characters,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Session;
+
+    #[test]
+    fn digest_covers_only_the_period() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        let idle = db.get_or_create_character("Idle").unwrap();
+        for id in [c, idle] {
+            db.increment_character_field(id, "logins", 1).unwrap();
+        }
+
+        db.upsert_trainer_rank(c, "Histia", "2024-02-20 10:00:00", 1.0).unwrap();
+        db.upsert_trainer_rank(c, "Histia", "2024-03-02 10:00:00", 1.0).unwrap();
+        db.upsert_trainer_rank(alt, "Detha", "2024-03-03 10:00:00", 1.0).unwrap();
+        db.upsert_kill(c, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(c, "Orga", "killed_count", 40, "2024-03-04 10:00:00").unwrap();
+        db.upsert_lasty(c, "Ramandu", "Movements", "2024-03-05 10:00:00").unwrap();
+        let mut session = Session::new(c, "a.txt", "2024-03-04 09:00:00".parse().unwrap());
+        session.kills = 12;
+        session.deaths = 1;
+        db.upsert_session(&session, false).unwrap();
+        db.upsert_session(&Session::new(c, "b.txt", "2024-02-01 09:00:00".parse().unwrap()), false).unwrap();
+        db.merge_characters(&[alt], c).unwrap();
+
+        let digest = db.digest(Some("2024-03-01 00:00:00"), "2024-03-08 00:00:00").unwrap();
+        assert_eq!(digest.characters.len(), 1);
+        let g = &digest.characters[0];
+        assert_eq!(g.ranks, vec![("Detha".to_string(), 1), ("Histia".to_string(), 1)]);
+        assert_eq!((g.sessions, g.kills, g.deaths), (1, 12, 1));
+        assert_eq!(g.first_kills, vec!["Orga"]);
+        assert_eq!(g.lastys[0].creature, "Ramandu");
+
+        let md = digest.to_markdown();
+        assert!(md.contains("## Gandor"));
+        assert!(md.contains("- 2 ranks: Detha 1, Histia 1"));
+        assert!(md.contains("- First kills: Orga"));
+
+        let all = db.digest(None, "2024-03-08 00:00:00").unwrap();
+        assert_eq!(all.characters[0].total_ranks(), 3);
+        assert_eq!(all.characters[0].first_kills, vec!["Orga", "Rat"]);
+
+        let quiet = db.digest(Some("2024-04-01 00:00:00"), "2024-04-08 00:00:00").unwrap();
+        assert!(quiet.characters.is_empty());
+        assert!(quiet.to_markdown().contains("No new activity."));
+    }
+
+    #[test]
+    fn since_last_covers_files_read_after_the_previous_digest_whatever_their_log_dates() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.increment_character_field(c, "logins", 1).unwrap();
+        let scan = |file: &str, started: &str, kills: i64, read: &str| {
+            let mut session = Session::new(c, file, started.parse().unwrap());
+            session.kills = kills;
+            db.upsert_session(&session, false).unwrap();
+            db.mark_log_scanned(c, file, file, 1, read).unwrap();
+        };
+        scan("old.txt", "2024-01-01 10:00:00", 1, "2024-03-01 00:00:00");
+        scan("recent.txt", "2024-03-05 10:00:00", 2, "2024-03-06 00:00:00");
+
+        // The first digest covers the week of log time before the latest session.
+        let (first, position) = db.digest_since_last().unwrap();
+        assert_eq!(first.since.as_deref(), Some("2024-02-27 10:00:00"));
+        assert_eq!(first.characters[0].kills, 2);
+        db.record_digest(&position).unwrap();
+
+        let (quiet, _) = db.digest_since_last().unwrap();
+        assert!(quiet.characters.is_empty());
+
+        // A backlog scanned afterwards is covered even though its log dates are older.
+        scan("backlog.txt", "2024-02-01 10:00:00", 4, "2024-03-07 00:00:00");
+        let (late, position) = db.digest_since_last().unwrap();
+        assert_eq!(late.since.as_deref(), Some("2024-02-01 10:00:00"));
+        assert_eq!(late.until, "2024-03-05 10:00:00");
+        assert_eq!(late.characters[0].kills, 6);
+        assert_eq!(position, "2024-03-07 00:00:00");
+    }
+}
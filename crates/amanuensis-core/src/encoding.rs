@@ -1,3 +1,5 @@
+use std::io::{self, BufRead};
+
 use encoding_rs::WINDOWS_1252;
 
 /// Remap Mac Roman bytes to their W1252 equivalents so that W1252 decoding yields
@@ -112,16 +114,73 @@ pub fn decode_log_bytes(bytes: &[u8]) -> String {
         if !result.is_empty() {
             result.push('\n');
         }
-        match std::str::from_utf8(line) {
-            Ok(s) => result.push_str(s),
-            Err(_) => {
-                let patched = patch_mac_roman_bytes(line);
-                let (cow, _, _) = WINDOWS_1252.decode(&patched);
-                result.push_str(&cow);
+        result.push_str(&decode_log_line(line));
+    }
+    result
+}
+
+/// Decode one line (without its line ending) the way [`decode_log_bytes`] decodes each line
+/// of a mixed-encoding file: UTF-8 when valid, otherwise W1252 with the Mac Roman patch.
+pub fn decode_log_line(line: &[u8]) -> String {
+    match std::str::from_utf8(line) {
+        Ok(s) => s.to_string(),
+        Err(_) => {
+            let patched = patch_mac_roman_bytes(line);
+            let (cow, _, _) = WINDOWS_1252.decode(&patched);
+            cow.into_owned()
+        }
+    }
+}
+
+/// The lines of a log read incrementally, split and decoded as [`decode_log_bytes`] followed
+/// by `str::lines` would (CR, LF or CRLF endings), but holding only one line at a time.
+pub struct LogLineReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    /// The last line ended with CR, so a LF right after it belongs to that ending.
+    after_cr: bool,
+}
+
+impl<R: BufRead> LogLineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader, line: Vec::new(), after_cr: false }
+    }
+}
+
+impl<R: BufRead> Iterator for LogLineReader<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.line.clear();
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            if available.is_empty() {
+                // End of input: an unterminated last line, or nothing more.
+                return (!self.line.is_empty()).then(|| Ok(decode_log_line(&self.line)));
+            }
+            if std::mem::take(&mut self.after_cr) && available[0] == b'\n' {
+                self.reader.consume(1);
+                continue;
+            }
+            match available.iter().position(|&b| b == b'\n' || b == b'\r') {
+                Some(end) => {
+                    self.after_cr = available[end] == b'\r';
+                    self.line.extend_from_slice(&available[..end]);
+                    self.reader.consume(end + 1);
+                    return Some(Ok(decode_log_line(&self.line)));
+                }
+                None => {
+                    let n = available.len();
+                    self.line.extend_from_slice(available);
+                    self.reader.consume(n);
+                }
             }
         }
     }
-    result
 }
 
 #[cfg(test)]
@@ -144,6 +203,19 @@ mod tests {
         assert_eq!(result, "¥Hello");
     }
 
+    #[test]
+    fn line_reader_matches_whole_file_decoding() {
+        let mut bytes = b"a\r\nb\rc\n\n".to_vec();
+        bytes.extend_from_slice(&[0xA5, b'd', b'\r']);
+        bytes.extend_from_slice("\u{2022}e".as_bytes());
+        let whole: Vec<String> = decode_log_bytes(&bytes).lines().map(String::from).collect();
+        // A tiny buffer splits lines (and the CRLF) across reads.
+        let streamed: Vec<String> =
+            LogLineReader::new(io::BufReader::with_capacity(2, &bytes[..])).map(|l| l.unwrap()).collect();
+        assert_eq!(streamed, whole);
+        assert_eq!(streamed, ["a", "b", "c", "", "¥d", "\u{2022}e"]);
+    }
+
     #[test]
     fn test_pure_ascii() {
         let input = b"You slaughtered a Rat.";
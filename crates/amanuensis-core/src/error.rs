@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -16,6 +18,72 @@ pub enum AmanuensisError {
 
     #[error("Data error: {0}")]
     Data(String),
+
+    /// No character with this name (or id, for id-based lookups) exists.
+    #[error("Character '{name}' not found")]
+    CharacterNotFound { name: String },
+
+    /// A file the operation needs could not be read.
+    #[error("Cannot read {}: {source}", path.display())]
+    FileUnreadable {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A database file is not in the expected format (not an Amanuensis or Scribius database).
+    #[error("Schema mismatch: {detail}")]
+    SchemaMismatch { detail: String },
+
+    /// The full-text log index cannot be queried.
+    #[error("Full-text search unavailable: {reason}")]
+    FtsUnavailable { reason: String },
+
+    /// A scan was stopped through its cancel flag; its transaction was rolled back.
+    #[error("Scan cancelled")]
+    Cancelled,
+}
+
+impl AmanuensisError {
+    /// Stable machine-readable kind, for callers (GUI, scripts) that branch on the error
+    /// rather than display it.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AmanuensisError::Io(_) => "io",
+            AmanuensisError::Database(_) => "database",
+            AmanuensisError::Json(_) => "json",
+            AmanuensisError::Parse(_) => "parse",
+            AmanuensisError::Data(_) => "data",
+            AmanuensisError::CharacterNotFound { .. } => "character_not_found",
+            AmanuensisError::FileUnreadable { .. } => "file_unreadable",
+            AmanuensisError::SchemaMismatch { .. } => "schema_mismatch",
+            AmanuensisError::FtsUnavailable { .. } => "fts_unavailable",
+            AmanuensisError::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn file_unreadable(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        AmanuensisError::FileUnreadable { path: path.into(), source }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, AmanuensisError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structured_errors_keep_context() {
+        let e = AmanuensisError::file_unreadable(
+            "/logs/CL Log 2024-01-01.txt",
+            std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied"),
+        );
+        assert_eq!(e.kind(), "file_unreadable");
+        assert_eq!(e.to_string(), "Cannot read /logs/CL Log 2024-01-01.txt: denied");
+        assert!(std::error::Error::source(&e).is_some());
+
+        let e = AmanuensisError::CharacterNotFound { name: "Gandor".into() };
+        assert_eq!((e.kind(), e.to_string().as_str()), ("character_not_found", "Character 'Gandor' not found"));
+    }
+}
@@ -0,0 +1,153 @@
+//! Training ETA.
+//!
+//! Projects when a trainer's rank count reaches a target from the recent training pace. The
+//! pace is the ranks per week recorded in `trainer_rank_history` over the trailing
+//! `ETA_WINDOW_DAYS` up to now — shortened to the time since the first rank with that trainer
+//! (but never below a week), so a trainer picked up last month isn't averaged over three.
+//! A trainer not trained within the window has no pace, and so no ETA.
+
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::{AmanuensisError, Result};
+
+/// Length of the trailing window the training pace is averaged over.
+pub const ETA_WINDOW_DAYS: i64 = 84;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TrainingEta {
+    pub trainer: String,
+    /// Effective ranks (respects the trainer's rank mode).
+    pub current_ranks: i64,
+    pub target: i64,
+    /// Ranks still needed; 0 once the target is reached.
+    pub remaining: i64,
+    /// Days the pace was averaged over.
+    pub window_days: i64,
+    pub ranks_in_window: i64,
+    pub ranks_per_week: f64,
+    pub last_rank: Option<String>,
+    /// Projected weeks until the target; None when reached or without a pace.
+    pub weeks_remaining: Option<f64>,
+    /// Projected day ("YYYY-MM-DD") the target is reached; None when reached or without a pace.
+    pub eta: Option<String>,
+}
+
+/// Project the ETA from the trainer's current ranks and the times of its recorded ranks
+/// (any order), as of `now`.
+pub fn compute_eta(
+    trainer: &str,
+    current_ranks: i64,
+    target: i64,
+    rank_times: &[NaiveDateTime],
+    now: NaiveDateTime,
+) -> TrainingEta {
+    let first = rank_times.iter().min().copied();
+    let window_start = match first {
+        Some(first) => first.max(now - Duration::days(ETA_WINDOW_DAYS)),
+        None => now - Duration::days(ETA_WINDOW_DAYS),
+    };
+    let window_days = (now - window_start).num_days().clamp(7, ETA_WINDOW_DAYS);
+    let ranks_in_window = rank_times.iter().filter(|t| **t >= window_start && **t <= now).count() as i64;
+    let ranks_per_week = ranks_in_window as f64 * 7.0 / window_days as f64;
+
+    let remaining = (target - current_ranks).max(0);
+    let weeks_remaining = (remaining > 0 && ranks_per_week > 0.0).then(|| remaining as f64 / ranks_per_week);
+    let eta = weeks_remaining.map(|w| {
+        let days = (w * 7.0).ceil() as i64;
+        (now + Duration::days(days)).format("%Y-%m-%d").to_string()
+    });
+
+    TrainingEta {
+        trainer: trainer.to_string(),
+        current_ranks,
+        target,
+        remaining,
+        window_days,
+        ranks_in_window,
+        ranks_per_week,
+        last_rank: rank_times.iter().max().map(crate::date::to_storage),
+        weeks_remaining,
+        eta,
+    }
+}
+
+impl Database {
+    /// Training ETA to `target` effective ranks with a trainer (case-insensitive) for a
+    /// (possibly merged) character, as of `now`. Errors if the character has no record of
+    /// that trainer.
+    pub fn training_eta_merged(
+        &self,
+        char_id: i64,
+        trainer: &str,
+        target: i64,
+        now: NaiveDateTime,
+    ) -> Result<TrainingEta> {
+        let record = self
+            .get_trainers_merged(char_id)?
+            .into_iter()
+            .find(|t| t.trainer_name.eq_ignore_ascii_case(trainer))
+            .ok_or_else(|| AmanuensisError::Data(format!("No ranks recorded with trainer '{}'", trainer)))?;
+        let times: Vec<NaiveDateTime> = self
+            .get_trainer_rank_times_merged(char_id, &record.trainer_name)?
+            .iter()
+            .filter_map(|t| crate::date::parse(t))
+            .collect();
+        Ok(compute_eta(&record.trainer_name, record.effective_ranks(), target, &times, now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        crate::date::parse(s).unwrap()
+    }
+
+    #[test]
+    fn eta_projects_recent_pace() {
+        let now = at("2024-04-01 00:00:00");
+        // 24 ranks over the last 12 weeks: 2 per week. One old rank outside the window.
+        let mut times = vec![at("2023-06-01 12:00:00")];
+        for week in 0..12 {
+            for _ in 0..2 {
+                times.push(now - Duration::days(7 * week + 1));
+            }
+        }
+        let eta = compute_eta("Histia", 100, 120, &times, now);
+        assert_eq!((eta.window_days, eta.ranks_in_window, eta.ranks_per_week), (84, 24, 2.0));
+        assert_eq!((eta.remaining, eta.weeks_remaining), (20, Some(10.0)));
+        assert_eq!(eta.eta.as_deref(), Some("2024-06-10"));
+
+        // Reached: no projection.
+        let done = compute_eta("Histia", 130, 120, &times, now);
+        assert_eq!((done.remaining, done.eta), (0, None));
+
+        // A trainer started 14 days ago is averaged over those 14 days.
+        let fresh = [now - Duration::days(14), now - Duration::days(3)];
+        let eta = compute_eta("Detha", 2, 10, &fresh, now);
+        assert_eq!((eta.window_days, eta.ranks_per_week), (14, 1.0));
+
+        // Not trained recently: no pace, no ETA.
+        let stale = compute_eta("Detha", 2, 10, &[at("2023-01-01 00:00:00")], now);
+        assert_eq!((stale.ranks_per_week, stale.eta), (0.0, None));
+    }
+
+    #[test]
+    fn eta_uses_effective_ranks_and_history() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        for day in 1..=14 {
+            db.upsert_trainer_rank(c, "Histia", &format!("2024-03-{day:02} 10:00:00"), 1.0).unwrap();
+        }
+        db.set_modified_ranks(c, "Histia", 6).unwrap();
+
+        let eta = db.training_eta_merged(c, "histia", 50, at("2024-03-15 10:00:00")).unwrap();
+        assert_eq!((eta.trainer.as_str(), eta.current_ranks, eta.remaining), ("Histia", 20, 30));
+        assert_eq!(eta.last_rank.as_deref(), Some("2024-03-14 10:00:00"));
+        assert_eq!(eta.ranks_per_week, 7.0);
+        assert!(db.training_eta_merged(c, "Nobody", 50, at("2024-03-15 10:00:00")).is_err());
+    }
+}
@@ -1,16 +1,135 @@
 use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
 
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::date::Timestamp;
 use crate::db::queries::CreatureFrequency;
-use crate::error::Result;
+use crate::db::{LogSearchOptions, LogSearchResult};
+use crate::error::{AmanuensisError, Result};
 use crate::models::Kill;
 use crate::Database;
 
-/// Output format for the unified kills export.
+/// Output format for the unified kills export and the per-table exports.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExportFormat {
     Csv,
     Text,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Text => "text",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "text" | "txt" => Some(ExportFormat::Text),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// File extension for an export written in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Text => "txt",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// File format for saved search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchExportFormat {
+    Csv,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+impl SearchExportFormat {
+    /// The format a file name asks for: `.csv`, or `.jsonl` / `.ndjson`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "csv" => Some(SearchExportFormat::Csv),
+            "jsonl" | "ndjson" => Some(SearchExportFormat::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+/// Results fetched per query while saving search results.
+const SEARCH_EXPORT_PAGE: i64 = 1000;
+
+/// A database table the `export` command can dump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTable {
+    Characters,
+    Kills,
+    Trainers,
+    Lastys,
+    Pets,
+    ClanHistory,
+    /// Other players seen hunting with the character.
+    Companions,
+}
+
+impl ExportTable {
+    pub const ALL: [ExportTable; 7] = [
+        ExportTable::Characters,
+        ExportTable::Kills,
+        ExportTable::Trainers,
+        ExportTable::Lastys,
+        ExportTable::Pets,
+        ExportTable::ClanHistory,
+        ExportTable::Companions,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportTable::Characters => "characters",
+            ExportTable::Kills => "kills",
+            ExportTable::Trainers => "trainers",
+            ExportTable::Lastys => "lastys",
+            ExportTable::Pets => "pets",
+            ExportTable::ClanHistory => "clan_history",
+            ExportTable::Companions => "companions",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == s.to_lowercase())
+    }
+}
+
+/// Replaces other players' names with `Player 1`, `Player 2`, … for a shareable export.
+/// Numbers are handed out in order of first appearance (case-insensitively), so one
+/// player keeps one pseudonym across every table written with the same `Anonymizer`,
+/// and nothing about the name survives to be guessed back.
+#[derive(Debug, Default)]
+pub struct Anonymizer {
+    names: HashMap<String, String>,
+}
+
+impl Anonymizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pseudonym(&mut self, name: &str) -> String {
+        let next = self.names.len() + 1;
+        self.names.entry(name.to_lowercase()).or_insert_with(|| format!("Player {next}")).clone()
+    }
 }
 
 /// Column headers, in the Kills-view order.
@@ -39,8 +158,8 @@ fn row_cells(k: &Kill, freq: Option<&CreatureFrequency>) -> Vec<String> {
         (k.slaughtered_count + k.assisted_slaughter_count).to_string(),
         k.killed_by_count.to_string(),
         k.creature_value.to_string(),
-        date_only(k.date_first.as_deref()),
-        date_only(k.date_last.as_deref()),
+        date_only(k.date_first),
+        date_only(k.date_last),
         best_day,
         best_day_date,
         best_2h,
@@ -53,8 +172,8 @@ fn num_or_blank(n: i64) -> String {
 }
 
 /// Date portion of a "YYYY-MM-DD HH:MM:SS" timestamp (matches the GUI's date display).
-fn date_only(s: Option<&str>) -> String {
-    s.unwrap_or("").split(' ').next().unwrap_or("").to_string()
+fn date_only(d: Option<Timestamp>) -> String {
+    d.map(|d| d.day()).unwrap_or_default()
 }
 
 /// "YYYY-MM-DD HH:00" hour-bucket start -> "YYYY-MM-DD HH:00–HH:00" (start + 2h,
@@ -75,7 +194,7 @@ fn two_hour_window(start: &str) -> String {
 /// Quote a CSV cell when it contains a comma, quote, or space; double inner quotes.
 /// (Same rule the CLI's frequency export uses.)
 fn csv_cell(s: &str) -> String {
-    if s.contains(',') || s.contains('"') || s.contains(' ') {
+    if s.contains(',') || s.contains('"') || s.contains(' ') || s.contains('\n') {
         format!("\"{}\"", s.replace('"', "\"\""))
     } else {
         s.to_string()
@@ -107,6 +226,16 @@ pub fn format_kills_export(
             out
         }
         ExportFormat::Text => format_text(kills, &freq_by_name),
+        ExportFormat::Json => {
+            let rows: Vec<IndexMap<String, String>> = kills
+                .iter()
+                .map(|k| {
+                    let cells = row_cells(k, freq_by_name.get(k.creature_name.as_str()).copied());
+                    HEADERS.iter().map(|h| h.to_string()).zip(cells).collect::<IndexMap<_, _>>()
+                })
+                .collect();
+            serde_json::to_string_pretty(&rows).unwrap_or_default()
+        }
     }
 }
 
@@ -130,7 +259,234 @@ fn format_text(kills: &[Kill], freq_by_name: &HashMap<&str, &CreatureFrequency>)
     table.to_string()
 }
 
+/// A serialized model row whose objects keep their fields in declaration order
+/// (`serde_json::Value` sorts keys), so export columns follow the model structs.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Record {
+    Object(IndexMap<String, Record>),
+    Array(Vec<Record>),
+    Scalar(serde_json::Value),
+}
+
+impl Record {
+    fn of(row: &impl Serialize) -> Result<Self> {
+        Ok(serde_json::from_str(&serde_json::to_string(row)?)?)
+    }
+
+    fn get(&self, key: &str) -> Option<&Record> {
+        match self {
+            Record::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+}
+
+/// Render serialized model rows as a table. Columns follow the first row's field order;
+/// nested values (e.g. a character's `fishing_catches`) are written as inline JSON.
+fn format_records(rows: &[Record], format: ExportFormat) -> Result<String> {
+    if format == ExportFormat::Json {
+        return Ok(serde_json::to_string_pretty(rows)?);
+    }
+    let headers: Vec<String> = match rows.first() {
+        Some(Record::Object(map)) => map.keys().cloned().collect(),
+        _ => Vec::new(),
+    };
+    let cell = |v: Option<&Record>| match v {
+        None | Some(Record::Scalar(serde_json::Value::Null)) => String::new(),
+        Some(Record::Scalar(serde_json::Value::String(s))) => s.clone(),
+        Some(Record::Scalar(other)) => other.to_string(),
+        Some(nested) => serde_json::to_string(nested).unwrap_or_default(),
+    };
+    let body: Vec<Vec<String>> = rows
+        .iter()
+        .map(|r| headers.iter().map(|h| cell(r.get(h))).collect())
+        .collect();
+
+    if format == ExportFormat::Text {
+        use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+        use comfy_table::presets::UTF8_FULL;
+        use comfy_table::{ContentArrangement, Table};
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(headers);
+        for row in body {
+            table.add_row(row);
+        }
+        return Ok(table.to_string());
+    }
+
+    if headers.is_empty() {
+        return Ok(String::new());
+    }
+    let mut out = String::new();
+    out.push_str(&headers.iter().map(|h| csv_cell(h)).collect::<Vec<_>>().join(","));
+    out.push('\n');
+    for row in body {
+        out.push_str(&row.iter().map(|c| csv_cell(c)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Serialize a model row, prefixing a `character` column with the owning character's name
+/// (rows from merged sources keep their own `character_id`, so the name is the stable key).
+fn record_for(character: &str, row: &impl Serialize) -> Result<Record> {
+    let mut map = IndexMap::new();
+    map.insert("character".to_string(), Record::Scalar(character.into()));
+    if let Record::Object(fields) = Record::of(row)? {
+        map.extend(fields);
+    }
+    Ok(Record::Object(map))
+}
+
+/// Flatten a record into (dotted path, cell) pairs, e.g. `character.name` or
+/// `most_killed.count`. Arrays stay whole, as inline JSON.
+fn flatten_fields(prefix: &str, value: Record, out: &mut Vec<Record>) {
+    match value {
+        Record::Object(map) => {
+            for (key, v) in map {
+                let path = if prefix.is_empty() { key } else { format!("{prefix}.{key}") };
+                flatten_fields(&path, v, out);
+            }
+        }
+        other => out.push(Record::Object(IndexMap::from([
+            ("field".to_string(), Record::Scalar(prefix.into())),
+            ("value".to_string(), other),
+        ]))),
+    }
+}
+
 impl Database {
+    /// Dump one table for `char_id` (merged view, like the GUI) or, when None, for every
+    /// visible character. Characters are one row each; the other tables get a leading
+    /// `character` column.
+    pub fn export_table(
+        &self,
+        table: ExportTable,
+        char_id: Option<i64>,
+        format: ExportFormat,
+    ) -> Result<String> {
+        self.export_table_rows(table, char_id, format, None)
+    }
+
+    /// `export_table` for posting publicly: other players' names become `anonymizer`
+    /// pseudonyms and free-text trainer notes are dropped. The character's own name and
+    /// creature, trainer and clan names are kept; speech is never part of an export.
+    pub fn export_table_anonymized(
+        &self,
+        table: ExportTable,
+        char_id: Option<i64>,
+        format: ExportFormat,
+        anonymizer: &mut Anonymizer,
+    ) -> Result<String> {
+        self.export_table_rows(table, char_id, format, Some(anonymizer))
+    }
+
+    fn export_table_rows(
+        &self,
+        table: ExportTable,
+        char_id: Option<i64>,
+        format: ExportFormat,
+        mut anonymizer: Option<&mut Anonymizer>,
+    ) -> Result<String> {
+        let characters = match char_id {
+            Some(id) => self.get_character_merged(id)?.into_iter().collect(),
+            None => self.list_characters()?,
+        };
+
+        let mut rows = Vec::new();
+        for c in &characters {
+            let Some(id) = c.id else { continue };
+            match table {
+                ExportTable::Characters => rows.push(Record::of(c)?),
+                ExportTable::Kills => {
+                    let mut kills = self.get_kills_merged(id)?;
+                    kills.sort_by_key(|k| Reverse(k.total_all()));
+                    for k in &kills {
+                        rows.push(record_for(&c.name, k)?);
+                    }
+                }
+                ExportTable::Trainers => {
+                    for mut t in self.get_trainers_merged(id)? {
+                        if anonymizer.is_some() {
+                            t.notes = None;
+                        }
+                        rows.push(record_for(&c.name, &t)?);
+                    }
+                }
+                ExportTable::Lastys => {
+                    for l in &self.get_lastys_merged(id)? {
+                        rows.push(record_for(&c.name, l)?);
+                    }
+                }
+                ExportTable::Pets => {
+                    for p in &self.get_pets_merged(id)? {
+                        rows.push(record_for(&c.name, p)?);
+                    }
+                }
+                ExportTable::ClanHistory => {
+                    for e in &self.get_clan_history_merged(id)? {
+                        rows.push(record_for(&c.name, e)?);
+                    }
+                }
+                ExportTable::Companions => {
+                    for mut companion in self.get_companions_merged(id)? {
+                        if let Some(anonymizer) = anonymizer.as_deref_mut() {
+                            companion.name = anonymizer.pseudonym(&companion.name);
+                        }
+                        rows.push(record_for(&c.name, &companion)?);
+                    }
+                }
+            }
+        }
+        format_records(&rows, format)
+    }
+
+    /// Write the matches of a search to `out`, starting `options.offset` results in and at
+    /// most `options.limit` of them, fetched a page at a time so any number can be saved.
+    /// CSV rows are `timestamp,character,file_path,content` plus the context lines (joined
+    /// by newlines) when requested. Returns the number of results written.
+    pub fn export_search_results(
+        &self,
+        query: &str,
+        options: &LogSearchOptions,
+        format: SearchExportFormat,
+        out: &mut impl Write,
+    ) -> Result<i64> {
+        let with_context = options.lines_before > 0 || options.lines_after > 0;
+        if format == SearchExportFormat::Csv {
+            let mut header = vec!["timestamp", "character", "file_path", "content"];
+            if with_context {
+                header.extend(["context_before", "context_after"]);
+            }
+            writeln!(out, "{}", header.join(","))?;
+        }
+        let mut written = 0;
+        let mut offset = options.offset.max(0);
+        while written < options.limit {
+            let page_options = LogSearchOptions {
+                offset,
+                limit: SEARCH_EXPORT_PAGE.min(options.limit - written),
+                ..options.clone()
+            };
+            let page = self.search_log_lines_page(query, &page_options)?;
+            for result in &page.results {
+                write_search_result(out, result, format, with_context)?;
+            }
+            written += page.results.len() as i64;
+            offset += page.results.len() as i64;
+            if !page.has_more || page.results.is_empty() {
+                break;
+            }
+        }
+        Ok(written)
+    }
+
     /// Render the unified Kills table for a (possibly merged) character to a string.
     /// Fetches merged kills + frequency, sorts by total kills descending (the Kills
     /// view's default order), joins frequency by creature name, and formats.
@@ -140,6 +496,58 @@ impl Database {
         let freq = self.kill_frequency_merged_with(char_id, true)?;
         Ok(format_kills_export(&kills, &freq, format))
     }
+
+    /// Render a (possibly merged) character's summary (`Database::character_summary`).
+    /// JSON is the summary as-is; CSV and text are `field,value` rows with nested fields
+    /// flattened to dotted names.
+    pub fn export_character_summary(&self, char_id: i64, format: ExportFormat) -> Result<String> {
+        let summary = self
+            .character_summary(char_id)?
+            .ok_or_else(|| AmanuensisError::CharacterNotFound { name: char_id.to_string() })?;
+        if format == ExportFormat::Json {
+            return Ok(serde_json::to_string_pretty(&summary)?);
+        }
+        let mut rows = Vec::new();
+        flatten_fields("", Record::of(&summary)?, &mut rows);
+        format_records(&rows, format)
+    }
+}
+
+fn write_search_result(
+    out: &mut impl Write,
+    result: &LogSearchResult,
+    format: SearchExportFormat,
+    with_context: bool,
+) -> Result<()> {
+    match format {
+        SearchExportFormat::JsonLines => {
+            let mut record = serde_json::json!({
+                "timestamp": result.timestamp,
+                "character": result.character_name,
+                "file_path": result.file_path,
+                "content": result.content,
+            });
+            if with_context {
+                record["context_before"] = serde_json::json!(result.context_before);
+                record["context_after"] = serde_json::json!(result.context_after);
+            }
+            writeln!(out, "{}", serde_json::to_string(&record)?)?;
+        }
+        SearchExportFormat::Csv => {
+            let mut cells = vec![
+                csv_cell(&result.timestamp),
+                csv_cell(&result.character_name),
+                csv_cell(&result.file_path),
+                csv_cell(&result.content),
+            ];
+            if with_context {
+                cells.push(csv_cell(&result.context_before.join("\n")));
+                cells.push(csv_cell(&result.context_after.join("\n")));
+            }
+            writeln!(out, "{}", cells.join(","))?;
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -155,16 +563,16 @@ mod tests {
         k.assisted_kill_count = 2;
         k.slaughtered_count = 3;
         k.killed_by_count = 1;
-        k.date_first = Some("2024-01-01 09:00:00".into());
-        k.date_last = Some("2024-01-05 12:00:00".into());
+        k.date_first = Some("2024-01-01 09:00:00".parse().unwrap());
+        k.date_last = Some("2024-01-05 12:00:00".parse().unwrap());
         k
     }
 
     fn rat() -> Kill {
         let mut k = Kill::new(0, "Rat".into(), 2);
         k.killed_count = 8;
-        k.date_first = Some("2024-02-01 09:00:00".into());
-        k.date_last = Some("2024-02-02 09:00:00".into());
+        k.date_first = Some("2024-02-01 09:00:00".parse().unwrap());
+        k.date_last = Some("2024-02-02 09:00:00".parse().unwrap());
         k
     }
 
@@ -172,7 +580,7 @@ mod tests {
         CreatureFrequency {
             creature_name: "Large Vermine".into(),
             best_day_count: 4,
-            best_day_date: Some("2024-01-03".into()),
+            best_day_date: Some("2024-01-03".parse().unwrap()),
             best_day_verbs: BTreeMap::new(),
             best_2h_count: 3,
             best_2h_start: Some("2024-01-03 08:00".into()),
@@ -244,4 +652,148 @@ mod tests {
         assert!(lines[1].starts_with("Wolf,"));
         assert!(lines[2].starts_with("Rat,"));
     }
+
+    #[test]
+    fn json_kills_export_uses_headers_as_keys() {
+        let out = format_kills_export(&[rat()], &[], ExportFormat::Json);
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed[0]["Creature"], "Rat");
+        assert_eq!(parsed[0]["Killed"], "8");
+        assert_eq!(parsed[0]["Best Day"], "");
+    }
+
+    #[test]
+    fn export_table_csv_and_json_for_all_characters() {
+        use crate::db::queries::Database;
+
+        let db = Database::open_in_memory().unwrap();
+        let a = db.get_or_create_character("Alpha").unwrap();
+        let b = db.get_or_create_character("Beta").unwrap();
+        // list_characters only shows characters with at least one login.
+        db.increment_character_field(a, "logins", 1).unwrap();
+        db.increment_character_field(b, "logins", 1).unwrap();
+        db.upsert_kill(a, "Rat", "killed_count", 2, "2024-02-01 09:00:00").unwrap();
+        db.upsert_kill(b, "Wolf, Grey", "killed_count", 50, "2024-01-01 09:00:00").unwrap();
+
+        let csv = db.export_table(ExportTable::Kills, None, ExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("character,id,character_id,creature_name,"));
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().any(|l| l.starts_with("Alpha,") && l.contains(",Rat,")));
+        // Cells with commas are quoted.
+        assert!(lines.iter().any(|l| l.starts_with("Beta,") && l.contains("\"Wolf, Grey\"")));
+
+        let json = db.export_table(ExportTable::Characters, Some(a), ExportFormat::Json).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.as_array().unwrap().len(), 1);
+        assert_eq!(parsed[0]["name"], "Alpha");
+
+        let empty = db.export_table(ExportTable::Pets, None, ExportFormat::Csv).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn character_summary_export_flattens_for_csv() {
+        use crate::db::queries::Database;
+
+        let db = Database::open_in_memory().unwrap();
+        let id = db.get_or_create_character("Alpha").unwrap();
+        db.increment_character_field(id, "logins", 3).unwrap();
+        db.upsert_kill(id, "Rat", "killed_count", 2, "2024-02-01 09:00:00").unwrap();
+
+        let csv = db.export_character_summary(id, ExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "field,value");
+        assert!(lines.contains(&"character.name,Alpha"));
+        assert!(lines.contains(&"character.logins,3"));
+        assert!(lines.contains(&"most_killed.creature,Rat"));
+        assert!(lines.contains(&"nemesis,"));
+
+        let json = db.export_character_summary(id, ExportFormat::Json).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["solo_kills"], 1);
+        assert_eq!(parsed["character"]["name"], "Alpha");
+
+        let err = db.export_character_summary(9999, ExportFormat::Json).unwrap_err();
+        assert_eq!(err.kind(), "character_not_found");
+    }
+
+    #[test]
+    fn anonymized_export_replaces_other_players_and_drops_notes() {
+        use crate::db::queries::Database;
+
+        let db = Database::open_in_memory().unwrap();
+        let a = db.get_or_create_character("Alpha").unwrap();
+        let b = db.get_or_create_character("Beta").unwrap();
+        db.increment_character_field(a, "logins", 1).unwrap();
+        db.increment_character_field(b, "logins", 1).unwrap();
+        db.record_companion(a, "Fen", "2024-01-01").unwrap();
+        db.record_companion(a, "Histia", "2024-01-01").unwrap();
+        db.record_companion(b, "fen", "2024-01-02").unwrap();
+        db.set_trainer_note(a, "Bangus Anmash", Some("ask Fen about this")).unwrap();
+
+        let plain = db.export_table(ExportTable::Companions, None, ExportFormat::Csv).unwrap();
+        assert!(plain.contains("Fen") && plain.contains("Histia"));
+
+        let mut anonymizer = Anonymizer::new();
+        let csv = db.export_table_anonymized(ExportTable::Companions, None, ExportFormat::Csv, &mut anonymizer).unwrap();
+        assert!(!csv.contains("Fen") && !csv.contains("Histia"));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("character,name,"));
+        assert!(lines[1..].iter().all(|l| l.starts_with("Alpha,\"Player ") || l.starts_with("Beta,\"Player ")));
+        // Fen is the same pseudonym in both characters' rows.
+        let fen = anonymizer.pseudonym("FEN");
+        assert_eq!(lines.iter().filter(|l| l.contains(&format!("\"{fen}\""))).count(), 2);
+
+        let trainers =
+            db.export_table_anonymized(ExportTable::Trainers, Some(a), ExportFormat::Json, &mut anonymizer).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&trainers).unwrap();
+        // Trainer names are NPCs, not players, and stay.
+        assert_eq!(parsed[0]["trainer_name"], "Bangus Anmash");
+        assert_eq!(parsed[0]["notes"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn export_names_round_trip() {
+        for t in ExportTable::ALL {
+            assert_eq!(ExportTable::parse(t.as_str()), Some(t));
+        }
+        for f in [ExportFormat::Csv, ExportFormat::Text, ExportFormat::Json] {
+            assert_eq!(ExportFormat::parse(f.as_str()), Some(f));
+        }
+        assert_eq!(ExportTable::parse("Kills"), Some(ExportTable::Kills));
+        assert_eq!(ExportFormat::parse("xml"), None);
+    }
+
+    #[test]
+    fn search_export_pages_through_every_match() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db.get_or_create_character("Fen").unwrap();
+        let lines: Vec<String> = (0..2500).map(|i| format!("You slaughtered a Rat, #{i}.")).collect();
+        let rows: Vec<(i64, &str, &str, &str)> =
+            lines.iter().map(|l| (id, l.as_str(), "2024-01-01 13:00:00", "/logs/a.txt")).collect();
+        db.insert_log_lines(&rows).unwrap();
+        let options = LogSearchOptions { include_speech: true, offset: 10, limit: i64::MAX, ..Default::default() };
+        assert_eq!(db.count_log_matches("rat", &options).unwrap(), 2500);
+
+        let mut csv = Vec::new();
+        assert_eq!(db.export_search_results("rat", &options, SearchExportFormat::Csv, &mut csv).unwrap(), 2490);
+        let csv = String::from_utf8(csv).unwrap();
+        let mut csv_lines = csv.lines();
+        assert_eq!(csv_lines.next(), Some("timestamp,character,file_path,content"));
+        assert_eq!(
+            csv_lines.next(),
+            Some("\"2024-01-01 13:00:00\",Fen,/logs/a.txt,\"You slaughtered a Rat, #2489.\"")
+        );
+
+        let mut jsonl = Vec::new();
+        let options = LogSearchOptions { limit: 3, lines_before: 1, ..options };
+        db.export_search_results("rat", &options, SearchExportFormat::JsonLines, &mut jsonl).unwrap();
+        let records: Vec<serde_json::Value> =
+            String::from_utf8(jsonl).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["context_before"], serde_json::json!(["You slaughtered a Rat, #2488."]));
+        assert_eq!(SearchExportFormat::from_path(Path::new("hits.JSONL")), Some(SearchExportFormat::JsonLines));
+        assert_eq!(SearchExportFormat::from_path(Path::new("hits.txt")), None);
+    }
 }
@@ -1,6 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-/// Human race base stats (from Gorvin's Fighter Calculator).
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AmanuensisError, Result};
+use crate::models::Trainer;
+
+/// Human race base stats (from Gorvin's Fighter Calculator). Other races, weapons and items
+/// are deltas from these, in `data/fighter_modifiers.json`.
 const RACE_ACCURACY: i64 = 300;
 const RACE_MIN_DAMAGE: i64 = 100;
 const RACE_MAX_DAMAGE: i64 = 200;
@@ -58,6 +64,13 @@ fn sp_cost(trainer: &str) -> Option<i64> {
     }
 }
 
+/// Trainers that appear in the stat formulas, by DB name.
+pub const FIGHTER_TRAINERS: [&str; 25] = [
+    "Atkus", "Darkus", "Balthus", "Regia", "Evus", "Swengus", "Histia", "Detha", "Bodrus",
+    "Hardia", "Troilus", "Spiritus", "Aktur", "Atkia", "Darktur", "Angilsa", "Knox", "Heen",
+    "Bangus Anmash", "Farly Buff", "Stedfustus", "Forvyola", "Anemia", "Rodnus", "Erthron",
+];
+
 /// Map DB trainer names to formula names.
 fn formula_name(db_name: &str) -> &str {
     match db_name {
@@ -67,8 +80,136 @@ fn formula_name(db_name: &str) -> &str {
     }
 }
 
+/// Additive stat changes from a race, weapon or item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatModifiers {
+    pub accuracy: i64,
+    pub min_damage: i64,
+    pub max_damage: i64,
+    pub balance: i64,
+    pub bal_regen: i64,
+    pub health: i64,
+    pub defense: i64,
+    pub health_regen: i64,
+    pub spirit: i64,
+    pub spirit_regen: i64,
+}
+
+impl std::ops::Add for StatModifiers {
+    type Output = StatModifiers;
+
+    fn add(self, o: StatModifiers) -> StatModifiers {
+        StatModifiers {
+            accuracy: self.accuracy + o.accuracy,
+            min_damage: self.min_damage + o.min_damage,
+            max_damage: self.max_damage + o.max_damage,
+            balance: self.balance + o.balance,
+            bal_regen: self.bal_regen + o.bal_regen,
+            health: self.health + o.health,
+            defense: self.defense + o.defense,
+            health_regen: self.health_regen + o.health_regen,
+            spirit: self.spirit + o.spirit,
+            spirit_regen: self.spirit_regen + o.spirit_regen,
+        }
+    }
+}
+
+/// A character's race, weapon and equipped items, by catalog name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FighterLoadout {
+    pub race: String,
+    pub weapon: String,
+    pub items: Vec<String>,
+}
+
+impl Default for FighterLoadout {
+    /// The calculator's base fighter: Human, Roguewood Club, no items.
+    fn default() -> Self {
+        Self { race: "Human".to_string(), weapon: "Roguewood Club".to_string(), items: Vec::new() }
+    }
+}
+
+impl std::fmt::Display for FighterLoadout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let items = if self.items.is_empty() { "No Items".to_string() } else { self.items.join(", ") };
+        write!(f, "{} / {} / {}", self.race, self.weapon, items)
+    }
+}
+
+/// A loadout with its modifiers looked up. Race modifiers count toward the slaughter-point
+/// base; weapon and item modifiers (`equipment`) do not.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolvedLoadout {
+    pub loadout: FighterLoadout,
+    pub race: StatModifiers,
+    pub equipment: StatModifiers,
+    /// Parts whose modifiers aren't known, counted as no change.
+    pub unknown: Vec<String>,
+}
+
+/// Race, weapon and item modifiers, keyed by name. `null` in the file marks a name whose
+/// modifiers haven't been sourced yet; it resolves to no change and is listed in
+/// `ResolvedLoadout::unknown`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FighterModifiers {
+    pub races: BTreeMap<String, Option<StatModifiers>>,
+    pub weapons: BTreeMap<String, Option<StatModifiers>>,
+    pub items: BTreeMap<String, Option<StatModifiers>>,
+}
+
+impl FighterModifiers {
+    /// Load from the bundled fighter_modifiers.json (compiled into the binary).
+    pub fn bundled() -> Result<Self> {
+        serde_json::from_slice(include_bytes!("../data/fighter_modifiers.json"))
+            .map_err(|e| AmanuensisError::Data(format!("Invalid fighter_modifiers.json: {e}")))
+    }
+
+    /// Look up every part of `loadout` (names match case-insensitively) and return it with
+    /// canonical names.
+    pub fn resolve(&self, loadout: &FighterLoadout) -> Result<ResolvedLoadout> {
+        fn lookup<'a>(
+            table: &'a BTreeMap<String, Option<StatModifiers>>,
+            kind: &str,
+            name: &str,
+        ) -> Result<(&'a str, Option<StatModifiers>)> {
+            table
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name.trim()))
+                .map(|(k, m)| (k.as_str(), *m))
+                .ok_or_else(|| {
+                    let known: Vec<&str> = table.keys().map(String::as_str).collect();
+                    AmanuensisError::Data(format!("Unknown {kind} '{name}' (known: {})", known.join(", ")))
+                })
+        }
+
+        let mut unknown = Vec::new();
+        let mut known = |(name, m): (&str, Option<StatModifiers>)| {
+            if m.is_none() {
+                unknown.push(name.to_string());
+            }
+            (name.to_string(), m.unwrap_or_default())
+        };
+        let (race_name, race) = known(lookup(&self.races, "race", &loadout.race)?);
+        let (weapon_name, mut equipment) = known(lookup(&self.weapons, "weapon", &loadout.weapon)?);
+        let mut items = Vec::new();
+        for item in &loadout.items {
+            let (item_name, m) = known(lookup(&self.items, "item", item)?);
+            equipment = equipment + m;
+            items.push(item_name);
+        }
+        Ok(ResolvedLoadout {
+            loadout: FighterLoadout { race: race_name, weapon: weapon_name, items },
+            race,
+            equipment,
+            unknown,
+        })
+    }
+}
+
 /// Computed fighter statistics.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FighterStats {
     pub trained_ranks: i64,
     pub effective_ranks: f64,
@@ -90,18 +231,45 @@ pub struct FighterStats {
     pub heal_receptivity: i64,
     pub balance_per_swing: i64,
     pub shieldstone_drain: i64,
+    /// The race, weapon and items the stats were computed for.
+    pub loadout: FighterLoadout,
+    /// Parts of the loadout whose modifiers aren't known and were counted as no change.
+    pub unknown_modifiers: Vec<String>,
 }
 
 /// Compute fighter stats from trainer ranks and multipliers.
 ///
-/// `ranks`: trainer name -> total ranks (ranks + modified_ranks).
+/// `ranks`: trainer name -> effective ranks, as built by [`fighter_rank_map`].
 /// `multipliers`: trainer name -> effective rank multiplier.
 ///
 /// Trainer names should use DB names; aliases (e.g. "Bangus Anmash") are
 /// mapped internally to formula names (e.g. "Bangus").
+///
+/// Computes for the base fighter (Human, Roguewood Club, no items); see
+/// [`compute_fighter_stats_with`] for other loadouts.
 pub fn compute_fighter_stats(
     ranks: &HashMap<String, i64>,
     multipliers: &HashMap<String, f64>,
+) -> FighterStats {
+    compute_fighter_stats_with(ranks, multipliers, &ResolvedLoadout::default())
+}
+
+/// Ranks per trainer for the calculator: each trainer's [`Trainer::effective_ranks`] (rank
+/// mode applied, apply-learning ranks included), trainers without any left out. Every
+/// caller builds its rank map here, so reports and previews use the same basis.
+pub fn fighter_rank_map(trainers: &[Trainer]) -> HashMap<String, i64> {
+    trainers
+        .iter()
+        .map(|t| (t.trainer_name.clone(), t.effective_ranks()))
+        .filter(|(_, ranks)| *ranks > 0)
+        .collect()
+}
+
+/// Compute fighter stats for a race, weapon and items (see [`FighterModifiers::resolve`]).
+pub fn compute_fighter_stats_with(
+    ranks: &HashMap<String, i64>,
+    multipliers: &HashMap<String, f64>,
+    loadout: &ResolvedLoadout,
 ) -> FighterStats {
     // Build a formula-name -> ranks map
     let mut r: HashMap<&str, i64> = HashMap::new();
@@ -169,17 +337,18 @@ pub fn compute_fighter_stats(
 
     let heal_receptivity = 2 * rodnus + spiritus;
 
-    // Total stats (trainer contribution + race base)
-    let total_accuracy = accuracy + RACE_ACCURACY;
-    let total_min_dmg = min_damage + RACE_MIN_DAMAGE;
-    let total_max_dmg = max_damage + RACE_MAX_DAMAGE;
-    let total_balance = balance + RACE_BALANCE;
-    let total_bal_regen = bal_regen + RACE_BAL_REGEN;
-    let total_health = health + RACE_HEALTH;
-    let total_defense = defense + RACE_DEFENSE;
-    let total_health_regen = health_regen + RACE_HEALTH_REGEN;
-    let total_spirit = spirit + RACE_SPIRIT;
-    let total_spirit_regen = spirit_regen + RACE_SPIRIT_REGEN;
+    // Total stats (trainer contribution + race base + race, weapon and item modifiers)
+    let m = loadout.race + loadout.equipment;
+    let total_accuracy = accuracy + RACE_ACCURACY + m.accuracy;
+    let total_min_dmg = min_damage + RACE_MIN_DAMAGE + m.min_damage;
+    let total_max_dmg = max_damage + RACE_MAX_DAMAGE + m.max_damage;
+    let total_balance = balance + RACE_BALANCE + m.balance;
+    let total_bal_regen = bal_regen + RACE_BAL_REGEN + m.bal_regen;
+    let total_health = health + RACE_HEALTH + m.health;
+    let total_defense = defense + RACE_DEFENSE + m.defense;
+    let total_health_regen = health_regen + RACE_HEALTH_REGEN + m.health_regen;
+    let total_spirit = spirit + RACE_SPIRIT + m.spirit;
+    let total_spirit_regen = spirit_regen + RACE_SPIRIT_REGEN + m.spirit_regen;
 
     // Derived stats
     let damage_min = total_min_dmg.max(0) + 100;
@@ -212,8 +381,19 @@ pub fn compute_fighter_stats(
     }
     effective_ranks = (effective_ranks * 10.0).round() / 10.0;
 
-    // Slaughter points
-    let mut slaughter_points = RACE_SP;
+    // Slaughter points: the race's base stats plus trained ranks; equipment doesn't count
+    let r = loadout.race;
+    let mut slaughter_points = RACE_SP
+        + r.accuracy
+        + r.min_damage
+        + r.max_damage
+        + (RACE_BALANCE + r.balance) / 3 - RACE_BALANCE / 3
+        + r.bal_regen
+        + (RACE_HEALTH + r.health) / 3 - RACE_HEALTH / 3
+        + r.defense
+        + r.health_regen
+        + r.spirit
+        + r.spirit_regen;
     for (name, &total) in ranks {
         let fname = formula_name(name);
         if let Some(cost) = sp_cost(fname) {
@@ -242,13 +422,140 @@ pub fn compute_fighter_stats(
         heal_receptivity,
         balance_per_swing,
         shieldstone_drain,
+        loadout: loadout.loadout.clone(),
+        unknown_modifiers: loadout.unknown.clone(),
+    }
+}
+
+impl FighterStats {
+    /// The numeric stats by field name, in struct order (for diffs and tables).
+    pub fn numeric_fields(&self) -> [(&'static str, f64); 20] {
+        [
+            ("trained_ranks", self.trained_ranks as f64),
+            ("effective_ranks", self.effective_ranks),
+            ("slaughter_points", self.slaughter_points as f64),
+            ("accuracy", self.accuracy as f64),
+            ("damage_min", self.damage_min as f64),
+            ("damage_max", self.damage_max as f64),
+            ("offense", self.offense as f64),
+            ("balance", self.balance as f64),
+            ("balance_regen", self.balance_regen as f64),
+            ("balance_per_frame", self.balance_per_frame),
+            ("health", self.health as f64),
+            ("health_regen", self.health_regen as f64),
+            ("health_per_frame", self.health_per_frame),
+            ("defense", self.defense as f64),
+            ("spirit", self.spirit as f64),
+            ("spirit_regen", self.spirit_regen as f64),
+            ("spirit_per_frame", self.spirit_per_frame),
+            ("heal_receptivity", self.heal_receptivity as f64),
+            ("balance_per_swing", self.balance_per_swing as f64),
+            ("shieldstone_drain", self.shieldstone_drain as f64),
+        ]
+    }
+}
+
+/// One stat that a simulated rank change moves.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StatChange {
+    pub stat: &'static str,
+    pub before: f64,
+    pub after: f64,
+    pub delta: f64,
+}
+
+/// Fighter stats before and after hypothetical rank additions.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSimulation {
+    /// Ranks added per trainer (negative removes, never below zero).
+    pub deltas: BTreeMap<String, i64>,
+    pub before: FighterStats,
+    pub after: FighterStats,
+    /// The stats that changed, in `FighterStats::numeric_fields` order.
+    pub changes: Vec<StatChange>,
+}
+
+/// Parse a rank delta list like `"Evus:50,Darkus:20"` (or `"Knox:-5"`) into
+/// (trainer, ranks) pairs. A trainer named twice has its deltas summed.
+pub fn parse_rank_deltas(spec: &str) -> Result<BTreeMap<String, i64>> {
+    let mut deltas = BTreeMap::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, n) = part
+            .rsplit_once(':')
+            .and_then(|(name, n)| Some((name.trim(), n.trim().parse::<i64>().ok()?)))
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| {
+                AmanuensisError::Data(format!("Invalid rank change '{part}' (expected Trainer:ranks)"))
+            })?;
+        *deltas.entry(name.to_string()).or_insert(0) += n;
+    }
+    if deltas.is_empty() {
+        return Err(AmanuensisError::Data("No rank changes given (expected e.g. Evus:50,Darkus:20)".to_string()));
     }
+    Ok(deltas)
+}
+
+/// Apply `deltas` to a copy of `base_ranks` and compare the fighter stats before and after,
+/// for the base fighter. See [`simulate_stats_with`] for other loadouts.
+pub fn simulate_stats(
+    base_ranks: &HashMap<String, i64>,
+    deltas: &BTreeMap<String, i64>,
+    multipliers: &HashMap<String, f64>,
+) -> StatsSimulation {
+    simulate_stats_with(base_ranks, deltas, multipliers, &ResolvedLoadout::default())
+}
+
+/// [`simulate_stats`] for a race, weapon and items.
+pub fn simulate_stats_with(
+    base_ranks: &HashMap<String, i64>,
+    deltas: &BTreeMap<String, i64>,
+    multipliers: &HashMap<String, f64>,
+    loadout: &ResolvedLoadout,
+) -> StatsSimulation {
+    let mut ranks = base_ranks.clone();
+    for (name, &n) in deltas {
+        let total = ranks.entry(name.clone()).or_insert(0);
+        *total = (*total + n).max(0);
+    }
+    ranks.retain(|_, total| *total > 0);
+    let before = compute_fighter_stats_with(base_ranks, multipliers, loadout);
+    let after = compute_fighter_stats_with(&ranks, multipliers, loadout);
+    let changes = before
+        .numeric_fields()
+        .into_iter()
+        .zip(after.numeric_fields())
+        .filter(|((_, b), (_, a))| (a - b).abs() > f64::EPSILON)
+        .map(|((stat, b), (_, a))| StatChange {
+            stat,
+            before: b,
+            after: a,
+            delta: ((a - b) * 1000.0).round() / 1000.0,
+        })
+        .collect();
+    StatsSimulation { deltas: deltas.clone(), before, after, changes }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rank_map_uses_effective_ranks() {
+        let mut logged = Trainer::new(1, "Atkus".to_string());
+        logged.ranks = 10;
+        logged.modified_ranks = 2;
+        logged.apply_learning_ranks = 3;
+        let mut overridden = Trainer::new(1, "Darkus".to_string());
+        overridden.ranks = 10;
+        overridden.modified_ranks = 4;
+        overridden.rank_mode = "override".to_string();
+        let untrained = Trainer::new(1, "Balthus".to_string());
+
+        let ranks = fighter_rank_map(&[logged, overridden, untrained]);
+        assert_eq!(ranks.len(), 2);
+        assert_eq!((ranks["Atkus"], ranks["Darkus"]), (15, 4));
+    }
+
     #[test]
     fn test_zero_ranks() {
         let ranks = HashMap::new();
@@ -452,6 +759,61 @@ mod tests {
         assert_eq!(stats.health, RACE_HEALTH);
     }
 
+    #[test]
+    fn test_loadout_modifiers_apply_and_race_counts_toward_sp() {
+        let catalog: FighterModifiers = serde_json::from_str(
+            r#"{
+                "races": { "Human": {}, "Sylvan": { "accuracy": 50, "balance": 500, "health": -600, "defense": -25 } },
+                "weapons": { "Roguewood Club": {}, "Axe": { "accuracy": -20, "min_damage": 20, "max_damage": 40 } },
+                "items": { "Chain Mail": { "defense": 50, "bal_regen": -10 } }
+            }"#,
+        )
+        .unwrap();
+        let loadout = FighterLoadout {
+            race: "sylvan".to_string(),
+            weapon: "Axe".to_string(),
+            items: vec!["chain mail".to_string()],
+        };
+        let resolved = catalog.resolve(&loadout).unwrap();
+        assert_eq!(resolved.loadout.to_string(), "Sylvan / Axe / Chain Mail");
+
+        let mut ranks = HashMap::new();
+        ranks.insert("Atkus".to_string(), 10);
+        let base = compute_fighter_stats(&ranks, &HashMap::new());
+        let stats = compute_fighter_stats_with(&ranks, &HashMap::new(), &resolved);
+        let (race, weapon, item) = (
+            catalog.races["Sylvan"].unwrap(),
+            catalog.weapons["Axe"].unwrap(),
+            catalog.items["Chain Mail"].unwrap(),
+        );
+        assert_eq!(stats.accuracy, base.accuracy + race.accuracy + weapon.accuracy);
+        assert_eq!(stats.defense, base.defense + race.defense + item.defense);
+        assert_eq!(stats.damage_max, (RACE_MAX_DAMAGE + weapon.max_damage) * 3 + 100);
+        assert_eq!(
+            stats.slaughter_points,
+            base.slaughter_points + race.accuracy + race.defense
+                + (RACE_BALANCE + race.balance) / 3 - RACE_BALANCE / 3
+                + (RACE_HEALTH + race.health) / 3 - RACE_HEALTH / 3
+        );
+        assert_eq!(base.loadout, FighterLoadout::default());
+
+        let err = catalog.resolve(&FighterLoadout { race: "Orga".to_string(), ..Default::default() });
+        assert!(err.unwrap_err().to_string().contains("Unknown race 'Orga'"));
+    }
+
+    #[test]
+    fn test_unsourced_bundled_modifiers_count_as_no_change_and_are_reported() {
+        let catalog = FighterModifiers::bundled().unwrap();
+        let loadout = FighterLoadout { race: "dwarf".to_string(), ..Default::default() };
+        let resolved = catalog.resolve(&loadout).unwrap();
+        assert_eq!(resolved.unknown, vec!["Dwarf"]);
+        assert_eq!(resolved.race, StatModifiers::default());
+        let stats = compute_fighter_stats_with(&HashMap::new(), &HashMap::new(), &resolved);
+        assert_eq!(stats.unknown_modifiers, vec!["Dwarf"]);
+        assert_eq!(stats.accuracy, RACE_ACCURACY);
+        assert!(catalog.resolve(&FighterLoadout::default()).unwrap().unknown.is_empty());
+    }
+
     #[test]
     fn test_heal_receptivity() {
         let mut ranks = HashMap::new();
@@ -462,4 +824,30 @@ mod tests {
 
         assert_eq!(stats.heal_receptivity, 2 * 10 + 5);
     }
+
+    #[test]
+    fn test_simulate_stats_adds_ranks_and_reports_changes() {
+        let mut base = HashMap::new();
+        base.insert("Atkus".to_string(), 10);
+        base.insert("Knox".to_string(), 3);
+        let multipliers = HashMap::new();
+        let deltas = parse_rank_deltas("Atkus:5, Histia:2,Knox:-10").unwrap();
+        assert_eq!(deltas.get("Knox"), Some(&-10));
+
+        let sim = simulate_stats(&base, &deltas, &multipliers);
+        let expected = {
+            let mut r = HashMap::new();
+            r.insert("Atkus".to_string(), 15);
+            r.insert("Histia".to_string(), 2);
+            compute_fighter_stats(&r, &multipliers)
+        };
+        assert_eq!(sim.after.accuracy, expected.accuracy);
+        assert_eq!(sim.after.trained_ranks, 17);
+        let health = sim.changes.iter().find(|c| c.stat == "health").unwrap();
+        assert_eq!(health.delta, (2 * 111 + 3 * 24) as f64);
+        assert!(sim.changes.iter().all(|c| c.stat != "spirit"));
+
+        assert!(parse_rank_deltas("Evus50").is_err());
+        assert!(parse_rank_deltas(" , ").is_err());
+    }
 }
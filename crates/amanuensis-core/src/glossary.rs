@@ -0,0 +1,154 @@
+//! Personal bestiary: every creature a character has met.
+//!
+//! Folds the character's kill rows (killed, assisted, killed by) and lastys (studied) onto
+//! one entry per creature, keyed by bestiary name so log-name variants ("the Ramandu",
+//! aliases) share an entry, and adds the bestiary family and value. Unlike the kill table
+//! it includes creatures only studied or only died to; unlike the creature catalog it
+//! lists nothing the character hasn't met.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::date::Timestamp;
+use crate::data::CreatureDb;
+use crate::db::Database;
+use crate::error::Result;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GlossaryStudy {
+    /// "Befriend", "Morph" or "Movements".
+    pub lasty_type: String,
+    pub finished: bool,
+    /// Lasty messages seen.
+    pub messages: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct GlossaryEntry {
+    /// Bestiary name, or the log name for creatures the bestiary doesn't know.
+    pub creature: String,
+    pub family: Option<String>,
+    /// Bestiary value; 0 when unknown.
+    pub value: i32,
+    pub killed: i64,
+    pub assisted: i64,
+    pub killed_by: i64,
+    pub studied: Vec<GlossaryStudy>,
+    /// Earliest dated kill or lasty message. Deaths carry no date.
+    pub first_seen: Option<Timestamp>,
+    pub last_seen: Option<Timestamp>,
+}
+
+impl GlossaryEntry {
+    /// Kills, assists and deaths, plus lasty messages.
+    pub fn encounters(&self) -> i64 {
+        self.killed + self.assisted + self.killed_by + self.studied.iter().map(|s| s.messages).sum::<i64>()
+    }
+
+    fn seen(&mut self, first: Option<Timestamp>, last: Option<Timestamp>) {
+        if let Some(d) = first {
+            self.first_seen = Some(self.first_seen.map_or(d, |f| f.min(d)));
+        }
+        self.last_seen = self.last_seen.max(last);
+    }
+}
+
+impl Database {
+    /// The personal bestiary of a (possibly merged) character, alphabetical (ignoring case).
+    pub fn glossary_merged(&self, creatures: &CreatureDb, char_id: i64) -> Result<Vec<GlossaryEntry>> {
+        // Lowercased creature name -> entry
+        let mut by_name: BTreeMap<String, GlossaryEntry> = BTreeMap::new();
+        for kill in self.get_kills_merged(char_id)? {
+            if kill.total_all() == 0 && kill.killed_by_count == 0 {
+                continue;
+            }
+            let entry = entry_for(&mut by_name, creatures, &kill.creature_name);
+            entry.killed += kill.total_solo();
+            entry.assisted += kill.total_assisted();
+            entry.killed_by += kill.killed_by_count;
+            entry.seen(kill.date_first, kill.date_last);
+        }
+        for lasty in self.get_lastys_merged(char_id)? {
+            let entry = entry_for(&mut by_name, creatures, &lasty.creature_name);
+            entry.seen(lasty.first_seen_date, lasty.last_seen_date);
+            match entry.studied.iter_mut().find(|s| s.lasty_type == lasty.lasty_type) {
+                Some(study) => {
+                    study.finished |= lasty.finished;
+                    study.messages += lasty.message_count;
+                }
+                None => entry.studied.push(GlossaryStudy {
+                    lasty_type: lasty.lasty_type,
+                    finished: lasty.finished,
+                    messages: lasty.message_count,
+                }),
+            }
+        }
+        Ok(by_name.into_values().collect())
+    }
+}
+
+/// The entry for `log_name`, created from its bestiary record on first use.
+fn entry_for<'a>(
+    by_name: &'a mut BTreeMap<String, GlossaryEntry>,
+    creatures: &CreatureDb,
+    log_name: &str,
+) -> &'a mut GlossaryEntry {
+    let bestiary = creatures.get_entry(log_name);
+    let name = bestiary.map(|e| e.name.as_str()).unwrap_or(log_name);
+    by_name.entry(name.to_lowercase()).or_insert_with(|| GlossaryEntry {
+        creature: name.to_string(),
+        family: bestiary
+            .and_then(|e| e.family.as_deref())
+            .map(|f| creatures.canonical_family(f).to_string()),
+        value: bestiary.map(|e| e.exp_taxidermy).unwrap_or(0),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{BestiaryEntry, BestiaryFile};
+
+    #[test]
+    fn glossary_merges_kills_and_lastys_per_creature() {
+        let file = BestiaryFile {
+            version: "20260101".into(),
+            entries: vec![
+                BestiaryEntry { name: "Ramandu".into(), family: Some("Ramandu".into()), exp_taxidermy: 300, ..Default::default() },
+                BestiaryEntry { name: "Rat".into(), exp_taxidermy: 2, ..Default::default() },
+                BestiaryEntry { name: "Vermine".into(), exp_taxidermy: 5, ..Default::default() },
+            ],
+        };
+        let creatures = CreatureDb::from_json_bytes(&serde_json::to_vec(&file).unwrap(), b"[]").unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.upsert_kill(c, "Rat", "killed_count", 2, "2024-01-02 10:00:00").unwrap();
+        db.upsert_kill(alt, "Rat", "assisted_kill_count", 2, "2024-01-01 09:00:00").unwrap();
+        db.upsert_kill(c, "the Ramandu", "killed_by_count", 300, "2024-01-03 10:00:00").unwrap();
+        db.upsert_lasty(c, "Ramandu", "Movements", "2024-01-04 10:00:00").unwrap();
+        db.finish_lasty(c, "Maha Ruknee", "Befriend", "2024-01-05 10:00:00").unwrap();
+        db.merge_characters(&[alt], c).unwrap();
+
+        let glossary = db.glossary_merged(&creatures, c).unwrap();
+        let names: Vec<&str> = glossary.iter().map(|e| e.creature.as_str()).collect();
+        assert_eq!(names, vec!["Maha Ruknee", "Ramandu", "Rat"]);
+
+        let maha = &glossary[0];
+        assert_eq!((maha.value, maha.encounters()), (0, 1));
+        assert!(maha.studied[0].finished);
+
+        let ramandu = &glossary[1];
+        assert_eq!((ramandu.killed_by, ramandu.value, ramandu.family.as_deref()), (1, 300, Some("Ramandu")));
+        assert_eq!(ramandu.studied, vec![GlossaryStudy { lasty_type: "Movements".into(), finished: false, messages: 1 }]);
+        assert_eq!(ramandu.first_seen.unwrap(), "2024-01-04 10:00:00");
+
+        let rat = &glossary[2];
+        assert_eq!((rat.killed, rat.assisted, rat.encounters()), (1, 1, 2));
+        assert_eq!(rat.first_seen.unwrap(), "2024-01-01 09:00:00");
+        assert_eq!(rat.last_seen.unwrap(), "2024-01-02 10:00:00");
+    }
+}
@@ -0,0 +1,225 @@
+//! User-configured external commands run when a scan records selected events.
+//!
+//! A hook config is a JSON file:
+//!
+//! ```json
+//! { "hooks": [
+//!     { "event": "lasty_completed", "command": "notify-send", "args": ["Lasty done!"] },
+//!     { "event": "rank", "command": "/usr/local/bin/obs-overlay", "every": 50 }
+//! ] }
+//! ```
+//!
+//! Each matching hook is spawned with the event as a JSON [`HookEvent`] on stdin. Hooks only
+//! fire for events parsed by the scan they are attached to, so attaching them to a full rescan
+//! replays every historical event. They run once that scan has committed (a scan that fails or
+//! is cancelled runs none), one at a time, and a hook still running after `timeout_secs`
+//! (default [`DEFAULT_HOOK_TIMEOUT_SECS`]) is killed.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AmanuensisError, Result};
+
+/// Event names a hook can subscribe to.
+/// `boss_kill` is a solo kill of a creature the bestiary marks as a boss.
+pub const HOOK_EVENT_TYPES: &[&str] =
+    &["kill", "boss_kill", "death", "rank", "lasty_finished", "lasty_completed", "title", "milestone"];
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct Hook {
+    /// One of [`HOOK_EVENT_TYPES`].
+    pub event: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Only fire when the event's running count is a multiple of this (rank milestones);
+    /// ignored for events without a count.
+    #[serde(default)]
+    pub every: Option<i64>,
+    /// Seconds the command may run before it is killed (default [`DEFAULT_HOOK_TIMEOUT_SECS`]).
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// How long a hook may run when its config doesn't say.
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct HookConfig {
+    pub hooks: Vec<Hook>,
+}
+
+/// Payload written to a hook's stdin.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HookEvent {
+    pub event: String,
+    pub character: String,
+    /// Log timestamp of the line that produced the event.
+    pub date: String,
+    /// Creature for kills/boss kills/deaths/lasty_finished/milestones, trainer for ranks/lasty_completed,
+    /// title for titles.
+    pub subject: String,
+    /// Ranks with `subject` after this one, for `rank`; the kill count reached, for
+    /// `milestone`; absent otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+    /// Area the character was in (see `crate::db::queries::ZoneVisit`), when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+}
+
+impl HookConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| AmanuensisError::file_unreadable(path, e))?;
+        let config: HookConfig = serde_json::from_str(&text)?;
+        if let Some(bad) = config.hooks.iter().find(|h| !HOOK_EVENT_TYPES.contains(&h.event.as_str())) {
+            return Err(AmanuensisError::Data(format!(
+                "Unknown hook event '{}' (expected one of: {})",
+                bad.event,
+                HOOK_EVENT_TYPES.join(", ")
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Whether any hook subscribes to `event` (lets the scanner skip building payloads).
+    pub fn wants(&self, event: &str) -> bool {
+        self.hooks.iter().any(|h| h.event == event)
+    }
+
+    fn matching<'a>(&'a self, event: &'a HookEvent) -> impl Iterator<Item = &'a Hook> + 'a {
+        self.hooks.iter().filter(move |h| h.event == event.event && every_matches(h.every, event.count))
+    }
+
+    /// Run every hook matching `event`, waiting for each to exit or time out. Returns one
+    /// message per hook that could not be started, exited unsuccessfully or was killed.
+    pub fn fire(&self, event: &HookEvent) -> Vec<String> {
+        let mut failures = Vec::new();
+        for hook in self.matching(event) {
+            if let Err(msg) = run_hook(hook, event) {
+                failures.push(format!("Hook '{}' for {}: {}", hook.command, event.event, msg));
+            }
+        }
+        failures
+    }
+}
+
+/// An `every` filter: counts that are a multiple of `every`, or any event when either is unset.
+pub(crate) fn every_matches(every: Option<i64>, count: Option<i64>) -> bool {
+    match (every, count) {
+        (Some(every), Some(count)) if every > 0 => count % every == 0,
+        _ => true,
+    }
+}
+
+fn run_hook(hook: &Hook, event: &HookEvent) -> std::result::Result<(), String> {
+    let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+    let mut child = Command::new(&hook.command)
+        .args(&hook.args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores its payload may exit before reading it; that's not a failure.
+        let _ = stdin.write_all(&payload);
+    }
+    let timeout = hook.timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS);
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("timed out after {}s", timeout));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("exited with {}", status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank_event(count: i64) -> HookEvent {
+        HookEvent {
+            event: "rank".into(),
+            character: "Gandor".into(),
+            date: "2024-01-01 12:00:00".parse().unwrap(),
+            subject: "Histia".into(),
+            count: Some(count),
+            area: None,
+        }
+    }
+
+    #[test]
+    fn milestone_filter_and_unknown_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hooks.json");
+        std::fs::write(&path, r#"{"hooks":[{"event":"rank","command":"true","every":50}]}"#).unwrap();
+        let config = HookConfig::load(&path).unwrap();
+        assert!(config.wants("rank") && !config.wants("kill"));
+        assert_eq!(config.matching(&rank_event(49)).count(), 0);
+        assert_eq!(config.matching(&rank_event(100)).count(), 1);
+
+        std::fs::write(&path, r#"{"hooks":[{"event":"ranked","command":"true"}]}"#).unwrap();
+        assert_eq!(HookConfig::load(&path).unwrap_err().kind(), "data");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hook_receives_payload_on_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("payload.json");
+        let config = HookConfig {
+            hooks: vec![Hook {
+                event: "rank".into(),
+                command: "sh".into(),
+                args: vec!["-c".into(), format!("cat > '{}'", out.display())],
+                every: None,
+                timeout_secs: None,
+            }],
+        };
+        assert!(config.fire(&rank_event(7)).is_empty());
+        let payload: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(payload["subject"], "Histia");
+        assert_eq!(payload["count"], 7);
+
+        let failing = HookConfig {
+            hooks: vec![Hook {
+                event: "rank".into(),
+                command: "false".into(),
+                args: vec![],
+                every: None,
+                timeout_secs: None,
+            }],
+        };
+        assert_eq!(failing.fire(&rank_event(1)).len(), 1);
+
+        let hanging = HookConfig {
+            hooks: vec![Hook {
+                event: "rank".into(),
+                command: "sleep".into(),
+                args: vec!["10".into()],
+                every: None,
+                timeout_secs: Some(0),
+            }],
+        };
+        let started = Instant::now();
+        let failures = hanging.fire(&rank_event(1));
+        assert!(failures[0].contains("timed out"), "{failures:?}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,307 @@
+//! Shareable hunt ledger for one play session.
+//!
+//! The `sessions` table only keeps tallies, so the ledger re-reads the session's log file and
+//! classifies the lines between its start and end: who hunted along (clanning, sharing, heals,
+//! falls and raises), what the group killed, loot worth against the character's own share,
+//! and who fell to what. `HuntReport::to_markdown` renders it for posting.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::data::TrainerDb;
+use crate::date::Timestamp;
+use crate::db::Database;
+use crate::encoding::decode_log_bytes;
+use crate::error::{AmanuensisError, Result};
+use crate::models::Session;
+use crate::parser::events::{LogEvent, LootType};
+use crate::parser::line_classifier::classify_line;
+use crate::parser::timestamp::parse_timestamp;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HuntKill {
+    pub creature: String,
+    pub solo: i64,
+    /// "You helped kill …" — kills shared with the party.
+    pub assisted: i64,
+}
+
+/// Loot shares for one item, summed over the session.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HuntLoot {
+    /// Creature and part, e.g. "Orga Anger fur".
+    pub item: String,
+    pub count: i64,
+    /// Full value of the recovered items.
+    pub worth: i64,
+    /// The character's cut of `worth`.
+    pub share: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HuntDeath {
+    pub name: String,
+    pub cause: String,
+    pub date: Timestamp,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HuntReport {
+    pub character: String,
+    pub session: Session,
+    /// Other players seen in the party, alphabetical.
+    pub participants: Vec<String>,
+    /// Most kills first.
+    pub kills: Vec<HuntKill>,
+    /// Most valuable first.
+    pub loot: Vec<HuntLoot>,
+    pub coins_picked_up: i64,
+    /// Party members' falls (the character's own included), in log order.
+    pub deaths: Vec<HuntDeath>,
+}
+
+impl HuntReport {
+    pub fn total_worth(&self) -> i64 {
+        self.loot.iter().map(|l| l.worth).sum()
+    }
+
+    pub fn my_share(&self) -> i64 {
+        self.loot.iter().map(|l| l.share).sum()
+    }
+
+    /// The ledger as a Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let s = &self.session;
+        let mut out = String::new();
+        let _ = writeln!(out, "## Hunt report: {} — {}", self.character, s.started_at);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "{} to {} ({}h {:02}m)",
+            s.started_at,
+            s.ended_at,
+            s.duration_secs / 3600,
+            (s.duration_secs / 60) % 60
+        );
+        let _ = writeln!(out);
+
+        let _ = writeln!(out, "### Party");
+        if self.participants.is_empty() {
+            let _ = writeln!(out, "- {} (solo)", self.character);
+        } else {
+            let _ = writeln!(out, "- {}", self.character);
+            for p in &self.participants {
+                let _ = writeln!(out, "- {}", p);
+            }
+        }
+        let _ = writeln!(out);
+
+        let _ = writeln!(out, "### Kills");
+        if self.kills.is_empty() {
+            let _ = writeln!(out, "None.");
+        } else {
+            let _ = writeln!(out, "| Creature | Solo | Assisted | Total |");
+            let _ = writeln!(out, "|---|---:|---:|---:|");
+            for k in &self.kills {
+                let _ = writeln!(out, "| {} | {} | {} | {} |", k.creature, k.solo, k.assisted, k.solo + k.assisted);
+            }
+            let solo: i64 = self.kills.iter().map(|k| k.solo).sum();
+            let assisted: i64 = self.kills.iter().map(|k| k.assisted).sum();
+            let _ = writeln!(out, "| **Total** | {} | {} | {} |", solo, assisted, solo + assisted);
+        }
+        let _ = writeln!(out);
+
+        let _ = writeln!(out, "### Loot");
+        if self.loot.is_empty() {
+            let _ = writeln!(out, "No loot shares.");
+        } else {
+            let _ = writeln!(out, "| Item | Count | Worth | My share |");
+            let _ = writeln!(out, "|---|---:|---:|---:|");
+            for l in &self.loot {
+                let _ = writeln!(out, "| {} | {} | {}c | {}c |", l.item, l.count, l.worth, l.share);
+            }
+            let _ = writeln!(out, "| **Total** | | {}c | {}c |", self.total_worth(), self.my_share());
+        }
+        if self.coins_picked_up > 0 {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "Coins picked up: {}c", self.coins_picked_up);
+        }
+        let _ = writeln!(out);
+
+        let _ = writeln!(out, "### Deaths");
+        if self.deaths.is_empty() {
+            let _ = writeln!(out, "None.");
+        } else {
+            for d in &self.deaths {
+                let _ = writeln!(out, "- {} fell to {} ({})", d.name, d.cause, d.date);
+            }
+        }
+        out
+    }
+}
+
+impl Database {
+    /// Build the hunt ledger for session `session_id` of a (possibly merged) character by
+    /// re-reading the session's log file.
+    pub fn hunt_report(&self, char_id: i64, session_id: i64) -> Result<HuntReport> {
+        let ids = self.char_ids_for_merged(char_id)?;
+        let session = self
+            .get_session(session_id)?
+            .filter(|s| ids.contains(&s.character_id))
+            .ok_or_else(|| AmanuensisError::Data(format!("No session {} for this character", session_id)))?;
+        let character = self
+            .get_character_by_id(session.character_id)?
+            .ok_or_else(|| AmanuensisError::CharacterNotFound { name: session.character_id.to_string() })?
+            .name;
+        let source = self.log_file_source(&session.log_file)?;
+        let bytes = std::fs::read(&source).map_err(|e| AmanuensisError::file_unreadable(&source, e))?;
+        let trainer_db = TrainerDb::bundled()?;
+        Ok(build_report(&bytes, character, session, &trainer_db))
+    }
+}
+
+/// Tally the session's window of `bytes` (the whole log file) into a report.
+fn build_report(bytes: &[u8], character: String, session: Session, trainer_db: &TrainerDb) -> HuntReport {
+    let content = decode_log_bytes(bytes);
+    let is_me = |name: &str| name.eq_ignore_ascii_case(&character);
+
+    let mut participants = BTreeSet::new();
+    let mut kills: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    let mut loot: BTreeMap<String, HuntLoot> = BTreeMap::new();
+    let mut coins_picked_up = 0;
+    let mut deaths = Vec::new();
+
+    // Untimestamped lines belong to the window of the last timestamp seen.
+    let mut current_date: Option<Timestamp> = None;
+    for line in content.lines() {
+        let message = match parse_timestamp(line) {
+            Some((dt, msg)) => {
+                current_date = Some(dt.into());
+                msg
+            }
+            None => line,
+        };
+        let Some(date) = current_date.filter(|d| *d >= session.started_at && *d <= session.ended_at) else {
+            continue;
+        };
+
+        match classify_line(message, trainer_db) {
+            LogEvent::SoloKill { creature, .. } => kills.entry(creature).or_default().0 += 1,
+            LogEvent::AssistedKill { creature, .. } => kills.entry(creature).or_default().1 += 1,
+            LogEvent::LootShare { item, worth, amount, loot_type } => {
+                let item = match loot_type {
+                    LootType::Fur => format!("{} fur", item),
+                    LootType::Blood => format!("{} blood", item),
+                    LootType::Mandible => format!("{} mandibles", item),
+                    LootType::Other => item,
+                };
+                let entry = loot.entry(item.clone()).or_insert_with(|| HuntLoot { item, count: 0, worth: 0, share: 0 });
+                entry.count += 1;
+                entry.worth += worth;
+                entry.share += amount;
+            }
+            LogEvent::CoinsPickedUp { amount } => coins_picked_up += amount,
+            LogEvent::Fallen { name, cause } => {
+                if !is_me(&name) {
+                    participants.insert(name.clone());
+                }
+                deaths.push(HuntDeath { name, cause, date });
+            }
+            LogEvent::ClanningChange { name, is_clanning: true }
+            | LogEvent::Recovered { name }
+            | LogEvent::SharingStarted { name }
+            | LogEvent::HealGiven { name }
+            | LogEvent::HealReceived { name }
+                if !is_me(&name) =>
+            {
+                participants.insert(name);
+            }
+            _ => {}
+        }
+    }
+
+    let mut kills: Vec<HuntKill> = kills
+        .into_iter()
+        .map(|(creature, (solo, assisted))| HuntKill { creature, solo, assisted })
+        .collect();
+    kills.sort_by_key(|k| std::cmp::Reverse(k.solo + k.assisted));
+    let mut loot: Vec<HuntLoot> = loot.into_values().collect();
+    loot.sort_by_key(|l| std::cmp::Reverse(l.worth));
+
+    HuntReport {
+        character,
+        session,
+        participants: participants.into_iter().collect(),
+        kills,
+        loot,
+        coins_picked_up,
+        deaths,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+1/1/24 9:00:00p You slaughtered a Rat.\r
+1/1/24 10:00:00p Welcome to Clan Lord, Ashwin!\r
+1/1/24 10:00:05p Bramble is now Clanning.\r
+1/1/24 10:01:00p You helped kill an Orga Anger.\r
+1/1/24 10:01:30p You slaughtered a Rat.\r
+1/1/24 10:01:40p You helped vanquish an Orga Anger.\r
+1/1/24 10:02:00p * Bramble recovers the Orga Anger fur, worth 40c. Your share is 20c.\r
+1/1/24 10:03:00p Ashwin has fallen to an Orga Anger.\r
+1/1/24 10:03:30p Corvid heals you.\r
+1/1/24 10:04:00p * You pick up 7 coins.\r
+1/1/24 10:30:00p You slaughtered a Rat.\r
+";
+
+    fn session() -> Session {
+        let mut s = Session::new(1, "log.txt", "2024-01-01 22:00:00".parse().unwrap());
+        s.ended_at = "2024-01-01 22:04:00".parse().unwrap();
+        s.duration_secs = 240;
+        s
+    }
+
+    #[test]
+    fn ledger_covers_only_the_session_window() {
+        let tdb = TrainerDb::bundled().unwrap();
+        let report = build_report(LOG.as_bytes(), "Ashwin".into(), session(), &tdb);
+        assert_eq!(report.participants, vec!["Bramble", "Corvid"]);
+        let kills: Vec<(&str, i64, i64)> =
+            report.kills.iter().map(|k| (k.creature.as_str(), k.solo, k.assisted)).collect();
+        assert_eq!(kills, vec![("Orga Anger", 0, 2), ("Rat", 1, 0)]);
+        assert_eq!((report.total_worth(), report.my_share(), report.coins_picked_up), (40, 20, 7));
+        assert_eq!(report.deaths.len(), 1);
+        assert_eq!(report.deaths[0].name, "Ashwin");
+
+        let md = report.to_markdown();
+        assert!(md.starts_with("## Hunt report: Ashwin — 2024-01-01 22:00:00\n"));
+        assert!(md.contains("| Orga Anger | 0 | 2 | 2 |"));
+        assert!(md.contains("| **Total** | | 40c | 20c |"));
+        assert!(md.contains("- Ashwin fell to Orga Anger (2024-01-01 22:03:00)"));
+    }
+
+    #[test]
+    fn report_rejects_other_characters_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CL Log 2024:01:01 22.00.00.txt");
+        std::fs::write(&path, LOG).unwrap();
+        let db = Database::open_in_memory().unwrap();
+        let ashwin = db.get_or_create_character("Ashwin").unwrap();
+        let other = db.get_or_create_character("Other").unwrap();
+        let mut s = session();
+        s.character_id = ashwin;
+        s.log_file = path.to_string_lossy().to_string();
+        db.upsert_session(&s, false).unwrap();
+        let id = db.get_sessions(ashwin).unwrap()[0].id.unwrap();
+
+        let report = db.hunt_report(ashwin, id).unwrap();
+        assert_eq!(report.character, "Ashwin");
+        assert_eq!(report.kills.len(), 2);
+        assert_eq!(db.hunt_report(other, id).unwrap_err().kind(), "data");
+    }
+}
@@ -0,0 +1,136 @@
+//! Cross-character leaderboards.
+//!
+//! Every visible character (merge targets, never their sources) is scored with the merged
+//! aggregation queries, so an alt folded into a main counts once, under the main.
+
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardMetric {
+    /// Solo + assisted kills.
+    Kills,
+    /// Effective trainer ranks (respects each trainer's rank mode).
+    Ranks,
+    /// Coins picked up plus loot shares and bounties.
+    Coins,
+    Deaths,
+    /// Departs as a percentage of deaths + departs. Characters that never fell are skipped.
+    DepartRate,
+}
+
+impl LeaderboardMetric {
+    pub const ALL: [LeaderboardMetric; 5] = [
+        LeaderboardMetric::Kills,
+        LeaderboardMetric::Ranks,
+        LeaderboardMetric::Coins,
+        LeaderboardMetric::Deaths,
+        LeaderboardMetric::DepartRate,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardMetric::Kills => "kills",
+            LeaderboardMetric::Ranks => "ranks",
+            LeaderboardMetric::Coins => "coins",
+            LeaderboardMetric::Deaths => "deaths",
+            LeaderboardMetric::DepartRate => "depart-rate",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == s.to_lowercase())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LeaderboardEntry {
+    /// 1-based position; tied values share a position.
+    pub position: usize,
+    pub name: String,
+    pub profession: String,
+    pub value: f64,
+}
+
+impl Database {
+    /// Rank every visible character by `metric`, highest first (ties by name).
+    pub fn leaderboard(&self, metric: LeaderboardMetric) -> Result<Vec<LeaderboardEntry>> {
+        let mut scored: Vec<(String, String, f64)> = Vec::new();
+        for listed in self.list_characters()? {
+            let Some(id) = listed.id else { continue };
+            let c = self.get_character_merged(id)?.unwrap_or(listed);
+            let value = match metric {
+                LeaderboardMetric::Kills => {
+                    self.get_kills_merged(id)?.iter().map(|k| k.total_all()).sum::<i64>() as f64
+                }
+                LeaderboardMetric::Ranks => {
+                    self.get_trainers_merged(id)?.iter().map(|t| t.effective_ranks()).sum::<i64>() as f64
+                }
+                LeaderboardMetric::Coins => {
+                    (c.coins_picked_up + c.fur_coins + c.blood_coins + c.mandible_coins + c.bounty_coins) as f64
+                }
+                LeaderboardMetric::Deaths => c.deaths as f64,
+                LeaderboardMetric::DepartRate => {
+                    let exits = c.deaths + c.departs;
+                    if exits == 0 {
+                        continue;
+                    }
+                    (c.departs as f64 / exits as f64 * 1000.0).round() / 10.0
+                }
+            };
+            scored.push((c.name, c.profession.to_string(), value));
+        }
+        scored.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(&b.0)));
+
+        let mut entries: Vec<LeaderboardEntry> = Vec::with_capacity(scored.len());
+        for (i, (name, profession, value)) in scored.into_iter().enumerate() {
+            let position = match entries.last() {
+                Some(prev) if prev.value == value => prev.position,
+                _ => i + 1,
+            };
+            entries.push(LeaderboardEntry { position, name, profession, value });
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_alts_count_once_under_the_target() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Main").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        let other = db.get_or_create_character("Other").unwrap();
+        for (id, deaths) in [(main, 2), (alt, 3), (other, 4)] {
+            db.increment_character_field(id, "logins", 1).unwrap();
+            db.increment_character_field(id, "deaths", deaths).unwrap();
+        }
+        db.merge_characters(&[alt], main).unwrap();
+
+        let board = db.leaderboard(LeaderboardMetric::Deaths).unwrap();
+        let rows: Vec<(usize, &str, f64)> =
+            board.iter().map(|e| (e.position, e.name.as_str(), e.value)).collect();
+        assert_eq!(rows, vec![(1, "Main", 5.0), (2, "Other", 4.0)]);
+    }
+
+    #[test]
+    fn depart_rate_skips_characters_that_never_fell_and_shares_ties() {
+        let db = Database::open_in_memory().unwrap();
+        for (name, deaths, departs) in [("A", 1, 1), ("B", 2, 2), ("C", 0, 0)] {
+            let id = db.get_or_create_character(name).unwrap();
+            db.increment_character_field(id, "logins", 1).unwrap();
+            db.increment_character_field(id, "deaths", deaths).unwrap();
+            db.increment_character_field(id, "departs", departs).unwrap();
+        }
+        let board = db.leaderboard(LeaderboardMetric::DepartRate).unwrap();
+        let rows: Vec<(usize, &str, f64)> =
+            board.iter().map(|e| (e.position, e.name.as_str(), e.value)).collect();
+        assert_eq!(rows, vec![(1, "A", 50.0), (1, "B", 50.0)]);
+        assert_eq!(LeaderboardMetric::parse("Depart-Rate"), Some(LeaderboardMetric::DepartRate));
+    }
+}
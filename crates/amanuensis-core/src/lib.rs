@@ -1,16 +1,61 @@
+#[cfg(feature = "async")]
+pub mod async_db;
+pub mod catalog;
+pub mod compare;
+pub mod creature_names;
 pub mod data;
+pub mod data_dir;
+pub mod date;
 pub mod db;
+pub mod demo;
+pub mod diagnostics;
+pub mod digest;
 pub mod encoding;
 pub mod error;
+pub mod eta;
 pub mod export;
 pub mod fighter_stats;
+pub mod glossary;
+pub mod hooks;
+pub mod hunt_report;
+pub mod leaderboard;
+pub mod log_paths;
 pub mod models;
+pub mod notify;
 pub mod parser;
+pub mod progression;
+pub mod reconcile;
+pub mod report;
+pub mod retention;
+pub mod summary;
+pub mod transcript;
+pub mod untrain;
+pub mod widget;
 
-pub use data::{CreatureDb, TrainerDb, TrainerMeta};
-pub use db::{Database, LogSearchResult, KillsFilter, filter_kills};
+pub use catalog::{CatalogEntry, CreatureCatalog, Encounter, TrainerCoverage, TrainerCoverageEntry};
+pub use compare::{CharacterComparison, KillComparison, StatComparison, TrainerComparison};
+pub use creature_names::{CreatureNameRule, CreatureNameRules, CreatureRegroup};
+pub use data::{CreatureCatalogUpdate, CreatureDb, TrainerCatalogUpdate, TrainerDb, TrainerMeta, CREATURE_CSV_COLUMNS, TRAINER_CSV_COLUMNS};
+pub use db::{Database, LogSearchOptions, LogSearchPage, LogSearchResult, SearchSegment, KillsFilter, filter_kills};
 pub use db::import::{import_scribius, ImportResult};
+pub use diagnostics::{Diagnostics, UnknownLine};
+pub use digest::{CharacterDigest, Digest};
 pub use error::{Result, AmanuensisError};
-pub use export::ExportFormat;
-pub use fighter_stats::compute_fighter_stats;
-pub use parser::{LogParser, pending_files};
+pub use eta::TrainingEta;
+pub use export::{Anonymizer, ExportFormat, ExportTable, SearchExportFormat};
+pub use fighter_stats::{
+    compute_fighter_stats, compute_fighter_stats_with, fighter_rank_map, simulate_stats, simulate_stats_with,
+    FighterLoadout, FighterModifiers,
+};
+pub use glossary::{GlossaryEntry, GlossaryStudy};
+pub use hooks::{HookConfig, HookEvent};
+pub use hunt_report::HuntReport;
+pub use leaderboard::{LeaderboardEntry, LeaderboardMetric};
+pub use log_paths::{PathAlias, PathNormalization};
+pub use notify::{NotifyConfig, Notifier};
+pub use parser::{LogParser, pending_files, pending_complete_files, pending_files_by_character, compare_archives};
+pub use progression::{HuntingProgression, ProgressionCriteria};
+pub use report::RosterReport;
+pub use retention::RetentionPolicy;
+pub use summary::CharacterSummary;
+pub use widget::{Widget, WIDGET_SCHEMA_VERSION};
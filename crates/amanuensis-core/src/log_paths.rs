@@ -0,0 +1,263 @@
+//! Canonical log file paths.
+//!
+//! A file's path is its key in `log_files`, `scan_contributions`, `sessions.log_file` and the
+//! search index, so one archive scanned from macOS and from Windows (say over a network share)
+//! must produce one key, not two. Paths are stored with `/` separators, no doubled or trailing
+//! separators, an upper-case drive letter, and Windows' `\\?\` verbatim prefix removed. Root
+//! aliases (`SETTING_LOG_PATH_ALIASES`) then map a mount point onto a shared name, e.g.
+//! `//nas/games/Clan Lord` and `/Volumes/games/Clan Lord` both onto `Clan Lord`.
+//!
+//! Stored keys are for matching only and are never opened. The scanner reads files through
+//! the path it was given and records that path in `log_files.source_path`; anything reading a
+//! scanned file back (reindexing, hunt reports, `context`) goes through
+//! `Database::log_file_source`.
+
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::db::queries::SETTING_LOG_PATH_ALIASES;
+use crate::db::Database;
+use crate::error::Result;
+
+/// Stored paths starting with `from` (a whole-component prefix, ignoring ASCII case) are
+/// stored starting with `to` instead.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PathAlias {
+    pub from: String,
+    pub to: String,
+}
+
+/// What `normalize_stored_log_paths` changed.
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+pub struct PathNormalization {
+    /// `log_files` rows whose path was rewritten.
+    pub rewritten: usize,
+    /// `log_files` rows dropped as another form of a path already recorded.
+    pub duplicates_removed: usize,
+}
+
+/// `path` with canonical separators. Does not touch the filesystem or resolve `..`.
+pub fn normalize(path: &str) -> String {
+    let mut unified = path.replace('\\', "/");
+    // Verbatim paths from canonicalize on Windows: //?/C:/... and //?/UNC/server/share/...
+    if let Some(rest) = unified.strip_prefix("//?/") {
+        unified = match rest.strip_prefix("UNC/") {
+            Some(unc) => format!("//{unc}"),
+            None => rest.to_string(),
+        };
+    }
+    let (root, rest) = if let Some(rest) = unified.strip_prefix("//") {
+        ("//", rest)
+    } else if let Some(rest) = unified.strip_prefix('/') {
+        ("/", rest)
+    } else {
+        ("", unified.as_str())
+    };
+    let parts: Vec<&str> = rest.split('/').filter(|p| !p.is_empty() && *p != ".").collect();
+    let mut out = format!("{root}{}", parts.join("/"));
+    let bytes = out.as_bytes();
+    if root.is_empty() && bytes.len() >= 2 && bytes[1] == b':' && bytes[0].is_ascii_alphabetic() {
+        out[..1].make_ascii_uppercase();
+    }
+    out
+}
+
+/// The stored key for `path`: normalized, then rewritten by the longest matching alias.
+pub fn key(path: &str, aliases: &[PathAlias]) -> String {
+    let path = normalize(path);
+    let best = aliases
+        .iter()
+        .map(|a| (normalize(&a.from), a))
+        .filter(|(from, _)| !from.is_empty() && has_prefix(&path, from))
+        .max_by_key(|(from, _)| from.len());
+    match best {
+        Some((from, alias)) => {
+            let rest = path[from.len()..].trim_start_matches('/');
+            let to = normalize(&alias.to);
+            match (to.is_empty(), rest.is_empty()) {
+                (true, _) => rest.to_string(),
+                (false, true) => to,
+                (false, false) if to.ends_with('/') => format!("{to}{rest}"),
+                (false, false) => format!("{to}/{rest}"),
+            }
+        }
+        None => path,
+    }
+}
+
+/// Whether `prefix` is `path` or a leading run of its components, ignoring ASCII case.
+fn has_prefix(path: &str, prefix: &str) -> bool {
+    path.len() >= prefix.len()
+        && path.is_char_boundary(prefix.len())
+        && path[..prefix.len()].eq_ignore_ascii_case(prefix)
+        && (path.len() == prefix.len() || prefix.ends_with('/') || path[prefix.len()..].starts_with('/'))
+}
+
+fn parse_aliases(value: Option<String>) -> Vec<PathAlias> {
+    value.and_then(|v| serde_json::from_str(&v).ok()).unwrap_or_default()
+}
+
+/// Rewrite every stored log path to its key, folding rows that now share a key into the one
+/// that got furthest (largest `byte_len`, then latest read). The dropped rows' provenance is
+/// added to the kept path, so it still lists everything those scans wrote.
+/// Runs at every open (from `migrate_tables`) and after the aliases change.
+pub(crate) fn normalize_stored_log_paths(conn: &Connection) -> Result<PathNormalization> {
+    let aliases = parse_aliases(
+        conn.query_row("SELECT value FROM app_settings WHERE key = ?1", params![SETTING_LOG_PATH_ALIASES], |row| row.get(0))
+            .optional()
+            .unwrap_or(None),
+    );
+    let mut stmt = match conn.prepare("SELECT rowid, file_path, byte_len, date_read FROM log_files") {
+        Ok(stmt) => stmt,
+        Err(_) => return Ok(PathNormalization::default()),
+    };
+    let rows: Vec<(i64, String, i64, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // Key -> rows stored under some form of it
+    let mut by_key: BTreeMap<String, Vec<(i64, String, i64, String)>> = BTreeMap::new();
+    for row in rows {
+        by_key.entry(key(&row.1, &aliases)).or_default().push(row);
+    }
+
+    let has_index = conn.prepare("SELECT 1 FROM log_lines LIMIT 0").is_ok();
+    let mut summary = PathNormalization::default();
+    for (key, mut group) in by_key {
+        if group.len() == 1 && group[0].1 == key {
+            continue;
+        }
+        group.sort_by(|a, b| (b.2, &b.3, b.0).cmp(&(a.2, &a.3, a.0)));
+        for (i, (rowid, path, _, _)) in group.iter().enumerate() {
+            if i > 0 {
+                conn.execute("DELETE FROM log_files WHERE rowid = ?1", params![rowid])?;
+                summary.duplicates_removed += 1;
+            }
+            if *path == key {
+                continue;
+            }
+            conn.execute(
+                "INSERT INTO scan_contributions (file_path, character_id, target, key, field, amount)
+                 SELECT ?1, character_id, target, key, field, amount FROM scan_contributions WHERE file_path = ?2
+                 ON CONFLICT(file_path, character_id, target, key, field) DO UPDATE SET
+                     amount = amount + excluded.amount",
+                params![key, path],
+            )?;
+            conn.execute("DELETE FROM scan_contributions WHERE file_path = ?1", params![path])?;
+            conn.execute("UPDATE sessions SET log_file = ?1 WHERE log_file = ?2", params![key, path])?;
+            if has_index {
+                conn.execute("UPDATE log_lines SET file_path = ?1 WHERE file_path = ?2", params![key, path])?;
+            }
+        }
+        if group[0].1 != key {
+            conn.execute("UPDATE log_files SET file_path = ?1 WHERE rowid = ?2", params![key, group[0].0])?;
+            summary.rewritten += 1;
+        }
+    }
+    Ok(summary)
+}
+
+impl Database {
+    pub fn log_path_aliases(&self) -> Result<Vec<PathAlias>> {
+        Ok(parse_aliases(self.get_setting(SETTING_LOG_PATH_ALIASES)?))
+    }
+
+    /// Replace the root aliases (empty clears them) and re-key every stored path to match.
+    pub fn set_log_path_aliases(&self, aliases: &[PathAlias]) -> Result<PathNormalization> {
+        self.begin_transaction()?;
+        let result = (|| {
+            if aliases.is_empty() {
+                self.delete_setting(SETTING_LOG_PATH_ALIASES)?;
+            } else {
+                self.set_setting(SETTING_LOG_PATH_ALIASES, &serde_json::to_string(aliases)?)?;
+            }
+            normalize_stored_log_paths(self.conn())
+        })();
+        match result {
+            Ok(summary) => { self.commit_transaction()?; Ok(summary) }
+            Err(e) => { let _ = self.rollback_transaction(); Err(e) }
+        }
+    }
+
+    /// The key a log file is stored under.
+    pub fn log_path_key(&self, path: &str) -> Result<String> {
+        Ok(key(path, &self.log_path_aliases()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(from: &str, to: &str) -> PathAlias {
+        PathAlias { from: from.into(), to: to.into() }
+    }
+
+    #[test]
+    fn normalize_unifies_platform_forms() {
+        assert_eq!(normalize(r"c:\Games\Clan Lord\\Text Logs\"), "C:/Games/Clan Lord/Text Logs");
+        assert_eq!(normalize(r"\\nas\games\Clan Lord\CL Log 1.txt"), "//nas/games/Clan Lord/CL Log 1.txt");
+        assert_eq!(normalize(r"\\?\UNC\nas\games\a.txt"), "//nas/games/a.txt");
+        assert_eq!(normalize(r"\\?\D:\logs\a.txt"), "D:/logs/a.txt");
+        assert_eq!(normalize("/Users/me/./logs//a.txt"), "/Users/me/logs/a.txt");
+        assert_eq!(normalize("logs/a.txt"), "logs/a.txt");
+    }
+
+    #[test]
+    fn key_applies_longest_alias_on_component_boundaries() {
+        let aliases = [alias(r"\\NAS\games", "archive"), alias("/Volumes/games", "archive"), alias("/Volumes/games/old", "old")];
+        assert_eq!(key(r"\\nas\games\Fen\CL Log 1.txt", &aliases), "archive/Fen/CL Log 1.txt");
+        assert_eq!(key("/Volumes/games/Fen/CL Log 1.txt", &aliases), "archive/Fen/CL Log 1.txt");
+        assert_eq!(key("/Volumes/games/old/a.txt", &aliases), "old/a.txt");
+        assert_eq!(key("/Volumes/gamesX/a.txt", &aliases), "/Volumes/gamesX/a.txt");
+        assert_eq!(key("/Volumes/games", &aliases), "archive");
+    }
+
+    #[test]
+    fn stored_path_variants_are_rekeyed_and_deduped() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Fen").unwrap();
+        let insert = |path: &str, len: i64| {
+            db.conn()
+                .execute(
+                    "INSERT INTO log_files (character_id, file_path, content_hash, byte_len, date_read)
+                     VALUES (?1, ?2, 'h', ?3, '2024-01-01 00:00:00')",
+                    params![c, path, len],
+                )
+                .unwrap();
+        };
+        insert(r"\\nas\games\Fen\CL Log 1.txt", 100);
+        insert("/Volumes/games/Fen/CL Log 1.txt", 250);
+        insert(r"C:\logs\Fen\CL Log 2.txt", 10);
+        for path in [r"\\nas\games\Fen\CL Log 1.txt", "/Volumes/games/Fen/CL Log 1.txt"] {
+            db.conn()
+                .execute(
+                    "INSERT INTO scan_contributions (file_path, character_id, target, key, field, amount)
+                     VALUES (?1, ?2, 'character', '', 'logins', 1)",
+                    params![path, c],
+                )
+                .unwrap();
+        }
+
+        // Separators alone: the share paths still differ, the drive path is rewritten.
+        let summary = normalize_stored_log_paths(db.conn()).unwrap();
+        assert_eq!(summary, PathNormalization { rewritten: 2, duplicates_removed: 0 });
+        assert!(db.is_log_scanned(r"C:\logs\Fen\CL Log 2.txt").unwrap());
+        assert_eq!(db.scanned_log_count().unwrap(), 3);
+
+        let summary = db
+            .set_log_path_aliases(&[alias("//nas/games", "games"), alias("/Volumes/games", "games")])
+            .unwrap();
+        assert_eq!(summary, PathNormalization { rewritten: 1, duplicates_removed: 1 });
+        assert_eq!(db.scanned_log_count().unwrap(), 2);
+        assert_eq!(db.get_log_scan_state(r"\\NAS\games\Fen\CL Log 1.txt").unwrap().map(|s| s.0), Some(250));
+        let contributions = db.get_file_contributions("games/Fen/CL Log 1.txt").unwrap();
+        assert_eq!(contributions.iter().map(|c| c.amount).collect::<Vec<_>>(), vec![2]);
+
+        // Clean: nothing left to do.
+        assert_eq!(normalize_stored_log_paths(db.conn()).unwrap(), PathNormalization::default());
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Profession {
@@ -91,7 +92,7 @@ pub struct Character {
     pub gave_good_karma: i64,
     pub gave_bad_karma: i64,
     // Start date (earliest login timestamp)
-    pub start_date: Option<String>,
+    pub start_date: Option<Timestamp>,
     // Loot worth (total recovered value, not just share)
     pub fur_worth: i64,
     pub mandible_worth: i64,
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
 
 /// A trainer rank checkpoint: a point-in-time reading of a character's rank
 /// range with a specific trainer, derived from the trainer's greeting message.
@@ -10,5 +11,5 @@ pub struct TrainerCheckpoint {
     pub rank_min: i64,
     /// None = maxed (no upper bound)
     pub rank_max: Option<i64>,
-    pub timestamp: String,
+    pub timestamp: Timestamp,
 }
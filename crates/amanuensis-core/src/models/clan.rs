@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
+
+/// A dated clan membership change or title earned, parsed from the logs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClanEvent {
+    pub id: Option<i64>,
+    pub character_id: i64,
+    /// "join", "leave" or "title".
+    pub event_type: String,
+    /// Clan name for join/leave; the title text for titles.
+    pub value: String,
+    pub date: Timestamp,
+}
+
+impl ClanEvent {
+    pub fn new(character_id: i64, event_type: &str, value: &str, date: Timestamp) -> Self {
+        Self {
+            id: None,
+            character_id,
+            event_type: event_type.to_string(),
+            value: value.to_string(),
+            date,
+        }
+    }
+}
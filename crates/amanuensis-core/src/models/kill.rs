@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Kill {
@@ -14,18 +15,18 @@ pub struct Kill {
     pub assisted_vanquish_count: i64,
     pub assisted_dispatch_count: i64,
     pub killed_by_count: i64,
-    pub date_first: Option<String>,
-    pub date_last: Option<String>,
+    pub date_first: Option<Timestamp>,
+    pub date_last: Option<Timestamp>,
     pub creature_value: i32,
     // Per-type first/last dates
-    pub date_first_killed: Option<String>,
-    pub date_first_slaughtered: Option<String>,
-    pub date_first_vanquished: Option<String>,
-    pub date_first_dispatched: Option<String>,
-    pub date_last_killed: Option<String>,
-    pub date_last_slaughtered: Option<String>,
-    pub date_last_vanquished: Option<String>,
-    pub date_last_dispatched: Option<String>,
+    pub date_first_killed: Option<Timestamp>,
+    pub date_first_slaughtered: Option<Timestamp>,
+    pub date_first_vanquished: Option<Timestamp>,
+    pub date_first_dispatched: Option<Timestamp>,
+    pub date_last_killed: Option<Timestamp>,
+    pub date_last_slaughtered: Option<Timestamp>,
+    pub date_last_vanquished: Option<Timestamp>,
+    pub date_last_dispatched: Option<Timestamp>,
     pub best_loot_value: i64,
     pub best_loot_item: String,
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LastyType {
@@ -40,10 +41,10 @@ pub struct Lasty {
     pub lasty_type: String,
     pub finished: bool,
     pub message_count: i64,
-    pub first_seen_date: Option<String>,
-    pub last_seen_date: Option<String>,
-    pub completed_date: Option<String>,
-    pub abandoned_date: Option<String>,
+    pub first_seen_date: Option<Timestamp>,
+    pub last_seen_date: Option<Timestamp>,
+    pub completed_date: Option<Timestamp>,
+    pub abandoned_date: Option<Timestamp>,
 }
 
 impl Lasty {
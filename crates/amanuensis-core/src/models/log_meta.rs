@@ -1,15 +1,16 @@
 use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogMeta {
     pub id: Option<i64>,
     pub character_id: i64,
     pub file_path: String,
-    pub date_read: String,
+    pub date_read: Timestamp,
 }
 
 impl LogMeta {
-    pub fn new(character_id: i64, file_path: String, date_read: String) -> Self {
+    pub fn new(character_id: i64, file_path: String, date_read: Timestamp) -> Self {
         Self {
             id: None,
             character_id,
@@ -1,17 +1,23 @@
 pub mod character;
 pub mod checkpoint;
+pub mod clan;
 pub mod kill;
 pub mod lasty;
 pub mod log_meta;
 pub mod pet;
 pub mod process_log;
+pub mod scan_error;
+pub mod session;
 pub mod trainer;
 
 pub use character::{Character, Profession};
 pub use checkpoint::TrainerCheckpoint;
+pub use clan::ClanEvent;
 pub use kill::Kill;
 pub use lasty::{Lasty, LastyType};
 pub use log_meta::LogMeta;
-pub use pet::Pet;
+pub use pet::{Pet, PET_STATUSES};
 pub use process_log::ProcessLog;
-pub use trainer::{RankMode, Trainer};
+pub use scan_error::ScanError;
+pub use session::Session;
+pub use trainer::{RankMode, Trainer, APPLY_LEARNING_PARTIAL_ESTIMATE};
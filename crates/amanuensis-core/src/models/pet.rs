@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
+
+/// Values of `pets.status`.
+pub const PET_STATUSES: &[&str] = &["active", "dead", "released"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pet {
@@ -6,6 +10,19 @@ pub struct Pet {
     pub character_id: i64,
     pub pet_name: String,
     pub creature_name: String,
+    /// One of [`PET_STATUSES`]: "dead" until the pet is seen alive again.
+    pub status: String,
+    pub adopted_date: Option<Timestamp>,
+    pub death_count: i64,
+    pub feed_count: i64,
+    pub last_fed_date: Option<Timestamp>,
+    pub released_date: Option<Timestamp>,
+    /// Ranks gained, from `pet_ranks`.
+    pub ranks: i64,
+    /// Training sessions, from `pet_ranks`.
+    pub trainings: i64,
+    /// Latest training session or rank gain.
+    pub last_trained_date: Option<Timestamp>,
 }
 
 impl Pet {
@@ -15,6 +32,15 @@ impl Pet {
             character_id,
             pet_name,
             creature_name,
+            status: "active".to_string(),
+            adopted_date: None,
+            death_count: 0,
+            feed_count: 0,
+            last_fed_date: None,
+            released_date: None,
+            ranks: 0,
+            trainings: 0,
+            last_trained_date: None,
         }
     }
 }
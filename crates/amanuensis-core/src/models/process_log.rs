@@ -1,9 +1,10 @@
 use serde::Serialize;
+use crate::date::Timestamp;
 
 #[derive(Debug, Serialize)]
 pub struct ProcessLog {
     pub id: i64,
-    pub created_at: String,
+    pub created_at: Timestamp,
     pub level: String,
     pub message: String,
 }
@@ -0,0 +1,16 @@
+use serde::Serialize;
+use crate::date::Timestamp;
+
+/// A log file that failed to read or scan. Kept until the file scans cleanly.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScanError {
+    /// The path the scanner was given, so the file can be re-tried from it.
+    pub file_path: String,
+    /// 1-based line being scanned when it failed; None for read errors and failures
+    /// outside the line loop.
+    pub line: Option<i64>,
+    /// `AmanuensisError::kind` of the failure.
+    pub kind: String,
+    pub message: String,
+    pub created_at: Timestamp,
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
+
+/// One play session: from a `Welcome` line to the next disconnect, welcome, or end of log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Session {
+    pub id: Option<i64>,
+    pub character_id: i64,
+    pub log_file: String,
+    pub started_at: Timestamp,
+    pub ended_at: Timestamp,
+    /// Seconds between `started_at` and `ended_at` (derived on read).
+    pub duration_secs: i64,
+    /// Solo and assisted kills.
+    pub kills: i64,
+    pub ranks: i64,
+    /// Coins picked up plus loot shares.
+    pub coins: i64,
+    pub deaths: i64,
+}
+
+impl Session {
+    pub fn new(character_id: i64, log_file: &str, started_at: Timestamp) -> Self {
+        Self {
+            id: None,
+            character_id,
+            log_file: log_file.to_string(),
+            started_at,
+            ended_at: started_at,
+            duration_secs: 0,
+            kills: 0,
+            ranks: 0,
+            coins: 0,
+            deaths: 0,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::date::Timestamp;
 
 /// The three rank-tracking modes for a trainer record.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -41,20 +42,38 @@ impl std::fmt::Display for RankMode {
     }
 }
 
+/// Ranks assumed per partial ("more") apply-learning event, which grants 1-9 ranks.
+pub const APPLY_LEARNING_PARTIAL_ESTIMATE: i64 = 5;
+
+/// A trainer's total is built from four sources, kept apart so it can be audited against an
+/// in-game trainer query: `ranks` (counted from log messages), `apply_learning_ranks`
+/// (confirmed "much more" apply-learning, 10 each), `apply_learning_estimated_ranks` (partial
+/// apply-learning, whose exact amount is unknown) and `modified_ranks` (entered manually).
+/// The estimate is informational and not part of `effective_ranks`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trainer {
     pub id: Option<i64>,
     pub character_id: i64,
     pub trainer_name: String,
+    /// Ranks counted from trainer messages in the logs.
     pub ranks: i64,
+    /// Manually entered ranks (a modifier, or the baseline in the override modes).
     pub modified_ranks: i64,
-    pub date_of_last_rank: Option<String>,
+    pub date_of_last_rank: Option<Timestamp>,
+    /// Confirmed apply-learning ranks.
     pub apply_learning_ranks: i64,
+    /// Partial apply-learning events.
     pub apply_learning_unknown_count: i64,
+    /// `apply_learning_unknown_count` × `APPLY_LEARNING_PARTIAL_ESTIMATE`.
+    #[serde(default)]
+    pub apply_learning_estimated_ranks: i64,
     pub rank_mode: String,
     pub override_date: Option<String>,
     pub effective_multiplier: f64,
     pub notes: Option<String>,
+    /// The trainer said it has nothing more to teach (the character is at its cap).
+    #[serde(default)]
+    pub maxed: bool,
 }
 
 impl Trainer {
@@ -68,10 +87,12 @@ impl Trainer {
             date_of_last_rank: None,
             apply_learning_ranks: 0,
             apply_learning_unknown_count: 0,
+            apply_learning_estimated_ranks: 0,
             rank_mode: RankMode::Modifier.as_str().to_string(),
             override_date: None,
             effective_multiplier: 1.0,
             notes: None,
+            maxed: false,
         }
     }
 
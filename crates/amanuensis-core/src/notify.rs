@@ -0,0 +1,227 @@
+//! Discord webhook notifications for notable events.
+//!
+//! A notify config is a JSON file:
+//!
+//! ```json
+//! { "webhook_url": "https://discord.com/api/webhooks/...",
+//!   "triggers": [
+//!     { "event": "death" },
+//!     { "event": "boss_kill", "template": "{character} took down {subject}!" },
+//!     { "event": "rank", "every": 1000 },
+//!     { "event": "lasty_completed" }
+//! ] }
+//! ```
+//!
+//! Triggers use the hook event names (`crate::hooks::HOOK_EVENT_TYPES`) and the same `every`
+//! filter. Templates fill in `{character}`, `{subject}`, `{count}`, `{date}`, `{event}` and
+//! `{area}` (where the character was, empty when unknown); each event has a default. The scanner queues messages in a [`Notifier`] and the caller
+//! sends them after each pass, so a notifier attached to a first full scan doesn't flood the
+//! channel: past `max_per_pass` the rest collapse into one "and N more" line.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::{AmanuensisError, Result};
+use crate::hooks::{every_matches, HookEvent, HOOK_EVENT_TYPES};
+
+/// Discord rejects message content longer than this.
+pub const DISCORD_MAX_CONTENT: usize = 2000;
+
+fn default_max_per_pass() -> usize {
+    5
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct NotifyTrigger {
+    /// One of [`HOOK_EVENT_TYPES`].
+    pub event: String,
+    /// Message text; [`default_template`] for the event when absent.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Only when the event's count is a multiple of this (e.g. every 1000th rank).
+    #[serde(default)]
+    pub every: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct NotifyConfig {
+    pub webhook_url: String,
+    /// Overrides the webhook's display name.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Messages sent per scan pass before the rest are summarized.
+    #[serde(default = "default_max_per_pass")]
+    pub max_per_pass: usize,
+    pub triggers: Vec<NotifyTrigger>,
+}
+
+/// Message text used when a trigger has no template.
+pub fn default_template(event: &str) -> &'static str {
+    match event {
+        "kill" => "{character} killed {subject}.",
+        "boss_kill" => "**{character}** killed {subject}!",
+        "death" => "**{character}** has fallen to {subject}.",
+        "rank" => "**{character}** reached {count} ranks with {subject}.",
+        "lasty_finished" => "{character} finished studying {subject}.",
+        "lasty_completed" => "**{character}** completed the {subject} lasty!",
+        "title" => "**{character}** earned the title {subject}.",
+        "milestone" => "**{character}** reached {count} kills of {subject}.",
+        _ => "{character}: {event} {subject}",
+    }
+}
+
+/// Fill in a template's placeholders from `event`; unknown placeholders are left as written.
+pub fn render_template(template: &str, event: &HookEvent) -> String {
+    template
+        .replace("{character}", &event.character)
+        .replace("{subject}", &event.subject)
+        .replace("{count}", &event.count.map(|c| c.to_string()).unwrap_or_default())
+        .replace("{date}", &event.date)
+        .replace("{event}", &event.event)
+        .replace("{area}", event.area.as_deref().unwrap_or(""))
+}
+
+impl NotifyConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| AmanuensisError::file_unreadable(path, e))?;
+        let config: NotifyConfig = serde_json::from_str(&text)?;
+        if !config.webhook_url.starts_with("https://") && !config.webhook_url.starts_with("http://") {
+            return Err(AmanuensisError::Data(format!(
+                "webhook_url must be an http(s) URL, got '{}'",
+                config.webhook_url
+            )));
+        }
+        if let Some(bad) = config.triggers.iter().find(|t| !HOOK_EVENT_TYPES.contains(&t.event.as_str())) {
+            return Err(AmanuensisError::Data(format!(
+                "Unknown notify event '{}' (expected one of: {})",
+                bad.event,
+                HOOK_EVENT_TYPES.join(", ")
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Whether any trigger is for `event` (lets the scanner skip building payloads).
+    pub fn wants(&self, event: &str) -> bool {
+        self.triggers.iter().any(|t| t.event == event)
+    }
+
+    /// One message per trigger matching `event`.
+    pub fn render(&self, event: &HookEvent) -> Vec<String> {
+        self.triggers
+            .iter()
+            .filter(|t| t.event == event.event && every_matches(t.every, event.count))
+            .map(|t| render_template(t.template.as_deref().unwrap_or(default_template(&t.event)), event))
+            .collect()
+    }
+
+    /// The webhook request body for one message. Mentions are disabled, so a character
+    /// name or title can't ping the channel.
+    pub fn discord_payload(&self, content: &str) -> serde_json::Value {
+        let content: String = content.chars().take(DISCORD_MAX_CONTENT).collect();
+        let mut payload = serde_json::json!({ "content": content, "allowed_mentions": { "parse": [] } });
+        if let Some(username) = &self.username {
+            payload["username"] = serde_json::Value::String(username.clone());
+        }
+        payload
+    }
+}
+
+/// Messages queued during a scan, waiting to be sent.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    pub config: NotifyConfig,
+    pending: Vec<String>,
+}
+
+impl Notifier {
+    pub fn new(config: NotifyConfig) -> Self {
+        Self { config, pending: Vec::new() }
+    }
+
+    pub fn wants(&self, event: &str) -> bool {
+        self.config.wants(event)
+    }
+
+    pub fn notify(&mut self, event: &HookEvent) {
+        self.pending.extend(self.config.render(event));
+    }
+
+    /// Take the queued messages, at most `max_per_pass` of them with any overflow replaced
+    /// by a count.
+    pub fn take(&mut self) -> Vec<String> {
+        let mut messages = std::mem::take(&mut self.pending);
+        let max = self.config.max_per_pass.max(1);
+        if messages.len() > max {
+            let more = messages.len() - (max - 1);
+            messages.truncate(max - 1);
+            messages.push(format!("...and {} more notable event{}.", more, if more == 1 { "" } else { "s" }));
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: &str, subject: &str, count: Option<i64>) -> HookEvent {
+        HookEvent {
+            event: event.into(),
+            character: "Gandor".into(),
+            date: "2024-01-01 12:00:00".parse().unwrap(),
+            subject: subject.into(),
+            count,
+            area: None,
+        }
+    }
+
+    #[test]
+    fn config_validation_and_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify.json");
+        std::fs::write(
+            &path,
+            r#"{"webhook_url":"https://discord.com/api/webhooks/1/x","triggers":[
+                {"event":"rank","every":1000},
+                {"event":"boss_kill","template":"{character} downed {subject} at {date} ({missing})"}]}"#,
+        )
+        .unwrap();
+        let config = NotifyConfig::load(&path).unwrap();
+        assert_eq!(config.max_per_pass, 5);
+        assert!(config.wants("boss_kill") && !config.wants("death"));
+        assert!(config.render(&event("rank", "Histia", Some(999))).is_empty());
+        assert_eq!(config.render(&event("rank", "Histia", Some(1000))), ["**Gandor** reached 1000 ranks with Histia."]);
+        assert_eq!(
+            config.render(&event("boss_kill", "Kolar Bear", None)),
+            ["Gandor downed Kolar Bear at 2024-01-01 12:00:00 ({missing})"]
+        );
+        let payload = config.discord_payload(&"x".repeat(3000));
+        assert_eq!(payload["content"].as_str().unwrap().len(), DISCORD_MAX_CONTENT);
+        assert_eq!(payload["allowed_mentions"]["parse"], serde_json::json!([]));
+
+        std::fs::write(&path, r#"{"webhook_url":"https://x","triggers":[{"event":"levelup"}]}"#).unwrap();
+        assert!(NotifyConfig::load(&path).unwrap_err().to_string().contains("levelup"));
+        std::fs::write(&path, r#"{"webhook_url":"discord","triggers":[]}"#).unwrap();
+        assert!(NotifyConfig::load(&path).is_err());
+    }
+
+    #[test]
+    fn overflow_collapses_into_a_count() {
+        let config = NotifyConfig {
+            webhook_url: "https://x".into(),
+            username: None,
+            max_per_pass: 3,
+            triggers: vec![NotifyTrigger { event: "death".into(), template: Some("{subject}".into()), every: None }],
+        };
+        let mut notifier = Notifier::new(config);
+        for cause in ["Rat", "Vermine", "Orga", "Noth", "Maha"] {
+            notifier.notify(&event("death", cause, None));
+        }
+        assert_eq!(notifier.take(), ["Rat", "Vermine", "...and 3 more notable events."]);
+        assert!(notifier.take().is_empty());
+    }
+}
@@ -0,0 +1,79 @@
+//! Attributing assisted kills to the player who landed the final blow.
+//!
+//! "You helped kill a Rat." doesn't say who finished the creature, but that player's own line
+//! ("Gandor killed a Rat.") appears within a few seconds of it, before or after. The tracker
+//! pairs each assist with another player's kill of the same creature within
+//! [`ASSIST_WINDOW_SECS`], oldest unpaired line first.
+
+use std::collections::VecDeque;
+
+/// How far apart (in seconds) an assist and another player's kill may be and still pair.
+pub const ASSIST_WINDOW_SECS: i64 = 5;
+
+/// An assist paired with the player who landed the final blow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribution {
+    pub char_id: i64,
+    pub creature: String,
+    pub player: String,
+    /// Date of the assist line.
+    pub date: String,
+}
+
+#[derive(Debug, Default)]
+pub struct AssistTracker {
+    /// Unpaired assists: (character_id, creature, epoch, date).
+    assists: VecDeque<(i64, String, i64, String)>,
+    /// Unpaired kills by other players: (player, creature, epoch).
+    kills: VecDeque<(String, String, i64)>,
+}
+
+impl AssistTracker {
+    /// The active character helped kill `creature`.
+    pub fn assisted(&mut self, char_id: i64, creature: &str, epoch: i64, date: &str) -> Option<Attribution> {
+        self.expire(epoch);
+        if let Some(i) = self.kills.iter().position(|(_, c, _)| c == creature) {
+            let (player, creature, _) = self.kills.remove(i)?;
+            return Some(Attribution { char_id, creature, player, date: date.to_string() });
+        }
+        self.assists.push_back((char_id, creature.to_string(), epoch, date.to_string()));
+        None
+    }
+
+    /// Another player killed `creature`.
+    pub fn other_kill(&mut self, player: &str, creature: &str, epoch: i64) -> Option<Attribution> {
+        self.expire(epoch);
+        if let Some(i) = self.assists.iter().position(|(_, c, _, _)| c == creature) {
+            let (char_id, creature, _, date) = self.assists.remove(i)?;
+            return Some(Attribution { char_id, creature, player: player.to_string(), date });
+        }
+        self.kills.push_back((player.to_string(), creature.to_string(), epoch));
+        None
+    }
+
+    fn expire(&mut self, now: i64) {
+        self.assists.retain(|(_, _, t, _)| (now - t).abs() <= ASSIST_WINDOW_SECS);
+        self.kills.retain(|(_, _, t)| (now - t).abs() <= ASSIST_WINDOW_SECS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_assists_with_nearby_kills_either_way_round() {
+        let mut t = AssistTracker::default();
+        // Killer's line first.
+        assert!(t.other_kill("Gandor", "Rat", 100).is_none());
+        let a = t.assisted(1, "Rat", 101, "d1").unwrap();
+        assert_eq!((a.player.as_str(), a.date.as_str()), ("Gandor", "d1"));
+        // Assist first; a kill of another creature doesn't pair.
+        assert!(t.assisted(1, "Orga Anger", 200, "d2").is_none());
+        assert!(t.other_kill("Fen", "Rat", 201).is_none());
+        assert_eq!(t.other_kill("Fen", "Orga Anger", 202).unwrap().player, "Fen");
+        // Too far apart.
+        assert!(t.assisted(1, "Vermine", 300, "d3").is_none());
+        assert!(t.other_kill("Fen", "Vermine", 300 + ASSIST_WINDOW_SECS + 1).is_none());
+    }
+}
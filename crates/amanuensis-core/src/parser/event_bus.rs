@@ -0,0 +1,115 @@
+//! Event bus: fans each parsed event out to subscribers while the parser writes it to the
+//! database.
+//!
+//! A scan publishes every classified (non-ignored) event, in log order, as a
+//! [`StreamedEvent`]. Subscribers see only what the scan actually reads, so an incremental
+//! or watch-mode update publishes just the new lines. `scan --emit-events` is a [`JsonLines`]
+//! subscriber; `watch --ws` adds one that pushes to WebSocket clients.
+
+use std::io::Write;
+
+use crate::parser::events::StreamedEvent;
+
+/// Receives events from a scan. Runs on the scanning thread, so it should hand work off
+/// rather than block.
+pub trait EventSubscriber: Send {
+    /// Called once per event. Returning an error unsubscribes this subscriber; the scan
+    /// carries on.
+    fn publish(&mut self, event: &StreamedEvent<'_>) -> std::io::Result<()>;
+}
+
+/// Writes each event as one line of JSON (NDJSON).
+pub struct JsonLines<W>(pub W);
+
+impl<W: Write + Send> EventSubscriber for JsonLines<W> {
+    fn publish(&mut self, event: &StreamedEvent<'_>) -> std::io::Result<()> {
+        let json = serde_json::to_string(event)?;
+        writeln!(self.0, "{json}")
+    }
+}
+
+/// Returned by [`EventBus::subscribe`], to unsubscribe later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionId(u64);
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Vec<(SubscriptionId, Box<dyn EventSubscriber>)>,
+    next_id: u64,
+}
+
+impl EventBus {
+    pub fn subscribe(&mut self, subscriber: Box<dyn EventSubscriber>) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.subscribers.push((id, subscriber));
+        id
+    }
+
+    /// Remove a subscriber (dropping it, which flushes buffered writers). False if it was
+    /// already gone.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let before = self.subscribers.len();
+        self.subscribers.retain(|(s, _)| *s != id);
+        self.subscribers.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// Deliver `event` to every subscriber, dropping the ones that fail. Returns their errors.
+    pub fn publish(&mut self, event: &StreamedEvent<'_>) -> Vec<(SubscriptionId, std::io::Error)> {
+        let mut failed = Vec::new();
+        self.subscribers.retain_mut(|(id, subscriber)| match subscriber.publish(event) {
+            Ok(()) => true,
+            Err(e) => {
+                failed.push((*id, e));
+                false
+            }
+        });
+        failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::events::LogEvent;
+    use std::sync::{Arc, Mutex};
+
+    struct Collect(Arc<Mutex<Vec<String>>>);
+    impl EventSubscriber for Collect {
+        fn publish(&mut self, event: &StreamedEvent<'_>) -> std::io::Result<()> {
+            self.0.lock().unwrap().push(event.character.to_string());
+            Ok(())
+        }
+    }
+
+    struct Broken;
+    impl EventSubscriber for Broken {
+        fn publish(&mut self, _: &StreamedEvent<'_>) -> std::io::Result<()> {
+            Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "gone"))
+        }
+    }
+
+    #[test]
+    fn failing_subscribers_drop_out_others_keep_receiving() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut bus = EventBus::default();
+        let collect = bus.subscribe(Box::new(Collect(Arc::clone(&seen))));
+        let broken = bus.subscribe(Box::new(Broken));
+        let event = LogEvent::Login { name: "Fen".into() };
+        let streamed = StreamedEvent { character: "Fen", date: "2024-01-01 13:00:00", file: "f", area: None, event: &event };
+
+        let failed = bus.publish(&streamed);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, broken);
+        assert!(bus.publish(&streamed).is_empty());
+        assert_eq!(*seen.lock().unwrap(), ["Fen", "Fen"]);
+
+        assert!(!bus.unsubscribe(broken));
+        assert!(bus.unsubscribe(collect));
+        assert!(bus.is_empty());
+    }
+}
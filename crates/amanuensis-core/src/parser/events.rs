@@ -1,7 +1,9 @@
 use chrono::NaiveDateTime;
+use serde::Serialize;
 
 /// Represents a single parsed event from a log line.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum KillVerb {
     Killed,
     Slaughtered,
@@ -20,7 +22,10 @@ impl std::fmt::Display for KillVerb {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Serialized (for `LogParser::set_event_stream`) as `{"type": "solo_kill", "data": {...}}`;
+/// unit variants have no `data`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
 pub enum LogEvent {
     /// Character logged in: Welcome to Clan Lord, {name}!
     Login { name: String },
@@ -30,10 +35,34 @@ pub enum LogEvent {
     SoloKill { creature: String, verb: KillVerb },
     /// Assisted kill: You helped {verb} a/an {creature}.
     AssistedKill { creature: String, verb: KillVerb },
+    /// Another player's kill: {player} {verb} a/an/the {creature}.
+    OtherKill { player: String, creature: String, verb: KillVerb },
+    /// Melee hit: You hit a/an/the {creature}.
+    AttackHit { creature: String },
+    /// Melee miss: You swing at a/an/the {creature} and miss. / You miss a/an/the {creature}.
+    AttackMiss { creature: String },
+    /// Damage taken: The/A {creature} hits/bites/claws/... you.
+    DamageTaken { creature: String },
     /// Character fell: {name} has fallen to a/an {creature/cause}.
     Fallen { name: String, cause: String },
     /// Character recovered: {name} is no longer fallen.
     Recovered { name: String },
+    /// Healed by another player: {name} heals you.
+    HealReceived { name: String },
+    /// Healed another player: You heal {name}.
+    HealGiven { name: String },
+    /// Dragged by another player: {name} starts dragging you. / {name} is dragging you.
+    DraggedBy { name: String },
+    /// Raised by another player: {name} raises you.
+    RaisedBy { name: String },
+    /// Raised another player: You raise {name}.
+    RaisedOther { name: String },
+    /// Private thought received: {name} thinks to you, "…"
+    ThoughtReceived { name: String },
+    /// Private thought sent: You think to {name}, "…"
+    ThoughtSent { name: String },
+    /// Started sharing with another player: You are now sharing experiences with {name}.
+    SharingStarted { name: String },
     /// Spirit depart (first time)
     FirstDepart,
     /// Spirit depart with count
@@ -95,8 +124,37 @@ pub enum LogEvent {
     KarmaGiven { good: bool },
     /// Esteem gain: "* You gain esteem." or "* You gain experience and esteem."
     EsteemGain,
-    /// Profession announcement from NPC (circle test or "become a" message)
-    ProfessionAnnouncement { name: String, profession: String },
+    /// Profession announcement from NPC (circle test or "become a" message). `circle` is the
+    /// circle number of a circle test.
+    ProfessionAnnouncement { name: String, profession: String, circle: Option<i64> },
+    /// Clan joined: "You are now a member of {clan}."
+    ClanJoined { clan: String },
+    /// Clan left: "You are no longer a member of {clan}."
+    ClanLeft { clan: String },
+    /// Title earned. `name` is None for the first-person message, or the character named
+    /// in an NPC announcement (may differ from the log owner).
+    TitleEarned { name: Option<String>, title: String },
+    /// Arena entered: "You enter the {arena}."
+    ArenaEntered { arena: String },
+    /// Arena bout against another player: "You defeated {opponent} in the arena." (won) or
+    /// "You were defeated by {opponent} in the arena." (lost)
+    ArenaBout { opponent: String, won: bool },
+    /// Tournament result: "{winner} has won the tournament!" (may name someone else)
+    TournamentWon { winner: String },
+    /// Healer pet adopted: "The {creature} is now your pet."
+    PetAdopted { creature: String },
+    /// Pet named: "You name your {pet} "{name}"." (`pet` is its creature or old name)
+    PetNamed { pet: String, name: String },
+    /// Pet fed: "You feed your pet {pet}."
+    PetFed { pet: String },
+    /// Pet died: "Your pet {pet} has died."
+    PetDied { pet: String },
+    /// Pet released: "You release your pet {pet}."
+    PetReleased { pet: String },
+    /// Pet training session: "You train your pet {pet}."
+    PetTraining { pet: String },
+    /// Pet gained a rank: "Your pet {pet} gains a rank!"
+    PetRank { pet: String },
     /// Ore found: "You found a lump of {type} ore!"
     /// The string is the ore type in lowercase, e.g. "iron", "copper", "tin", "gold".
     OreFound(String),
@@ -108,6 +166,12 @@ pub enum LogEvent {
     FishingMiss,
     /// Fish caught: "You reel in a/an {item}." — item is normalized (e.g. "Fish", "Mimic", "Sea Bass")
     FishCaught { item: String },
+    /// Stat boost from a consumable or shrine blessing: "You feel {stat}." — stat is the
+    /// lowercase adjective, e.g. "stronger", "more agile", "blessed". `permanent` for
+    /// "You feel permanently {stat}."; otherwise the boost lasts until it fades.
+    StatBoost { stat: String, permanent: bool },
+    /// A temporary boost wearing off: "You no longer feel {stat}."
+    StatBoostFaded { stat: String },
     /// Ranger reflect: a study-list header ("You have studied/learned to befriend/...
     /// the following creatures:"). `lasty_type` is the type the following list belongs to
     /// (Movements / Befriend / Morph).
@@ -126,6 +190,13 @@ pub enum LogEvent {
     /// Trainer checkpoint message without greeting prefix (bow sequence step 3):
     /// Trainer says, "{known_checkpoint_message}" — character name comes from the preceding greeting.
     TrainerCheckpointUnhailed { trainer_name: String, rank_min: i64, rank_max: Option<i64> },
+    /// Trainer has nothing more to teach: a greeting ("Hail, Name. There is nothing I can
+    /// teach you.", `character_name` = who was addressed) or the "{trainer} has nothing more
+    /// to teach you." system message (`character_name` = None, the log owner).
+    TrainerMaxed { trainer_name: String, character_name: Option<String> },
+    /// Maxed message spoken without a greeting (bow sequence step 3), like
+    /// `TrainerCheckpointUnhailed`.
+    TrainerMaxedUnhailed { trainer_name: String },
     /// TRAINER_GREETING matched (has rank text after "Hail, Name.") but rank message
     /// not found in checkpoint DB — likely a missing entry in rankmessages.
     TrainerGreetingWithUnknownCheckpoint {
@@ -137,7 +208,8 @@ pub enum LogEvent {
     Ignored,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum LootType {
     Fur,
     Blood,
@@ -145,6 +217,24 @@ pub enum LootType {
     Other,
 }
 
+/// One event as published on the parser's event bus (`event_bus`); `set_event_stream`
+/// writes each as a JSON object per line.
+#[derive(Debug, Serialize)]
+pub struct StreamedEvent<'a> {
+    /// The character the line is attributed to.
+    pub character: &'a str,
+    /// Storage-format date of the line (the last seen timestamp for untimestamped lines).
+    pub date: &'a str,
+    /// Stored key of the log file (see `crate::log_paths`).
+    pub file: &'a str,
+    /// Area the character is in, as inferred from the creatures it fights (see
+    /// `crate::db::queries::ZoneVisit`); absent until one is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area: Option<&'a str>,
+    #[serde(flatten)]
+    pub event: &'a LogEvent,
+}
+
 /// A log line with its parsed timestamp and event.
 #[derive(Debug, Clone)]
 pub struct ParsedLine {
@@ -0,0 +1,111 @@
+//! Golden-log regression suite.
+//!
+//! Scans the anonymized logs in `tests/fixtures/golden_logs` and compares the resulting
+//! aggregates against `tests/fixtures/golden_logs/expected.json`. Any change to patterns,
+//! classification or scan bookkeeping that alters stats shows up as a diff here.
+//!
+//! When a change is intentional, regenerate the expectations with
+//! `AMANUENSIS_BLESS=1 cargo test -p amanuensis-core golden` and review the diff.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::db::Database;
+use crate::parser::LogParser;
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden_logs")
+}
+
+/// Drop row ids (they depend on insert order, not on what was parsed) and sort object
+/// keys so `HashMap` fields such as `fishing_catches` serialize the same way every run.
+fn normalize(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("id");
+            map.remove("character_id");
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            for (key, mut item) in entries {
+                normalize(&mut item);
+                map.insert(key, item);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+/// Deterministic snapshot of every aggregate the scanner writes.
+fn snapshot(db: &Database) -> Value {
+    let mut characters = Vec::new();
+    for character in db.list_characters().unwrap() {
+        let id = character.id.unwrap();
+        let mut sessions = db.get_sessions(id).unwrap();
+        for s in &mut sessions {
+            s.log_file = Path::new(&s.log_file)
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        let events: serde_json::Map<String, Value> = crate::db::queries::HISTORY_EVENT_TYPES
+            .iter()
+            .map(|t| (t.to_string(), json!(db.event_history(id, t).unwrap())))
+            .collect();
+        characters.push(json!({
+            "character": character,
+            "kills": db.get_kills(id).unwrap(),
+            "trainers": db.get_trainers(id).unwrap(),
+            "checkpoints": db.get_all_trainer_checkpoints(id).unwrap(),
+            "lastys": db.get_lastys(id).unwrap(),
+            "economy": db.get_economy_merged(id, None).unwrap(),
+            "sessions": sessions,
+            "clan_history": db.get_clan_history_merged(id).unwrap(),
+            "accuracy": db.get_accuracy_merged(id).unwrap(),
+            "damage_taken": db.get_damage_taken_merged(id).unwrap(),
+            "companions": db.get_companions_merged(id).unwrap(),
+            "heals": db.get_heals_merged(id).unwrap(),
+            "rescues": db.get_rescues_merged(id).unwrap(),
+            "circles": db.get_circles_merged(id).unwrap(),
+            "correspondents": db.get_correspondents_merged(id).unwrap(),
+            "zones": db.get_zone_visits_merged(id).unwrap(),
+            "milestones": db.get_milestones_merged(id).unwrap(),
+            "assists": db.get_assists_merged(id, None).unwrap(),
+            "events": events,
+        }));
+    }
+    let mut value = json!({ "characters": characters });
+    normalize(&mut value);
+    value
+}
+
+#[test]
+fn golden_logs_match_expected_aggregates() {
+    let dir = fixtures_dir();
+    let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+    let result = parser.scan_folder(&dir, false).unwrap();
+    assert_eq!(result.errors, 0, "fixture logs should scan cleanly");
+    // Same post-scan pass the CLI and GUI run (profession and coin-level reconciliation).
+    parser.finalize_characters().unwrap();
+
+    let actual = snapshot(parser.db());
+    let expected_path = dir.join("expected.json");
+    if std::env::var_os("AMANUENSIS_BLESS").is_some() {
+        let mut text = serde_json::to_string_pretty(&actual).unwrap();
+        text.push('\n');
+        std::fs::write(&expected_path, text).unwrap();
+        return;
+    }
+
+    let expected: Value = serde_json::from_str(
+        &std::fs::read_to_string(&expected_path).expect("expected.json missing; run with AMANUENSIS_BLESS=1"),
+    )
+    .unwrap();
+    assert!(
+        actual == expected,
+        "golden aggregates changed; diff against {}:\n{}",
+        expected_path.display(),
+        serde_json::to_string_pretty(&actual).unwrap()
+    );
+}
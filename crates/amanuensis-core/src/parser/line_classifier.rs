@@ -1,11 +1,16 @@
+use serde::Serialize;
+
 use crate::data::TrainerDb;
 use crate::models::LastyType;
 use crate::parser::events::{KillVerb, LogEvent, LootType};
 use crate::parser::patterns;
+use crate::parser::timestamp::parse_timestamp;
 
 /// Classify a message body (after timestamp extraction) into a LogEvent.
 pub fn classify_line(message: &str, trainer_db: &TrainerDb) -> LogEvent {
-    // Trim leading whitespace (e.g. double-space after timestamp) before any checks
+    // Straighten curly quotes etc., then trim leading whitespace (e.g. double-space after
+    // timestamp) before any checks
+    let message = patterns::normalize_typography(message);
     let message = message.trim_start();
 
     // Skip empty lines
@@ -46,13 +51,35 @@ pub fn classify_line(message: &str, trainer_db: &TrainerDb) -> LogEvent {
     if let Some(caps) = patterns::PROFESSION_CIRCLE_TEST.captures(message) {
         return LogEvent::ProfessionAnnouncement {
             name: caps[1].to_string(),
-            profession: normalize_profession(&caps[2]),
+            profession: normalize_profession(&caps[3]),
+            circle: ordinal_number(&caps[2]),
         };
     }
     if let Some(caps) = patterns::PROFESSION_BECOME.captures(message) {
         return LogEvent::ProfessionAnnouncement {
             name: caps[1].to_string(),
             profession: normalize_profession(&caps[2]),
+            circle: None,
+        };
+    }
+
+    // Title announcements (NPC speech — check before speech filter)
+    if let Some(caps) = patterns::TITLE_ANNOUNCEMENT.captures(message) {
+        return LogEvent::TitleEarned {
+            name: Some(caps[1].to_string()),
+            title: caps[2].to_string(),
+        };
+    }
+
+    // Private thoughts look like speech — check before speech filter
+    if let Some(caps) = patterns::THOUGHT_RECEIVED.captures(message) {
+        return LogEvent::ThoughtReceived {
+            name: caps[1].to_string(),
+        };
+    }
+    if let Some(caps) = patterns::THOUGHT_SENT.captures(message) {
+        return LogEvent::ThoughtSent {
+            name: caps[1].to_string(),
         };
     }
 
@@ -77,6 +104,8 @@ pub fn classify_line(message: &str, trainer_db: &TrainerDb) -> LogEvent {
                 });
             if let Some((rank_min, rank_max)) = checkpoint {
                 return LogEvent::TrainerCheckpoint { trainer_name, character_name, rank_min, rank_max };
+            } else if crate::data::is_maxed_message(rank_message) {
+                return LogEvent::TrainerMaxed { trainer_name, character_name: Some(character_name) };
             } else {
                 // TRAINER_GREETING matched (has rank text) but message not in checkpoint DB
                 return LogEvent::TrainerGreetingWithUnknownCheckpoint {
@@ -111,6 +140,9 @@ pub fn classify_line(message: &str, trainer_db: &TrainerDb) -> LogEvent {
         if let Some((rank_min, rank_max)) = crate::data::lookup_checkpoint_message(spoken) {
             return LogEvent::TrainerCheckpointUnhailed { trainer_name, rank_min, rank_max };
         }
+        if crate::data::is_maxed_message(spoken) {
+            return LogEvent::TrainerMaxedUnhailed { trainer_name };
+        }
     }
 
     // Handle ¥-prefixed lines (Mac client) and •-prefixed lines (Windows client).
@@ -163,6 +195,67 @@ pub fn classify_line(message: &str, trainer_db: &TrainerDb) -> LogEvent {
             verb,
         };
     }
+    if let Some(caps) = patterns::OTHER_KILL.captures(message) {
+        if &caps[1] != "You" {
+            let verb = match &caps[2] {
+                "killed" => KillVerb::Killed,
+                "slaughtered" => KillVerb::Slaughtered,
+                "vanquished" => KillVerb::Vanquished,
+                "dispatched" => KillVerb::Dispatched,
+                _ => unreachable!(),
+            };
+            return LogEvent::OtherKill {
+                player: caps[1].to_string(),
+                creature: strip_article(&caps[3]),
+                verb,
+            };
+        }
+    }
+
+    // Melee hit/miss (accuracy statistics)
+    if let Some(caps) = patterns::ATTACK_HIT.captures(message) {
+        return LogEvent::AttackHit {
+            creature: strip_article(&caps[1]),
+        };
+    }
+    if let Some(caps) = patterns::ATTACK_MISS.captures(message) {
+        let target = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+        return LogEvent::AttackMiss {
+            creature: strip_article(target),
+        };
+    }
+    if let Some(caps) = patterns::DAMAGE_TAKEN.captures(message) {
+        // Sentence-initial article is capitalized: "The Rat hits you."
+        let attacker = &caps[1];
+        let creature = ["The ", "An ", "A "]
+            .iter()
+            .find_map(|a| attacker.strip_prefix(a))
+            .map_or_else(|| strip_article(attacker), str::to_string);
+        return LogEvent::DamageTaken { creature };
+    }
+
+    // Healer pets (before death patterns: "Your pet X has fallen to ..." is not the player)
+    if let Some(caps) = patterns::PET_ADOPTED.captures(message) {
+        return LogEvent::PetAdopted { creature: caps[1].to_string() };
+    }
+    if let Some(caps) = patterns::PET_NAMED.captures(message) {
+        return LogEvent::PetNamed { pet: caps[1].to_string(), name: caps[2].to_string() };
+    }
+    if let Some(caps) = patterns::PET_FED.captures(message) {
+        return LogEvent::PetFed { pet: caps[1].to_string() };
+    }
+    if let Some(caps) = patterns::PET_DIED.captures(message) {
+        return LogEvent::PetDied { pet: caps[1].to_string() };
+    }
+    if let Some(caps) = patterns::PET_RELEASED.captures(message) {
+        return LogEvent::PetReleased { pet: caps[1].to_string() };
+    }
+    if let Some(caps) = patterns::PET_TRAINING.captures(message) {
+        return LogEvent::PetTraining { pet: caps[1].to_string() };
+    }
+    if let Some(caps) = patterns::PET_RANK.captures(message) {
+        return LogEvent::PetRank { pet: caps[1].to_string() };
+    }
 
     // Death patterns
     if let Some(caps) = patterns::FALLEN.captures(message) {
@@ -223,6 +316,31 @@ pub fn classify_line(message: &str, trainer_db: &TrainerDb) -> LogEvent {
         };
     }
 
+    // Clan membership and titles
+    if let Some(caps) = patterns::CLAN_JOIN.captures(message) {
+        return LogEvent::ClanJoined { clan: caps[1].to_string() };
+    }
+    if let Some(caps) = patterns::CLAN_LEAVE.captures(message) {
+        return LogEvent::ClanLeft { clan: caps[1].to_string() };
+    }
+    if let Some(caps) = patterns::TITLE_EARNED.captures(message) {
+        return LogEvent::TitleEarned { name: None, title: caps[1].to_string() };
+    }
+
+    // Arena bouts and tournaments
+    if let Some(caps) = patterns::ARENA_ENTER.captures(message) {
+        return LogEvent::ArenaEntered { arena: caps[1].to_string() };
+    }
+    if let Some(caps) = patterns::ARENA_WIN.captures(message) {
+        return LogEvent::ArenaBout { opponent: caps[1].to_string(), won: true };
+    }
+    if let Some(caps) = patterns::ARENA_LOSS.captures(message) {
+        return LogEvent::ArenaBout { opponent: caps[1].to_string(), won: false };
+    }
+    if let Some(caps) = patterns::TOURNAMENT_WON.captures(message) {
+        return LogEvent::TournamentWon { winner: caps[1].to_string() };
+    }
+
     // Equipment patterns
     if patterns::BELL_BROKEN.is_match(message) {
         return LogEvent::BellBroken;
@@ -279,6 +397,14 @@ pub fn classify_line(message: &str, trainer_db: &TrainerDb) -> LogEvent {
         return LogEvent::FishCaught { item };
     }
 
+    // Stat boosts (after fishing: "You feel a tug on your line" is not a boost)
+    if let Some(caps) = patterns::STAT_BOOST.captures(message) {
+        return LogEvent::StatBoost { stat: caps[2].to_string(), permanent: caps.get(1).is_some() };
+    }
+    if let Some(caps) = patterns::STAT_BOOST_FADED.captures(message) {
+        return LogEvent::StatBoostFaded { stat: caps[1].to_string() };
+    }
+
     // Esteem gain (check before experience since it also starts with "* You gain")
     if patterns::ESTEEM_GAIN.is_match(message) {
         return LogEvent::EsteemGain;
@@ -289,6 +415,39 @@ pub fn classify_line(message: &str, trainer_db: &TrainerDb) -> LogEvent {
         return LogEvent::ExperienceGain;
     }
 
+    if let Some(caps) = patterns::HEAL_RECEIVED.captures(message) {
+        return LogEvent::HealReceived {
+            name: caps[1].to_string(),
+        };
+    }
+    if let Some(caps) = patterns::HEAL_GIVEN.captures(message) {
+        if &caps[1] != "yourself" {
+            return LogEvent::HealGiven {
+                name: caps[1].to_string(),
+            };
+        }
+    }
+    if let Some(caps) = patterns::DRAGGED_BY.captures(message) {
+        return LogEvent::DraggedBy {
+            name: caps[1].to_string(),
+        };
+    }
+    if let Some(caps) = patterns::RAISED_BY.captures(message) {
+        return LogEvent::RaisedBy {
+            name: caps[1].to_string(),
+        };
+    }
+    if let Some(caps) = patterns::RAISED_OTHER.captures(message) {
+        return LogEvent::RaisedOther {
+            name: caps[1].to_string(),
+        };
+    }
+    if let Some(caps) = patterns::SHARING_START.captures(message) {
+        return LogEvent::SharingStarted {
+            name: caps[1].to_string(),
+        };
+    }
+
     // Clanning
     if let Some(caps) = patterns::CLANNING_ON.captures(message) {
         return LogEvent::ClanningChange {
@@ -339,6 +498,17 @@ fn titlecase_words(s: &str) -> String {
 }
 
 /// Normalize a profession name from log text to canonical form.
+/// "first" → 1 ... "twentieth" → 20.
+fn ordinal_number(word: &str) -> Option<i64> {
+    const ORDINALS: [&str; 20] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+        "eleventh", "twelfth", "thirteenth", "fourteenth", "fifteenth", "sixteenth", "seventeenth",
+        "eighteenth", "nineteenth", "twentieth",
+    ];
+    let word = word.to_lowercase();
+    ORDINALS.iter().position(|o| *o == word).map(|i| i as i64 + 1)
+}
+
 fn normalize_profession(raw: &str) -> String {
     match raw.to_lowercase().as_str() {
         "fighter" => "Fighter".to_string(),
@@ -469,6 +639,10 @@ fn classify_system_message(message: &str, trainer_db: &TrainerDb) -> LogEvent {
         return LogEvent::Ignored;
     }
 
+    if let Some(caps) = patterns::TRAINER_MAXED.captures(body) {
+        return LogEvent::TrainerMaxed { trainer_name: caps[1].to_string(), character_name: None };
+    }
+
     // Try trainer lookup
     if let Some(trainer_name) = trainer_db.get_trainer(body) {
         return LogEvent::TrainerRank {
@@ -481,6 +655,99 @@ fn classify_system_message(message: &str, trainer_db: &TrainerDb) -> LogEvent {
     LogEvent::Ignored
 }
 
+/// For a message `classify_line` ignored: its shape if it looks like a system message
+/// (¥/• prefixed or starred), with digit runs replaced by `#` so "3 chimes" and "5 chimes"
+/// are one format. None for speech, emotes, plain text and ¥ messages skipped on purpose.
+pub fn unrecognized_system_message(message: &str) -> Option<String> {
+    let message = patterns::normalize_typography(message);
+    let message = message.trim();
+    if let Some(body) = message.strip_prefix('¥').or_else(|| message.strip_prefix('•')) {
+        let body = body.trim();
+        if body.is_empty()
+            || patterns::YEN_HEALING_SENSE.is_match(body)
+            || patterns::YEN_SUN_EVENT.is_match(body)
+            || patterns::YEN_STUDY_GAIN.is_match(body)
+            || patterns::YEN_STUDY_CONCURRENT.is_match(body)
+        {
+            return None;
+        }
+    } else if !message.starts_with('*') || patterns::SPEECH.is_match(message) || patterns::EMOTE.is_match(message) {
+        return None;
+    }
+
+    let mut shape = String::with_capacity(message.len());
+    let mut in_digits = false;
+    for c in message.chars() {
+        if c.is_ascii_digit() {
+            if !in_digits {
+                shape.push('#');
+            }
+            in_digits = true;
+        } else {
+            shape.push(c);
+            in_digits = false;
+        }
+    }
+    Some(shape)
+}
+
+/// The parts of a speech or thought line, for the opt-in `chat` table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatSpeech {
+    /// None when the log's own character is speaking ("You think to ...").
+    pub speaker: Option<String>,
+    /// "says", "yells in Thoom", "thinks to you", ...
+    pub verb: String,
+    pub text: String,
+}
+
+/// Split a speech or thought line into speaker, verb and text; None for anything else.
+pub fn parse_chat(message: &str) -> Option<ChatSpeech> {
+    let message = patterns::normalize_typography(message);
+    let message = message.trim();
+    if let Some(caps) = patterns::CHAT_SENT.captures(message) {
+        return Some(ChatSpeech { speaker: None, verb: format!("thinks {}", &caps[1]), text: caps[2].to_string() });
+    }
+    let caps = patterns::CHAT.captures(message)?;
+    if caps[1].starts_with(['*', '¥', '•', '(']) {
+        return None;
+    }
+    Some(ChatSpeech { speaker: Some(caps[1].to_string()), verb: caps[2].to_string(), text: caps[3].to_string() })
+}
+
+/// One line of text run through [`classify_line`].
+#[derive(Debug, Clone, Serialize)]
+pub struct LineClassification {
+    /// 1-based line number.
+    pub line: usize,
+    /// The line's timestamp as written in the log, when it has one.
+    pub timestamp: Option<String>,
+    pub message: String,
+    pub event: LogEvent,
+}
+
+/// Classify each non-blank line of `text` on its own, as a debugging aid. Log timestamps
+/// are stripped first; lines without one are classified whole. Events the parser builds
+/// from context (multi-line messages, pending kills) aren't reproduced.
+pub fn classify_text(text: &str, trainer_db: &TrainerDb) -> Vec<LineClassification> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let (timestamp, message) = match parse_timestamp(line) {
+                Some((_, message)) => (Some(line[..line.len() - message.len()].trim().to_string()), message),
+                None => (None, line),
+            };
+            LineClassification {
+                line: i + 1,
+                timestamp,
+                message: message.trim().to_string(),
+                event: classify_line(message, trainer_db),
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -528,6 +795,21 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_other_players_kill() {
+        let db = test_db();
+        let event = classify_line("Mary Ann slaughtered an Orga Anger.", &db);
+        assert!(matches!(
+            event,
+            LogEvent::OtherKill {
+                ref player,
+                ref creature,
+                verb: KillVerb::Slaughtered
+            } if player == "Mary Ann" && creature == "Orga Anger"
+        ));
+        assert!(!matches!(classify_line("Gandor says, \"I killed a Rat.\"", &db), LogEvent::OtherKill { .. }));
+    }
+
     #[test]
     fn test_login() {
         let db = test_db();
@@ -587,6 +869,24 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_curly_quotes_and_no_break_spaces_classify() {
+        let db = test_db();
+        let event = classify_line("¥You notice that you\u{2019}re able to increase others\u{2019} accuracy more.", &db);
+        assert!(matches!(
+            event,
+            LogEvent::TrainerRank { ref trainer_name, ref message }
+                if trainer_name == "Pontifen" && !message.contains('\u{2019}')
+        ));
+        let event = classify_line("Histia says, \u{201C}Hail, Gandor.\u{00A0}You keep me on my toes.\u{201D}", &db);
+        assert!(matches!(
+            event,
+            LogEvent::TrainerCheckpoint { ref trainer_name, rank_min: 100, .. } if trainer_name == "Histia"
+        ));
+        let event = classify_line("You slaughtered\u{00A0}a Rat.", &db);
+        assert!(matches!(event, LogEvent::SoloKill { ref creature, .. } if creature == "Rat"));
+    }
+
     #[test]
     fn test_yen_healing_sense_ignored() {
         let db = test_db();
@@ -791,6 +1091,17 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_unrecognized_system_message_shapes() {
+        assert_eq!(
+            unrecognized_system_message("¥You hear 12 distant chimes."),
+            Some("¥You hear # distant chimes.".into())
+        );
+        assert_eq!(unrecognized_system_message("* The ground trembles."), Some("* The ground trembles.".into()));
+        assert_eq!(unrecognized_system_message("Fen says, \"* not a system line\""), None);
+        assert_eq!(unrecognized_system_message("Just some text."), None);
+    }
+
     #[test]
     fn test_lasty_befriend() {
         let db = test_db();
@@ -1138,7 +1449,7 @@ mod tests {
         );
         assert!(matches!(
             event,
-            LogEvent::ProfessionAnnouncement { ref name, ref profession }
+            LogEvent::ProfessionAnnouncement { ref name, ref profession, circle: Some(7) }
             if name == "Camo" && profession == "Fighter"
         ));
     }
@@ -1152,7 +1463,7 @@ mod tests {
         );
         assert!(matches!(
             event,
-            LogEvent::ProfessionAnnouncement { ref name, ref profession }
+            LogEvent::ProfessionAnnouncement { ref name, ref profession, circle: Some(6) }
             if name == "Squib" && profession == "Healer"
         ));
     }
@@ -1166,7 +1477,7 @@ mod tests {
         );
         assert!(matches!(
             event,
-            LogEvent::ProfessionAnnouncement { ref name, ref profession }
+            LogEvent::ProfessionAnnouncement { ref name, ref profession, circle: None }
             if name == "Kargan" && profession == "Bloodmage"
         ));
     }
@@ -1201,6 +1512,214 @@ mod tests {
         assert!(matches!(event, LogEvent::Ignored));
     }
 
+    #[test]
+    fn test_pet_messages() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("The Maha Ruknee is now your pet.", &db),
+            LogEvent::PetAdopted { creature: "Maha Ruknee".to_string() }
+        );
+        assert_eq!(
+            classify_line("You name your Maha Ruknee \"Fluffy\".", &db),
+            LogEvent::PetNamed { pet: "Maha Ruknee".to_string(), name: "Fluffy".to_string() }
+        );
+        assert_eq!(
+            classify_line("You feed your pet Fluffy.", &db),
+            LogEvent::PetFed { pet: "Fluffy".to_string() }
+        );
+        assert_eq!(
+            classify_line("Your pet Fluffy has fallen to a Large Vermine.", &db),
+            LogEvent::PetDied { pet: "Fluffy".to_string() }
+        );
+        assert_eq!(
+            classify_line("You release your Maha Ruknee into the wild.", &db),
+            LogEvent::PetReleased { pet: "Maha Ruknee".to_string() }
+        );
+        assert_eq!(
+            classify_line("You spend some time training your Maha Ruknee.", &db),
+            LogEvent::PetTraining { pet: "Maha Ruknee".to_string() }
+        );
+        assert_eq!(
+            classify_line("Your pet Fluffy gains a rank!", &db),
+            LogEvent::PetRank { pet: "Fluffy".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_arena_and_tournament_messages() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("You enter the Puddleby Arena.", &db),
+            LogEvent::ArenaEntered { arena: "Puddleby Arena".to_string() }
+        );
+        assert_eq!(
+            classify_line("You defeated Mary Ann in the arena!", &db),
+            LogEvent::ArenaBout { opponent: "Mary Ann".to_string(), won: true }
+        );
+        assert_eq!(
+            classify_line("You were defeated by Gandor in the tournament.", &db),
+            LogEvent::ArenaBout { opponent: "Gandor".to_string(), won: false }
+        );
+        assert_eq!(
+            classify_line("*** Squib has won the tournament! ***", &db),
+            LogEvent::TournamentWon { winner: "Squib".to_string() }
+        );
+        // Speech about the arena is not a result
+        assert_eq!(classify_line(r#"Squib says, "You defeated Gandor in the arena!""#, &db), LogEvent::Ignored);
+        assert_eq!(classify_line("You enter the tavern.", &db), LogEvent::Ignored);
+    }
+
+    #[test]
+    fn test_clan_membership_and_titles() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("You are now a member of the Night Watch.", &db),
+            LogEvent::ClanJoined { clan: "Night Watch".to_string() }
+        );
+        assert_eq!(
+            classify_line("You are no longer a member of the Night Watch.", &db),
+            LogEvent::ClanLeft { clan: "Night Watch".to_string() }
+        );
+        assert_eq!(
+            classify_line("You have earned the title of Rat Catcher.", &db),
+            LogEvent::TitleEarned { name: None, title: "Rat Catcher".to_string() }
+        );
+        assert_eq!(
+            classify_line(
+                r#"Honor thinks, "Congratulations to Squib, who has earned the title of Rat Catcher.""#,
+                &db
+            ),
+            LogEvent::TitleEarned { name: Some("Squib".to_string()), title: "Rat Catcher".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_stat_boost() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("You feel stronger.", &db),
+            LogEvent::StatBoost { stat: "stronger".to_string(), permanent: false }
+        );
+        assert_eq!(
+            classify_line("* You feel blessed!", &db),
+            LogEvent::StatBoost { stat: "blessed".to_string(), permanent: false }
+        );
+        assert_eq!(
+            classify_line("You feel much hardier.", &db),
+            LogEvent::StatBoost { stat: "hardier".to_string(), permanent: false }
+        );
+        assert_eq!(
+            classify_line("You feel permanently more agile!", &db),
+            LogEvent::StatBoost { stat: "more agile".to_string(), permanent: true }
+        );
+        assert_eq!(
+            classify_line("You no longer feel stronger.", &db),
+            LogEvent::StatBoostFaded { stat: "stronger".to_string() }
+        );
+        for not_a_boost in ["You feel better.", "You feel sleepier.", "You feel more alone.", "You feel tired."] {
+            assert!(!matches!(classify_line(not_a_boost, &db), LogEvent::StatBoost { .. }), "{not_a_boost}");
+        }
+        assert!(!matches!(classify_line("You no longer feel lonelier.", &db), LogEvent::StatBoostFaded { .. }));
+        // The same wording behind a trainer prefix is a rank message, not a boost.
+        assert!(matches!(
+            classify_line("\u{a5}You feel tougher.", &db),
+            LogEvent::TrainerRank { .. }
+        ));
+    }
+
+    #[test]
+    fn test_attack_hit_and_miss() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("You hit the Rat.", &db),
+            LogEvent::AttackHit { creature: "Rat".to_string() }
+        );
+        assert_eq!(
+            classify_line("You swing at an Orga Anger and miss.", &db),
+            LogEvent::AttackMiss { creature: "Orga Anger".to_string() }
+        );
+        assert_eq!(
+            classify_line("You miss a Vermine.", &db),
+            LogEvent::AttackMiss { creature: "Vermine".to_string() }
+        );
+        // Someone else's swing is not ours.
+        assert_eq!(classify_line("Squib hits the Rat.", &db), LogEvent::Ignored);
+    }
+
+    #[test]
+    fn test_heals() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("Bramble heals you.", &db),
+            LogEvent::HealReceived { name: "Bramble".to_string() }
+        );
+        assert_eq!(
+            classify_line("You heal Corvid.", &db),
+            LogEvent::HealGiven { name: "Corvid".to_string() }
+        );
+        assert_eq!(classify_line("You heal yourself.", &db), LogEvent::Ignored);
+    }
+
+    #[test]
+    fn test_rescues() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("Bramble starts dragging you.", &db),
+            LogEvent::DraggedBy { name: "Bramble".to_string() }
+        );
+        assert_eq!(
+            classify_line("Bramble is dragging you.", &db),
+            LogEvent::DraggedBy { name: "Bramble".to_string() }
+        );
+        assert_eq!(
+            classify_line("Corvid raises you.", &db),
+            LogEvent::RaisedBy { name: "Corvid".to_string() }
+        );
+        assert_eq!(
+            classify_line("You raise Corvid.", &db),
+            LogEvent::RaisedOther { name: "Corvid".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_thoughts() {
+        let db = test_db();
+        assert_eq!(
+            classify_line(r#"Bramble thinks to you, "heading to the orgas?""#, &db),
+            LogEvent::ThoughtReceived { name: "Bramble".to_string() }
+        );
+        assert_eq!(
+            classify_line(r#"You think to Corvid, "on my way, says Bramble""#, &db),
+            LogEvent::ThoughtSent { name: "Corvid".to_string() }
+        );
+        // Broadcast thoughts are plain speech.
+        assert_eq!(classify_line(r#"Bramble thinks, "anyone hunting?""#, &db), LogEvent::Ignored);
+    }
+
+    #[test]
+    fn test_sharing_started() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("You are now sharing experiences with Bramble.", &db),
+            LogEvent::SharingStarted { name: "Bramble".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_damage_taken() {
+        let db = test_db();
+        assert_eq!(
+            classify_line("The Rat hits you.", &db),
+            LogEvent::DamageTaken { creature: "Rat".to_string() }
+        );
+        assert_eq!(
+            classify_line("An Orga Anger claws you.", &db),
+            LogEvent::DamageTaken { creature: "Orga Anger".to_string() }
+        );
+        // Player attacks have no article and are not creature damage.
+        assert_eq!(classify_line("Squib hits you.", &db), LogEvent::Ignored);
+    }
+
     #[test]
     fn test_esteem_gain() {
         let db = test_db();
@@ -1322,6 +1841,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_trainer_maxed_messages_classify() {
+        let db = test_db();
+        assert_eq!(
+            classify_line(r#"Histia says, "Hail, Gandor. There is nothing I can teach you.""#, &db),
+            LogEvent::TrainerMaxed { trainer_name: "Histia".into(), character_name: Some("Gandor".into()) }
+        );
+        assert_eq!(
+            classify_line(r#"Regia says, "There is nothing I can teach you.""#, &db),
+            LogEvent::TrainerMaxedUnhailed { trainer_name: "Regia".into() }
+        );
+        assert_eq!(
+            classify_line("¥Bangus Anmash has nothing more to teach you.", &db),
+            LogEvent::TrainerMaxed { trainer_name: "Bangus Anmash".into(), character_name: None }
+        );
+    }
+
     #[test]
     fn test_trainer_greeting_other_player_classified_with_their_name() {
         // The classifier extracts whatever name appears after "Hail, ";
@@ -1478,4 +2014,27 @@ mod tests {
             LogEvent::FishCaught { ref item } if item == "Eel"
         ));
     }
+
+    #[test]
+    fn classify_text_strips_timestamps_and_numbers_lines() {
+        let db = test_db();
+        let lines = classify_text("1/1/24 1:00:00p You slaughtered a Rat.\n\nGandor says, \"hi\"\n", &db);
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].line, lines[0].timestamp.as_deref()), (1, Some("1/1/24 1:00:00p")));
+        assert_eq!(lines[0].message, "You slaughtered a Rat.");
+        assert!(matches!(lines[0].event, LogEvent::SoloKill { ref creature, .. } if creature == "Rat"));
+        assert_eq!((lines[1].line, lines[1].timestamp.as_deref()), (3, None));
+    }
+
+    #[test]
+    fn parse_chat_splits_speaker_verb_and_text() {
+        let chat = |s: &str| parse_chat(s).map(|c| (c.speaker, c.verb, c.text));
+        assert_eq!(chat("Fen says, \"going hunting, coming?\""), Some((Some("Fen".into()), "says".into(), "going hunting, coming?".into())));
+        assert_eq!(chat("Pip yells in Thoom, \"Aiee!\""), Some((Some("Pip".into()), "yells in Thoom".into(), "Aiee!".into())));
+        assert_eq!(chat("Bramble thinks to you, \"meet at the fountain\""), Some((Some("Bramble".into()), "thinks to you".into(), "meet at the fountain".into())));
+        assert_eq!(chat("You think to Bramble, \"on my way\""), Some((None, "thinks to Bramble".into(), "on my way".into())));
+        assert_eq!(chat("Fen says, \u{201C}curly\u{201D}").map(|c| c.2), Some("curly".into()));
+        assert_eq!(chat("You slaughtered a Rat."), None);
+        assert_eq!(chat("(Fen waves)"), None);
+    }
 }
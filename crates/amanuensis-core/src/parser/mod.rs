@@ -1,25 +1,45 @@
+pub mod event_bus;
 pub mod events;
 pub mod line_classifier;
 pub mod patterns;
 pub mod timestamp;
 
-use std::cell::RefCell;
+mod assist;
+#[cfg(test)]
+mod golden_tests;
+mod prepare;
+mod reindex;
+mod unscan;
+
+use std::cell::{Cell, RefCell};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use chrono::Utc;
-
+use crate::creature_names::CreatureNameRules;
 use crate::data::{CreatureDb, TrainerDb};
+use crate::date::Timestamp;
+use crate::db::queries::{CharacterCache, ZoneVisit};
 use crate::db::Database;
 use crate::encoding::decode_log_bytes;
-use crate::error::Result;
-use crate::models::{Profession, RankMode};
+use crate::error::{AmanuensisError, Result};
+use crate::hooks::{HookConfig, HookEvent};
+use crate::notify::{NotifyConfig, Notifier};
+use crate::models::{Profession, RankMode, ScanError, Session};
+use crate::parser::event_bus::{EventBus, EventSubscriber, JsonLines, SubscriptionId};
 use crate::parser::events::{KillVerb, LogEvent, LootType};
-use crate::parser::line_classifier::classify_line;
-use crate::parser::timestamp::parse_filename_date;
+use crate::parser::assist::AssistTracker;
+pub use crate::parser::assist::ASSIST_WINDOW_SECS;
+pub use crate::parser::prepare::NICE_FILE_PAUSE;
+pub use crate::parser::reindex::ReindexResult;
+use crate::parser::prepare::{ClassifiedLine, PreparedFiles, PreparedLines, ScanPlan};
+use crate::parser::timestamp::{is_log_filename, parse_filename_date};
 use crate::parser::timestamp::parse_timestamp;
+use crate::retention::RetentionPolicy;
 
 /// Override configuration for a character's trainers, loaded before scanning.
 struct OverrideConfig {
@@ -30,15 +50,25 @@ struct OverrideConfig {
     override_until_date: HashMap<String, String>,
 }
 
+/// Lines between calls to a `set_line_progress` callback within one file.
+pub const LINE_PROGRESS_INTERVAL: usize = 5_000;
+
+/// Called with (lines scanned, lines to scan) while a file is scanned.
+pub type LineProgress = Box<dyn Fn(usize, usize) + Send>;
+
 /// Per-character Ranger reflect snapshot: lasty_type → (timestamp, finished_creatures).
 /// Each study type's most recent (most complete) list is kept independently.
 type ReflectByType = HashMap<String, (String, Vec<String>)>;
 
 /// Main log parser orchestrator.
 /// Walks character subdirectories, scans log files, and stores events in the database.
+/// Files are read and classified on a worker pool (see `prepare`); the parser itself is the
+/// single writer that applies them, in order, on the calling thread.
 pub struct LogParser {
-    creature_db: CreatureDb,
-    trainer_db: TrainerDb,
+    /// Replaceable between passes (see `set_creature_db`).
+    creature_db: RefCell<CreatureDb>,
+    /// Shared with the preparation workers.
+    trainer_db: Arc<TrainerDb>,
     db: Database,
     /// Tracks abandoned studies per character: char_id → set of creature names.
     /// Progress messages for abandoned creatures are skipped until "begin studying" re-enables them.
@@ -50,22 +80,285 @@ pub struct LogParser {
     /// A reflect dump lists each study type (Movements / Befriend / Morph) under its own header;
     /// only the newest list per type is applied (it is the most complete one).
     last_reflect: RefCell<HashMap<i64, ReflectByType>>,
+    /// When set, a file's trailing partial line (no terminator yet) is left for the next
+    /// pass instead of being parsed — for tailing logs the client is still writing.
+    complete_lines_only: Cell<bool>,
+    /// Low-priority scanning: one file prepared at a time, a pause before each file, smaller
+    /// search-index batches and a smaller SQLite cache, so a background scan stays out of the
+    /// way of the game and the GUI.
+    nice: Cell<bool>,
+    /// External commands to run for selected events (see `crate::hooks`); none by default.
+    hooks: RefCell<Option<HookConfig>>,
+    /// Hook events recorded by the scan in progress, run once it commits (see `end_scan`).
+    queued_hooks: RefCell<Vec<HookEvent>>,
+    /// Queues webhook messages for the same events as hooks (see `crate::notify`).
+    notifier: RefCell<Option<Notifier>>,
+    /// Subscribers to every classified event (see `event_bus`); none by default.
+    event_bus: RefCell<EventBus>,
+    /// The bus subscription installed by `set_event_stream`.
+    event_stream: Cell<Option<SubscriptionId>>,
+    /// Raised from another thread to stop a scan before its next file (see `set_cancel_flag`).
+    cancel: RefCell<Option<Arc<AtomicBool>>>,
+    /// Reports progress through each file's lines (see `set_line_progress`); none by default.
+    line_progress: RefCell<Option<LineProgress>>,
+    /// Record other players' falls in `falls_witnessed` (off by default; the table grows
+    /// with every fall on screen).
+    track_witnessed_falls: Cell<bool>,
+    /// Count system-looking lines no pattern matched in `unknown_lines` (off by default).
+    track_unknown_lines: Cell<bool>,
+    /// Store speech and thought lines in `chat` (off by default; kept apart from the search
+    /// index so it costs nothing unless asked for).
+    track_chat: Cell<bool>,
+    /// Line of the file `scan_lines` is on (0 outside the line loop), reported with scan errors.
+    scan_line: Cell<usize>,
+    /// Area of the visit in progress in `scan_lines` (cleared with it by `flush_session`),
+    /// tagged onto streamed events and hook payloads and counted in `kills_by_location`.
+    current_area: RefCell<Option<String>>,
+    /// Creature-name canonicalization applied to parsed events; the database's active rules
+    /// by default (see `crate::creature_names`).
+    creature_rules: RefCell<Option<CreatureNameRules>>,
+    /// Character IDs by name. Shareable with other threads via `character_cache`.
+    characters: CharacterCache,
 }
 
 impl LogParser {
     pub fn new(db: Database) -> Result<Self> {
+        Self::with_trainer_db(db, Arc::new(TrainerDb::bundled()?))
+    }
+
+    /// A parser that recognizes trainers from `trainer_db` instead of the bundled catalog
+    /// (e.g. one extended by `TrainerDb::load`).
+    pub fn with_trainer_db(db: Database, trainer_db: Arc<TrainerDb>) -> Result<Self> {
         let creature_db = CreatureDb::bundled()?;
-        let trainer_db = TrainerDb::bundled()?;
+        let creature_rules = db.active_creature_name_rules()?;
         Ok(Self {
-            creature_db,
+            creature_db: RefCell::new(creature_db),
             trainer_db,
             db,
             abandoned_studies: RefCell::new(HashMap::new()),
             override_configs: RefCell::new(HashMap::new()),
             last_reflect: RefCell::new(HashMap::new()),
+            complete_lines_only: Cell::new(false),
+            nice: Cell::new(false),
+            hooks: RefCell::new(None),
+            queued_hooks: RefCell::new(Vec::new()),
+            notifier: RefCell::new(None),
+            event_bus: RefCell::new(EventBus::default()),
+            event_stream: Cell::new(None),
+            cancel: RefCell::new(None),
+            line_progress: RefCell::new(None),
+            track_witnessed_falls: Cell::new(false),
+            track_unknown_lines: Cell::new(false),
+            track_chat: Cell::new(false),
+            scan_line: Cell::new(0),
+            current_area: RefCell::new(None),
+            creature_rules: RefCell::new(creature_rules),
+            characters: CharacterCache::new(),
         })
     }
 
+    /// Only scan up to the last complete line of each file (see `complete_lines_only`).
+    /// Used by live watch mode, where the client may be mid-write when a file is read.
+    pub fn set_complete_lines_only(&self, enabled: bool) {
+        self.complete_lines_only.set(enabled);
+    }
+
+    /// Scan at low priority (see `nice`). Slower, but light on CPU, disk and memory.
+    pub fn set_nice(&self, enabled: bool) {
+        self.nice.set(enabled);
+    }
+
+    /// Watch `flag` during subsequent scans (`None` stops watching). Once it is set, the scan
+    /// stops before its next file with `AmanuensisError::Cancelled` and the in-flight
+    /// transaction is rolled back, as for any other scan error. The flag is not cleared.
+    pub fn set_cancel_flag(&self, flag: Option<Arc<AtomicBool>>) {
+        *self.cancel.borrow_mut() = flag;
+    }
+
+    /// Call `callback` every [`LINE_PROGRESS_INTERVAL`] lines of each file subsequent scans
+    /// read, and at the end of the file, so a huge file shows progress between the per-file
+    /// progress calls (`None` stops). For a tail scan the counts cover just the new lines.
+    pub fn set_line_progress(&self, callback: Option<LineProgress>) {
+        *self.line_progress.borrow_mut() = callback;
+    }
+
+    fn report_line_progress(&self, done: usize, total: usize) {
+        if let Some(callback) = &*self.line_progress.borrow() {
+            callback(done, total);
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        match &*self.cancel.borrow() {
+            Some(flag) if flag.load(Ordering::Relaxed) => Err(AmanuensisError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Count other players' falls in subsequent scans (see `track_witnessed_falls`).
+    pub fn set_track_witnessed_falls(&self, enabled: bool) {
+        self.track_witnessed_falls.set(enabled);
+    }
+
+    /// Record unrecognized system messages in subsequent scans (see `track_unknown_lines`).
+    pub fn set_track_unknown_lines(&self, enabled: bool) {
+        self.track_unknown_lines.set(enabled);
+    }
+
+    /// Record who said what in subsequent scans (see `track_chat`).
+    pub fn set_track_chat(&self, enabled: bool) {
+        self.track_chat.set(enabled);
+    }
+
+    /// Run `hooks` for events recorded by subsequent scans (`None` disables them).
+    pub fn set_hooks(&self, hooks: Option<HookConfig>) {
+        *self.hooks.borrow_mut() = hooks;
+    }
+
+    /// Queue notifications for events recorded by subsequent scans (`None` disables them).
+    /// Queued messages are collected with `take_notifications`.
+    pub fn set_notifier(&self, config: Option<NotifyConfig>) {
+        *self.notifier.borrow_mut() = config.map(Notifier::new);
+    }
+
+    /// Messages queued since the last call, capped per `NotifyConfig::max_per_pass`.
+    pub fn take_notifications(&self) -> Vec<String> {
+        self.notifier.borrow_mut().as_mut().map(Notifier::take).unwrap_or_default()
+    }
+
+    /// Write each event parsed by subsequent scans to `stream` as one JSON
+    /// [`events::StreamedEvent`] per line, alongside the database writes (`None` stops).
+    /// Only lines a scan actually reads are streamed, so an incremental scan streams only
+    /// what is new.
+    pub fn set_event_stream(&self, stream: Option<Box<dyn std::io::Write + Send>>) {
+        if let Some(id) = self.event_stream.take() {
+            self.unsubscribe_events(id);
+        }
+        if let Some(stream) = stream {
+            self.event_stream.set(Some(self.subscribe_events(Box::new(JsonLines(stream)))));
+        }
+    }
+
+    /// Publish each event parsed by subsequent scans to `subscriber` as well (see
+    /// `event_bus`). Any number of subscribers can listen at once.
+    pub fn subscribe_events(&self, subscriber: Box<dyn EventSubscriber>) -> SubscriptionId {
+        self.event_bus.borrow_mut().subscribe(subscriber)
+    }
+
+    /// Stop publishing to a subscriber added with `subscribe_events`.
+    pub fn unsubscribe_events(&self, id: SubscriptionId) -> bool {
+        self.event_bus.borrow_mut().unsubscribe(id)
+    }
+
+    /// Value creatures from `creature_db` in subsequent scans instead of the bundled bestiary
+    /// (e.g. one extended by `CreatureDb::load`, reloaded when its file changes).
+    pub fn set_creature_db(&self, creature_db: CreatureDb) {
+        *self.creature_db.borrow_mut() = creature_db;
+    }
+
+    /// Record creatures under names from `rules` in subsequent scans (`None` records them as
+    /// logged). Replaces the rules loaded from the database.
+    pub fn set_creature_name_rules(&self, rules: Option<CreatureNameRules>) {
+        *self.creature_rules.borrow_mut() = rules;
+    }
+
+    /// Publish one event. A subscriber that fails (e.g. the reader went away) is dropped
+    /// with a warning; the scan itself carries on.
+    fn emit_event(&self, character: &str, date: &str, file: &str, event: &LogEvent) {
+        let mut bus = self.event_bus.borrow_mut();
+        if bus.is_empty() {
+            return;
+        }
+        let area = self.current_area.borrow();
+        let record = events::StreamedEvent { character, date, file, area: area.as_deref(), event };
+        for (id, e) in bus.publish(&record) {
+            let msg = if self.event_stream.get() == Some(id) {
+                self.event_stream.set(None);
+                format!("Event stream stopped: {e}")
+            } else {
+                format!("Event subscriber stopped: {e}")
+            };
+            log::warn!("{}", msg);
+            let _ = self.db.add_process_log("warn", &msg);
+        }
+    }
+
+    /// Whether hooks or the notifier subscribe to `event`.
+    fn hooks_want(&self, event: &str) -> bool {
+        self.hooks.borrow().as_ref().is_some_and(|h| h.wants(event))
+            || self.notifier.borrow().as_ref().is_some_and(|n| n.wants(event))
+    }
+
+    /// Queue hooks subscribed to `event` (run when the scan commits) and its notifications.
+    fn fire_hooks(&self, event: &str, character: &str, date: &str, subject: &str, count: Option<i64>) {
+        if !self.hooks_want(event) {
+            return;
+        }
+        let payload = HookEvent {
+            event: event.to_string(),
+            character: character.to_string(),
+            date: date.to_string(),
+            subject: subject.to_string(),
+            count,
+            area: self.current_area.borrow().clone(),
+        };
+        if let Some(notifier) = self.notifier.borrow_mut().as_mut().filter(|n| n.wants(event)) {
+            notifier.notify(&payload);
+        }
+        if self.hooks.borrow().as_ref().is_some_and(|h| h.wants(event)) {
+            self.queued_hooks.borrow_mut().push(payload);
+        }
+    }
+
+    /// Run the hooks for committed events, in the order they were recorded. Hook failures
+    /// (including timeouts) are logged, never fatal.
+    fn run_hooks(&self, events: &[HookEvent]) {
+        let hooks = self.hooks.borrow();
+        let Some(hooks) = hooks.as_ref() else { return };
+        for event in events {
+            for msg in hooks.fire(event) {
+                log::warn!("{}", msg);
+                let _ = self.db.add_process_log("warn", &msg);
+            }
+        }
+    }
+
+    /// Count a kill or death in `kills_by_location` when the character's area is known.
+    fn record_kill_location(&self, char_id: i64, creature: &str, field: &str) -> Result<()> {
+        match self.current_area.borrow().as_deref() {
+            Some(area) => self.db.upsert_kill_location(char_id, creature, area, field),
+            None => Ok(()),
+        }
+    }
+
+    /// Record the milestones a kill reached and announce each to `milestone` hooks.
+    fn record_kill_milestones(&self, char_id: i64, char_name: &str, creature: &str, solo: bool, date: &str) -> Result<()> {
+        let boss = solo && self.creature_db.borrow().is_boss(creature);
+        for m in self.db.record_kill_milestones(char_id, creature, solo, boss, date)? {
+            self.fire_hooks("milestone", char_name, date, &m.creature, Some(m.threshold));
+        }
+        Ok(())
+    }
+
+    /// Count a rescue exchanged with another player (who is also a companion for the day).
+    fn record_rescue_event(&self, char_id: i64, char_name: &str, player: &str, direction: &str, kind: &str, date: &str) -> Result<()> {
+        if player.eq_ignore_ascii_case(char_name) {
+            return Ok(());
+        }
+        self.db.record_rescue(char_id, player, direction, kind, date)?;
+        self.db.record_companion(char_id, player, day_bucket(date))
+    }
+
+    /// A heal names another player, so it is both a heals row and a companion sighting.
+    /// Self-heals ("You heal Ashwin." in Ashwin's own log) are ignored.
+    fn record_heal_event(&self, char_id: i64, char_name: &str, player: &str, direction: &str, date: &str) -> Result<()> {
+        if player.eq_ignore_ascii_case(char_name) {
+            return Ok(());
+        }
+        self.db.record_heal(char_id, player, direction, date)?;
+        self.db.record_companion(char_id, player, day_bucket(date))
+    }
+
     /// Load override config for a character from the database.
     /// Called before scanning a character's log files.
     fn load_override_config(&self, char_id: i64) -> Result<()> {
@@ -118,6 +411,12 @@ impl LogParser {
         &self.db
     }
 
+    /// A handle to the parser's character cache, for threads resolving characters on their
+    /// own connections to the same database.
+    pub fn character_cache(&self) -> CharacterCache {
+        self.characters.clone()
+    }
+
     /// Consume the parser and return the inner Database.
     pub fn into_db(self) -> Database {
         self.db
@@ -134,173 +433,18 @@ impl LogParser {
             )));
         }
 
-        self.db.set_scan_pragmas()?;
+        self.begin_scan()?;
         self.db.begin_transaction()?;
 
         let scan_result = self.scan_folder_inner(folder, force, &mut result);
 
-        match scan_result {
-            Ok(()) => {
-                self.db.commit_transaction()?;
-                self.db.reset_pragmas()?;
-            }
-            Err(e) => {
-                let _ = self.db.rollback_transaction();
-                let _ = self.db.reset_pragmas();
-                return Err(e);
-            }
-        }
+        self.end_scan(scan_result)?;
 
         Ok(result)
     }
 
     fn scan_folder_inner(&self, folder: &Path, force: bool, result: &mut ScanResult) -> Result<()> {
-        // Find character subdirectories
-        let mut entries: Vec<_> = std::fs::read_dir(folder)?
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
-            .collect();
-        entries.sort_by_key(|e| e.file_name());
-
-        for entry in entries {
-            let dir_name = entry.file_name().to_string_lossy().to_string();
-            // Skip hidden dirs and known non-character dirs
-            if dir_name.starts_with('.') || dir_name == "CL_Movies" {
-                continue;
-            }
-
-            // Find log files BEFORE creating a character record
-            let char_dir = entry.path();
-            let mut log_files = find_log_files(&char_dir)?;
-            if log_files.is_empty() {
-                log::debug!("Skipping directory with no CL Log files: {}", dir_name);
-                continue;
-            }
-            // Sort chronologically by filename (CL Log YYYY:MM:DD HH.MM.SS.txt)
-            log_files.sort();
-
-            // Try to extract character name from welcome message in earliest log files
-            let char_name = log_files
-                .iter()
-                .find_map(|path| {
-                    std::fs::read(path)
-                        .ok()
-                        .and_then(|bytes| extract_character_name(&bytes))
-                })
-                .unwrap_or_else(|| dir_name.clone());
-
-            log::info!("Processing character: {}", char_name);
-            let char_id = self.db.get_or_create_character(&char_name)?;
-            self.load_override_config(char_id)?;
-
-            let mut char_files_scanned: usize = 0;
-            let mut char_files_skipped: usize = 0;
-            let mut char_events_found: usize = 0;
-
-            for log_path in &log_files {
-                let path_str = log_path.to_string_lossy().to_string();
-
-                let (bytes, offset, full_hash, is_full_scan) =
-                    match self.plan_file_scan(log_path, &path_str, force)? {
-                        ScanPlan::Skip => {
-                            result.skipped += 1;
-                            char_files_skipped += 1;
-                            continue;
-                        }
-                        ScanPlan::SkipDuplicate => {
-                            let fname = Path::new(&path_str).file_name()
-                                .and_then(|n| n.to_str()).unwrap_or(&path_str);
-                            log::debug!("Skipping duplicate content: {}", path_str);
-                            let _ = self.db.add_process_log(
-                                "info",
-                                &format!("Skipped duplicate file (same content already scanned): {fname}"),
-                            );
-                            result.skipped += 1;
-                            char_files_skipped += 1;
-                            continue;
-                        }
-                        ScanPlan::SkipChanged => {
-                            let fname = Path::new(&path_str).file_name()
-                                .and_then(|n| n.to_str()).unwrap_or(&path_str);
-                            let _ = self.db.add_process_log(
-                                "warn",
-                                &format!("Log file changed unexpectedly (rotated/truncated); skipped — run Rescan Logs to reconcile: {fname}"),
-                            );
-                            result.skipped += 1;
-                            char_files_skipped += 1;
-                            continue;
-                        }
-                        ScanPlan::ReadError(e) => {
-                            log::warn!("Error reading {}: {}", path_str, e);
-                            let _ = self.db.add_process_log(
-                                "error",
-                                &format!("Could not read file: {} — {}", path_str, e),
-                            );
-                            result.errors += 1;
-                            char_files_skipped += 1;
-                            continue;
-                        }
-                        ScanPlan::Scan { bytes, offset, full_hash, count_login: is_full_scan } => {
-                            (bytes, offset, full_hash, is_full_scan)
-                        }
-                    };
-
-                let initial = if offset > 0 {
-                    self.active_char_at_offset(&bytes, offset)?
-                        .or_else(|| Some((char_id, char_name.clone())))
-                } else {
-                    Some((char_id, char_name.clone()))
-                };
-                match self.scan_bytes(&bytes[offset..], initial, &path_str, true, is_full_scan) {
-                    Ok(file_result) => {
-                        result.files_scanned += 1;
-                        result.lines_parsed += file_result.lines_parsed;
-                        result.events_found += file_result.events_found;
-                        char_files_scanned += 1;
-                        char_events_found += file_result.events_found;
-
-                        for (trainer, count) in &file_result.override_skips {
-                            let fname = Path::new(&path_str).file_name()
-                                .and_then(|n| n.to_str()).unwrap_or(&path_str);
-                            let _ = self.db.add_process_log(
-                                "info",
-                                &format!("Skipped {count} rank(s) for {trainer} ({char_name}) — override active: {fname}"),
-                            );
-                        }
-
-                        let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
-                        self.db
-                            .mark_log_scanned(char_id, &path_str, &full_hash, bytes.len() as i64, &now)?;
-                    }
-                    Err(e) => {
-                        log::warn!("Error scanning {}: {}", path_str, e);
-                        let _ = self.db.add_process_log(
-                            "error",
-                            &format!("Error scanning file: {} — {}", path_str, e),
-                        );
-                        result.errors += 1;
-                        char_files_skipped += 1;
-                    }
-                }
-            }
-            // Apply only the most recent reflect output for this character
-            self.flush_reflect_lastys(char_id)?;
-            let _ = self.db.add_process_log(
-                "info",
-                &format!(
-                    "Scanned {char_name}: {char_files_scanned} file(s) processed, \
-                     {char_files_skipped} skipped, {char_events_found} events found"
-                ),
-            );
-            result.characters += 1;
-        }
-
-        // Also scan loose CL Log files sitting directly in this log root.
-        for log_path in find_log_files(folder)? {
-            self.scan_loose_file(&log_path, force, true, result)?;
-        }
-
-        Ok(())
+        self.scan_folder_with_progress_inner(folder, force, true, &|_, _, _| {}, result)
     }
 
     /// Scan a single loose log file (one sitting directly in a log root, with no character
@@ -309,26 +453,27 @@ impl LogParser {
     fn scan_loose_file(
         &self,
         log_path: &Path,
-        force: bool,
+        plan: ScanPlan,
         index_lines: bool,
         result: &mut ScanResult,
     ) -> Result<bool> {
-        let path_str = log_path.to_string_lossy().to_string();
-        let (bytes, offset, full_hash, is_full_scan) = match self.plan_file_scan(log_path, &path_str, force)? {
+        let path_str = self.db.log_path_key(&log_path.to_string_lossy())?;
+        let file = match plan {
             ScanPlan::Skip | ScanPlan::SkipDuplicate | ScanPlan::SkipChanged => {
                 result.skipped += 1;
                 return Ok(false);
             }
             ScanPlan::ReadError(e) => {
                 log::warn!("Error reading {}: {}", path_str, e);
-                result.errors += 1;
+                self.file_failed(result, log_path, &AmanuensisError::file_unreadable(log_path, e), 0);
                 return Ok(false);
             }
-            ScanPlan::Scan { bytes, offset, full_hash, count_login } => (bytes, offset, full_hash, count_login),
+            ScanPlan::Scan(file) => file,
         };
 
-        let initial = self.active_char_at_offset(&bytes, offset)?;
-        let file_result = self.scan_bytes(&bytes[offset..], initial, &path_str, index_lines, is_full_scan)?;
+        let initial = self.resume_character(file.resume_name)?;
+        let file_result =
+            self.scan_lines(file.lines, file.first_line, initial, &path_str, index_lines, file.count_login)?;
         if !file_result.attributed {
             // No determinable character anywhere in the file — skip and log; do NOT mark
             // scanned, and never create an "Unknown" character.
@@ -343,98 +488,97 @@ impl LogParser {
         // The log_files.character_id FK is enforced (rusqlite's bundled SQLite is built with
         // SQLITE_DEFAULT_FOREIGN_KEYS=1), so a placeholder 0 would be rejected. Use the first
         // real character the file attributed to for the bookkeeping row (events themselves were
-        // already attributed to their real characters inside scan_bytes). attributed == true
+        // already attributed to their real characters inside scan_lines). attributed == true
         // guarantees first_char_id is Some. NOTE: for a multi-character loose file this stored
         // log_files.character_id is the FIRST attributed character ONLY — it is bookkeeping /
         // offset-resume metadata (skip-guard + tail-resume), not an attribution of the file's
-        // events, which were each counted under their real active character in scan_bytes.
+        // events, which were each counted under their real active character in scan_lines.
         let book_char_id = file_result.first_char_id.expect("attributed file must have a first_char_id");
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        self.db.mark_log_scanned(book_char_id, &path_str, &full_hash, bytes.len() as i64, &now)?;
+        let now = crate::date::now();
+        self.db.mark_log_scanned(book_char_id, &log_path.to_string_lossy(), &file.full_hash, file.byte_len as i64, &now)?;
+        self.db.clear_scan_error(&log_path.to_string_lossy())?;
         Ok(true)
     }
 
-    /// Scan log file bytes and process events into the database.
-    /// Decide how to (re)scan a single log file, implementing offset-resume so that a
-    /// daily Clan Lord log which GROWS as the player keeps playing is picked up on the
-    /// next incremental scan instead of being skipped wholesale.
-    ///
-    /// Fast path: a previously-scanned file whose on-disk size is unchanged is skipped
-    /// without reading it. Only files that grew (or are new) are read.
-    fn plan_file_scan(&self, log_path: &Path, path_str: &str, force: bool) -> Result<ScanPlan> {
-        let prior = if force {
-            None
+    /// Start preparing `paths` on the worker pool (see `prepare`).
+    fn prepare_files(&self, paths: Vec<PathBuf>, force: bool) -> Result<PreparedFiles> {
+        PreparedFiles::start(&self.db, &self.trainer_db, paths, force, self.complete_lines_only.get(), self.nice.get())
+    }
+
+    /// Per-scan setup: a cleared character cache (characters may have been deleted since the
+    /// last scan) and the bulk-scan PRAGMAs, or their low-memory variant for nice scans.
+    fn begin_scan(&self) -> Result<()> {
+        self.characters.clear();
+        if self.nice.get() {
+            self.db.set_nice_scan_pragmas()
         } else {
-            self.db.get_log_scan_state(path_str)?
-        };
+            self.db.set_scan_pragmas()
+        }
+    }
 
-        if let Some((prev_len, _)) = &prior {
-            if *prev_len > 0 {
-                // Known length: skip without reading when the file hasn't grown.
-                if let Ok(meta) = std::fs::metadata(log_path) {
-                    if meta.len() == *prev_len as u64 {
-                        return Ok(ScanPlan::Skip);
-                    }
-                }
-            } else {
-                // Legacy row recorded before offset-resume (byte_len unknown): preserve the
-                // old skip-by-path behavior. A full Rescan Logs repopulates byte_len.
-                return Ok(ScanPlan::Skip);
+    /// Commit a scan's transaction and run the hooks its events queued, or roll it back and
+    /// drop them (and its notifications) so nothing announces events that were never kept.
+    /// Either way the bulk-scan PRAGMAs are reset.
+    fn end_scan(&self, outcome: Result<()>) -> Result<()> {
+        let queued = std::mem::take(&mut *self.queued_hooks.borrow_mut());
+        match outcome {
+            Ok(()) => {
+                self.db.commit_transaction()?;
+                self.db.reset_pragmas()?;
+                self.run_hooks(&queued);
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self.db.rollback_transaction();
+                let _ = self.db.reset_pragmas();
+                let _ = self.take_notifications();
+                Err(e)
             }
         }
+    }
 
-        let bytes = match std::fs::read(log_path) {
-            Ok(b) => b,
-            Err(e) => return Ok(ScanPlan::ReadError(e)),
+    /// Count a file that couldn't be read or scanned and keep the details, in the result and
+    /// in `scan_errors` (until the file scans cleanly). `line` is 0 when not in the line loop.
+    fn file_failed(&self, result: &mut ScanResult, log_path: &Path, error: &AmanuensisError, line: usize) {
+        let failure = ScanError {
+            file_path: log_path.to_string_lossy().to_string(),
+            line: (line > 0).then_some(line as i64),
+            kind: error.kind().to_string(),
+            message: error.to_string(),
+            created_at: Timestamp::now(),
         };
+        let _ = self.db.record_scan_error(&failure);
+        result.errors += 1;
+        result.error_details.push(failure);
+    }
 
-        match prior {
-            None => {
-                // Never-seen path. Dedup against identical content scanned under another path.
-                let full_hash = hash_bytes(&bytes);
-                if !force && self.db.is_hash_scanned(&full_hash)? {
-                    return Ok(ScanPlan::SkipDuplicate);
-                }
-                Ok(ScanPlan::Scan {
-                    bytes,
-                    offset: 0,
-                    full_hash,
-                    count_login: true,
-                })
-            }
-            Some((prev_len, prev_hash)) => {
-                let prev_len = prev_len as usize;
-                let cur_len = bytes.len();
-                if cur_len == prev_len {
-                    // Unchanged (metadata fast-path was unavailable).
-                    Ok(ScanPlan::Skip)
-                } else if cur_len > prev_len && hash_bytes(&bytes[..prev_len]) == prev_hash {
-                    // True append: scan only the new tail; the login was already counted.
-                    let full_hash = hash_bytes(&bytes);
-                    Ok(ScanPlan::Scan {
-                        bytes,
-                        offset: prev_len,
-                        full_hash,
-                        count_login: false,
-                    })
-                } else {
-                    // Shrunk or prefix changed → rotated/replaced. Can't reconcile
-                    // incrementally without double-counting; skip and defer to a rescan.
-                    Ok(ScanPlan::SkipChanged)
-                }
-            }
-        }
+    /// Apply one file's classified lines to the database. Counter and kill writes are
+    /// recorded against `file_path` in `scan_contributions`, to show what each file added.
+    /// `first_line` is the number of lines before `lines` (non-zero for tail scans).
+    fn scan_lines(
+        &self,
+        lines: PreparedLines,
+        first_line: usize,
+        initial_char: Option<(i64, String)>,
+        file_path: &str,
+        index_lines: bool,
+        is_full_scan: bool,
+    ) -> Result<FileResult> {
+        self.db.set_scan_provenance(Some(file_path));
+        self.scan_line.set(first_line);
+        let result = self.scan_lines_inner(lines, initial_char, file_path, index_lines, is_full_scan);
+        self.db.set_scan_provenance(None);
+        result
     }
 
-    fn scan_bytes(
+    fn scan_lines_inner(
         &self,
-        bytes: &[u8],
+        lines: PreparedLines,
         initial_char: Option<(i64, String)>,
         file_path: &str,
         index_lines: bool,
         is_full_scan: bool,
     ) -> Result<FileResult> {
-        let content = decode_log_bytes(bytes);
         // Logins are counted per `Welcome to Clan Lord` (credited to that welcome's character);
         // the start_date fallback and the no-welcome fallback login are credited to the initial
         // (folder) character. Event attribution follows the mutable `active` below.
@@ -443,7 +587,12 @@ impl LogParser {
         let mut file_result = FileResult::default();
         let mut found_login = false;
         let mut first_date_str: Option<String> = None;
+        // Search index rows, inserted a batch at a time as the file is read.
         let mut log_lines: Vec<(i64, String, String, String)> = Vec::new();
+        let index_batch = if self.nice.get() { 200 } else { 1000 };
+        // Search index retention (see `crate::retention`), resolved once per file.
+        let retention = if index_lines { self.db.retention_policy()? } else { RetentionPolicy::default() };
+        let retention_cutoff = retention.cutoff(crate::date::now_naive());
 
         // Use filename date as the starting fallback; updated by each timestamped line.
         let filename_only = std::path::Path::new(file_path)
@@ -451,7 +600,7 @@ impl LogParser {
             .and_then(|n| n.to_str())
             .unwrap_or("");
         let filename_date = parse_filename_date(filename_only);
-        let mut current_date: String = filename_date.clone().unwrap_or_default();
+        let mut current_date: Option<Timestamp> = filename_date.as_deref().and_then(Timestamp::parse);
         let mut had_real_timestamp = false;
 
         // State for parsing Ranger reflect creature lists (multi-line).
@@ -472,40 +621,86 @@ impl LogParser {
         // line. Starts as the caller-provided fallback (folder name) or None for loose files.
         let mut active: Option<(i64, String)> = initial_char.clone();
         let mut saw_welcome_login = false;
-
-        for line in content.lines() {
+        // The play session being tallied, flushed at each welcome, disconnect and end of file.
+        // The flag marks a tail-scan continuation of a session whose welcome was already read.
+        let mut session: Option<(Session, bool)> = None;
+        // Set by a disconnect: lines before the next welcome are not played, so they open no
+        // session (their events still count everywhere else).
+        let mut disconnected = false;
+        // The area visit in progress (character, visit), flushed along with the session.
+        let mut zone: Option<(i64, ZoneVisit)> = None;
+        self.current_area.borrow_mut().take();
+        // The arena last entered since login, credited on arena bouts.
+        let mut arena = String::new();
+        // Assists and other players' kills waiting to be paired (timestamped lines only).
+        let mut assists = AssistTracker::default();
+        // Date of the active character's last fall, until a depart or recovery later in this
+        // file resolves it.
+        let mut fallen_at: Option<String> = None;
+
+        let total_lines = lines.len();
+        let mut lines = lines.read(&self.trainer_db)?;
+        // A filename that doesn't date the file: the lines before the first timestamp take
+        // that timestamp, so nothing they record (the welcome and login above all) is lost.
+        let mut undated: Vec<Result<ClassifiedLine>> = Vec::new();
+        if current_date.is_none() {
+            for classified in lines.by_ref() {
+                let ts = classified.as_ref().ok().and_then(|c| c.ts);
+                undated.push(classified);
+                if let Some(dt) = ts {
+                    current_date = Some(dt.into());
+                    break;
+                }
+            }
+        }
+        for classified in undated.into_iter().chain(lines) {
+            let classified = classified?;
             file_result.lines_parsed += 1;
+            self.scan_line.set(self.scan_line.get() + 1);
+            if file_result.lines_parsed % LINE_PROGRESS_INTERVAL == 0 {
+                self.report_line_progress(file_result.lines_parsed, total_lines);
+            }
 
-            let (ts, message) = match parse_timestamp(line) {
-                Some((dt, msg)) => (Some(dt), msg),
-                None => (None, line),
-            };
-
-            let event = classify_line(message, &self.trainer_db);
+            let ClassifiedLine { line: raw, ts, message_start, mut event } = classified;
+            if let Some(rules) = self.creature_rules.borrow().as_ref() {
+                rules.canonicalize_event(&mut event);
+            }
+            let line = raw.as_str();
+            let message = &raw[message_start..];
 
-            let date_str = if let Some(dt) = ts {
+            if let Some(dt) = ts {
                 had_real_timestamp = true;
-                let s = dt.format("%Y-%m-%d %H:%M:%S").to_string();
-                current_date = s.clone();
-                s
-            } else {
-                current_date.clone()
+                current_date = Some(dt.into());
+            }
+            // Nothing in the file dates it (reported as an error after the file): nothing it
+            // records could be placed in time, so it is skipped.
+            let Some(date) = current_date else {
+                continue;
             };
+            let date_str = date.to_string();
 
             // Welcome lines switch the active character (and `Welcome to Clan Lord` will
             // also be counted as a login in Task 2). Fall through afterward so the existing
             // WelcomeLogin event still records start_date under the now-active character.
             if let Some(caps) = patterns::WELCOME_LOGIN.captures(message) {
                 let name = titlecase_name(&caps[1]);
-                let id = self.db.get_or_create_character(&name)?;
+                let id = self.characters.get_or_create(&self.db, &name)?;
                 self.load_override_config(id)?;
                 self.db.increment_character_field(id, "logins", 1)?;
                 saw_welcome_login = true;
+                self.flush_session(session.take(), zone.take())?;
+                fallen_at = None;
+                session = Some((Session::new(id, file_path, date), false));
+                disconnected = false;
                 active = Some((id, name));
             } else if let Some(caps) = patterns::WELCOME_BACK.captures(message) {
                 let name = titlecase_name(&caps[1]);
-                let id = self.db.get_or_create_character(&name)?;
+                let id = self.characters.get_or_create(&self.db, &name)?;
                 self.load_override_config(id)?;
+                self.flush_session(session.take(), zone.take())?;
+                fallen_at = None;
+                session = Some((Session::new(id, file_path, date), false));
+                disconnected = false;
                 active = Some((id, name));
             }
 
@@ -522,17 +717,70 @@ impl LogParser {
                 None => continue,
             };
 
-            if index_lines && !line.trim().is_empty() {
+            if !disconnected {
+                let (tally, _) = session
+                    .get_or_insert_with(|| (Session::new(char_id, file_path, date), !is_full_scan));
+                if ts.is_some() {
+                    tally.ended_at = date;
+                }
+            }
+
+            if index_lines
+                && !line.trim().is_empty()
+                && retention.keeps(retention_cutoff.as_deref(), &date_str, &event)
+            {
                 log_lines.push((
                     char_id,
                     line.to_string(),
                     date_str.clone(),
                     file_path.to_string(),
                 ));
+                if log_lines.len() >= index_batch {
+                    self.flush_log_lines(&mut log_lines)?;
+                }
+            }
+
+            if self.track_chat.get() {
+                if let Some(chat) = line_classifier::parse_chat(message) {
+                    let speaker = chat.speaker.as_deref().unwrap_or(char_name);
+                    self.db.record_chat(char_id, speaker, &chat.verb, &chat.text, &date_str, file_path)?;
+                }
+            }
+
+            // Where the character is, inferred from the creatures it fights (the log has no
+            // zone messages). A creature of another area closes the current visit there.
+            let sighted = match &event {
+                LogEvent::SoloKill { creature, .. }
+                | LogEvent::AssistedKill { creature, .. }
+                | LogEvent::AttackHit { creature }
+                | LogEvent::AttackMiss { creature }
+                | LogEvent::DamageTaken { creature } => Some(creature.as_str()),
+                LogEvent::Fallen { name, cause } if name.eq_ignore_ascii_case(char_name) => Some(cause.as_str()),
+                _ => None,
+            };
+            if let Some(area) = sighted.filter(|_| ts.is_some()).and_then(|c| self.creature_db.borrow().get_area(c)) {
+                match &mut zone {
+                    Some((_, visit)) if visit.area == area => {
+                        visit.left_at = date;
+                        visit.events += 1;
+                    }
+                    _ => {
+                        if let Some((id, mut visit)) = zone.take() {
+                            visit.left_at = date;
+                            self.db.record_zone_visit(id, &visit)?;
+                        }
+                        *self.current_area.borrow_mut() = Some(area.clone());
+                        zone = Some((char_id, ZoneVisit::new(area, date)));
+                    }
+                }
+            }
+
+            if !matches!(event, LogEvent::Ignored) {
+                self.emit_event(char_name, &date_str, file_path, &event);
             }
 
             // Track first timestamp in file for file-as-login fallback
-            if first_date_str.is_none() && !date_str.is_empty() {
+            if first_date_str.is_none() {
                 first_date_str = Some(date_str.clone());
             }
 
@@ -595,12 +843,66 @@ impl LogParser {
             }
 
             match event {
-                LogEvent::Ignored
-                | LogEvent::CoinBalance { .. }
+                LogEvent::Ignored => {
+                    if self.track_unknown_lines.get() {
+                        if let Some(shape) = line_classifier::unrecognized_system_message(message) {
+                            self.db.record_unknown_line(char_id, &shape, message.trim(), &date_str)?;
+                        }
+                    }
+                }
+                LogEvent::CoinBalance { .. }
                 | LogEvent::ExperienceGain
-                | LogEvent::ClanningChange { .. }
-                | LogEvent::Disconnect
-                | LogEvent::Recovered { .. } => {}
+                | LogEvent::ClanningChange { .. } => {}
+
+                // Other players falling, being raised or sharing with us mark them as
+                // hunting companions for the day.
+                LogEvent::HealReceived { name } => {
+                    self.record_heal_event(char_id, char_name, &name, "received", &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::HealGiven { name } => {
+                    self.record_heal_event(char_id, char_name, &name, "given", &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::DraggedBy { name } => {
+                    if let Some(date) = &fallen_at {
+                        self.db.record_death_drag(char_id, date)?;
+                    }
+                    self.record_rescue_event(char_id, char_name, &name, "received", "drag", &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::RaisedBy { name } => {
+                    self.record_rescue_event(char_id, char_name, &name, "received", "raise", &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::RaisedOther { name } => {
+                    self.record_rescue_event(char_id, char_name, &name, "given", "raise", &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::ThoughtReceived { name } => {
+                    self.db.record_thought(char_id, &name, "received", day_bucket(&date_str))?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::ThoughtSent { name } => {
+                    self.db.record_thought(char_id, &name, "sent", day_bucket(&date_str))?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::Recovered { name } if name.eq_ignore_ascii_case(char_name) => {
+                    if let Some(date) = fallen_at.take() {
+                        self.db.resolve_death(char_id, &date, "rescued", &date_str)?;
+                    }
+                }
+                LogEvent::Recovered { name } | LogEvent::SharingStarted { name } => {
+                    if !name.eq_ignore_ascii_case(char_name) {
+                        self.db.record_companion(char_id, &name, day_bucket(&date_str))?;
+                        file_result.events_found += 1;
+                    }
+                }
+
+                LogEvent::Disconnect => {
+                    self.flush_session(session.take(), zone.take())?;
+                    disconnected = true;
+                }
 
                 LogEvent::StudyProgress { creature, .. } => {
                     // Track as in-progress — these lines precede the reflect header and identify
@@ -620,6 +922,7 @@ impl LogParser {
 
                 LogEvent::Login { .. } | LogEvent::Reconnect { .. } => {
                     found_login = true;
+                    arena.clear();
                     if !date_str.is_empty() {
                         self.db.update_start_date(char_id, &date_str)?;
                     }
@@ -628,39 +931,110 @@ impl LogParser {
 
                 LogEvent::SoloKill { creature, verb } => {
                     let field = kill_verb_to_field(&verb, false);
-                    let value = self.creature_db.get_value(&creature).unwrap_or(0);
+                    let value = self.creature_db.borrow().get_value(&creature).unwrap_or(0);
                     self.db
                         .upsert_kill(char_id, &creature, field, value, &date_str)?;
+                    self.record_kill_milestones(char_id, char_name, &creature, true, &date_str)?;
                     self.db
                         .upsert_kill_hourly(char_id, &creature, field, &hour_bucket(&date_str))?;
+                    self.db
+                        .record_history_event(char_id, "kill", &creature, value as i64, &date_str, self.scan_line.get())?;
+                    self.record_kill_location(char_id, &creature, "solo_count")?;
+                    if let Some((tally, _)) = &mut session {
+                        tally.kills += 1;
+                    }
+                    self.fire_hooks("kill", char_name, &date_str, &creature, None);
+                    if self.hooks_want("boss_kill") && self.creature_db.borrow().is_boss(&creature) {
+                        self.fire_hooks("boss_kill", char_name, &date_str, &creature, None);
+                    }
+                    file_result.events_found += 1;
+                }
+                LogEvent::AttackHit { creature } => {
+                    self.db.record_attack(char_id, &creature, true)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::AttackMiss { creature } => {
+                    self.db.record_attack(char_id, &creature, false)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::DamageTaken { creature } => {
+                    self.db.record_damage_taken(char_id, &creature)?;
                     file_result.events_found += 1;
                 }
                 LogEvent::AssistedKill { creature, verb } => {
                     let field = kill_verb_to_field(&verb, true);
-                    let value = self.creature_db.get_value(&creature).unwrap_or(0);
+                    let value = self.creature_db.borrow().get_value(&creature).unwrap_or(0);
                     self.db
                         .upsert_kill(char_id, &creature, field, value, &date_str)?;
+                    self.record_kill_milestones(char_id, char_name, &creature, false, &date_str)?;
                     self.db
                         .upsert_kill_hourly(char_id, &creature, field, &hour_bucket(&date_str))?;
+                    self.db.record_history_event(
+                        char_id, "assisted_kill", &creature, value as i64, &date_str, self.scan_line.get(),
+                    )?;
+                    self.record_kill_location(char_id, &creature, "assisted_count")?;
+                    if let Some((tally, _)) = &mut session {
+                        tally.kills += 1;
+                    }
+                    if let Some(dt) = ts {
+                        let epoch = dt.and_utc().timestamp();
+                        if let Some(a) = assists.assisted(char_id, &creature, epoch, &date_str) {
+                            self.db.record_assist(a.char_id, &a.creature, &a.player, &a.date)?;
+                        }
+                    }
                     file_result.events_found += 1;
                 }
+                LogEvent::OtherKill { player, creature, .. } => {
+                    if let Some(dt) = ts.filter(|_| !player.eq_ignore_ascii_case(char_name)) {
+                        let epoch = dt.and_utc().timestamp();
+                        if let Some(a) = assists.other_kill(&player, &creature, epoch) {
+                            self.db.record_assist(a.char_id, &a.creature, &a.player, &a.date)?;
+                        }
+                    }
+                }
 
                 LogEvent::Fallen { name, cause } => {
                     if name.eq_ignore_ascii_case(char_name) {
-                        let value = self.creature_db.get_value(&cause).unwrap_or(0);
+                        let value = self.creature_db.borrow().get_value(&cause).unwrap_or(0);
                         self.db
                             .upsert_kill(char_id, &cause, "killed_by_count", value, &date_str)?;
+                        self.record_kill_location(char_id, &cause, "killed_by_count")?;
+                        self.db.record_death(char_id, &cause, self.current_area.borrow().as_deref(), &date_str)?;
+                        fallen_at = Some(date_str.clone());
                         self.db.increment_character_field(char_id, "deaths", 1)?;
+                        if let Some((tally, _)) = &mut session {
+                            tally.deaths += 1;
+                        }
+                        self.db.record_history_event(
+                            char_id, "death", &cause, value as i64, &date_str, self.scan_line.get(),
+                        )?;
+                        self.fire_hooks("death", char_name, &date_str, &cause, None);
+                        file_result.events_found += 1;
+                    } else {
+                        self.db.record_companion(char_id, &name, day_bucket(&date_str))?;
+                        if self.track_witnessed_falls.get() {
+                            self.db.record_witnessed_fall(char_id, &name, &cause, &date_str)?;
+                        }
                         file_result.events_found += 1;
                     }
                 }
                 LogEvent::FirstDepart => {
+                    if let Some(date) = fallen_at.take() {
+                        self.db.resolve_death(char_id, &date, "departed", &date_str)?;
+                    }
                     self.db.increment_character_field(char_id, "departs", 1)?;
+                    self.db
+                        .record_history_event(char_id, "depart", "", 0, &date_str, self.scan_line.get())?;
                     file_result.events_found += 1;
                 }
                 LogEvent::Depart { count } => {
+                    if let Some(date) = fallen_at.take() {
+                        self.db.resolve_death(char_id, &date, "departed", &date_str)?;
+                    }
                     // Set departs to the absolute count (it's cumulative)
                     self.db.set_departs(char_id, count)?;
+                    self.db
+                        .record_history_event(char_id, "depart", "", 0, &date_str, self.scan_line.get())?;
                     file_result.events_found += 1;
                 }
 
@@ -669,6 +1043,21 @@ impl LogParser {
                         let multiplier = self.trainer_db.get_multiplier(&trainer_name);
                         self.db
                             .upsert_trainer_rank(char_id, &trainer_name, &date_str, multiplier)?;
+                        self.db.record_history_event(
+                            char_id, "rank", &trainer_name, 0, &date_str, self.scan_line.get(),
+                        )?;
+                        if let Some((tally, _)) = &mut session {
+                            tally.ranks += 1;
+                        }
+                        if self.hooks_want("rank") {
+                            let ranks = self
+                                .db
+                                .get_trainers(char_id)?
+                                .into_iter()
+                                .find(|t| t.trainer_name == trainer_name)
+                                .map(|t| t.ranks);
+                            self.fire_hooks("rank", char_name, &date_str, &trainer_name, ranks);
+                        }
                         file_result.events_found += 1;
                     } else {
                         *file_result.override_skips.entry(trainer_name).or_insert(0) += 1;
@@ -717,9 +1106,32 @@ impl LogParser {
                     }
                 }
 
+                LogEvent::TrainerMaxed { trainer_name, character_name } => {
+                    if character_name.is_none_or(|c| c.eq_ignore_ascii_case(char_name)) {
+                        let multiplier = self.trainer_db.get_multiplier(&trainer_name);
+                        self.db.set_trainer_maxed(char_id, &trainer_name, multiplier)?;
+                        file_result.events_found += 1;
+                    }
+                }
+
+                LogEvent::TrainerMaxedUnhailed { trainer_name } => {
+                    if let Some((_, true)) = pending_bow_checkpoints.remove(&trainer_name) {
+                        let multiplier = self.trainer_db.get_multiplier(&trainer_name);
+                        self.db.set_trainer_maxed(char_id, &trainer_name, multiplier)?;
+                        file_result.events_found += 1;
+                    }
+                }
+
                 LogEvent::CoinsPickedUp { amount } => {
                     self.db
                         .increment_character_field(char_id, "coins_picked_up", amount)?;
+                    self.db
+                        .upsert_coin_daily(char_id, "picked_up", day_bucket(&date_str), amount)?;
+                    self.db
+                        .record_history_event(char_id, "coins", "picked_up", amount, &date_str, self.scan_line.get())?;
+                    if let Some((tally, _)) = &mut session {
+                        tally.coins += amount;
+                    }
                     file_result.events_found += 1;
                 }
                 LogEvent::LootShare {
@@ -740,6 +1152,19 @@ impl LogParser {
                         self.db
                             .increment_character_field(char_id, worth_field, worth)?;
                     }
+                    let ledger_source = match loot_type {
+                        LootType::Fur => "fur",
+                        LootType::Blood => "blood",
+                        LootType::Mandible => "mandible",
+                        LootType::Other => "bounty",
+                    };
+                    self.db
+                        .upsert_coin_daily(char_id, ledger_source, day_bucket(&date_str), amount)?;
+                    self.db
+                        .record_history_event(char_id, "coins", ledger_source, amount, &date_str, self.scan_line.get())?;
+                    if let Some((tally, _)) = &mut session {
+                        tally.coins += amount;
+                    }
                     // Track best single-loot recovery per creature (by player's share)
                     let loot_type_label = match loot_type {
                         LootType::Fur => "fur",
@@ -755,6 +1180,10 @@ impl LogParser {
                     // Track as negative coins (spent on studies)
                     self.db
                         .increment_character_field(char_id, "chest_coins", amount)?;
+                    self.db
+                        .upsert_coin_daily(char_id, "study", day_bucket(&date_str), -amount)?;
+                    self.db
+                        .record_history_event(char_id, "coins", "study", -amount, &date_str, self.scan_line.get())?;
                     file_result.events_found += 1;
                 }
 
@@ -773,9 +1202,10 @@ impl LogParser {
                         .increment_character_field(char_id, "chains_broken", 1)?;
                     file_result.events_found += 1;
                 }
-                LogEvent::ChainUsed { .. } => {
+                LogEvent::ChainUsed { target } => {
                     self.db
                         .increment_character_field(char_id, "chains_used", 1)?;
+                    self.record_rescue_event(char_id, char_name, &target, "given", "drag", &date_str)?;
                     file_result.events_found += 1;
                 }
                 LogEvent::ShieldstoneUsed => {
@@ -857,14 +1287,79 @@ impl LogParser {
                         .increment_character_field(char_id, "esteem", 1)?;
                     file_result.events_found += 1;
                 }
-                LogEvent::ProfessionAnnouncement { name, profession } => {
+                LogEvent::ProfessionAnnouncement { name, profession, circle } => {
                     if name.eq_ignore_ascii_case(char_name) {
                         self.db
                             .update_character_profession(char_id, &profession)?;
+                        if let Some(circle) = circle {
+                            self.db.record_circle(char_id, &profession, circle, &date_str)?;
+                        }
+                    }
+                    file_result.events_found += 1;
+                }
+
+                LogEvent::ClanJoined { clan } => {
+                    self.db.record_clan_event(char_id, "join", &clan, &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::ClanLeft { clan } => {
+                    self.db.record_clan_event(char_id, "leave", &clan, &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::TitleEarned { name, title } => {
+                    if name.is_none_or(|n| n.eq_ignore_ascii_case(char_name)) {
+                        self.db.record_clan_event(char_id, "title", &title, &date_str)?;
+                        self.fire_hooks("title", char_name, &date_str, &title, None);
                     }
                     file_result.events_found += 1;
                 }
 
+                LogEvent::ArenaEntered { arena: entered } => {
+                    self.db.record_tournament_event(char_id, "entered", &entered, "", &date_str)?;
+                    arena = entered;
+                    file_result.events_found += 1;
+                }
+                LogEvent::ArenaBout { opponent, won } => {
+                    let kind = if won { "win" } else { "loss" };
+                    self.db.record_tournament_event(char_id, kind, &arena, &opponent, &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::TournamentWon { winner } => {
+                    if winner.eq_ignore_ascii_case(char_name) {
+                        self.db.record_tournament_event(char_id, "tournament_win", &arena, "", &date_str)?;
+                    }
+                    file_result.events_found += 1;
+                }
+
+                LogEvent::PetAdopted { creature } => {
+                    self.db.adopt_pet(char_id, &creature, &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::PetNamed { pet, name } => {
+                    self.db.name_pet(char_id, &pet, &name)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::PetFed { pet } => {
+                    self.db.feed_pet(char_id, &pet, &date_str, self.scan_line.get())?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::PetDied { pet } => {
+                    self.db.record_pet_death(char_id, &pet, &date_str, self.scan_line.get())?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::PetReleased { pet } => {
+                    self.db.release_pet(char_id, &pet, &date_str)?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::PetTraining { pet } => {
+                    self.db.record_pet_training(char_id, &pet, &date_str, self.scan_line.get())?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::PetRank { pet } => {
+                    self.db.record_pet_rank(char_id, &pet, &date_str, self.scan_line.get())?;
+                    file_result.events_found += 1;
+                }
+
                 LogEvent::StudyAbandon { creature } => {
                     self.abandoned_studies
                         .borrow_mut()
@@ -915,10 +1410,23 @@ impl LogParser {
                         .or_default()
                         .remove(&creature);
                     self.db.finish_lasty(char_id, &creature, &lasty_type, &date_str)?;
+                    self.fire_hooks("lasty_finished", char_name, &date_str, &creature, None);
                     file_result.events_found += 1;
                 }
                 LogEvent::LastyCompleted { trainer } => {
                     self.db.complete_lasty(char_id, &trainer)?;
+                    self.fire_hooks("lasty_completed", char_name, &date_str, &trainer, None);
+                    file_result.events_found += 1;
+                }
+                LogEvent::StatBoost { stat, permanent } => {
+                    let kind = if permanent { "permanent_boost" } else { "boost" };
+                    self.db
+                        .record_history_event(char_id, kind, &stat, 0, &date_str, self.scan_line.get())?;
+                    file_result.events_found += 1;
+                }
+                LogEvent::StatBoostFaded { stat } => {
+                    self.db
+                        .record_history_event(char_id, "boost_faded", &stat, 0, &date_str, self.scan_line.get())?;
                     file_result.events_found += 1;
                 }
                 LogEvent::Untrained => {
@@ -948,6 +1456,10 @@ impl LogParser {
                 }
             }
         }
+        self.scan_line.set(0);
+        self.report_line_progress(total_lines, total_lines);
+
+        self.flush_session(session.take(), zone.take())?;
 
         // Log a warning if no per-line timestamps were found in this file.
         if !had_real_timestamp {
@@ -959,7 +1471,7 @@ impl LogParser {
             } else {
                 let _ = self.db.add_process_log(
                     "error",
-                    &format!("No timestamps in log lines and filename date could not be parsed: {filename_only}"),
+                    &format!("No timestamps in log lines and filename date could not be parsed, lines skipped: {filename_only}"),
                 );
             }
         }
@@ -981,20 +1493,37 @@ impl LogParser {
             }
         }
 
-        // Batch-insert log lines into FTS5 index
-        if index_lines && !log_lines.is_empty() {
-            for chunk in log_lines.chunks(1000) {
-                let refs: Vec<(i64, &str, &str, &str)> = chunk
-                    .iter()
-                    .map(|(id, content, ts, fp)| (*id, content.as_str(), ts.as_str(), fp.as_str()))
-                    .collect();
-                self.db.insert_log_lines(&refs)?;
-            }
-        }
+        self.flush_log_lines(&mut log_lines)?;
 
         Ok(file_result)
     }
 
+    /// Insert the pending search index rows into the FTS5 index.
+    fn flush_log_lines(&self, log_lines: &mut Vec<(i64, String, String, String)>) -> Result<()> {
+        if log_lines.is_empty() {
+            return Ok(());
+        }
+        let refs: Vec<(i64, &str, &str, &str)> = log_lines
+            .iter()
+            .map(|(id, content, ts, fp)| (*id, content.as_str(), ts.as_str(), fp.as_str()))
+            .collect();
+        self.db.insert_log_lines(&refs)?;
+        log_lines.clear();
+        Ok(())
+    }
+
+    /// Record a finished (or file-ending) session tally and its last area visit.
+    fn flush_session(&self, session: Option<(Session, bool)>, zone: Option<(i64, ZoneVisit)>) -> Result<()> {
+        self.current_area.borrow_mut().take();
+        if let Some((id, visit)) = zone {
+            self.db.record_zone_visit(id, &visit)?;
+        }
+        match session {
+            Some((tally, continuation)) => self.db.upsert_session(&tally, continuation),
+            None => Ok(()),
+        }
+    }
+
     /// Apply the most recent Ranger reflect data for a character, upserting all
     /// finished creatures as Movements lastys.  Called after all files for the character
     /// have been scanned so that only the most recent (most complete) reflect is used.
@@ -1044,9 +1573,9 @@ impl LogParser {
         let mut champion_ranks: i64 = 0;
 
         // Track most recent rank date per specialization (A: date-based tiebreaker)
-        let mut ranger_last: Option<String> = None;
-        let mut bloodmage_last: Option<String> = None;
-        let mut champion_last: Option<String> = None;
+        let mut ranger_last: Option<Timestamp> = None;
+        let mut bloodmage_last: Option<Timestamp> = None;
+        let mut champion_last: Option<Timestamp> = None;
 
         // C: Decay trainer ranks to subtract from specialization totals
         let mut gossamer_decay_ranks: i64 = 0;
@@ -1081,27 +1610,15 @@ impl LogParser {
                         "Ranger" => {
                             ranger_ranks += total;
                             // A: track most recent rank date for tiebreaking
-                            if let Some(date) = &t.date_of_last_rank {
-                                if ranger_last.as_deref() < Some(date.as_str()) {
-                                    ranger_last = Some(date.clone());
-                                }
-                            }
+                            ranger_last = ranger_last.max(t.date_of_last_rank);
                         }
                         "Bloodmage" => {
                             bloodmage_ranks += total;
-                            if let Some(date) = &t.date_of_last_rank {
-                                if bloodmage_last.as_deref() < Some(date.as_str()) {
-                                    bloodmage_last = Some(date.clone());
-                                }
-                            }
+                            bloodmage_last = bloodmage_last.max(t.date_of_last_rank);
                         }
                         "Champion" => {
                             champion_ranks += total;
-                            if let Some(date) = &t.date_of_last_rank {
-                                if champion_last.as_deref() < Some(date.as_str()) {
-                                    champion_last = Some(date.clone());
-                                }
-                            }
+                            champion_last = champion_last.max(t.date_of_last_rank);
                         }
                         _ => {}
                     }
@@ -1124,7 +1641,7 @@ impl LogParser {
             // A: Collect tied specializations, then tiebreak by most recent rank date.
             // Push in priority order (Ranger > Bloodmage > Champion) so stable sort
             // preserves the original priority when dates are also equal.
-            let mut tied: Vec<(Profession, Option<String>)> = Vec::new();
+            let mut tied: Vec<(Profession, Option<Timestamp>)> = Vec::new();
             if ranger_ranks == max_spec {
                 tied.push((Profession::Ranger, ranger_last));
             }
@@ -1137,7 +1654,7 @@ impl LogParser {
 
             // Sort descending by date (most recent first); stable sort preserves
             // insertion order on equal dates, giving Ranger > Bloodmage > Champion fallback.
-            tied.sort_by(|a, b| b.1.cmp(&a.1));
+            tied.sort_by_key(|t| std::cmp::Reverse(t.1));
             return Ok(tied.into_iter().next().map(|(p, _)| p).unwrap_or(Profession::Unknown));
         }
 
@@ -1185,22 +1702,12 @@ impl LogParser {
         }
 
         let _ = self.db.clear_process_logs();
-        self.db.set_scan_pragmas()?;
+        self.begin_scan()?;
         self.db.begin_transaction()?;
 
         let scan_result = self.scan_folder_with_progress_inner(folder, force, index_lines, &progress, &mut result);
 
-        match scan_result {
-            Ok(()) => {
-                self.db.commit_transaction()?;
-                self.db.reset_pragmas()?;
-            }
-            Err(e) => {
-                let _ = self.db.rollback_transaction();
-                let _ = self.db.reset_pragmas();
-                return Err(e);
-            }
-        }
+        self.end_scan(scan_result)?;
 
         Ok(result)
     }
@@ -1233,11 +1740,10 @@ impl LogParser {
             }
 
             let char_dir = entry.path();
-            let mut log_files = find_log_files(&char_dir)?;
+            let log_files = find_log_files(&char_dir)?;
             if log_files.is_empty() {
                 continue;
             }
-            log_files.sort();
 
             let char_name = log_files
                 .iter()
@@ -1258,10 +1764,16 @@ impl LogParser {
         total_files += loose_files.len();
 
         let mut current_file: usize = 0;
+        let paths: Vec<PathBuf> = all_work
+            .iter()
+            .flat_map(|(_, _, files)| files.iter().cloned())
+            .chain(loose_files.iter().cloned())
+            .collect();
+        let plans = self.prepare_files(paths, force)?;
 
         for (_char_dir, char_name, log_files) in &all_work {
             log::info!("Processing character: {}", char_name);
-            let char_id = self.db.get_or_create_character(char_name)?;
+            let char_id = self.characters.get_or_create(&self.db, char_name)?;
             self.load_override_config(char_id)?;
 
             let mut char_files_scanned: usize = 0;
@@ -1274,12 +1786,12 @@ impl LogParser {
                     .file_name()
                     .map(|f| f.to_string_lossy().to_string())
                     .unwrap_or_default();
+                self.check_cancelled()?;
                 progress(current_file, total_files, &filename);
 
-                let path_str = log_path.to_string_lossy().to_string();
+                let path_str = self.db.log_path_key(&log_path.to_string_lossy())?;
 
-                let (bytes, offset, full_hash, is_full_scan) =
-                    match self.plan_file_scan(log_path, &path_str, force)? {
+                let file = match plans.next(&self.db)? {
                         ScanPlan::Skip => {
                             result.skipped += 1;
                             char_files_skipped += 1;
@@ -1313,22 +1825,17 @@ impl LogParser {
                                 "error",
                                 &format!("Could not read file: {} — {}", path_str, e),
                             );
-                            result.errors += 1;
+                            self.file_failed(result, log_path, &AmanuensisError::file_unreadable(log_path.as_path(), e), 0);
                             char_files_skipped += 1;
                             continue;
                         }
-                        ScanPlan::Scan { bytes, offset, full_hash, count_login: is_full_scan } => {
-                            (bytes, offset, full_hash, is_full_scan)
-                        }
+                        ScanPlan::Scan(file) => file,
                     };
 
-                let initial = if offset > 0 {
-                    self.active_char_at_offset(&bytes, offset)?
-                        .or_else(|| Some((char_id, char_name.clone())))
-                } else {
-                    Some((char_id, char_name.clone()))
-                };
-                match self.scan_bytes(&bytes[offset..], initial, &path_str, index_lines, is_full_scan) {
+                let initial = self
+                    .resume_character(file.resume_name)?
+                    .or_else(|| Some((char_id, char_name.clone())));
+                match self.scan_lines(file.lines, file.first_line, initial, &path_str, index_lines, file.count_login) {
                     Ok(file_result) => {
                         result.files_scanned += 1;
                         result.lines_parsed += file_result.lines_parsed;
@@ -1345,9 +1852,10 @@ impl LogParser {
                             );
                         }
 
-                        let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                        let now = crate::date::now();
                         self.db
-                            .mark_log_scanned(char_id, &path_str, &full_hash, bytes.len() as i64, &now)?;
+                            .mark_log_scanned(char_id, &log_path.to_string_lossy(), &file.full_hash, file.byte_len as i64, &now)?;
+                        self.db.clear_scan_error(&log_path.to_string_lossy())?;
                     }
                     Err(e) => {
                         log::warn!("Error scanning {}: {}", path_str, e);
@@ -1355,7 +1863,7 @@ impl LogParser {
                             "error",
                             &format!("Error scanning file: {} — {}", path_str, e),
                         );
-                        result.errors += 1;
+                        self.file_failed(result, log_path, &e, self.scan_line.get());
                         char_files_skipped += 1;
                     }
                 }
@@ -1376,8 +1884,9 @@ impl LogParser {
         for log_path in &loose_files {
             current_file += 1;
             let filename = log_path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+            self.check_cancelled()?;
             progress(current_file, total_files, &filename);
-            self.scan_loose_file(log_path, force, index_lines, result)?;
+            self.scan_loose_file(log_path, plans.next(&self.db)?, index_lines, result)?;
         }
 
         Ok(())
@@ -1400,22 +1909,12 @@ impl LogParser {
         let mut result = ScanResult::default();
 
         let _ = self.db.clear_process_logs();
-        self.db.set_scan_pragmas()?;
+        self.begin_scan()?;
         self.db.begin_transaction()?;
 
         let scan_result = self.scan_files_with_progress_inner(files, force, index_lines, &progress, &mut result);
 
-        match scan_result {
-            Ok(()) => {
-                self.db.commit_transaction()?;
-                self.db.reset_pragmas()?;
-            }
-            Err(e) => {
-                let _ = self.db.rollback_transaction();
-                let _ = self.db.reset_pragmas();
-                return Err(e);
-            }
-        }
+        self.end_scan(scan_result)?;
 
         Ok(result)
     }
@@ -1433,18 +1932,19 @@ impl LogParser {
     {
         let total_files = files.len();
         let mut seen_characters = std::collections::HashSet::new();
+        let plans = self.prepare_files(files.to_vec(), force)?;
 
         for (i, log_path) in files.iter().enumerate() {
             let filename = log_path
                 .file_name()
                 .map(|f| f.to_string_lossy().to_string())
                 .unwrap_or_default();
+            self.check_cancelled()?;
             progress(i + 1, total_files, &filename);
 
-            let path_str = log_path.to_string_lossy().to_string();
+            let path_str = self.db.log_path_key(&log_path.to_string_lossy())?;
 
-            let (bytes, offset, full_hash, is_full_scan) =
-                match self.plan_file_scan(log_path, &path_str, force)? {
+            let file = match plans.next(&self.db)? {
                     ScanPlan::Skip => {
                         result.skipped += 1;
                         continue;
@@ -1475,18 +1975,16 @@ impl LogParser {
                             "error",
                             &format!("Could not read file: {} — {}", path_str, e),
                         );
-                        result.errors += 1;
+                        self.file_failed(result, log_path, &AmanuensisError::file_unreadable(log_path.as_path(), e), 0);
                         continue;
                     }
-                    ScanPlan::Scan { bytes, offset, full_hash, count_login: is_full_scan } => {
-                        (bytes, offset, full_hash, is_full_scan)
-                    }
+                    ScanPlan::Scan(file) => file,
                 };
 
             // Determine character from content; fall back to the parent directory name (an
             // explicit pick is usually inside a character folder). If neither yields a name,
             // skip and log — never invent an "Unknown" character.
-            let char_name = extract_character_name(&bytes).or_else(|| {
+            let char_name = file.welcome_name.clone().or_else(|| {
                 log_path.parent().and_then(|p| p.file_name()).map(|n| titlecase_name(&n.to_string_lossy()))
             });
             let char_name = match char_name {
@@ -1498,19 +1996,16 @@ impl LogParser {
                 }
             };
 
-            let char_id = self.db.get_or_create_character(&char_name)?;
+            let char_id = self.characters.get_or_create(&self.db, &char_name)?;
             if seen_characters.insert(char_name.clone()) {
                 result.characters += 1;
                 self.load_override_config(char_id)?;
             }
 
-            let initial = if offset > 0 {
-                self.active_char_at_offset(&bytes, offset)?
-                    .or_else(|| Some((char_id, char_name.clone())))
-            } else {
-                Some((char_id, char_name.clone()))
-            };
-            match self.scan_bytes(&bytes[offset..], initial, &path_str, index_lines, is_full_scan) {
+            let initial = self
+                .resume_character(file.resume_name)?
+                .or_else(|| Some((char_id, char_name.clone())));
+            match self.scan_lines(file.lines, file.first_line, initial, &path_str, index_lines, file.count_login) {
                 Ok(file_result) => {
                     result.files_scanned += 1;
                     result.lines_parsed += file_result.lines_parsed;
@@ -1525,9 +2020,10 @@ impl LogParser {
                         );
                     }
 
-                    let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                    let now = crate::date::now();
                     self.db
-                        .mark_log_scanned(char_id, &path_str, &full_hash, bytes.len() as i64, &now)?;
+                        .mark_log_scanned(char_id, &log_path.to_string_lossy(), &file.full_hash, file.byte_len as i64, &now)?;
+                    self.db.clear_scan_error(&log_path.to_string_lossy())?;
                 }
                 Err(e) => {
                     log::warn!("Error scanning {}: {}", path_str, e);
@@ -1535,7 +2031,7 @@ impl LogParser {
                         "error",
                         &format!("Error scanning file: {} — {}", path_str, e),
                     );
-                    result.errors += 1;
+                    self.file_failed(result, log_path, &e, self.scan_line.get());
                 }
             }
         }
@@ -1568,7 +2064,7 @@ impl LogParser {
         let mut combined = ScanResult::default();
 
         let _ = self.db.clear_process_logs();
-        self.db.set_scan_pragmas()?;
+        self.begin_scan()?;
         self.db.begin_transaction()?;
 
         let scan_result = (|| -> Result<()> {
@@ -1579,17 +2075,7 @@ impl LogParser {
             Ok(())
         })();
 
-        match scan_result {
-            Ok(()) => {
-                self.db.commit_transaction()?;
-                self.db.reset_pragmas()?;
-            }
-            Err(e) => {
-                let _ = self.db.rollback_transaction();
-                let _ = self.db.reset_pragmas();
-                return Err(e);
-            }
-        }
+        self.end_scan(scan_result)?;
 
         Ok(combined)
     }
@@ -1597,8 +2083,10 @@ impl LogParser {
     /// Rescan a set of source folders: clear log-derived data, scan each folder
     /// (honoring its own recursive flag), then finalize. Used by the GUI "Rescan Logs"
     /// action when the user has multiple remembered source folders. Rank overrides are
-    /// preserved (reset_log_data keeps them). The returned ScanResult sums the additive
-    /// per-folder counters; `characters` is the distinct character total after finalize.
+    /// preserved (reset_log_data keeps them). The reset runs in the scan's transaction, so
+    /// a cancelled or failed rescan leaves the previous data in place. The returned
+    /// ScanResult sums the additive per-folder counters; `characters` is the distinct
+    /// character total after finalize.
     pub fn rescan_sources<F>(
         &self,
         sources: &[(std::path::PathBuf, bool)],
@@ -1612,8 +2100,7 @@ impl LogParser {
         if sources.is_empty() {
             return Ok(ScanResult::default());
         }
-        self.db.reset_log_data()?;
-        self.scan_sources(sources, index_lines, progress)
+        self.scan_sources(sources, true, index_lines, progress)
     }
 
     /// Incrementally process all sources (force=false) WITHOUT resetting first. New files are
@@ -1631,58 +2118,58 @@ impl LogParser {
         if sources.is_empty() {
             return Ok(ScanResult::default());
         }
-        self.scan_sources(sources, index_lines, progress)
+        self.scan_sources(sources, false, index_lines, progress)
     }
 
-    /// Shared body for `rescan_sources` / `update_sources`: scan every source, finalize
-    /// characters, and report the combined `ScanResult`. Does NOT reset.
+    /// Shared body for `rescan_sources` / `update_sources`: optionally reset, scan every
+    /// source, finalize characters, and report the combined `ScanResult`, all in one
+    /// transaction. Every source is checked before anything is written.
     fn scan_sources<F>(
         &self,
         sources: &[(std::path::PathBuf, bool)],
+        reset: bool,
         index_lines: bool,
         progress: F,
     ) -> Result<ScanResult>
     where
         F: Fn(usize, usize, &str),
     {
-        let mut combined = ScanResult::default();
+        let mut roots = Vec::new();
         for (path, recursive) in sources {
-            let r = if *recursive {
-                self.scan_recursive_with_progress(path, false, index_lines, &progress)?
-            } else {
-                self.scan_folder_with_progress(path, false, index_lines, &progress)?
-            };
-            combined.files_scanned += r.files_scanned;
-            combined.skipped += r.skipped;
-            combined.lines_parsed += r.lines_parsed;
-            combined.events_found += r.events_found;
-            combined.errors += r.errors;
+            roots.extend(source_roots(path, *recursive)?);
         }
-        self.finalize_characters()?;
+
+        let mut combined = ScanResult::default();
+        let _ = self.db.clear_process_logs();
+        self.begin_scan()?;
+        self.db.begin_transaction()?;
+
+        let scan_result = (|| -> Result<()> {
+            if reset {
+                self.db.reset_log_data()?;
+            }
+            for root in &roots {
+                log::info!("Scanning log root: {}", root.display());
+                self.scan_folder_with_progress_inner(root, false, index_lines, &progress, &mut combined)?;
+            }
+            self.finalize_characters()
+        })();
+
+        self.end_scan(scan_result)?;
+
         combined.characters = self.db.list_characters()?.len();
         Ok(combined)
     }
 
-    /// The character active at byte `offset` of `bytes` — the last `Welcome …` before it.
-    /// Used to seed a tail (append) scan so events after the offset attribute correctly.
-    fn active_char_at_offset(&self, bytes: &[u8], offset: usize) -> Result<Option<(i64, String)>> {
-        if offset == 0 { return Ok(None); }
-        let prefix = decode_log_bytes(&bytes[..offset.min(bytes.len())]);
-        let mut name: Option<String> = None;
-        for line in prefix.lines() {
-            let message = match parse_timestamp(line) { Some((_dt, msg)) => msg, None => line };
-            if let Some(caps) = patterns::WELCOME_LOGIN.captures(message) {
-                name = Some(titlecase_name(&caps[1]));
-            } else if let Some(caps) = patterns::WELCOME_BACK.captures(message) {
-                name = Some(titlecase_name(&caps[1]));
-            }
-        }
+    /// Resolve the character a tail (append) scan resumes under — the last `Welcome …`
+    /// before the offset (`PreparedFile::resume_name`) — so events attribute correctly.
+    fn resume_character(&self, name: Option<String>) -> Result<Option<(i64, String)>> {
         match name {
             Some(n) => {
-                let id = self.db.get_or_create_character(&n)?;
+                let id = self.characters.get_or_create(&self.db, &n)?;
                 // Seed override config for the prefix-resolved active character. On a tail
                 // (append) scan whose appended bytes contain NO welcome line, the welcome
-                // block in `scan_bytes` never runs, so without this `should_count_rank`
+                // block in `scan_lines` never runs, so without this `should_count_rank`
                 // would default to true and wrongly count an override-skip trainer's ranks.
                 // Idempotent; mirrors what the welcome block does.
                 self.load_override_config(id)?;
@@ -1695,6 +2182,7 @@ impl LogParser {
     /// After scanning, determine professions and coin levels for all characters.
     /// If a character already has a profession set from a direct announcement (circle test
     /// or "become a" message), keep it. Otherwise, fall back to majority-vote from trainers.
+    /// Then takes the day's stats snapshots (`Database::record_daily_snapshots`).
     pub fn finalize_characters(&self) -> Result<()> {
         let chars = self.db.list_characters()?;
         for c in &chars {
@@ -1733,6 +2221,7 @@ impl LogParser {
             let interim = if coin_level == 0 { self.db.compute_interim_coin_level_from_kills(char_id)? } else { 0 };
             self.db.update_coin_level_interim(char_id, interim)?;
         }
+        self.db.record_daily_snapshots()?;
         Ok(())
     }
 }
@@ -1785,42 +2274,38 @@ fn extract_character_name(bytes: &[u8]) -> Option<String> {
     None
 }
 
-/// Compute a hex-encoded hash of file bytes for content-based dedup.
-fn hash_bytes(bytes: &[u8]) -> String {
+/// Compute a hex-encoded SHA-256 of file bytes for content-based dedup. Stored in
+/// `log_files.content_hash`, so it must be identical on every machine and Rust release.
+pub(crate) fn hash_bytes(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex_digest(Sha256::new_with_prefix(bytes))
+}
+
+/// The hex form [`hash_bytes`] stores, for a hash fed incrementally.
+pub(crate) fn hex_digest(hasher: sha2::Sha256) -> String {
+    use sha2::Digest;
+    hasher.finalize().iter().fold(String::with_capacity(64), |mut out, b| {
+        let _ = write!(out, "{:02x}", b);
+        out
+    })
+}
+
+/// The 16-hex-digit `DefaultHasher` hash older versions stored. Only the migration that
+/// re-hashes those rows uses it; its output is not stable across Rust releases.
+pub(crate) fn legacy_hash_bytes(bytes: &[u8]) -> String {
     let mut hasher = DefaultHasher::new();
     bytes.hash(&mut hasher);
     format!("{:016x}", hasher.finish())
 }
 
-/// Compare two dates, returning true if `log_date` is strictly after `cutoff`.
-/// Supports two formats:
-/// - ISO-ish from the DB date_str: "YYYY-MM-DD HH:MM:SS"
-/// - CL Log format: "M/D/YY" (e.g. "1/15/24")
+/// Compare two dates, returning true if `log_date` falls on a calendar day strictly after
+/// `cutoff`'s. Either side may be in any shape `crate::date::parse` accepts — in practice the
+/// stored `"YYYY-MM-DD HH:MM:SS"` for `log_date` and the user-entered CL `"M/D/YY"` cutoff.
 ///
 /// Falls back to string comparison if parsing fails.
 fn date_after(log_date: &str, cutoff: &str) -> bool {
-    fn parse_date(s: &str) -> Option<(i32, u32, u32)> {
-        // Try ISO format first: "YYYY-MM-DD ..."
-        if s.len() >= 10 && s.as_bytes().get(4) == Some(&b'-') {
-            let year: i32 = s[0..4].parse().ok()?;
-            let month: u32 = s[5..7].parse().ok()?;
-            let day: u32 = s[8..10].parse().ok()?;
-            return Some((year, month, day));
-        }
-        // Try CL Log format: "M/D/YY"
-        let parts: Vec<&str> = s.split('/').collect();
-        if parts.len() >= 3 {
-            let month: u32 = parts[0].parse().ok()?;
-            let day: u32 = parts[1].parse().ok()?;
-            let year_short: i32 = parts[2].parse().ok()?;
-            let year = if year_short >= 90 { 1900 + year_short } else { 2000 + year_short };
-            return Some((year, month, day));
-        }
-        None
-    }
-
-    match (parse_date(log_date), parse_date(cutoff)) {
-        (Some(log), Some(cut)) => log > cut,
+    match (crate::date::parse(log_date), crate::date::parse(cutoff)) {
+        (Some(log), Some(cut)) => log.date() > cut.date(),
         _ => log_date > cutoff,
     }
 }
@@ -1835,6 +2320,11 @@ fn hour_bucket(date_str: &str) -> String {
     }
 }
 
+/// Day bucket for the coin ledger: "YYYY-MM-DD" from a stored date string.
+fn day_bucket(date_str: &str) -> &str {
+    &date_str[..date_str.len().min(10)]
+}
+
 fn kill_verb_to_field(verb: &KillVerb, assisted: bool) -> &'static str {
     match (verb, assisted) {
         (KillVerb::Killed, false) => "killed_count",
@@ -1851,6 +2341,20 @@ fn kill_verb_to_field(verb: &KillVerb, assisted: bool) -> &'static str {
 /// Recursively discover log root folders under `root`.
 /// A "log root" is a directory that contains subdirectories with CL Log files.
 /// Skips hidden directories and `CL_Movies`.
+/// The log roots one scan source covers: the folders `discover_log_folders` finds under a
+/// recursive source, or else the source itself, which must be a directory (as
+/// `scan_recursive_with_progress` falls back to `scan_folder_with_progress`).
+fn source_roots(path: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let folders = if recursive { discover_log_folders(path) } else { Vec::new() };
+    if !folders.is_empty() {
+        return Ok(folders);
+    }
+    if !path.is_dir() {
+        return Err(AmanuensisError::Data(format!("Not a directory: {}", path.display())));
+    }
+    Ok(vec![path.to_path_buf()])
+}
+
 pub fn discover_log_folders(root: &Path) -> Vec<PathBuf> {
     let mut results = Vec::new();
     discover_log_folders_inner(root, &mut results);
@@ -1902,12 +2406,60 @@ fn discover_log_folders_inner(dir: &Path, results: &mut Vec<PathBuf>) {
 pub fn pending_files(
     db: &crate::db::Database,
     sources: &[(PathBuf, bool)],
+) -> Result<Vec<PathBuf>> {
+    pending_files_inner(db, sources, false)
+}
+
+/// `pending_files` counted per character id, for the character list's badge: a grown file
+/// counts for the character it was scanned under, a new one for the character it would be
+/// scanned as (its welcome line, else its folder). New files of characters with no row yet
+/// are left out; the Update creates them.
+pub fn pending_files_by_character(
+    db: &crate::db::Database,
+    sources: &[(PathBuf, bool)],
+) -> Result<HashMap<i64, i64>> {
+    let mut counts: HashMap<i64, i64> = HashMap::new();
+    for file in pending_files(db, sources)? {
+        let char_id = match db.log_file_character(&file.to_string_lossy())? {
+            Some(id) => Some(id),
+            None => {
+                let bytes = std::fs::read(&file).unwrap_or_default();
+                let name = extract_character_name(&bytes).or_else(|| {
+                    file.parent().and_then(|p| p.file_name()).map(|n| titlecase_name(&n.to_string_lossy()))
+                });
+                match name {
+                    Some(name) => db.get_character(&name)?.and_then(|c| c.id),
+                    None => None,
+                }
+            }
+        };
+        if let Some(id) = char_id {
+            *counts.entry(id).or_default() += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// `pending_files` for a scanner with `set_complete_lines_only(true)` (`watch`): a file whose
+/// only growth is a line still being written (no terminator yet) is waiting, not pending,
+/// since that scanner would leave it for a later pass.
+pub fn pending_complete_files(
+    db: &crate::db::Database,
+    sources: &[(PathBuf, bool)],
+) -> Result<Vec<PathBuf>> {
+    pending_files_inner(db, sources, true)
+}
+
+fn pending_files_inner(
+    db: &crate::db::Database,
+    sources: &[(PathBuf, bool)],
+    complete_lines_only: bool,
 ) -> Result<Vec<PathBuf>> {
     let mut pending = Vec::new();
     for (root, recursive) in sources {
         for (file, loose) in source_log_files(root, *recursive) {
             let path_str = file.to_string_lossy();
-            if would_scan(db, &file, &path_str, loose)? {
+            if would_scan(db, &file, &path_str, loose, complete_lines_only)? {
                 pending.push(file);
             }
         }
@@ -1915,6 +2467,84 @@ pub fn pending_files(
     Ok(pending)
 }
 
+/// Result of `compare_archives`: how the `CL Log` files of two log archives (e.g. copies of the
+/// Text Logs folder from two computers) relate by content. Paths are the files' own paths on
+/// disk; `shared` counts distinct contents present on both sides.
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ArchiveComparison {
+    pub files_a: usize,
+    pub files_b: usize,
+    pub shared: usize,
+    /// Files whose content exists only in archive A.
+    pub only_in_a: Vec<PathBuf>,
+    /// Files whose content exists only in archive B.
+    pub only_in_b: Vec<PathBuf>,
+    /// Same relative path on both sides but different content — typically one side holds a
+    /// longer (grown) copy of the same day's log. `(path_in_a, path_in_b)`.
+    pub diverged: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Hash every `CL Log` file under two archive roots and report which contents are unique to
+/// each side, so users can consolidate archives before scanning. Files are enumerated exactly
+/// as a recursive scan would (`source_log_files(root, true)`) and hashed with the scanner's
+/// own content hash, so "shared" means the scanner would treat the pair as a `SkipDuplicate`.
+/// Diverged files (same relative path, different content) are reported separately and are
+/// not repeated in `only_in_a` / `only_in_b`.
+pub fn compare_archives(a: &Path, b: &Path) -> Result<ArchiveComparison> {
+    fn hash_tree(root: &Path) -> Result<Vec<(PathBuf, String)>> {
+        let mut out = Vec::new();
+        for (file, _) in source_log_files(root, true) {
+            let bytes = std::fs::read(&file)
+                .map_err(|e| crate::error::AmanuensisError::file_unreadable(&file, e))?;
+            out.push((file, hash_bytes(&bytes)));
+        }
+        out.sort();
+        Ok(out)
+    }
+
+    let side_a = hash_tree(a)?;
+    let side_b = hash_tree(b)?;
+    let hashes_a: HashSet<&str> = side_a.iter().map(|(_, h)| h.as_str()).collect();
+    let hashes_b: HashSet<&str> = side_b.iter().map(|(_, h)| h.as_str()).collect();
+
+    // Relative path -> (path, hash) for the B side, to pair up diverged copies.
+    let rel_b: HashMap<PathBuf, &(PathBuf, String)> = side_b
+        .iter()
+        .filter_map(|entry| Some((entry.0.strip_prefix(b).ok()?.to_path_buf(), entry)))
+        .collect();
+
+    let mut result = ArchiveComparison {
+        files_a: side_a.len(),
+        files_b: side_b.len(),
+        shared: hashes_a.intersection(&hashes_b).count(),
+        ..Default::default()
+    };
+    let mut paired_b: HashSet<&Path> = HashSet::new();
+    for (path, hash) in &side_a {
+        if hashes_b.contains(hash.as_str()) {
+            continue;
+        }
+        let twin = path
+            .strip_prefix(a)
+            .ok()
+            .and_then(|rel| rel_b.get(rel))
+            .filter(|(_, h)| !hashes_a.contains(h.as_str()));
+        match twin {
+            Some((path_b, _)) => {
+                paired_b.insert(path_b.as_path());
+                result.diverged.push((path.clone(), path_b.clone()));
+            }
+            None => result.only_in_a.push(path.clone()),
+        }
+    }
+    result.only_in_b = side_b
+        .iter()
+        .filter(|(p, h)| !hashes_a.contains(h.as_str()) && !paired_b.contains(p.as_path()))
+        .map(|(p, _)| p.clone())
+        .collect();
+    Ok(result)
+}
+
 /// Whether an incremental (force=false) scan would actually scan `log_path` — the read-only
 /// twin of `plan_file_scan`. MUST stay in lockstep with `plan_file_scan`'s skip decisions:
 ///   - unchanged size                                  -> false (Skip)
@@ -1923,10 +2553,18 @@ pub fn pending_files(
 ///   - new path, content already scanned elsewhere     -> false (SkipDuplicate)
 ///   - loose new path, no determinable character       -> false (undetermined)
 ///   - new unique path / true append (prefix matches)  -> true  (Scan)
+///
 /// Reads the candidate file's bytes only for the cases the scanner itself must read.
 /// `loose` = the file sits directly in the log root (not in a character subfolder); such files
 /// are skipped by the scanner when no character can be determined from their content.
-fn would_scan(db: &crate::db::Database, log_path: &Path, path_str: &str, loose: bool) -> Result<bool> {
+/// `complete_lines_only` cuts the bytes at the last line terminator as the scanner does.
+fn would_scan(
+    db: &crate::db::Database,
+    log_path: &Path,
+    path_str: &str,
+    loose: bool,
+    complete_lines_only: bool,
+) -> Result<bool> {
     let prior = db.get_log_scan_state(path_str)?;
 
     if let Some((prev_len, _)) = &prior {
@@ -1942,10 +2580,21 @@ fn would_scan(db: &crate::db::Database, log_path: &Path, path_str: &str, loose:
         }
     }
 
-    let bytes = match std::fs::read(log_path) {
+    let mut bytes = match std::fs::read(log_path) {
         Ok(b) => b,
         Err(_) => return Ok(false), // unreadable -> the scanner reports an error, not a scan
     };
+    if complete_lines_only {
+        let prev_len = prior.as_ref().map_or(0, |(len, _)| *len as usize);
+        let complete = bytes
+            .iter()
+            .rposition(|&b| b == b'\n' || b == b'\r')
+            .map_or(0, |i| i + 1);
+        if complete == 0 && prior.is_none() {
+            return Ok(false);
+        }
+        bytes.truncate(complete.max(prev_len).min(bytes.len()));
+    }
 
     match prior {
         None => {
@@ -2023,9 +2672,9 @@ fn char_log_files(log_root: &Path) -> Vec<PathBuf> {
     files
 }
 
-/// Find CL Log files in a directory.
-/// Matches files starting with "CL Log " regardless of extension — newer clients produce
-/// ".txt" files, but older Mac clients (pre-2007 era) produce extensionless files.
+/// Find CL Log files (Mac or Windows naming, see `is_log_filename`) in a directory, oldest
+/// first by filename date so a mixed Mac/Windows folder is scanned in play order. Files
+/// without a parseable date sort first, by name.
 fn find_log_files(dir: &Path) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
     for entry in std::fs::read_dir(dir)? {
@@ -2035,10 +2684,14 @@ fn find_log_files(dir: &Path) -> Result<Vec<PathBuf>> {
             continue;
         }
         let name = entry.file_name().to_string_lossy().to_string();
-        if name.starts_with("CL Log ") {
+        if is_log_filename(&name) {
             files.push(entry.path());
         }
     }
+    files.sort_by_cached_key(|path| {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        (parse_filename_date(&name), path.clone())
+    });
     Ok(files)
 }
 
@@ -2050,6 +2703,8 @@ pub struct ScanResult {
     pub lines_parsed: usize,
     pub events_found: usize,
     pub errors: usize,
+    /// One entry per file counted in `errors`.
+    pub error_details: Vec<ScanError>,
 }
 
 #[derive(Debug, Default)]
@@ -2064,30 +2719,13 @@ struct FileResult {
     pub first_char_id: Option<i64>,
 }
 
-/// Outcome of deciding how to (re)scan a single log file. See `plan_file_scan`.
-enum ScanPlan {
-    /// File unchanged (or a legacy row with unknown length) — skip silently.
-    Skip,
-    /// New path whose content was already scanned at another path — skip as a duplicate.
-    SkipDuplicate,
-    /// File shrank or its already-scanned prefix changed (rotated/replaced) — skip and warn.
-    SkipChanged,
-    /// Reading the file failed.
-    ReadError(std::io::Error),
-    /// Scan `bytes[offset..]`; record `full_hash` + `bytes.len()` afterward.
-    /// `count_login` is false for tail (append) scans so the login isn't re-counted.
-    Scan {
-        bytes: Vec<u8>,
-        offset: usize,
-        full_hash: String,
-        count_login: bool,
-    },
-}
-
 #[cfg(test)]
+// The pending-file tests predate these lints and are kept as written.
+#[allow(clippy::useless_vec, clippy::manual_contains)]
 mod tests {
     use super::*;
     use std::fs;
+    use crate::db::queries::ChatFilter;
 
     fn create_test_log_dir() -> (tempfile::TempDir, PathBuf) {
         let tmp = tempfile::tempdir().unwrap();
@@ -2096,6 +2734,15 @@ mod tests {
         (tmp, char_dir)
     }
 
+    #[test]
+    fn content_hash_is_stable_sha256() {
+        // Fixed vector: stored hashes must match across machines and Rust releases.
+        assert_eq!(
+            hash_bytes(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
     #[test]
     fn scan_attributes_events_to_active_character_within_one_file() {
         // One file containing two characters' sessions, each introduced by its own welcome.
@@ -2134,6 +2781,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn trainer_maxed_messages_set_flag_for_log_owner_only() {
+        let (tmp, char_dir) = create_test_log_dir();
+        let body = "\
+1/1/24 1:00:00p Welcome to Clan Lord, Fen!
+1/1/24 1:01:00p Histia says, \"Hail, Fen. There is nothing I can teach you.\"
+1/1/24 1:02:00p Atkus says, \"Hail, Bork. There is nothing I can teach you.\"
+1/1/24 1:03:00p Regia says, \"Hail, Fen.\"
+1/1/24 1:03:01p Regia bows.
+1/1/24 1:03:02p Regia says, \"There is nothing I can teach you.\"
+1/1/24 1:04:00p Darkus says, \"There is nothing I can teach you.\"
+1/1/24 1:05:00p ¥Bangus Anmash has nothing more to teach you.
+";
+        fs::write(char_dir.join("CL Log 2024-01-01 13.00.00.txt"), body).unwrap();
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let fen = parser.db().get_character("Fen").unwrap().unwrap().id.unwrap();
+        let mut maxed: Vec<String> = parser
+            .db()
+            .get_trainers(fen)
+            .unwrap()
+            .into_iter()
+            .filter(|t| t.maxed)
+            .map(|t| t.trainer_name)
+            .collect();
+        maxed.sort();
+        // Atkus was talking to someone else; Darkus spoke without a greeting and bow
+        assert_eq!(maxed, ["Bangus Anmash", "Histia", "Regia"]);
+
+        parser.db().reset_log_data().unwrap();
+        assert!(parser.db().get_trainers(fen).unwrap().iter().all(|t| !t.maxed));
+    }
+
+    #[test]
+    fn mixed_mac_and_windows_logs_scan_in_date_order() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-02 13.00.00.txt"),
+            "1/2/24 1:00:00p Welcome to Clan Lord, Fen!\n1/2/24 1:01:00p You slaughtered a Rat.\n",
+        )
+        .unwrap();
+        fs::write(
+            char_dir.join("CL_Log_2024-01-01_09-00-00.txt"),
+            "1/1/2024 9:00:00 AM Welcome to Clan Lord, Fen!\n1/1/2024 9:01:00 AM You slaughtered a Rat.\n",
+        )
+        .unwrap();
+        fs::write(char_dir.join("notes.txt"), "not a log\n").unwrap();
+
+        let names: Vec<String> = find_log_files(&char_dir)
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["CL_Log_2024-01-01_09-00-00.txt", "CL Log 2024-01-02 13.00.00.txt"]);
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let c = parser.db().get_character("Fen").unwrap().unwrap();
+        assert_eq!(c.logins, 2);
+        let rat = parser.db().get_kills(c.id.unwrap()).unwrap().into_iter().find(|k| k.creature_name == "Rat").unwrap();
+        assert_eq!(rat.slaughtered_count, 2);
+        assert_eq!(rat.date_first.unwrap(), "2024-01-01 09:01:00");
+        assert_eq!(rat.date_last.unwrap(), "2024-01-02 13:01:00");
+    }
+
     #[test]
     fn loose_file_in_log_root_is_scanned_and_attributed_by_content() {
         // A CL Log file directly in the log root (not in a character subfolder) must be scanned
@@ -2156,7 +2871,7 @@ mod tests {
     #[test]
     fn tail_scan_seeds_override_config_from_prefix_welcome() {
         // Regression: a tail (append) scan that seeds the active character from the prefix's
-        // last welcome (via active_char_at_offset) must also load that character's override
+        // last welcome (via resume_character) must also load that character's override
         // config. Otherwise an override-skip trainer's rank messages appended in the tail
         // (which contains NO welcome line) are wrongly counted instead of skipped.
         let (tmp, _char_dir) = create_test_log_dir(); // tmp is the log root
@@ -2185,7 +2900,7 @@ mod tests {
         fs::write(&loose, grown).unwrap();
 
         // Incremental (force=false) re-scan: only the appended tail is parsed, and the active
-        // character is seeded from the prefix welcome via active_char_at_offset.
+        // character is seeded from the prefix welcome via resume_character.
         parser.scan_folder(tmp.path(), false).unwrap();
 
         let trainers = parser.db().get_trainers(alpha).unwrap();
@@ -2257,6 +2972,42 @@ mod tests {
         assert_eq!(ch.logins, 1, "no-welcome file => 1 fallback login for the folder character");
     }
 
+    #[test]
+    fn complete_lines_only_defers_partial_trailing_line() {
+        // Live watch mode reads files mid-write: a half-written last line must not be parsed
+        // (or recorded as consumed) until its terminator arrives.
+        let (tmp, char_dir) = create_test_log_dir();
+        let log_path = char_dir.join("CL Log 2024-01-01 13.00.00.txt");
+        let head = "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:01:00p You slaughtered a Rat.
+";
+        fs::write(&log_path, format!("{head}1/1/24 1:02:00p You slaugh")).unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.set_complete_lines_only(true);
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let slaughtered = |p: &LogParser| {
+            p.db().get_kills(char_id).unwrap().iter().map(|k| k.slaughtered_count).sum::<i64>()
+        };
+        assert_eq!(slaughtered(&parser), 1);
+        // Waiting on the unterminated line is not pending work for the live scanner.
+        let sources = vec![(tmp.path().to_path_buf(), false)];
+        assert!(pending_complete_files(parser.db(), &sources).unwrap().is_empty());
+        assert_eq!(pending_files(parser.db(), &sources).unwrap().len(), 1);
+        assert_eq!(pending_files_by_character(parser.db(), &sources).unwrap().get(&char_id), Some(&1));
+
+        // The line completes; the next pass resumes exactly at its start.
+        fs::write(&log_path, format!("{head}1/1/24 1:02:00p You slaughtered a Rat.\n")).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        assert_eq!(slaughtered(&parser), 2);
+        let char = parser.db().get_character("Testchar").unwrap().unwrap();
+        assert_eq!(char.logins, 1);
+    }
+
     #[test]
     fn incremental_scan_picks_up_appended_kills() {
         // Clan Lord writes one daily log file that GROWS as the player keeps playing.
@@ -3094,14 +3845,402 @@ mod tests {
         )
         .unwrap();
 
-        let db = Database::open_in_memory().unwrap();
-        let parser = LogParser::new(db).unwrap();
-        let result = parser.scan_folder(tmp.path(), false).unwrap();
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        let result = parser.scan_folder(tmp.path(), false).unwrap();
+
+        assert_eq!(result.characters, 1);
+        // Character should be named from the welcome message (title-cased), not the folder
+        assert!(parser.db().get_character("Actualname").unwrap().is_some());
+        assert!(parser.db().get_character("SomeFolder").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_notifier_queues_boss_kills_and_deaths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Fen");
+        fs::create_dir(&char_dir).unwrap();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "1/1/24 1:00:00p Welcome to Clan Lord, Fen!\n1/1/24 1:01:00p You slaughtered a Rat.\n\
+             1/1/24 1:02:00p You slaughtered the Kolar Bear.\n1/1/24 1:03:00p Fen has fallen to a Rat.\n",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.set_notifier(Some(NotifyConfig {
+            webhook_url: "https://example.invalid/hook".into(),
+            username: None,
+            max_per_pass: 5,
+            triggers: ["boss_kill", "death"]
+                .map(|e| crate::notify::NotifyTrigger { event: e.into(), template: None, every: None })
+                .to_vec(),
+        }));
+        parser.scan_folder(tmp.path(), false).unwrap();
+        assert_eq!(
+            parser.take_notifications(),
+            ["**Fen** killed Kolar Bear!", "**Fen** has fallen to Rat."]
+        );
+        assert!(parser.take_notifications().is_empty());
+    }
+
+    #[test]
+    fn test_kill_events_are_tagged_with_the_current_area() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:01:00p You slaughtered a Rat.
+1/1/24 1:05:00p You slaughtered a Large Vermine.
+1/1/24 1:10:00p You slaughtered a Rat.
+1/1/24 1:25:00p You slaughtered an Orga Anger.
+1/1/24 1:45:00p TestChar has fallen to an Orga Anger.
+",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.set_notifier(Some(NotifyConfig {
+            webhook_url: "https://example.invalid/hook".into(),
+            username: None,
+            max_per_pass: 10,
+            triggers: ["kill", "death"]
+                .map(|e| crate::notify::NotifyTrigger { event: e.into(), template: Some("{subject} @ {area}".into()), every: None })
+                .to_vec(),
+        }));
+        parser.scan_folder(tmp.path(), false).unwrap();
+        // A Rat lives everywhere: before any located creature there is no area, afterwards
+        // it takes the area the character is already in.
+        assert_eq!(
+            parser.take_notifications(),
+            [
+                "Rat @ ",
+                "Large Vermine @ Puddleby area",
+                "Rat @ Puddleby area",
+                "Orga Anger @ Orga territory",
+                "Orga Anger @ Orga territory",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kills_by_location_follow_the_current_area() {
+        let (tmp, char_dir) = create_test_log_dir();
+        let log = char_dir.join("CL Log 2024-01-01 13.00.00.txt");
+        fs::write(
+            &log,
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:01:00p You slaughtered a Rat.
+1/1/24 1:05:00p You slaughtered a Large Vermine.
+1/1/24 1:10:00p You slaughtered a Rat.
+1/1/24 1:25:00p You slaughtered an Orga Anger.
+1/1/24 1:26:00p You helped kill an Orga Anger.
+1/1/24 1:30:00p You slaughtered a Rat.
+1/1/24 1:45:00p TestChar has fallen to an Orga Anger.
+",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let rows: Vec<(String, String, i64, i64, i64)> = parser
+            .db()
+            .get_kills_by_location_merged(char_id, None)
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.creature_name, r.area, r.solo, r.assisted, r.killed_by))
+            .collect();
+        // The first Rat comes before any located creature, so it has no area.
+        assert_eq!(
+            rows,
+            vec![
+                ("Orga Anger".into(), "Orga territory".into(), 1, 1, 1),
+                ("Large Vermine".into(), "Puddleby area".into(), 1, 0, 0),
+                ("Rat".into(), "Orga territory".into(), 1, 0, 0),
+                ("Rat".into(), "Puddleby area".into(), 1, 0, 0),
+            ]
+        );
+        let orga = parser.db().get_kills_by_location_merged(char_id, Some("orga anger")).unwrap();
+        assert_eq!(orga.len(), 1);
+
+        parser.unscan_file(&log.to_string_lossy(), |_, _, _| {}).unwrap();
+        assert!(parser.db().get_kills_by_location_merged(char_id, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_deaths_record_area_and_whether_a_depart_or_rescue_followed() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:25:00p You slaughtered an Orga Anger.
+1/1/24 1:45:00p TestChar has fallen to an Orga Anger.
+1/1/24 1:46:00p Your spirit has departed your body 3 times.
+1/1/24 2:10:00p TestChar has fallen to an Orga Anger.
+1/1/24 2:11:00p Bramble starts dragging you.
+1/1/24 2:12:00p TestChar is no longer fallen.
+1/1/24 9:30:00p TestChar has fallen to a Large Vermine.
+",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let deaths: Vec<(String, String, String)> = parser
+            .db()
+            .get_deaths_merged(char_id)
+            .unwrap()
+            .into_iter()
+            .map(|d| (d.cause, d.area, d.outcome))
+            .collect();
+        assert_eq!(
+            deaths,
+            vec![
+                ("Orga Anger".into(), "Orga territory".into(), "departed".into()),
+                ("Orga Anger".into(), "Orga territory".into(), "rescued".into()),
+                ("Large Vermine".into(), "Puddleby area".into(), "unresolved".into()),
+            ]
+        );
+        let report = parser.db().get_death_report_merged(char_id).unwrap();
+        assert_eq!((report.total.deaths, report.total.departed, report.total.rescued), (3, 1, 1));
+        assert_eq!(report.by_time_of_day[2].deaths, 2);
+        assert_eq!(report.by_time_of_day[3].deaths, 1);
+        // Downtimes of 1 and 2 minutes; the Large Vermine death is never resolved.
+        assert_eq!(report.outcomes, parser.db().fall_outcomes(char_id).unwrap());
+        assert_eq!(report.outcomes.avg_downtime_secs, Some(90.0));
+        assert_eq!(report.outcomes.rescue_rate, Some(50.0));
+        assert_eq!(report.outcomes.chains_per_fall, 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_rescues_are_counted_per_player_and_direction() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:45:00p TestChar has fallen to an Orga Anger.
+1/1/24 1:46:00p Bramble starts dragging you.
+1/1/24 1:48:00p Corvid raises you.
+1/1/24 1:48:00p TestChar is no longer fallen.
+1/1/24 2:10:00p You start dragging Bramble.
+1/1/24 2:12:00p You raise Bramble.
+",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let rescues: Vec<(String, i64, i64, i64, i64)> = parser
+            .db()
+            .get_rescues_merged(char_id)
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.name, r.dragged_you, r.raised_you, r.you_dragged, r.you_raised))
+            .collect();
+        assert_eq!(rescues, vec![("Bramble".into(), 1, 0, 1, 1), ("Corvid".into(), 0, 1, 0, 0)]);
+        assert_eq!(parser.db().get_character("Testchar").unwrap().unwrap().chains_used, 1);
+    }
+
+    #[test]
+    fn test_unknown_lines_are_opt_in() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:10:00p ¥You hear 3 distant chimes.
+1/1/24 1:20:00p ¥You hear 12 distant chimes.
+1/1/24 1:30:00p * The ground trembles.
+1/1/24 1:40:00p Fen says, \"hello\"
+1/1/24 1:50:00p * You pick up 5 coins.
+",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        assert!(parser.db().get_unknown_lines(None, 50).unwrap().is_empty());
+
+        parser.set_track_unknown_lines(true);
+        parser.scan_folder(tmp.path(), true).unwrap();
+        let unknowns: Vec<(String, i64)> =
+            parser.db().get_unknown_lines(None, 50).unwrap().into_iter().map(|u| (u.message, u.count)).collect();
+        assert_eq!(
+            unknowns,
+            vec![("¥You hear # distant chimes.".into(), 2), ("* The ground trembles.".into(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_chat_is_opt_in_and_unscanned_with_its_file() {
+        let (tmp, char_dir) = create_test_log_dir();
+        let log = char_dir.join("CL Log 2024-01-01 13.00.00.txt");
+        fs::write(
+            &log,
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:01:00p You slaughtered a Rat.
+1/1/24 1:40:00p Fen says, \"hunt at the docks?\"
+1/1/24 1:41:00p You think to Fen, \"on my way\"
+",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        assert!(parser.db().get_chat(&ChatFilter::default()).unwrap().is_empty());
+
+        parser.set_track_chat(true);
+        parser.scan_folder(tmp.path(), true).unwrap();
+        let chat: Vec<(String, String, String)> = parser
+            .db()
+            .get_chat(&ChatFilter::default())
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.speaker, c.verb, c.text))
+            .collect();
+        assert_eq!(
+            chat,
+            vec![
+                ("Fen".into(), "says".into(), "hunt at the docks?".into()),
+                ("Testchar".into(), "thinks to Fen".into(), "on my way".into()),
+            ]
+        );
+
+        assert_eq!(parser.unscan_file(&log.to_string_lossy(), |_, _, _| {}).unwrap().chat_lines, 2);
+        assert!(parser.db().get_chat(&ChatFilter::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_line_progress_reports_through_large_files() {
+        let (tmp, char_dir) = create_test_log_dir();
+        let mut log = String::from("1/1/24 1:00:00p Welcome to Clan Lord, TestChar!\n");
+        for i in 0..LINE_PROGRESS_INTERVAL * 2 {
+            log.push_str(&format!("1/1/24 1:00:01p Fen says, \"{i}\"\n"));
+        }
+        fs::write(char_dir.join("CL Log 2024-01-01 13.00.00.txt"), log).unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = calls.clone();
+        parser.set_line_progress(Some(Box::new(move |done, total| sink.lock().unwrap().push((done, total)))));
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let total = LINE_PROGRESS_INTERVAL * 2 + 1;
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![(LINE_PROGRESS_INTERVAL, total), (LINE_PROGRESS_INTERVAL * 2, total), (total, total)]
+        );
+    }
+
+    #[test]
+    fn test_scan_errors_are_kept_until_the_file_scans() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:05:00p You slaughtered a Rat.
+",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser
+            .db()
+            .conn()
+            .execute_batch("CREATE TRIGGER no_kills BEFORE INSERT ON kills BEGIN SELECT RAISE(ABORT, 'no kills'); END;")
+            .unwrap();
+        let result = parser.scan_folder(tmp.path(), false).unwrap();
+        assert_eq!((result.errors, result.error_details.len()), (1, 1));
+        let failure = &result.error_details[0];
+        assert!(failure.file_path.ends_with("CL Log 2024-01-01 13.00.00.txt"));
+        assert_eq!((failure.line, failure.kind.as_str()), (Some(2), "database"));
+        assert!(failure.message.contains("no kills"));
+        assert_eq!(parser.db().get_scan_errors().unwrap(), result.error_details);
+
+        // The failed file wasn't marked scanned, so the next scan retries it and clears the error.
+        parser.db().conn().execute_batch("DROP TRIGGER no_kills;").unwrap();
+        let result = parser.scan_folder(tmp.path(), false).unwrap();
+        assert_eq!((result.files_scanned, result.errors), (1, 0));
+        assert!(parser.db().get_scan_errors().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_witnessed_falls_are_opt_in() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:45:00p Bramble has fallen to an Orga Anger.
+1/1/24 1:50:00p Corvid has fallen to an Orga Anger.
+1/1/24 1:55:00p TestChar has fallen to an Orga Anger.
+",
+        )
+        .unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        assert!(parser.db().get_witnessed_falls_merged(char_id, false).unwrap().is_empty());
+
+        parser.set_track_witnessed_falls(true);
+        parser.scan_folder(tmp.path(), true).unwrap();
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let by_cause = parser.db().get_witnessed_falls_merged(char_id, true).unwrap();
+        assert_eq!(by_cause.len(), 1);
+        assert_eq!((by_cause[0].key.as_str(), by_cause[0].falls, by_cause[0].distinct), ("Orga Anger", 2, 2));
+    }
 
-        assert_eq!(result.characters, 1);
-        // Character should be named from the welcome message (title-cased), not the folder
-        assert!(parser.db().get_character("Actualname").unwrap().is_some());
-        assert!(parser.db().get_character("SomeFolder").unwrap().is_none());
+    #[test]
+    fn test_event_stream_writes_ndjson_per_event() {
+        struct Shared(Arc<std::sync::Mutex<Vec<u8>>>);
+        impl std::io::Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Fen");
+        fs::create_dir(&char_dir).unwrap();
+        let log = char_dir.join("CL Log 2024-01-01 13.00.00.txt");
+        fs::write(&log, "1/1/24 1:00:00p Welcome to Clan Lord, Fen!\n1/1/24 1:01:00p Fen says, \"hi\"\n1/1/24 1:02:00p You slaughtered a Rat.\n").unwrap();
+
+        let out = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.set_event_stream(Some(Box::new(Shared(Arc::clone(&out)))));
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let text = String::from_utf8(out.lock().unwrap().clone()).unwrap();
+        let records: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        let types: Vec<&str> = records.iter().map(|r| r["type"].as_str().unwrap()).collect();
+        assert_eq!(types, vec!["login", "solo_kill"], "speech is not an event");
+        assert_eq!(records[1]["character"], "Fen");
+        assert_eq!(records[1]["date"], "2024-01-01 13:02:00");
+        assert_eq!(records[1]["data"], serde_json::json!({ "creature": "Rat", "verb": "slaughtered" }));
+        assert!(records[1]["file"].as_str().unwrap().ends_with("Fen/CL Log 2024-01-01 13.00.00.txt"));
+
+        // An update streams only the new lines.
+        out.lock().unwrap().clear();
+        let mut file = fs::OpenOptions::new().append(true).open(&log).unwrap();
+        std::io::Write::write_all(&mut file, b"1/1/24 1:03:00p You killed a Rat.\n").unwrap();
+        parser.update_sources(&[(tmp.path().to_path_buf(), false)], false, |_, _, _| {}).unwrap();
+        let text = String::from_utf8(out.lock().unwrap().clone()).unwrap();
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.contains("\"verb\":\"killed\""));
     }
 
     #[test]
@@ -3201,6 +4340,117 @@ mod tests {
         assert!(parser.db().get_character("Pip").unwrap().is_some());
     }
 
+    #[test]
+    fn cancelled_scan_rolls_back_and_can_be_rerun() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Fen");
+        fs::create_dir_all(&char_dir).unwrap();
+        for day in 1..=3 {
+            fs::write(
+                char_dir.join(format!("CL Log 2024-01-0{day} 13.00.00.txt")),
+                format!("1/{day}/24 1:00:00p Welcome to Clan Lord, Fen!\n1/{day}/24 1:01:00p You slaughtered a Rat.\n"),
+            )
+            .unwrap();
+        }
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        let cancel = Arc::new(AtomicBool::new(false));
+        parser.set_cancel_flag(Some(cancel.clone()));
+        // Cancel while the first file is being scanned; the scan stops before the second.
+        let err = parser
+            .scan_folder_with_progress(tmp.path(), false, false, |current, _, _| {
+                if current == 1 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            })
+            .unwrap_err();
+        assert_eq!(err.kind(), "cancelled");
+        assert!(parser.db().get_character("Fen").unwrap().is_none());
+        assert_eq!(parser.db().scanned_log_count().unwrap(), 0);
+
+        parser.set_cancel_flag(None);
+        let result = parser.scan_folder_with_progress(tmp.path(), false, false, |_, _, _| {}).unwrap();
+        assert_eq!(result.files_scanned, 3);
+        assert_eq!(parser.db().get_character("Fen").unwrap().unwrap().logins, 3);
+    }
+
+    #[test]
+    fn cancelled_rescan_keeps_previous_data() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (folder_a, folder_b) = (tmp.path().join("a"), tmp.path().join("b"));
+        for (folder, name, days) in [(&folder_a, "Fen", 1..=1), (&folder_b, "Pip", 1..=2)] {
+            fs::create_dir_all(folder.join(name)).unwrap();
+            for day in days {
+                fs::write(
+                    folder.join(name).join(format!("CL Log 2024-01-0{day} 13.00.00.txt")),
+                    format!("1/{day}/24 1:00:00p Welcome to Clan Lord, {name}!\n1/{day}/24 1:01:00p You slaughtered a Rat.\n"),
+                )
+                .unwrap();
+            }
+        }
+        let sources = [(folder_a.clone(), false), (folder_b.clone(), false)];
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.rescan_sources(&sources, false, |_, _, _| {}).unwrap();
+        let snapshot = |parser: &LogParser| {
+            let db = parser.db();
+            let fen = db.get_character("Fen").unwrap().unwrap();
+            let pip = db.get_character("Pip").unwrap().unwrap();
+            let kills = db.get_kills(pip.id.unwrap()).unwrap().iter().map(|k| k.total_all()).sum::<i64>();
+            (fen.logins, pip.logins, kills, db.scanned_log_count().unwrap())
+        };
+        let before = snapshot(&parser);
+        assert_eq!(before, (1, 2, 2, 3));
+
+        // Cancel in the second source, after the reset and the first source were written.
+        let cancel = Arc::new(AtomicBool::new(false));
+        parser.set_cancel_flag(Some(cancel.clone()));
+        let calls = std::cell::Cell::new(0);
+        let err = parser
+            .rescan_sources(&sources, false, |_, _, _| {
+                calls.set(calls.get() + 1);
+                if calls.get() == 2 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            })
+            .unwrap_err();
+        assert_eq!(err.kind(), "cancelled");
+        assert_eq!(calls.get(), 2);
+        assert_eq!(snapshot(&parser), before);
+
+        // A missing source fails before the reset, too.
+        parser.set_cancel_flag(None);
+        let missing = [(folder_a.clone(), false), (tmp.path().join("gone"), false)];
+        assert!(parser.rescan_sources(&missing, false, |_, _, _| {}).is_err());
+        assert_eq!(snapshot(&parser), before);
+    }
+
+    #[test]
+    fn test_nice_scan_matches_normal_scan() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Fen");
+        fs::create_dir_all(&char_dir).unwrap();
+        for day in 1..=3 {
+            fs::write(
+                char_dir.join(format!("CL Log 2024-01-0{day} 13.00.00.txt")),
+                format!("1/{day}/24 1:00:00p Welcome to Clan Lord, Fen!\n1/{day}/24 1:01:00p You slaughtered a Rat.\n"),
+            )
+            .unwrap();
+        }
+
+        let scan = |nice: bool| {
+            let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+            parser.set_nice(nice);
+            let result = parser.scan_folder_with_progress(tmp.path(), false, true, |_, _, _| {}).unwrap();
+            let char_id = parser.db().get_character("Fen").unwrap().unwrap().id.unwrap();
+            let kills = parser.db().get_kills(char_id).unwrap()[0].total_solo();
+            (result.files_scanned, result.lines_parsed, kills, parser.db().log_line_count().unwrap())
+        };
+        let started = std::time::Instant::now();
+        assert_eq!(scan(true), scan(false));
+        assert_eq!(scan(true), (3, 6, 3, 6));
+        assert!(started.elapsed() >= NICE_FILE_PAUSE * 6, "nice scans pause before each file");
+    }
+
     #[test]
     fn test_extract_character_name_login() {
         let bytes = b"1/1/24 1:00:00p Welcome to Clan Lord, Fen!\n";
@@ -3372,7 +4622,7 @@ mod tests {
 
         let char = parser.db().get_character("Testchar").unwrap().unwrap();
         // Should be the earlier date
-        assert_eq!(char.start_date, Some("2024-01-15 15:00:00".to_string()));
+        assert_eq!(char.start_date, Some("2024-01-15 15:00:00".parse().unwrap()));
     }
 
     #[test]
@@ -3520,6 +4770,11 @@ mod tests {
 
         let char = parser.db().get_character("Testchar").unwrap().unwrap();
         assert_eq!(char.profession, crate::models::Profession::Fighter);
+        let circles = parser.db().get_circles_merged(char.id.unwrap()).unwrap();
+        assert_eq!(
+            circles,
+            [crate::db::queries::CircleTest { profession: "Fighter".into(), circle: 2, date: "2024-01-01 13:01:00".parse().unwrap() }]
+        );
     }
 
     #[test]
@@ -3664,7 +4919,7 @@ mod tests {
         // File without welcome message still counts as 1 login
         assert_eq!(char.logins, 1);
         // Start date should come from first timestamp in file
-        assert_eq!(char.start_date, Some("2024-01-01 13:00:00".to_string()));
+        assert_eq!(char.start_date, Some("2024-01-01 13:00:00".parse().unwrap()));
     }
 
     #[test]
@@ -3829,6 +5084,455 @@ mod tests {
         assert_eq!(assisted_kill, 1, "expected assisted_kill_count=1");
     }
 
+    #[test]
+    fn test_event_history_recorded_during_scan() {
+        let (tmp, char_dir) = create_test_log_dir();
+        let log_content = "\
+1/1/24 1:00:00p \u{00a5}Your combat ability improves.
+1/1/24 1:01:00p TestChar has fallen to a Large Vermine.
+1/2/24 1:02:00p \u{00a5}Your combat ability improves.
+1/2/24 1:03:00p You slaughtered a Rat.
+1/2/24 1:03:00p You slaughtered a Rat.
+1/2/24 1:04:00p * You pick up 12 coins.
+";
+        fs::write(char_dir.join("CL Log 2024-01-01 13.00.00.txt"), log_content).unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_or_create_character("TestChar").unwrap();
+        let ranks: Vec<(String, i64)> = parser
+            .db()
+            .ranks_per_day(char_id)
+            .unwrap()
+            .into_iter()
+            .map(|d| (d.day, d.count))
+            .collect();
+        assert_eq!(ranks, vec![("2024-01-01".to_string(), 1), ("2024-01-02".to_string(), 1)]);
+        let history = parser.db().event_history(char_id, "rank").unwrap();
+        assert_eq!(history[0].subject, "Bangus Anmash");
+        let deaths = parser.db().event_history(char_id, "death").unwrap();
+        assert_eq!(deaths.len(), 1);
+        assert_eq!(deaths[0].subject, "Large Vermine");
+
+        // Kills and coins are events too, one row per line even within the same second.
+        parser.scan_folder(tmp.path(), true).unwrap();
+        let kills = parser.db().history_events(char_id, "kill").unwrap();
+        assert_eq!(kills.len(), 2);
+        assert_eq!((kills[0].date.as_str(), kills[0].subject.as_str()), ("2024-01-02 13:03:00", "Rat"));
+        let coins = parser.db().history_events(char_id, "coins").unwrap();
+        assert_eq!(coins.len(), 1);
+        assert_eq!((coins[0].subject.as_str(), coins[0].value), ("picked_up", 12));
+    }
+
+    #[test]
+    fn test_sessions_split_on_disconnect_and_resume_on_tail_scan() {
+        let (tmp, char_dir) = create_test_log_dir();
+        let log_path = char_dir.join("CL Log 2024-01-01 13.00.00.txt");
+        let first = "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:10:00p You slaughtered a Rat.
+1/1/24 1:20:00p * You pick up 25 coins.
+1/1/24 1:30:00p *** We are no longer connected to the Clan Lord game server. ***
+1/1/24 2:00:00p Welcome back, TestChar!
+1/1/24 2:05:00p \u{00a5}Your combat ability improves.
+";
+        fs::write(&log_path, first).unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let sessions = parser.db().get_sessions(char_id).unwrap();
+        assert_eq!(sessions.len(), 2);
+        // Most recent first.
+        assert_eq!(sessions[1].started_at, "2024-01-01 13:00:00");
+        assert_eq!(sessions[1].duration_secs, 30 * 60);
+        assert_eq!((sessions[1].kills, sessions[1].coins, sessions[1].ranks), (1, 25, 0));
+        assert_eq!(sessions[0].started_at, "2024-01-01 14:00:00");
+        assert_eq!(sessions[0].ranks, 1);
+
+        // The file grows; the appended lines belong to the still-open second session.
+        fs::write(&log_path, format!("{first}1/1/24 2:40:00p TestChar has fallen to a Rat.\n")).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        let sessions = parser.db().get_sessions(char_id).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].deaths, 1);
+        assert_eq!(sessions[0].duration_secs, 40 * 60);
+    }
+
+    #[test]
+    fn test_lines_after_a_disconnect_open_no_session() {
+        let (tmp, char_dir) = create_test_log_dir();
+        let log = "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:10:00p You slaughtered a Rat.
+1/1/24 1:30:00p *** We are no longer connected to the Clan Lord game server. ***
+1/1/24 3:00:00p You slaughtered a Rat.
+";
+        fs::write(char_dir.join("CL Log 2024-01-01 13.00.00.txt"), log).unwrap();
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let sessions = parser.db().get_sessions(char_id).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!((sessions[0].duration_secs, sessions[0].kills), (30 * 60, 1));
+        // The stray line is still a kill, just not session time.
+        assert_eq!(parser.db().get_kills(char_id).unwrap()[0].slaughtered_count, 2);
+    }
+
+    #[test]
+    fn test_creature_name_rules_apply_at_scan() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:05:00p You slaughtered a Large Vermine.
+1/1/24 1:06:00p You killed a Vermine.
+1/1/24 1:07:00p You slaughtered a Rat.
+",
+        )
+        .unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        db.set_creature_name_rules(&[crate::creature_names::CreatureNameRule {
+            pattern: "Large (.+)".into(),
+            name: "$1".into(),
+        }])
+        .unwrap();
+        db.set_creature_name_rules_enabled(true).unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let stored: Vec<(String, i64)> =
+            parser.db().get_kills(char_id).unwrap().iter().map(|k| (k.creature_name.clone(), k.total_all())).collect();
+        assert_eq!(stored, vec![("Vermine".to_string(), 2), ("Rat".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_arena_bouts_recorded_with_arena() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:05:00p You enter the Puddleby Arena.
+1/1/24 1:06:00p You defeated Gandor in the arena!
+1/1/24 1:09:00p You were defeated by Squib in the arena.
+1/1/24 1:30:00p *** Squib has won the tournament! ***
+1/1/24 1:31:00p *** TestChar has won the tournament! ***
+",
+        )
+        .unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        // Rescanning the same lines records nothing new
+        parser.scan_folder(tmp.path(), true).unwrap();
+
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let summary = parser.db().get_arena_summary_merged(char_id).unwrap();
+        assert_eq!((summary.entries, summary.wins, summary.losses, summary.tournaments_won), (1, 1, 1, 1));
+        let bouts: Vec<(&str, &str, &str)> = summary
+            .history
+            .iter()
+            .filter(|e| e.kind == "win" || e.kind == "loss")
+            .map(|e| (e.kind.as_str(), e.arena.as_str(), e.opponent.as_str()))
+            .collect();
+        assert_eq!(bouts, vec![("win", "Puddleby Arena", "Gandor"), ("loss", "Puddleby Arena", "Squib")]);
+    }
+
+    #[test]
+    fn test_pet_lifecycle_recorded() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:05:00p The Maha Ruknee is now your pet.
+1/1/24 1:06:00p You name your Maha Ruknee \"Fluffy\".
+1/1/24 1:07:00p You feed your pet Fluffy.
+1/1/24 1:10:00p You train your pet Fluffy.
+1/1/24 1:11:00p Your pet Fluffy gains a rank!
+1/1/24 1:20:00p Your pet Fluffy has fallen to a Large Vermine.
+1/1/24 1:30:00p You feed your pet Fluffy.
+1/1/24 1:40:00p You release your pet Fluffy into the wild.
+",
+        )
+        .unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        parser.scan_folder(tmp.path(), true).unwrap();
+
+        let char = parser.db().get_character("Testchar").unwrap().unwrap();
+        assert_eq!(char.deaths, 0);
+        let pets = parser.db().get_pets_merged(char.id.unwrap()).unwrap();
+        assert_eq!(pets.len(), 1);
+        let p = &pets[0];
+        assert_eq!((p.pet_name.as_str(), p.creature_name.as_str(), p.status.as_str()), ("Fluffy", "Maha Ruknee", "released"));
+        assert_eq!((p.feed_count, p.death_count), (2, 1));
+        assert_eq!(p.adopted_date.unwrap(), "2024-01-01 13:05:00");
+        assert_eq!(p.released_date.unwrap(), "2024-01-01 13:40:00");
+        assert_eq!((p.ranks, p.trainings), (1, 1));
+        assert_eq!(p.last_trained_date.unwrap(), "2024-01-01 13:11:00");
+    }
+
+    #[test]
+    fn test_zone_visits_follow_located_creatures() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:05:00p You slaughtered a Large Vermine.
+1/1/24 1:15:00p You killed a Large Vermine.
+1/1/24 1:20:00p You slaughtered a Rat.
+1/1/24 1:25:00p You slaughtered an Orga Anger.
+1/1/24 1:45:00p TestChar has fallen to an Orga Anger.
+1/1/24 1:50:00p *** We are no longer connected to the Clan Lord game server. ***
+",
+        )
+        .unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let visits = parser.db().get_zone_visits_merged(char_id).unwrap();
+        let rows: Vec<(&str, String, String, i64)> = visits
+            .iter()
+            .map(|v| (v.area.as_str(), v.entered_at.to_string(), v.left_at.to_string(), v.events))
+            .collect();
+        // Rats live everywhere and don't move the character; travel time counts toward the
+        // area being left, and the last visit ends at its last sighting.
+        assert_eq!(
+            rows,
+            vec![
+                ("Puddleby area", "2024-01-01 13:05:00".to_string(), "2024-01-01 13:25:00".to_string(), 2),
+                ("Orga territory", "2024-01-01 13:25:00".to_string(), "2024-01-01 13:45:00".to_string(), 2),
+            ]
+        );
+        let zones = parser.db().get_zones_merged(char_id).unwrap();
+        assert_eq!(zones.len(), 2);
+        assert_eq!((zones[0].seconds, zones[0].sessions), (20 * 60, 1));
+    }
+
+    #[test]
+    fn test_assists_attributed_to_nearby_killer() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:05:00p Gandor slaughtered a Rat.
+1/1/24 1:05:01p You helped slaughter a Rat.
+1/1/24 1:10:00p You helped vanquish an Orga Anger.
+1/1/24 1:10:01p Mary Ann vanquished an Orga Anger.
+1/1/24 1:20:00p You helped kill a Rat.
+1/1/24 1:30:00p Gandor killed a Rat.
+1/1/24 1:31:00p TestChar killed a Rat.
+",
+        )
+        .unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let credits: Vec<(String, String, i64)> = parser
+            .db()
+            .get_assists_merged(char_id, None)
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.creature, a.player, a.count))
+            .collect();
+        // The 1:20 assist has no killer line within the window, so it stays unattributed.
+        assert_eq!(
+            credits,
+            vec![("Orga Anger".into(), "Mary Ann".into(), 1), ("Rat".into(), "Gandor".into(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_kill_milestones_recorded_during_scan() {
+        let (tmp, char_dir) = create_test_log_dir();
+        let mut body = String::from("1/1/24 1:00:00p Welcome to Clan Lord, TestChar!\n");
+        for i in 0..100 {
+            body.push_str(&format!("1/1/24 2:{:02}:{:02}p You slaughtered a Rat.\n", i / 60, i % 60));
+        }
+        body.push_str("1/1/24 3:00:00p You helped vanquish the Kolar Bear.\n");
+        body.push_str("1/1/24 3:10:00p You vanquished the Kolar Bear.\n");
+        fs::write(char_dir.join("CL Log 2024-01-01 13.00.00.txt"), body).unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_character("Testchar").unwrap().unwrap().id.unwrap();
+        let milestones = parser.db().get_milestones_merged(char_id).unwrap();
+        let rows: Vec<(&str, &str, String)> = milestones
+            .iter()
+            .map(|m| (m.creature.as_str(), m.kind.as_str(), m.achieved_at.to_string()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Rat", "kills", "2024-01-01 14:01:39".to_string()),
+                ("Kolar Bear", "first_solo_boss", "2024-01-01 15:10:00".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lines_before_the_first_timestamp_take_it_when_the_filename_has_no_date() {
+        let (_tmp, char_dir) = create_test_log_dir();
+        let log = char_dir.join("CL Log backup.txt");
+        fs::write(
+            &log,
+            "Welcome to Clan Lord, TestChar!\nYou slaughtered a Rat.\n1/1/24 1:05:00p You slaughtered a Rat.\n",
+        )
+        .unwrap();
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        let result = parser.scan_files_with_progress(&[log], false, false, |_, _, _| {}).unwrap();
+        assert_eq!(result.files_scanned, 1);
+
+        let c = parser.db().get_character("Testchar").unwrap().unwrap();
+        assert_eq!(c.logins, 1);
+        let kills = parser.db().get_kills(c.id.unwrap()).unwrap();
+        assert_eq!(kills[0].slaughtered_count, 2);
+        assert_eq!(kills[0].date_first.unwrap(), "2024-01-01 13:05:00");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hooks_run_after_commit_and_not_for_a_rolled_back_scan() {
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "1/1/24 1:00:00p Welcome to Clan Lord, TestChar!\n1/1/24 1:01:00p You slaughtered a Rat.\n",
+        )
+        .unwrap();
+        fs::write(
+            char_dir.join("CL Log 2024-01-02 13.00.00.txt"),
+            "1/2/24 1:00:00p Welcome to Clan Lord, TestChar!\n",
+        )
+        .unwrap();
+        let out = tmp.path().join("hooked.txt");
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.set_hooks(Some(HookConfig {
+            hooks: vec![crate::hooks::Hook {
+                event: "kill".into(),
+                command: "sh".into(),
+                args: vec!["-c".into(), format!("cat >> '{}'; echo >> '{}'", out.display(), out.display())],
+                every: None,
+                timeout_secs: None,
+            }],
+        }));
+
+        // Cancelled before the second file: the first file's kill is rolled back, unannounced.
+        let cancel = Arc::new(AtomicBool::new(false));
+        parser.set_cancel_flag(Some(cancel.clone()));
+        let err = parser
+            .scan_folder_with_progress(tmp.path(), false, false, |_, _, _| cancel.store(true, Ordering::SeqCst))
+            .unwrap_err();
+        assert_eq!(err.kind(), "cancelled");
+        assert!(!out.exists());
+
+        cancel.store(false, Ordering::SeqCst);
+        parser.scan_folder(tmp.path(), false).unwrap();
+        let fired = fs::read_to_string(&out).unwrap();
+        assert_eq!(fired.lines().count(), 1);
+        assert!(fired.contains("\"subject\":\"Rat\""));
+    }
+
+    #[test]
+    fn test_unscan_restores_stats_without_the_file() {
+        let mine = "\
+1/1/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/1/24 1:05:00p You slaughtered a Rat.
+1/1/24 1:06:00p * You pick up 5 coins.
+";
+        let friends = "\
+1/2/24 1:00:00p Welcome to Clan Lord, TestChar!
+1/2/24 1:05:00p You slaughtered a Rat.
+1/2/24 1:06:00p You killed an Orga Anger.
+1/2/24 1:07:00p TestChar has fallen to an Orga Anger.
+";
+        let (tmp, char_dir) = create_test_log_dir();
+        fs::write(char_dir.join("CL Log 2024-01-01 13.00.00.txt"), mine).unwrap();
+        let friend_path = char_dir.join("CL Log 2024-01-02 13.00.00.txt");
+        fs::write(&friend_path, friends).unwrap();
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let (only_tmp, only_dir) = create_test_log_dir();
+        fs::write(only_dir.join("CL Log 2024-01-01 13.00.00.txt"), mine).unwrap();
+        let expected = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        expected.scan_folder(only_tmp.path(), false).unwrap();
+
+        let summary = parser.unscan_file(&friend_path.to_string_lossy(), |_, _, _| {}).unwrap();
+        assert_eq!(summary.sessions, 1);
+        let snapshot = |p: &LogParser| {
+            let c = p.db().get_character("Testchar").unwrap().unwrap();
+            let kills: Vec<(i64, i64, String)> = p
+                .db()
+                .get_kills(c.id.unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|k| (k.total_solo(), k.killed_by_count, k.creature_name))
+                .collect();
+            (c.logins, c.deaths, c.coins_picked_up, kills)
+        };
+        assert_eq!(snapshot(&parser), snapshot(&expected));
+
+        // Forgotten, so the next scan picks the file up again.
+        parser.scan_folder(tmp.path(), false).unwrap();
+        assert_eq!(parser.db().get_character("Testchar").unwrap().unwrap().deaths, 1);
+    }
+
+    #[test]
+    fn test_coin_ledger_recorded_during_scan() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Tester");
+        fs::create_dir(&char_dir).unwrap();
+
+        let log_content = "\
+1/1/24 10:00:00a Welcome to Clan Lord, Tester!
+1/1/24 10:00:01a * You pick up 25 coins.
+1/1/24 10:00:02a * Fen recovers the Dark Vermine fur, worth 20c. Your share is 10c.
+1/2/24 10:00:03a * You pick up 5 coins.
+1/2/24 10:00:04a ¥ You have been charged 100 coins for advanced studies.
+";
+        fs::write(
+            char_dir.join("CL Log 2024-01-01 10.00.00.txt"),
+            log_content,
+        )
+        .unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let char_id = parser.db().get_character("Tester").unwrap().unwrap().id.unwrap();
+        let eco = parser.db().get_economy_merged(char_id, None).unwrap();
+        assert_eq!(eco.totals.coins_picked_up, 30);
+        assert_eq!(eco.totals.fur_coins, 10);
+        let history: Vec<(&str, i64, i64)> =
+            eco.history.iter().map(|p| (p.day.as_str(), p.amount, p.balance)).collect();
+        assert_eq!(history, vec![("2024-01-01", 35, 35), ("2024-01-02", -95, -60)]);
+    }
+
     #[test]
     fn pending_files_detects_new_grown_unchanged_shrank_and_legacy() {
         use super::pending_files;
@@ -4130,4 +5834,27 @@ mod tests {
             "appended Large Vermine must attribute to Ruuk via prefix re-derivation"
         );
     }
+
+    #[test]
+    fn compare_archives_reports_unique_shared_and_diverged() {
+        use super::compare_archives;
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        for root in [a.path(), b.path()] {
+            fs::create_dir_all(root.join("Ruuk")).unwrap();
+            fs::write(root.join("Ruuk/CL Log 2024-01-01 10.00.00.txt"), "1/1/24 1:00:00p Welcome to Clan Lord, Ruuk!\n").unwrap();
+        }
+        // Grown on A, shorter on B: same relative path, different content.
+        fs::write(a.path().join("Ruuk/CL Log 2024-01-02 10.00.00.txt"), "1/2/24 1:00:00p A\n1/2/24 1:05:00p B\n").unwrap();
+        fs::write(b.path().join("Ruuk/CL Log 2024-01-02 10.00.00.txt"), "1/2/24 1:00:00p A\n").unwrap();
+        fs::write(a.path().join("Ruuk/CL Log 2024-01-03 10.00.00.txt"), "only on a\n").unwrap();
+        fs::write(b.path().join("Ruuk/CL Log 2024-01-04 10.00.00.txt"), "only on b\n").unwrap();
+
+        let cmp = compare_archives(a.path(), b.path()).unwrap();
+        assert_eq!((cmp.files_a, cmp.files_b, cmp.shared), (3, 3, 1));
+        assert_eq!(cmp.only_in_a, vec![a.path().join("Ruuk/CL Log 2024-01-03 10.00.00.txt")]);
+        assert_eq!(cmp.only_in_b, vec![b.path().join("Ruuk/CL Log 2024-01-04 10.00.00.txt")]);
+        assert_eq!(cmp.diverged.len(), 1);
+        assert!(cmp.diverged[0].0.ends_with("CL Log 2024-01-02 10.00.00.txt"));
+    }
 }
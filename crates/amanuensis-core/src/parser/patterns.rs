@@ -1,6 +1,35 @@
+use std::borrow::Cow;
+
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+/// Fold client typography into the plain forms the patterns and trainer messages are written
+/// in: curly quotes and primes become `'` / `"`, an ellipsis `...`, and no-break spaces plain
+/// spaces. Some clients (and logs passed through an editor) write `Evus’s` or `“Hail, Fen.”`,
+/// which would otherwise silently fail to classify.
+pub fn normalize_typography(message: &str) -> Cow<'_, str> {
+    fn replacement(c: char) -> Option<&'static str> {
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => Some("'"),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => Some("\""),
+            '\u{2026}' => Some("..."),
+            '\u{00A0}' | '\u{2007}' | '\u{202F}' => Some(" "),
+            _ => None,
+        }
+    }
+    if message.is_ascii() || !message.chars().any(|c| replacement(c).is_some()) {
+        return Cow::Borrowed(message);
+    }
+    let mut out = String::with_capacity(message.len());
+    for c in message.chars() {
+        match replacement(c) {
+            Some(r) => out.push_str(r),
+            None => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
 // === Character detection ===
 pub static WELCOME_LOGIN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^Welcome to Clan Lord, (.+)!$").expect("regex compile error"));
@@ -14,6 +43,24 @@ pub static SOLO_KILL: Lazy<Regex> =
 // Assisted: "You helped kill/slaughter/vanquish/dispatch a/an/the {creature}."
 pub static ASSISTED_KILL: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^You helped (kill|slaughter|vanquish|dispatch) ((?:an?|the) .+)\.$").expect("regex compile error"));
+// Another player's kill: "{Player} slaughtered a/an/the {creature}." (capitalized name words)
+pub static OTHER_KILL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([A-Z][A-Za-z'-]*(?: [A-Z][A-Za-z'-]*)*) (killed|slaughtered|vanquished|dispatched) ((?:an?|the) .+)\.$")
+        .expect("regex compile error")
+});
+
+// === Melee hit/miss ===
+// "You hit the Rat." / "You swing at a Vermine and miss." / "You miss the Rat."
+pub static ATTACK_HIT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You hit ((?:an?|the) .+)\.$").expect("regex compile error"));
+pub static ATTACK_MISS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You (?:swing at ((?:an?|the) .+) and miss|miss ((?:an?|the) .+))\.$").expect("regex compile error"));
+
+// === Damage taken ===
+// "The Rat hits you." / "A Vermine bites you." — article required so other players'
+// attacks ("Squib hits you.") are not counted.
+pub static DAMAGE_TAKEN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^((?:[Aa]n?|[Tt]he) .+?) (?:hits|bites|claws|stings|strikes|slashes|mauls) you\.$").expect("regex compile error"));
 
 // === Death/fall patterns ===
 // "X has fallen to [a/an] Y." — cause may or may not have an article
@@ -83,6 +130,27 @@ pub static FISHING_MISS_EMPTY: Lazy<Regex> =
 pub static FISHING_CATCH: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^You reel in (?:a |an )?(.+?)[\.\!]*$").expect("regex compile error"));
 
+// === Stat boosts ===
+// Stat-boost consumables and shrine blessings: "You feel stronger." / "You feel much healthier!"
+// / "You feel more agile." / "You feel blessed."; "permanently" marks a lasting boost. Only
+// the stats in `STAT_BOOST_STATS` match — an open "any comparative" pattern also caught
+// ordinary lines such as "You feel better." or "You feel sleepier." — so this list is a
+// best guess from known boost wording and unverified against real logs. Only reached for
+// unprefixed lines — ¥/• trainer rank messages such as "You feel tougher." are classified
+// as system messages first.
+const STAT_BOOST_STATS: &str =
+    "stronger|healthier|hardier|quicker|faster|wiser|smarter|luckier|more agile|more nimble|blessed";
+pub static STAT_BOOST: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^(?:\* )?You feel (permanently )?(?:much |a (?:little|bit) |slightly )?({STAT_BOOST_STATS})[\.!]$"
+    ))
+    .expect("regex compile error")
+});
+// A temporary boost wearing off: "You no longer feel stronger." / "You no longer feel blessed."
+pub static STAT_BOOST_FADED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"^(?:\* )?You no longer feel ({STAT_BOOST_STATS})[\.!]$")).expect("regex compile error")
+});
+
 // === Karma patterns ===
 // "You just received good karma from {name}." / "You just received bad karma from {name}."
 pub static KARMA_RECEIVED: Lazy<Regex> =
@@ -113,6 +181,10 @@ pub static TRAINER_GREETING_SIMPLE: Lazy<Regex> =
 pub static TRAINER_BOW: Lazy<Regex> =
     Lazy::new(|| Regex::new(r#"^(.+?) bows(?: deeply)?\.?$"#).expect("regex compile error"));
 
+/// Trainer cap reached (system message): "{trainer} has nothing more to teach you."
+pub static TRAINER_MAXED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+?) has nothing more to teach you\.$").expect("regex compile error"));
+
 /// Generic NPC speech: Trainer says, "{message}"
 pub static NPC_SPEECH: Lazy<Regex> =
     Lazy::new(|| Regex::new(r#"^(.+?) says(?:\s+in\s+\S+)?, "(.+)"$"#).expect("regex compile error"));
@@ -123,6 +195,47 @@ pub static SPEECH: Lazy<Regex> =
 pub static EMOTE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^\(.+ .+\)$").expect("regex compile error"));
 
+// === Chat (the opt-in `chat` table) ===
+// "Fen says, "…"", "Fen yells in Thoom, "…"", "Bramble thinks to you, "…"": speaker, verb
+// (with any "in"/"to" qualifier), text. "You think to Bramble, "…"" is the log owner's.
+/// Verbs that introduce speech and thoughts ("Fen yells in Thoom, "…"", "Pip thinks to you, "…"").
+pub const SPEECH_VERBS: &[&str] = &["says", "exclaims", "yells", "ponders", "thinks", "asks", "whispers"];
+pub static CHAT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r#"^([^,"]+?) ((?:{})(?: (?:in|to) [^,"]+)?), "(.*)"$"#, SPEECH_VERBS.join("|")))
+        .expect("regex compile error")
+});
+pub static CHAT_SENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^You think (to [^,"]+), "(.*)"$"#).expect("regex compile error"));
+
+// === Thoughts ===
+// "Bramble thinks to you, "…"" / "You think to Bramble, "…"" (private thoughts, i.e. thinkto)
+pub static THOUGHT_RECEIVED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^([^,"]+?) thinks to you, ""#).expect("regex compile error"));
+pub static THOUGHT_SENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^You think to ([^,"]+?), ""#).expect("regex compile error"));
+
+// === Heals ===
+// "Bramble heals you." / "You heal Bramble." ("You heal yourself." is skipped by the classifier)
+pub static HEAL_RECEIVED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\S+) heals you\.$").expect("regex compile error"));
+pub static HEAL_GIVEN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You heal (\S+)\.$").expect("regex compile error"));
+
+// === Rescues ===
+// "Bramble starts dragging you." / "Bramble is dragging you." / "Bramble raises you." /
+// "You raise Bramble." (our own drags are `CHAIN_DRAG`)
+pub static DRAGGED_BY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+?) (?:starts|is) dragging you\.$").expect("regex compile error"));
+pub static RAISED_BY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+?) raises you\.$").expect("regex compile error"));
+pub static RAISED_OTHER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You raise (.+?)\.$").expect("regex compile error"));
+
+// === Sharing ===
+// "You are now sharing experiences with Bramble."
+pub static SHARING_START: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You are now sharing experiences with (.+)\.$").expect("regex compile error"));
+
 // === Clanning ===
 pub static CLANNING_ON: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^(.+) is now Clanning\.$").expect("regex compile error"));
@@ -183,22 +296,85 @@ pub static REFLECT_MORPH_HEADER: Lazy<Regex> =
 pub static STUDY_ABANDON: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^You abandon your study of (?:the|an?) (.+)\.$").expect("regex compile error"));
 
+// === Clan membership and titles ===
+// "You are now a member of the Night Watch." / "You are no longer a member of the Night Watch."
+pub static CLAN_JOIN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You are now a member of (?:the )?(.+?)\.$").expect("regex compile error"));
+pub static CLAN_LEAVE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You are no longer a member of (?:the )?(.+?)\.$").expect("regex compile error"));
+// "You have earned the title of Rat Catcher." (quotes around the title are optional)
+pub static TITLE_EARNED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^You have earned the title (?:of )?"?(.+?)"?[.!]$"#).expect("regex compile error"));
+// NPC announcement: Honor thinks, "Congratulations to {name}, who has earned the title of {title}."
+pub static TITLE_ANNOUNCEMENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?:thinks|says), "Congratulations to (.+?), who has (?:just )?earned the title (?:of )?(.+?)\.""#).expect("regex compile error"));
+
+// === Arena and tournaments ===
+// "You enter the Puddleby Arena." / "You step into the Tournament Arena."
+pub static ARENA_ENTER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You (?:enter|step into) (?:the )?(.*\b(?:Arena|Colosseum))\.$").expect("regex compile error"));
+// "You defeated Gandor in the arena!" / "You have defeated Gandor in the tournament."
+pub static ARENA_WIN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^You (?:have )?defeated ([A-Z][A-Za-z'-]*(?: [A-Z][A-Za-z'-]*)*) in the (?:arena|tournament)[.!]$")
+        .expect("regex compile error")
+});
+// "You were defeated by Gandor in the arena." / "You have been defeated by Gandor in the tournament."
+pub static ARENA_LOSS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^You (?:were|have been) defeated by ([A-Z][A-Za-z'-]*(?: [A-Z][A-Za-z'-]*)*) in the (?:arena|tournament)[.!]$")
+        .expect("regex compile error")
+});
+// "*** Gandor has won the tournament! ***" / "Gandor wins the tournament!"
+pub static TOURNAMENT_WON: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\*+ )?([A-Z][A-Za-z'-]*(?: [A-Z][A-Za-z'-]*)*) (?:has won|wins) the tournament[.!](?: \*+)?$")
+        .expect("regex compile error")
+});
+
+// === Healer pets ===
+// "The Maha Ruknee is now your pet." / "Maha Ruknee is now your pet!"
+pub static PET_ADOPTED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:(?:The|An?) )?(.+?) is now your pet[.!]$").expect("regex compile error"));
+// "You name your Maha Ruknee "Fluffy"." / "You name your pet Maha Ruknee "Fluffy"."
+pub static PET_NAMED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^You name your (?:pet )?(.+?) "(.+?)"\.$"#).expect("regex compile error"));
+// "You feed your pet Fluffy." / "You feed your Maha Ruknee."
+pub static PET_FED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You feed your (?:pet )?(.+?)\.$").expect("regex compile error"));
+// "Your pet Fluffy has died." / "Your pet Fluffy has fallen to a Large Vermine."
+pub static PET_DIED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Your pet (.+?) (?:has died|dies|has fallen)\b.*[.!]$").expect("regex compile error"));
+// "You release your pet Fluffy." / "You release your Maha Ruknee into the wild."
+pub static PET_RELEASED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You release your (?:pet )?(.+?)(?: into the wild)?\.$").expect("regex compile error"));
+
+// "You train your pet Fluffy." / "You spend some time training your Maha Ruknee."
+// Unverified: no captured log line confirms either wording.
+pub static PET_TRAINING: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^You (?:train|spend some time training) your (?:pet )?(.+?)\.$").expect("regex compile error"));
+// "Your pet Fluffy gains a rank!" / "Your pet Fluffy has grown more skilled."
+// Unverified, like PET_TRAINING.
+pub static PET_RANK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Your pet (.+?) (?:gains a rank|has gained a rank|grows more skilled|has grown more skilled)[.!]$")
+        .expect("regex compile error")
+});
+
 // === Profession detection from NPC announcements ===
 // Circle test: Honor thinks, "Congratulations go out to {name}, who has just passed the {ordinal} circle {profession} test."
+// Captures the name, the ordinal word ("seventh") and the profession.
 // Glory thinks, "Congratulations go out to {name}, who has just passed the {ordinal} circle healer test."
 pub static PROFESSION_CIRCLE_TEST: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r#"(?:thinks|says), "Congratulations go out to (.+), who has just passed the \w+ circle (\w+) test\."#).expect("regex compile error"));
+    Lazy::new(|| Regex::new(r#"(?:thinks|says), "Congratulations go out to (.+), who has just passed the (\w+) circle (\w+) test\."#).expect("regex compile error"));
 // Becoming: "Congratulations to {name}, who has just become a {profession}."
 pub static PROFESSION_BECOME: Lazy<Regex> =
     Lazy::new(|| Regex::new(r#"(?:thinks|says), "Congratulations to (.+), who has just become an? (\w+)\."#).expect("regex compile error"));
 
 // === Apply-learning bonus rank (non-¥, spoken by NPC) ===
+// Curly apostrophes are straightened by `normalize_typography` before these run.
 pub static APPLY_LEARNING_OFFER: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"[Ww]ould you like to apply some of your learning to (.+?)[\u{0027}\u{2019}]s lessons").expect("regex compile error"));
+    Lazy::new(|| Regex::new(r"[Ww]ould you like to apply some of your learning to (.+?)'s lessons").expect("regex compile error"));
 pub static APPLY_LEARNING_CONFIRM: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"Congratulations, (.+?)\. [Yy]ou should now understand much more of (.+?)[\u{0027}\u{2019}]s teachings").expect("regex compile error"));
+    Lazy::new(|| Regex::new(r"Congratulations, (.+?)\. [Yy]ou should now understand much more of (.+?)'s teachings").expect("regex compile error"));
 pub static APPLY_LEARNING_PARTIAL: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"Congratulations, (.+?)\. [Yy]ou should now understand more of (.+?)[\u{0027}\u{2019}]s teachings").expect("regex compile error"));
+    Lazy::new(|| Regex::new(r"Congratulations, (.+?)\. [Yy]ou should now understand more of (.+?)'s teachings").expect("regex compile error"));
 
 // === ¥-prefixed lines to skip (not trainer ranks) ===
 pub static YEN_HEALING_SENSE: Lazy<Regex> =
@@ -214,6 +390,16 @@ pub static YEN_STUDY_CONCURRENT: Lazy<Regex> =
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_normalize_typography() {
+        assert!(matches!(normalize_typography("You slaughtered a Rat."), Cow::Borrowed(_)));
+        assert!(matches!(normalize_typography("¥Your combat ability improves."), Cow::Borrowed(_)));
+        assert_eq!(
+            normalize_typography("Histia says, \u{201C}Hail, Fen.\u{00A0}You\u{2019}re quick\u{2026}\u{201D}"),
+            "Histia says, \"Hail, Fen. You're quick...\""
+        );
+    }
+
     #[test]
     fn test_welcome_login() {
         let caps = WELCOME_LOGIN.captures("Welcome to Clan Lord, Fen!").unwrap();
@@ -308,6 +494,13 @@ mod tests {
         assert_eq!(&caps[1], "Ava");
     }
 
+    #[test]
+    fn test_rescue_names_with_spaces() {
+        assert_eq!(&DRAGGED_BY.captures("Ava Lune starts dragging you.").unwrap()[1], "Ava Lune");
+        assert_eq!(&RAISED_BY.captures("Ava Lune raises you.").unwrap()[1], "Ava Lune");
+        assert_eq!(&RAISED_OTHER.captures("You raise Ava Lune.").unwrap()[1], "Ava Lune");
+    }
+
     #[test]
     fn test_speech_skip() {
         assert!(SPEECH.is_match(r#"Donk thinks, "south""#));
@@ -420,7 +613,7 @@ mod tests {
     #[test]
     fn test_apply_learning_offer_curly_apostrophe() {
         assert!(APPLY_LEARNING_OFFER.is_match(
-            "Aitnos asks, \"Would you like to apply some of your learning to Evus\u{2019}s lessons?\""
+            &normalize_typography("Aitnos asks, \"Would you like to apply some of your learning to Evus\u{2019}s lessons?\"")
         ));
     }
 
@@ -435,9 +628,10 @@ mod tests {
 
     #[test]
     fn test_apply_learning_confirm_curly_apostrophe() {
-        let caps = APPLY_LEARNING_CONFIRM.captures(
+        let message = normalize_typography(
             "Aitnos says, \"Congratulations, Sensei. You should now understand much more of Evus\u{2019}s teachings.\""
-        ).unwrap();
+        );
+        let caps = APPLY_LEARNING_CONFIRM.captures(&message).unwrap();
         assert_eq!(&caps[1], "Sensei");
         assert_eq!(&caps[2], "Evus");
     }
@@ -446,15 +640,16 @@ mod tests {
     fn test_apply_learning_partial_not_counted_as_full() {
         // "understand more" (without "much") is partial learning, not a full rank
         assert!(APPLY_LEARNING_CONFIRM.captures(
-            "Aitnos says, \"Congratulations, Sensei. You should now understand more of Evus\u{2019}s teachings.\""
+            &normalize_typography("Aitnos says, \"Congratulations, Sensei. You should now understand more of Evus\u{2019}s teachings.\"")
         ).is_none());
     }
 
     #[test]
     fn test_apply_learning_partial() {
-        let caps = APPLY_LEARNING_PARTIAL.captures(
+        let message = normalize_typography(
             "Aitnos says, \"Congratulations, Sensei. You should now understand more of Evus\u{2019}s teachings.\""
-        ).unwrap();
+        );
+        let caps = APPLY_LEARNING_PARTIAL.captures(&message).unwrap();
         assert_eq!(&caps[1], "Sensei");
         assert_eq!(&caps[2], "Evus");
     }
@@ -463,7 +658,7 @@ mod tests {
     fn test_apply_learning_partial_does_not_match_much_more() {
         // "understand much more" should NOT match "understand more" (literal match)
         assert!(APPLY_LEARNING_PARTIAL.captures(
-            "Aitnos says, \"Congratulations, Sensei. You should now understand much more of Evus\u{2019}s teachings.\""
+            &normalize_typography("Aitnos says, \"Congratulations, Sensei. You should now understand much more of Evus\u{2019}s teachings.\"")
         ).is_none());
     }
 
@@ -516,7 +711,8 @@ mod tests {
             r#"Honor thinks, "Congratulations go out to Camo, who has just passed the seventh circle fighter test.""#
         ).unwrap();
         assert_eq!(&caps[1], "Camo");
-        assert_eq!(&caps[2], "fighter");
+        assert_eq!(&caps[2], "seventh");
+        assert_eq!(&caps[3], "fighter");
     }
 
     #[test]
@@ -525,7 +721,8 @@ mod tests {
             r#"Glory thinks, "Congratulations go out to Squib, who has just passed the sixth circle healer test.""#
         ).unwrap();
         assert_eq!(&caps[1], "Squib");
-        assert_eq!(&caps[2], "healer");
+        assert_eq!(&caps[2], "sixth");
+        assert_eq!(&caps[3], "healer");
     }
 
     #[test]
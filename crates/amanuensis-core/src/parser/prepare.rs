@@ -0,0 +1,559 @@
+//! The parallel half of a scan: reading, hashing, decoding and classifying log files.
+//!
+//! None of this touches the database or `LogParser`'s per-scan state, so it runs on the
+//! rayon pool. A background thread prepares files a batch at a time and hands the results
+//! over a bounded channel, in file order, to the calling thread — the single writer that
+//! applies them to SQLite (`LogParser::scan_lines`). Everything that depends on what has
+//! already been written (duplicate-content checks, character lookups, override configs)
+//! stays on the writer.
+//!
+//! A nice (low-priority) scan prepares one file at a time on a single worker and pauses
+//! before handing each file to the writer, trading speed for a machine that stays responsive.
+//!
+//! Files over [`STREAMED_FILE_BYTES`] are never held whole: the worker hashes and measures
+//! them in passes over the file, and the writer reads, decodes and classifies their lines
+//! one at a time as it applies them (`PreparedLines::Streamed`).
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use rayon::prelude::*;
+
+use crate::data::TrainerDb;
+use crate::db::Database;
+use crate::encoding::{decode_log_bytes, LogLineReader};
+use crate::error::{AmanuensisError, Result};
+use crate::parser::events::LogEvent;
+use crate::parser::line_classifier::classify_line;
+use crate::parser::timestamp::parse_timestamp;
+
+use super::{extract_character_name, hash_bytes, hex_digest, patterns, titlecase_name};
+
+/// How long a nice scan waits before each file.
+pub const NICE_FILE_PAUSE: Duration = Duration::from_millis(50);
+
+/// Files larger than this are streamed: read and classified a line at a time by the writer
+/// instead of prepared whole on the worker pool.
+pub const STREAMED_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Read size for the passes over a streamed file.
+const STREAM_CHUNK: usize = 64 * 1024;
+
+/// One log line, split into timestamp and message and classified.
+pub(super) struct ClassifiedLine {
+    /// The raw line as written (what the search index stores).
+    pub line: String,
+    pub ts: Option<NaiveDateTime>,
+    /// Byte offset of the message (the text after the timestamp) within `line`.
+    pub message_start: usize,
+    pub event: LogEvent,
+}
+
+impl ClassifiedLine {
+    #[cfg(test)]
+    fn message(&self) -> &str {
+        &self.line[self.message_start..]
+    }
+}
+
+/// The lines a prepared file has to scan, in order: the whole file, or just the appended tail.
+pub(super) enum PreparedLines {
+    /// Classified up front on the worker pool.
+    Classified(Vec<ClassifiedLine>),
+    /// Bytes `start..end` of `path`, `count` lines, read as the writer goes.
+    Streamed { path: PathBuf, start: u64, end: u64, count: usize },
+}
+
+impl PreparedLines {
+    pub fn len(&self) -> usize {
+        match self {
+            PreparedLines::Classified(lines) => lines.len(),
+            PreparedLines::Streamed { count, .. } => *count,
+        }
+    }
+
+    /// The classified lines. A streamed file is opened here and read as the iterator is
+    /// advanced; a read failure part way through ends the iteration with that error.
+    pub fn read(self, trainer_db: &TrainerDb) -> Result<Box<dyn Iterator<Item = Result<ClassifiedLine>> + '_>> {
+        match self {
+            PreparedLines::Classified(lines) => Ok(Box::new(lines.into_iter().map(Ok))),
+            PreparedLines::Streamed { path, start, end, .. } => {
+                let mut file = File::open(&path).map_err(|e| AmanuensisError::file_unreadable(&path, e))?;
+                file.seek(SeekFrom::Start(start))?;
+                let lines = LogLineReader::new(BufReader::new(file.take(end - start)));
+                Ok(Box::new(lines.map(move |line| Ok(classify_log_line(line?, trainer_db)))))
+            }
+        }
+    }
+}
+
+/// A file that needs scanning, ready for the writer.
+pub(super) struct PreparedFile {
+    pub lines: PreparedLines,
+    /// Lines before `lines` in the file (0 unless scanning an appended tail).
+    pub first_line: usize,
+    /// Length and hash of the whole file, recorded in `log_files` afterward.
+    pub byte_len: usize,
+    pub full_hash: String,
+    /// False for tail scans so the login isn't re-counted.
+    pub count_login: bool,
+    /// Character named by the last welcome line before the appended tail (tail scans only).
+    pub resume_name: Option<String>,
+    /// Character named by the first welcome line in the file.
+    pub welcome_name: Option<String>,
+}
+
+/// Outcome of deciding how to (re)scan a single log file. See `plan_file_scan`.
+pub(super) enum ScanPlan {
+    /// File unchanged (or a legacy row with unknown length) — skip silently.
+    Skip,
+    /// New path whose content was already scanned at another path — skip as a duplicate.
+    SkipDuplicate,
+    /// File shrank or its already-scanned prefix changed (rotated/replaced) — skip and warn.
+    SkipChanged,
+    /// Reading the file failed.
+    ReadError(std::io::Error),
+    Scan(PreparedFile),
+}
+
+/// Decide how to (re)scan a single log file, implementing offset-resume so that a
+/// daily Clan Lord log which GROWS as the player keeps playing is picked up on the
+/// next incremental scan instead of being skipped wholesale.
+///
+/// `prior` is the file's `log_files` (byte_len, hash) as of the start of the scan (`None` when
+/// forcing). Fast path: a previously-scanned file whose on-disk size is unchanged is skipped
+/// without reading it. Only files that grew (or are new) are read.
+///
+/// Never returns `SkipDuplicate`: whether a new file's content was already scanned depends on
+/// files written earlier in this same scan, so the writer checks that (`PreparedFiles::next`).
+fn plan_file_scan(
+    log_path: &Path,
+    prior: Option<(i64, String)>,
+    complete_lines_only: bool,
+    trainer_db: &TrainerDb,
+) -> ScanPlan {
+    let size = std::fs::metadata(log_path).map(|meta| meta.len()).ok();
+    if let Some((prev_len, _)) = &prior {
+        if *prev_len > 0 {
+            // Known length: skip without reading when the file hasn't grown.
+            if size == Some(*prev_len as u64) {
+                return ScanPlan::Skip;
+            }
+        } else {
+            // Legacy row recorded before offset-resume (byte_len unknown): preserve the
+            // old skip-by-path behavior. A full Rescan Logs repopulates byte_len.
+            return ScanPlan::Skip;
+        }
+    }
+    if let Some(size) = size.filter(|&size| size > STREAMED_FILE_BYTES) {
+        return plan_streamed_scan(log_path, size, prior, complete_lines_only).unwrap_or_else(ScanPlan::ReadError);
+    }
+
+    let mut bytes = match std::fs::read(log_path) {
+        Ok(b) => b,
+        Err(e) => return ScanPlan::ReadError(e),
+    };
+
+    if complete_lines_only {
+        // Cut after the last line terminator (LF, CRLF or old-Mac CR), but never below the
+        // recorded length — a prior non-live scan may already have consumed a partial line.
+        let prev_len = prior.as_ref().map_or(0, |(len, _)| *len as usize);
+        let complete = bytes
+            .iter()
+            .rposition(|&b| b == b'\n' || b == b'\r')
+            .map_or(0, |i| i + 1);
+        if complete == 0 && prior.is_none() {
+            return ScanPlan::Skip;
+        }
+        bytes.truncate(complete.max(prev_len).min(bytes.len()));
+    }
+
+    let (offset, count_login) = match prior {
+        None => (0, true),
+        Some((prev_len, prev_hash)) => {
+            let prev_len = prev_len as usize;
+            let cur_len = bytes.len();
+            if cur_len == prev_len {
+                // Unchanged (metadata fast-path was unavailable).
+                return ScanPlan::Skip;
+            } else if cur_len > prev_len && hash_bytes(&bytes[..prev_len]) == prev_hash {
+                // True append: scan only the new tail; the login was already counted.
+                (prev_len, false)
+            } else {
+                // Shrunk or prefix changed → rotated/replaced. Can't reconcile
+                // incrementally without double-counting; skip and defer to a rescan.
+                return ScanPlan::SkipChanged;
+            }
+        }
+    };
+
+    ScanPlan::Scan(PreparedFile {
+        lines: PreparedLines::Classified(classify_bytes(&bytes[offset..], trainer_db)),
+        first_line: lines_before(&bytes, offset),
+        byte_len: bytes.len(),
+        full_hash: hash_bytes(&bytes),
+        count_login,
+        resume_name: active_name_at_offset(&bytes, offset),
+        welcome_name: extract_character_name(&bytes),
+    })
+}
+
+/// `plan_file_scan` for a file of `size` bytes over [`STREAMED_FILE_BYTES`]: the same
+/// decisions, made in passes over the file instead of from its bytes in memory.
+fn plan_streamed_scan(
+    log_path: &Path,
+    size: u64,
+    prior: Option<(i64, String)>,
+    complete_lines_only: bool,
+) -> std::io::Result<ScanPlan> {
+    let mut file = File::open(log_path)?;
+    let prev_len = prior.as_ref().map(|(len, _)| *len as u64);
+    let mut end = size;
+    if complete_lines_only {
+        let complete = last_line_end(&mut file, size)?;
+        if complete == 0 && prior.is_none() {
+            return Ok(ScanPlan::Skip);
+        }
+        end = complete.max(prev_len.unwrap_or(0)).min(size);
+    }
+
+    let offset = prev_len.unwrap_or(0);
+    let count_login = match &prior {
+        None => true,
+        Some(_) if end == offset => return Ok(ScanPlan::Skip),
+        Some(_) if end < offset => return Ok(ScanPlan::SkipChanged),
+        Some(_) => false,
+    };
+    let pass = hash_pass(&mut file, end, offset)?;
+    if let Some((_, prev_hash)) = &prior {
+        if pass.prefix_hash != *prev_hash {
+            return Ok(ScanPlan::SkipChanged);
+        }
+    }
+
+    let (welcome_name, resume_name) = streamed_welcome_names(log_path, offset)?;
+    Ok(ScanPlan::Scan(PreparedFile {
+        lines: PreparedLines::Streamed { path: log_path.to_path_buf(), start: offset, end, count: pass.lines },
+        first_line: pass.first_line,
+        byte_len: end as usize,
+        full_hash: pass.full_hash,
+        count_login,
+        resume_name,
+        welcome_name,
+    }))
+}
+
+/// What one read of a streamed file up to `end` measures.
+struct HashPass {
+    full_hash: String,
+    /// Hash of the bytes before the tail offset (the previously scanned prefix).
+    prefix_hash: String,
+    /// Lines ended before the offset.
+    first_line: usize,
+    /// Lines from the offset to `end`.
+    lines: usize,
+}
+
+fn hash_pass(file: &mut File, end: u64, offset: u64) -> std::io::Result<HashPass> {
+    use sha2::{Digest, Sha256};
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut reader = file.take(end);
+    let (mut full, mut prefix) = (Sha256::new(), Sha256::new());
+    let (mut before, mut after) = (LineCounter::default(), LineCounter::default());
+    let mut buf = vec![0; STREAM_CHUNK];
+    let mut pos = 0u64;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        let chunk = &buf[..n];
+        full.update(chunk);
+        let split = (offset.saturating_sub(pos) as usize).min(n);
+        prefix.update(&chunk[..split]);
+        before.feed(&chunk[..split]);
+        after.feed(&chunk[split..]);
+        pos += n as u64;
+    }
+    Ok(HashPass {
+        full_hash: hex_digest(full),
+        prefix_hash: hex_digest(prefix),
+        first_line: before.ended,
+        lines: after.lines(),
+    })
+}
+
+/// Byte length of `file` (`size` bytes) up to and including its last line ending; 0 when
+/// there is none.
+fn last_line_end(file: &mut File, size: u64) -> std::io::Result<u64> {
+    let mut buf = vec![0; STREAM_CHUNK];
+    let mut end = size;
+    while end > 0 {
+        let start = end.saturating_sub(STREAM_CHUNK as u64);
+        let chunk = &mut buf[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+        if let Some(i) = chunk.iter().rposition(|&b| b == b'\n' || b == b'\r') {
+            return Ok(start + i as u64 + 1);
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+/// A streamed file's first welcome name and the last one before byte `offset`
+/// (`extract_character_name` and `active_name_at_offset` without the bytes in memory).
+fn streamed_welcome_names(path: &Path, offset: u64) -> std::io::Result<(Option<String>, Option<String>)> {
+    let mut first = None;
+    let mut last = None;
+    for line in LogLineReader::new(BufReader::new(File::open(path)?.take(offset))) {
+        if let Some(name) = welcome_name(&line?) {
+            first.get_or_insert_with(|| name.clone());
+            last = Some(name);
+        }
+    }
+    if first.is_none() {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        for line in LogLineReader::new(BufReader::new(file)) {
+            first = welcome_name(&line?);
+            if first.is_some() {
+                break;
+            }
+        }
+    }
+    Ok((first, last))
+}
+
+/// Counts lines as [`LogLineReader`] splits them, over bytes fed in chunks.
+#[derive(Default)]
+struct LineCounter {
+    /// Lines whose ending has been seen.
+    ended: usize,
+    after_cr: bool,
+    /// Bytes follow the last line ending.
+    partial: bool,
+}
+
+impl LineCounter {
+    fn feed(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            match b {
+                b'\n' if self.after_cr => {}
+                b'\r' | b'\n' => {
+                    self.ended += 1;
+                    self.partial = false;
+                }
+                _ => self.partial = true,
+            }
+            self.after_cr = b == b'\r';
+        }
+    }
+
+    /// Lines so far, counting an unterminated last one.
+    fn lines(&self) -> usize {
+        self.ended + usize::from(self.partial)
+    }
+}
+
+/// Decode `bytes` and classify every line.
+pub(super) fn classify_bytes(bytes: &[u8], trainer_db: &TrainerDb) -> Vec<ClassifiedLine> {
+    decode_log_bytes(bytes).lines().map(|line| classify_log_line(line.to_string(), trainer_db)).collect()
+}
+
+/// Split one decoded line into timestamp and message and classify it.
+fn classify_log_line(line: String, trainer_db: &TrainerDb) -> ClassifiedLine {
+    let (ts, message_start, event) = match parse_timestamp(&line) {
+        Some((dt, message)) => (Some(dt), line.len() - message.len(), classify_line(message, trainer_db)),
+        None => (None, 0, classify_line(&line, trainer_db)),
+    };
+    ClassifiedLine { line, ts, message_start, event }
+}
+
+/// Lines ended before byte `offset` of `bytes`; a CRLF ends one line.
+fn lines_before(bytes: &[u8], offset: usize) -> usize {
+    let mut counter = LineCounter::default();
+    counter.feed(&bytes[..offset.min(bytes.len())]);
+    counter.ended
+}
+
+/// The character a `Welcome …` line names, if `line` is one.
+pub(super) fn welcome_name(line: &str) -> Option<String> {
+    let message = match parse_timestamp(line) { Some((_dt, msg)) => msg, None => line };
+    patterns::WELCOME_LOGIN
+        .captures(message)
+        .or_else(|| patterns::WELCOME_BACK.captures(message))
+        .map(|caps| titlecase_name(&caps[1]))
+}
+
+/// The character named by the last `Welcome …` line before byte `offset` of `bytes`.
+/// Used to seed a tail (append) scan so events after the offset attribute correctly.
+fn active_name_at_offset(bytes: &[u8], offset: usize) -> Option<String> {
+    if offset == 0 {
+        return None;
+    }
+    decode_log_bytes(&bytes[..offset.min(bytes.len())]).lines().rev().find_map(welcome_name)
+}
+
+/// Scan plans for a list of files, prepared in the background and yielded in order.
+pub(super) struct PreparedFiles {
+    plans: Receiver<ScanPlan>,
+    force: bool,
+    nice: bool,
+}
+
+impl PreparedFiles {
+    /// Start preparing `paths`. Prior scan state is read up front, so this must be called
+    /// after anything earlier in the scan that could have recorded one of these paths.
+    pub fn start(
+        db: &Database,
+        trainer_db: &Arc<TrainerDb>,
+        paths: Vec<PathBuf>,
+        force: bool,
+        complete_lines_only: bool,
+        nice: bool,
+    ) -> Result<Self> {
+        let jobs = paths
+            .into_iter()
+            .map(|path| {
+                let prior = if force {
+                    None
+                } else {
+                    db.get_log_scan_state(&path.to_string_lossy())?
+                };
+                Ok((path, prior))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Enough files in flight to keep every worker busy while the writer catches up,
+        // without holding a whole archive's decoded text in memory. Nice scans keep a single
+        // file in flight, so only one worker is ever busy.
+        let batch = if nice { 1 } else { rayon::current_num_threads().max(1) * 2 };
+        let (tx, plans) = mpsc::sync_channel(batch);
+        let trainer_db = Arc::clone(trainer_db);
+        thread::spawn(move || {
+            for chunk in jobs.chunks(batch) {
+                let prepared: Vec<ScanPlan> = chunk
+                    .par_iter()
+                    .map(|(path, prior)| plan_file_scan(path, prior.clone(), complete_lines_only, &trainer_db))
+                    .collect();
+                for plan in prepared {
+                    if tx.send(plan).is_err() {
+                        // The writer stopped early (error or cancellation).
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Self { plans, force, nice })
+    }
+
+    /// The next file's plan. New files whose content was already scanned (earlier in this
+    /// scan or a previous one) come back as `SkipDuplicate`.
+    pub fn next(&self, db: &Database) -> Result<ScanPlan> {
+        if self.nice {
+            thread::sleep(NICE_FILE_PAUSE);
+        }
+        let plan = self.plans.recv().map_err(|_| {
+            AmanuensisError::Data("Log file preparation stopped unexpectedly".to_string())
+        })?;
+        match plan {
+            // Only never-seen paths count their login; those are the ones to dedup.
+            ScanPlan::Scan(file) if file.count_login && !self.force && db.is_hash_scanned(&file.full_hash)? => {
+                Ok(ScanPlan::SkipDuplicate)
+            }
+            plan => Ok(plan),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_bytes_splits_timestamp_from_message() {
+        let tdb = TrainerDb::bundled().unwrap();
+        let lines = classify_bytes(b"1/1/24 1:00:00p You slaughtered a Rat.\r\nno timestamp\r\n", &tdb);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ts.is_some());
+        assert_eq!(lines[0].message(), "You slaughtered a Rat.");
+        assert!(matches!(lines[0].event, LogEvent::SoloKill { .. }));
+        assert_eq!((lines[1].ts, lines[1].message()), (None, "no timestamp"));
+    }
+
+    #[test]
+    fn lines_before_counts_each_line_ending_once() {
+        assert_eq!(lines_before(b"a\r\nb\rc\nd", 0), 0);
+        assert_eq!(lines_before(b"a\r\nb\rc\nd", 3), 1);
+        assert_eq!(lines_before(b"a\r\nb\rc\nd", 7), 3);
+    }
+
+    #[test]
+    fn streamed_plan_matches_in_memory_plan() {
+        let tdb = TrainerDb::bundled().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CL Log 2024-01-01 12.00.00.txt");
+        let head = "1/1/24 1:00:00p Welcome to Clan Lord, Gandor!\r\n1/1/24 1:00:01p You slaughtered a Rat.\r\n";
+        std::fs::write(&path, format!("{head}1/1/24 1:00:02p Welcome back, Histia!\r\npartial")).unwrap();
+        let prefix_hash = hash_bytes(head.as_bytes());
+
+        for (prior, complete_lines_only) in [(None, false), (Some((head.len() as i64, prefix_hash)), true)] {
+            let size = std::fs::metadata(&path).unwrap().len();
+            let (ScanPlan::Scan(memory), Ok(ScanPlan::Scan(streamed))) = (
+                plan_file_scan(&path, prior.clone(), complete_lines_only, &tdb),
+                plan_streamed_scan(&path, size, prior, complete_lines_only),
+            ) else {
+                panic!("both plans scan");
+            };
+            assert_eq!(
+                (memory.first_line, memory.byte_len, &memory.full_hash, memory.count_login),
+                (streamed.first_line, streamed.byte_len, &streamed.full_hash, streamed.count_login)
+            );
+            assert_eq!((&memory.welcome_name, &memory.resume_name), (&streamed.welcome_name, &streamed.resume_name));
+            assert_eq!(memory.lines.len(), streamed.lines.len());
+            let lines = |p: PreparedLines| -> Vec<String> { p.read(&tdb).unwrap().map(|l| l.unwrap().line).collect() };
+            assert_eq!(lines(memory.lines), lines(streamed.lines));
+        }
+    }
+
+    #[test]
+    fn prepared_files_yield_in_order_and_flag_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<PathBuf> = (0..20)
+            .map(|i| {
+                let p = dir.path().join(format!("CL Log 2024:01:{:02} 10.00.00.txt", i + 1));
+                // Files 0 and 10 share content; the second must be reported as a duplicate.
+                let body = format!("1/1/24 1:00:00p You slaughtered a Rat.\r\nfile {}\r\n", i % 10);
+                std::fs::write(&p, if i <= 10 { body } else { format!("{body}{i}\r\n") }).unwrap();
+                p
+            })
+            .collect();
+        let db = Database::open_in_memory().unwrap();
+        let char_id = db.get_or_create_character("Tester").unwrap();
+        let tdb = Arc::new(TrainerDb::bundled().unwrap());
+        let prepared = PreparedFiles::start(&db, &tdb, paths.clone(), false, false, false).unwrap();
+
+        for (i, path) in paths.iter().enumerate() {
+            match prepared.next(&db).unwrap() {
+                ScanPlan::Scan(file) => {
+                    assert_ne!(i, 10, "identical content should be a duplicate");
+                    let PreparedLines::Classified(lines) = &file.lines else { panic!("small files are classified") };
+                    assert!(lines[1].line.ends_with(&format!("file {}", i % 10)));
+                    db.mark_log_scanned(char_id, &path.to_string_lossy(), &file.full_hash, file.byte_len as i64, "2024-01-01")
+                        .unwrap();
+                }
+                ScanPlan::SkipDuplicate => assert_eq!(i, 10),
+                _ => panic!("unexpected plan for file {i}"),
+            }
+        }
+        assert!(prepared.next(&db).is_err(), "no plans past the end");
+    }
+}
@@ -0,0 +1,203 @@
+//! Rebuilding the search index from the files already scanned.
+//!
+//! A database scanned with `--no-index` (or indexed under an older retention policy) used to
+//! need a full forced rescan to become searchable. The rebuild re-reads each file in
+//! `log_files` up to the length that was scanned and indexes its lines the way the scanner
+//! does — dated by their timestamps (or the filename date), attributed to the character of
+//! the latest welcome, filtered by the retention policy — without touching any other table.
+//! Lines before a file's first welcome go to the character the file was recorded under.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::encoding::LogLineReader;
+use crate::error::{AmanuensisError, Result};
+use crate::parser::events::LogEvent;
+use crate::parser::line_classifier::classify_line;
+use crate::parser::prepare::welcome_name;
+use crate::parser::timestamp::{parse_filename_date, parse_timestamp};
+
+use super::LogParser;
+
+/// Rows inserted per FTS5 batch during a rebuild.
+const REINDEX_BATCH: usize = 1000;
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ReindexResult {
+    /// Scanned files read back into the index.
+    pub files: usize,
+    /// Lines indexed.
+    pub lines: i64,
+    /// Scanned files that could no longer be opened, with the reason.
+    pub missing: Vec<(String, String)>,
+}
+
+impl LogParser {
+    /// Replace the search index with the lines of every scanned file. `progress` gets
+    /// (files done, total files, current file), as for a scan. Every file is checked before
+    /// the index is touched: files that can't be opened keep their indexed lines and are
+    /// listed in `missing`. The old lines go in the same transaction as the new ones, so a
+    /// failed rebuild leaves the index as it was; the file is compacted after the commit.
+    pub fn rebuild_search_index<F>(&self, progress: F) -> Result<ReindexResult>
+    where
+        F: Fn(usize, usize, &str),
+    {
+        let mut result = ReindexResult::default();
+        let mut readable = Vec::new();
+        for (char_id, path, source, byte_len) in self.db.scanned_log_files()? {
+            match File::open(&source) {
+                Ok(_) => readable.push((char_id, path, source, byte_len)),
+                Err(e) => {
+                    let reason = AmanuensisError::file_unreadable(Path::new(&source), e).to_string();
+                    result.missing.push((path, reason));
+                }
+            }
+        }
+        let kept: Vec<String> = result.missing.iter().map(|(path, _)| path.clone()).collect();
+
+        self.db.begin_transaction()?;
+        let rebuilt = (|| -> Result<i64> {
+            let removed = self.db.delete_log_lines_except(&kept)?;
+            for (i, (char_id, path, source, byte_len)) in readable.iter().enumerate() {
+                progress(i + 1, readable.len(), path);
+                result.lines += self.reindex_file(*char_id, path, source, *byte_len)?;
+                result.files += 1;
+            }
+            Ok(removed)
+        })();
+        match rebuilt {
+            Ok(removed) => {
+                self.db.commit_transaction()?;
+                if removed > 0 {
+                    self.db.vacuum()?;
+                }
+                Ok(result)
+            }
+            Err(e) => {
+                let _ = self.db.rollback_transaction();
+                Err(e)
+            }
+        }
+    }
+
+    /// Index the first `byte_len` bytes of the file stored as `path` (all of it for legacy
+    /// rows without a length), read from `source`, starting out attributed to `char_id`.
+    /// Returns the lines indexed.
+    fn reindex_file(&self, char_id: i64, path: &str, source: &str, byte_len: i64) -> Result<i64> {
+        let file = File::open(source).map_err(|e| AmanuensisError::file_unreadable(Path::new(source), e))?;
+        let limit = if byte_len > 0 { byte_len as u64 } else { u64::MAX };
+        let retention = self.db.retention_policy()?;
+        let retention_cutoff = retention.cutoff(crate::date::now_naive());
+
+        let filename = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let mut current_date = parse_filename_date(filename).unwrap_or_default();
+        let mut active = char_id;
+        let mut indexed = 0;
+        let mut log_lines: Vec<(i64, String, String, String)> = Vec::new();
+        for line in LogLineReader::new(BufReader::new(file.take(limit))) {
+            let line = line?;
+            let message = match parse_timestamp(&line) {
+                Some((dt, message)) => {
+                    current_date = crate::date::to_storage(&dt);
+                    message
+                }
+                None => line.as_str(),
+            };
+            if let Some(name) = welcome_name(&line) {
+                active = self.characters.get_or_create(&self.db, &name)?;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Only an events-only policy looks at the event; skip classifying otherwise.
+            let event = if retention.events_only { classify_line(message, &self.trainer_db) } else { LogEvent::Ignored };
+            if !retention.keeps(retention_cutoff.as_deref(), &current_date, &event) {
+                continue;
+            }
+            log_lines.push((active, line.clone(), current_date.clone(), path.to_string()));
+            indexed += 1;
+            if log_lines.len() >= REINDEX_BATCH {
+                self.flush_log_lines(&mut log_lines)?;
+            }
+        }
+        self.flush_log_lines(&mut log_lines)?;
+        Ok(indexed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn indexed_rows(db: &Database) -> Vec<(i64, String, String, String)> {
+        let mut stmt = db
+            .conn()
+            .prepare("SELECT character_id, content, timestamp, file_path FROM log_lines ORDER BY rowid")
+            .unwrap();
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))).unwrap();
+        rows.map(|r| r.unwrap()).collect()
+    }
+
+    #[test]
+    fn rebuild_matches_scanning_with_the_index_on() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Gandor");
+        std::fs::create_dir(&char_dir).unwrap();
+        std::fs::write(
+            char_dir.join("CL Log 2024-01-01 13.00.00.txt"),
+            "1/1/24 1:00:00p Welcome to Clan Lord, Gandor!\n1/1/24 1:01:00p You slaughtered a Rat.\n\n\
+             no timestamp here\n1/1/24 1:05:00p Welcome back, Histia!\n1/1/24 1:06:00p Fen says, \"hi\"\n",
+        )
+        .unwrap();
+        let noop = |_: usize, _: usize, _: &str| {};
+
+        let indexed = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        indexed.scan_folder_with_progress(tmp.path(), false, true, noop).unwrap();
+        let expected = indexed_rows(indexed.db());
+        assert_eq!(expected.len(), 5);
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder_with_progress(tmp.path(), false, false, noop).unwrap();
+        assert!(indexed_rows(parser.db()).is_empty());
+        let result = parser.rebuild_search_index(noop).unwrap();
+        assert_eq!((result.files, result.lines, result.missing.len()), (1, 5, 0));
+        assert_eq!(indexed_rows(parser.db()), expected);
+
+        // A file that can no longer be read keeps the lines it had.
+        std::fs::remove_dir_all(&char_dir).unwrap();
+        let result = parser.rebuild_search_index(noop).unwrap();
+        assert_eq!((result.files, result.lines, result.missing.len()), (0, 0, 1));
+        assert_eq!(indexed_rows(parser.db()), expected);
+    }
+
+    #[test]
+    fn rebuild_reads_files_through_their_source_path_under_an_alias() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Gandor");
+        std::fs::create_dir(&char_dir).unwrap();
+        let file = char_dir.join("CL Log 2024-01-01 13.00.00.txt");
+        std::fs::write(&file, "1/1/24 1:00:00p Welcome to Clan Lord, Gandor!\n1/1/24 1:01:00p You slaughtered a Rat.\n")
+            .unwrap();
+        let noop = |_: usize, _: usize, _: &str| {};
+
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder_with_progress(tmp.path(), false, false, noop).unwrap();
+        let root = tmp.path().to_string_lossy().to_string();
+        parser
+            .db()
+            .set_log_path_aliases(&[crate::log_paths::PathAlias { from: root, to: "archive".into() }])
+            .unwrap();
+        let key = "archive/Gandor/CL Log 2024-01-01 13.00.00.txt";
+        assert!(parser.db().is_log_scanned(key).unwrap());
+        assert_eq!(parser.db().log_file_source(key).unwrap(), file.to_string_lossy());
+
+        let result = parser.rebuild_search_index(noop).unwrap();
+        assert_eq!((result.files, result.lines, result.missing.len()), (1, 2, 0));
+        // Indexed lines keep the key, so they still match the file's other rows.
+        assert!(indexed_rows(parser.db()).iter().all(|row| row.3 == key));
+    }
+}
@@ -1,94 +1,115 @@
 use chrono::NaiveDateTime;
 
-/// Extract a date string from a CL Log filename.
-/// Handles both modern format (`CL Log YYYY:MM:DD HH.MM.SS.txt`) and old Mac client
-/// format (`CL Log YYYY:MM:DD HH.MM.SS` — no .txt extension).
-/// Returns `"YYYY-MM-DD HH:MM:SS"` or None if the filename doesn't match.
+/// Filename prefixes of Clan Lord text logs: the Mac client's `CL Log ` and the Windows
+/// client's `CL_Log_`. Matched case-insensitively.
+pub const LOG_FILE_PREFIXES: &[&str] = &["CL Log ", "CL_Log_"];
+
+/// Whether a filename looks like a Clan Lord text log, regardless of extension — newer clients
+/// produce ".txt" files, but older Mac clients (pre-2007 era) produce extensionless files.
+pub fn is_log_filename(filename: &str) -> bool {
+    strip_log_prefix(filename).is_some()
+}
+
+fn strip_log_prefix(filename: &str) -> Option<&str> {
+    LOG_FILE_PREFIXES.iter().find_map(|prefix| {
+        let head = filename.get(..prefix.len())?;
+        head.eq_ignore_ascii_case(prefix).then(|| &filename[prefix.len()..])
+    })
+}
+
+/// Extract a date string from a CL Log filename. Handles:
+/// - modern Mac: `CL Log YYYY-MM-DD HH.MM.SS.txt`
+/// - old Mac (no extension, colons): `CL Log YYYY:MM:DD HH.MM.SS`, or with underscores once
+///   copied through Windows (`CL Log YYYY_MM_DD HH.MM.SS`)
+/// - Windows: `CL_Log_YYYY-MM-DD_HH-MM-SS.txt`, and the compact `CL_Log_YYYYMMDD_HHMMSS.txt`
+///
+/// Returns `"YYYY-MM-DD HH:MM:SS"` or None if the filename doesn't match or isn't a real date.
 pub fn parse_filename_date(filename: &str) -> Option<String> {
-    let after_prefix = filename.strip_prefix("CL Log ")?;
-    // Strip optional .txt suffix
-    let inner = after_prefix.strip_suffix(".txt").unwrap_or(after_prefix);
-    // inner = "YYYY:MM:DD HH.MM.SS"
-    let (date_part, time_part) = inner.split_once(' ')?;
-    // date_part = "YYYY:MM:DD", time_part = "HH.MM.SS"
-    let date = date_part.replace([':', '_'], "-");
-    let time = time_part.replace('.', ":");
-    // Validate it looks like a real date (basic sanity: 4-digit year, valid format)
-    if date.len() < 8 || !date.contains('-') { return None; }
-    Some(format!("{date} {time}"))
+    let inner = strip_log_prefix(filename)?;
+    let inner = match inner.rsplit_once('.') {
+        Some((stem, ext)) if ext.eq_ignore_ascii_case("txt") => stem,
+        _ => inner,
+    };
+    // Runs of digits: six fields, or "YYYYMMDD" + "HHMMSS". Anything after (a copy's " (2)")
+    // is ignored.
+    let groups: Vec<&str> = inner.split(|c: char| !c.is_ascii_digit()).filter(|g| !g.is_empty()).collect();
+    let fields: Vec<u32> = match groups.as_slice() {
+        [date, time, ..] if date.len() == 8 && time.len() == 6 => [&date[..4], &date[4..6], &date[6..], &time[..2], &time[2..4], &time[4..]]
+            .iter()
+            .map(|f| f.parse().ok())
+            .collect::<Option<_>>()?,
+        [year, ..] if groups.len() >= 6 && year.len() == 4 => {
+            groups[..6].iter().map(|f| f.parse().ok()).collect::<Option<_>>()?
+        }
+        _ => return None,
+    };
+    let dt = chrono::NaiveDate::from_ymd_opt(fields[0] as i32, fields[1], fields[2])?
+        .and_hms_opt(fields[3], fields[4], fields[5])?;
+    Some(crate::date::to_storage(&dt))
 }
 
 /// Parse a Clan Lord log timestamp from the beginning of a line.
-/// Format: `M/D/YY H:MM:SSa/p` (12-hour, no leading zeros on month/day/hour)
+/// Mac client format: `M/D/YY H:MM:SSa/p` (12-hour, no leading zeros on month/day/hour), or
+/// 24-hour `M/D/YY H:MM:SS` from some clients. The Windows client follows the system locale,
+/// so it also writes four-digit years (`M/D/YYYY`), ISO dates (`YYYY-MM-DD`) and `AM`/`PM`
+/// suffixes, attached or after a space (`9:21:44 PM`).
 /// Returns (NaiveDateTime, rest_of_line) or None if no timestamp found.
 pub fn parse_timestamp(line: &str) -> Option<(NaiveDateTime, &str)> {
-    // Find the position after the timestamp: look for 'a ' or 'p ' after time
-    // Pattern: M/D/YY H:MM:SSa or M/D/YY H:MM:SSp
     // Min length: "1/1/17 1:00:00a " = 16 chars
-
-    let bytes = line.as_bytes();
-    if bytes.len() < 16 {
+    if line.len() < 16 {
         return None;
     }
 
     // Find first space (between date and time)
     let date_end = line.find(' ')?;
-    let date_part = &line[..date_end];
+    let (year, month, day) = parse_date(&line[..date_end])?;
 
-    // Parse date: M/D/YY
-    let mut date_parts = date_part.split('/');
-    let month: u32 = date_parts.next()?.parse().ok()?;
-    let day: u32 = date_parts.next()?.parse().ok()?;
-    let year_short: i32 = date_parts.next()?.parse().ok()?;
-    if date_parts.next().is_some() {
-        return None; // Extra slash
-    }
-
-    // Convert 2-digit year: 00-99 → 2000-2099
-    let year = 2000 + year_short;
-
-    // Parse time: H:MM:SSa/p <message>
+    // Parse time: H:MM:SS[a|p|am|pm] [AM|PM] <message>
     let rest = &line[date_end + 1..];
 
     // Find the space between time and message (e.g., "7:39:54p You slaughtered...")
     let time_end = rest.find(' ')?;
-    let time_with_ampm = &rest[..time_end];
-    let message = &rest[time_end + 1..];
+    let time_token = &rest[..time_end];
+    let mut message = &rest[time_end + 1..];
 
-    // Last char of time string is a/p (12-hour) or a digit (24-hour)
-    if time_with_ampm.is_empty() {
+    // Split off a trailing a/p/am/pm (12-hour); none means 24-hour, unless the Windows
+    // client's separate "AM"/"PM" word follows.
+    let digits_end = time_token.trim_end_matches(|c: char| c.is_ascii_alphabetic()).len();
+    let (time_part, mut suffix) = time_token.split_at(digits_end);
+    if time_part.is_empty() {
         return None;
     }
-    let last = time_with_ampm.as_bytes()[time_with_ampm.len() - 1];
-
-    let (mut hour, minute, second) = if last == b'a' || last == b'p' {
-        // 12-hour format: H:MM:SSa/p
-        let time_part = &time_with_ampm[..time_with_ampm.len() - 1];
-        let mut time_parts = time_part.split(':');
-        let h: u32 = time_parts.next()?.parse().ok()?;
-        let m: u32 = time_parts.next()?.parse().ok()?;
-        let s: u32 = time_parts.next()?.parse().ok()?;
-        if time_parts.next().is_some() {
-            return None;
-        }
-        (h, m, s)
-    } else if last.is_ascii_digit() {
-        // 24-hour format: H:MM:SS or HH:MM:SS (no am/pm suffix)
-        let mut time_parts = time_with_ampm.split(':');
-        let h: u32 = time_parts.next()?.parse().ok()?;
-        let m: u32 = time_parts.next()?.parse().ok()?;
-        let s: u32 = time_parts.next()?.parse().ok()?;
-        if time_parts.next().is_some() {
-            return None;
+    let mut time_parts = time_part.split(':');
+    let mut hour: u32 = time_parts.next()?.parse().ok()?;
+    let minute: u32 = time_parts.next()?.parse().ok()?;
+    let second: u32 = time_parts.next()?.parse().ok()?;
+    if time_parts.next().is_some() {
+        return None;
+    }
+    if suffix.is_empty() && (1..=12).contains(&hour) {
+        let word_end = message.find(' ').unwrap_or(message.len());
+        let word = &message[..word_end];
+        if word.eq_ignore_ascii_case("am") || word.eq_ignore_ascii_case("pm") {
+            suffix = word;
+            message = message.get(word_end + 1..).unwrap_or("");
         }
-        (h, m, s)
+    }
+
+    // Convert 12-hour to 24-hour
+    let pm = if suffix.is_empty() {
+        None
+    } else if suffix.eq_ignore_ascii_case("a") || suffix.eq_ignore_ascii_case("am") {
+        Some(false)
+    } else if suffix.eq_ignore_ascii_case("p") || suffix.eq_ignore_ascii_case("pm") {
+        Some(true)
     } else {
         return None;
     };
-
-    // Convert 12-hour to 24-hour (only for a/p format)
-    if last == b'a' && hour == 12 { hour = 0; }
-    else if last == b'p' && hour != 12 { hour += 12; }
+    match pm {
+        Some(false) if hour == 12 => hour = 0,
+        Some(true) if hour != 12 => hour += 12,
+        _ => {}
+    }
 
     // Validate ranges
     if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
@@ -101,6 +122,33 @@ pub fn parse_timestamp(line: &str) -> Option<(NaiveDateTime, &str)> {
     Some((dt, message))
 }
 
+/// Parse the date of a log timestamp: `M/D/YY` (2000-based, as the Mac client writes it),
+/// `M/D/YYYY` or `YYYY-MM-DD`. Returns (year, month, day).
+fn parse_date(date_part: &str) -> Option<(i32, u32, u32)> {
+    if let Some((year, rest)) = date_part.split_once('-') {
+        let (month, day) = rest.split_once('-')?;
+        if year.len() != 4 {
+            return None;
+        }
+        return Some((year.parse().ok()?, month.parse().ok()?, day.parse().ok()?));
+    }
+    let mut date_parts = date_part.split('/');
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+    let year_part = date_parts.next()?;
+    if date_parts.next().is_some() {
+        return None; // Extra slash
+    }
+    let year: i32 = year_part.parse().ok()?;
+    let year = match year_part.len() {
+        // Convert 2-digit year: 00-99 → 2000-2099
+        1 | 2 => 2000 + year,
+        4 => year,
+        _ => return None,
+    };
+    Some((year, month, day))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,6 +239,43 @@ mod tests {
         assert_eq!(result, "2001-04-18 21:21:44");
     }
 
+    #[test]
+    fn test_filename_date_windows_layouts() {
+        assert_eq!(parse_filename_date("CL_Log_2024-03-01_21-21-44.txt").as_deref(), Some("2024-03-01 21:21:44"));
+        assert_eq!(parse_filename_date("CL_Log_20240301_212144.txt").as_deref(), Some("2024-03-01 21:21:44"));
+        assert_eq!(parse_filename_date("cl_log_2024-03-01_21.21.44.TXT").as_deref(), Some("2024-03-01 21:21:44"));
+        // A Windows copy suffix doesn't hide the date
+        assert_eq!(parse_filename_date("CL Log 2024-03-01 21.21.44 (2).txt").as_deref(), Some("2024-03-01 21:21:44"));
+        assert!(parse_filename_date("CL_Log_2024-13-01_21-21-44.txt").is_none());
+        assert!(parse_filename_date("Notes 2024-03-01 21.21.44.txt").is_none());
+        assert!(is_log_filename("CL_Log_20240301_212144.txt"));
+        assert!(is_log_filename("CL Log 2001:04:18 21.21.44"));
+        assert!(!is_log_filename("CL_Movies"));
+    }
+
+    #[test]
+    fn test_windows_timestamp_variants() {
+        let (dt, msg) = parse_timestamp("3/1/2024 9:21:44 PM You slaughtered a Rat.").unwrap();
+        assert_eq!((dt.year(), dt.hour()), (2024, 21));
+        assert_eq!(msg, "You slaughtered a Rat.");
+
+        let (dt, msg) = parse_timestamp("3/1/2024 12:05:00AM Welcome to Clan Lord, Fen!").unwrap();
+        assert_eq!(dt.hour(), 0);
+        assert_eq!(msg, "Welcome to Clan Lord, Fen!");
+
+        let (dt, msg) = parse_timestamp("2024-03-01 21:21:44 You slaughtered a Rat.").unwrap();
+        assert_eq!((dt.month(), dt.day(), dt.hour()), (3, 1, 21));
+        assert_eq!(msg, "You slaughtered a Rat.");
+
+        // A 24-hour afternoon time never takes a following word as AM/PM
+        let (dt, msg) = parse_timestamp("3/1/24 13:00:00 PM me later").unwrap();
+        assert_eq!(dt.hour(), 13);
+        assert_eq!(msg, "PM me later");
+
+        assert!(parse_timestamp("3/1/24 9:21:44x Hello there").is_none());
+        assert!(parse_timestamp("Well-known words here").is_none());
+    }
+
     #[test]
     fn test_no_timestamp() {
         assert!(parse_timestamp("This has no timestamp").is_none());
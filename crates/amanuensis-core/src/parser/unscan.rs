@@ -0,0 +1,183 @@
+//! Taking a scanned file back out of the database.
+//!
+//! Most derived tables (trainers, rank history, lastys, pets, accuracy, sessions, …) are not
+//! plain sums a file's share can be subtracted from, so unscanning replays instead: the
+//! log-derived data is reset and every other scanned file is scanned again from its source
+//! path, in one transaction. The result is what a scan without the file would have produced.
+//! Every other file has to still be readable; otherwise nothing is changed and the user is
+//! pointed at Rescan Logs.
+
+use std::path::{Path, PathBuf};
+
+use crate::db::queries::UnscanSummary;
+use crate::error::{AmanuensisError, Result};
+use crate::parser::timestamp::parse_filename_date;
+
+use super::{LogParser, ScanResult};
+
+impl LogParser {
+    /// Forget a scanned file (any form of its path) and everything derived from it. The
+    /// next scan sees the file as new. Opt-in tables (chat, witnessed falls, unknown lines)
+    /// are rebuilt too when they already hold rows; the search index follows the
+    /// `index_log_lines` setting.
+    pub fn unscan_file<F>(&self, file_path: &str, progress: F) -> Result<UnscanSummary>
+    where
+        F: Fn(usize, usize, &str),
+    {
+        let key = self.db.log_path_key(file_path)?;
+        if !self.db.is_log_scanned(&key)? {
+            return Err(AmanuensisError::Data(format!("{} has not been scanned", file_path)));
+        }
+
+        let mut files: Vec<PathBuf> = Vec::new();
+        for (_, path, source, _) in self.db.scanned_log_files()? {
+            if path == key {
+                continue;
+            }
+            let source = PathBuf::from(source);
+            if !source.is_file() {
+                return Err(AmanuensisError::Data(format!(
+                    "{} can no longer be read, so the other files can't be replayed without {}; use Rescan Logs",
+                    source.display(),
+                    file_path
+                )));
+            }
+            files.push(source);
+        }
+        // The order a folder scan takes: character folder, then the date in the file name.
+        files.sort_by_cached_key(|path| {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            (path.parent().map(Path::to_path_buf), parse_filename_date(&name), path.clone())
+        });
+
+        let mut summary = self.db.file_row_counts(&key)?;
+        summary.files_replayed = files.len();
+        let index_lines = self.db.indexing_enabled()?;
+        let tracking = [
+            (&self.track_witnessed_falls, "falls_witnessed"),
+            (&self.track_unknown_lines, "unknown_lines"),
+            (&self.track_chat, "chat"),
+        ];
+        let saved: Vec<bool> = tracking.iter().map(|(flag, _)| flag.get()).collect();
+        for (flag, table) in &tracking {
+            flag.set(flag.get() || self.db.has_rows(table)?);
+        }
+
+        // Replayed events aren't new: nothing is announced for them.
+        let hooks = self.hooks.borrow_mut().take();
+        let notifier = self.notifier.borrow_mut().take();
+
+        let _ = self.db.clear_process_logs();
+        let replayed = self.begin_scan().and_then(|()| self.db.begin_transaction()).and_then(|()| {
+            let mut result = ScanResult::default();
+            let replay = (|| -> Result<()> {
+                self.db.reset_log_data()?;
+                self.scan_files_with_progress_inner(&files, false, index_lines, &progress, &mut result)?;
+                if let Some(failure) = result.error_details.first() {
+                    return Err(AmanuensisError::Data(format!(
+                        "{} could not be replayed ({}); nothing was changed",
+                        failure.file_path, failure.message
+                    )));
+                }
+                self.finalize_characters()
+            })();
+            self.end_scan(replay)
+        });
+        *self.hooks.borrow_mut() = hooks;
+        *self.notifier.borrow_mut() = notifier;
+        for ((flag, _), enabled) in tracking.iter().zip(saved) {
+            flag.set(enabled);
+        }
+        replayed.map(|()| summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::db::Database;
+    use crate::parser::LogParser;
+
+    fn write_log(dir: &std::path::Path, name: &str, content: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn unscan_then_update_matches_a_single_scan() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Gandor");
+        fs::create_dir(&char_dir).unwrap();
+        write_log(
+            &char_dir,
+            "CL Log 2024-01-01 13.00.00.txt",
+            "1/1/24 1:00:00p Welcome to Clan Lord, Gandor!\n\
+             1/1/24 1:01:00p You slaughtered a Rat.\n\
+             1/1/24 1:02:00p \u{a5}Your combat ability improves.\n",
+        );
+        let friend = write_log(
+            &char_dir,
+            "CL Log 2024-01-02 13.00.00.txt",
+            "1/2/24 1:00:00p Welcome to Clan Lord, Gandor!\n\
+             1/2/24 1:01:00p You slaughtered a Rat.\n\
+             1/2/24 1:02:00p \u{a5}Your combat ability improves.\n\
+             1/2/24 1:03:00p \u{a5}Your combat ability improves.\n",
+        );
+        let noop = |_: usize, _: usize, _: &str| {};
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+
+        let snapshot = |p: &LogParser| {
+            let c = p.db().get_character("Gandor").unwrap().unwrap();
+            let id = c.id.unwrap();
+            let trainers: Vec<(String, i64)> =
+                p.db().get_trainers(id).unwrap().into_iter().map(|t| (t.trainer_name, t.ranks)).collect();
+            let kills: Vec<(i64, String)> =
+                p.db().get_kills(id).unwrap().into_iter().map(|k| (k.total_solo(), k.creature_name)).collect();
+            (c.logins, trainers, kills)
+        };
+        let before = snapshot(&parser);
+
+        let summary = parser.unscan_file(&friend.to_string_lossy(), noop).unwrap();
+        assert_eq!((summary.files_replayed, summary.sessions), (1, 1));
+        assert!(!parser.db().is_log_scanned(&friend.to_string_lossy()).unwrap());
+        let without = snapshot(&parser);
+        assert_eq!(without.0, 1);
+        assert_eq!(without.1, vec![("Bangus Anmash".to_string(), 1)]);
+
+        // Scanning it again lands exactly where the first scan did: nothing double-counted.
+        parser.scan_folder(tmp.path(), false).unwrap();
+        assert_eq!(snapshot(&parser), before);
+    }
+
+    #[test]
+    fn unscan_refuses_when_another_file_is_gone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Gandor");
+        fs::create_dir(&char_dir).unwrap();
+        let kept = write_log(
+            &char_dir,
+            "CL Log 2024-01-01 13.00.00.txt",
+            "1/1/24 1:00:00p Welcome to Clan Lord, Gandor!\n1/1/24 1:01:00p You slaughtered a Rat.\n",
+        );
+        let friend = write_log(
+            &char_dir,
+            "CL Log 2024-01-02 13.00.00.txt",
+            "1/2/24 1:00:00p Welcome to Clan Lord, Gandor!\n1/2/24 1:01:00p You slaughtered a Rat.\n",
+        );
+        let noop = |_: usize, _: usize, _: &str| {};
+        let parser = LogParser::new(Database::open_in_memory().unwrap()).unwrap();
+        parser.scan_folder(tmp.path(), false).unwrap();
+        fs::remove_file(&kept).unwrap();
+
+        let err = parser.unscan_file(&friend.to_string_lossy(), noop).unwrap_err();
+        assert!(err.to_string().contains("Rescan Logs"));
+        assert!(parser.db().is_log_scanned(&friend.to_string_lossy()).unwrap());
+        let id = parser.db().get_character("Gandor").unwrap().unwrap().id.unwrap();
+        assert_eq!(parser.db().get_kills(id).unwrap()[0].total_solo(), 2);
+
+        assert_eq!(parser.unscan_file("elsewhere.txt", noop).unwrap_err().kind(), "data");
+    }
+}
@@ -0,0 +1,222 @@
+//! Hunting-tier progression.
+//!
+//! A creature's bestiary value is used as a difficulty proxy. A creature counts as *reliably
+//! soloed* once the character has at least `min_solo_kills` solo kills of it and has been killed
+//! by it at most `max_killed_by_ratio` times per solo kill. The character's hunting tier is the
+//! highest value among reliably-soloed creatures; the progression timeline records each day the
+//! tier rose, using the `kill_hourly` buckets to find when each creature crossed the kill
+//! threshold.
+//!
+//! Killed-by counts are only stored as lifetime totals, so the ratio test uses the lifetime
+//! ratio for every point on the timeline.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::Kill;
+
+/// Thresholds for calling a creature reliably soloed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressionCriteria {
+    pub min_solo_kills: i64,
+    pub max_killed_by_ratio: f64,
+}
+
+impl Default for ProgressionCriteria {
+    fn default() -> Self {
+        Self { min_solo_kills: 50, max_killed_by_ratio: 0.1 }
+    }
+}
+
+/// A creature the character reliably solos.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TierCreature {
+    pub creature_name: String,
+    pub creature_value: i32,
+    pub solo_kills: i64,
+    pub killed_by: i64,
+    /// Day ("YYYY-MM-DD") of the kill that crossed `min_solo_kills`, if the hourly
+    /// buckets cover it (databases scanned before `kill_hourly` existed have no history).
+    pub reached: Option<String>,
+}
+
+/// A day the hunting tier rose.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TierMilestone {
+    pub day: String,
+    pub creature_name: String,
+    pub creature_value: i32,
+}
+
+/// Current hunting tier plus its history.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct HuntingProgression {
+    /// The highest-value reliably-soloed creature.
+    pub current: Option<TierCreature>,
+    /// Every reliably-soloed creature, highest value first.
+    pub reliable: Vec<TierCreature>,
+    /// Tier increases in chronological order.
+    pub milestones: Vec<TierMilestone>,
+}
+
+/// Compute progression from kill totals and per-day solo counts
+/// (`(creature_name, "YYYY-MM-DD", solo_kills)`, any order).
+pub fn compute_progression(
+    kills: &[Kill],
+    solo_by_day: &[(String, String, i64)],
+    criteria: &ProgressionCriteria,
+) -> HuntingProgression {
+    let mut days_by_creature: HashMap<&str, BTreeMap<&str, i64>> = HashMap::new();
+    for (creature, day, n) in solo_by_day {
+        *days_by_creature.entry(creature).or_default().entry(day).or_default() += n;
+    }
+
+    let mut reliable: Vec<TierCreature> = kills
+        .iter()
+        .filter(|k| k.creature_value > 0)
+        .filter_map(|k| {
+            let solo = k.total_solo();
+            if solo < criteria.min_solo_kills.max(1)
+                || k.killed_by_count as f64 / solo as f64 > criteria.max_killed_by_ratio
+            {
+                return None;
+            }
+            let reached = days_by_creature.get(k.creature_name.as_str()).and_then(|days| {
+                let mut running = 0;
+                days.iter().find_map(|(day, n)| {
+                    running += n;
+                    (running >= criteria.min_solo_kills).then(|| day.to_string())
+                })
+            });
+            Some(TierCreature {
+                creature_name: k.creature_name.clone(),
+                creature_value: k.creature_value,
+                solo_kills: solo,
+                killed_by: k.killed_by_count,
+                reached,
+            })
+        })
+        .collect();
+    reliable.sort_by(|a, b| {
+        b.creature_value
+            .cmp(&a.creature_value)
+            .then(a.creature_name.cmp(&b.creature_name))
+    });
+
+    let mut dated: Vec<&TierCreature> = reliable.iter().filter(|c| c.reached.is_some()).collect();
+    dated.sort_by(|a, b| {
+        a.reached
+            .cmp(&b.reached)
+            .then(b.creature_value.cmp(&a.creature_value))
+    });
+    let mut milestones: Vec<TierMilestone> = Vec::new();
+    for c in dated {
+        if milestones.last().is_some_and(|m| c.creature_value <= m.creature_value) {
+            continue;
+        }
+        milestones.push(TierMilestone {
+            day: c.reached.clone().unwrap_or_default(),
+            creature_name: c.creature_name.clone(),
+            creature_value: c.creature_value,
+        });
+    }
+
+    HuntingProgression {
+        current: reliable.first().cloned(),
+        reliable,
+        milestones,
+    }
+}
+
+impl Database {
+    /// Hunting progression for a (possibly merged) character.
+    pub fn hunting_progression_merged(
+        &self,
+        char_id: i64,
+        criteria: &ProgressionCriteria,
+    ) -> Result<HuntingProgression> {
+        let kills = self.get_kills_merged(char_id)?;
+        let ids = self.char_ids_for_merged(char_id)?;
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT creature_name, substr(hour, 1, 10),
+                    SUM(killed_count + slaughtered_count + vanquished_count + dispatched_count)
+             FROM kill_hourly
+             WHERE character_id IN ({placeholders})
+             GROUP BY creature_name, substr(hour, 1, 10)",
+        );
+        let mut stmt = self.conn().prepare(&sql)?;
+        let solo_by_day: Vec<(String, String, i64)> = stmt
+            .query_map(rusqlite::params_from_iter(ids.iter()), |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(compute_progression(&kills, &solo_by_day, criteria))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kill(name: &str, value: i32, solo: i64, killed_by: i64) -> Kill {
+        let mut k = Kill::new(1, name.to_string(), value);
+        k.killed_count = solo;
+        k.killed_by_count = killed_by;
+        k
+    }
+
+    fn day(creature: &str, day: &str, n: i64) -> (String, String, i64) {
+        (creature.to_string(), day.to_string(), n)
+    }
+
+    #[test]
+    fn tier_requires_kills_and_low_killed_by_ratio() {
+        let kills = vec![
+            kill("Rat", 2, 200, 0),
+            kill("Orga", 40, 60, 2),
+            kill("Large Vermine", 60, 49, 0), // too few kills
+            kill("Maha Ruknee", 300, 80, 30), // dies too often
+        ];
+        let p = compute_progression(&kills, &[], &ProgressionCriteria::default());
+        let names: Vec<&str> = p.reliable.iter().map(|c| c.creature_name.as_str()).collect();
+        assert_eq!(names, vec!["Orga", "Rat"]);
+        assert_eq!(p.current.unwrap().creature_value, 40);
+        assert!(p.milestones.is_empty(), "no hourly history, no timeline");
+    }
+
+    #[test]
+    fn milestones_follow_threshold_crossings() {
+        let kills = vec![kill("Rat", 2, 60, 0), kill("Orga", 40, 50, 0), kill("Vermine", 5, 55, 0)];
+        let history = vec![
+            day("Rat", "2024-01-01", 30),
+            day("Rat", "2024-01-03", 30),
+            day("Vermine", "2024-01-02", 55),
+            day("Orga", "2024-02-01", 20),
+            day("Orga", "2024-03-01", 30),
+        ];
+        let p = compute_progression(&kills, &history, &ProgressionCriteria::default());
+        let steps: Vec<(&str, &str)> =
+            p.milestones.iter().map(|m| (m.day.as_str(), m.creature_name.as_str())).collect();
+        // Rat crosses 50 on 01-03, after Vermine (higher value) already did — not a tier rise.
+        assert_eq!(steps, vec![("2024-01-02", "Vermine"), ("2024-03-01", "Orga")]);
+    }
+
+    #[test]
+    fn progression_from_database() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Tester").unwrap();
+        for i in 0..50 {
+            let ts = format!("2024-01-0{} 10:00:00", 1 + i / 25);
+            db.upsert_kill(c, "Orga", "killed_count", 40, &ts).unwrap();
+            db.upsert_kill_hourly(c, "Orga", "killed_count", &ts[..13]).unwrap();
+        }
+        let p = db.hunting_progression_merged(c, &ProgressionCriteria::default()).unwrap();
+        assert_eq!(p.milestones.len(), 1);
+        assert_eq!(p.milestones[0].day, "2024-01-02");
+    }
+}
@@ -0,0 +1,242 @@
+//! Reconcile log-derived trainer ranks against a pasted rank listing.
+//!
+//! Logs rarely go back to a character's first rank, so players calibrate by pasting the
+//! ranks the game shows them and topping up the difference with a manual modifier.
+//! `parse_rank_listing` reads one trainer per line with its rank count in any of the usual
+//! shapes (`Histia: 150`, `Histia 150`, `150 Histia`, `Histia - 150 ranks`, tab separated),
+//! matching names case-insensitively against the known trainers. `reconcile` diffs the
+//! listing against the database and proposes a new `modified_ranks` wherever the database
+//! comes up short. Where it counts more than the listing nothing is proposed — that usually
+//! means a stale listing or a misattributed trainer message, which a person should check.
+//!
+//! `parse_ranks_csv` is the strict counterpart for `set-ranks-bulk`: `trainer,modified_ranks`
+//! rows where any bad row rejects the whole file.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::error::{AmanuensisError, Result};
+use crate::models::{RankMode, Trainer};
+
+/// One trainer line from a listing.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ListedRanks {
+    pub trainer: String,
+    pub ranks: i64,
+}
+
+/// A parsed listing: recognized lines, and the ones that were not understood.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RankListing {
+    pub entries: Vec<ListedRanks>,
+    pub unparsed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconcileRow {
+    pub trainer: String,
+    pub listed: i64,
+    /// Effective ranks in the database (merged across linked characters).
+    pub database: i64,
+    /// `listed - database`.
+    pub difference: i64,
+    pub rank_mode: String,
+    /// The character's own manual ranks for the trainer.
+    pub modified_ranks: i64,
+    /// New `modified_ranks` that makes the database match the listing, when it is short.
+    pub proposed_modified_ranks: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Reconciliation {
+    /// Listed trainers, largest disagreement first.
+    pub rows: Vec<ReconcileRow>,
+    /// Trainers with ranks in the database that the listing doesn't mention.
+    pub not_listed: Vec<String>,
+    pub unparsed: Vec<String>,
+}
+
+impl Reconciliation {
+    pub fn proposals(&self) -> impl Iterator<Item = &ReconcileRow> {
+        self.rows.iter().filter(|r| r.proposed_modified_ranks.is_some())
+    }
+}
+
+/// Parse a pasted listing. Blank lines and `#` comments are skipped; a line needs exactly
+/// one number and a known trainer name, anything else (including a trainer listed twice)
+/// goes to `unparsed`.
+pub fn parse_rank_listing<S: AsRef<str>>(text: &str, known_trainers: &[S]) -> RankListing {
+    let known: HashMap<String, &str> =
+        known_trainers.iter().map(|t| (t.as_ref().to_lowercase(), t.as_ref())).collect();
+    let mut listing = RankListing::default();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let mut numbers = Vec::new();
+        let mut words = Vec::new();
+        for token in trimmed.split(|c: char| c.is_whitespace() || matches!(c, ':' | '=' | '|' | ',')) {
+            let token = token.trim_matches(|c: char| matches!(c, '-' | '.' | '(' | ')'));
+            if token.is_empty() {
+                continue;
+            }
+            match token.parse::<i64>() {
+                Ok(n) => numbers.push(n),
+                Err(_) if token.eq_ignore_ascii_case("ranks") || token.eq_ignore_ascii_case("rank") => {}
+                Err(_) => words.push(token),
+            }
+        }
+        let name = words.join(" ").to_lowercase();
+        match (numbers.as_slice(), known.get(&name)) {
+            ([ranks], Some(trainer)) if !listing.entries.iter().any(|e| e.trainer == *trainer) => {
+                listing.entries.push(ListedRanks { trainer: trainer.to_string(), ranks: *ranks });
+            }
+            _ => listing.unparsed.push(trimmed.to_string()),
+        }
+    }
+    listing
+}
+
+/// Parse `trainer,modified_ranks` CSV rows (an optional `trainer,...` header, blank lines
+/// skipped, fields may be quoted). Every row must name a known trainer once and give an
+/// integer; all problems are reported together, with line numbers, as one error.
+pub fn parse_ranks_csv<S: AsRef<str>>(text: &str, known_trainers: &[S]) -> Result<Vec<ListedRanks>> {
+    let known: HashMap<String, &str> =
+        known_trainers.iter().map(|t| (t.as_ref().to_lowercase(), t.as_ref())).collect();
+    let mut rows: Vec<ListedRanks> = Vec::new();
+    let mut problems = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim().trim_matches('"').trim()).collect();
+        if fields.iter().all(|f| f.is_empty()) || (i == 0 && fields[0].eq_ignore_ascii_case("trainer")) {
+            continue;
+        }
+        let line_no = i + 1;
+        let [name, ranks] = fields.as_slice() else {
+            problems.push(format!("line {}: expected trainer,modified_ranks", line_no));
+            continue;
+        };
+        let Some(trainer) = known.get(&name.to_lowercase()) else {
+            problems.push(format!("line {}: unknown trainer '{}'", line_no, name));
+            continue;
+        };
+        let Ok(ranks) = ranks.parse::<i64>() else {
+            problems.push(format!("line {}: '{}' is not a whole number of ranks", line_no, ranks));
+            continue;
+        };
+        if rows.iter().any(|r| r.trainer == *trainer) {
+            problems.push(format!("line {}: {} is listed more than once", line_no, trainer));
+            continue;
+        }
+        rows.push(ListedRanks { trainer: trainer.to_string(), ranks });
+    }
+    if !problems.is_empty() {
+        return Err(AmanuensisError::Data(problems.join("; ")));
+    }
+    Ok(rows)
+}
+
+/// Diff `listing` against a character's trainers. `merged` are the trainer totals shown to
+/// the user (`get_trainers_merged`); `own` are the character's own rows, which hold the
+/// manual ranks a proposal replaces.
+pub fn reconcile(merged: &[Trainer], own: &[Trainer], listing: &RankListing) -> Reconciliation {
+    let effective: HashMap<&str, i64> =
+        merged.iter().map(|t| (t.trainer_name.as_str(), t.effective_ranks())).collect();
+    let own: HashMap<&str, &Trainer> = own.iter().map(|t| (t.trainer_name.as_str(), t)).collect();
+
+    let mut rows: Vec<ReconcileRow> = listing
+        .entries
+        .iter()
+        .map(|entry| {
+            let database = effective.get(entry.trainer.as_str()).copied().unwrap_or(0);
+            let difference = entry.ranks - database;
+            let (rank_mode, modified_ranks) = match own.get(entry.trainer.as_str()) {
+                Some(t) => (t.rank_mode.clone(), t.modified_ranks),
+                None => (RankMode::Modifier.as_str().to_string(), 0),
+            };
+            ReconcileRow {
+                trainer: entry.trainer.clone(),
+                listed: entry.ranks,
+                database,
+                difference,
+                rank_mode,
+                modified_ranks,
+                proposed_modified_ranks: (difference > 0).then_some(modified_ranks + difference),
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| b.difference.abs().cmp(&a.difference.abs()).then(a.trainer.cmp(&b.trainer)));
+
+    let mut not_listed: Vec<String> = merged
+        .iter()
+        .filter(|t| t.effective_ranks() > 0 && !listing.entries.iter().any(|e| e.trainer == t.trainer_name))
+        .map(|t| t.trainer_name.clone())
+        .collect();
+    not_listed.sort();
+    Reconciliation { rows, not_listed, unparsed: listing.unparsed.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KNOWN: [&str; 4] = ["Histia", "Atkus", "Bangus Anmash", "Evus"];
+
+    #[test]
+    fn listing_shapes_and_rejects() {
+        let text = "# my ranks\nHistia: 150\n  atkus\t42 ranks\n12 Bangus Anmash\nEvus - 7\n\nSnorg 5\nHistia 3\nAtkus 4 5\n";
+        let listing = parse_rank_listing(text, &KNOWN);
+        assert_eq!(
+            listing.entries,
+            vec![
+                ListedRanks { trainer: "Histia".into(), ranks: 150 },
+                ListedRanks { trainer: "Atkus".into(), ranks: 42 },
+                ListedRanks { trainer: "Bangus Anmash".into(), ranks: 12 },
+                ListedRanks { trainer: "Evus".into(), ranks: 7 },
+            ]
+        );
+        assert_eq!(listing.unparsed, ["Snorg 5", "Histia 3", "Atkus 4 5"]);
+    }
+
+    #[test]
+    fn ranks_csv_is_all_or_nothing() {
+        let rows = parse_ranks_csv("trainer,modified_ranks\nHistia,40\n\n\"Bangus Anmash\", -3\n", &KNOWN).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ListedRanks { trainer: "Histia".into(), ranks: 40 },
+                ListedRanks { trainer: "Bangus Anmash".into(), ranks: -3 },
+            ]
+        );
+        let err = parse_ranks_csv("Histia,40\nSnorg,5\nAtkus,lots\nhistia,1\nEvus", &KNOWN).unwrap_err().to_string();
+        assert!(err.contains("line 2: unknown trainer 'Snorg'"), "{err}");
+        assert!(err.contains("line 3: 'lots'"), "{err}");
+        assert!(err.contains("line 4: Histia is listed more than once"), "{err}");
+        assert!(err.contains("line 5: expected"), "{err}");
+    }
+
+    #[test]
+    fn proposes_modifiers_only_where_database_is_short() {
+        let mut histia = Trainer::new(1, "Histia".into());
+        histia.ranks = 100;
+        histia.modified_ranks = 10;
+        let mut atkus = Trainer::new(1, "Atkus".into());
+        atkus.ranks = 50;
+        let mut darkus = Trainer::new(1, "Darkus".into());
+        darkus.ranks = 3;
+        let trainers = vec![histia, atkus, darkus];
+        let listing = parse_rank_listing("Histia 150\nAtkus 45\nEvus 5", &KNOWN);
+
+        let result = reconcile(&trainers, &trainers, &listing);
+        let row = |name: &str| result.rows.iter().find(|r| r.trainer == name).unwrap();
+        assert_eq!(row("Histia").difference, 40);
+        assert_eq!(row("Histia").proposed_modified_ranks, Some(50));
+        assert_eq!(row("Atkus").difference, -5);
+        assert_eq!(row("Atkus").proposed_modified_ranks, None);
+        assert_eq!(row("Evus").proposed_modified_ranks, Some(5));
+        assert_eq!(result.rows[0].trainer, "Histia");
+        assert_eq!(result.proposals().count(), 2);
+        assert_eq!(result.not_listed, ["Darkus"]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Roster report for unattended publishing.
+//!
+//! Every listed character's profile widget (see `widget`), rendered as a standalone HTML
+//! page and/or a JSON document and written into a folder that a clan-website uploader
+//! watches. Files are written to a temporary name and renamed into place, so the uploader
+//! never picks up a half-written report.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::{AmanuensisError, Result};
+use crate::widget::Widget;
+
+/// File name of the HTML roster report.
+pub const REPORT_HTML_FILE: &str = "amanuensis-report.html";
+/// File name of the JSON roster export.
+pub const REPORT_JSON_FILE: &str = "amanuensis-report.json";
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RosterReport {
+    /// UTC, "YYYY-MM-DD HH:MM:SS".
+    pub generated_at: String,
+    /// One widget per listed character, alphabetical.
+    pub characters: Vec<Widget>,
+}
+
+impl RosterReport {
+    /// The roster as a self-contained HTML page.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "<!DOCTYPE html>");
+        let _ = writeln!(out, "<html lang=\"en\">");
+        let _ = writeln!(out, "<head>");
+        let _ = writeln!(out, "<meta charset=\"utf-8\">");
+        let _ = writeln!(out, "<title>Amanuensis roster</title>");
+        let _ = writeln!(
+            out,
+            "<style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse}}\
+             th,td{{border:1px solid #ccc;padding:4px 8px;text-align:left}}td.n{{text-align:right}}\
+             img.portrait{{width:32px;height:32px;object-fit:cover;vertical-align:middle;margin-right:6px}}</style>"
+        );
+        let _ = writeln!(out, "</head>");
+        let _ = writeln!(out, "<body>");
+        let _ = writeln!(out, "<h1>Roster</h1>");
+        let _ = writeln!(out, "<p>Generated {} UTC</p>", escape_html(&self.generated_at));
+        let _ = writeln!(out, "<table>");
+        let _ = writeln!(
+            out,
+            "<tr><th>Name</th><th>Profession</th><th>Coin level</th><th>Ranks</th><th>Deaths</th><th>Top kills</th></tr>"
+        );
+        for w in &self.characters {
+            let top = w
+                .top_kills
+                .iter()
+                .map(|k| format!("{} ({})", escape_html(&k.creature), k.count))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let portrait = w
+                .portrait
+                .as_ref()
+                .map(|url| format!("<img class=\"portrait\" src=\"{}\" alt=\"\">", escape_html(url)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "<tr><td>{}{}</td><td>{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td><td>{}</td></tr>",
+                portrait,
+                escape_html(&w.name),
+                escape_html(&w.profession),
+                w.coin_level,
+                w.total_ranks,
+                w.deaths,
+                top
+            );
+        }
+        let _ = writeln!(out, "</table>");
+        let _ = writeln!(out, "</body>");
+        let _ = writeln!(out, "</html>");
+        out
+    }
+}
+
+impl Database {
+    /// Build the roster from every listed (non-merged, scanned) character.
+    pub fn roster_report(&self) -> Result<RosterReport> {
+        let mut characters = Vec::new();
+        for c in self.list_characters()? {
+            if let Some(widget) = self.widget_merged(c.id.unwrap_or_default())? {
+                characters.push(widget);
+            }
+        }
+        Ok(RosterReport { generated_at: crate::date::now(), characters })
+    }
+
+    /// Write the roster into `folder` as HTML and/or JSON, returning the files written.
+    pub fn write_roster_report(&self, folder: &Path, html: bool, json: bool) -> Result<Vec<PathBuf>> {
+        if !folder.is_dir() {
+            return Err(AmanuensisError::Data(format!("Not a directory: {}", folder.display())));
+        }
+        let report = self.roster_report()?;
+        let mut written = Vec::new();
+        if html {
+            written.push(write_replacing(&folder.join(REPORT_HTML_FILE), &report.to_html())?);
+        }
+        if json {
+            written.push(write_replacing(&folder.join(REPORT_JSON_FILE), &serde_json::to_string_pretty(&report)?)?);
+        }
+        Ok(written)
+    }
+}
+
+/// Write `contents` beside `path` and rename it over `path`.
+fn write_replacing(path: &Path, contents: &str) -> Result<PathBuf> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(path.to_path_buf())
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roster_written_as_html_and_json() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.increment_character_field(c, "logins", 1).unwrap();
+        db.upsert_kill(c, "Rat <small>", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+        // Never logged in: not listed, so not published.
+        db.get_or_create_character("Ghost").unwrap();
+        let alt = db.get_or_create_character("Alt").unwrap();
+        db.increment_character_field(alt, "logins", 1).unwrap();
+        db.set_character_portrait(alt, &[0xFF, 0xD8, 0xFF], None).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let written = db.write_roster_report(dir.path(), true, true).unwrap();
+        assert_eq!(written, vec![dir.path().join(REPORT_HTML_FILE), dir.path().join(REPORT_JSON_FILE)]);
+
+        let html = std::fs::read_to_string(&written[0]).unwrap();
+        assert!(html.contains("<td>Gandor</td>"));
+        assert!(html.contains("Rat &lt;small&gt; (1)"));
+        assert!(html.contains("<td><img class=\"portrait\" src=\"data:image/jpeg;base64,/9j/\" alt=\"\">Alt</td>"));
+        assert!(!html.contains("Ghost"));
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&written[1]).unwrap()).unwrap();
+        assert_eq!(json["characters"].as_array().unwrap().len(), 2);
+        assert_eq!(json["characters"][1]["name"], "Gandor");
+        assert_eq!(json["characters"][0]["portrait"], "data:image/jpeg;base64,/9j/");
+        assert!(!dir.path().join("amanuensis-report.tmp").exists());
+
+        assert!(db.write_roster_report(&dir.path().join("missing"), true, false).is_err());
+    }
+}
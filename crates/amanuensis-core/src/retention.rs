@@ -0,0 +1,188 @@
+//! Retention policy for the search index.
+//!
+//! Decade-old chat rarely gets searched but dominates the `log_lines` index. A per-database
+//! policy (stored in `app_settings`) can keep only lines from the last N years, and/or only
+//! lines the classifier recognizes as events (kills, ranks, loot, thoughts, ...), dropping
+//! speech, emotes and other unclassified text. The scanner applies the policy as it indexes;
+//! `prune_log_lines` applies it to lines indexed before the policy was set.
+
+use chrono::{Datelike, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::data::TrainerDb;
+use crate::db::queries::{SETTING_INDEX_EVENTS_ONLY, SETTING_INDEX_RETENTION_YEARS};
+use crate::db::Database;
+use crate::error::Result;
+use crate::parser::events::LogEvent;
+use crate::parser::line_classifier::classify_line;
+use crate::parser::timestamp::parse_timestamp;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Keep lines from the last this many years; None keeps every year.
+    pub max_age_years: Option<u32>,
+    /// Keep only lines that classify as an event.
+    pub events_only: bool,
+}
+
+impl RetentionPolicy {
+    /// Whether the policy keeps everything.
+    pub fn keeps_all(&self) -> bool {
+        self.max_age_years.is_none() && !self.events_only
+    }
+
+    /// Storage date before which lines are dropped, as of `now`.
+    pub fn cutoff(&self, now: NaiveDateTime) -> Option<String> {
+        let years = self.max_age_years? as i32;
+        // Feb 29 of a leap year falls back to Feb 28.
+        let then = now
+            .with_year(now.year() - years)
+            .or_else(|| (now - chrono::Duration::days(1)).with_year(now.year() - years))?;
+        Some(crate::date::to_storage(&then))
+    }
+
+    /// Whether to index a line dated `date` (storage form; empty when undated, which is kept)
+    /// that classified as `event`. `cutoff` comes from [`RetentionPolicy::cutoff`].
+    pub fn keeps(&self, cutoff: Option<&str>, date: &str, event: &LogEvent) -> bool {
+        if self.events_only && matches!(event, LogEvent::Ignored) {
+            return false;
+        }
+        cutoff.is_none_or(|c| date.is_empty() || date >= c)
+    }
+}
+
+impl Database {
+    /// The search index retention policy; keeps everything unless set.
+    pub fn retention_policy(&self) -> Result<RetentionPolicy> {
+        Ok(RetentionPolicy {
+            max_age_years: self
+                .get_setting(SETTING_INDEX_RETENTION_YEARS)?
+                .and_then(|v| v.parse().ok()),
+            events_only: self.get_setting(SETTING_INDEX_EVENTS_ONLY)?.as_deref() == Some("true"),
+        })
+    }
+
+    /// Store the retention policy. Already-indexed lines are untouched until `prune_log_lines`.
+    pub fn set_retention_policy(&self, policy: &RetentionPolicy) -> Result<()> {
+        match policy.max_age_years {
+            Some(years) => self.set_setting(SETTING_INDEX_RETENTION_YEARS, &years.to_string())?,
+            None => self.delete_setting(SETTING_INDEX_RETENTION_YEARS)?,
+        }
+        self.set_setting(SETTING_INDEX_EVENTS_ONLY, if policy.events_only { "true" } else { "false" })
+    }
+
+    /// Delete indexed lines the stored policy no longer keeps, as of `now`, and compact the
+    /// file. Classifying lines for `events_only` needs the trainer catalog. Returns the number
+    /// of lines removed.
+    pub fn prune_log_lines(&self, trainer_db: &TrainerDb, now: NaiveDateTime) -> Result<i64> {
+        let policy = self.retention_policy()?;
+        if policy.keeps_all() {
+            return Ok(0);
+        }
+        let cutoff = policy.cutoff(now);
+
+        self.begin_transaction()?;
+        let pruned = (|| -> Result<i64> {
+            let mut removed = 0;
+            if let Some(cutoff) = &cutoff {
+                removed += self.conn().execute(
+                    "DELETE FROM log_lines WHERE timestamp <> '' AND timestamp < ?1",
+                    [cutoff],
+                )? as i64;
+            }
+            if policy.events_only {
+                let mut stmt = self.conn().prepare("SELECT rowid, content FROM log_lines")?;
+                let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+                let mut chat = Vec::new();
+                for (rowid, content) in rows.filter_map(|r| r.ok()) {
+                    let message = parse_timestamp(&content).map(|(_, m)| m).unwrap_or(&content);
+                    if matches!(classify_line(message, trainer_db), LogEvent::Ignored) {
+                        chat.push(rowid);
+                    }
+                }
+                let mut delete = self.conn().prepare("DELETE FROM log_lines WHERE rowid = ?1")?;
+                for rowid in chat {
+                    removed += delete.execute([rowid])? as i64;
+                }
+            }
+            Ok(removed)
+        })();
+        let removed = match pruned {
+            Ok(n) => {
+                self.commit_transaction()?;
+                n
+            }
+            Err(e) => {
+                let _ = self.rollback_transaction();
+                return Err(e);
+            }
+        };
+        if removed > 0 {
+            self.conn().execute_batch("VACUUM")?;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    fn at(s: &str) -> NaiveDateTime {
+        crate::date::parse(s).unwrap()
+    }
+
+    #[test]
+    fn cutoff_counts_back_whole_years() {
+        let policy = RetentionPolicy { max_age_years: Some(2), events_only: false };
+        assert_eq!(policy.cutoff(at("2024-03-01 10:00:00")).as_deref(), Some("2022-03-01 10:00:00"));
+        assert_eq!(policy.cutoff(at("2024-02-29 10:00:00")).as_deref(), Some("2022-02-28 10:00:00"));
+        assert_eq!(RetentionPolicy::default().cutoff(at("2024-03-01 10:00:00")), None);
+        assert!(policy.keeps(Some("2022-03-01 10:00:00"), "", &LogEvent::Ignored));
+        assert!(!policy.keeps(Some("2022-03-01 10:00:00"), "2021-12-31 23:59:59", &LogEvent::Ignored));
+    }
+
+    #[test]
+    fn prune_applies_stored_policy_to_existing_lines() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.insert_log_lines(&[
+            (c, "1/1/15 10:00:00a You slaughtered a Rat.", "2015-01-01 10:00:00", "old.txt"),
+            (c, "3/1/24 10:00:00a You slaughtered a Rat.", "2024-03-01 10:00:00", "new.txt"),
+            (c, "3/1/24 10:01:00a Fen says, \"hello\"", "2024-03-01 10:01:00", "new.txt"),
+        ])
+        .unwrap();
+        let trainers = TrainerDb::bundled().unwrap();
+        let now = at("2024-06-01 00:00:00");
+
+        assert_eq!(db.prune_log_lines(&trainers, now).unwrap(), 0);
+        db.set_retention_policy(&RetentionPolicy { max_age_years: Some(5), events_only: false }).unwrap();
+        assert_eq!(db.prune_log_lines(&trainers, now).unwrap(), 1);
+        db.set_retention_policy(&RetentionPolicy { max_age_years: Some(5), events_only: true }).unwrap();
+        assert_eq!(db.prune_log_lines(&trainers, now).unwrap(), 1);
+        assert_eq!(db.log_line_count().unwrap(), 1);
+        assert_eq!(db.retention_policy().unwrap(), RetentionPolicy { max_age_years: Some(5), events_only: true });
+    }
+
+    #[test]
+    fn scan_indexes_only_what_the_policy_keeps() {
+        let tmp = tempfile::tempdir().unwrap();
+        let char_dir = tmp.path().join("Fen");
+        std::fs::create_dir(&char_dir).unwrap();
+        std::fs::write(
+            char_dir.join("CL Log 2024-01-01 10.00.00.txt"),
+            "1/1/24 10:00:00a Welcome to Clan Lord, Fen!\n\
+             1/1/24 10:01:00a Fen says, \"hello\"\n\
+             1/1/24 10:02:00a You slaughtered a Rat.\n",
+        )
+        .unwrap();
+
+        let db = Database::open_in_memory().unwrap();
+        db.set_retention_policy(&RetentionPolicy { max_age_years: None, events_only: true }).unwrap();
+        let parser = LogParser::new(db).unwrap();
+        parser.scan_folder_with_progress(tmp.path(), false, true, |_, _, _| {}).unwrap();
+        // The welcome and the kill; not the speech.
+        assert_eq!(parser.db().log_line_count().unwrap(), 2);
+    }
+}
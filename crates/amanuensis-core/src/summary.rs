@@ -0,0 +1,169 @@
+//! Character summary.
+//!
+//! Everything the `summary` screen shows for one (possibly merged) character, aggregated in
+//! one place so the CLI's `summary` command and the GUI's summary view print the same numbers.
+
+use serde::Serialize;
+
+use crate::db::queries::{CircleTest, ClanSummary, StoneUsage};
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::Character;
+
+/// A creature and how many times it was killed (or killed the character).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SummaryCreature {
+    pub creature: String,
+    pub count: i64,
+}
+
+/// A player and how many times they rescued the character.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SummaryRescuer {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterSummary {
+    /// The character with merged sources' counters folded in.
+    pub character: Character,
+    /// Names of the characters merged into this one.
+    pub merged_from: Vec<String>,
+    pub clan: ClanSummary,
+    /// The highest circle passed (the latest, among equals).
+    pub current_circle: Option<CircleTest>,
+    /// Every circle test passed, oldest first.
+    pub circles: Vec<CircleTest>,
+    pub solo_kills: i64,
+    pub assisted_kills: i64,
+    pub killed_by: i64,
+    pub unique_creatures: i64,
+    /// Most kills (solo + assisted).
+    pub most_killed: Option<SummaryCreature>,
+    /// The creature that killed the character most often.
+    pub nemesis: Option<SummaryCreature>,
+    /// The player who dragged or raised the character most often.
+    pub top_rescuer: Option<SummaryRescuer>,
+    /// Trained ranks, as counted from the logs.
+    pub total_ranks: i64,
+    /// Effective ranks (respecting rank mode and apply-learning) times each trainer's
+    /// multiplier, rounded to one decimal.
+    pub effective_ranks: f64,
+    pub trainers_visited: i64,
+    /// Departs as a percentage of deaths + departs; None before the first death.
+    pub depart_rate: Option<f64>,
+    /// Chains broken as a percentage of chains used + broken; None if none were used.
+    pub chain_break_rate: Option<f64>,
+    pub lastys: i64,
+    pub lastys_active: i64,
+    pub lastys_completed: i64,
+    pub pets: i64,
+    /// Darkstone and moonstone charges, uses and expiries.
+    pub stones: Vec<StoneUsage>,
+}
+
+impl Database {
+    /// Build the summary for a (possibly merged) character. None if the character is unknown.
+    pub fn character_summary(&self, char_id: i64) -> Result<Option<CharacterSummary>> {
+        let Some(character) = self.get_character_merged(char_id)? else {
+            return Ok(None);
+        };
+        let kills = self.get_kills_merged(char_id)?;
+        let trainers = self.get_trainers_merged(char_id)?;
+        let lastys = self.get_lastys_merged(char_id)?;
+        let pets = self.get_pets_merged(char_id)?;
+
+        // Folded from +0.0: an empty f64 `sum()` is -0.0, which would print as "-0".
+        let effective_ranks = trainers
+            .iter()
+            .map(|t| t.effective_ranks() as f64 * t.effective_multiplier)
+            .fold(0.0, |acc, r| acc + r);
+        let most_killed = kills
+            .iter()
+            .filter(|k| k.total_all() > 0)
+            .max_by_key(|k| k.total_all())
+            .map(|k| SummaryCreature { creature: k.creature_name.clone(), count: k.total_all() });
+        let nemesis = kills
+            .iter()
+            .filter(|k| k.killed_by_count > 0)
+            .max_by_key(|k| k.killed_by_count)
+            .map(|k| SummaryCreature { creature: k.creature_name.clone(), count: k.killed_by_count });
+        let top_rescuer = self
+            .get_rescues_merged(char_id)?
+            .into_iter()
+            .filter(|r| r.received() > 0)
+            .max_by(|a, b| a.received().cmp(&b.received()).then_with(|| b.name.cmp(&a.name)))
+            .map(|r| SummaryRescuer { count: r.received(), name: r.name });
+        let circles = self.get_circles_merged(char_id)?;
+        let current_circle = circles.iter().max_by(|a, b| a.circle.cmp(&b.circle).then_with(|| a.date.cmp(&b.date))).cloned();
+        let percent = |part: i64, whole: i64| (whole > 0).then(|| part as f64 / whole as f64 * 100.0);
+        let lastys_completed = lastys.iter().filter(|l| l.finished).count() as i64;
+
+        Ok(Some(CharacterSummary {
+            merged_from: self.get_merge_sources(char_id)?.into_iter().map(|c| c.name).collect(),
+            clan: self.get_clan_summary_merged(char_id)?,
+            current_circle,
+            circles,
+            solo_kills: kills.iter().map(|k| k.total_solo()).sum(),
+            assisted_kills: kills.iter().map(|k| k.total_assisted()).sum(),
+            killed_by: kills.iter().map(|k| k.killed_by_count).sum(),
+            unique_creatures: kills.len() as i64,
+            most_killed,
+            nemesis,
+            top_rescuer,
+            total_ranks: trainers.iter().map(|t| t.ranks).sum(),
+            effective_ranks: (effective_ranks * 10.0).round() / 10.0,
+            trainers_visited: trainers.len() as i64,
+            depart_rate: percent(character.departs, character.deaths + character.departs),
+            chain_break_rate: percent(character.chains_broken, character.chains_used + character.chains_broken),
+            lastys: lastys.len() as i64,
+            lastys_active: lastys.len() as i64 - lastys_completed,
+            lastys_completed,
+            pets: pets.len() as i64,
+            stones: self.get_stone_usage_merged(char_id)?,
+            character,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_aggregates_merged_character() {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Gandor").unwrap();
+        let alt = db.get_or_create_character("Gandor Alt").unwrap();
+        db.increment_character_field(main, "deaths", 3).unwrap();
+        db.increment_character_field(alt, "departs", 1).unwrap();
+        db.upsert_kill(main, "Rat", "slaughtered_count", 2, "2024-01-01 10:00:00").unwrap();
+        db.upsert_kill(alt, "Rat", "assisted_kill_count", 2, "2024-01-02 10:00:00").unwrap();
+        db.upsert_kill(alt, "Orga Anger", "killed_by_count", 30, "2024-01-02 11:00:00").unwrap();
+        db.upsert_trainer_rank(main, "Histia", "2024-01-01", 1.0).unwrap();
+        db.upsert_trainer_rank(alt, "Bodrus", "2024-01-02", 1.5).unwrap();
+        db.record_rescue(main, "Bramble", "received", "drag", "2024-01-01 10:00:00").unwrap();
+        db.record_rescue(alt, "Corvid", "received", "raise", "2024-01-02 10:00:00").unwrap();
+        db.record_rescue(alt, "Corvid", "received", "drag", "2024-01-02 11:00:00").unwrap();
+        db.record_rescue(main, "Bramble", "given", "drag", "2024-01-03 10:00:00").unwrap();
+        db.record_rescue(main, "Bramble", "given", "drag", "2024-01-03 11:00:00").unwrap();
+        db.record_circle(main, "Fighter", 1, "2024-01-01 09:00:00").unwrap();
+        db.record_circle(alt, "Fighter", 2, "2024-01-05 09:00:00").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let s = db.character_summary(main).unwrap().unwrap();
+        assert_eq!(s.merged_from, vec!["Gandor Alt"]);
+        assert_eq!((s.solo_kills, s.assisted_kills, s.killed_by, s.unique_creatures), (1, 1, 1, 2));
+        assert_eq!(s.most_killed, Some(SummaryCreature { creature: "Rat".into(), count: 2 }));
+        assert_eq!(s.nemesis, Some(SummaryCreature { creature: "Orga Anger".into(), count: 1 }));
+        assert_eq!(s.circles.len(), 2);
+        assert_eq!(s.current_circle.as_ref().map(|c| (c.circle, c.date.to_string())), Some((2, "2024-01-05 09:00:00".to_string())));
+        assert_eq!(s.top_rescuer, Some(SummaryRescuer { name: "Corvid".into(), count: 2 }));
+        assert_eq!((s.total_ranks, s.effective_ranks, s.trainers_visited), (2, 2.5, 2));
+        assert_eq!(s.depart_rate, Some(25.0));
+        assert_eq!(s.chain_break_rate, None);
+
+        assert!(db.character_summary(9999).unwrap().is_none());
+    }
+}
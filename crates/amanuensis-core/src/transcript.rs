@@ -0,0 +1,105 @@
+//! Pulling the raw transcript around a moment out of an original log file.
+//!
+//! The search index holds single lines; reconstructing a conversation found through search
+//! needs the lines around the hit as the log recorded them, including the untimestamped
+//! continuation lines. The file is streamed and decoded line by line (Mac Roman and
+//! Windows-1252 logs included), keeping only the window being collected.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::encoding::LogLineReader;
+use crate::error::{AmanuensisError, Result};
+use crate::parser::timestamp::parse_timestamp;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TranscriptLine {
+    /// 1-based line number in the file.
+    pub number: usize,
+    pub text: String,
+    /// The line the transcript is centred on.
+    pub hit: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Transcript {
+    pub file_path: String,
+    pub lines: Vec<TranscriptLine>,
+}
+
+/// Parse the moment to look for: a stored date (`2024-01-01 13:01:00`, as search results
+/// show it), a bare date, or a Clan Lord timestamp (`1/1/24 1:01:00p`).
+pub fn parse_transcript_time(value: &str) -> Option<NaiveDateTime> {
+    crate::date::parse(value).or_else(|| parse_timestamp(&format!("{} ", value.trim())).map(|(dt, _)| dt))
+}
+
+/// The `lines` lines before and after the first line of `path` timestamped at or after
+/// `at`. Errors when no line is that late.
+pub fn transcript_around(path: &Path, at: NaiveDateTime, lines: usize) -> Result<Transcript> {
+    let file = File::open(path).map_err(|e| AmanuensisError::file_unreadable(path, e))?;
+    let mut before: VecDeque<TranscriptLine> = VecDeque::with_capacity(lines + 1);
+    let mut collected: Vec<TranscriptLine> = Vec::new();
+    let mut after = 0;
+    for (i, text) in LogLineReader::new(BufReader::new(file)).enumerate() {
+        let text = text?;
+        if !collected.is_empty() {
+            if after == lines {
+                break;
+            }
+            collected.push(TranscriptLine { number: i + 1, text, hit: false });
+            after += 1;
+            continue;
+        }
+        if parse_timestamp(&text).is_some_and(|(ts, _)| ts >= at) {
+            collected.extend(before.drain(..));
+            collected.push(TranscriptLine { number: i + 1, text, hit: true });
+            continue;
+        }
+        if lines > 0 {
+            if before.len() == lines {
+                before.pop_front();
+            }
+            before.push_back(TranscriptLine { number: i + 1, text, hit: false });
+        }
+    }
+    if collected.is_empty() {
+        return Err(AmanuensisError::Data(format!(
+            "No line in {} is timestamped at or after {}",
+            path.display(),
+            crate::date::to_storage(&at)
+        )));
+    }
+    Ok(Transcript { file_path: path.display().to_string(), lines: collected })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_is_centred_on_the_first_line_at_or_after_the_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CL Log 2024-01-01 13.00.00.txt");
+        // Mac Roman bytes (0x8E is é) on a CR-terminated log.
+        let mut bytes = b"1/1/24 1:00:00p Welcome to Clan Lord, Gandor!\r".to_vec();
+        bytes.extend_from_slice(b"1/1/24 1:00:30p Fen says, \"caf\x8E?\"\r(continued)\r");
+        bytes.extend_from_slice(b"1/1/24 1:01:00p Gandor says, \"yes\"\r1/1/24 1:02:00p You slaughtered a Rat.\r");
+        std::fs::write(&path, bytes).unwrap();
+
+        let at = parse_transcript_time("1/1/24 1:00:45p").unwrap();
+        let transcript = transcript_around(&path, at, 2).unwrap();
+        let numbers: Vec<usize> = transcript.lines.iter().map(|l| l.number).collect();
+        assert_eq!(numbers, [2, 3, 4, 5]);
+        assert_eq!(transcript.lines[0].text, "1/1/24 1:00:30p Fen says, \"café?\"");
+        assert!(transcript.lines[2].hit && transcript.lines.iter().filter(|l| l.hit).count() == 1);
+
+        let start = transcript_around(&path, parse_transcript_time("2024-01-01").unwrap(), 1).unwrap();
+        assert_eq!(start.lines.iter().map(|l| l.number).collect::<Vec<_>>(), [1, 2]);
+        assert!(transcript_around(&path, parse_transcript_time("2024-01-02 00:00:00").unwrap(), 2).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+//! Untrain planner.
+//!
+//! Given stats to raise (`more`) and stats to lower (`less`), suggests which of a character's
+//! trainer ranks to untrain at Untrainus and which trainers to put the freed ranks into. Each
+//! trainer is scored by the marginal effect of one more rank on the chosen stats, measured
+//! from the character's current ranks with the fighter calculator and scaled per stat by the
+//! largest effect any fighter trainer has on it, so stats of different magnitudes weigh the
+//! same. `more` stats count positively and `less` stats negatively.
+//!
+//! Untraining is costly — Untrainus charges per rank, the ranks are gone and each trainer
+//! untrained is a separate visit — so the plan moves at most `max_ranks` ranks (and no more
+//! than the coin budget pays for), only untrains fighter trainers that do nothing for (or work
+//! against) the profile, and retrains rank-for-rank into the best scoring trainer. Healer,
+//! mystic and other trainers the stats model doesn't cover are never proposed, and retired
+//! trainers are never recommended for retraining.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::error::{AmanuensisError, Result};
+use crate::fighter_stats::{
+    compute_fighter_stats_with, simulate_stats_with, ResolvedLoadout, StatsSimulation, FIGHTER_TRAINERS,
+};
+
+/// Totals that can't be planned for (they follow from the ranks themselves).
+const NOT_PLANNABLE: [&str; 3] = ["trained_ranks", "effective_ranks", "slaughter_points"];
+
+/// Ranks to untrain from, or retrain into, one trainer.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RankMove {
+    pub trainer: String,
+    pub ranks: i64,
+    /// The trainer's score for the requested profile (higher is better).
+    pub score: f64,
+}
+
+/// What Untrainus charges, in coins. The fee isn't in the logs, so the caller supplies it.
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub struct UntrainCost {
+    pub coins_per_rank: i64,
+    /// Most coins to spend; None for no limit.
+    pub budget: Option<i64>,
+}
+
+impl UntrainCost {
+    /// Ranks the budget pays for, capped at `max_ranks`.
+    fn affordable_ranks(&self, max_ranks: i64) -> i64 {
+        match self.budget {
+            Some(budget) if self.coins_per_rank > 0 => max_ranks.min(budget.max(0) / self.coins_per_rank),
+            _ => max_ranks,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UntrainPlan {
+    pub more: Vec<String>,
+    pub less: Vec<String>,
+    pub max_ranks: i64,
+    pub untrain: Vec<RankMove>,
+    pub retrain: Vec<RankMove>,
+    /// One per trainer untrained.
+    pub untrainus_visits: usize,
+    pub ranks_moved: i64,
+    pub cost: UntrainCost,
+    /// `ranks_moved` × `cost.coins_per_rank`.
+    pub coins: i64,
+    /// Fighter stats before and after the plan.
+    pub simulation: StatsSimulation,
+}
+
+/// Stat names accepted by `more` / `less`.
+pub fn plannable_stats() -> Vec<&'static str> {
+    compute_fighter_stats_with(&HashMap::new(), &HashMap::new(), &ResolvedLoadout::default())
+        .numeric_fields()
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| !NOT_PLANNABLE.contains(name))
+        .collect()
+}
+
+/// Plan moving up to `max_ranks` ranks from `base_ranks` (trainer name -> ranks) toward the
+/// `more` / `less` stat profile, within `cost`'s budget. `retired` names trainers removed from
+/// the game (`TrainerDb::is_retired`), which can't be retrained into.
+#[allow(clippy::too_many_arguments)]
+pub fn plan_untrain(
+    base_ranks: &HashMap<String, i64>,
+    multipliers: &HashMap<String, f64>,
+    retired: &HashSet<String>,
+    loadout: &ResolvedLoadout,
+    more: &[String],
+    less: &[String],
+    max_ranks: i64,
+    cost: UntrainCost,
+) -> Result<UntrainPlan> {
+    let known = plannable_stats();
+    let mut weights: HashMap<&str, f64> = HashMap::new();
+    for (stats, weight) in [(more, 1.0), (less, -1.0)] {
+        for stat in stats {
+            let name = known.iter().find(|k| k.eq_ignore_ascii_case(stat.trim())).ok_or_else(|| {
+                AmanuensisError::Data(format!("Unknown stat '{}' (known: {})", stat, known.join(", ")))
+            })?;
+            if weights.insert(name, weight).is_some() {
+                return Err(AmanuensisError::Data(format!("Stat '{}' given more than once", name)));
+            }
+        }
+    }
+    if weights.is_empty() {
+        return Err(AmanuensisError::Data("Give at least one stat to raise or lower".to_string()));
+    }
+
+    // Marginal effect of one more rank with each fighter trainer, per chosen stat
+    let base = compute_fighter_stats_with(base_ranks, multipliers, loadout).numeric_fields();
+    let effects: Vec<(&str, HashMap<&str, f64>)> = FIGHTER_TRAINERS
+        .iter()
+        .map(|&trainer| {
+            let mut ranks = base_ranks.clone();
+            *ranks.entry(trainer.to_string()).or_insert(0) += 1;
+            let after = compute_fighter_stats_with(&ranks, multipliers, loadout).numeric_fields();
+            let effect = base
+                .iter()
+                .zip(after)
+                .filter(|((name, _), _)| weights.contains_key(name))
+                .map(|((name, b), (_, a))| (*name, a - b))
+                .collect();
+            (trainer, effect)
+        })
+        .collect();
+    let scale: HashMap<&str, f64> = weights
+        .keys()
+        .map(|&stat| {
+            let max = effects.iter().map(|(_, e)| e[stat].abs()).fold(0.0, f64::max);
+            (stat, if max > 0.0 { max } else { 1.0 })
+        })
+        .collect();
+    let score_of = |trainer: &str| -> f64 {
+        effects
+            .iter()
+            .find(|(t, _)| *t == trainer)
+            .map(|(_, e)| weights.iter().map(|(stat, w)| w * e[stat] / scale[stat]).sum::<f64>())
+            .unwrap_or(0.0)
+    };
+    let round = |x: f64| (x * 1000.0).round() / 1000.0;
+
+    // Best trainer to retrain into, among those still in the game
+    let target = FIGHTER_TRAINERS
+        .iter()
+        .filter(|&&t| !retired.contains(t))
+        .map(|&t| (t, score_of(t)))
+        .filter(|(_, score)| *score > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    // Untrain the worst-scoring fighter ranks first; never ranks that help the profile
+    let mut sources: Vec<(&String, i64, f64)> = base_ranks
+        .iter()
+        .filter(|(t, &n)| n > 0 && FIGHTER_TRAINERS.contains(&t.as_str()))
+        .map(|(t, &n)| (t, n, score_of(t)))
+        .filter(|(t, _, score)| *score <= 0.0 && target.is_some_and(|(best, _)| best != t.as_str()))
+        .collect();
+    sources.sort_by(|a, b| a.2.total_cmp(&b.2).then(b.1.cmp(&a.1)).then(a.0.cmp(b.0)));
+
+    let mut untrain = Vec::new();
+    let mut left = cost.affordable_ranks(max_ranks.max(0));
+    for (trainer, ranks, score) in sources {
+        if left == 0 {
+            break;
+        }
+        let n = ranks.min(left);
+        left -= n;
+        untrain.push(RankMove { trainer: trainer.clone(), ranks: n, score: round(score) });
+    }
+    let ranks_moved: i64 = untrain.iter().map(|m| m.ranks).sum();
+    let retrain: Vec<RankMove> = match target {
+        Some((trainer, score)) if ranks_moved > 0 => {
+            vec![RankMove { trainer: trainer.to_string(), ranks: ranks_moved, score: round(score) }]
+        }
+        _ => Vec::new(),
+    };
+
+    let mut deltas: BTreeMap<String, i64> = BTreeMap::new();
+    for m in &untrain {
+        *deltas.entry(m.trainer.clone()).or_insert(0) -= m.ranks;
+    }
+    for m in &retrain {
+        *deltas.entry(m.trainer.clone()).or_insert(0) += m.ranks;
+    }
+    Ok(UntrainPlan {
+        more: more.to_vec(),
+        less: less.to_vec(),
+        max_ranks,
+        untrainus_visits: untrain.len(),
+        ranks_moved,
+        cost,
+        coins: ranks_moved * cost.coins_per_rank,
+        untrain,
+        retrain,
+        simulation: simulate_stats_with(base_ranks, &deltas, multipliers, loadout),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranks(pairs: &[(&str, i64)]) -> HashMap<String, i64> {
+        pairs.iter().map(|(t, n)| (t.to_string(), *n)).collect()
+    }
+
+    #[test]
+    fn moves_balance_ranks_into_accuracy() {
+        let base = ranks(&[("Balthus", 30), ("Atkus", 10), ("Histia", 5)]);
+        let plan = plan_untrain(
+            &base,
+            &HashMap::new(),
+            &HashSet::new(),
+            &ResolvedLoadout::default(),
+            &["accuracy".to_string()],
+            &["balance".to_string()],
+            20,
+            UntrainCost::default(),
+        )
+        .unwrap();
+
+        // Balthus works against the profile, so it goes first; Atkus helps and is kept
+        assert_eq!(plan.untrain[0].trainer, "Balthus");
+        assert_eq!(plan.untrain[0].ranks, 20);
+        assert_eq!(plan.untrainus_visits, 1);
+        assert_eq!(plan.retrain.len(), 1);
+        assert_eq!(plan.retrain[0].ranks, 20);
+        assert!(plan.simulation.after.accuracy > plan.simulation.before.accuracy);
+        assert!(plan.simulation.after.balance < plan.simulation.before.balance);
+        assert_eq!(plan.simulation.after.trained_ranks, plan.simulation.before.trained_ranks);
+    }
+
+    #[test]
+    fn budget_spreads_over_trainers_and_rejects_bad_stats() {
+        let base = ranks(&[("Histia", 3), ("Troilus", 4)]);
+        let plan = plan_untrain(
+            &base,
+            &HashMap::new(),
+            &HashSet::new(),
+            &ResolvedLoadout::default(),
+            &["Accuracy".to_string()],
+            &[],
+            5,
+            UntrainCost::default(),
+        )
+        .unwrap();
+        assert_eq!(plan.ranks_moved, 5);
+        assert_eq!(plan.untrainus_visits, 2);
+
+        let none = plan_untrain(&base, &HashMap::new(), &HashSet::new(), &ResolvedLoadout::default(), &[], &[], 5, UntrainCost::default());
+        assert!(none.is_err());
+        let luck = ["luck".to_string()];
+        let bad = plan_untrain(&base, &HashMap::new(), &HashSet::new(), &ResolvedLoadout::default(), &luck, &[], 5, UntrainCost::default());
+        assert!(bad.unwrap_err().to_string().contains("known: accuracy"));
+    }
+
+    #[test]
+    fn only_fighter_ranks_are_untrained_and_the_budget_caps_the_moves() {
+        // Healer and mystic ranks aren't in the stats model, so they are never proposed.
+        let base = ranks(&[("Balthus", 30), ("Faustus", 40), ("Quantos", 40)]);
+        let accuracy = ["accuracy".to_string()];
+        let cost = UntrainCost { coins_per_rank: 100, budget: Some(750) };
+        let plan = plan_untrain(&base, &HashMap::new(), &HashSet::new(), &ResolvedLoadout::default(), &accuracy, &[], 20, cost).unwrap();
+        assert_eq!(plan.untrain.iter().map(|m| m.trainer.as_str()).collect::<Vec<_>>(), vec!["Balthus"]);
+        assert_eq!((plan.ranks_moved, plan.coins), (7, 700));
+
+        let free = plan_untrain(&base, &HashMap::new(), &HashSet::new(), &ResolvedLoadout::default(), &accuracy, &[], 20, UntrainCost::default())
+            .unwrap();
+        assert_eq!((free.ranks_moved, free.coins), (20, 0));
+    }
+
+    #[test]
+    fn retired_trainers_are_never_recommended() {
+        let base = ranks(&[("Balthus", 30)]);
+        let accuracy = ["accuracy".to_string()];
+        let plan = |retired: &HashSet<String>| {
+            plan_untrain(&base, &HashMap::new(), retired, &ResolvedLoadout::default(), &accuracy, &[], 5, UntrainCost::default())
+                .unwrap()
+        };
+        let best = plan(&HashSet::new()).retrain[0].trainer.clone();
+        let without = plan(&HashSet::from([best.clone()]));
+        assert_eq!(without.retrain.len(), 1);
+        assert_ne!(without.retrain[0].trainer, best);
+    }
+}
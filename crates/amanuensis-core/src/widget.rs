@@ -0,0 +1,139 @@
+//! Embeddable profile widget.
+//!
+//! A compact, stable JSON summary of one character for clan-website profile widgets. The
+//! shape is a public contract, versioned by `schema_version`:
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "name": "Gandor",
+//!   "profession": "Fighter",
+//!   "coin_level": 120,
+//!   "total_ranks": 1543,
+//!   "deaths": 87,
+//!   "top_kills": [
+//!     { "creature": "Rat", "count": 4021, "value": 2 }
+//!   ]
+//! }
+//! ```
+//!
+//! - `profession` is one of the `Profession` display names (`"Unknown"` until determined).
+//! - `coin_level` is the confirmed coin level, or 0 if none is known yet.
+//! - `total_ranks` is trained ranks plus user-modified ranks, as the GUI summary shows.
+//! - `top_kills` has up to 3 entries, most kills (solo + assisted) first.
+//! - `portrait` is the character's attached portrait as a `data:` URL; omitted when none is set.
+//!
+//! Adding a field is backwards compatible and keeps the version. Renaming, removing or
+//! changing the meaning of a field bumps `WIDGET_SCHEMA_VERSION`.
+
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::Result;
+
+/// Current widget schema version.
+pub const WIDGET_SCHEMA_VERSION: u32 = 1;
+
+/// Number of creatures listed in `top_kills`.
+const TOP_KILLS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WidgetKill {
+    pub creature: String,
+    pub count: i64,
+    pub value: i32,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Widget {
+    pub schema_version: u32,
+    pub name: String,
+    pub profession: String,
+    pub coin_level: i64,
+    pub total_ranks: i64,
+    pub deaths: i64,
+    pub top_kills: Vec<WidgetKill>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub portrait: Option<String>,
+}
+
+impl Database {
+    /// Build the widget for a (possibly merged) character. None if the character is unknown.
+    pub fn widget_merged(&self, char_id: i64) -> Result<Option<Widget>> {
+        let Some(character) = self.get_character_merged(char_id)? else {
+            return Ok(None);
+        };
+        let trainers = self.get_trainers_merged(char_id)?;
+        let portrait = self.get_character_portrait(char_id)?.map(|p| p.data_url());
+        let mut kills = self.get_kills_merged(char_id)?;
+        kills.retain(|k| k.total_all() > 0);
+        kills.sort_by(|a, b| {
+            b.total_all()
+                .cmp(&a.total_all())
+                .then(a.creature_name.cmp(&b.creature_name))
+        });
+
+        Ok(Some(Widget {
+            schema_version: WIDGET_SCHEMA_VERSION,
+            name: character.name,
+            profession: character.profession.to_string(),
+            coin_level: character.coin_level,
+            total_ranks: trainers.iter().map(|t| t.ranks + t.modified_ranks).sum(),
+            deaths: character.deaths,
+            top_kills: kills
+                .into_iter()
+                .take(TOP_KILLS)
+                .map(|k| WidgetKill {
+                    count: k.total_all(),
+                    value: k.creature_value,
+                    creature: k.creature_name,
+                })
+                .collect(),
+            portrait,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widget_has_stable_shape() {
+        let db = Database::open_in_memory().unwrap();
+        let c = db.get_or_create_character("Gandor").unwrap();
+        db.increment_character_field(c, "deaths", 2).unwrap();
+        for (creature, n) in [("Rat", 5), ("Orga", 3), ("Vermine", 4), ("Maha", 1)] {
+            for _ in 0..n {
+                db.upsert_kill(c, creature, "killed_count", 10, "2024-01-01 10:00:00").unwrap();
+            }
+        }
+        db.upsert_trainer_rank(c, "Detha", "2024-01-01 10:00:00", 1.0).unwrap();
+
+        let w = db.widget_merged(c).unwrap().unwrap();
+        let text = serde_json::to_string(&w).unwrap();
+        let ordered: indexmap::IndexMap<String, serde_json::Value> = serde_json::from_str(&text).unwrap();
+        let keys: Vec<&str> = ordered.keys().map(|k| k.as_str()).collect();
+        let json = serde_json::to_value(&w).unwrap();
+        assert_eq!(
+            keys,
+            vec!["schema_version", "name", "profession", "coin_level", "total_ranks", "deaths", "top_kills"]
+        );
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["deaths"], 2);
+        assert_eq!(json["total_ranks"], 1);
+        let top: Vec<&str> = w.top_kills.iter().map(|k| k.creature.as_str()).collect();
+        assert_eq!(top, vec!["Rat", "Vermine", "Orga"]);
+        assert_eq!(json["top_kills"][0], serde_json::json!({"creature": "Rat", "count": 5, "value": 10}));
+
+        db.set_character_portrait(c, &[0x89, b'P', b'N', b'G'], None).unwrap();
+        let json = serde_json::to_value(db.widget_merged(c).unwrap().unwrap()).unwrap();
+        assert_eq!(json["portrait"], "data:image/png;base64,iVBORw==");
+    }
+
+    #[test]
+    fn unknown_character_has_no_widget() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(db.widget_merged(999).unwrap().is_none());
+    }
+}
@@ -1,13 +1,25 @@
 use tauri::State;
 
+use amanuensis_core::db::queries::{CharacterListEntry, ClanSummary, MergePreview};
 use amanuensis_core::models::Character;
+use amanuensis_core::{CharacterComparison, CharacterSummary, TrainerDb};
 
+use super::{source_folders, SourceSpec};
 use crate::state::AppState;
 
-/// List all characters in the database.
+/// List all characters in the database, with the list screen's aggregate badges
+/// (total ranks, total kills, last played) from a single query, and the pending log files
+/// under `sources` per character as `unscanned_files`.
 #[tauri::command]
-pub fn list_characters(state: State<'_, AppState>) -> Result<Vec<Character>, String> {
-    state.with_db(|db| db.list_characters().map_err(|e| e.to_string()))
+pub fn list_characters(
+    sources: Vec<SourceSpec>,
+    state: State<'_, AppState>,
+) -> Result<Vec<CharacterListEntry>, String> {
+    let folders = source_folders(sources);
+    state.with_db(|db| {
+        let pending = amanuensis_core::parser::pending_files_by_character(db, &folders).map_err(|e| e.to_string())?;
+        db.list_characters_with_stats(&pending).map_err(|e| e.to_string())
+    })
 }
 
 /// Get a single character by name.
@@ -22,6 +34,35 @@ pub fn get_character_merged(char_id: i64, state: State<'_, AppState>) -> Result<
     state.with_db(|db| db.get_character_merged(char_id).map_err(|e| e.to_string()))
 }
 
+/// Current clan, titles and dated clan history for a character (includes merged sources).
+#[tauri::command]
+pub fn get_clan_summary(char_id: i64, state: State<'_, AppState>) -> Result<ClanSummary, String> {
+    state.with_db(|db| db.get_clan_summary_merged(char_id).map_err(|e| e.to_string()))
+}
+
+/// Everything the summary view shows for a (possibly merged) character, aggregated exactly
+/// as the CLI's `summary` command does.
+#[tauri::command]
+pub fn get_character_summary(
+    char_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<CharacterSummary>, String> {
+    state.with_db(|db| db.character_summary(char_id).map_err(|e| e.to_string()))
+}
+
+/// Two (possibly merged) characters side by side, as the CLI's `compare` command shows
+/// them: effective ranks per trainer, kills per creature, and stat differences
+/// (`right - left`).
+#[tauri::command]
+pub fn compare_characters(
+    left_id: i64,
+    right_id: i64,
+    state: State<'_, AppState>,
+) -> Result<CharacterComparison, String> {
+    let trainer_db = TrainerDb::bundled().map_err(|e| e.to_string())?;
+    state.with_db(|db| db.compare_characters(left_id, right_id, &trainer_db).map_err(|e| e.to_string()))
+}
+
 /// Merge source characters into a target character.
 #[tauri::command]
 pub fn merge_characters(
@@ -29,13 +70,24 @@ pub fn merge_characters(
     target_id: i64,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    state.with_db(|db| db.merge_characters(&source_ids, target_id).map_err(|e| e.to_string()))
+    state.with_db(|db| db.merge_characters(&source_ids, target_id).map_err(super::tagged_error))
+}
+
+/// Preview a merge: the combined stats, kills and trainer totals it would produce.
+/// Nothing is written to the database.
+#[tauri::command]
+pub fn preview_merge(
+    source_ids: Vec<i64>,
+    target_id: i64,
+    state: State<'_, AppState>,
+) -> Result<MergePreview, String> {
+    state.with_db(|db| db.preview_merge(&source_ids, target_id).map_err(super::tagged_error))
 }
 
 /// Unmerge a character (restore it from a merged state).
 #[tauri::command]
 pub fn unmerge_character(source_id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    state.with_db(|db| db.unmerge_character(source_id).map_err(|e| e.to_string()))
+    state.with_db(|db| db.unmerge_character(source_id).map_err(super::tagged_error))
 }
 
 /// Get all characters that have been merged into the given target.
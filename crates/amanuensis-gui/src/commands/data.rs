@@ -1,9 +1,12 @@
+use std::path::Path;
+
 use tauri::State;
 
-use amanuensis_core::db::queries::CreatureFrequency;
-use amanuensis_core::export::ExportFormat;
+use amanuensis_core::db::queries::{CreatureAccuracy, CreatureFrequency, Economy, HealSummary, StoneUsage};
+use amanuensis_core::export::{ExportFormat, ExportTable};
 use amanuensis_core::models::{Kill, Lasty, Pet, ProcessLog, Trainer};
-use amanuensis_core::{LogSearchResult, TrainerDb};
+use amanuensis_core::db::IndexStatus;
+use amanuensis_core::{LogSearchOptions, LogSearchPage, TrainerDb};
 
 use crate::state::AppState;
 
@@ -45,6 +48,22 @@ pub fn get_log_line_count(state: State<'_, AppState>) -> Result<i64, String> {
     state.with_db(|db| db.log_line_count().map_err(|e| e.to_string()))
 }
 
+/// Get the search index's size and whether scans fill it.
+#[tauri::command]
+pub fn get_index_status(state: State<'_, AppState>) -> Result<IndexStatus, String> {
+    state.with_db(|db| db.index_status().map_err(|e| e.to_string()))
+}
+
+/// Turn search indexing on or off (stored in the database). Turning it off drops the
+/// existing index. Returns the updated status.
+#[tauri::command]
+pub fn set_indexing_enabled(enabled: bool, state: State<'_, AppState>) -> Result<IndexStatus, String> {
+    state.with_db(|db| {
+        db.set_indexing_enabled(enabled).map_err(|e| e.to_string())?;
+        db.index_status().map_err(|e| e.to_string())
+    })
+}
+
 /// Get the full trainer catalog (for "Show Zero Trainers" toggle).
 #[tauri::command]
 pub fn get_trainer_db_info() -> Result<Vec<TrainerInfo>, String> {
@@ -58,6 +77,8 @@ pub fn get_trainer_db_info() -> Result<Vec<TrainerInfo>, String> {
             multiplier: m.multiplier,
             is_combo: m.is_combo,
             combo_components: m.combo_components,
+            retired: m.retired,
+            replaced_by: m.replaced_by,
         })
         .collect())
 }
@@ -68,38 +89,81 @@ pub fn get_process_logs(state: State<'_, AppState>) -> Result<Vec<ProcessLog>, S
     state.with_db(|db| db.get_process_logs().map_err(|e| e.to_string()))
 }
 
-/// Search indexed log lines using FTS5 full-text search.
+/// Unknown lines included in a diagnostics bundle.
+const DIAGNOSTICS_UNKNOWN_LINES: usize = 200;
+
+/// Warnings and errors from the last scan, newest first (at most `limit`, default 100).
+#[tauri::command]
+pub fn get_recent_errors(limit: Option<usize>, state: State<'_, AppState>) -> Result<Vec<ProcessLog>, String> {
+    state.with_db(|db| db.get_recent_errors(limit.unwrap_or(100)).map_err(|e| e.to_string()))
+}
+
+/// Write a diagnostics zip (scan errors, unknown-line samples, schema version, settings and
+/// app info) to `dest`, for attaching to a bug report.
+#[tauri::command]
+pub fn export_diagnostics(dest: String, state: State<'_, AppState>) -> Result<(), String> {
+    let trainer_db = TrainerDb::bundled().map_err(|e| e.to_string())?;
+    let db_path = state.db_path.lock().map_err(|e| format!("Lock poisoned: {e}"))?.clone();
+    state.with_db(|db| {
+        let mut diagnostics = db
+            .diagnostics(&trainer_db, DIAGNOSTICS_UNKNOWN_LINES)
+            .map_err(|e| e.to_string())?;
+        diagnostics
+            .environment
+            .insert("app_version".to_string(), env!("CARGO_PKG_VERSION").to_string());
+        if let Some(path) = db_path {
+            diagnostics.environment.insert("database_path".to_string(), path);
+        }
+        diagnostics.write_zip(Path::new(&dest)).map_err(|e| e.to_string())
+    })
+}
+
+/// Search indexed log lines using FTS5 full-text search, one page at a time (`offset`
+/// results in, newest files first). Each result carries `segments` for highlighting.
+/// `from`/`to` bound the line dates (storage form); `regex` matches with a regular
+/// expression instead of FTS5.
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub fn search_logs(
     query: String,
     char_id: Option<i64>,
     limit: Option<i64>,
+    offset: Option<i64>,
     include_speech: Option<bool>,
     lines_before: Option<i64>,
     lines_after: Option<i64>,
+    from: Option<String>,
+    to: Option<String>,
+    regex: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<Vec<LogSearchResult>, String> {
+) -> Result<LogSearchPage, String> {
+    let defaults = LogSearchOptions::default();
+    let options = LogSearchOptions {
+        char_id,
+        include_speech: include_speech.unwrap_or(defaults.include_speech),
+        lines_before: lines_before.unwrap_or(0),
+        lines_after: lines_after.unwrap_or(0),
+        from,
+        to,
+        regex: regex.unwrap_or(false),
+        offset: offset.unwrap_or(0),
+        limit: limit.unwrap_or(defaults.limit),
+    };
     if query.trim().is_empty() {
-        return Ok(Vec::new());
+        return Ok(LogSearchPage { results: Vec::new(), total: 0, offset: options.offset, has_more: false });
     }
-    let limit = limit.unwrap_or(200);
-    let include_speech = include_speech.unwrap_or(false);
-    let lines_before = lines_before.unwrap_or(0);
-    let lines_after = lines_after.unwrap_or(0);
-    state.with_db(|db| {
-        db.search_log_lines(&query, char_id, limit, include_speech, lines_before, lines_after)
-            .map_err(|e| e.to_string())
-    })
+    state.with_db(|db| db.search_log_lines_page(&query, &options).map_err(super::tagged_error))
 }
 
-/// Get the set of creature names the character has encountered (killed).
+/// Get the set of creature names the character has encountered (killed, died to or
+/// studied), merged sources.
 #[tauri::command]
 pub fn get_encountered_creatures(
     char_id: i64,
     state: State<'_, AppState>,
 ) -> Result<Vec<String>, String> {
     state.with_db(|db| {
-        db.get_encountered_creatures(char_id)
+        db.get_encountered_creatures_merged(char_id)
             .map(|set| {
                 let mut v: Vec<String> = set.into_iter().collect();
                 v.sort();
@@ -123,8 +187,60 @@ pub fn get_kill_frequency(
     })
 }
 
+/// Observed per-creature melee hits and misses, merged sources, most swings first.
+#[tauri::command]
+pub fn get_accuracy(char_id: i64, state: State<'_, AppState>) -> Result<Vec<CreatureAccuracy>, String> {
+    state.with_db(|db| db.get_accuracy_merged(char_id).map_err(|e| e.to_string()))
+}
+
+/// Heals given to and received from each player, merged sources, most heals first.
+#[tauri::command]
+pub fn get_heals(char_id: i64, state: State<'_, AppState>) -> Result<Vec<HealSummary>, String> {
+    state.with_db(|db| db.get_heals_merged(char_id).map_err(|e| e.to_string()))
+}
+
+/// Darkstone and moonstone charges, uses and expiries, merged sources.
+#[tauri::command]
+pub fn get_stone_usage(char_id: i64, state: State<'_, AppState>) -> Result<Vec<StoneUsage>, String> {
+    state.with_db(|db| db.get_stone_usage_merged(char_id).map_err(|e| e.to_string()))
+}
+
+/// Coin totals, per-source ledger buckets and daily balance history for the economy
+/// dashboard, merged sources. `range` is "all" (default) or "<N>d", counted back from the
+/// character's most recent coin activity.
+#[tauri::command]
+pub fn get_economy(
+    char_id: i64,
+    range: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Economy, String> {
+    let days = match range.as_deref().unwrap_or("all") {
+        "all" => None,
+        r => match r.strip_suffix('d').and_then(|n| n.parse::<i64>().ok()) {
+            Some(n) if n > 0 => Some(n),
+            _ => return Err(format!("Unknown economy range: {r}")),
+        },
+    };
+    state.with_db(|db| db.get_economy_merged(char_id, days).map_err(|e| e.to_string()))
+}
+
+/// Parse an export command's `format` ("csv", "json" or "text").
+fn export_format(format: &str) -> Result<ExportFormat, String> {
+    ExportFormat::parse(format).ok_or_else(|| format!("Unknown export format: {format}"))
+}
+
+/// Render an export with `render` and write it to `path` (chosen in the frontend's save dialog).
+fn write_export(
+    state: &State<'_, AppState>,
+    path: &str,
+    render: impl FnOnce(&amanuensis_core::Database) -> amanuensis_core::Result<String>,
+) -> Result<(), String> {
+    let contents = state.with_db(|db| render(db).map_err(super::tagged_error))?;
+    std::fs::write(path, contents).map_err(|e| e.to_string())
+}
+
 /// Export the unified Kills table for a character to a file at `path`.
-/// `format` is "csv" or "text".
+/// `format` is "csv", "json" or "text".
 #[tauri::command]
 pub fn export_kills(
     char_id: i64,
@@ -132,15 +248,33 @@ pub fn export_kills(
     path: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let fmt = match format.as_str() {
-        "csv" => ExportFormat::Csv,
-        "text" => ExportFormat::Text,
-        other => return Err(format!("Unknown export format: {other}")),
-    };
-    let contents = state.with_db(|db| {
-        db.export_kills_merged(char_id, fmt).map_err(|e| e.to_string())
-    })?;
-    std::fs::write(&path, contents).map_err(|e| e.to_string())
+    let fmt = export_format(&format)?;
+    write_export(&state, &path, |db| db.export_kills_merged(char_id, fmt))
+}
+
+/// Export a character's (merged) trainer rows to `path`, as the CLI's
+/// `export --table trainers --character` writes them.
+#[tauri::command]
+pub fn export_trainers(
+    char_id: i64,
+    format: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let fmt = export_format(&format)?;
+    write_export(&state, &path, |db| db.export_table(ExportTable::Trainers, Some(char_id), fmt))
+}
+
+/// Export a character's summary to `path`: the summary JSON, or `field,value` rows for CSV/text.
+#[tauri::command]
+pub fn export_character_summary(
+    char_id: i64,
+    format: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let fmt = export_format(&format)?;
+    write_export(&state, &path, |db| db.export_character_summary(char_id, fmt))
 }
 
 /// Set or clear a free-text note on a trainer row.
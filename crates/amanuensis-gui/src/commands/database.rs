@@ -2,7 +2,7 @@ use std::path::Path;
 
 use tauri::{Manager, State};
 
-use amanuensis_core::{Database, LogParser};
+use amanuensis_core::{data_dir, demo, Database, LogParser};
 
 use crate::state::AppState;
 
@@ -19,12 +19,28 @@ pub fn open_database(path: String, state: State<'_, AppState>) -> Result<(), Str
     Ok(())
 }
 
-/// Get the default database path in the app's data directory.
+/// Get the default database path in the app's data directory — the same file the CLI uses
+/// by default (`amanuensis_core::data_dir`).
 #[tauri::command]
 pub fn get_default_db_path(app: tauri::AppHandle) -> Result<String, String> {
-    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-    Ok(dir.join("amanuensis.db").to_string_lossy().into_owned())
+    let path = match data_dir::default_db_path() {
+        Some(path) => path,
+        None => app.path().app_data_dir().map_err(|e| e.to_string())?.join(data_dir::DB_FILE_NAME),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Rebuild the demo database from the bundled sample logs (in the temp directory) and
+/// return its path for `open_database`. Closes the open database first, since it may be the
+/// previous demo file that is about to be replaced.
+#[tauri::command]
+pub fn build_demo_database(state: State<'_, AppState>) -> Result<String, String> {
+    *state.db.lock().map_err(|e| format!("Lock poisoned: {e}"))? = None;
+    let setup = demo::build_demo(&demo::default_demo_dir()).map_err(|e| e.to_string())?;
+    Ok(setup.db_path.to_string_lossy().into_owned())
 }
 
 /// Check if a database file exists at a path (for auto-detection).
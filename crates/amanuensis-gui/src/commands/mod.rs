@@ -6,6 +6,7 @@ mod rank;
 mod portraits;
 mod updates;
 mod bestiary;
+mod reports;
 
 // Re-export all commands so main.rs keeps using `commands::X` unchanged.
 pub use database::*;
@@ -16,6 +17,13 @@ pub use rank::*;
 pub use portraits::*;
 pub use updates::*;
 pub use bestiary::*;
+pub use reports::*;
+
+/// Render a core error for the frontend as `[kind] message`, so the UI can branch on
+/// `AmanuensisError::kind()` (see `errorKind` in ui/src/lib/commands.ts).
+pub(crate) fn tagged_error(e: amanuensis_core::AmanuensisError) -> String {
+    format!("[{}] {}", e.kind(), e)
+}
 
 // ---------------------------------------------------------------------------
 // Shared scan infrastructure (used by scanning.rs)
@@ -45,11 +53,16 @@ pub struct TrainerInfo {
     pub multiplier: f64,
     pub is_combo: bool,
     pub combo_components: Vec<String>,
+    pub retired: bool,
+    pub replaced_by: Option<String>,
 }
 
 pub(super) enum ScanOp {
     Folder { path: String, force: bool, recursive: bool },
     Files { files: Vec<String>, force: bool },
+    /// Every remembered source (path, recursive); `reset` clears log-derived data first
+    /// (`LogParser::rescan_sources`), otherwise an incremental update.
+    Sources { folders: Vec<(PathBuf, bool)>, reset: bool },
 }
 
 #[derive(serde::Deserialize)]
@@ -58,22 +71,31 @@ pub(super) struct SourceSpec {
     pub recursive: bool,
 }
 
+pub(super) fn source_folders(sources: Vec<SourceSpec>) -> Vec<(PathBuf, bool)> {
+    sources
+        .into_iter()
+        .map(|s| (PathBuf::from(s.path), s.recursive))
+        .collect()
+}
+
+/// Run a scan on a blocking worker thread, emitting `scan-progress` events. The scan can be
+/// stopped with `cancel_scan`, which rolls back its in-flight transaction; the error is then
+/// tagged `[cancelled]`. The database is put back into state however the scan ends.
 pub(super) async fn run_scan(
     state: &State<'_, AppState>,
     app: tauri::AppHandle,
     op: ScanOp,
     index_lines: bool,
-    reset_first: bool,
+    nice: bool,
 ) -> Result<ScanResult, String> {
     let db = state.take_db()?;
     let state_db = state.db.clone();
+    let cancel = state.begin_scan()?;
 
     let result = tauri::async_runtime::spawn_blocking(move || {
-        if reset_first {
-            db.reset_log_data().map_err(|e| e.to_string())?;
-        }
-
-        let parser = LogParser::new(db).map_err(|e| e.to_string())?;
+        let parser = LogParser::new(db).map_err(tagged_error)?;
+        parser.set_nice(nice);
+        parser.set_cancel_flag(Some(cancel));
 
         let app_handle = app.clone();
         let progress_cb = |current: usize, total: usize, filename: &str| {
@@ -89,30 +111,135 @@ pub(super) async fn run_scan(
 
         let result = match op {
             ScanOp::Folder { path, force, recursive } => {
-                if recursive {
-                    parser
-                        .scan_recursive_with_progress(Path::new(&path), force, index_lines, progress_cb)
-                        .map_err(|e| e.to_string())?
+                let scanned = if recursive {
+                    parser.scan_recursive_with_progress(Path::new(&path), force, index_lines, progress_cb)
                 } else {
-                    parser
-                        .scan_folder_with_progress(Path::new(&path), force, index_lines, progress_cb)
-                        .map_err(|e| e.to_string())?
-                }
+                    parser.scan_folder_with_progress(Path::new(&path), force, index_lines, progress_cb)
+                };
+                scanned.and_then(|r| parser.finalize_characters().map(|()| r))
             }
             ScanOp::Files { files, force } => {
                 let paths: Vec<PathBuf> = files.iter().map(PathBuf::from).collect();
                 parser
                     .scan_files_with_progress(&paths, force, index_lines, progress_cb)
-                    .map_err(|e| e.to_string())?
+                    .and_then(|r| parser.finalize_characters().map(|()| r))
             }
+            ScanOp::Sources { folders, reset: true } => parser.rescan_sources(&folders, index_lines, progress_cb),
+            ScanOp::Sources { folders, reset: false } => parser.update_sources(&folders, index_lines, progress_cb),
         };
 
-        parser.finalize_characters().map_err(|e| e.to_string())?;
         *state_db.lock().map_err(|e| format!("Lock poisoned: {e}"))? = Some(parser.into_db());
-        Ok(result)
+        result.map_err(tagged_error)
     })
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| e.to_string());
+    state.end_scan();
+    let result = result?;
 
+    if result.is_ok() {
+        reports::write_scheduled_reports(state.inner());
+    }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use tauri::Manager;
+
+    use amanuensis_core::Database;
+
+    use super::*;
+
+    /// A mock app whose open database holds "Fen" with an alt merged into it; every row is
+    /// recorded once per character, so merged totals are twice a single character's.
+    fn merged_app() -> (tauri::App<tauri::test::MockRuntime>, i64, i64) {
+        let db = Database::open_in_memory().unwrap();
+        let main = db.get_or_create_character("Fen").unwrap();
+        let alt = db.get_or_create_character("Fenn").unwrap();
+        for id in [main, alt] {
+            db.increment_character_field(id, "logins", 1).unwrap();
+            db.upsert_kill(id, "Rat", "killed_count", 2, "2024-01-01 10:00:00").unwrap();
+            db.upsert_trainer_rank(id, "Histia", "2024-01-01 10:00:00", 1.0).unwrap();
+            db.upsert_lasty(id, "Orga", "Movements", "2024-01-01 10:00:00").unwrap();
+            db.upsert_pet(id, "Maha Ruknee").unwrap();
+            db.insert_trainer_checkpoint(id, "Histia", 10, Some(19), "2024-01-01 12:00:00").unwrap();
+        }
+        db.upsert_kill(alt, "Orga", "killed_by_count", 40, "2024-01-02 10:00:00").unwrap();
+        db.merge_characters(&[alt], main).unwrap();
+
+        let app = tauri::test::mock_app();
+        let state = AppState::new();
+        *state.db.lock().unwrap() = Some(db);
+        app.manage(state);
+        (app, main, alt)
+    }
+
+    fn json<T: Serialize>(value: T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[test]
+    fn character_commands_match_the_cli_merged_queries() {
+        let (app, id, _) = merged_app();
+        let state = || app.state::<AppState>();
+
+        // What the CLI shows: it reads every per-character table through the _merged queries.
+        let expected = state()
+            .with_db(|db| {
+                Ok(vec![
+                    json(db.get_character_merged(id).unwrap()),
+                    json(db.get_kills_merged(id).unwrap()),
+                    json(db.get_trainers_merged(id).unwrap()),
+                    json(db.get_pets_merged(id).unwrap()),
+                    json(db.get_lastys_merged(id).unwrap()),
+                    json(db.get_all_trainer_checkpoints_merged(id).unwrap()),
+                    json(db.get_latest_trainer_checkpoints_merged(id).unwrap()),
+                ])
+            })
+            .unwrap();
+        let gui = vec![
+            json(get_character_merged(id, state()).unwrap()),
+            json(get_kills(id, state()).unwrap()),
+            json(get_trainers(id, state()).unwrap()),
+            json(get_pets(id, state()).unwrap()),
+            json(get_lastys(id, state()).unwrap()),
+            json(get_all_trainer_checkpoints(id, state()).unwrap()),
+            json(get_trainer_checkpoints(id, state()).unwrap()),
+        ];
+        assert_eq!(gui, expected);
+
+        // And those are the merged numbers, not the target's own.
+        let character = get_character_merged(id, state()).unwrap().unwrap();
+        assert_eq!(character.logins, 2);
+        let kills = get_kills(id, state()).unwrap();
+        let rat = kills.iter().find(|k| k.creature_name == "Rat").unwrap();
+        assert_eq!(rat.killed_count, 2);
+        assert_eq!(get_trainers(id, state()).unwrap()[0].ranks, 2);
+        assert_eq!(get_all_trainer_checkpoints(id, state()).unwrap().len(), 2);
+        let encountered = get_encountered_creatures(id, state()).unwrap();
+        assert_eq!(encountered, vec!["Orga".to_string(), "Rat".to_string()]);
+    }
+
+    #[test]
+    fn merged_source_commands_show_only_its_own_rows() {
+        let (app, _, alt) = merged_app();
+        let state = || app.state::<AppState>();
+        assert_eq!(get_character_merged(alt, state()).unwrap().unwrap().logins, 1);
+        assert_eq!(get_kills(alt, state()).unwrap().iter().map(|k| k.killed_count).sum::<i64>(), 1);
+    }
+
+    #[test]
+    fn cancel_scan_raises_only_the_running_scans_flag() {
+        let (app, _, _) = merged_app();
+        let state = || app.state::<AppState>();
+        assert!(!cancel_scan(state()));
+
+        let flag = state().begin_scan().unwrap();
+        assert!(cancel_scan(state()));
+        assert!(flag.load(std::sync::atomic::Ordering::Relaxed));
+
+        state().end_scan();
+        assert!(!cancel_scan(state()));
+        assert!(!state().begin_scan().unwrap().load(std::sync::atomic::Ordering::Relaxed));
+    }
+}
@@ -1,6 +1,8 @@
 use std::path::{Path, PathBuf};
 
-use tauri::Manager;
+use tauri::{Manager, State};
+
+use crate::state::AppState;
 
 /// Sanitize a character name for use as a filename.
 fn sanitize_portrait_name(name: &str) -> String {
@@ -103,3 +105,37 @@ pub fn get_character_portrait_path(
     let path = dir.join(format!("{sanitized}.png"));
     read_cached_as_base64(&path)
 }
+
+/// The portrait attached to a character (stored in the database), as a data URL.
+/// Takes precedence over the Rank Tracker avatar and is included in roster reports.
+#[tauri::command]
+pub fn get_stored_character_portrait(
+    char_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state.with_db(|db| {
+        db.get_character_portrait(char_id)
+            .map(|p| p.map(|p| p.data_url()))
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// Attach the image at `path` as the character's portrait, or remove the stored portrait
+/// when `path` is None. Returns the stored portrait as a data URL.
+#[tauri::command]
+pub fn set_character_portrait(
+    char_id: i64,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state.with_db(|db| match path {
+        Some(path) => db
+            .attach_portrait_file(char_id, Path::new(&path))
+            .map(|p| Some(p.data_url()))
+            .map_err(|e| e.to_string()),
+        None => db
+            .clear_character_portrait(char_id)
+            .map(|_| None)
+            .map_err(|e| e.to_string()),
+    })
+}
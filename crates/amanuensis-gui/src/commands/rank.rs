@@ -1,7 +1,31 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
 use tauri::State;
 
+use amanuensis_core::{compute_fighter_stats_with, fighter_rank_map, simulate_stats_with, FighterModifiers, TrainerDb};
+use amanuensis_core::fighter_stats::{FighterStats, StatsSimulation};
+use amanuensis_core::models::Trainer;
+
 use crate::state::AppState;
 
+/// A hypothetical modified-ranks value for one trainer, as edited in the GUI before saving.
+#[derive(Deserialize)]
+pub struct RankChange {
+    pub trainer_name: String,
+    pub modified_ranks: i64,
+}
+
+/// Result of `preview_effective_ranks`: the trainer rows with the changes applied,
+/// plus the totals and fighter stats they would produce.
+#[derive(Serialize)]
+pub struct RankPreview {
+    pub trainers: Vec<Trainer>,
+    pub total_ranks: i64,
+    pub effective_ranks: f64,
+    pub fighter_stats: FighterStats,
+}
+
 /// Set modified ranks for a trainer (user-specified baseline).
 #[tauri::command]
 pub fn set_modified_ranks(
@@ -60,27 +84,104 @@ pub fn set_profession_override(
     })
 }
 
-/// Get all trainer rank checkpoints for a character, sorted by timestamp.
-/// Used for the checkpoint progression timeline graph.
+/// Get all trainer rank checkpoints for a character (includes merged sources), sorted by
+/// timestamp. Used for the checkpoint progression timeline graph.
 #[tauri::command]
 pub fn get_all_trainer_checkpoints(
     char_id: i64,
     state: State<'_, AppState>,
 ) -> Result<Vec<amanuensis_core::models::TrainerCheckpoint>, String> {
     state.with_db(|db| {
-        db.get_all_trainer_checkpoints(char_id)
+        db.get_all_trainer_checkpoints_merged(char_id)
             .map_err(|e| e.to_string())
     })
 }
 
-/// Get the most recent trainer rank checkpoint for each trainer for a character.
+/// Get the most recent trainer rank checkpoint for each trainer for a character (includes
+/// merged sources).
 #[tauri::command]
 pub fn get_trainer_checkpoints(
     char_id: i64,
     state: State<'_, AppState>,
 ) -> Result<Vec<amanuensis_core::models::TrainerCheckpoint>, String> {
     state.with_db(|db| {
-        db.get_latest_trainer_checkpoints(char_id)
+        db.get_latest_trainer_checkpoints_merged(char_id)
             .map_err(|e| e.to_string())
     })
 }
+
+/// Apply hypothetical modified-rank changes in memory and return the resulting effective
+/// ranks and fighter stats. Nothing is written to the database — the GUI uses this for a
+/// live preview while the user edits ranks, then calls `set_modified_ranks` to save.
+/// Changes naming a trainer the character has no row for are applied to a fresh row.
+#[tauri::command]
+pub fn preview_effective_ranks(
+    char_id: i64,
+    changes: Vec<RankChange>,
+    state: State<'_, AppState>,
+) -> Result<RankPreview, String> {
+    let mut trainers = state.with_db(|db| {
+        db.get_trainers_merged(char_id).map_err(|e| e.to_string())
+    })?;
+    let trainer_db = TrainerDb::bundled().map_err(|e| e.to_string())?;
+
+    for change in changes {
+        match trainers.iter_mut().find(|t| t.trainer_name == change.trainer_name) {
+            Some(t) => t.modified_ranks = change.modified_ranks,
+            None => {
+                let mut t = Trainer::new(char_id, change.trainer_name);
+                t.modified_ranks = change.modified_ranks;
+                t.effective_multiplier = trainer_db.get_multiplier(&t.trainer_name);
+                trainers.push(t);
+            }
+        }
+    }
+
+    let ranks = fighter_rank_map(&trainers);
+    let multipliers: HashMap<String, f64> = trainers
+        .iter()
+        .filter(|t| ranks.contains_key(&t.trainer_name))
+        .map(|t| (t.trainer_name.clone(), t.effective_multiplier))
+        .collect();
+    let loadout = state.with_db(|db| {
+        db.get_fighter_loadout(char_id).map_err(|e| e.to_string())
+    })?;
+    let resolved = FighterModifiers::bundled()
+        .and_then(|m| m.resolve(&loadout.unwrap_or_default()))
+        .map_err(|e| e.to_string())?;
+    let fighter_stats = compute_fighter_stats_with(&ranks, &multipliers, &resolved);
+
+    Ok(RankPreview {
+        total_ranks: fighter_stats.trained_ranks,
+        effective_ranks: fighter_stats.effective_ranks,
+        trainers,
+        fighter_stats,
+    })
+}
+
+/// What-if simulator: add ranks per trainer (trainer name -> ranks, negative to remove) to
+/// the character's current effective ranks and return the fighter stats before and after,
+/// for the saved loadout. Nothing is written to the database.
+#[tauri::command]
+pub fn simulate_rank_additions(
+    char_id: i64,
+    deltas: BTreeMap<String, i64>,
+    state: State<'_, AppState>,
+) -> Result<StatsSimulation, String> {
+    let (trainers, loadout) = state.with_db(|db| {
+        let trainers = db.get_trainers_merged(char_id).map_err(|e| e.to_string())?;
+        let loadout = db.get_fighter_loadout(char_id).map_err(|e| e.to_string())?;
+        Ok((trainers, loadout))
+    })?;
+    let trainer_db = TrainerDb::bundled().map_err(|e| e.to_string())?;
+
+    let ranks = fighter_rank_map(&trainers);
+    let mut multipliers: HashMap<String, f64> = HashMap::new();
+    for name in trainers.iter().map(|t| &t.trainer_name).chain(deltas.keys()) {
+        multipliers.insert(name.clone(), trainer_db.get_multiplier(name));
+    }
+    let resolved = FighterModifiers::bundled()
+        .and_then(|m| m.resolve(&loadout.unwrap_or_default()))
+        .map_err(|e| e.to_string())?;
+    Ok(simulate_stats_with(&ranks, &deltas, &multipliers, &resolved))
+}
@@ -0,0 +1,79 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::state::AppState;
+
+/// Where the roster report goes and how often it may be rewritten.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportScheduleConfig {
+    pub folder: String,
+    pub html: bool,
+    pub json: bool,
+    /// Minimum minutes between rewrites; 0 rewrites after every scan.
+    pub min_interval_minutes: u64,
+}
+
+/// An active schedule and when it last wrote.
+pub struct ReportSchedule {
+    pub config: ReportScheduleConfig,
+    pub last_written: Option<Instant>,
+}
+
+/// Set (or clear, with `None`) the report schedule. A new schedule writes the report right
+/// away when a database is open, returning the files written, so a bad folder fails here
+/// rather than silently after the next scan.
+#[tauri::command]
+pub fn set_report_schedule(
+    config: Option<ReportScheduleConfig>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let mut schedule = state
+        .report_schedule
+        .lock()
+        .map_err(|e| format!("Lock poisoned: {e}"))?;
+    let Some(config) = config else {
+        *schedule = None;
+        return Ok(Vec::new());
+    };
+    if !config.html && !config.json {
+        return Err("Choose HTML, JSON or both".to_string());
+    }
+    let db = state.db.lock().map_err(|e| format!("Database lock poisoned: {e}"))?;
+    let written = match db.as_ref() {
+        Some(db) => db
+            .write_roster_report(Path::new(&config.folder), config.html, config.json)
+            .map_err(|e| e.to_string())?,
+        None => Vec::new(),
+    };
+    let last_written = (!written.is_empty()).then(Instant::now);
+    *schedule = Some(ReportSchedule { config, last_written });
+    Ok(written.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+/// Rewrite the scheduled report after a scan, unless it was written within the schedule's
+/// interval. Failures go to the process log; they never fail the scan.
+pub(super) fn write_scheduled_reports(state: &AppState) {
+    let Ok(mut schedule) = state.report_schedule.lock() else {
+        return;
+    };
+    let Some(schedule) = schedule.as_mut() else {
+        return;
+    };
+    let interval = Duration::from_secs(schedule.config.min_interval_minutes * 60);
+    if schedule.last_written.is_some_and(|t| t.elapsed() < interval) {
+        return;
+    }
+    let config = schedule.config.clone();
+    let _ = state.with_db(|db| {
+        match db.write_roster_report(Path::new(&config.folder), config.html, config.json) {
+            Ok(_) => schedule.last_written = Some(Instant::now()),
+            Err(e) => {
+                let _ = db.add_process_log("error", &format!("Scheduled report failed: {e}"));
+            }
+        }
+        Ok(())
+    });
+}
@@ -1,19 +1,19 @@
-use std::path::PathBuf;
 use tauri::State;
-use tauri::Emitter;
-use amanuensis_core::parser::{LogParser, ScanResult};
+use amanuensis_core::parser::ScanResult;
 use crate::state::AppState;
-use super::{run_scan, ScanOp, ScanProgress, SourceSpec};
+use super::{run_scan, source_folders, ScanOp, SourceSpec};
 
 /// Scan a log folder, emitting progress events.
 /// When `recursive` is true, recursively discovers log root folders under `folder`.
 /// When `index_lines` is true, raw log lines are stored in the FTS5 index for search.
+/// When `nice` is true, the scan runs at low priority (see `LogParser::set_nice`).
 #[tauri::command]
 pub async fn scan_logs(
     folder: String,
     force: bool,
     recursive: bool,
     index_lines: bool,
+    nice: bool,
     app: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<ScanResult, String> {
@@ -22,7 +22,7 @@ pub async fn scan_logs(
         app,
         ScanOp::Folder { path: folder, force, recursive },
         index_lines,
-        false,
+        nice,
     )
     .await
 }
@@ -33,6 +33,7 @@ pub async fn scan_files(
     files: Vec<String>,
     force: bool,
     index_lines: bool,
+    nice: bool,
     app: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<ScanResult, String> {
@@ -41,7 +42,7 @@ pub async fn scan_files(
         app,
         ScanOp::Files { files, force },
         index_lines,
-        false,
+        nice,
     )
     .await
 }
@@ -52,39 +53,12 @@ pub async fn scan_files(
 pub async fn rescan_logs(
     sources: Vec<SourceSpec>,
     index_lines: bool,
+    nice: bool,
     app: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<ScanResult, String> {
-    let db = state.take_db()?;
-    let state_db = state.db.clone();
-    let folders: Vec<(std::path::PathBuf, bool)> = sources
-        .into_iter()
-        .map(|s| (std::path::PathBuf::from(s.path), s.recursive))
-        .collect();
-
-    let result = tauri::async_runtime::spawn_blocking(move || {
-        let parser = LogParser::new(db).map_err(|e| e.to_string())?;
-        let app_handle = app.clone();
-        let progress_cb = |current: usize, total: usize, filename: &str| {
-            let _ = app_handle.emit(
-                "scan-progress",
-                ScanProgress {
-                    current_file: current,
-                    total_files: total,
-                    filename: filename.to_string(),
-                },
-            );
-        };
-        let result = parser
-            .rescan_sources(&folders, index_lines, progress_cb)
-            .map_err(|e| e.to_string())?;
-        *state_db.lock().map_err(|e| format!("Lock poisoned: {e}"))? = Some(parser.into_db());
-        Ok(result)
-    })
-    .await
-    .map_err(|e| e.to_string())?;
-
-    result
+    let folders = source_folders(sources);
+    run_scan(&state, app, ScanOp::Sources { folders, reset: true }, index_lines, nice).await
 }
 
 /// Count the log files an incremental Update would touch right now (new + grown), without
@@ -95,10 +69,7 @@ pub async fn get_pending_log_count(
     sources: Vec<SourceSpec>,
     state: State<'_, AppState>,
 ) -> Result<usize, String> {
-    let folders: Vec<(PathBuf, bool)> = sources
-        .into_iter()
-        .map(|s| (PathBuf::from(s.path), s.recursive))
-        .collect();
+    let folders = source_folders(sources);
     state.with_db(|db| {
         amanuensis_core::parser::pending_files(db, &folders)
             .map(|v| v.len())
@@ -111,37 +82,18 @@ pub async fn get_pending_log_count(
 pub async fn update_logs(
     sources: Vec<SourceSpec>,
     index_lines: bool,
+    nice: bool,
     app: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<ScanResult, String> {
-    let db = state.take_db()?;
-    let state_db = state.db.clone();
-    let folders: Vec<(PathBuf, bool)> = sources
-        .into_iter()
-        .map(|s| (PathBuf::from(s.path), s.recursive))
-        .collect();
-
-    let result = tauri::async_runtime::spawn_blocking(move || {
-        let parser = LogParser::new(db).map_err(|e| e.to_string())?;
-        let app_handle = app.clone();
-        let progress_cb = |current: usize, total: usize, filename: &str| {
-            let _ = app_handle.emit(
-                "scan-progress",
-                ScanProgress {
-                    current_file: current,
-                    total_files: total,
-                    filename: filename.to_string(),
-                },
-            );
-        };
-        let result = parser
-            .update_sources(&folders, index_lines, progress_cb)
-            .map_err(|e| e.to_string())?;
-        *state_db.lock().map_err(|e| format!("Lock poisoned: {e}"))? = Some(parser.into_db());
-        Ok(result)
-    })
-    .await
-    .map_err(|e| e.to_string())?;
+    let folders = source_folders(sources);
+    run_scan(&state, app, ScanOp::Sources { folders, reset: false }, index_lines, nice).await
+}
 
-    result
+/// Stop the running scan before its next file. Its in-flight transaction is rolled back and
+/// the scan command fails with a `[cancelled]` error; sources an update or rescan already
+/// finished stay committed. Returns false when no scan is running.
+#[tauri::command]
+pub fn cancel_scan(state: State<'_, AppState>) -> bool {
+    state.cancel_scan()
 }
@@ -18,9 +18,13 @@ fn main() {
             commands::get_character_merged,
             commands::get_kills,
             commands::export_kills,
+            commands::export_trainers,
+            commands::export_character_summary,
             commands::get_trainers,
             commands::set_modified_ranks,
             commands::set_rank_override,
+            commands::preview_effective_ranks,
+            commands::simulate_rank_additions,
             commands::get_pets,
             commands::get_lastys,
             commands::get_scanned_log_count,
@@ -29,22 +33,34 @@ fn main() {
             commands::rescan_logs,
             commands::scan_files,
             commands::update_logs,
+            commands::cancel_scan,
             commands::get_pending_log_count,
             commands::clear_rank_overrides,
             commands::set_profession_override,
             commands::import_scribius_db,
             commands::get_default_db_path,
+            commands::build_demo_database,
             commands::check_db_exists,
             commands::reset_database,
             commands::delete_all_data,
             commands::search_logs,
             commands::get_log_line_count,
+            commands::get_index_status,
+            commands::set_indexing_enabled,
             commands::get_process_logs,
+            commands::get_recent_errors,
+            commands::export_diagnostics,
+            commands::get_clan_summary,
+            commands::get_character_summary,
+            commands::compare_characters,
             commands::merge_characters,
+            commands::preview_merge,
             commands::unmerge_character,
             commands::get_merge_sources,
             commands::fetch_character_portrait,
             commands::get_character_portrait_path,
+            commands::get_stored_character_portrait,
+            commands::set_character_portrait,
             commands::check_for_update,
             commands::reveal_database,
             commands::get_trainer_checkpoints,
@@ -53,6 +69,11 @@ fn main() {
             commands::get_bestiary,
             commands::get_encountered_creatures,
             commands::get_kill_frequency,
+            commands::get_accuracy,
+            commands::get_heals,
+            commands::get_stone_usage,
+            commands::get_economy,
+            commands::set_report_schedule,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
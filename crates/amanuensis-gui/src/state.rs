@@ -1,11 +1,18 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use amanuensis_core::Database;
 
+use crate::commands::ReportSchedule;
+
 /// Application state shared across Tauri commands.
 pub struct AppState {
     pub db: Arc<Mutex<Option<Database>>>,
     pub db_path: Mutex<Option<String>>,
+    /// Roster report rewritten after scans (see `commands::set_report_schedule`).
+    pub report_schedule: Mutex<Option<ReportSchedule>>,
+    /// Cancel flag of the scan in progress, if any (see `commands::cancel_scan`).
+    pub scan_cancel: Mutex<Option<Arc<AtomicBool>>>,
 }
 
 impl AppState {
@@ -13,6 +20,8 @@ impl AppState {
         Self {
             db: Arc::new(Mutex::new(None)),
             db_path: Mutex::new(None),
+            report_schedule: Mutex::new(None),
+            scan_cancel: Mutex::new(None),
         }
     }
 
@@ -39,4 +48,32 @@ impl AppState {
             .map_err(|e| format!("Database lock poisoned: {e}"))?;
         guard.take().ok_or("No database open".to_string())
     }
+
+    /// Register a fresh cancel flag for a scan that is about to start.
+    pub fn begin_scan(&self) -> Result<Arc<AtomicBool>, String> {
+        let flag = Arc::new(AtomicBool::new(false));
+        *self
+            .scan_cancel
+            .lock()
+            .map_err(|e| format!("Scan lock poisoned: {e}"))? = Some(flag.clone());
+        Ok(flag)
+    }
+
+    /// Forget the finished scan's cancel flag.
+    pub fn end_scan(&self) {
+        if let Ok(mut guard) = self.scan_cancel.lock() {
+            *guard = None;
+        }
+    }
+
+    /// Ask the running scan to stop. Returns false when no scan is running.
+    pub fn cancel_scan(&self) -> bool {
+        match self.scan_cancel.lock().ok().and_then(|guard| guard.clone()) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
 }
@@ -4,9 +4,11 @@ import { ProfessionBadge } from "../shared/ProfessionBadge";
 
 interface CharacterListProps {
   onSelectCharacter: (charId: number) => Promise<void>;
+  /** Open the bundled demo dataset (offered while there are no characters). */
+  onOpenDemo?: () => Promise<void>;
 }
 
-export function CharacterList({ onSelectCharacter }: CharacterListProps) {
+export function CharacterList({ onSelectCharacter, onOpenDemo }: CharacterListProps) {
   const {
     characters,
     selectedCharacterId,
@@ -89,8 +91,22 @@ export function CharacterList({ onSelectCharacter }: CharacterListProps) {
                 <ProfessionBadge profession={char.profession} />
                 <span className="text-[11px] text-[var(--color-text-muted)]">
                   {char.total_ranks > 0 ? `${char.total_ranks} ranks` : "0 ranks"}
+                  {char.total_kills > 0 && ` · ${char.total_kills.toLocaleString()} kills`}
                 </span>
+                {char.unscanned_files > 0 && (
+                  <span
+                    className="text-[11px] text-amber-400"
+                    title={`${char.unscanned_files} new or grown log file(s); Update Logs reads them`}
+                  >
+                    ⚠
+                  </span>
+                )}
               </div>
+              {char.last_played && (
+                <div className="text-[10px] text-[var(--color-text-muted)]">
+                  Last played {char.last_played.slice(0, 10)}
+                </div>
+              )}
             </div>
           </button>
         ))}
@@ -99,11 +115,27 @@ export function CharacterList({ onSelectCharacter }: CharacterListProps) {
             No characters found.
             <br />
             Scan logs to get started.
+            {onOpenDemo && (
+              <>
+                <br />
+                <button onClick={onOpenDemo} className="mt-2 text-[var(--color-accent)] hover:underline">
+                  Try the demo
+                </button>
+              </>
+            )}
           </div>
         )}
         {!dbPath && (
           <div className="p-3 text-center text-xs text-[var(--color-text-muted)]">
             Scan logs to get started.
+            {onOpenDemo && (
+              <>
+                <br />
+                <button onClick={onOpenDemo} className="mt-2 text-[var(--color-accent)] hover:underline">
+                  Try the demo
+                </button>
+              </>
+            )}
           </div>
         )}
       </div>
@@ -11,16 +11,21 @@ import { SourcesDialog } from "../shared/SourcesDialog";
 import { UpdateResultDialog } from "../shared/UpdateResultDialog";
 import type { Theme } from "../../lib/store";
 
+function formatBytes(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${Math.max(1, Math.round(bytes / 1024))} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 export function Sidebar() {
-  const { dbPath, sources, scannedLogCount, recursiveScan, setRecursiveScan, indexLogLines, setIndexLogLines, theme, setTheme, characters, setCharacters, pendingLogCount, updateResult, setUpdateResult } = useStore();
+  const { dbPath, sources, reportSchedule, scannedLogCount, recursiveScan, setRecursiveScan, indexLogLines, indexStatus, niceScan, setNiceScan, theme, setTheme, characters, setCharacters, pendingLogCount, updateResult, setUpdateResult } = useStore();
 
   const [advancedOpen, setAdvancedOpen] = useState(false);
   const [showMergeDialog, setShowMergeDialog] = useState(false);
   const [showSourcesDialog, setShowSourcesDialog] = useState(false);
 
-  const { handleOpenDb, handleReset, handleDeleteAll, handleImportScribius, handleSelectCharacter, ensureDb, isScanning } = useDatabase();
+  const { handleOpenDb, handleOpenDemo, handleReset, handleDeleteAll, handleImportScribius, handlePublishReports, handleToggleIndexing, handleSelectCharacter, ensureDb, isScanning } = useDatabase();
 
-  const { scanProgress, handleScanFolder, handleScanFiles, handleRescanLogs, handleUpdateLogs } = useScan(
+  const { scanProgress, handleScanFolder, handleScanFiles, handleRescanLogs, handleUpdateLogs, handleCancelScan } = useScan(
     async (chars) => {
       if (chars.length > 0 && chars[0].id !== null) {
         await handleSelectCharacter(chars[0].id);
@@ -107,6 +112,13 @@ export function Sidebar() {
             <button onClick={handleImportScribius} disabled={isScanning} className="rounded border border-[var(--color-border)] bg-[var(--color-btn-secondary)] px-3 py-1.5 text-sm font-medium hover:opacity-80 disabled:opacity-50">
               Import Scribius DB
             </button>
+            <button
+              onClick={handlePublishReports}
+              className="rounded border border-[var(--color-border)] bg-[var(--color-btn-secondary)] px-3 py-1.5 text-sm font-medium hover:opacity-80"
+              title={reportSchedule ? `Writing reports to ${reportSchedule.folder}` : "Rewrite an HTML/JSON roster into a folder after every scan"}
+            >
+              {reportSchedule ? "Stop Publishing Reports" : "Publish Reports…"}
+            </button>
             {characters.length >= 2 && (
               <button
                 onClick={() => setShowMergeDialog(true)}
@@ -117,9 +129,18 @@ export function Sidebar() {
               </button>
             )}
             <label className="flex items-center gap-1.5 text-xs text-[var(--color-text-muted)]">
-              <input type="checkbox" checked={indexLogLines} onChange={(e) => setIndexLogLines(e.target.checked)} disabled={isScanning} className="accent-[var(--color-accent)]" />
+              <input type="checkbox" checked={indexLogLines} onChange={(e) => handleToggleIndexing(e.target.checked)} disabled={isScanning} className="accent-[var(--color-accent)]" />
               Index logs for search
             </label>
+            {indexStatus && indexStatus.available && indexStatus.lines > 0 && (
+              <div className="text-[10px] text-[var(--color-text-muted)]/60 leading-tight">
+                {indexStatus.lines.toLocaleString()} lines · {indexStatus.characters} character{indexStatus.characters === 1 ? "" : "s"} · ~{formatBytes(indexStatus.estimated_bytes)}
+              </div>
+            )}
+            <label className="flex items-center gap-1.5 text-xs text-[var(--color-text-muted)]" title="Scan one file at a time with short pauses and a small cache, so a background scan doesn't slow down the game">
+              <input type="checkbox" checked={niceScan} onChange={(e) => setNiceScan(e.target.checked)} disabled={isScanning} className="accent-[var(--color-accent)]" />
+              Low-priority scanning
+            </label>
             <div className="text-[10px] text-[var(--color-text-muted)]/60 leading-tight">
               Characters are detected automatically from log content. Mixed-character folders are supported.
             </div>
@@ -168,11 +189,17 @@ export function Sidebar() {
       {isScanning && scanProgress && (
         <div className="border-b border-[var(--color-border)] p-3">
           <ProgressBar current={scanProgress.current_file} total={scanProgress.total_files} label={scanProgress.filename} />
+          <button
+            onClick={handleCancelScan}
+            className="mt-2 w-full rounded border border-[var(--color-border)] px-3 py-1 text-xs text-[var(--color-text-muted)] hover:bg-[var(--color-card)]"
+          >
+            Cancel Scan
+          </button>
         </div>
       )}
 
       {/* Character list */}
-      <CharacterList onSelectCharacter={handleSelectCharacter} />
+      <CharacterList onSelectCharacter={handleSelectCharacter} onOpenDemo={isScanning ? undefined : handleOpenDemo} />
 
       {showMergeDialog && (
         <MergeDialog
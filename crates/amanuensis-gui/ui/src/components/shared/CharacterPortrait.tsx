@@ -1,22 +1,41 @@
 import { useState, useEffect, useRef } from "react";
+import { open } from "@tauri-apps/plugin-dialog";
 import {
   getCharacterPortraitPath,
   fetchCharacterPortrait,
+  getStoredCharacterPortrait,
+  setCharacterPortrait,
 } from "../../lib/commands";
 
 interface CharacterPortraitProps {
   name: string;
+  /** When set, a portrait stored in the database wins over the Rank Tracker avatar,
+   *  and the user can attach or reset one. */
+  charId?: number | null;
   className?: string;
 }
 
-export function CharacterPortrait({ name, className }: CharacterPortraitProps) {
+export function CharacterPortrait({ name, charId, className }: CharacterPortraitProps) {
   const [src, setSrc] = useState<string | null>(null);
+  const [stored, setStored] = useState(false);
+  const [reloadKey, setReloadKey] = useState(0);
   const fetchedRef = useRef<string | null>(null);
 
   useEffect(() => {
     let cancelled = false;
 
     async function load() {
+      // A portrait attached by the user is used as-is
+      if (charId != null) {
+        const own = await getStoredCharacterPortrait(charId);
+        if (cancelled) return;
+        if (own) {
+          setSrc(own);
+          setStored(true);
+          return;
+        }
+      }
+
       // Show cached version immediately if available
       const cached = await getCharacterPortraitPath(name);
       if (!cancelled && cached) {
@@ -34,22 +53,65 @@ export function CharacterPortrait({ name, className }: CharacterPortraitProps) {
     }
 
     setSrc(null);
+    setStored(false);
     fetchedRef.current = null;
     load().catch(() => {});
 
     return () => {
       cancelled = true;
     };
-  }, [name]);
+  }, [name, charId, reloadKey]);
 
-  if (!src) return null;
+  async function handleSet() {
+    if (charId == null) return;
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Images", extensions: ["png", "jpg", "jpeg", "gif", "webp"] }],
+      title: "Select Portrait",
+    });
+    if (!selected || Array.isArray(selected)) return;
+    try {
+      await setCharacterPortrait(charId, selected);
+      setReloadKey((k) => k + 1);
+    } catch (e) {
+      console.error("Setting portrait failed:", e);
+    }
+  }
 
-  return (
+  async function handleReset() {
+    if (charId == null) return;
+    try {
+      await setCharacterPortrait(charId, null);
+      setReloadKey((k) => k + 1);
+    } catch (e) {
+      console.error("Resetting portrait failed:", e);
+    }
+  }
+
+  const image = src ? (
     <img
       src={src}
       alt={`${name} portrait`}
       className={className ?? "h-16 w-auto rounded-lg"}
       style={{ imageRendering: "auto" }}
     />
+  ) : null;
+
+  if (charId == null) return image;
+
+  return (
+    <div className="flex h-full flex-col items-center justify-center gap-2">
+      {image}
+      <div className="flex gap-2 text-xs text-[var(--color-text-muted)]">
+        <button className="hover:text-[var(--color-text)]" onClick={handleSet}>
+          Set portrait…
+        </button>
+        {stored && (
+          <button className="hover:text-[var(--color-text)]" onClick={handleReset}>
+            Reset
+          </button>
+        )}
+      </div>
+    </div>
   );
 }
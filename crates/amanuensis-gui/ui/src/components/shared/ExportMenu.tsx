@@ -0,0 +1,65 @@
+import { save, message } from "@tauri-apps/plugin-dialog";
+
+export type ExportFormat = "csv" | "json" | "text";
+
+const FORMATS: Record<ExportFormat, { label: string; ext: string; filter: string }> = {
+  csv: { label: "CSV file (.csv)", ext: "csv", filter: "CSV" },
+  json: { label: "JSON file (.json)", ext: "json", filter: "JSON" },
+  text: { label: "Plain text (.txt)", ext: "txt", filter: "Text" },
+};
+
+/**
+ * Ask for a destination with the save dialog, then run `write` (one of the `export_*`
+ * commands) and report the outcome. Does nothing if the dialog is cancelled.
+ */
+export async function saveExport(
+  what: string,
+  baseName: string,
+  format: ExportFormat,
+  write: (path: string) => Promise<void>,
+) {
+  const { ext, filter } = FORMATS[format];
+  const path = await save({
+    title: `Export ${what}`,
+    defaultPath: `${baseName}.${ext}`,
+    filters: [{ name: filter, extensions: [ext] }],
+  });
+  if (!path) return; // user cancelled
+  try {
+    await write(path);
+    await message(`Exported ${what.toLowerCase()} to ${path}.`, { title: "Export Complete" });
+  } catch (e) {
+    await message(String(e), { title: "Export Failed", kind: "error" });
+  }
+}
+
+interface ExportMenuProps {
+  onExport: (format: ExportFormat) => void;
+  formats?: ExportFormat[];
+}
+
+/** "Export ▾" dropdown with one entry per format. */
+export function ExportMenu({ onExport, formats = ["csv", "json", "text"] }: ExportMenuProps) {
+  return (
+    <details className="relative">
+      <summary className="cursor-pointer select-none rounded border border-[var(--color-border)] px-3 py-1 text-sm">
+        Export ▾
+      </summary>
+      <div className="absolute right-0 z-10 mt-1 w-44 rounded border border-[var(--color-border)] bg-[var(--color-bg)] shadow">
+        {formats.map((format) => (
+          <button
+            key={format}
+            type="button"
+            className="block w-full px-3 py-2 text-left text-sm hover:bg-[var(--color-bg-hover)]"
+            onClick={(e) => {
+              (e.currentTarget.closest("details") as HTMLDetailsElement)?.removeAttribute("open");
+              onExport(format);
+            }}
+          >
+            {FORMATS[format].label}
+          </button>
+        ))}
+      </div>
+    </details>
+  );
+}
@@ -1,5 +1,5 @@
 import { useState, useCallback } from "react";
-import { mergeCharacters } from "../../lib/commands";
+import { errorMessage, mergeCharacters } from "../../lib/commands";
 import { ProfessionBadge } from "./ProfessionBadge";
 import type { Character } from "../../types";
 
@@ -40,7 +40,7 @@ export function MergeDialog({ characters, onClose, onMerged }: MergeDialogProps)
       await mergeCharacters(sourceIds, primaryId);
       onMerged();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsMerging(false);
     }
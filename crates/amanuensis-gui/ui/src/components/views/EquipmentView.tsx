@@ -1,8 +1,25 @@
+import { useEffect, useState } from "react";
 import { useStore } from "../../lib/store";
+import { getStoneUsage } from "../../lib/commands";
 import { StatCard } from "../shared/StatCard";
+import type { StoneUsage } from "../../types";
+
+const STONE_LABELS: Record<StoneUsage["stone"], string> = {
+  darkstone: "Darkstone",
+  moonstone: "Moonstone",
+};
 
 export function EquipmentView() {
   const { characters, selectedCharacterId } = useStore();
+  const [stones, setStones] = useState<StoneUsage[]>([]);
+
+  useEffect(() => {
+    if (selectedCharacterId == null) return;
+    getStoneUsage(selectedCharacterId)
+      .then(setStones)
+      .catch((err) => console.error("Failed to load stone usage:", err));
+  }, [selectedCharacterId]);
+
   const char = characters.find((c) => c.id === selectedCharacterId);
   if (!char) return null;
 
@@ -33,6 +50,18 @@ export function EquipmentView() {
           label="Purgatory Pendant"
           value={char.purgatory_pendant.toLocaleString()}
         />
+        {stones.map((s) => (
+          <StatCard
+            key={s.stone}
+            label={`${STONE_LABELS[s.stone]} Uses`}
+            value={s.used.toLocaleString()}
+            sub={[
+              `${s.charged} charged`,
+              s.expired > 0 ? `${s.expired} expired` : null,
+              s.charges_left != null ? `${s.charges_left} charges left` : null,
+            ].filter(Boolean).join(" · ")}
+          />
+        ))}
         <StatCard
           label="Ore Found"
           value={char.ore_found.toLocaleString()}
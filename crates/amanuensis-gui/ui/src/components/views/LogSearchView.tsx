@@ -1,8 +1,19 @@
 import { useState, useCallback } from "react";
 import { useStore } from "../../lib/store";
-import { searchLogs } from "../../lib/commands";
+import { errorKind, searchLogs } from "../../lib/commands";
 import type { LogSearchResult } from "../../types";
 
+const PAGE_SIZE = 100;
+
+/** The parameters of the search on screen, reused to fetch further pages. */
+interface ActiveSearch {
+  query: string;
+  charId: number | null;
+  includeSpeech: boolean;
+  linesBefore: number;
+  linesAfter: number;
+}
+
 export function LogSearchView() {
   const { selectedCharacterId, logLineCount } = useStore();
 
@@ -13,26 +24,54 @@ export function LogSearchView() {
   const [linesAfter, setLinesAfter] = useState(0);
   const [results, setResults] = useState<LogSearchResult[]>([]);
   const [resultCount, setResultCount] = useState<number | null>(null);
+  const [hasMore, setHasMore] = useState(false);
+  const [activeSearch, setActiveSearch] = useState<ActiveSearch | null>(null);
   const [isSearching, setIsSearching] = useState(false);
+  const [indexUnavailable, setIndexUnavailable] = useState(false);
 
-  const handleSearch = useCallback(async () => {
-    const trimmed = query.trim();
-    if (!trimmed) return;
-
+  const runSearch = useCallback(async (search: ActiveSearch, offset: number) => {
     setIsSearching(true);
     try {
-      const charId = scope === "character" ? selectedCharacterId : null;
-      const res = await searchLogs(trimmed, charId, 200, includeSpeech, linesBefore, linesAfter);
-      setResults(res);
-      setResultCount(res.length);
+      const page = await searchLogs(
+        search.query,
+        search.charId,
+        PAGE_SIZE,
+        search.includeSpeech,
+        search.linesBefore,
+        search.linesAfter,
+        offset,
+      );
+      setResults((prev) => (offset === 0 ? page.results : [...prev, ...page.results]));
+      setResultCount(page.total);
+      setHasMore(page.has_more);
     } catch (e) {
       console.error("Search failed:", e);
+      setIndexUnavailable(errorKind(e) === "fts_unavailable");
       setResults([]);
       setResultCount(0);
+      setHasMore(false);
     } finally {
       setIsSearching(false);
     }
-  }, [query, scope, selectedCharacterId, includeSpeech, linesBefore, linesAfter]);
+  }, []);
+
+  const handleSearch = useCallback(async () => {
+    const trimmed = query.trim();
+    if (!trimmed) return;
+    const search: ActiveSearch = {
+      query: trimmed,
+      charId: scope === "character" ? selectedCharacterId : null,
+      includeSpeech,
+      linesBefore,
+      linesAfter,
+    };
+    setActiveSearch(search);
+    await runSearch(search, 0);
+  }, [query, scope, selectedCharacterId, includeSpeech, linesBefore, linesAfter, runSearch]);
+
+  const handleLoadMore = useCallback(() => {
+    if (activeSearch) runSearch(activeSearch, results.length);
+  }, [activeSearch, results.length, runSearch]);
 
   const handleKeyDown = useCallback(
     (e: React.KeyboardEvent) => {
@@ -41,7 +80,7 @@ export function LogSearchView() {
     [handleSearch],
   );
 
-  if (logLineCount === 0) {
+  if (logLineCount === 0 || indexUnavailable) {
     return (
       <div className="flex flex-col items-center justify-center py-16 text-[var(--color-text-muted)]">
         <div className="text-lg font-medium">No log lines indexed</div>
@@ -123,7 +162,7 @@ export function LogSearchView() {
         <div className="mt-2 text-xs text-[var(--color-text-muted)]">
           {resultCount === 0
             ? "No matching log lines found"
-            : `${resultCount} result${resultCount === 1 ? "" : "s"} found${resultCount >= 200 ? " (showing first 200)" : ""}`}
+            : `${resultCount} result${resultCount === 1 ? "" : "s"} found${hasMore ? ` (showing ${results.length})` : ""}`}
         </div>
       )}
 
@@ -155,10 +194,17 @@ export function LogSearchView() {
                 ))}
               </div>
             )}
-            <div
-              className="text-sm leading-relaxed [&_mark]:rounded [&_mark]:bg-yellow-500/30 [&_mark]:px-0.5 [&_mark]:text-[var(--color-text)]"
-              dangerouslySetInnerHTML={{ __html: r.snippet }}
-            />
+            <div className="text-sm leading-relaxed">
+              {r.segments.map((seg, j) =>
+                seg.highlight ? (
+                  <mark key={j} className="rounded bg-yellow-500/30 px-0.5 text-[var(--color-text)]">
+                    {seg.text}
+                  </mark>
+                ) : (
+                  <span key={j}>{seg.text}</span>
+                ),
+              )}
+            </div>
             {r.context_after.length > 0 && (
               <div className="mt-1 border-l-2 border-[var(--color-border)] pl-2">
                 {r.context_after.map((line, j) => (
@@ -168,6 +214,17 @@ export function LogSearchView() {
             )}
           </div>
         ))}
+        {hasMore && (
+          <div className="py-2 text-center">
+            <button
+              onClick={handleLoadMore}
+              disabled={isSearching}
+              className="rounded border border-[var(--color-border)] px-4 py-1.5 text-sm text-[var(--color-text-muted)] hover:text-[var(--color-text)] disabled:opacity-50"
+            >
+              {isSearching ? "Loading..." : `Load ${Math.min(PAGE_SIZE, (resultCount ?? 0) - results.length)} more`}
+            </button>
+          </div>
+        )}
       </div>
     </div>
   );
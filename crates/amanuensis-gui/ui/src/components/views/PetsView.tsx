@@ -12,8 +12,40 @@ const columns = [
   }),
   columnHelper.accessor("creature_name", {
     header: "Creature",
+    cell: (info) => info.getValue() || "-",
+  }),
+  columnHelper.accessor("status", {
+    header: "Status",
+    cell: (info) => info.getValue(),
+  }),
+  columnHelper.accessor("adopted_date", {
+    header: "Adopted",
+    cell: (info) => info.getValue() ?? "-",
+  }),
+  columnHelper.accessor("ranks", {
+    header: "Ranks",
     cell: (info) => info.getValue(),
   }),
+  columnHelper.accessor("last_trained_date", {
+    header: "Last Trained",
+    cell: (info) => info.getValue() ?? "-",
+  }),
+  columnHelper.accessor("feed_count", {
+    header: "Fed",
+    cell: (info) => info.getValue(),
+  }),
+  columnHelper.accessor("last_fed_date", {
+    header: "Last Fed",
+    cell: (info) => info.getValue() ?? "-",
+  }),
+  columnHelper.accessor("death_count", {
+    header: "Deaths",
+    cell: (info) => info.getValue(),
+  }),
+  columnHelper.accessor("released_date", {
+    header: "Released",
+    cell: (info) => info.getValue() ?? "-",
+  }),
 ];
 
 export function PetsView() {
@@ -1,5 +1,7 @@
 import { useState } from "react";
+import { save, message } from "@tauri-apps/plugin-dialog";
 import { useStore } from "../../lib/store";
+import { exportDiagnostics } from "../../lib/commands";
 
 const LEVEL_STYLES: Record<string, string> = {
   error: "bg-red-500/20 text-red-400",
@@ -27,6 +29,33 @@ export function ProcessLogsView() {
     }
   };
 
+  const handleExportDiagnostics = async () => {
+    const path = await save({
+      title: "Export Diagnostics",
+      defaultPath: "amanuensis-diagnostics.zip",
+      filters: [{ name: "Zip", extensions: ["zip"] }],
+    });
+    if (!path) return; // user cancelled
+    try {
+      await exportDiagnostics(path);
+      await message(`Saved diagnostics to ${path}. Attach this file to your bug report.`, {
+        title: "Export Complete",
+      });
+    } catch (e) {
+      await message(String(e), { title: "Export Failed", kind: "error" });
+    }
+  };
+
+  const diagnosticsButton = (
+    <button
+      onClick={handleExportDiagnostics}
+      className="rounded border border-[var(--color-border)] px-2 py-0.5 text-xs text-[var(--color-text-muted)] hover:bg-[var(--color-card)] hover:text-[var(--color-text)]"
+      title="Save scan errors, unrecognized log lines and app info as a zip for a bug report"
+    >
+      Export Diagnostics
+    </button>
+  );
+
   if (processLogs.length === 0) {
     return (
       <div className="flex flex-col items-center justify-center py-16 text-[var(--color-text-muted)]">
@@ -34,6 +63,7 @@ export function ProcessLogsView() {
         <div className="mt-2 max-w-sm text-center text-sm">
           Warnings and errors from your last scan will appear here.
         </div>
+        <div className="mt-4">{diagnosticsButton}</div>
       </div>
     );
   }
@@ -66,6 +96,7 @@ export function ProcessLogsView() {
           >
             {copied ? "Copied!" : "Copy All"}
           </button>
+          {diagnosticsButton}
         </div>
       </div>
       <div className="min-h-0 flex-1 overflow-y-auto space-y-1.5">
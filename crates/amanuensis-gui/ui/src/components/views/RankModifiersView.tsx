@@ -22,6 +22,7 @@ interface TrainerRow {
   ranks: number;
   modified_ranks: number;
   apply_learning_ranks: number;
+  apply_learning_estimated_ranks: number;
   multiplier: number;
   is_combo: boolean;
   combo_components: string[];
@@ -200,6 +201,7 @@ export function RankModifiersView() {
         ranks: existing?.ranks ?? 0,
         modified_ranks: existing?.modified_ranks ?? 0,
         apply_learning_ranks: existing?.apply_learning_ranks ?? 0,
+        apply_learning_estimated_ranks: existing?.apply_learning_estimated_ranks ?? 0,
         multiplier: dbTrainer.multiplier,
         is_combo: dbTrainer.is_combo,
         combo_components: dbTrainer.combo_components,
@@ -434,7 +436,10 @@ export function RankModifiersView() {
                             Trainer
                           </th>
                           <th className="w-24 px-3 py-2 text-right font-medium">
-                            Log Ranks
+                            Counted
+                          </th>
+                          <th className="w-24 px-3 py-2 text-right font-medium">
+                            Applied
                           </th>
                           <th className="px-3 py-2 text-left font-medium">
                             Mode / Value
@@ -480,8 +485,11 @@ export function RankModifiersView() {
                               </td>
                               <td className={`px-3 py-1.5 text-right ${isNonModifier ? "opacity-40" : "text-[var(--color-text-muted)]"}`}>
                                 {t.ranks}
-                                {t.apply_learning_ranks > 0 && (
-                                  <span className="text-[var(--color-text-muted)]"> +{t.apply_learning_ranks}a</span>
+                              </td>
+                              <td className={`px-3 py-1.5 text-right ${isNonModifier ? "opacity-40" : "text-[var(--color-text-muted)]"}`}>
+                                {t.apply_learning_ranks}
+                                {t.apply_learning_estimated_ranks > 0 && (
+                                  <span title="Estimate from partial apply-learning (not included in Total)"> ~{t.apply_learning_estimated_ranks}</span>
                                 )}
                               </td>
                               <td className="px-3 py-1.5">
@@ -12,17 +12,19 @@ import {
   getMergeSources,
   unmergeCharacter,
   listCharacters,
-  getCharacterMerged,
+  getCharacterSummary,
   getKills,
   getTrainers,
   getPets,
   getLastys,
   setProfessionOverride,
+  exportCharacterSummary,
 } from "../../lib/commands";
+import { ExportMenu, saveExport, type ExportFormat } from "../shared/ExportMenu";
 import { computeKillStats } from "../../lib/killStats";
 import { computeFighterStats } from "../../lib/fighterStats";
 import { timeAgo } from "../../lib/timeAgo";
-import type { Character, TrainerInfo } from "../../types";
+import type { Character, CharacterSummary, TrainerInfo } from "../../types";
 
 export function SummaryView() {
   const {
@@ -39,7 +41,7 @@ export function SummaryView() {
   } = useStore();
   const [trainerDb, setTrainerDb] = useState<TrainerInfo[]>([]);
   const [mergeSources, setMergeSources] = useState<Character[]>([]);
-  const [mergedChar, setMergedChar] = useState<Character | null>(null);
+  const [summary, setSummary] = useState<CharacterSummary | null>(null);
 
   useEffect(() => {
     getTrainerDbInfo()
@@ -52,15 +54,22 @@ export function SummaryView() {
       getMergeSources(selectedCharacterId)
         .then(setMergeSources)
         .catch(() => setMergeSources([]));
-      getCharacterMerged(selectedCharacterId)
-        .then(setMergedChar)
-        .catch(() => setMergedChar(null));
     } else {
       setMergeSources([]);
-      setMergedChar(null);
     }
   }, [selectedCharacterId]);
 
+  // Re-fetched when kills or trainers reload (rescans, rank edits) so totals stay current.
+  useEffect(() => {
+    if (selectedCharacterId !== null) {
+      getCharacterSummary(selectedCharacterId)
+        .then(setSummary)
+        .catch(() => setSummary(null));
+    } else {
+      setSummary(null);
+    }
+  }, [selectedCharacterId, kills, trainers]);
+
   const handleUnmerge = useCallback(
     async (sourceId: number) => {
       try {
@@ -69,17 +78,17 @@ export function SummaryView() {
         const chars = await listCharacters();
         setCharacters(chars);
         if (selectedCharacterId !== null) {
-          // Reload merge sources and merged character stats
-          const [sources, mc, k, t, p, l] = await Promise.all([
+          // Reload merge sources and the merged summary
+          const [sources, sm, k, t, p, l] = await Promise.all([
             getMergeSources(selectedCharacterId),
-            getCharacterMerged(selectedCharacterId),
+            getCharacterSummary(selectedCharacterId),
             getKills(selectedCharacterId),
             getTrainers(selectedCharacterId),
             getPets(selectedCharacterId),
             getLastys(selectedCharacterId),
           ]);
           setMergeSources(sources);
-          setMergedChar(mc);
+          setSummary(sm);
           setKills(k);
           setTrainers(t);
           setPets(p);
@@ -108,10 +117,21 @@ export function SummaryView() {
     [selectedCharacterId, setCharacters],
   );
 
+  const handleExport = useCallback(
+    async (format: ExportFormat) => {
+      if (selectedCharacterId === null) return;
+      const charName = characters.find((c) => c.id === selectedCharacterId)?.name ?? "character";
+      await saveExport("Summary", `${charName}-summary`, format, (path) =>
+        exportCharacterSummary(selectedCharacterId, format, path),
+      );
+    },
+    [selectedCharacterId, characters],
+  );
+
   const baseChar = characters.find((c) => c.id === selectedCharacterId);
   if (!baseChar) return null;
   // Use merged stats when available (aggregated logins, deaths, etc.)
-  const char = mergedChar ?? baseChar;
+  const char = summary?.character ?? baseChar;
 
   const {
     totalSolo: totalKills,
@@ -146,12 +166,7 @@ export function SummaryView() {
   const displayCoinLevel = confirmedCoinLevel > 0 ? confirmedCoinLevel : char.coin_level_interim;
   const coinLevelEstimated = confirmedCoinLevel === 0 && char.coin_level_interim > 0;
 
-  const totalRanks = trainers.reduce(
-    (sum, t) => sum + t.ranks + t.modified_ranks,
-    0,
-  );
-
-  const { effectiveRanks, slaughterPoints } = useMemo(() => {
+  const slaughterPoints = useMemo(() => {
     const ranksMap = new Map<string, number>();
     const multMap = new Map<string, number>();
     for (const t of trainerDb) multMap.set(t.name, t.multiplier);
@@ -159,29 +174,15 @@ export function SummaryView() {
       const total = t.ranks + t.modified_ranks;
       ranksMap.set(t.trainer_name, (ranksMap.get(t.trainer_name) ?? 0) + total);
     }
-    const stats = computeFighterStats(ranksMap, multMap);
-    const effectiveRanks = trainers.reduce(
-      (sum, t) => sum + (t.ranks + t.modified_ranks) * (multMap.get(t.trainer_name) ?? 1.0),
-      0,
-    );
-    return { effectiveRanks, slaughterPoints: stats.slaughterPoints };
+    return computeFighterStats(ranksMap, multMap).slaughterPoints;
   }, [trainers, trainerDb]);
 
-  const effectiveRounded = Math.round(effectiveRanks * 10) / 10;
-
-  // Computed percentages
-  const chanceOfDepart =
-    char.deaths + char.departs > 0
-      ? ((char.departs / (char.deaths + char.departs)) * 100).toFixed(1)
-      : null;
-
+  // Rank totals and rates come from the Rust summary, so they match `amanuensis summary`.
+  const totalRanks = summary?.total_ranks ?? 0;
+  const effectiveRounded = summary?.effective_ranks ?? 0;
+  const chanceOfDepart = summary?.depart_rate != null ? summary.depart_rate.toFixed(1) : null;
   const chanceOfChainBreak =
-    char.chains_used + char.chains_broken > 0
-      ? (
-          (char.chains_broken / (char.chains_used + char.chains_broken)) *
-          100
-        ).toFixed(1)
-      : null;
+    summary?.chain_break_rate != null ? summary.chain_break_rate.toFixed(1) : null;
 
   return (
     <div>
@@ -210,6 +211,9 @@ export function SummaryView() {
             </p>
           )}
         </div>
+        <div className="ml-auto">
+          <ExportMenu onExport={handleExport} />
+        </div>
       </div>
 
       {mergeSources.length > 0 && (
@@ -243,6 +247,7 @@ export function SummaryView() {
         <div className="row-span-2 flex items-center justify-center rounded-lg bg-[var(--color-card)] p-4">
           <CharacterPortrait
             name={char.name}
+            charId={selectedCharacterId}
             className="h-full max-h-40 w-auto rounded-lg"
           />
         </div>
@@ -425,7 +430,7 @@ export function SummaryView() {
         <StatCard
           label="Total Ranks"
           value={totalRanks.toLocaleString()}
-          sub={`${trainers.length} trainers`}
+          sub={`${summary?.trainers_visited ?? trainers.length} trainers`}
         />
         <StatCard
           label="Effective Ranks"
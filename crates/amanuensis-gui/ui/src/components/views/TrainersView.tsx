@@ -3,7 +3,8 @@ import { createColumnHelper } from "@tanstack/react-table";
 import { useStore } from "../../lib/store";
 import { DataTable } from "../shared/DataTable";
 import { ProfessionBadge } from "../shared/ProfessionBadge";
-import { getTrainerDbInfo, setTrainerNote } from "../../lib/commands";
+import { getTrainerDbInfo, setTrainerNote, exportTrainers } from "../../lib/commands";
+import { ExportMenu, saveExport, type ExportFormat } from "../shared/ExportMenu";
 import { effectiveRanks } from "../../lib/trainerUtils";
 import { PROFESSION_ORDER } from "../../lib/constants";
 import type { Trainer, TrainerInfo } from "../../types";
@@ -13,12 +14,14 @@ type EnrichedTrainer = Trainer & {
   multiplier: number;
   is_combo: boolean;
   combo_components: string[];
+  retired: boolean;
+  replaced_by: string | null;
 };
 
 const columnHelper = createColumnHelper<EnrichedTrainer>();
 
 export function TrainersView() {
-  const { trainers, setTrainers, trainersViewState, setTrainersViewState, selectedCharacterId } = useStore();
+  const { trainers, setTrainers, trainersViewState, setTrainersViewState, selectedCharacterId, characters } = useStore();
   const { showZero, showEffective, searchQuery, collapsedGroups: collapsedArr, alphabetical } = trainersViewState;
   const collapsedGroups = useMemo(() => new Set(collapsedArr), [collapsedArr]);
   const setShowZero = useCallback((v: boolean) => setTrainersViewState({ showZero: v }), [setTrainersViewState]);
@@ -61,6 +64,22 @@ export function TrainersView() {
                   *
                 </span>
               )}
+              {row.retired && (
+                <span
+                  className="ml-1 rounded bg-zinc-500/20 px-1 py-0.5 text-[10px] font-medium leading-none text-[var(--color-text-muted)]"
+                  title={row.replaced_by ? `Retired trainer: replaced by ${row.replaced_by}` : "Retired trainer: no longer in the game"}
+                >
+                  RETIRED
+                </span>
+              )}
+              {row.maxed && (
+                <span
+                  className="ml-1 rounded bg-emerald-500/20 px-1 py-0.5 text-[10px] font-medium leading-none text-emerald-400"
+                  title="This trainer has nothing more to teach you"
+                >
+                  MAXED
+                </span>
+              )}
               {isNonModifier && (
                 <span
                   className={`ml-1 rounded px-1 py-0.5 text-[10px] font-medium leading-none ${
@@ -85,37 +104,35 @@ export function TrainersView() {
         },
       }),
       columnHelper.accessor("ranks", {
-        header: "Ranks",
-        cell: (info) => info.getValue(),
-      }),
-      columnHelper.accessor("modified_ranks", {
-        header: "Modified",
+        header: "Counted",
         cell: (info) => info.getValue(),
       }),
       columnHelper.accessor("apply_learning_ranks", {
         id: "applied",
         header: "Applied",
+        cell: (info) => info.getValue(),
+      }),
+      columnHelper.accessor("apply_learning_estimated_ranks", {
+        id: "applied_estimated",
+        header: "Applied (est.)",
         cell: (info) => {
           const row = info.row.original;
-          const confirmed = row.apply_learning_ranks;
           const unknown = row.apply_learning_unknown_count;
-          if (confirmed === 0 && unknown === 0) return 0;
-          if (unknown > 0) {
-            return (
-              <span>
-                {confirmed}
-                <span
-                  className="text-[var(--color-text-muted)]"
-                  title={`${unknown} partial apply-learning event${unknown > 1 ? "s" : ""} (1-9 ranks each, exact amount unknown)`}
-                >
-                  +{unknown}?
-                </span>
-              </span>
-            );
-          }
-          return confirmed;
+          if (unknown === 0) return null;
+          return (
+            <span
+              className="text-[var(--color-text-muted)]"
+              title={`${unknown} partial apply-learning event${unknown > 1 ? "s" : ""} (1-9 ranks each, exact amount unknown; not included in Total)`}
+            >
+              ~{info.getValue()}
+            </span>
+          );
         },
       }),
+      columnHelper.accessor("modified_ranks", {
+        header: "Manual",
+        cell: (info) => info.getValue(),
+      }),
       columnHelper.accessor(
         (row) => effectiveRanks(row),
         {
@@ -186,7 +203,14 @@ export function TrainersView() {
     // Build metadata maps from trainerDb
     const metaMap = new Map<
       string,
-      { profession: string | null; multiplier: number; is_combo: boolean; combo_components: string[] }
+      {
+        profession: string | null;
+        multiplier: number;
+        is_combo: boolean;
+        combo_components: string[];
+        retired: boolean;
+        replaced_by: string | null;
+      }
     >();
     for (const t of trainerDb) {
       metaMap.set(t.name, {
@@ -194,6 +218,8 @@ export function TrainersView() {
         multiplier: t.multiplier,
         is_combo: t.is_combo,
         combo_components: t.combo_components,
+        retired: t.retired,
+        replaced_by: t.replaced_by,
       });
     }
 
@@ -202,7 +228,13 @@ export function TrainersView() {
       charTrainerMap.set(t.trainer_name, t);
     }
 
-    const defaultMeta = { multiplier: 1.0, is_combo: false, combo_components: [] as string[] };
+    const defaultMeta = {
+      multiplier: 1.0,
+      is_combo: false,
+      combo_components: [] as string[],
+      retired: false,
+      replaced_by: null as string | null,
+    };
 
     if (showZero) {
       const allTrainers: EnrichedTrainer[] = [];
@@ -215,8 +247,11 @@ export function TrainersView() {
             multiplier: dbTrainer.multiplier,
             is_combo: dbTrainer.is_combo,
             combo_components: dbTrainer.combo_components,
+            retired: dbTrainer.retired,
+            replaced_by: dbTrainer.replaced_by,
           });
-        } else {
+        } else if (!dbTrainer.retired) {
+          // Retired trainers can't be visited, so only show them where ranks were earned
           allTrainers.push({
             id: null,
             character_id: 0,
@@ -226,14 +261,18 @@ export function TrainersView() {
             date_of_last_rank: null,
             apply_learning_ranks: 0,
             apply_learning_unknown_count: 0,
+            apply_learning_estimated_ranks: 0,
             rank_mode: "modifier",
             override_date: null,
             notes: null,
+            maxed: false,
             effective_multiplier: dbTrainer.multiplier,
             profession: dbTrainer.profession,
             multiplier: dbTrainer.multiplier,
             is_combo: dbTrainer.is_combo,
             combo_components: dbTrainer.combo_components,
+            retired: dbTrainer.retired,
+            replaced_by: dbTrainer.replaced_by,
           });
         }
       }
@@ -248,6 +287,8 @@ export function TrainersView() {
         multiplier: meta?.multiplier ?? defaultMeta.multiplier,
         is_combo: meta?.is_combo ?? defaultMeta.is_combo,
         combo_components: meta?.combo_components ?? defaultMeta.combo_components,
+        retired: meta?.retired ?? defaultMeta.retired,
+        replaced_by: meta?.replaced_by ?? defaultMeta.replaced_by,
       };
     });
   }, [trainers, trainerDb, showZero]);
@@ -292,6 +333,17 @@ export function TrainersView() {
     );
   }, [enrichedTrainers]);
 
+  const handleExport = useCallback(
+    async (format: ExportFormat) => {
+      if (selectedCharacterId == null) return;
+      const charName = characters.find((c) => c.id === selectedCharacterId)?.name ?? "character";
+      await saveExport("Trainers", `${charName}-trainers`, format, (path) =>
+        exportTrainers(selectedCharacterId, format, path),
+      );
+    },
+    [selectedCharacterId, characters],
+  );
+
   return (
     <div className="flex h-full flex-col">
       <div className="mb-4 flex items-center justify-between">
@@ -305,6 +357,7 @@ export function TrainersView() {
           )}
         </div>
         <div className="flex items-center gap-4">
+          {selectedCharacterId != null && <ExportMenu onExport={handleExport} />}
           <label className="flex cursor-pointer items-center gap-2 text-sm">
             <input
               type="checkbox"
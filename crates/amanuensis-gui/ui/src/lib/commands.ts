@@ -1,6 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
+import { useStore } from "./store";
 import type {
   Character,
+  CharacterListEntry,
   Kill,
   Trainer,
   TrainerCheckpoint,
@@ -9,18 +11,52 @@ import type {
   ScanResult,
   TrainerInfo,
   ImportResult,
-  LogSearchResult,
+  LogSearchPage,
   ProcessLog,
   BestiaryPayload,
   CreatureFrequency,
+  CreatureAccuracy,
+  HealSummary,
+  StoneUsage,
+  Economy,
+  RankChange,
+  RankPreview,
+  StatsSimulation,
+  MergePreview,
+  ClanSummary,
+  CharacterSummary,
+  CharacterComparison,
+  ReportScheduleConfig,
+  IndexStatus,
 } from "../types";
 
+/**
+ * Stable error kind (`AmanuensisError::kind()`) for commands that report tagged errors
+ * (`[kind] message`), or null for untagged errors.
+ */
+export function errorKind(err: unknown): string | null {
+  const match = /^\[([a-z_]+)\] /.exec(String(err));
+  return match ? match[1] : null;
+}
+
+/** The human-readable part of a command error, without the `[kind]` tag. */
+export function errorMessage(err: unknown): string {
+  return String(err).replace(/^\[[a-z_]+\] /, "");
+}
+
 export async function openDatabase(path: string): Promise<void> {
   return invoke("open_database", { path });
 }
 
-export async function listCharacters(): Promise<Character[]> {
-  return invoke("list_characters");
+/** Rebuild the demo database from the bundled sample logs; returns its path. */
+export async function buildDemoDatabase(): Promise<string> {
+  return invoke("build_demo_database");
+}
+
+/** Characters with their list badges; `unscanned_files` counts the configured sources' pending logs. */
+export async function listCharacters(): Promise<CharacterListEntry[]> {
+  const sources = useStore.getState().sources.map(({ path, recursive }) => ({ path, recursive }));
+  return invoke("list_characters", { sources });
 }
 
 export async function getCharacter(name: string): Promise<Character | null> {
@@ -75,6 +111,20 @@ export async function setRankOverride(
   });
 }
 
+export async function previewEffectiveRanks(
+  charId: number,
+  changes: RankChange[],
+): Promise<RankPreview> {
+  return invoke("preview_effective_ranks", { charId, changes });
+}
+
+export async function simulateRankAdditions(
+  charId: number,
+  deltas: Record<string, number>,
+): Promise<StatsSimulation> {
+  return invoke("simulate_rank_additions", { charId, deltas });
+}
+
 export async function setProfessionOverride(
   charId: number,
   profession: string | null,
@@ -87,16 +137,18 @@ export async function scanLogs(
   force: boolean,
   recursive: boolean = false,
   indexLines: boolean = true,
+  nice: boolean = false,
 ): Promise<ScanResult> {
-  return invoke("scan_logs", { folder, force, recursive, indexLines });
+  return invoke("scan_logs", { folder, force, recursive, indexLines, nice });
 }
 
 export async function scanFiles(
   files: string[],
   force: boolean = false,
   indexLines: boolean = true,
+  nice: boolean = false,
 ): Promise<ScanResult> {
-  return invoke("scan_files", { files, force, indexLines });
+  return invoke("scan_files", { files, force, indexLines, nice });
 }
 
 export async function searchLogs(
@@ -106,11 +158,13 @@ export async function searchLogs(
   includeSpeech?: boolean,
   linesBefore?: number,
   linesAfter?: number,
-): Promise<LogSearchResult[]> {
+  offset?: number,
+): Promise<LogSearchPage> {
   return invoke("search_logs", {
     query,
     charId: charId ?? null,
     limit: limit ?? 200,
+    offset: offset ?? 0,
     includeSpeech: includeSpeech ?? false,
     linesBefore: linesBefore ?? 0,
     linesAfter: linesAfter ?? 0,
@@ -129,6 +183,14 @@ export async function getLogLineCount(): Promise<number> {
   return invoke("get_log_line_count");
 }
 
+export async function getIndexStatus(): Promise<IndexStatus> {
+  return invoke("get_index_status");
+}
+
+export async function setIndexingEnabled(enabled: boolean): Promise<IndexStatus> {
+  return invoke("set_indexing_enabled", { enabled });
+}
+
 export async function getDefaultDbPath(): Promise<string> {
   return invoke("get_default_db_path");
 }
@@ -148,8 +210,9 @@ export async function deleteAllData(): Promise<void> {
 export async function rescanLogs(
   sources: { path: string; recursive: boolean }[],
   indexLines: boolean = true,
+  nice: boolean = false,
 ): Promise<ScanResult> {
-  return invoke("rescan_logs", { sources, indexLines });
+  return invoke("rescan_logs", { sources, indexLines, nice });
 }
 
 export async function getPendingLogCount(
@@ -161,8 +224,14 @@ export async function getPendingLogCount(
 export async function updateLogs(
   sources: { path: string; recursive: boolean }[],
   indexLines: boolean = true,
+  nice: boolean = false,
 ): Promise<ScanResult> {
-  return invoke("update_logs", { sources, indexLines });
+  return invoke("update_logs", { sources, indexLines, nice });
+}
+
+/** Stop the running scan; it fails with a `cancelled` error. False when none is running. */
+export async function cancelScan(): Promise<boolean> {
+  return invoke("cancel_scan");
 }
 
 export async function clearRankOverrides(): Promise<void> {
@@ -181,6 +250,18 @@ export async function getCharacterMerged(charId: number): Promise<Character | nu
   return invoke("get_character_merged", { charId });
 }
 
+export async function getClanSummary(charId: number): Promise<ClanSummary> {
+  return invoke("get_clan_summary", { charId });
+}
+
+export async function getCharacterSummary(charId: number): Promise<CharacterSummary | null> {
+  return invoke("get_character_summary", { charId });
+}
+
+export async function compareCharacters(leftId: number, rightId: number): Promise<CharacterComparison> {
+  return invoke("compare_characters", { leftId, rightId });
+}
+
 export async function mergeCharacters(
   sourceIds: number[],
   targetId: number,
@@ -188,6 +269,13 @@ export async function mergeCharacters(
   return invoke("merge_characters", { sourceIds, targetId });
 }
 
+export async function previewMerge(
+  sourceIds: number[],
+  targetId: number,
+): Promise<MergePreview> {
+  return invoke("preview_merge", { sourceIds, targetId });
+}
+
 export async function unmergeCharacter(sourceId: number): Promise<void> {
   return invoke("unmerge_character", { sourceId });
 }
@@ -208,6 +296,20 @@ export async function getCharacterPortraitPath(
   return invoke("get_character_portrait_path", { name });
 }
 
+export async function getStoredCharacterPortrait(
+  charId: number,
+): Promise<string | null> {
+  return invoke("get_stored_character_portrait", { charId });
+}
+
+/** Attach the image at `path` as the portrait, or remove it with `null`. */
+export async function setCharacterPortrait(
+  charId: number,
+  path: string | null,
+): Promise<string | null> {
+  return invoke("set_character_portrait", { charId, path });
+}
+
 export interface UpdateInfo {
   version: string;
   url: string;
@@ -225,6 +327,14 @@ export async function getProcessLogs(): Promise<ProcessLog[]> {
   return invoke("get_process_logs");
 }
 
+export async function getRecentErrors(limit?: number): Promise<ProcessLog[]> {
+  return invoke("get_recent_errors", { limit });
+}
+
+export async function exportDiagnostics(dest: string): Promise<void> {
+  return invoke("export_diagnostics", { dest });
+}
+
 export async function getTrainerCheckpoints(charId: number): Promise<TrainerCheckpoint[]> {
   return invoke("get_trainer_checkpoints", { charId });
 }
@@ -248,10 +358,55 @@ export async function getKillFrequency(
   return invoke("get_kill_frequency", { charId, includeAssisted });
 }
 
+export async function getAccuracy(charId: number): Promise<CreatureAccuracy[]> {
+  return invoke("get_accuracy", { charId });
+}
+
+export async function getHeals(charId: number): Promise<HealSummary[]> {
+  return invoke("get_heals", { charId });
+}
+
+export async function getStoneUsage(charId: number): Promise<StoneUsage[]> {
+  return invoke("get_stone_usage", { charId });
+}
+
+export async function getEconomy(
+  charId: number,
+  range?: "all" | `${number}d`,
+): Promise<Economy> {
+  return invoke("get_economy", { charId, range });
+}
+
 export async function exportKills(
   charId: number,
-  format: "csv" | "text",
+  format: "csv" | "json" | "text",
   path: string,
 ): Promise<void> {
   return invoke("export_kills", { charId, format, path });
 }
+
+export async function exportTrainers(
+  charId: number,
+  format: "csv" | "json" | "text",
+  path: string,
+): Promise<void> {
+  return invoke("export_trainers", { charId, format, path });
+}
+
+/** The character summary as JSON, or `field,value` rows for CSV/text. */
+export async function exportCharacterSummary(
+  charId: number,
+  format: "csv" | "json" | "text",
+  path: string,
+): Promise<void> {
+  return invoke("export_character_summary", { charId, format, path });
+}
+
+/**
+ * Rewrite the roster report (amanuensis-report.html / .json) into `config.folder` after
+ * every scan, at most once per `min_interval_minutes`. Writes once immediately and returns
+ * the files written; `null` stops publishing.
+ */
+export async function setReportSchedule(config: ReportScheduleConfig | null): Promise<string[]> {
+  return invoke("set_report_schedule", { config });
+}
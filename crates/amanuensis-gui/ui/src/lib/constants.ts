@@ -6,10 +6,12 @@ export const STORAGE_KEYS = {
   LAST_CHARACTER: "amanuensis_last_character",
   THEME: "amanuensis_theme",
   INDEX_LOGS: "amanuensis_index_logs",
+  NICE_SCAN: "amanuensis_nice_scan",
   COLLAPSED_TRAINERS: "amanuensis_collapsed_trainers",
   COLLAPSED_RANK_MODIFIERS: "amanuensis_collapsed_rankModifiers",
   TRAINERS_ALPHA_VIEW: "amanuensis_trainers_alpha_view",
   LOG_SOURCES: "amanuensis_log_sources",
+  REPORT_SCHEDULE: "amanuensis_report_schedule",
 } as const;
 
 // ---------------------------------------------------------------------------
@@ -7,7 +7,10 @@ import {
   listCharacters,
   getScannedLogCount,
   getLogLineCount,
+  getIndexStatus,
+  setIndexingEnabled,
   getDefaultDbPath,
+  buildDemoDatabase,
   getKills,
   getTrainers,
   getPets,
@@ -16,6 +19,7 @@ import {
   deleteAllData,
   importScribiusDb,
   getProcessLogs,
+  setReportSchedule as applyReportSchedule,
 } from "../commands";
 import { computeKillStats } from "../killStats";
 
@@ -33,8 +37,12 @@ export function useDatabase() {
     setPets,
     setLastys,
     setLogLineCount,
+    setIndexStatus,
+    setIndexLogLines,
     setProcessLogs,
     setCoinLevelForChar,
+    reportSchedule,
+    setReportSchedule,
   } = useStore();
 
   const loadCharacterData = useCallback(
@@ -75,6 +83,13 @@ export function useDatabase() {
       setScannedLogCount(count);
       const lineCount = await getLogLineCount();
       setLogLineCount(lineCount);
+      const indexStatus = await getIndexStatus();
+      setIndexStatus(indexStatus);
+      setIndexLogLines(indexStatus.enabled);
+      const schedule = useStore.getState().reportSchedule;
+      if (schedule) {
+        await applyReportSchedule(schedule).catch((e) => console.error("Report schedule failed:", e));
+      }
       const logs = await getProcessLogs();
       setProcessLogs(logs);
       if (chars.length > 0) {
@@ -85,7 +100,7 @@ export function useDatabase() {
         }
       }
     },
-    [setDbPath, setCharacters, setScannedLogCount, setLogLineCount, setProcessLogs, handleSelectCharacter],
+    [setDbPath, setCharacters, setScannedLogCount, setLogLineCount, setIndexStatus, setIndexLogLines, setProcessLogs, handleSelectCharacter],
   );
 
   // Auto-open database on startup: last-used path, or default app data dir
@@ -114,6 +129,23 @@ export function useDatabase() {
     }
   }, [loadDatabase]);
 
+  // Explore the bundled sample logs. The demo is not remembered as the last database, so
+  // the next launch opens the user's own again.
+  const handleOpenDemo = useCallback(async () => {
+    const lastDb = localStorage.getItem(STORAGE_KEYS.LAST_DB);
+    try {
+      await loadDatabase(await buildDemoDatabase());
+    } catch (e) {
+      await message(String(e), { title: "Demo Failed", kind: "error" });
+    } finally {
+      if (lastDb) {
+        localStorage.setItem(STORAGE_KEYS.LAST_DB, lastDb);
+      } else {
+        localStorage.removeItem(STORAGE_KEYS.LAST_DB);
+      }
+    }
+  }, [loadDatabase]);
+
   const ensureDb = useCallback(async (): Promise<boolean> => {
     if (dbPath) return true;
     const defaultPath = await getDefaultDbPath();
@@ -202,12 +234,67 @@ export function useDatabase() {
     }
   }, [loadDatabase]);
 
+  // Publish the roster report to a folder after every scan, or stop publishing.
+  const handlePublishReports = useCallback(async () => {
+    if (reportSchedule) {
+      const stop = await confirm(`Stop writing reports to ${reportSchedule.folder}?`, { title: "Publish Reports" });
+      if (!stop) return;
+      await applyReportSchedule(null);
+      setReportSchedule(null);
+      return;
+    }
+    const folder = await open({ directory: true, title: "Choose Report Folder" });
+    if (!folder) return;
+    const config = { folder, html: true, json: true, min_interval_minutes: 15 };
+    try {
+      const written = await applyReportSchedule(config);
+      setReportSchedule(config);
+      await message(
+        (written.length > 0 ? `Wrote:\n${written.join("\n")}\n\n` : "") +
+          "The report is rewritten after scans, at most every 15 minutes.",
+        { title: "Publish Reports" },
+      );
+    } catch (e) {
+      await message(String(e), { title: "Publish Reports Failed", kind: "error" });
+    }
+  }, [reportSchedule, setReportSchedule]);
+
+  // Turn search indexing on or off. Off also drops the index, so confirm when it has lines.
+  const handleToggleIndexing = useCallback(
+    async (enabled: boolean) => {
+      if (!dbPath) {
+        setIndexLogLines(enabled);
+        return;
+      }
+      const lines = useStore.getState().indexStatus?.lines ?? 0;
+      if (!enabled && lines > 0) {
+        const confirmed = await confirm(
+          `This will delete the search index (${lines.toLocaleString()} lines) to keep the database small. Log Search will be empty until indexing is turned back on and logs are rescanned.\n\nAre you sure?`,
+          { title: "Turn Off Search Indexing", kind: "warning" },
+        );
+        if (!confirmed) return;
+      }
+      try {
+        const status = await setIndexingEnabled(enabled);
+        setIndexStatus(status);
+        setIndexLogLines(status.enabled);
+        setLogLineCount(status.lines);
+      } catch (e) {
+        await message(String(e), { title: "Search Indexing", kind: "error" });
+      }
+    },
+    [dbPath, setIndexStatus, setIndexLogLines, setLogLineCount],
+  );
+
   return {
     loadDatabase,
     handleOpenDb,
+    handleOpenDemo,
     handleReset,
     handleDeleteAll,
     handleImportScribius,
+    handlePublishReports,
+    handleToggleIndexing,
     handleSelectCharacter,
     ensureDb,
     selectedCharacterId,
@@ -2,7 +2,7 @@ import { open, confirm } from "@tauri-apps/plugin-dialog";
 import { listen } from "@tauri-apps/api/event";
 import { useEffect, useCallback } from "react";
 import { useStore } from "../store";
-import { scanLogs, rescanLogs, scanFiles, updateLogs, getPendingLogCount, listCharacters, getScannedLogCount, getLogLineCount, getProcessLogs } from "../commands";
+import { scanLogs, rescanLogs, scanFiles, updateLogs, cancelScan, errorKind, getPendingLogCount, listCharacters, getScannedLogCount, getLogLineCount, getIndexStatus, getProcessLogs } from "../commands";
 import type { ScanProgress } from "../../types";
 
 export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCharacters>>) => Promise<void>) {
@@ -17,11 +17,13 @@ export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCh
     setCharacters,
     setScannedLogCount,
     setLogLineCount,
+    setIndexStatus,
     setProcessLogs,
     setPendingLogCount,
     setUpdateResult,
     recursiveScan,
     indexLogLines,
+    niceScan,
   } = useStore();
 
   const finishScan = useCallback(async () => {
@@ -31,12 +33,13 @@ export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCh
     setScannedLogCount(count);
     const lineCount = await getLogLineCount();
     setLogLineCount(lineCount);
+    setIndexStatus(await getIndexStatus());
     const logs = await getProcessLogs();
     setProcessLogs(logs);
     const pending = await getPendingLogCount(sources);
     setPendingLogCount(pending);
     await onScanComplete(chars);
-  }, [setCharacters, setScannedLogCount, setLogLineCount, setProcessLogs, sources, setPendingLogCount, onScanComplete]);
+  }, [setCharacters, setScannedLogCount, setLogLineCount, setIndexStatus, setProcessLogs, sources, setPendingLogCount, onScanComplete]);
 
   // Listen for scan progress events
   useEffect(() => {
@@ -59,16 +62,18 @@ export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCh
     setScanProgress(null);
 
     try {
-      await scanLogs(folderPath, false, recursiveScan, indexLogLines);
+      await scanLogs(folderPath, false, recursiveScan, indexLogLines, niceScan);
       addSource(folderPath, recursiveScan);
       await finishScan();
     } catch (e) {
-      console.error("Scan failed:", e);
+      // A cancelled scan rolled back its own work; refresh for anything committed before it.
+      if (errorKind(e) === "cancelled") await finishScan();
+      else console.error("Scan failed:", e);
     } finally {
       setIsScanning(false);
       setScanProgress(null);
     }
-  }, [addSource, setIsScanning, setScanProgress, finishScan, recursiveScan, indexLogLines]);
+  }, [addSource, setIsScanning, setScanProgress, finishScan, recursiveScan, indexLogLines, niceScan]);
 
   const handleScanFiles = useCallback(async (ensureDb: () => Promise<boolean>) => {
     if (!(await ensureDb())) return;
@@ -85,15 +90,16 @@ export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCh
     setScanProgress(null);
 
     try {
-      await scanFiles(files, false, indexLogLines);
+      await scanFiles(files, false, indexLogLines, niceScan);
       await finishScan();
     } catch (e) {
-      console.error("Scan failed:", e);
+      if (errorKind(e) === "cancelled") await finishScan();
+      else console.error("Scan failed:", e);
     } finally {
       setIsScanning(false);
       setScanProgress(null);
     }
-  }, [setIsScanning, setScanProgress, finishScan, indexLogLines]);
+  }, [setIsScanning, setScanProgress, finishScan, indexLogLines, niceScan]);
 
   const handleRescanLogs = useCallback(async () => {
     if (sources.length === 0) return;
@@ -105,15 +111,16 @@ export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCh
     setIsScanning(true);
     setScanProgress(null);
     try {
-      await rescanLogs(sources, indexLogLines);
+      await rescanLogs(sources, indexLogLines, niceScan);
       await finishScan();
     } catch (e) {
-      console.error("Rescan failed:", e);
+      if (errorKind(e) === "cancelled") await finishScan();
+      else console.error("Rescan failed:", e);
     } finally {
       setIsScanning(false);
       setScanProgress(null);
     }
-  }, [sources, indexLogLines, setIsScanning, setScanProgress, finishScan]);
+  }, [sources, indexLogLines, niceScan, setIsScanning, setScanProgress, finishScan]);
 
   const handleUpdateLogs = useCallback(async () => {
     if (sources.length === 0) return;
@@ -122,7 +129,7 @@ export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCh
     try {
       // Snapshot per-character stats before the run so we can report the deltas.
       const before = new Map(characters.map((c) => [c.name, c]));
-      const scan = await updateLogs(sources, indexLogLines);
+      const scan = await updateLogs(sources, indexLogLines, niceScan);
       await finishScan();
       const after = await listCharacters();
       const perCharacter = after
@@ -135,12 +142,17 @@ export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCh
         .sort((a, b) => b.loginsDelta - a.loginsDelta);
       setUpdateResult({ scan, perCharacter });
     } catch (e) {
-      console.error("Update logs failed:", e);
+      if (errorKind(e) === "cancelled") await finishScan();
+      else console.error("Update logs failed:", e);
     } finally {
       setIsScanning(false);
       setScanProgress(null);
     }
-  }, [sources, indexLogLines, characters, setIsScanning, setScanProgress, finishScan, setUpdateResult]);
+  }, [sources, indexLogLines, niceScan, characters, setIsScanning, setScanProgress, finishScan, setUpdateResult]);
+
+  const handleCancelScan = useCallback(async () => {
+    await cancelScan();
+  }, []);
 
   return {
     isScanning,
@@ -150,5 +162,6 @@ export function useScan(onScanComplete: (chars: Awaited<ReturnType<typeof listCh
     handleScanFiles,
     handleRescanLogs,
     handleUpdateLogs,
+    handleCancelScan,
   };
 }
@@ -3,6 +3,7 @@ import type { SortingState } from "@tanstack/react-table";
 import { STORAGE_KEYS } from "./constants";
 import type {
   Character,
+  CharacterListEntry,
   Kill,
   Trainer,
   Pet,
@@ -13,6 +14,8 @@ import type {
   BestiaryEntry,
   CreatureFrequency,
   UpdateResult,
+  ReportScheduleConfig,
+  IndexStatus,
 } from "../types";
 
 export type Theme = "dark" | "light" | "midnight" | "dark-v2" | "light-v2" | "midnight-v2";
@@ -37,6 +40,17 @@ function loadSources(): LogSource[] {
   }
 }
 
+function loadReportSchedule(): ReportScheduleConfig | null {
+  try {
+    const raw = localStorage.getItem(STORAGE_KEYS.REPORT_SCHEDULE);
+    if (!raw) return null;
+    const parsed = JSON.parse(raw);
+    return parsed && typeof parsed.folder === "string" ? parsed : null;
+  } catch {
+    return null;
+  }
+}
+
 interface DataTableViewState {
   sorting: SortingState;
   globalFilter: string;
@@ -70,9 +84,13 @@ interface AppStore {
   addSource: (path: string, recursive: boolean) => void;
   removeSource: (path: string) => void;
 
+  // Scheduled roster report (persisted; re-applied whenever a database opens)
+  reportSchedule: ReportScheduleConfig | null;
+  setReportSchedule: (config: ReportScheduleConfig | null) => void;
+
   // Characters
-  characters: Character[];
-  setCharacters: (chars: Character[]) => void;
+  characters: CharacterListEntry[];
+  setCharacters: (chars: CharacterListEntry[]) => void;
   selectedCharacterId: number | null;
   selectCharacter: (id: number | null) => void;
 
@@ -121,9 +139,14 @@ interface AppStore {
   setExcludeUnknown: (exclude: boolean) => void;
 
   // Index log lines for search
+  /** Mirrors the database's indexing setting once a database is open. */
   indexLogLines: boolean;
   setIndexLogLines: (index: boolean) => void;
 
+  // Low-priority scanning (slower, but keeps the game and GUI responsive)
+  niceScan: boolean;
+  setNiceScan: (nice: boolean) => void;
+
   // Pending-logs badge count + last update-run result (for the confirmation dialog)
   pendingLogCount: number;
   setPendingLogCount: (n: number) => void;
@@ -133,6 +156,8 @@ interface AppStore {
   // Log line count (FTS5 indexed)
   logLineCount: number;
   setLogLineCount: (count: number) => void;
+  indexStatus: IndexStatus | null;
+  setIndexStatus: (status: IndexStatus | null) => void;
 
   // Theme
   theme: Theme;
@@ -203,6 +228,16 @@ export const useStore = create<AppStore>((set) => ({
       return { sources: next };
     }),
 
+  reportSchedule: loadReportSchedule(),
+  setReportSchedule: (config) => {
+    if (config) {
+      localStorage.setItem(STORAGE_KEYS.REPORT_SCHEDULE, JSON.stringify(config));
+    } else {
+      localStorage.removeItem(STORAGE_KEYS.REPORT_SCHEDULE);
+    }
+    set({ reportSchedule: config });
+  },
+
   characters: [],
   setCharacters: (chars) => set({ characters: chars }),
   selectedCharacterId: null,
@@ -251,6 +286,12 @@ export const useStore = create<AppStore>((set) => ({
     set({ indexLogLines: index });
   },
 
+  niceScan: localStorage.getItem(STORAGE_KEYS.NICE_SCAN) === "true",
+  setNiceScan: (nice) => {
+    localStorage.setItem(STORAGE_KEYS.NICE_SCAN, String(nice));
+    set({ niceScan: nice });
+  },
+
   pendingLogCount: 0,
   setPendingLogCount: (n) => set({ pendingLogCount: n }),
   updateResult: null,
@@ -258,6 +299,8 @@ export const useStore = create<AppStore>((set) => ({
 
   logLineCount: 0,
   setLogLineCount: (count) => set({ logLineCount: count }),
+  indexStatus: null,
+  setIndexStatus: (status) => set({ indexStatus: status }),
 
   theme: (localStorage.getItem(STORAGE_KEYS.THEME) as Theme) || "dark",
   setTheme: (theme) => {
@@ -51,6 +51,13 @@ export interface Character {
   total_ranks: number;
 }
 
+/** Mirrors Rust `CharacterListEntry` struct (flattened `Character` plus list badges) */
+export interface CharacterListEntry extends Character {
+  total_kills: number;
+  last_played: string | null;
+  unscanned_files: number;
+}
+
 /** Mirrors Rust `Kill` struct */
 export interface Kill {
   id: number | null;
@@ -90,10 +97,13 @@ export interface Trainer {
   date_of_last_rank: string | null;
   apply_learning_ranks: number;
   apply_learning_unknown_count: number;
+  apply_learning_estimated_ranks: number;
   rank_mode: "modifier" | "override" | "override_until_date";
   override_date: string | null;
   effective_multiplier: number;
   notes: string | null;
+  /** The trainer said it has nothing more to teach. */
+  maxed: boolean;
 }
 
 /** Mirrors Rust `Pet` struct */
@@ -102,6 +112,16 @@ export interface Pet {
   character_id: number;
   pet_name: string;
   creature_name: string;
+  /** "active" | "dead" | "released" */
+  status: string;
+  adopted_date: string | null;
+  death_count: number;
+  feed_count: number;
+  last_fed_date: string | null;
+  released_date: string | null;
+  ranks: number;
+  trainings: number;
+  last_trained_date: string | null;
 }
 
 /** Mirrors Rust `Lasty` struct */
@@ -126,6 +146,16 @@ export interface ScanResult {
   lines_parsed: number;
   events_found: number;
   errors: number;
+  error_details: ScanError[];
+}
+
+/** Mirrors Rust `ScanError` struct */
+export interface ScanError {
+  file_path: string;
+  line: number | null;
+  kind: string;
+  message: string;
+  created_at: string;
 }
 
 /** Per-character activity delta produced by an Update Logs run. */
@@ -155,6 +185,9 @@ export interface TrainerInfo {
   multiplier: number;
   is_combo: boolean;
   combo_components: string[];
+  /** Trainer has been removed from the game */
+  retired: boolean;
+  replaced_by: string | null;
 }
 
 /** Mirrors Rust `ImportResult` struct */
@@ -168,9 +201,17 @@ export interface ImportResult {
   warnings: string[];
 }
 
+/** Mirrors Rust `SearchSegment` struct */
+export interface SearchSegment {
+  text: string;
+  highlight: boolean;
+}
+
 /** Mirrors Rust `LogSearchResult` struct */
 export interface LogSearchResult {
   content: string;
+  /** `content` split into runs; matches of the query have `highlight` set. */
+  segments: SearchSegment[];
   character_id: number;
   timestamp: string;
   file_path: string;
@@ -180,6 +221,14 @@ export interface LogSearchResult {
   context_after: string[];
 }
 
+/** Mirrors Rust `LogSearchPage` struct */
+export interface LogSearchPage {
+  results: LogSearchResult[];
+  total: number;
+  offset: number;
+  has_more: boolean;
+}
+
 /** Mirrors Rust `ProcessLog` struct */
 export interface ProcessLog {
   id: number;
@@ -263,3 +312,232 @@ export interface CreatureFrequency {
   best_2h_start: string | null;
   best_2h_verbs: Record<string, number>;
 }
+
+/** Mirrors Rust `CreatureAccuracy` struct */
+export interface CreatureAccuracy {
+  creature_name: string;
+  hits: number;
+  misses: number;
+}
+
+/** Mirrors Rust `HealSummary` struct */
+export interface HealSummary {
+  name: string;
+  given: number;
+  received: number;
+  first_date: string;
+  last_date: string;
+}
+
+/** Mirrors Rust `StoneUsage` struct */
+export interface StoneUsage {
+  stone: "darkstone" | "moonstone";
+  charged: number;
+  used: number;
+  expired: number;
+  charges_left: number | null;
+  first_date: string;
+  last_date: string;
+}
+
+/** Mirrors Rust `CoinTotals` struct */
+export interface CoinTotals {
+  coins_picked_up: number;
+  fur_coins: number;
+  blood_coins: number;
+  mandible_coins: number;
+  fur_worth: number;
+  blood_worth: number;
+  mandible_worth: number;
+  bounty_coins: number;
+  chest_coins: number;
+  casino_won: number;
+  casino_lost: number;
+}
+
+/** Mirrors Rust `CoinBucket` struct */
+export interface CoinBucket {
+  source: string;
+  amount: number;
+}
+
+/** Mirrors Rust `BalancePoint` struct */
+export interface BalancePoint {
+  day: string;
+  amount: number;
+  balance: number;
+}
+
+/** Mirrors Rust `Economy` struct */
+export interface Economy {
+  totals: CoinTotals;
+  buckets: CoinBucket[];
+  history: BalancePoint[];
+}
+
+/** Mirrors Rust `ClanEvent` struct */
+export interface ClanEvent {
+  id: number | null;
+  character_id: number;
+  event_type: "join" | "leave" | "title";
+  value: string;
+  date: string;
+}
+
+/** Mirrors Rust `ClanSummary` struct */
+export interface ClanSummary {
+  current_clan: string | null;
+  member_since: string | null;
+  titles: string[];
+  history: ClanEvent[];
+}
+
+/** Mirrors Rust `SummaryCreature` struct */
+export interface SummaryCreature {
+  creature: string;
+  count: number;
+}
+
+/** Mirrors Rust `SummaryRescuer` struct */
+export interface SummaryRescuer {
+  name: string;
+  count: number;
+}
+
+/** Mirrors Rust `CircleTest` struct */
+export interface CircleTest {
+  profession: string;
+  circle: number;
+  date: string;
+}
+
+/** Mirrors Rust `CharacterSummary` struct */
+export interface CharacterSummary {
+  character: Character;
+  merged_from: string[];
+  clan: ClanSummary;
+  current_circle: CircleTest | null;
+  circles: CircleTest[];
+  solo_kills: number;
+  assisted_kills: number;
+  killed_by: number;
+  unique_creatures: number;
+  most_killed: SummaryCreature | null;
+  nemesis: SummaryCreature | null;
+  top_rescuer: SummaryRescuer | null;
+  total_ranks: number;
+  effective_ranks: number;
+  trainers_visited: number;
+  depart_rate: number | null;
+  chain_break_rate: number | null;
+  lastys: number;
+  lastys_active: number;
+  lastys_completed: number;
+  pets: number;
+  stones: StoneUsage[];
+}
+
+/** Mirrors Rust `MergePreview` struct */
+export interface MergePreview {
+  before: Character;
+  after: Character;
+  kills: Kill[];
+  trainers: Trainer[];
+  total_ranks_before: number;
+  total_ranks_after: number;
+}
+
+/** Mirrors Rust `FighterStats` struct (core `fighter_stats` module) */
+export interface FighterStatsPayload {
+  trained_ranks: number;
+  effective_ranks: number;
+  slaughter_points: number;
+  accuracy: number;
+  damage_min: number;
+  damage_max: number;
+  offense: number;
+  balance: number;
+  balance_regen: number;
+  balance_per_frame: number;
+  health: number;
+  health_regen: number;
+  health_per_frame: number;
+  defense: number;
+  spirit: number;
+  spirit_regen: number;
+  spirit_per_frame: number;
+  heal_receptivity: number;
+  balance_per_swing: number;
+  shieldstone_drain: number;
+  loadout: FighterLoadout;
+  /** Loadout parts whose modifiers aren't known, counted as no change. */
+  unknown_modifiers: string[];
+}
+
+/** Mirrors Rust `CharacterComparison` struct (core `compare` module); deltas are right - left */
+export interface CharacterComparison {
+  left: string;
+  right: string;
+  trainers: { trainer: string; left: number; right: number }[];
+  kills: { creature: string; left: number; right: number }[];
+  stats: { stat: string; left: number; right: number; delta: number }[];
+}
+
+/** Mirrors Rust `StatChange` struct (core `fighter_stats` module) */
+export interface StatChange {
+  stat: string;
+  before: number;
+  after: number;
+  delta: number;
+}
+
+/** Mirrors Rust `StatsSimulation` struct (core `fighter_stats` module) */
+export interface StatsSimulation {
+  deltas: Record<string, number>;
+  before: FighterStatsPayload;
+  after: FighterStatsPayload;
+  changes: StatChange[];
+}
+
+/** Mirrors Rust `FighterLoadout` struct (core `fighter_stats` module) */
+export interface FighterLoadout {
+  race: string;
+  weapon: string;
+  items: string[];
+}
+
+/** Mirrors Rust `RankChange` struct (GUI rank commands) */
+export interface RankChange {
+  trainer_name: string;
+  modified_ranks: number;
+}
+
+/** Mirrors Rust `RankPreview` struct (GUI rank commands) */
+export interface RankPreview {
+  trainers: Trainer[];
+  total_ranks: number;
+  effective_ranks: number;
+  fighter_stats: FighterStatsPayload;
+}
+
+/** Mirrors Rust `IndexStatus` struct (db/queries/log_file.rs) */
+export interface IndexStatus {
+  enabled: boolean;
+  /** False when SQLite lacks FTS5. */
+  available: boolean;
+  lines: number;
+  characters: number;
+  /** Scanned files with indexed lines, and all scanned files. */
+  files: number;
+  scanned_files: number;
+  estimated_bytes: number;
+}
+
+/** Mirrors Rust `ReportScheduleConfig` struct (GUI report commands) */
+export interface ReportScheduleConfig {
+  folder: string;
+  html: boolean;
+  json: boolean;
+  /** Minimum minutes between rewrites; 0 rewrites after every scan. */
+  min_interval_minutes: number;
+}